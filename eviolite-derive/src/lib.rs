@@ -0,0 +1,493 @@
+#![warn(missing_docs)]
+
+//! Derive macro for eviolite's `Solution` trait
+//!
+//! This crate is re-exported by `eviolite` when its `derive` feature is enabled,
+//! and shouldn't be used directly. See the documentation of `eviolite::Solution` for usage.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Expr, Member, Result, Type,
+};
+
+/// Derive `eviolite::Solution` for a struct whose fields are all genomes.
+///
+/// See the documentation of `eviolite::Solution` for usage.
+#[proc_macro_derive(Solution, attributes(solution))]
+pub fn derive_solution(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct StructOptions {
+    fitness: Type,
+    evaluate: Expr,
+    directions: Option<Expr>,
+    dominance_tolerance: Option<Expr>,
+    repair: Option<Expr>,
+}
+
+// Where a field gets its configuration from.
+enum ConfigSource {
+    Expr(Expr),
+    Vector(Box<VectorOptions>),
+}
+
+struct VectorOptions {
+    len: Expr,
+    bounds: Option<Expr>,
+    crossover: Expr,
+    mutation: Expr,
+}
+
+struct GenomeField {
+    member: Member,
+    ty: Type,
+    config: ConfigSource,
+    cxpb: Option<Expr>,
+    mutpb: Option<Expr>,
+}
+
+enum FieldKind {
+    Genome(Box<GenomeField>),
+    Skip(Member),
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "`Solution` cannot be derived for generic types",
+        ));
+    }
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "`Solution` can only be derived for structs",
+            ))
+        }
+    };
+
+    let options = parse_struct_options(&input)?;
+    let fields = data
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(i.into()),
+            };
+            parse_field(member, field)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let name = &input.ident;
+    let fitness = &options.fitness;
+    let evaluate = &options.evaluate;
+    let directions = options.directions.as_ref().map(|directions| {
+        quote!(const DIRECTIONS: &'static [::eviolite::fitness::Direction] = &#directions;)
+    });
+    let dominance_tolerance = options
+        .dominance_tolerance
+        .as_ref()
+        .map(|tolerance| quote!(const DOMINANCE_TOLERANCE: f64 = #tolerance;));
+    let repair = options.repair.as_ref().map(|repair| {
+        quote! {
+            fn repair(&mut self) {
+                (#repair)(self)
+            }
+        }
+    });
+
+    let mut config_fns = Vec::new();
+    let mut generate = Vec::new();
+    let mut crossover = Vec::new();
+    let mut mutate = Vec::new();
+
+    for (i, field) in fields.iter().enumerate() {
+        let field = match field {
+            FieldKind::Genome(field) => field,
+            FieldKind::Skip(member) => {
+                generate.push(quote!(#member: ::std::default::Default::default()));
+                continue;
+            }
+        };
+        let GenomeField {
+            member,
+            ty,
+            config,
+            cxpb,
+            mutpb,
+        } = &**field;
+        let config_fn = format_ident!("__eviolite_config_{}", i);
+        let config_ty = quote!(<#ty as ::eviolite::genome::Genome>::Config);
+        let init = match config {
+            ConfigSource::Expr(expr) => quote!(#expr),
+            ConfigSource::Vector(options) => {
+                let VectorOptions {
+                    len,
+                    bounds,
+                    crossover,
+                    mutation,
+                } = &**options;
+                let bounds = bounds.as_ref().map_or_else(|| quote!(()), |b| quote!(#b));
+                quote! {
+                    <#config_ty as ::eviolite::genome::VectorConfig>::vector_config(
+                        #len, #bounds, #crossover, #mutation,
+                    )
+                }
+            }
+        };
+        config_fns.push(quote! {
+            fn #config_fn() -> &'static #config_ty {
+                static CONFIG: ::std::sync::OnceLock<#config_ty> = ::std::sync::OnceLock::new();
+                CONFIG.get_or_init(|| #init)
+            }
+        });
+
+        generate.push(quote!(#member: <#ty as ::eviolite::genome::Genome>::generate(#config_fn())));
+
+        let cx = quote! {
+            <#ty as ::eviolite::genome::Genome>::crossover(&mut a.#member, &mut b.#member, #config_fn());
+        };
+        crossover.push(with_probability(cxpb.as_ref(), cx));
+
+        let mt = quote! {
+            <#ty as ::eviolite::genome::Genome>::mutate(&mut self.#member, #config_fn());
+        };
+        mutate.push(with_probability(mutpb.as_ref(), mt));
+    }
+
+    Ok(quote! {
+        const _: () = {
+            #(#config_fns)*
+
+            impl ::eviolite::Solution for #name {
+                type Fitness = #fitness;
+                #directions
+                #dominance_tolerance
+
+                fn generate() -> Self {
+                    #name { #(#generate),* }
+                }
+
+                fn evaluate(&self) -> Self::Fitness {
+                    (#evaluate)(self)
+                }
+
+                fn crossover(a: &mut Self, b: &mut Self) {
+                    #(#crossover)*
+                }
+
+                fn mutate(&mut self) {
+                    #(#mutate)*
+                }
+
+                #repair
+            }
+        };
+    })
+}
+
+// Wrap an operation in a random check if a probability was given.
+fn with_probability(prob: Option<&Expr>, op: TokenStream2) -> TokenStream2 {
+    match prob {
+        Some(prob) => quote! {
+            if ::eviolite::repro_rng::random::<f64>() < (#prob) {
+                #op
+            }
+        },
+        None => op,
+    }
+}
+
+fn parse_struct_options(input: &DeriveInput) -> Result<StructOptions> {
+    let mut fitness = None;
+    let mut evaluate = None;
+    let mut directions = None;
+    let mut dominance_tolerance = None;
+    let mut repair = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("solution")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("fitness") {
+                fitness = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("evaluate") {
+                evaluate = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("directions") {
+                directions = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("dominance_tolerance") {
+                dominance_tolerance = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("repair") {
+                repair = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error(
+                    "expected `fitness`, `evaluate`, `directions`, `dominance_tolerance`, or `repair`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    let missing = |key| {
+        Error::new(
+            input.ident.span(),
+            format!("missing `#[solution({} = ...)]` attribute", key),
+        )
+    };
+    Ok(StructOptions {
+        fitness: fitness.ok_or_else(|| missing("fitness"))?,
+        evaluate: evaluate.ok_or_else(|| missing("evaluate"))?,
+        directions,
+        dominance_tolerance,
+        repair,
+    })
+}
+
+fn parse_field(member: Member, field: &syn::Field) -> Result<FieldKind> {
+    let mut skip = false;
+    let mut config = None;
+    let mut len = None;
+    let mut bounds = None;
+    let mut crossover = None;
+    let mut mutation = None;
+    let mut cxpb = None;
+    let mut mutpb = None;
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("solution")) {
+        attr.parse_nested_meta(|meta| {
+            let slot = if meta.path.is_ident("skip") {
+                skip = true;
+                return Ok(());
+            } else if meta.path.is_ident("config") {
+                &mut config
+            } else if meta.path.is_ident("len") {
+                &mut len
+            } else if meta.path.is_ident("bounds") {
+                &mut bounds
+            } else if meta.path.is_ident("crossover") {
+                &mut crossover
+            } else if meta.path.is_ident("mutation") {
+                &mut mutation
+            } else if meta.path.is_ident("cxpb") {
+                &mut cxpb
+            } else if meta.path.is_ident("mutpb") {
+                &mut mutpb
+            } else {
+                return Err(meta.error(
+                    "expected one of `skip`, `config`, `len`, `bounds`, `crossover`, `mutation`, `cxpb`, `mutpb`",
+                ));
+            };
+            *slot = Some(meta.value()?.parse::<Expr>()?);
+            Ok(())
+        })?;
+    }
+
+    if skip {
+        return Ok(FieldKind::Skip(member));
+    }
+
+    let config = match (config, len, crossover, mutation) {
+        (Some(expr), None, None, None) if bounds.is_none() => ConfigSource::Expr(expr),
+        (None, Some(len), Some(crossover), Some(mutation)) => {
+            ConfigSource::Vector(Box::new(VectorOptions {
+                len,
+                bounds,
+                crossover,
+                mutation,
+            }))
+        }
+        (Some(_), ..) => {
+            return Err(Error::new(
+                field.span(),
+                "`config` cannot be combined with `len`, `bounds`, `crossover`, or `mutation`",
+            ))
+        }
+        _ => {
+            return Err(Error::new(
+                field.span(),
+                "genome fields need either `#[solution(config = ...)]` \
+                 or `#[solution(len = ..., crossover = ..., mutation = ...)]`, \
+                 or `#[solution(skip)]` to leave them out",
+            ))
+        }
+    };
+
+    Ok(FieldKind::Genome(Box::new(GenomeField {
+        member,
+        ty: field.ty.clone(),
+        config,
+        cxpb,
+        mutpb,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, ImplItem, Item, Stmt};
+
+    use super::*;
+
+    // The items generated for `input`, as strings, with the names of the methods of the impl
+    fn expand_items(input: DeriveInput) -> (Vec<String>, Vec<(String, String)>) {
+        let expanded: Item = syn::parse2(expand(input).unwrap()).unwrap();
+        let block = match expanded {
+            Item::Const(item) => match *item.expr {
+                Expr::Block(block) => block.block,
+                _ => panic!("expected a block"),
+            },
+            _ => panic!("expected `const _: () = {{ ... }};`"),
+        };
+        let mut config_fns = Vec::new();
+        let mut methods = Vec::new();
+        for stmt in block.stmts {
+            match stmt {
+                Stmt::Item(Item::Fn(f)) => config_fns.push(f.sig.ident.to_string()),
+                Stmt::Item(Item::Impl(imp)) => {
+                    for item in imp.items {
+                        match item {
+                            ImplItem::Fn(f) => {
+                                methods.push((f.sig.ident.to_string(), quote!(#f).to_string()))
+                            }
+                            ImplItem::Type(ty) => {
+                                methods.push((ty.ident.to_string(), quote!(#ty).to_string()))
+                            }
+                            ImplItem::Const(c) => {
+                                methods.push((c.ident.to_string(), quote!(#c).to_string()))
+                            }
+                            _ => panic!("unexpected impl item"),
+                        }
+                    }
+                }
+                _ => panic!("unexpected statement"),
+            }
+        }
+        (config_fns, methods)
+    }
+
+    fn error(input: DeriveInput) -> String {
+        match expand(input) {
+            Ok(_) => panic!("expected an error"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn expansion() {
+        let (config_fns, methods) = expand_items(parse_quote! {
+            #[solution(fitness = f64, evaluate = Foo::eval)]
+            struct Foo {
+                #[solution(config = CONFIG)]
+                a: RealVector,
+                #[solution(skip)]
+                counter: usize,
+                #[solution(len = 3, crossover = Cx, mutation = Mt, cxpb = 0.5)]
+                c: BitString,
+            }
+        });
+        // fields are numbered by position, including skipped ones
+        assert_eq!(config_fns, ["__eviolite_config_0", "__eviolite_config_2"]);
+        let names: Vec<&str> = methods.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["Fitness", "generate", "evaluate", "crossover", "mutate"]
+        );
+
+        let generate = &methods[1].1;
+        assert!(generate.contains("counter : :: std :: default :: Default :: default ()"));
+        assert!(generate.contains("a : < RealVector as :: eviolite :: genome :: Genome > :: generate (__eviolite_config_0 ())"));
+        let crossover = &methods[3].1;
+        assert!(crossover.contains("if :: eviolite :: repro_rng :: random :: < f64 > () < (0.5)"));
+        assert!(!crossover.contains("counter"));
+        let mutate = &methods[4].1;
+        assert!(!mutate.contains("random"));
+        assert!(mutate.contains("Genome > :: mutate (& mut self . c , __eviolite_config_2 ())"));
+    }
+
+    #[test]
+    fn optional_items() {
+        let (config_fns, methods) = expand_items(parse_quote! {
+            #[solution(fitness = MultiObjective<2>, evaluate = |s: &Foo| s.0.eval())]
+            #[solution(directions = [Direction::Minimize, Direction::Maximize])]
+            #[solution(dominance_tolerance = 1e-6, repair = Foo::fix)]
+            struct Foo(#[solution(config = CONFIG, mutpb = 0.1)] RealVector);
+        });
+        assert_eq!(config_fns, ["__eviolite_config_0"]);
+        let names: Vec<&str> = methods.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "Fitness",
+                "DIRECTIONS",
+                "DOMINANCE_TOLERANCE",
+                "generate",
+                "evaluate",
+                "crossover",
+                "mutate",
+                "repair"
+            ]
+        );
+        assert!(methods[3].1.contains("Foo { 0 :"));
+        assert!(methods[6].1.contains("< (0.1)"));
+        assert!(methods[7].1.contains("(Foo :: fix) (self)"));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(error(parse_quote! {
+            #[solution(fitness = f64, evaluate = eval)]
+            struct Foo<T>(#[solution(config = CONFIG)] T);
+        })
+        .contains("generic"));
+        assert!(error(parse_quote! {
+            #[solution(fitness = f64, evaluate = eval)]
+            enum Foo { A }
+        })
+        .contains("only be derived for structs"));
+        assert!(error(parse_quote! {
+            #[solution(evaluate = eval)]
+            struct Foo;
+        })
+        .contains("missing `#[solution(fitness = ...)]`"));
+        assert!(error(parse_quote! {
+            #[solution(fitness = f64)]
+            struct Foo;
+        })
+        .contains("missing `#[solution(evaluate = ...)]`"));
+        assert!(error(parse_quote! {
+            #[solution(fitness = f64, evaluate = eval, speed = 1)]
+            struct Foo;
+        })
+        .contains("expected `fitness`"));
+        assert!(error(parse_quote! {
+            #[solution(fitness = f64, evaluate = eval)]
+            struct Foo(RealVector);
+        })
+        .contains("genome fields need"));
+        assert!(error(parse_quote! {
+            #[solution(fitness = f64, evaluate = eval)]
+            struct Foo(#[solution(len = 3, crossover = Cx)] RealVector);
+        })
+        .contains("genome fields need"));
+        assert!(error(parse_quote! {
+            #[solution(fitness = f64, evaluate = eval)]
+            struct Foo(#[solution(config = CONFIG, len = 3)] RealVector);
+        })
+        .contains("cannot be combined"));
+        assert!(error(parse_quote! {
+            #[solution(fitness = f64, evaluate = eval)]
+            struct Foo(#[solution(config = CONFIG, bounds = (0.0, 1.0))] RealVector);
+        })
+        .contains("cannot be combined"));
+        assert!(error(parse_quote! {
+            #[solution(fitness = f64, evaluate = eval)]
+            struct Foo(#[solution(size = 3)] RealVector);
+        })
+        .contains("expected one of"));
+    }
+}
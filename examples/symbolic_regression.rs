@@ -0,0 +1,105 @@
+use eviolite::gp::{self, symreg::*, Limits, PrimitiveSet, Tree};
+use eviolite::prelude::*;
+
+use rand::Rng;
+
+lazy_static::lazy_static! {
+    static ref PROBLEM: SymbolicRegression = {
+        // build the functions and terminals our expressions can use
+        // each expression takes one argument, which we'll call x
+        let mut pset = PrimitiveSet::new(1);
+        pset.rename_argument(0, "x");
+        // functions, with their arity
+        pset.add_primitive("add", 2, |args| args[0] + args[1]);
+        pset.add_primitive("sub", 2, |args| args[0] - args[1]);
+        pset.add_primitive("mul", 2, |args| args[0] * args[1]);
+        pset.add_primitive("div", 2, |args| protected_div(args[0], args[1]));
+        pset.add_primitive("sin", 1, |args| args[0].sin());
+        // a terminal that always has the same value
+        pset.add_terminal("one", 1.0);
+        // an ephemeral constant, which gets a new random value every time it's put into a tree
+        pset.add_ephemeral("c", || thread_rng().gen_range(-2.0..2.0));
+
+        // the quartic polynomial x⁴ + x³ + x² + x, sampled at 20 points between -1 and 1
+        let dataset = Dataset::from_fn(
+            (0..20).map(|i| vec![i as f64 / 10.0 - 1.0]),
+            |x| x[0].powi(4) + x[0].powi(3) + x[0].powi(2) + x[0],
+        );
+
+        SymbolicRegression::new(pset, dataset)
+            // penalize large expressions slightly so they don't bloat
+            .with_parsimony(Parsimony::Penalty { per_node: 0.0001 })
+    };
+}
+
+// DEAP's usual limit on tree depth
+const LIMITS: Limits = Limits {
+    max_depth: 17,
+    max_size: usize::MAX,
+};
+
+#[derive(Clone)]
+struct Expr(Tree<f64>);
+
+impl Solution for Expr {
+    type Fitness = f64;
+
+    fn generate() -> Self {
+        // ramped half-and-half, with depths between 1 and 4
+        Expr(PROBLEM.generate())
+    }
+
+    fn evaluate(&self) -> Self::Fitness {
+        // the negated mean squared error, plus the parsimony penalty
+        PROBLEM.fitness(&self.0)
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        // swap a random subtree of each expression
+        gp::subtree_crossover(&PROBLEM.pset, &mut a.0, &mut b.0, &LIMITS);
+    }
+
+    fn mutate(&mut self) {
+        if thread_rng().gen_bool(0.5) {
+            // replace a random subtree with a new one, up to 2 levels deep
+            gp::subtree_mutation(&PROBLEM.pset, &mut self.0, 0, 2, &LIMITS);
+        } else {
+            // or replace individual nodes with others of the same arity, keeping the shape
+            gp::point_mutation(&PROBLEM.pset, &mut self.0, 0.1);
+        }
+    }
+}
+
+fn main() {
+    let evo: Evolution<Expr, _, _, ()> = Evolution::new(
+        // the same algorithm as DEAP's symbolic regression example
+        alg::Simple::new(
+            // population size
+            300,
+            // crossover chance (cxpb)
+            0.5,
+            // mutation chance (mutpb)
+            0.1,
+            // selection operator
+            select::Tournament::new(3),
+        ),
+        // a hall of fame that will track the single best expression we've found
+        hof::BestN::new(1),
+    );
+
+    let start = std::time::Instant::now();
+    // run until the mean squared error is below 10⁻⁴, or for at most 200 generations
+    let log = evo.run_until(|gen| {
+        gen.gen >= 200 || PROBLEM.error(&gen.hall_of_fame[0].as_ref().0) < 1e-4
+    });
+    let time = start.elapsed();
+
+    let best = &log.hall_of_fame[0].as_ref().0;
+    println!(
+        "found in {:.3} secs: f(x) ≈ {} (mean squared error {:.2e}, {} nodes)",
+        time.as_secs_f64(),
+        best.display(&PROBLEM.pset),
+        PROBLEM.error(best),
+        best.len()
+    );
+}
@@ -3,14 +3,24 @@
 //! This module contains the [`Algorithm`] trait and several pre-built algorithms that are commonly used.
 //! If you want to get started quickly, using one of the pre-built algorithms is your best bet.
 
-use std::marker::PhantomData;
+use alloc::{vec, vec::Vec};
+use core::{
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
 
-use rand::{seq::SliceRandom, Rng};
+use rand::{
+    seq::{index::sample, SliceRandom},
+    Rng,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use crate::{
-    fitness::{par_evaluate, MultiObjective},
-    repro_rng::thread_rng,
+    fitness::{par_evaluate, Direction, MultiObjective},
+    repro_rng::{self, thread_rng},
     select::{Select, Stochastic},
+    species::{self, Compatible},
     utils::Cached,
     Solution,
 };
@@ -35,6 +45,25 @@ pub trait Algorithm<T: Solution> {
     /// [`par_evaluate()`]: ./fn.par_evaluate.html
     fn step(&self, population: &mut Vec<Cached<T>>);
 
+    /// Advance one generation, using `scratch` as working space for offspring.
+    ///
+    /// [`Evolution`] keeps the same `scratch` vector for the whole run and passes it to every step,
+    /// so an algorithm can leave solutions in it for the next generation to reuse their allocations,
+    /// e.g. by overwriting them with [`gen_or_into()`], and by passing it to [`Select::select_reusing()`]
+    /// so the solutions that aren't selected end up in it. Anything in it may be overwritten or discarded
+    /// at the start of the next step.
+    ///
+    /// The default implementation ignores `scratch` and calls [`.step()`].
+    ///
+    /// [`Evolution`]: ../struct.Evolution.html
+    /// [`gen_or_into()`]: ./fn.gen_or_into.html
+    /// [`Select::select_reusing()`]: ../select/trait.Select.html#method.select_reusing
+    /// [`.step()`]: #tymethod.step
+    fn step_with_scratch(&self, population: &mut Vec<Cached<T>>, scratch: &mut Vec<Cached<T>>) {
+        let _ = scratch;
+        self.step(population);
+    }
+
     /// Get the desired population size of the algorithm.
     ///
     /// This is used by [`Evolution`] to generate the initial population for a run.
@@ -43,6 +72,363 @@ pub trait Algorithm<T: Solution> {
     fn pop_size(&self) -> usize;
 }
 
+/// The reason a parameter was rejected by one of the `try_new` constructors
+///
+/// This is returned by the algorithms in this module, and by [`Tournament::try_new()`].
+/// Their `new` constructors don't check their parameters, so invalid ones only cause a panic
+/// once the run has started, if at all.
+///
+/// [`Tournament::try_new()`]: ../select/struct.Tournament.html#method.try_new
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigError {
+    /// A probability was outside of `[0, 1]`, or NaN.
+    Probability {
+        /// The name of the parameter, e.g. `"cxpb"`.
+        name: &'static str,
+        /// The value that was given.
+        value: f64,
+    },
+    /// The crossover and mutation probabilities add up to more than 1,
+    /// for an algorithm that chooses between crossover, mutation, and reproduction for each offspring.
+    ProbabilitySum {
+        /// The crossover probability that was given.
+        cxpb: f64,
+        /// The mutation probability that was given.
+        mutpb: f64,
+    },
+    /// A number that has to be at least 1, like a population size, was 0.
+    Zero {
+        /// The name of the parameter, e.g. `"lambda"`.
+        name: &'static str,
+    },
+    /// μ was larger than λ, so μ solutions can't be selected from the λ offspring.
+    MuAboveLambda {
+        /// The value of μ that was given.
+        mu: usize,
+        /// The value of λ that was given.
+        lambda: usize,
+    },
+}
+
+impl core::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConfigError::Probability { name, value } => {
+                write!(
+                    f,
+                    "{} must be a probability between 0 and 1, got {}",
+                    name, value
+                )
+            }
+            ConfigError::ProbabilitySum { cxpb, mutpb } => write!(
+                f,
+                "cxpb + mutpb must be at most 1, got {} + {} = {}",
+                cxpb,
+                mutpb,
+                cxpb + mutpb
+            ),
+            ConfigError::Zero { name } => write!(f, "{} must be at least 1", name),
+            ConfigError::MuAboveLambda { mu, lambda } => write!(
+                f,
+                "mu must be at most lambda, got mu = {} and lambda = {}",
+                mu, lambda
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConfigError {}
+
+fn check_probability(name: &'static str, value: f64) -> Result<(), ConfigError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(())
+    } else {
+        Err(ConfigError::Probability { name, value })
+    }
+}
+
+pub(crate) fn check_nonzero(name: &'static str, value: usize) -> Result<(), ConfigError> {
+    if value == 0 {
+        Err(ConfigError::Zero { name })
+    } else {
+        Ok(())
+    }
+}
+
+// For algorithms that generate offspring with `gen_or()`
+fn check_gen_or(cxpb: f64, mutpb: f64) -> Result<(), ConfigError> {
+    check_probability("cxpb", cxpb)?;
+    check_probability("mutpb", mutpb)?;
+    if cxpb + mutpb > 1.0 {
+        return Err(ConfigError::ProbabilitySum { cxpb, mutpb });
+    }
+    Ok(())
+}
+
+/// A way of producing offspring from a pool of selected parents
+///
+/// The pre-built algorithms that use crossover and mutation take their variation as a type parameter,
+/// which defaults to the fixed scheme they've always used: [`VarAnd`] for [`Simple`] and [`Speciated`],
+/// and [`GenOr`] for [`MuPlusLambda`], [`MuCommaLambda`], and [`NSGA2`]. Replacing it with
+/// `.with_variation()` lets you change how offspring are made, e.g. with brood recombination,
+/// crossover between more than two parents, or a policy that chooses between several operators,
+/// without reimplementing the rest of the algorithm.
+///
+/// Each of the variation functions in this module has a matching implementation:
+/// - [`VarAnd`] for [`var_and()`], and for [`var_and_with()`] with `.with_pairing()`
+/// - [`ParVarAnd`] for [`par_var_and()`]
+/// - [`GenOr`] for [`gen_or()`]
+/// - [`GenOrPairs`] for [`gen_or_pairs()`]
+///
+/// Algorithms that generate a separate set of offspring call [`.vary()`],
+/// while algorithms that replace the selected parents with their offspring call [`.vary_in_place()`].
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use eviolite::prelude::*;
+/// use eviolite::alg::Variation;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+/// use rand::seq::SliceRandom;
+///
+/// // Brood recombination: each pair of parents has several children, and only the best one is kept
+/// struct Brood(usize);
+///
+/// impl<T: Solution<Fitness = f64>> Variation<T> for Brood {
+///     fn vary(&self, parents: &[Cached<T>], n_offspring: usize, offspring: &mut Vec<Cached<T>>) {
+///         offspring.clear();
+///         let mut rng = thread_rng();
+///         for _ in 0..n_offspring {
+///             let brood: Vec<Cached<T>> = (0..self.0)
+///                 .map(|_| {
+///                     let mut pair = parents.choose_multiple(&mut rng, 2).cloned();
+///                     let (mut a, mut b) = (pair.next().unwrap(), pair.next().unwrap());
+///                     Cached::crossover(&mut a, &mut b);
+///                     a
+///                 })
+///                 .collect();
+///             fitness::par_evaluate(&brood);
+///             let best = brood
+///                 .into_iter()
+///                 .min_by(|a, b| a.evaluate().total_cmp(&b.evaluate()))
+///                 .unwrap();
+///             offspring.push(best);
+///         }
+///     }
+/// }
+///
+/// let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> = Evolution::new(
+///     alg::MuPlusLambda::new(20, 40, 0.0, 0.0, select::Tournament::new(3)).with_variation(Brood(4)),
+///     hof::BestN::new(1),
+/// );
+/// evo.run_for(10);
+/// ```
+///
+/// [`VarAnd`]: ./struct.VarAnd.html
+/// [`GenOr`]: ./struct.GenOr.html
+/// [`var_and()`]: ./fn.var_and.html
+/// [`var_and_with()`]: ./fn.var_and_with.html
+/// [`ParVarAnd`]: ./struct.ParVarAnd.html
+/// [`par_var_and()`]: ./fn.par_var_and.html
+/// [`gen_or()`]: ./fn.gen_or.html
+/// [`GenOrPairs`]: ./struct.GenOrPairs.html
+/// [`gen_or_pairs()`]: ./fn.gen_or_pairs.html
+/// [`Simple`]: ./struct.Simple.html
+/// [`Speciated`]: ./struct.Speciated.html
+/// [`MuPlusLambda`]: ./struct.MuPlusLambda.html
+/// [`MuCommaLambda`]: ./struct.MuCommaLambda.html
+/// [`NSGA2`]: ./struct.NSGA2.html
+/// [`.vary()`]: #tymethod.vary
+/// [`.vary_in_place()`]: #method.vary_in_place
+pub trait Variation<T: Solution> {
+    /// Produce `n_offspring` offspring from `parents`, replacing the contents of `offspring` with them.
+    ///
+    /// `offspring` may contain solutions left over from an earlier generation,
+    /// which can be overwritten to reuse their allocations.
+    fn vary(&self, parents: &[Cached<T>], n_offspring: usize, offspring: &mut Vec<Cached<T>>);
+
+    /// Replace a population of selected parents with the same number of offspring,
+    /// using `scratch` as working space.
+    ///
+    /// The default implementation generates the offspring into `scratch` with [`.vary()`]
+    /// and swaps it with the population.
+    ///
+    /// [`.vary()`]: #tymethod.vary
+    fn vary_in_place(&self, population: &mut Vec<Cached<T>>, scratch: &mut Vec<Cached<T>>) {
+        self.vary(population, population.len(), scratch);
+        core::mem::swap(population, scratch);
+    }
+}
+
+/// The [`Variation`] that applies [`var_and_with()`] to the parents
+///
+/// When generating a separate set of offspring, the parents are cloned in order,
+/// starting over from the first one if more offspring than parents are needed, and the clones are varied.
+///
+/// [`Variation`]: ./trait.Variation.html
+/// [`var_and_with()`]: ./fn.var_and_with.html
+#[derive(Clone, Debug)]
+pub struct VarAnd<T> {
+    cxpb: f64,
+    mutpb: f64,
+    pairing: Pairing<T>,
+}
+
+impl<T> VarAnd<T> {
+    /// Cross over adjacent solutions with chance `cxpb` and mutate each solution with chance `mutpb`.
+    pub fn new(cxpb: f64, mutpb: f64) -> Self {
+        VarAnd {
+            cxpb,
+            mutpb,
+            pairing: Pairing::Adjacent,
+        }
+    }
+
+    /// Create a new `VarAnd`, checking that `cxpb` and `mutpb` are probabilities.
+    pub fn try_new(cxpb: f64, mutpb: f64) -> Result<Self, ConfigError> {
+        check_probability("cxpb", cxpb)?;
+        check_probability("mutpb", mutpb)?;
+        Ok(VarAnd::new(cxpb, mutpb))
+    }
+
+    /// Choose the solutions that are crossed over with each other using `pairing`.
+    ///
+    /// See [`Pairing`] for the options.
+    ///
+    /// [`Pairing`]: ./enum.Pairing.html
+    pub fn with_pairing(mut self, pairing: Pairing<T>) -> Self {
+        self.pairing = pairing;
+        self
+    }
+}
+
+impl<T: Solution> Variation<T> for VarAnd<T> {
+    fn vary(&self, parents: &[Cached<T>], n_offspring: usize, offspring: &mut Vec<Cached<T>>) {
+        for (i, parent) in parents.iter().cycle().take(n_offspring).enumerate() {
+            overwrite(offspring, i, parent);
+        }
+        offspring.truncate(n_offspring);
+        self.vary_in_place(offspring, &mut Vec::new());
+    }
+
+    fn vary_in_place(&self, population: &mut Vec<Cached<T>>, _scratch: &mut Vec<Cached<T>>) {
+        vary_pairs(
+            population,
+            self.cxpb,
+            self.mutpb,
+            self.pairing,
+            Cached::as_ref,
+        );
+    }
+}
+
+/// The [`Variation`] that applies [`par_var_and()`] to the parents
+///
+/// Like [`VarAnd`], it clones the parents in order when generating a separate set of offspring,
+/// but then crosses over disjoint pairs and varies them in parallel.
+///
+/// [`Variation`]: ./trait.Variation.html
+/// [`par_var_and()`]: ./fn.par_var_and.html
+/// [`VarAnd`]: ./struct.VarAnd.html
+#[derive(Clone, Copy, Debug)]
+pub struct ParVarAnd {
+    cxpb: f64,
+    mutpb: f64,
+}
+
+impl ParVarAnd {
+    /// Cross over disjoint pairs of solutions with chance `cxpb` and mutate each solution with chance `mutpb`.
+    pub fn new(cxpb: f64, mutpb: f64) -> Self {
+        ParVarAnd { cxpb, mutpb }
+    }
+
+    /// Create a new `ParVarAnd`, checking that `cxpb` and `mutpb` are probabilities.
+    pub fn try_new(cxpb: f64, mutpb: f64) -> Result<Self, ConfigError> {
+        check_probability("cxpb", cxpb)?;
+        check_probability("mutpb", mutpb)?;
+        Ok(ParVarAnd::new(cxpb, mutpb))
+    }
+}
+
+impl<T> Variation<T> for ParVarAnd
+where
+    T: Solution,
+    Cached<T>: Send,
+{
+    fn vary(&self, parents: &[Cached<T>], n_offspring: usize, offspring: &mut Vec<Cached<T>>) {
+        for (i, parent) in parents.iter().cycle().take(n_offspring).enumerate() {
+            overwrite(offspring, i, parent);
+        }
+        offspring.truncate(n_offspring);
+        self.vary_in_place(offspring, &mut Vec::new());
+    }
+
+    fn vary_in_place(&self, population: &mut Vec<Cached<T>>, _scratch: &mut Vec<Cached<T>>) {
+        par_var_and(population, self.cxpb, self.mutpb);
+    }
+}
+
+/// The [`Variation`] that generates offspring with [`gen_or_into()`]
+///
+/// [`Variation`]: ./trait.Variation.html
+/// [`gen_or_into()`]: ./fn.gen_or_into.html
+#[derive(Clone, Copy, Debug)]
+pub struct GenOr {
+    cxpb: f64,
+    mutpb: f64,
+}
+
+impl GenOr {
+    /// Make each offspring by crossover with chance `cxpb`, by mutation with chance `mutpb`,
+    /// and by cloning otherwise.
+    pub fn new(cxpb: f64, mutpb: f64) -> Self {
+        GenOr { cxpb, mutpb }
+    }
+
+    /// Create a new `GenOr`, checking that `cxpb` and `mutpb` are probabilities that add up to at most 1.
+    pub fn try_new(cxpb: f64, mutpb: f64) -> Result<Self, ConfigError> {
+        check_gen_or(cxpb, mutpb)?;
+        Ok(GenOr::new(cxpb, mutpb))
+    }
+}
+
+impl<T: Solution> Variation<T> for GenOr {
+    fn vary(&self, parents: &[Cached<T>], n_offspring: usize, offspring: &mut Vec<Cached<T>>) {
+        gen_or_into(parents, n_offspring, self.cxpb, self.mutpb, offspring);
+    }
+}
+
+/// The [`Variation`] that generates offspring with [`gen_or_pairs_into()`],
+/// keeping both children of each crossover
+///
+/// [`Variation`]: ./trait.Variation.html
+/// [`gen_or_pairs_into()`]: ./fn.gen_or_pairs_into.html
+#[derive(Clone, Copy, Debug)]
+pub struct GenOrPairs {
+    cxpb: f64,
+    mutpb: f64,
+}
+
+impl GenOrPairs {
+    /// Make each pair of offspring by crossover with chance `cxpb`, and each single offspring
+    /// by mutation with chance `mutpb` and by cloning otherwise.
+    pub fn new(cxpb: f64, mutpb: f64) -> Self {
+        GenOrPairs { cxpb, mutpb }
+    }
+
+    /// Create a new `GenOrPairs`, checking that `cxpb` and `mutpb` are probabilities that add up to at most 1.
+    pub fn try_new(cxpb: f64, mutpb: f64) -> Result<Self, ConfigError> {
+        check_gen_or(cxpb, mutpb)?;
+        Ok(GenOrPairs::new(cxpb, mutpb))
+    }
+}
+
+impl<T: Solution> Variation<T> for GenOrPairs {
+    fn vary(&self, parents: &[Cached<T>], n_offspring: usize, offspring: &mut Vec<Cached<T>>) {
+        gen_or_pairs_into(parents, n_offspring, self.cxpb, self.mutpb, offspring);
+    }
+}
+
 /// One of the simplest possible evolutionary algorithms.
 ///
 /// This is a good starting point, especially for single-objective optimization,
@@ -60,18 +446,27 @@ pub trait Algorithm<T: Solution> {
 /// ```notrust
 /// select N solutions out of the population of N (this must necessarily result in duplicates)
 /// replace the population with that selection
-/// apply var_and to the population
+/// replace the population with its offspring, using var_and by default,
+/// except for the elites kept by the selector
 /// ```
+///
+/// The offspring are made with [`Variation::vary_in_place()`]; see [`.with_variation()`].
+/// With [`.par_var_and()`], the population is varied with [`par_var_and()`] instead.
+///
+/// [`Variation::vary_in_place()`]: ./trait.Variation.html#method.vary_in_place
+/// [`.with_variation()`]: #method.with_variation
+/// [`.par_var_and()`]: #method.par_var_and
+/// [`par_var_and()`]: ./fn.par_var_and.html
 #[derive(Clone, Debug)]
-pub struct Simple<T, S>
+pub struct Simple<T, S, V = VarAnd<T>>
 where
     T: Solution,
     S: Select<T> + Stochastic,
+    V: Variation<T>,
 {
     pop_size: usize,
-    cxpb: f64,
-    mutpb: f64,
     selector: S,
+    variation: V,
     _phantom: PhantomData<T>,
 }
 
@@ -84,29 +479,104 @@ where
     pub fn new(pop_size: usize, cxpb: f64, mutpb: f64, selector: S) -> Self {
         Simple {
             pop_size,
-            cxpb,
-            mutpb,
             selector,
+            variation: VarAnd::new(cxpb, mutpb),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Choose the solutions that are crossed over with each other using `pairing`
+    /// instead of crossing each solution over with the one before it.
+    ///
+    /// See [`Pairing`] for the options.
+    ///
+    /// [`Pairing`]: ./enum.Pairing.html
+    pub fn with_pairing(mut self, pairing: Pairing<T>) -> Self {
+        self.variation = self.variation.with_pairing(pairing);
+        self
+    }
+
+    /// Create a new instance of the `Simple` algorithm, checking that `pop_size` isn't 0
+    /// and that `cxpb` and `mutpb` are probabilities.
+    pub fn try_new(
+        pop_size: usize,
+        cxpb: f64,
+        mutpb: f64,
+        selector: S,
+    ) -> Result<Self, ConfigError> {
+        check_nonzero("pop_size", pop_size)?;
+        check_probability("cxpb", cxpb)?;
+        check_probability("mutpb", mutpb)?;
+        Ok(Simple::new(pop_size, cxpb, mutpb, selector))
+    }
+}
+
+impl<T, S> Simple<T, S>
+where
+    T: Solution,
+    Cached<T>: Send,
+    S: Select<T> + Stochastic,
+{
+    /// Vary the population with [`par_var_and()`] instead of [`var_and()`],
+    /// crossing over disjoint pairs of solutions in parallel.
+    ///
+    /// This is shorthand for `.with_variation(ParVarAnd::new(cxpb, mutpb))`,
+    /// so it leaves out any [`.with_pairing()`].
+    ///
+    /// [`par_var_and()`]: ./fn.par_var_and.html
+    /// [`var_and()`]: ./fn.var_and.html
+    /// [`.with_pairing()`]: #method.with_pairing
+    pub fn par_var_and(self) -> Simple<T, S, ParVarAnd> {
+        let VarAnd { cxpb, mutpb, .. } = self.variation;
+        self.with_variation(ParVarAnd::new(cxpb, mutpb))
+    }
+}
+
+impl<T, S, V> Simple<T, S, V>
+where
+    T: Solution,
+    S: Select<T> + Stochastic,
+    V: Variation<T>,
+{
+    /// Make the offspring with `variation` instead of [`var_and()`].
+    ///
+    /// [`var_and()`]: ./fn.var_and.html
+    pub fn with_variation<W: Variation<T>>(self, variation: W) -> Simple<T, S, W> {
+        Simple {
+            pop_size: self.pop_size,
+            selector: self.selector,
+            variation,
             _phantom: PhantomData,
         }
     }
 }
 
-impl<T, S> Algorithm<T> for Simple<T, S>
+impl<T, S, V> Algorithm<T> for Simple<T, S, V>
 where
     T: Solution,
     S: Select<T> + Stochastic,
+    V: Variation<T>,
 {
     fn pop_size(&self) -> usize {
         self.pop_size
     }
 
     fn step(&self, population: &mut Vec<Cached<T>>) {
-        debug_assert_eq!(self.pop_size, population.len());
+        self.step_with_scratch(population, &mut Vec::new());
+    }
 
-        self.selector.select(self.pop_size, population);
+    fn step_with_scratch(&self, population: &mut Vec<Cached<T>>, scratch: &mut Vec<Cached<T>>) {
+        self.selector
+            .select_reusing(self.pop_size, population, scratch);
 
-        var_and(population, self.cxpb, self.mutpb);
+        let elites = self.selector.elites().min(population.len());
+        if elites == 0 {
+            self.variation.vary_in_place(population, scratch);
+        } else {
+            let mut rest = population.split_off(elites);
+            self.variation.vary_in_place(&mut rest, scratch);
+            population.append(&mut rest);
+        }
     }
 }
 
@@ -116,23 +586,28 @@ where
 /// ----------
 /// A single step of the algorithm does the following:
 /// ```notrust
-/// generate λ offspring using gen_or
+/// generate λ offspring, using gen_or by default
 /// evaluate the offspring
 /// add the offspring to the population
 /// select μ solutions out of the population of μ + λ
 /// replace the population with that selection
 /// ```
+///
+/// With [`.gen_or_pairs()`], the offspring are generated with [`gen_or_pairs()`] instead.
+///
+/// [`.gen_or_pairs()`]: #method.gen_or_pairs
+/// [`gen_or_pairs()`]: ./fn.gen_or_pairs.html
 #[derive(Clone, Debug)]
-pub struct MuPlusLambda<T, S>
+pub struct MuPlusLambda<T, S, V = GenOr>
 where
     T: Solution,
     S: Select<T>,
+    V: Variation<T>,
 {
     mu: usize,
     lambda: usize,
-    cxpb: f64,
-    mutpb: f64,
     selector: S,
+    variation: V,
     _phantom: PhantomData<T>,
 }
 
@@ -146,29 +621,81 @@ where
         MuPlusLambda {
             mu,
             lambda,
-            cxpb,
-            mutpb,
             selector,
+            variation: GenOr::new(cxpb, mutpb),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create a new instance of the `MuPlusLambda` algorithm, checking that `mu` and `lambda` aren't 0,
+    /// and that `cxpb` and `mutpb` are probabilities that add up to at most 1.
+    pub fn try_new(
+        mu: usize,
+        lambda: usize,
+        cxpb: f64,
+        mutpb: f64,
+        selector: S,
+    ) -> Result<Self, ConfigError> {
+        check_nonzero("mu", mu)?;
+        check_nonzero("lambda", lambda)?;
+        check_gen_or(cxpb, mutpb)?;
+        Ok(MuPlusLambda::new(mu, lambda, cxpb, mutpb, selector))
+    }
+
+    /// Generate offspring with [`gen_or_pairs()`] instead of [`gen_or()`],
+    /// keeping both children of each crossover.
+    ///
+    /// This is shorthand for `.with_variation(GenOrPairs::new(cxpb, mutpb))`.
+    ///
+    /// [`gen_or_pairs()`]: ./fn.gen_or_pairs.html
+    /// [`gen_or()`]: ./fn.gen_or.html
+    pub fn gen_or_pairs(self) -> MuPlusLambda<T, S, GenOrPairs> {
+        let GenOr { cxpb, mutpb } = self.variation;
+        self.with_variation(GenOrPairs::new(cxpb, mutpb))
+    }
+}
+
+impl<T, S, V> MuPlusLambda<T, S, V>
+where
+    T: Solution,
+    S: Select<T>,
+    V: Variation<T>,
+{
+    /// Make the offspring with `variation` instead of [`gen_or()`].
+    ///
+    /// [`gen_or()`]: ./fn.gen_or.html
+    pub fn with_variation<W: Variation<T>>(self, variation: W) -> MuPlusLambda<T, S, W> {
+        MuPlusLambda {
+            mu: self.mu,
+            lambda: self.lambda,
+            selector: self.selector,
+            variation,
             _phantom: PhantomData,
         }
     }
 }
 
-impl<T, S> Algorithm<T> for MuPlusLambda<T, S>
+impl<T, S, V> Algorithm<T> for MuPlusLambda<T, S, V>
 where
     T: Solution,
     S: Select<T>,
+    V: Variation<T>,
 {
     fn pop_size(&self) -> usize {
         self.mu
     }
 
     fn step(&self, population: &mut Vec<Cached<T>>) {
-        population.append(&mut gen_or(population, self.lambda, self.cxpb, self.mutpb));
+        self.step_with_scratch(population, &mut Vec::new());
+    }
+
+    fn step_with_scratch(&self, population: &mut Vec<Cached<T>>, scratch: &mut Vec<Cached<T>>) {
+        self.variation.vary(population, self.lambda, scratch);
+        population.append(scratch);
 
         par_evaluate(population);
 
-        self.selector.select(self.mu, population);
+        self.selector.select_reusing(self.mu, population, scratch);
     }
 }
 
@@ -178,23 +705,28 @@ where
 /// ----------
 /// A single step of the algorithm does the following:
 /// ```notrust
-/// generate λ offspring using gen_or
+/// generate λ offspring, using gen_or by default
 /// evaluate the offspring
 /// replace the population with the offspring
 /// select μ solutions out of the population of λ
 /// make that selection the new population
 /// ```
+///
+/// With [`.gen_or_pairs()`], the offspring are generated with [`gen_or_pairs()`] instead.
+///
+/// [`.gen_or_pairs()`]: #method.gen_or_pairs
+/// [`gen_or_pairs()`]: ./fn.gen_or_pairs.html
 #[derive(Clone, Debug)]
-pub struct MuCommaLambda<T, S>
+pub struct MuCommaLambda<T, S, V = GenOr>
 where
     T: Solution,
     S: Select<T>,
+    V: Variation<T>,
 {
     mu: usize,
     lambda: usize,
-    cxpb: f64,
-    mutpb: f64,
     selector: S,
+    variation: V,
     _phantom: PhantomData<T>,
 }
 
@@ -216,92 +748,1055 @@ where
         MuCommaLambda {
             mu,
             lambda,
-            cxpb,
-            mutpb,
             selector,
+            variation: GenOr::new(cxpb, mutpb),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create a new instance of the `MuCommaLambda` algorithm, checking that `mu` isn't 0 or larger than `lambda`,
+    /// and that `cxpb` and `mutpb` are probabilities that add up to at most 1.
+    pub fn try_new(
+        mu: usize,
+        lambda: usize,
+        cxpb: f64,
+        mutpb: f64,
+        selector: S,
+    ) -> Result<Self, ConfigError> {
+        check_nonzero("mu", mu)?;
+        if mu > lambda {
+            return Err(ConfigError::MuAboveLambda { mu, lambda });
+        }
+        check_gen_or(cxpb, mutpb)?;
+        Ok(MuCommaLambda::new(mu, lambda, cxpb, mutpb, selector))
+    }
+
+    /// Generate offspring with [`gen_or_pairs()`] instead of [`gen_or()`],
+    /// keeping both children of each crossover.
+    ///
+    /// This is shorthand for `.with_variation(GenOrPairs::new(cxpb, mutpb))`.
+    ///
+    /// [`gen_or_pairs()`]: ./fn.gen_or_pairs.html
+    /// [`gen_or()`]: ./fn.gen_or.html
+    pub fn gen_or_pairs(self) -> MuCommaLambda<T, S, GenOrPairs> {
+        let GenOr { cxpb, mutpb } = self.variation;
+        self.with_variation(GenOrPairs::new(cxpb, mutpb))
+    }
+}
+
+impl<T, S, V> MuCommaLambda<T, S, V>
+where
+    T: Solution,
+    S: Select<T>,
+    V: Variation<T>,
+{
+    /// Make the offspring with `variation` instead of [`gen_or()`].
+    ///
+    /// [`gen_or()`]: ./fn.gen_or.html
+    pub fn with_variation<W: Variation<T>>(self, variation: W) -> MuCommaLambda<T, S, W> {
+        MuCommaLambda {
+            mu: self.mu,
+            lambda: self.lambda,
+            selector: self.selector,
+            variation,
             _phantom: PhantomData,
         }
     }
 }
 
-impl<T, S> Algorithm<T> for MuCommaLambda<T, S>
+impl<T, S, V> Algorithm<T> for MuCommaLambda<T, S, V>
 where
     T: Solution,
     S: Select<T>,
+    V: Variation<T>,
 {
     fn pop_size(&self) -> usize {
         self.mu
     }
 
     fn step(&self, population: &mut Vec<Cached<T>>) {
-        *population = gen_or(population, self.lambda, self.cxpb, self.mutpb);
+        self.step_with_scratch(population, &mut Vec::new());
+    }
+
+    // The offspring are generated over the previous generation's parents,
+    // and the current parents are kept around to be overwritten in the next one.
+    fn step_with_scratch(&self, population: &mut Vec<Cached<T>>, scratch: &mut Vec<Cached<T>>) {
+        self.variation.vary(population, self.lambda, scratch);
+        core::mem::swap(population, scratch);
 
         par_evaluate(population);
 
-        self.selector.select(self.mu, population);
+        self.selector.select_reusing(self.mu, population, scratch);
     }
 }
 
-/// An implementation of the NSGA-II evolutionary algorithm.
+/// Step-size adaptation by the 1/5th success rule
 ///
-/// For more information about NSGA-II, see the documentation for
-/// [`select::NSGA2`].
+/// A `StepSizeControl` holds a mutation step size that [`Solution::mutate`] can read with
+/// [`.step_size()`], and adapts it to how often mutation succeeds, i.e. makes a child better
+/// than its parent. Every `period` trials, if more than a fifth of them succeeded, the step size
+/// is divided by `factor` to search further away, and if fewer did, it's multiplied by `factor`
+/// to search closer by. This is Rechenberg's 1/5th success rule.
 ///
-/// [`select::NSGA2`]: ../select/struct.NSGA2.html
-#[derive(Clone, Debug)]
-pub struct NSGA2 {
-    pop_size: usize,
-    cxpb: f64,
-    mutpb: f64,
+/// [`OnePlusOne`] and [`OnePlusLambda`] record every offspring as a trial when given one with
+/// `.with_step_size()`. Since [`Solution::mutate`] doesn't take any parameters,
+/// the controller is meant to be kept in a `static`, which all its methods take `&self` for.
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use eviolite::prelude::*;
+/// use eviolite::alg::StepSizeControl;
+///
+/// static STEP: StepSizeControl = StepSizeControl::new(1.0);
+///
+/// #[derive(Clone, Debug)]
+/// struct Point(f64);
+///
+/// impl Solution for Point {
+///     type Fitness = f64;
+///     const DIRECTIONS: &'static [Direction] = &[Direction::Minimize];
+///
+///     fn generate() -> Self {
+///         Point(thread_rng().gen_range(-100.0..100.0))
+///     }
+///     fn evaluate(&self) -> f64 {
+///         (self.0 - 3.0).abs()
+///     }
+///     fn crossover(_: &mut Self, _: &mut Self) {}
+///     fn mutate(&mut self) {
+///         self.0 += STEP.step_size() * thread_rng().gen_range(-1.0..1.0);
+///     }
+/// }
+///
+/// let evo: Evolution<Point, _, _, ()> = Evolution::new(
+///     alg::OnePlusOne::new().with_step_size(&STEP),
+///     hof::BestN::new(1),
+/// );
+/// let log = evo.run_for(500);
+/// assert!(log.hall_of_fame[0].evaluate() < 0.1);
+/// ```
+///
+/// [`Solution::mutate`]: ../trait.Solution.html#tymethod.mutate
+/// [`.step_size()`]: #method.step_size
+/// [`OnePlusOne`]: ./struct.OnePlusOne.html
+/// [`OnePlusLambda`]: ./struct.OnePlusLambda.html
+#[derive(Debug)]
+pub struct StepSizeControl {
+    step_size: AtomicU64,
+    successes: AtomicUsize,
+    trials: AtomicUsize,
+    period: usize,
+    factor: f64,
 }
 
-impl NSGA2 {
-    /// Create a new instance of the `NSGA2` algorithm with the specified parameters.
-    pub fn new(pop_size: usize, cxpb: f64, mutpb: f64) -> Self {
-        NSGA2 {
-            pop_size,
-            cxpb,
-            mutpb,
+impl StepSizeControl {
+    /// Create a controller starting at the step size `initial`, adapting it every 10 trials
+    /// by a factor of 0.85.
+    pub const fn new(initial: f64) -> Self {
+        StepSizeControl {
+            step_size: AtomicU64::new(initial.to_bits()),
+            successes: AtomicUsize::new(0),
+            trials: AtomicUsize::new(0),
+            period: 10,
+            factor: 0.85,
         }
     }
-}
 
-impl<T, const M: usize> Algorithm<T> for NSGA2
-where
-    T: Solution<Fitness = MultiObjective<M>>,
-{
-    fn pop_size(&self) -> usize {
-        self.pop_size
+    /// Adapt the step size every `period` trials instead of every 10.
+    pub const fn with_period(mut self, period: usize) -> Self {
+        self.period = period;
+        self
     }
 
-    fn step(&self, population: &mut Vec<Cached<T>>) {
-        population.append(&mut gen_or(
-            population,
-            self.pop_size,
-            self.cxpb,
-            self.mutpb,
-        ));
+    /// Multiply or divide the step size by `factor` instead of 0.85.
+    /// It should be a little less than 1.
+    pub const fn with_factor(mut self, factor: f64) -> Self {
+        self.factor = factor;
+        self
+    }
 
-        par_evaluate(population);
+    /// Get the current step size.
+    pub fn step_size(&self) -> f64 {
+        f64::from_bits(self.step_size.load(Ordering::Relaxed))
+    }
+
+    /// Replace the current step size, e.g. to restart a run, and forget the trials since the last adaptation.
+    pub fn set_step_size(&self, step_size: f64) {
+        self.step_size.store(step_size.to_bits(), Ordering::Relaxed);
+        self.successes.store(0, Ordering::Relaxed);
+        self.trials.store(0, Ordering::Relaxed);
+    }
+
+    /// Record whether a mutation succeeded, adapting the step size if that completes a period.
+    pub fn record(&self, success: bool) {
+        let successes = self
+            .successes
+            .fetch_add(success as usize, Ordering::Relaxed)
+            + success as usize;
+        let trials = self.trials.fetch_add(1, Ordering::Relaxed) + 1;
+        if trials < self.period.max(1) {
+            return;
+        }
 
-        crate::select::NSGA2.select(self.pop_size, population);
+        let rate = successes as f64 / trials as f64;
+        let step_size = self.step_size();
+        if rate > 0.2 {
+            self.step_size
+                .store((step_size / self.factor).to_bits(), Ordering::Relaxed);
+        } else if rate < 0.2 {
+            self.step_size
+                .store((step_size * self.factor).to_bits(), Ordering::Relaxed);
+        }
+        self.successes.store(0, Ordering::Relaxed);
+        self.trials.store(0, Ordering::Relaxed);
     }
 }
 
-/// Vary a population in place.
+/// Implementation of the (1 + λ) evolutionary algorithm.
 ///
-/// This function has the potential to apply both crossover *and* mutation
-/// to the same solution, hence the name.
+/// This algorithm keeps a single parent and only uses mutation, so [`Solution::crossover`]
+/// is never called. An offspring replaces the parent if it is *at least as good*,
+/// which lets the search drift across plateaus of equal fitness.
+/// This makes it the standard choice for [Cartesian genetic programming].
 ///
 /// Pseudocode
 /// ----------
+/// A single step of the algorithm does the following:
 /// ```notrust
-/// for each solution in the population:
-///     if a random check of chance cxpb passes:
-///         apply crossover between the solution and the one adjacent to it
-///     if a random check of chance mutpb passes:
-///         apply mutation to the solution
+/// generate λ offspring by cloning and mutating the parent
+/// evaluate the offspring
+/// if the best offspring's fitness is at least as good as the parent's:
+///     replace the parent with the best offspring
 /// ```
+///
+/// [`Solution::crossover`]: ../trait.Solution.html#tymethod.crossover
+/// [Cartesian genetic programming]: ../gp/cgp/index.html
+#[derive(Clone, Debug)]
+pub struct OnePlusLambda {
+    lambda: usize,
+    step_size: Option<&'static StepSizeControl>,
+}
+
+impl OnePlusLambda {
+    /// Create a new instance of the `OnePlusLambda` algorithm with the specified number of offspring.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `lambda` is 0.
+    pub fn new(lambda: usize) -> Self {
+        if lambda == 0 {
+            panic!("(1 + λ) requires λ > 0");
+        }
+        OnePlusLambda {
+            lambda,
+            step_size: None,
+        }
+    }
+
+    /// Create a new instance of the `OnePlusLambda` algorithm, returning an error instead of panicking
+    /// if `lambda` is 0.
+    pub fn try_new(lambda: usize) -> Result<Self, ConfigError> {
+        check_nonzero("lambda", lambda)?;
+        Ok(OnePlusLambda::new(lambda))
+    }
+
+    /// Record whether each offspring is better than its parent with `control`,
+    /// which adapts the step size by the 1/5th success rule.
+    ///
+    /// See [`StepSizeControl`] for an example.
+    ///
+    /// [`StepSizeControl`]: ./struct.StepSizeControl.html
+    pub fn with_step_size(mut self, control: &'static StepSizeControl) -> Self {
+        self.step_size = Some(control);
+        self
+    }
+}
+
+impl<T> Algorithm<T> for OnePlusLambda
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+{
+    fn pop_size(&self) -> usize {
+        1
+    }
+
+    fn step(&self, population: &mut Vec<Cached<T>>) {
+        let direction = Direction::of::<T>(0);
+        let fit = |ind: &Cached<T>| -> f64 { ind.evaluate().into() };
+
+        // the population only contains more than one solution if it was changed from outside
+        let parent_idx = (0..population.len())
+            .max_by(|&a, &b| direction.compare(fit(&population[a]), fit(&population[b])))
+            .unwrap();
+        let parent = population.swap_remove(parent_idx);
+
+        let offspring: Vec<Cached<T>> = (0..self.lambda)
+            .map(|_| {
+                let mut child = parent.clone();
+                child.mutate();
+                child
+            })
+            .collect();
+        par_evaluate(&offspring);
+        if let Some(control) = self.step_size {
+            for child in &offspring {
+                control.record(direction.is_better(fit(child), fit(&parent)));
+            }
+        }
+
+        // ties go to the offspring that comes first, so there's no bias beyond randomness
+        let best = offspring
+            .into_iter()
+            .reduce(|best, child| {
+                if direction.is_better(fit(&child), fit(&best)) {
+                    child
+                } else {
+                    best
+                }
+            })
+            .unwrap();
+
+        population.clear();
+        population.push(if !direction.is_better(fit(&parent), fit(&best)) {
+            best
+        } else {
+            parent
+        });
+    }
+}
+
+/// Implementation of the (1 + 1) evolution strategy, i.e. a randomized hill climber.
+///
+/// This is the simplest algorithm there is, which makes it a useful baseline to check
+/// that a problem and its mutation operator work before trying anything more complicated.
+/// It's the same as [`OnePlusLambda`] with λ = 1, so a child that's as good as its parent replaces it.
+///
+/// Pseudocode
+/// ----------
+/// A single step of the algorithm does the following:
+/// ```notrust
+/// clone the parent and mutate the clone
+/// evaluate the child
+/// if the child's fitness is at least as good as the parent's:
+///     replace the parent with the child
+/// ```
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+///
+/// let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> =
+///     Evolution::new(alg::OnePlusOne::new(), hof::BestN::new(1));
+/// evo.run_for(100);
+/// ```
+///
+/// [`OnePlusLambda`]: ./struct.OnePlusLambda.html
+#[derive(Clone, Debug, Default)]
+pub struct OnePlusOne {
+    step_size: Option<&'static StepSizeControl>,
+}
+
+impl OnePlusOne {
+    /// Create a new instance of the `OnePlusOne` algorithm.
+    pub fn new() -> Self {
+        OnePlusOne::default()
+    }
+
+    /// Record whether each child is better than its parent with `control`,
+    /// which adapts the step size by the 1/5th success rule.
+    ///
+    /// See [`StepSizeControl`] for an example.
+    ///
+    /// [`StepSizeControl`]: ./struct.StepSizeControl.html
+    pub fn with_step_size(mut self, control: &'static StepSizeControl) -> Self {
+        self.step_size = Some(control);
+        self
+    }
+}
+
+impl<T> Algorithm<T> for OnePlusOne
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+{
+    fn pop_size(&self) -> usize {
+        1
+    }
+
+    fn step(&self, population: &mut Vec<Cached<T>>) {
+        OnePlusLambda {
+            lambda: 1,
+            step_size: self.step_size,
+        }
+        .step(population);
+    }
+}
+
+/// Implementation of deterministic crowding, a simple niching method.
+///
+/// Every solution has exactly one offspring in each generation, and offspring only compete
+/// with the parent they're most similar to according to a distance function, so a good solution
+/// in one region of the search space can't take over the slots of solutions in other regions.
+/// This keeps several optima in the population at once, which is useful for multimodal problems.
+/// An offspring replaces its parent if it is at least as good.
+///
+/// Pseudocode
+/// ----------
+/// A single step of the algorithm does the following:
+/// ```notrust
+/// shuffle the population and split it into pairs of parents
+/// for each pair of parents:
+///     clone the parents into two offspring
+///     if a random check of chance cxpb passes:
+///         apply crossover between the offspring
+///     for each offspring:
+///         if a random check of chance mutpb passes:
+///             apply mutation to the offspring
+/// evaluate the offspring
+/// for each pair of parents and their offspring:
+///     match each offspring with the closer parent, so the total distance is smallest
+///     replace each parent with its offspring if the offspring is at least as good
+/// ```
+///
+/// If the population has an odd length, the last solution's offspring is only ever mutated.
+#[derive(Clone, Debug)]
+pub struct DeterministicCrowding<T>
+where
+    T: Solution,
+{
+    pop_size: usize,
+    cxpb: f64,
+    mutpb: f64,
+    distance: fn(&T, &T) -> f64,
+}
+
+impl<T> DeterministicCrowding<T>
+where
+    T: Solution,
+{
+    /// Create a new instance of the `DeterministicCrowding` algorithm with the specified parameters,
+    /// measuring how similar two solutions are with `distance`.
+    pub fn new(pop_size: usize, cxpb: f64, mutpb: f64, distance: fn(&T, &T) -> f64) -> Self {
+        DeterministicCrowding {
+            pop_size,
+            cxpb,
+            mutpb,
+            distance,
+        }
+    }
+
+    /// Create a new instance of the `DeterministicCrowding` algorithm, checking that `pop_size` isn't 0
+    /// and that `cxpb` and `mutpb` are probabilities.
+    pub fn try_new(
+        pop_size: usize,
+        cxpb: f64,
+        mutpb: f64,
+        distance: fn(&T, &T) -> f64,
+    ) -> Result<Self, ConfigError> {
+        check_nonzero("pop_size", pop_size)?;
+        check_probability("cxpb", cxpb)?;
+        check_probability("mutpb", mutpb)?;
+        Ok(DeterministicCrowding::new(pop_size, cxpb, mutpb, distance))
+    }
+}
+
+impl<T> Algorithm<T> for DeterministicCrowding<T>
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+{
+    fn pop_size(&self) -> usize {
+        self.pop_size
+    }
+
+    fn step(&self, population: &mut Vec<Cached<T>>) {
+        self.step_with_scratch(population, &mut Vec::new());
+    }
+
+    fn step_with_scratch(&self, population: &mut Vec<Cached<T>>, scratch: &mut Vec<Cached<T>>) {
+        let mut rng = thread_rng();
+        population.shuffle(&mut rng);
+
+        // the offspring of the parents at each index are kept at the same index
+        scratch.clone_from(population);
+        for pair in scratch.chunks_mut(2) {
+            if let [a, b] = pair {
+                if rng.gen_bool(self.cxpb) {
+                    Cached::crossover(a, b);
+                }
+            }
+            for child in pair {
+                if rng.gen_bool(self.mutpb) {
+                    child.mutate();
+                }
+            }
+        }
+        par_evaluate(scratch);
+
+        let direction = Direction::of::<T>(0);
+        let fit = |ind: &Cached<T>| -> f64 { ind.evaluate().into() };
+        let dist = |a: &Cached<T>, b: &Cached<T>| (self.distance)(a.as_ref(), b.as_ref());
+        for (parents, children) in population.chunks_mut(2).zip(scratch.chunks_mut(2)) {
+            if let ([p1, p2], [c1, c2]) = (&*parents, &mut *children) {
+                if dist(p1, c2) + dist(p2, c1) < dist(p1, c1) + dist(p2, c2) {
+                    core::mem::swap(c1, c2);
+                }
+            }
+            for (parent, child) in parents.iter_mut().zip(children) {
+                if !direction.is_better(fit(parent), fit(child)) {
+                    core::mem::swap(parent, child);
+                }
+            }
+        }
+    }
+}
+
+/// A version of the [`Simple`] algorithm that selects and varies each species separately.
+///
+/// The population is sorted into species with [`species::speciate()`] every generation,
+/// and the offspring are split between the species in proportion to their [shared fitness],
+/// so species compete with each other by their average fitness rather than by their best solution,
+/// and a new species isn't wiped out right away. Within a species, solutions are selected
+/// by their own fitness, and crossover only happens between members of the same species.
+/// The number of species can be tracked with [`SpeciesSummary`].
+///
+/// Pseudocode
+/// ----------
+/// A single step of the algorithm does the following:
+/// ```notrust
+/// sort the population into species
+/// split N offspring between the species in proportion to the sum of their shared fitness
+/// for each species:
+///     select as many solutions out of the species as it gets offspring
+///     replace the selection with its offspring, using var_and by default
+/// replace the population with all of the offspring
+/// ```
+///
+/// The offspring of each species are made with [`Variation::vary_in_place()`]; see [`.with_variation()`].
+///
+/// [`Variation::vary_in_place()`]: ./trait.Variation.html#method.vary_in_place
+/// [`.with_variation()`]: #method.with_variation
+/// [`Simple`]: ./struct.Simple.html
+/// [`species::speciate()`]: ../species/fn.speciate.html
+/// [shared fitness]: ../species/fn.shared_fitness.html
+/// [`SpeciesSummary`]: ../stats/struct.SpeciesSummary.html
+#[derive(Clone, Debug)]
+pub struct Speciated<T, S, V = VarAnd<T>>
+where
+    T: Compatible,
+    S: Select<T> + Stochastic,
+    V: Variation<T>,
+{
+    pop_size: usize,
+    selector: S,
+    variation: V,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, S> Speciated<T, S>
+where
+    T: Compatible,
+    S: Select<T> + Stochastic,
+{
+    /// Create a new instance of the `Speciated` algorithm with the specified parameters.
+    pub fn new(pop_size: usize, cxpb: f64, mutpb: f64, selector: S) -> Self {
+        Speciated {
+            pop_size,
+            selector,
+            variation: VarAnd::new(cxpb, mutpb),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create a new instance of the `Speciated` algorithm, checking that `pop_size` isn't 0
+    /// and that `cxpb` and `mutpb` are probabilities.
+    pub fn try_new(
+        pop_size: usize,
+        cxpb: f64,
+        mutpb: f64,
+        selector: S,
+    ) -> Result<Self, ConfigError> {
+        check_nonzero("pop_size", pop_size)?;
+        check_probability("cxpb", cxpb)?;
+        check_probability("mutpb", mutpb)?;
+        Ok(Speciated::new(pop_size, cxpb, mutpb, selector))
+    }
+}
+
+impl<T, S, V> Speciated<T, S, V>
+where
+    T: Compatible,
+    S: Select<T> + Stochastic,
+    V: Variation<T>,
+{
+    /// Make the offspring of each species with `variation` instead of [`var_and()`].
+    ///
+    /// [`var_and()`]: ./fn.var_and.html
+    pub fn with_variation<W: Variation<T>>(self, variation: W) -> Speciated<T, S, W> {
+        Speciated {
+            pop_size: self.pop_size,
+            selector: self.selector,
+            variation,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, S, V> Algorithm<T> for Speciated<T, S, V>
+where
+    T: Compatible,
+    T::Fitness: Into<f64>,
+    S: Select<T> + Stochastic,
+    V: Variation<T>,
+{
+    fn pop_size(&self) -> usize {
+        self.pop_size
+    }
+
+    fn step(&self, population: &mut Vec<Cached<T>>) {
+        let species = species::speciate(population);
+        let shared = species::shared_fitness(population, &species);
+        let weights: Vec<f64> = species
+            .iter()
+            .map(|members| members.iter().map(|&i| shared[i]).sum())
+            .collect();
+        let counts = species::allocate(self.pop_size, &weights);
+
+        let mut groups: Vec<Vec<Cached<T>>> = species
+            .iter()
+            .map(|members| Vec::with_capacity(members.len()))
+            .collect();
+        let mut species_of = vec![0; population.len()];
+        for (s, members) in species.iter().enumerate() {
+            for &i in members {
+                species_of[i] = s;
+            }
+        }
+        for (ind, s) in population.drain(..).zip(species_of) {
+            groups[s].push(ind);
+        }
+
+        let mut scratch = Vec::new();
+        for (mut group, count) in groups.into_iter().zip(counts) {
+            if count == 0 {
+                continue;
+            }
+            self.selector.select(count, &mut group);
+            self.variation.vary_in_place(&mut group, &mut scratch);
+            population.append(&mut group);
+        }
+    }
+}
+
+/// An implementation of the NSGA-II evolutionary algorithm.
+///
+/// For more information about NSGA-II, see the documentation for
+/// [`select::NSGA2`].
+///
+/// The offspring are made with [`gen_or()`] by default; see [`.with_variation()`].
+///
+/// [`select::NSGA2`]: ../select/struct.NSGA2.html
+/// [`gen_or()`]: ./fn.gen_or.html
+/// [`.with_variation()`]: #method.with_variation
+#[derive(Clone, Debug)]
+pub struct NSGA2<V = GenOr> {
+    pop_size: usize,
+    variation: V,
+}
+
+impl NSGA2 {
+    /// Create a new instance of the `NSGA2` algorithm with the specified parameters.
+    pub fn new(pop_size: usize, cxpb: f64, mutpb: f64) -> Self {
+        NSGA2 {
+            pop_size,
+            variation: GenOr::new(cxpb, mutpb),
+        }
+    }
+
+    /// Create a new instance of the `NSGA2` algorithm, checking that `pop_size` isn't 0,
+    /// and that `cxpb` and `mutpb` are probabilities that add up to at most 1.
+    pub fn try_new(pop_size: usize, cxpb: f64, mutpb: f64) -> Result<Self, ConfigError> {
+        check_nonzero("pop_size", pop_size)?;
+        check_gen_or(cxpb, mutpb)?;
+        Ok(NSGA2::new(pop_size, cxpb, mutpb))
+    }
+
+    /// Generate offspring with [`gen_or_pairs()`] instead of [`gen_or()`],
+    /// keeping both children of each crossover.
+    ///
+    /// This is shorthand for `.with_variation(GenOrPairs::new(cxpb, mutpb))`.
+    ///
+    /// [`gen_or_pairs()`]: ./fn.gen_or_pairs.html
+    /// [`gen_or()`]: ./fn.gen_or.html
+    pub fn gen_or_pairs(self) -> NSGA2<GenOrPairs> {
+        let GenOr { cxpb, mutpb } = self.variation;
+        self.with_variation(GenOrPairs::new(cxpb, mutpb))
+    }
+}
+
+impl<V> NSGA2<V> {
+    /// Make the offspring with `variation` instead of [`gen_or()`].
+    ///
+    /// [`gen_or()`]: ./fn.gen_or.html
+    pub fn with_variation<W>(self, variation: W) -> NSGA2<W> {
+        NSGA2 {
+            pop_size: self.pop_size,
+            variation,
+        }
+    }
+}
+
+impl<T, V, const M: usize> Algorithm<T> for NSGA2<V>
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+    V: Variation<T>,
+{
+    fn pop_size(&self) -> usize {
+        self.pop_size
+    }
+
+    fn step(&self, population: &mut Vec<Cached<T>>) {
+        self.step_with_scratch(population, &mut Vec::new());
+    }
+
+    fn step_with_scratch(&self, population: &mut Vec<Cached<T>>, scratch: &mut Vec<Cached<T>>) {
+        self.variation.vary(population, self.pop_size, scratch);
+        population.append(scratch);
+
+        par_evaluate(population);
+
+        crate::select::NSGA2.select_reusing(self.pop_size, population, scratch);
+    }
+}
+
+/// A wrapper that adds random immigrants to any algorithm
+///
+/// After each step of the wrapped algorithm, a fraction of the population is replaced
+/// with freshly generated solutions. This keeps diversity from ever running out, which helps
+/// on dynamic problems, where the optimum moves after the population has converged,
+/// and on deceptive problems, where the population converges to the wrong optimum.
+/// Which solutions are replaced is set by [`Replace`].
+///
+/// Pseudocode
+/// ----------
+/// A single step of the algorithm does the following:
+/// ```notrust
+/// apply a step of the wrapped algorithm
+/// choose round(fraction * N) solutions out of the population of N
+/// replace each of them with a newly generated solution
+/// ```
+///
+/// [`Replace`]: ./enum.Replace.html
+#[derive(Clone, Debug)]
+pub struct WithImmigrants<A> {
+    algorithm: A,
+    fraction: f64,
+    replace: Replace,
+}
+
+/// Which solutions [`WithImmigrants`] replaces with immigrants
+///
+/// [`WithImmigrants`]: ./struct.WithImmigrants.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Replace {
+    /// Replace solutions chosen at random, as in Grefenstette's original random immigrants.
+    #[default]
+    Random,
+    /// Replace the worst solutions, in the direction of the first objective.
+    /// The population is evaluated first to find them.
+    Worst,
+}
+
+impl<A> WithImmigrants<A> {
+    /// Wrap `algorithm`, replacing `fraction` of the population with random immigrants every generation.
+    pub fn new(algorithm: A, fraction: f64) -> Self {
+        WithImmigrants {
+            algorithm,
+            fraction,
+            replace: Replace::Random,
+        }
+    }
+
+    /// Wrap `algorithm`, checking that `fraction` is between 0 and 1.
+    pub fn try_new(algorithm: A, fraction: f64) -> Result<Self, ConfigError> {
+        check_probability("fraction", fraction)?;
+        Ok(WithImmigrants::new(algorithm, fraction))
+    }
+
+    /// Choose which solutions are replaced with `replace` instead of at random.
+    pub fn with_replace(mut self, replace: Replace) -> Self {
+        self.replace = replace;
+        self
+    }
+
+    /// Get the wrapped algorithm.
+    pub fn algorithm(&self) -> &A {
+        &self.algorithm
+    }
+}
+
+impl<T, A> Algorithm<T> for WithImmigrants<A>
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+    A: Algorithm<T>,
+{
+    fn pop_size(&self) -> usize {
+        self.algorithm.pop_size()
+    }
+
+    fn step(&self, population: &mut Vec<Cached<T>>) {
+        self.step_with_scratch(population, &mut Vec::new());
+    }
+
+    fn step_with_scratch(&self, population: &mut Vec<Cached<T>>, scratch: &mut Vec<Cached<T>>) {
+        self.algorithm.step_with_scratch(population, scratch);
+
+        let len = population.len();
+        let count = ((self.fraction * len as f64 + 0.5) as usize).min(len);
+        if count == 0 {
+            return;
+        }
+        let replaced: Vec<usize> = match self.replace {
+            Replace::Random => sample(&mut thread_rng(), len, count).into_vec(),
+            Replace::Worst => {
+                par_evaluate(population);
+                let direction = Direction::of::<T>(0);
+                let mut order: Vec<usize> = (0..len).collect();
+                order.sort_by(|&a, &b| {
+                    direction.compare(
+                        population[a].evaluate().into(),
+                        population[b].evaluate().into(),
+                    )
+                });
+                order.truncate(count);
+                order
+            }
+        };
+        for i in replaced {
+            population[i] = Cached::generate();
+        }
+    }
+}
+
+/// A trait for local search operators, which improve a single solution
+///
+/// This is used by [`Memetic`], which applies it to part of the population every generation.
+/// [`HillClimb`] is a simple implementation that works with any solution.
+///
+/// [`Memetic`]: ./struct.Memetic.html
+/// [`HillClimb`]: ./struct.HillClimb.html
+pub trait LocalSearch<T: Solution> {
+    /// Improve `solution` in place, e.g. by trying small changes and keeping the ones that make it better.
+    ///
+    /// The solution has been evaluated when this is called. Any changes to it must go through
+    /// the methods of [`Cached`] so its cached fitness value stays correct.
+    ///
+    /// [`Cached`]: ../struct.Cached.html
+    fn search(&self, solution: &mut Cached<T>);
+}
+
+/// A [`LocalSearch`] that tries mutating a solution a fixed number of times,
+/// keeping each mutant that's better than the solution so far
+///
+/// [`LocalSearch`]: ./trait.LocalSearch.html
+#[derive(Clone, Copy, Debug)]
+pub struct HillClimb {
+    attempts: usize,
+}
+
+impl HillClimb {
+    /// Try `attempts` mutations for each solution.
+    pub fn new(attempts: usize) -> Self {
+        HillClimb { attempts }
+    }
+}
+
+impl<T> LocalSearch<T> for HillClimb
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+{
+    fn search(&self, solution: &mut Cached<T>) {
+        let direction = Direction::of::<T>(0);
+        for _ in 0..self.attempts {
+            let mut mutant = solution.clone();
+            mutant.mutate();
+            if direction.is_better(mutant.evaluate().into(), solution.evaluate().into()) {
+                *solution = mutant;
+            }
+        }
+    }
+}
+
+/// What [`Memetic`] does with the result of a local search
+///
+/// [`Memetic`]: ./struct.Memetic.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Learning {
+    /// Replace the solution with the improved one, so the improvement is inherited by its offspring.
+    #[default]
+    Lamarckian,
+    /// Keep the solution as it was, but give it the fitness of the improved one,
+    /// so only selection sees the improvement. This keeps more diversity
+    /// than Lamarckian learning, at the cost of converging more slowly.
+    Baldwinian,
+}
+
+/// A wrapper that turns any algorithm into a memetic algorithm
+///
+/// After each step of the wrapped algorithm, a [`LocalSearch`] is applied to a fraction
+/// of the population chosen at random, and the result is used as set by [`Learning`].
+/// The local searches run in parallel if the `parallel` crate feature is enabled,
+/// with random numbers that are reproducible like those of [`par_var_and()`].
+///
+/// Pseudocode
+/// ----------
+/// A single step of the algorithm does the following:
+/// ```notrust
+/// apply a step of the wrapped algorithm
+/// evaluate the population
+/// for round(fraction * N) solutions chosen at random, in parallel:
+///     apply local search to a copy of the solution
+///     if learning is Lamarckian:
+///         replace the solution with the copy
+///     if learning is Baldwinian:
+///         give the solution the fitness of the copy
+/// ```
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use eviolite::prelude::*;
+/// use eviolite::alg::{HillClimb, Learning, Memetic};
+/// use eviolite::benchmarks::{Benchmark, Rastrigin};
+///
+/// let evo: Evolution<Benchmark<Rastrigin, 5>, _, _, ()> = Evolution::new(
+///     Memetic::new(
+///         alg::Simple::new(40, 0.6, 0.2, select::Tournament::new(3)),
+///         HillClimb::new(5),
+///         0.25,
+///     )
+///     .with_learning(Learning::Baldwinian),
+///     hof::BestN::new(1),
+/// );
+/// evo.run_for(20);
+/// ```
+///
+/// [`LocalSearch`]: ./trait.LocalSearch.html
+/// [`Learning`]: ./enum.Learning.html
+/// [`par_var_and()`]: ./fn.par_var_and.html
+#[derive(Clone, Debug)]
+pub struct Memetic<A, L> {
+    algorithm: A,
+    search: L,
+    fraction: f64,
+    learning: Learning,
+}
+
+impl<A, L> Memetic<A, L> {
+    /// Wrap `algorithm`, applying `search` to `fraction` of the population every generation.
+    pub fn new(algorithm: A, search: L, fraction: f64) -> Self {
+        Memetic {
+            algorithm,
+            search,
+            fraction,
+            learning: Learning::Lamarckian,
+        }
+    }
+
+    /// Wrap `algorithm`, checking that `fraction` is between 0 and 1.
+    pub fn try_new(algorithm: A, search: L, fraction: f64) -> Result<Self, ConfigError> {
+        check_probability("fraction", fraction)?;
+        Ok(Memetic::new(algorithm, search, fraction))
+    }
+
+    /// Use the result of each local search as set by `learning` instead of the Lamarckian way.
+    pub fn with_learning(mut self, learning: Learning) -> Self {
+        self.learning = learning;
+        self
+    }
+
+    /// Get the wrapped algorithm.
+    pub fn algorithm(&self) -> &A {
+        &self.algorithm
+    }
+}
+
+impl<T, A, L> Algorithm<T> for Memetic<A, L>
+where
+    T: Solution + Send,
+    A: Algorithm<T>,
+    L: LocalSearch<T> + Sync,
+{
+    fn pop_size(&self) -> usize {
+        self.algorithm.pop_size()
+    }
+
+    fn step(&self, population: &mut Vec<Cached<T>>) {
+        self.step_with_scratch(population, &mut Vec::new());
+    }
+
+    fn step_with_scratch(&self, population: &mut Vec<Cached<T>>, scratch: &mut Vec<Cached<T>>) {
+        self.algorithm.step_with_scratch(population, scratch);
+        par_evaluate(population);
+
+        let len = population.len();
+        let count = ((self.fraction * len as f64 + 0.5) as usize).min(len);
+        let mut chosen = vec![false; len];
+        for i in sample(&mut thread_rng(), len, count) {
+            chosen[i] = true;
+        }
+
+        let base: u64 = thread_rng().gen();
+        let (search, learning) = (&self.search, self.learning);
+        let learn = |(i, ind): (usize, &mut Cached<T>)| {
+            if !chosen[i] {
+                return;
+            }
+            repro_rng::with_stream(base.wrapping_add(i as u64), || match learning {
+                Learning::Lamarckian => search.search(ind),
+                Learning::Baldwinian => {
+                    let mut learned = ind.clone();
+                    search.search(&mut learned);
+                    let fitness = learned.evaluate();
+                    let (genotype, _) = core::mem::replace(ind, learned).into_inner();
+                    *ind = Cached::with_fitness(genotype, Some(fitness));
+                }
+            })
+        };
+
+        #[cfg(feature = "parallel")]
+        crate::utils::thread_pool::install(|| {
+            population
+                .par_iter_mut()
+                .enumerate()
+                .for_each(crate::utils::counter::carry(learn))
+        });
+        #[cfg(not(feature = "parallel"))]
+        population.iter_mut().enumerate().for_each(learn);
+    }
+}
+
+/// Vary a population in place.
+///
+/// This function has the potential to apply both crossover *and* mutation
+/// to the same solution, hence the name.
+/// Each solution is crossed over with the one before it; to pair solutions up differently,
+/// use [`var_and_with()`].
+///
+/// Pseudocode
+/// ----------
+/// ```notrust
+/// for each solution in the population:
+///     if a random check of chance cxpb passes:
+///         apply crossover between the solution and the one adjacent to it
+///     if a random check of chance mutpb passes:
+///         apply mutation to the solution
+/// ```
+///
+/// [`var_and_with()`]: ./fn.var_and_with.html
 pub fn var_and<T>(pop: &mut [T], cxpb: f64, mutpb: f64)
 where
     T: Solution,
@@ -321,6 +1816,193 @@ where
     }
 }
 
+/// How [`var_and_with()`] chooses which solutions are crossed over with each other
+///
+/// [`var_and_with()`]: ./fn.var_and_with.html
+#[derive(Default)]
+pub enum Pairing<T> {
+    /// Cross each solution over with the one before it, like [`var_and()`].
+    /// Each solution can take part in two crossovers, which chains information down the population.
+    ///
+    /// [`var_and()`]: ./fn.var_and.html
+    #[default]
+    Adjacent,
+    /// Cross over disjoint pairs in order: the first and second solutions,
+    /// the third and fourth, and so on. This is what DEAP's `varAnd` does.
+    Ordered,
+    /// Cross over disjoint pairs chosen at random, so the same solutions don't mate every generation
+    /// when selection doesn't shuffle the population.
+    Shuffled,
+    /// Cross over disjoint pairs chosen at random, but only pairs for which the function returns `true`,
+    /// e.g. to keep mating within a niche. Solutions without an allowed partner are only mutated.
+    Restricted(fn(&T, &T) -> bool),
+}
+
+impl<T> Clone for Pairing<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Pairing<T> {}
+
+impl<T> core::fmt::Debug for Pairing<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Pairing::Adjacent => f.write_str("Adjacent"),
+            Pairing::Ordered => f.write_str("Ordered"),
+            Pairing::Shuffled => f.write_str("Shuffled"),
+            Pairing::Restricted(_) => f.write_str("Restricted(..)"),
+        }
+    }
+}
+
+/// Vary a population in place, choosing the pairs that are crossed over with `pairing`.
+///
+/// With [`Pairing::Adjacent`], this is exactly [`var_and()`].
+/// Otherwise, the population is split into disjoint pairs, each pair is crossed over with chance `cxpb`,
+/// and then each solution is mutated with chance `mutpb`.
+///
+/// [`Pairing::Adjacent`]: ./enum.Pairing.html#variant.Adjacent
+/// [`var_and()`]: ./fn.var_and.html
+pub fn var_and_with<T>(pop: &mut [T], cxpb: f64, mutpb: f64, pairing: Pairing<T>)
+where
+    T: Solution,
+{
+    fn itself<T>(ind: &T) -> &T {
+        ind
+    }
+    vary_pairs(pop, cxpb, mutpb, pairing, itself);
+}
+
+// `var_and_with()`, where the pairing predicate looks at the part of each solution given by `get`,
+// so that it can be used on the wrapped solutions in a population of `Cached` solutions.
+fn vary_pairs<T, U>(
+    pop: &mut [T],
+    cxpb: f64,
+    mutpb: f64,
+    pairing: Pairing<U>,
+    get: impl Fn(&T) -> &U,
+) where
+    T: Solution,
+{
+    let mut rng = thread_rng();
+    let pairs: Vec<(usize, usize)> = match pairing {
+        Pairing::Adjacent => return var_and(pop, cxpb, mutpb),
+        Pairing::Ordered => (1..pop.len()).step_by(2).map(|i| (i - 1, i)).collect(),
+        Pairing::Shuffled => {
+            let mut order: Vec<usize> = (0..pop.len()).collect();
+            order.shuffle(&mut rng);
+            order
+                .chunks_exact(2)
+                .map(|pair| (pair[0], pair[1]))
+                .collect()
+        }
+        Pairing::Restricted(allowed) => {
+            let mut order: Vec<usize> = (0..pop.len()).collect();
+            order.shuffle(&mut rng);
+            let mut paired = vec![false; pop.len()];
+            let mut pairs = Vec::new();
+            for (x, &i) in order.iter().enumerate() {
+                if paired[i] {
+                    continue;
+                }
+                let partner = order[x + 1..]
+                    .iter()
+                    .copied()
+                    .find(|&j| !paired[j] && allowed(get(&pop[i]), get(&pop[j])));
+                if let Some(j) = partner {
+                    paired[i] = true;
+                    paired[j] = true;
+                    pairs.push((i, j));
+                }
+            }
+            pairs
+        }
+    };
+
+    for (i, j) in pairs {
+        if rng.gen_bool(cxpb) {
+            let (a, b) = if i < j {
+                let (head, tail) = pop.split_at_mut(j);
+                (&mut head[i], &mut tail[0])
+            } else {
+                let (head, tail) = pop.split_at_mut(i);
+                (&mut tail[0], &mut head[j])
+            };
+            T::crossover(a, b);
+        }
+    }
+    for ind in pop {
+        if rng.gen_bool(mutpb) {
+            ind.mutate();
+        }
+    }
+}
+
+/// Vary a population in place, in parallel.
+///
+/// This is a variant of [`var_and()`] that can be parallelized. Instead of crossing each solution
+/// over with the one before it, the population is split into disjoint pairs, i.e. the first and second
+/// solutions, the third and fourth, and so on. Each pair is crossed over with chance `cxpb`,
+/// and then each solution is mutated with chance `mutpb`. If the population has an odd length,
+/// the last solution is only ever mutated.
+///
+/// Pairs are varied in parallel using [`rayon`] if the `parallel` crate feature is enabled.
+/// Each pair gets its own stream of random numbers, seeded from the [reproducible RNG]
+/// of the calling thread, which [`thread_rng()`] returns while the pair is being varied.
+/// This means results are reproducible, and don't depend on the number of threads
+/// or whether the `parallel` feature is enabled, as long as [`Solution::crossover`] and
+/// [`Solution::mutate`] only use [`thread_rng()`] (or [`random()`]) for randomness.
+/// To vary the population of a pre-built algorithm this way, give it [`ParVarAnd`] with `.with_variation()`,
+/// or use the `.par_var_and()` shorthand of [`Simple`].
+///
+/// Pseudocode
+/// ----------
+/// ```notrust
+/// for each pair of adjacent solutions, in parallel:
+///     if a random check of chance cxpb passes:
+///         apply crossover between the two solutions
+///     for each of the two solutions:
+///         if a random check of chance mutpb passes:
+///             apply mutation to the solution
+/// ```
+///
+/// [`var_and()`]: ./fn.var_and.html
+/// [reproducible RNG]: ../repro_rng/index.html
+/// [`thread_rng()`]: ../repro_rng/fn.thread_rng.html
+/// [`random()`]: ../repro_rng/fn.random.html
+/// [`Solution::crossover`]: ../trait.Solution.html#tymethod.crossover
+/// [`Solution::mutate`]: ../trait.Solution.html#tymethod.mutate
+/// [`ParVarAnd`]: ./struct.ParVarAnd.html
+/// [`Simple`]: ./struct.Simple.html
+pub fn par_var_and<T>(pop: &mut [T], cxpb: f64, mutpb: f64)
+where
+    T: Solution + Send,
+{
+    let base: u64 = thread_rng().gen();
+    let vary = |(k, pair): (usize, &mut [T])| {
+        repro_rng::with_stream(base.wrapping_add(k as u64), || {
+            let mut rng = thread_rng();
+            if let [a, b] = pair {
+                if rng.gen_bool(cxpb) {
+                    T::crossover(a, b);
+                }
+            }
+            for ind in pair {
+                if rng.gen_bool(mutpb) {
+                    ind.mutate();
+                }
+            }
+        })
+    };
+
+    #[cfg(feature = "parallel")]
+    crate::utils::thread_pool::install(|| pop.par_chunks_mut(2).enumerate().for_each(vary));
+    #[cfg(not(feature = "parallel"))]
+    pop.chunks_mut(2).enumerate().for_each(vary);
+}
+
 /// Generate offspring from a population.
 ///
 /// This function only ever applies crossover *or* mutation to a solution, hence the name.
@@ -347,24 +2029,620 @@ where
 /// `cxpb`, `mutpb`, and `1 - (cxpb + mutpb)` respectively.
 pub fn gen_or<T: Solution>(pop: &[T], n_offspring: usize, cxpb: f64, mutpb: f64) -> Vec<T> {
     let mut offspring: Vec<T> = Vec::with_capacity(n_offspring);
-    for _ in 0..n_offspring {
+    gen_or_into(pop, n_offspring, cxpb, mutpb, &mut offspring);
+    offspring
+}
+
+/// Generate offspring from a population into an existing vector, replacing its contents.
+///
+/// This works exactly like [`gen_or()`] and makes the same random choices, but instead of
+/// allocating a new vector, it reuses `offspring`. Solutions already in `offspring` are overwritten
+/// with [`Clone::clone_from`], so solutions that implement it (directly or through [`Cached`])
+/// can reuse their own allocations too. Afterward, `offspring` contains exactly `n_offspring` solutions.
+///
+/// [`gen_or()`]: ./fn.gen_or.html
+/// [`Cached`]: ../struct.Cached.html
+pub fn gen_or_into<T: Solution>(
+    pop: &[T],
+    n_offspring: usize,
+    cxpb: f64,
+    mutpb: f64,
+    offspring: &mut Vec<T>,
+) {
+    for i in 0..n_offspring {
         let mut rng = thread_rng();
         let choice: f64 = rng.gen();
-        offspring.push(if choice < cxpb {
-            let mut iter = pop.choose_multiple(&mut rng, 2).cloned();
-            let mut a = iter.next().unwrap();
-            let mut b = iter.next().unwrap();
+        if choice < cxpb {
+            let mut iter = pop.choose_multiple(&mut rng, 2);
+            let a = iter.next().unwrap();
+            let mut b = iter.next().unwrap().clone();
 
-            T::crossover(&mut a, &mut b);
-            a
+            T::crossover(overwrite(offspring, i, a), &mut b);
         } else if choice < cxpb + mutpb {
-            let mut chosen = pop.choose(&mut rng).unwrap().clone();
-            chosen.mutate();
-            chosen
+            overwrite(offspring, i, pop.choose(&mut rng).unwrap()).mutate();
         } else {
-            pop.choose(&mut rng).unwrap().clone()
-        });
+            overwrite(offspring, i, pop.choose(&mut rng).unwrap());
+        }
     }
 
+    offspring.truncate(n_offspring);
+}
+
+/// Generate offspring from a population, keeping both children of each crossover.
+///
+/// This works like [`gen_or()`], except that when crossover is chosen, both of the
+/// crossed clones are added to the offspring as long as there is room for them,
+/// rather than discarding one. This halves the number of clones and crossovers needed
+/// for each crossed offspring, at the cost of making the offspring a little less diverse.
+/// It makes different random choices from [`gen_or()`], so it won't give the same results
+/// for the same seed.
+///
+/// To generate the offspring of a pre-built algorithm this way, give it [`GenOrPairs`] with `.with_variation()`,
+/// or use the `.gen_or_pairs()` shorthand of [`MuPlusLambda`], [`MuCommaLambda`], and [`NSGA2`].
+///
+/// Pseudocode
+/// ----------
+/// ```notrust
+/// until there are n_offspring offspring:
+///     randomly choose one operation from crossover, mutate, or clone
+///     if crossover is chosen:
+///         randomly choose two solutions from the population and clone them
+///         apply crossover between the clones
+///         add both clones to the offspring, or only one if there's room for just one more
+///     if mutate is chosen:
+///         randomly choose a solution from the population and clone it
+///         apply mutation to the clone
+///         add the clone to the offspring
+///     if clone is chosen:
+///         randomly choose a solution from the population and clone it
+///         add the clone to the offspring
+/// ```
+///
+/// [`gen_or()`]: ./fn.gen_or.html
+/// [`GenOrPairs`]: ./struct.GenOrPairs.html
+/// [`MuPlusLambda`]: ./struct.MuPlusLambda.html
+/// [`MuCommaLambda`]: ./struct.MuCommaLambda.html
+/// [`NSGA2`]: ./struct.NSGA2.html
+pub fn gen_or_pairs<T: Solution>(pop: &[T], n_offspring: usize, cxpb: f64, mutpb: f64) -> Vec<T> {
+    let mut offspring: Vec<T> = Vec::with_capacity(n_offspring);
+    gen_or_pairs_into(pop, n_offspring, cxpb, mutpb, &mut offspring);
     offspring
 }
+
+/// Generate offspring from a population into an existing vector, keeping both children of each crossover.
+///
+/// This is to [`gen_or_pairs()`] what [`gen_or_into()`] is to [`gen_or()`].
+///
+/// [`gen_or_pairs()`]: ./fn.gen_or_pairs.html
+/// [`gen_or_into()`]: ./fn.gen_or_into.html
+/// [`gen_or()`]: ./fn.gen_or.html
+pub fn gen_or_pairs_into<T: Solution>(
+    pop: &[T],
+    n_offspring: usize,
+    cxpb: f64,
+    mutpb: f64,
+    offspring: &mut Vec<T>,
+) {
+    let mut i = 0;
+    while i < n_offspring {
+        let mut rng = thread_rng();
+        let choice: f64 = rng.gen();
+        if choice < cxpb {
+            let mut iter = pop.choose_multiple(&mut rng, 2);
+            let a = iter.next().unwrap();
+            let b = iter.next().unwrap();
+
+            if i + 1 < n_offspring {
+                overwrite(offspring, i, a);
+                overwrite(offspring, i + 1, b);
+                let (left, right) = offspring.split_at_mut(i + 1);
+                T::crossover(&mut left[i], &mut right[0]);
+                i += 2;
+                continue;
+            }
+            T::crossover(overwrite(offspring, i, a), &mut b.clone());
+        } else if choice < cxpb + mutpb {
+            overwrite(offspring, i, pop.choose(&mut rng).unwrap()).mutate();
+        } else {
+            overwrite(offspring, i, pop.choose(&mut rng).unwrap());
+        }
+        i += 1;
+    }
+
+    offspring.truncate(n_offspring);
+}
+
+/// Replace every solution in a population that has the same `key` as an earlier one.
+///
+/// Each duplicate is mutated until its key is one that hasn't been seen yet,
+/// and if that still hasn't worked after a few tries, it's replaced with newly generated solutions instead.
+/// A duplicate that can't be replaced either way, e.g. because there are fewer possible keys
+/// than solutions in the population, is left as it is.
+///
+/// Returns the number of duplicates that are left.
+///
+/// The key is usually either the solution itself, cloned, or a cheaper value that identifies it,
+/// like a hash of its genome. See also [`Evolution::with_unique_by_key()`].
+///
+/// [`Evolution::with_unique_by_key()`]: ../struct.Evolution.html#method.with_unique_by_key
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn make_unique_by_key<T, K>(pop: &mut [T], mut key: impl FnMut(&T) -> K) -> usize
+where
+    T: Solution,
+    K: core::hash::Hash + Eq,
+{
+    const ATTEMPTS: usize = 10;
+
+    let mut seen = std::collections::HashSet::with_capacity(pop.len());
+    let mut left = 0;
+    for ind in pop {
+        let mut k = key(ind);
+        for attempt in 0..2 * ATTEMPTS {
+            if !seen.contains(&k) {
+                break;
+            }
+            if attempt < ATTEMPTS {
+                ind.mutate();
+            } else {
+                *ind = T::generate();
+            }
+            k = key(ind);
+        }
+        if !seen.insert(k) {
+            left += 1;
+        }
+    }
+    left
+}
+
+// Put a copy of `source` at index `i` of `vec`, which is at most `vec.len()`,
+// reusing the solution that's already there if there is one.
+fn overwrite<'a, T: Clone>(vec: &'a mut Vec<T>, i: usize, source: &T) -> &'a mut T {
+    if i < vec.len() {
+        vec[i].clone_from(source);
+    } else {
+        vec.push(source.clone());
+    }
+    &mut vec[i]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        repro_rng::reseed,
+        testutils::{Foo, Walk},
+    };
+
+    #[test]
+    fn try_new_validates() {
+        use crate::select::Tournament;
+
+        let simple = Simple::<Walk, _>::try_new(10, 1.5, 0.2, Tournament::new(3));
+        assert_eq!(
+            simple.err(),
+            Some(ConfigError::Probability {
+                name: "cxpb",
+                value: 1.5
+            })
+        );
+        assert!(Simple::<Walk, _>::try_new(10, 1.0, 1.0, Tournament::new(3)).is_ok());
+
+        let plus = MuPlusLambda::<Walk, _>::try_new(10, 20, 0.7, 0.4, Tournament::new(3));
+        assert_eq!(
+            plus.err(),
+            Some(ConfigError::ProbabilitySum {
+                cxpb: 0.7,
+                mutpb: 0.4
+            })
+        );
+        let comma = MuCommaLambda::<Walk, _>::try_new(30, 20, 0.5, 0.2, Tournament::new(3));
+        assert_eq!(
+            comma.err(),
+            Some(ConfigError::MuAboveLambda { mu: 30, lambda: 20 })
+        );
+        assert_eq!(
+            OnePlusLambda::try_new(0).err(),
+            Some(ConfigError::Zero { name: "lambda" })
+        );
+        assert!(matches!(
+            NSGA2::try_new(10, f64::NAN, 0.1),
+            Err(ConfigError::Probability { name: "cxpb", .. })
+        ));
+        assert!(Tournament::try_new(0).is_err());
+    }
+
+    #[test]
+    fn deterministic_crowding_never_gets_worse() {
+        let crowding =
+            DeterministicCrowding::new(9, 0.9, 0.5, |a: &Walk, b: &Walk| (a.0 - b.0).abs());
+        let mut pop: Vec<Cached<Walk>> =
+            (0..9).map(|i| Cached::new(Walk(i as f64 - 4.0))).collect();
+        par_evaluate(&pop);
+        let total = |pop: &[Cached<Walk>]| pop.iter().map(|ind| ind.evaluate()).sum::<f64>();
+
+        let mut scratch = Vec::new();
+        for _ in 0..20 {
+            let before = total(&pop);
+            crowding.step_with_scratch(&mut pop, &mut scratch);
+            assert_eq!(pop.len(), 9);
+            // every slot is only ever taken over by an offspring that's at least as good,
+            // but the shuffle changes the order of the sum
+            assert!(total(&pop) >= before - 1e-9);
+        }
+    }
+
+    #[test]
+    fn one_plus_one_never_gets_worse() {
+        let mut pop = vec![Cached::new(Walk(5.0))];
+        par_evaluate(&pop);
+        for _ in 0..50 {
+            let before = pop[0].evaluate();
+            OnePlusOne::new().step(&mut pop);
+            assert_eq!(pop.len(), 1);
+            assert!(pop[0].evaluate() >= before);
+        }
+        assert!(pop[0].evaluate() > -5.0);
+    }
+
+    #[test]
+    fn one_fifth_success_rule() {
+        let control = StepSizeControl::new(1.0).with_period(5).with_factor(0.5);
+        for success in [true, true, false, false, false] {
+            control.record(success);
+        }
+        assert_eq!(control.step_size(), 2.0);
+        for success in [false, false, false, false, true] {
+            control.record(success);
+        }
+        assert_eq!(control.step_size(), 2.0);
+        for _ in 0..10 {
+            control.record(false);
+        }
+        assert_eq!(control.step_size(), 0.5);
+    }
+
+    #[test]
+    fn gen_or_into_reuses_buffer() {
+        let pop: Vec<Foo> = (0..5).map(|i| Foo([i as f64, 0.0])).collect();
+
+        reseed(3);
+        let expected = gen_or(&pop, 8, 0.0, 0.0);
+
+        reseed(3);
+        let mut offspring = vec![Foo([-1.0, -1.0]); 12];
+        let buffer = offspring.as_ptr();
+        gen_or_into(&pop, 8, 0.0, 0.0, &mut offspring);
+        assert_eq!(offspring, expected);
+        assert_eq!(offspring.as_ptr(), buffer);
+
+        gen_or_into(&pop, 10, 0.0, 0.0, &mut offspring);
+        assert_eq!(offspring.len(), 10);
+        assert!(offspring.iter().all(|foo| pop.contains(foo)));
+    }
+
+    #[test]
+    fn gen_or_pairs_keeps_both_children() {
+        let pop = [Walk(0.0), Walk(1.0)];
+
+        let offspring = gen_or_pairs(&pop, 5, 1.0, 0.0);
+        assert_eq!(offspring.len(), 5);
+        // crossover blends the parents, so each pair of children sums to the parents' sum
+        for pair in offspring[..4].chunks(2) {
+            assert!((pair[0].0 + pair[1].0 - 1.0).abs() < 1e-12);
+        }
+        assert!((0.0..=1.0).contains(&offspring[4].0));
+
+        let mut reused = vec![Walk(-1.0); 7];
+        gen_or_pairs_into(&pop, 3, 0.0, 0.0, &mut reused);
+        assert_eq!(reused.len(), 3);
+        assert!(reused.iter().all(|walk| pop.contains(walk)));
+    }
+
+    #[test]
+    fn gen_or_pairs_variation() {
+        let parents: Vec<Cached<Walk>> = (0..6).map(|i| Cached::new(Walk(i as f64))).collect();
+        reseed(4);
+        let mut expected = Vec::new();
+        gen_or_pairs_into(&parents, 9, 0.5, 0.3, &mut expected);
+        reseed(4);
+        let mut offspring = Vec::new();
+        GenOrPairs::new(0.5, 0.3).vary(&parents, 9, &mut offspring);
+        assert_eq!(offspring, expected);
+
+        let run = |variation: Option<GenOrPairs>| {
+            reseed(9);
+            let tournament = crate::select::Tournament::new(3);
+            let hof = crate::hof::BestN::new(1);
+            let log = match variation {
+                Some(variation) => {
+                    let alg =
+                        MuPlusLambda::new(10, 20, 0.0, 0.0, tournament).with_variation(variation);
+                    crate::Evolution::<Walk, _, _, ()>::new(alg, hof).run_for(3)
+                }
+                None => {
+                    let alg = MuPlusLambda::new(10, 20, 0.5, 0.3, tournament);
+                    crate::Evolution::<Walk, _, _, ()>::new(alg, hof).run_for(3)
+                }
+            };
+            log.final_population
+                .iter()
+                .map(|ind| ind.as_ref().0)
+                .collect::<Vec<f64>>()
+        };
+        let pairs = run(Some(GenOrPairs::new(0.5, 0.3)));
+        assert_eq!(pairs, run(Some(GenOrPairs::new(0.5, 0.3))));
+        assert_ne!(pairs, run(None));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn make_unique_replaces_duplicates() {
+        let mut pop = vec![Cached::new(Walk(0.0)); 20];
+        pop[5] = Cached::new(Walk(1.0));
+        pop.iter().for_each(|ind| {
+            ind.evaluate();
+        });
+
+        let left = make_unique_by_key(&mut pop, |walk| walk.as_ref().0.to_bits());
+        assert_eq!(left, 0);
+        let keys: std::collections::HashSet<u64> =
+            pop.iter().map(|walk| walk.as_ref().0.to_bits()).collect();
+        assert_eq!(keys.len(), pop.len());
+        // only the duplicates were changed, so only their fitness has to be evaluated again
+        assert_eq!(pop[0].as_ref().0, 0.0);
+        assert_eq!(pop[5].as_ref().0, 1.0);
+        assert!(pop[5].fitness().is_some());
+        assert!(pop[1].fitness().is_none());
+
+        // with only two possible keys, the rest stay duplicates
+        let mut pop = vec![Walk(0.0); 5];
+        assert_eq!(make_unique_by_key(&mut pop, |walk| walk.0 > 0.0), 3);
+    }
+
+    #[test]
+    fn algorithms_gen_or_pairs() {
+        let pop: Vec<Cached<Walk>> = (0..6).map(|i| Cached::new(Walk(i as f64))).collect();
+        let selector = crate::select::Tournament::new(2);
+        let alg = MuCommaLambda::new(6, 6, 1.0, 0.0, selector).gen_or_pairs();
+
+        reseed(5);
+        let mut stepped = pop.clone();
+        alg.step(&mut stepped);
+        reseed(5);
+        let mut expected = gen_or_pairs(&pop, 6, 1.0, 0.0);
+        par_evaluate(&expected);
+        selector.select(6, &mut expected);
+        assert_eq!(stepped, expected);
+    }
+
+    #[test]
+    fn var_and_pairings() {
+        let sum = |pop: &[Walk]| pop.iter().map(|walk| walk.0).sum::<f64>();
+
+        // crossover blends the parents, so each pair keeps its sum
+        let mut pop = [0.0, 1.0, 2.0, 3.0, 4.0].map(Walk);
+        var_and_with(&mut pop, 1.0, 0.0, Pairing::Ordered);
+        assert!((pop[0].0 + pop[1].0 - 1.0).abs() < 1e-12);
+        assert!((pop[2].0 + pop[3].0 - 5.0).abs() < 1e-12);
+        assert_eq!(pop[4].0, 4.0);
+
+        let mut pop: Vec<Walk> = (0..10).map(|i| Walk(i as f64)).collect();
+        var_and_with(&mut pop, 1.0, 0.0, Pairing::Shuffled);
+        assert!((sum(&pop) - 45.0).abs() < 1e-9);
+
+        // only solutions on the same side of 0 mate, so none of them cross it
+        let mut pop = [-1.0, 1.0, -2.0, 2.0, 5.0].map(Walk);
+        var_and_with(
+            &mut pop,
+            1.0,
+            0.0,
+            Pairing::Restricted(|a: &Walk, b: &Walk| (a.0 < 0.0) == (b.0 < 0.0)),
+        );
+        assert!(pop[0].0 < 0.0 && pop[2].0 < 0.0);
+        assert!((pop[0].0 + pop[2].0 + 3.0).abs() < 1e-12);
+        assert!(pop[1].0 > 0.0 && pop[3].0 > 0.0 && pop[4].0 > 0.0);
+    }
+
+    #[test]
+    fn par_var_and_is_reproducible() {
+        let pop: Vec<Walk> = (0..101).map(|i| Walk(i as f64)).collect();
+        let vary = || {
+            let mut varied = pop.clone();
+            reseed(11);
+            par_var_and(&mut varied, 0.7, 0.5);
+            varied
+        };
+
+        let varied = vary();
+        assert_eq!(varied, vary());
+        assert_ne!(varied, pop);
+        // the caller's own stream only advances by the one draw used for seeding
+        reseed(11);
+        let _: u64 = thread_rng().gen();
+        let next: u64 = thread_rng().gen();
+        vary();
+        assert_eq!(thread_rng().gen::<u64>(), next);
+    }
+
+    #[test]
+    fn par_var_and_variation() {
+        let pop: Vec<Cached<Walk>> = (0..21).map(|i| Cached::new(Walk(i as f64))).collect();
+
+        reseed(3);
+        let mut expected = pop.clone();
+        par_var_and(&mut expected, 0.7, 0.5);
+        reseed(3);
+        let mut varied = pop.clone();
+        ParVarAnd::new(0.7, 0.5).vary_in_place(&mut varied, &mut Vec::new());
+        assert_eq!(varied, expected);
+
+        // offspring are clones of the parents in order, varied the same way
+        reseed(3);
+        let mut offspring = Vec::new();
+        ParVarAnd::new(0.7, 0.5).vary(&pop[..7], 21, &mut offspring);
+        reseed(3);
+        let mut expected: Vec<Cached<Walk>> = pop[..7].iter().cycle().take(21).cloned().collect();
+        par_var_and(&mut expected, 0.7, 0.5);
+        assert_eq!(offspring, expected);
+
+        let run = || {
+            reseed(8);
+            let evo: crate::Evolution<Walk, _, _, ()> = crate::Evolution::new(
+                Simple::new(10, 0.6, 0.3, crate::select::Tournament::new(2)).par_var_and(),
+                crate::hof::BestN::new(1),
+            );
+            evo.run_for(5).hall_of_fame[0].as_ref().0
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn variations() {
+        struct Zeros;
+        impl Variation<Walk> for Zeros {
+            fn vary(
+                &self,
+                _: &[Cached<Walk>],
+                n_offspring: usize,
+                offspring: &mut Vec<Cached<Walk>>,
+            ) {
+                offspring.clear();
+                offspring.resize_with(n_offspring, || Cached::new(Walk(0.0)));
+            }
+        }
+
+        let mut pop: Vec<Cached<Walk>> = (1..6).map(|i| Cached::new(Walk(i as f64))).collect();
+        let comma = MuCommaLambda::new(5, 8, 0.5, 0.5, crate::select::Tournament::new(2))
+            .with_variation(Zeros);
+        comma.step_with_scratch(&mut pop, &mut Vec::new());
+        assert!(pop.iter().all(|ind| ind.as_ref().0 == 0.0));
+
+        // the parents are cloned in order, over and over
+        let parents: Vec<Cached<Walk>> = (0..3).map(|i| Cached::new(Walk(i as f64))).collect();
+        let mut offspring = vec![Cached::new(Walk(9.0)); 10];
+        VarAnd::new(0.0, 0.0).vary(&parents, 7, &mut offspring);
+        let values: Vec<f64> = offspring.iter().map(|ind| ind.as_ref().0).collect();
+        assert_eq!(values, [0.0, 1.0, 2.0, 0.0, 1.0, 2.0, 0.0]);
+
+        // the default variation is the same as giving it explicitly
+        let run = |simple: &dyn Algorithm<Walk>| {
+            reseed(5);
+            let mut pop: Vec<Cached<Walk>> = (0..10).map(|_| Cached::generate()).collect();
+            par_evaluate(&pop);
+            simple.step(&mut pop);
+            pop.into_iter()
+                .map(|ind| ind.as_ref().0)
+                .collect::<Vec<f64>>()
+        };
+        let tournament = crate::select::Tournament::new(2);
+        assert_eq!(
+            run(&Simple::new(10, 0.6, 0.3, tournament)),
+            run(&Simple::new(10, 0.0, 0.0, tournament).with_variation(VarAnd::new(0.6, 0.3)))
+        );
+
+        // each pairing varies the same way as `var_and_with()`
+        let pop: Vec<Walk> = (0..9).map(|i| Walk(i as f64)).collect();
+        for pairing in [Pairing::Adjacent, Pairing::Ordered, Pairing::Shuffled] {
+            reseed(6);
+            let mut expected = pop.clone();
+            var_and_with(&mut expected, 0.8, 0.4, pairing);
+            reseed(6);
+            let mut varied: Vec<Cached<Walk>> = pop.iter().copied().map(Cached::new).collect();
+            VarAnd::new(0.8, 0.4)
+                .with_pairing(pairing)
+                .vary_in_place(&mut varied, &mut Vec::new());
+            let varied: Vec<Walk> = varied.iter().map(|ind| *ind.as_ref()).collect();
+            assert_eq!(varied, expected);
+        }
+    }
+
+    #[test]
+    fn memetic_learning() {
+        struct Keep;
+        impl Algorithm<Walk> for Keep {
+            fn pop_size(&self) -> usize {
+                6
+            }
+            fn step(&self, _: &mut Vec<Cached<Walk>>) {}
+        }
+
+        let initial: Vec<Cached<Walk>> =
+            (0..6).map(|i| Cached::new(Walk(5.0 + i as f64))).collect();
+
+        let mut pop = initial.clone();
+        Memetic::new(Keep, HillClimb::new(20), 0.5).step(&mut pop);
+        let moved = pop
+            .iter()
+            .zip(&initial)
+            .filter(|(ind, old)| ind != old)
+            .count();
+        assert_eq!(moved, 3);
+        assert!(pop
+            .iter()
+            .all(|ind| ind.evaluate() == ind.as_ref().evaluate()));
+
+        // the genotypes stay the same, but some of them get a better fitness than their own
+        let mut pop = initial.clone();
+        Memetic::new(Keep, HillClimb::new(20), 0.5)
+            .with_learning(Learning::Baldwinian)
+            .step(&mut pop);
+        assert_eq!(pop, initial);
+        let learned = pop
+            .iter()
+            .filter(|ind| ind.evaluate() > ind.as_ref().evaluate())
+            .count();
+        assert_eq!(learned, 3);
+    }
+
+    #[test]
+    fn memetic_counts_local_search() {
+        struct Keep;
+        impl Algorithm<Walk> for Keep {
+            fn pop_size(&self) -> usize {
+                6
+            }
+            fn step(&self, _: &mut Vec<Cached<Walk>>) {}
+        }
+
+        // the only evaluations after the first generation are the local search's
+        let evo = || -> crate::Evolution<Walk, _, _, ()> {
+            crate::Evolution::new(
+                Memetic::new(Keep, HillClimb::new(20), 0.5),
+                crate::hof::BestN::new(1),
+            )
+        };
+        assert!(evo().run_for(1).evaluations > 6);
+        assert!(evo().run_for_evaluations(200).evaluations >= 200);
+    }
+
+    #[test]
+    fn immigrants_replace_a_fraction() {
+        struct Keep;
+        impl Algorithm<Walk> for Keep {
+            fn pop_size(&self) -> usize {
+                10
+            }
+            fn step(&self, _: &mut Vec<Cached<Walk>>) {}
+        }
+
+        // immigrants are generated between 0 and 1, and everyone else is further from 0
+        let initial: Vec<Cached<Walk>> = (0..10)
+            .map(|i| Cached::new(Walk(10.0 + i as f64)))
+            .collect();
+        let immigrants =
+            |pop: &[Cached<Walk>]| pop.iter().filter(|ind| ind.as_ref().0 < 1.0).count();
+
+        let mut pop = initial.clone();
+        WithImmigrants::new(Keep, 0.3).step(&mut pop);
+        assert_eq!(immigrants(&pop), 3);
+
+        let mut pop = initial.clone();
+        WithImmigrants::new(Keep, 0.3)
+            .with_replace(Replace::Worst)
+            .step(&mut pop);
+        assert_eq!(immigrants(&pop), 3);
+        assert_eq!(&pop[..7], &initial[..7]);
+    }
+}
@@ -0,0 +1,31 @@
+//! Ready-made benchmark problems
+//!
+//! This module contains classic test problems implemented as [`Solution`]s,
+//! for sanity-checking a setup and comparing algorithms against each other.
+//! Every benchmark uses one of the genomes from the [`genome`] module together with
+//! standard operators and bounds, so it can be handed directly to [`Evolution`].
+//!
+//! Benchmarks are usually stated as minimization problems. Since fitness is always
+//! maximized in this crate, their fitness is the negated objective value,
+//! and each of them also provides the raw objective value.
+//! The exceptions are the [`binary`] benchmarks, which are traditionally maximized.
+//!
+//! [`Solution`]: ../trait.Solution.html
+//! [`genome`]: ../genome/index.html
+//! [`binary`]: ./binary/index.html
+//! [`Evolution`]: ../struct.Evolution.html
+
+pub mod bbob;
+pub mod binary;
+pub mod combinatorial;
+pub mod compare;
+pub mod continuous;
+pub mod multi;
+pub mod race;
+pub mod tune;
+pub mod variants;
+
+pub use continuous::{
+    Ackley, Benchmark, Griewank, Rastrigin, Rosenbrock, Schwefel, Sphere, TestFunction,
+};
+pub use multi::{MultiBenchmark, MultiTestFunction};
@@ -0,0 +1,360 @@
+//! The BBOB noiseless test suite and a COCO-style experiment runner
+//!
+//! This module contains the 24 noiseless functions of the BBOB[^1] test suite as [`BbobProblem`]s,
+//! and an [`Experiment`] that runs an algorithm over a [`BbobSuite`] the way the COCO[^2]
+//! platform does: every problem gets an evaluation budget proportional to its dimension,
+//! every evaluation is counted, and progress is logged in the folder layout of COCO's `bbob` logger,
+//! so the results can be read by its post-processing tools.
+//!
+//! Within an experiment, [`BbobSolution`] is the solution type to optimize.
+//! It always refers to the problem that is currently being run,
+//! and every evaluation of it is recorded by the experiment.
+//!
+//! ```no_run
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::bbob::{BbobSolution, BbobSuite, Experiment};
+//!
+//! let suite = BbobSuite::new().with_dimensions(vec![2, 5, 10]);
+//! Experiment::new(suite, "exdata", "mu-plus-lambda")
+//!     .with_budget_multiplier(1000)
+//!     .run(|problem| {
+//!         let evo: Evolution<BbobSolution, _, _, ()> = Evolution::new(
+//!             alg::MuPlusLambda::new(20, 40, 0.6, 0.3, select::Tournament::new(3)),
+//!             hof::BestN::new(1),
+//!         );
+//!         evo.run_until(|_| problem.is_done());
+//!     })
+//!     .unwrap();
+//! ```
+//!
+//! Instances are generated from their function and instance numbers with the pseudo-random
+//! generator of the original BBOB code, so each problem is fixed regardless of the [seed].
+//!
+//! [^1]: Hansen, Finck, Ros, & Auger.
+//! "Real-Parameter Black-Box Optimization Benchmarking 2009: Noiseless Functions Definitions."
+//! 2009. <https://hal.inria.fr/inria-00362633>
+//!
+//! [^2]: Hansen, Auger, Ros, Mersmann, Tušar, & Brockhoff.
+//! "COCO: A platform for comparing continuous optimizers in a black-box setting."
+//! 2021. <https://doi.org/10.1080/10556788.2020.1808977>
+//!
+//! [`BbobProblem`]: ./struct.BbobProblem.html
+//! [`Experiment`]: ./struct.Experiment.html
+//! [`BbobSuite`]: ./struct.BbobSuite.html
+//! [`BbobSolution`]: ./struct.BbobSolution.html
+//! [seed]: ../../repro_rng/index.html
+
+mod functions;
+mod observer;
+
+pub use observer::{observe, BbobSolution, Experiment, ObservedProblem, ProblemResult};
+
+use std::fmt::Debug;
+
+/// The names of the 24 noiseless BBOB functions, in order
+pub const FUNCTION_NAMES: [&str; 24] = [
+    "Sphere",
+    "Separable Ellipsoidal",
+    "Rastrigin",
+    "Büche-Rastrigin",
+    "Linear Slope",
+    "Attractive Sector",
+    "Step Ellipsoidal",
+    "Rosenbrock",
+    "Rotated Rosenbrock",
+    "Ellipsoidal",
+    "Discus",
+    "Bent Cigar",
+    "Sharp Ridge",
+    "Different Powers",
+    "Rotated Rastrigin",
+    "Weierstrass",
+    "Schaffers F7",
+    "Ill-Conditioned Schaffers F7",
+    "Composite Griewank-Rosenbrock F8F2",
+    "Schwefel",
+    "Gallagher's Gaussian 101-me Peaks",
+    "Gallagher's Gaussian 21-hi Peaks",
+    "Katsuura",
+    "Lunacek bi-Rastrigin",
+];
+
+/// One instance of a BBOB function in a given dimension
+///
+/// Every problem is to be minimized over the box `[-5, 5]` in every dimension,
+/// and has a known optimal value, `fopt`, which is reached at `xopt`.
+#[derive(Clone)]
+pub struct BbobProblem {
+    function: usize,
+    instance: u64,
+    dim: usize,
+    data: functions::Instance,
+}
+
+impl BbobProblem {
+    /// The bounds of every variable.
+    pub const BOUNDS: (f64, f64) = (-5.0, 5.0);
+
+    /// Create an instance of one of the 24 BBOB functions, numbered from 1, in `dim` dimensions.
+    ///
+    /// Returns `None` if `function` isn't between 1 and 24, if `instance` is 0, or if `dim` is less than 2.
+    pub fn new(function: usize, instance: u64, dim: usize) -> Option<Self> {
+        if !(1..=24).contains(&function) || instance == 0 || dim < 2 {
+            return None;
+        }
+        Some(BbobProblem {
+            function,
+            instance,
+            dim,
+            data: functions::Instance::new(function, instance, dim),
+        })
+    }
+
+    /// Get the function number, from 1 to 24.
+    pub fn function(&self) -> usize {
+        self.function
+    }
+
+    /// Get the name of the function.
+    pub fn name(&self) -> &'static str {
+        FUNCTION_NAMES[self.function - 1]
+    }
+
+    /// Get the instance number.
+    pub fn instance(&self) -> u64 {
+        self.instance
+    }
+
+    /// Get the number of dimensions.
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Get the COCO identifier of this problem, e.g. `bbob_f001_i01_d02`.
+    pub fn id(&self) -> String {
+        format!(
+            "bbob_f{:03}_i{:02}_d{:02}",
+            self.function, self.instance, self.dim
+        )
+    }
+
+    /// Get the optimal value.
+    pub fn fopt(&self) -> f64 {
+        self.data.fopt
+    }
+
+    /// Get the location of the optimum.
+    pub fn xopt(&self) -> &[f64] {
+        &self.data.xopt
+    }
+
+    /// Compute the value of the function at `x`.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `x` doesn't have exactly `dim` elements.
+    pub fn value(&self, x: &[f64]) -> f64 {
+        assert_eq!(
+            x.len(),
+            self.dim,
+            "point has the wrong number of dimensions"
+        );
+        self.data.evaluate(self.function, x) + self.data.fopt
+    }
+}
+
+impl Debug for BbobProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BbobProblem")
+            .field("function", &self.function)
+            .field("instance", &self.instance)
+            .field("dim", &self.dim)
+            .finish()
+    }
+}
+
+/// A set of BBOB problems to run an [`Experiment`] on
+///
+/// By default, this is all 24 functions in 2, 3, 5, 10, 20, and 40 dimensions,
+/// with instances 1 to 15.
+///
+/// [`Experiment`]: ./struct.Experiment.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BbobSuite {
+    functions: Vec<usize>,
+    instances: Vec<u64>,
+    dimensions: Vec<usize>,
+}
+
+impl BbobSuite {
+    /// Create the default suite.
+    pub fn new() -> Self {
+        BbobSuite {
+            functions: (1..=24).collect(),
+            instances: (1..=15).collect(),
+            dimensions: vec![2, 3, 5, 10, 20, 40],
+        }
+    }
+
+    /// Only use the given functions, numbered from 1 to 24.
+    pub fn with_functions(mut self, functions: Vec<usize>) -> Self {
+        self.functions = functions;
+        self
+    }
+
+    /// Only use the given instances.
+    pub fn with_instances(mut self, instances: Vec<u64>) -> Self {
+        self.instances = instances;
+        self
+    }
+
+    /// Only use the given dimensions.
+    pub fn with_dimensions(mut self, dimensions: Vec<usize>) -> Self {
+        self.dimensions = dimensions;
+        self
+    }
+
+    /// Iterate over every problem in the suite, ordered by dimension, then function, then instance.
+    /// Invalid combinations are skipped.
+    pub fn problems(&self) -> impl Iterator<Item = BbobProblem> + '_ {
+        self.dimensions.iter().flat_map(move |&dim| {
+            self.functions.iter().flat_map(move |&function| {
+                self.instances
+                    .iter()
+                    .filter_map(move |&instance| BbobProblem::new(function, instance, dim))
+            })
+        })
+    }
+}
+
+impl Default for BbobSuite {
+    fn default() -> Self {
+        BbobSuite::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optimum_has_optimal_value() {
+        for dim in [2, 5, 10] {
+            for function in 1..=24 {
+                for instance in [1, 2] {
+                    let problem = BbobProblem::new(function, instance, dim).unwrap();
+                    let xopt = problem.xopt();
+                    assert!(xopt.iter().all(|x| x.abs() <= 5.0), "{}", problem.id());
+                    let error = problem.value(xopt) - problem.fopt();
+                    assert!(error.abs() < 1e-6, "{}: {}", problem.id(), error);
+                    let elsewhere = problem.value(&vec![0.1; dim]) - problem.fopt();
+                    assert!(elsewhere > -1e-6, "{}: {}", problem.id(), elsewhere);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn problems() {
+        assert!(BbobProblem::new(0, 1, 2).is_none());
+        assert!(BbobProblem::new(25, 1, 2).is_none());
+        assert!(BbobProblem::new(1, 0, 2).is_none());
+        assert!(BbobProblem::new(1, 1, 1).is_none());
+
+        let problem = BbobProblem::new(15, 3, 10).unwrap();
+        assert_eq!(problem.id(), "bbob_f015_i03_d10");
+        assert_eq!(problem.name(), "Rotated Rastrigin");
+        assert_eq!(
+            (problem.function(), problem.instance(), problem.dim()),
+            (15, 3, 10)
+        );
+        assert_eq!(problem.xopt().len(), 10);
+        assert_eq!(
+            format!("{:?}", problem),
+            "BbobProblem { function: 15, instance: 3, dim: 10 }"
+        );
+
+        // instances are fixed by their numbers, and differ from each other
+        let again = BbobProblem::new(15, 3, 10).unwrap();
+        assert_eq!(
+            (again.fopt(), again.xopt()),
+            (problem.fopt(), problem.xopt())
+        );
+        let other = BbobProblem::new(15, 4, 10).unwrap();
+        assert!(other.fopt() != problem.fopt() && other.xopt() != problem.xopt());
+        // optimal values are rounded to 2 decimals and bounded by 1000
+        for function in 1..=24 {
+            let fopt = BbobProblem::new(function, 1, 2).unwrap().fopt();
+            assert!(fopt.abs() <= 1000.0);
+            assert!(((fopt * 100.0).round() - fopt * 100.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn values() {
+        // the sphere function is the squared distance to the optimum
+        let sphere = BbobProblem::new(1, 1, 3).unwrap();
+        let x = [1.0, -2.0, 0.5];
+        let distance: f64 = x
+            .iter()
+            .zip(sphere.xopt())
+            .map(|(x, o)| (x - o).powi(2))
+            .sum();
+        assert!((sphere.value(&x) - sphere.fopt() - distance).abs() < 1e-9);
+
+        // the linear slope is optimal anywhere beyond the optimum, which is on the boundary
+        let slope = BbobProblem::new(5, 1, 2).unwrap();
+        assert!(slope.xopt().iter().all(|x| x.abs() == 5.0));
+        let beyond: Vec<f64> = slope.xopt().iter().map(|x| x * 1.5).collect();
+        assert!((slope.value(&beyond) - slope.fopt()).abs() < 1e-9);
+        let towards: Vec<f64> = slope.xopt().iter().map(|x| x * 0.5).collect();
+        assert!(slope.value(&towards) > slope.value(slope.xopt()));
+    }
+
+    #[test]
+    #[should_panic = "wrong number of dimensions"]
+    fn value_needs_dim_elements() {
+        BbobProblem::new(1, 1, 3).unwrap().value(&[0.0; 2]);
+    }
+
+    #[test]
+    fn generators() {
+        let u = functions::unif(1000, 12345);
+        assert!(u.iter().all(|&u| u > 0.0 && u < 1.0));
+        assert_eq!(u, functions::unif(1000, 12345));
+        assert!(u != functions::unif(1000, 12346));
+        let mean = u.iter().sum::<f64>() / 1000.0;
+        assert!((mean - 0.5).abs() < 0.05, "mean was {}", mean);
+
+        let g = functions::gauss(1000, 12345);
+        let mean = g.iter().sum::<f64>() / 1000.0;
+        let var = g.iter().map(|g| (g - mean).powi(2)).sum::<f64>() / 1000.0;
+        assert!(mean.abs() < 0.1, "mean was {}", mean);
+        assert!((var - 1.0).abs() < 0.15, "variance was {}", var);
+    }
+
+    #[test]
+    fn suites() {
+        assert_eq!(BbobSuite::default(), BbobSuite::new());
+        assert_eq!(BbobSuite::new().problems().count(), 24 * 15 * 6);
+
+        let suite = BbobSuite::new()
+            .with_functions(vec![2, 0, 1])
+            .with_instances(vec![1, 2])
+            .with_dimensions(vec![5, 1, 2]);
+        let ids: Vec<String> = suite.problems().map(|problem| problem.id()).collect();
+        assert_eq!(
+            ids,
+            [
+                "bbob_f002_i01_d05",
+                "bbob_f002_i02_d05",
+                "bbob_f001_i01_d05",
+                "bbob_f001_i02_d05",
+                "bbob_f002_i01_d02",
+                "bbob_f002_i02_d02",
+                "bbob_f001_i01_d02",
+                "bbob_f001_i02_d02",
+            ]
+        );
+    }
+}
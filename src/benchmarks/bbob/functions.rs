@@ -0,0 +1,530 @@
+// The 24 noiseless BBOB functions and the generation of their instances,
+// following Hansen et al., "Real-Parameter Black-Box Optimization Benchmarking 2009:
+// Noiseless Functions Definitions", and the bbob2009 generator used by COCO.
+
+use std::f64::consts::PI;
+
+// The bbob2009 uniform generator, a shuffled Park-Miller generator.
+pub fn unif(n: usize, seed: i64) -> Vec<f64> {
+    let mut seed = seed.abs().max(1);
+    let next = |seed: &mut i64| {
+        let tmp = *seed / 127773;
+        *seed = 16807 * (*seed - tmp * 127773) - 2836 * tmp;
+        if *seed < 0 {
+            *seed += 2147483647;
+        }
+    };
+    let mut table = [0i64; 32];
+    for i in (0..40).rev() {
+        next(&mut seed);
+        if i < 32 {
+            table[i] = seed;
+        }
+    }
+    let mut current = table[0];
+    (0..n)
+        .map(|_| {
+            next(&mut seed);
+            let idx = (current / 67108865) as usize;
+            current = table[idx];
+            table[idx] = seed;
+            match current as f64 / 2.147483647e9 {
+                0.0 => 1e-99,
+                r => r,
+            }
+        })
+        .collect()
+}
+
+// Normally distributed numbers from the bbob2009 generator, using the Box-Muller transform.
+pub fn gauss(n: usize, seed: i64) -> Vec<f64> {
+    let u = unif(2 * n, seed);
+    (0..n)
+        .map(
+            |i| match (-2.0 * u[i].ln()).sqrt() * (2.0 * PI * u[n + i]).cos() {
+                0.0 => 1e-99,
+                g => g,
+            },
+        )
+        .collect()
+}
+
+// A random orthogonal matrix, stored as rows.
+fn rotation(dim: usize, seed: i64) -> Vec<Vec<f64>> {
+    let g = gauss(dim * dim, seed);
+    let mut b: Vec<Vec<f64>> = (0..dim)
+        .map(|i| (0..dim).map(|j| g[j * dim + i]).collect())
+        .collect();
+    // Gram-Schmidt on the columns
+    for i in 0..dim {
+        for j in 0..i {
+            let prod: f64 = (0..dim).map(|k| b[k][i] * b[k][j]).sum();
+            for row in b.iter_mut() {
+                row[i] -= prod * row[j];
+            }
+        }
+        let norm = (0..dim).map(|k| b[k][i] * b[k][i]).sum::<f64>().sqrt();
+        for row in b.iter_mut() {
+            row[i] /= norm;
+        }
+    }
+    b
+}
+
+fn mul(m: &[Vec<f64>], x: &[f64]) -> Vec<f64> {
+    m.iter()
+        .map(|row| row.iter().zip(x).map(|(a, b)| a * b).sum())
+        .collect()
+}
+
+fn frac(i: usize, dim: usize) -> f64 {
+    if dim > 1 {
+        i as f64 / (dim - 1) as f64
+    } else {
+        1.0
+    }
+}
+
+fn t_osz_scalar(x: f64) -> f64 {
+    if x == 0.0 {
+        return 0.0;
+    }
+    let x_hat = x.abs().ln();
+    let (c1, c2) = if x > 0.0 { (10.0, 7.9) } else { (5.5, 3.1) };
+    x.signum() * (x_hat + 0.049 * ((c1 * x_hat).sin() + (c2 * x_hat).sin())).exp()
+}
+
+fn t_osz(x: &mut [f64]) {
+    for x in x.iter_mut() {
+        *x = t_osz_scalar(*x);
+    }
+}
+
+fn t_asy(x: &mut [f64], beta: f64) {
+    let dim = x.len();
+    for (i, x) in x.iter_mut().enumerate() {
+        if *x > 0.0 {
+            *x = x.powf(1.0 + beta * frac(i, dim) * x.sqrt());
+        }
+    }
+}
+
+fn lambda(x: &mut [f64], alpha: f64) {
+    let dim = x.len();
+    for (i, x) in x.iter_mut().enumerate() {
+        *x *= alpha.powf(0.5 * frac(i, dim));
+    }
+}
+
+fn penalty(x: &[f64]) -> f64 {
+    x.iter().map(|x| (x.abs() - 5.0).max(0.0).powi(2)).sum()
+}
+
+fn rastrigin(z: &[f64]) -> f64 {
+    let dim = z.len() as f64;
+    10.0 * (dim - z.iter().map(|z| (2.0 * PI * z).cos()).sum::<f64>())
+        + z.iter().map(|z| z * z).sum::<f64>()
+}
+
+fn rosenbrock(z: &[f64]) -> f64 {
+    z.windows(2)
+        .map(|w| 100.0 * (w[0] * w[0] - w[1]).powi(2) + (w[0] - 1.0).powi(2))
+        .sum()
+}
+
+fn schaffer(z: &[f64]) -> f64 {
+    let n = z.len().saturating_sub(1).max(1) as f64;
+    let sum: f64 = z
+        .windows(2)
+        .map(|w| {
+            let s = (w[0] * w[0] + w[1] * w[1]).sqrt();
+            s.sqrt() + s.sqrt() * (50.0 * s.powf(0.2)).sin().powi(2)
+        })
+        .sum();
+    (sum / n).powi(2)
+}
+
+fn rosenbrock_factor(dim: usize) -> f64 {
+    (dim as f64).sqrt().max(8.0) / 8.0
+}
+
+// A peak of a Gallagher function.
+#[derive(Clone)]
+pub struct Peak {
+    pub y: Vec<f64>,
+    weight: f64,
+    scales: Vec<f64>,
+}
+
+// Everything that is random about a problem instance.
+#[derive(Clone)]
+pub struct Instance {
+    pub xopt: Vec<f64>,
+    pub fopt: f64,
+    r: Vec<Vec<f64>>,
+    q: Vec<Vec<f64>>,
+    peaks: Vec<Peak>,
+}
+
+impl Instance {
+    pub fn new(function: usize, instance: u64, dim: usize) -> Self {
+        // some functions share their instances with a related function
+        let base = match function {
+            4 => 3,
+            18 => 17,
+            f => f,
+        };
+        let seed = base as i64 + 10000 * instance as i64;
+
+        let fopt = {
+            let g = gauss(1, seed)[0];
+            let u = unif(1, seed + 1)[0];
+            ((100.0 * 100.0 * g / u).round() / 100.0).clamp(-1000.0, 1000.0)
+        };
+        let mut xopt: Vec<f64> = unif(dim, seed)
+            .into_iter()
+            .map(|u| match 8.0 * (1e4 * u).floor() / 1e4 - 4.0 {
+                0.0 => -1e-5,
+                x => x,
+            })
+            .collect();
+        let r = rotation(dim, seed + 1000000);
+        let q = rotation(dim, seed);
+        let mut peaks = Vec::new();
+
+        match function {
+            4 => {
+                for x in xopt.iter_mut().step_by(2) {
+                    *x = x.abs();
+                }
+            }
+            5 => {
+                for x in xopt.iter_mut() {
+                    *x = 5.0f64.copysign(*x);
+                }
+            }
+            8 => {
+                for x in xopt.iter_mut() {
+                    *x *= 0.75;
+                }
+            }
+            9 | 19 => {
+                // the optimum is where z = 1, i.e. x = Rᵀ(1 / (2 factor))
+                let factor = rosenbrock_factor(dim);
+                xopt = (0..dim)
+                    .map(|i| (0..dim).map(|j| r[j][i]).sum::<f64>() / (2.0 * factor))
+                    .collect();
+            }
+            20 => {
+                xopt = unif(dim, seed)
+                    .into_iter()
+                    .map(|u| 0.5 * 4.2096874637f64.copysign(u - 0.5))
+                    .collect();
+            }
+            21 | 22 => {
+                let (n_peaks, max_condition, inner, outer) = if function == 21 {
+                    (101, 1000.0, 4.0, 5.0)
+                } else {
+                    (21, 1000.0 * 1000.0, 3.92, 4.9)
+                };
+                let mut order: Vec<usize> = (0..n_peaks - 1).collect();
+                let u = unif(n_peaks - 1, seed);
+                order.sort_by(|&a, &b| u[a].total_cmp(&u[b]));
+                let u = unif(dim * n_peaks, seed);
+                peaks = (0..n_peaks)
+                    .map(|i| {
+                        let (condition, weight, bound) = if i == 0 {
+                            (max_condition, 10.0, inner)
+                        } else {
+                            let j = order[i - 1] as f64;
+                            (
+                                1000.0f64.powf(2.0 * j / (n_peaks - 2) as f64),
+                                1.1 + 8.0 * (i - 1) as f64 / (n_peaks - 2) as f64,
+                                outer,
+                            )
+                        };
+                        let mut axes: Vec<usize> = (0..dim).collect();
+                        let v = unif(dim, seed + 1000 * (i as i64 + 1));
+                        axes.sort_by(|&a, &b| v[a].total_cmp(&v[b]));
+                        let scales = axes
+                            .into_iter()
+                            .map(|k| condition.powf(0.5 * frac(k, dim)) / condition.powf(0.25))
+                            .collect();
+                        let y = (0..dim)
+                            .map(|k| bound * (2.0 * u[i * dim + k] - 1.0))
+                            .collect();
+                        Peak { y, weight, scales }
+                    })
+                    .collect();
+                xopt = peaks[0].y.clone();
+            }
+            24 => {
+                xopt = gauss(dim, seed)
+                    .into_iter()
+                    .map(|g| 1.25f64.copysign(g))
+                    .collect();
+            }
+            _ => {}
+        }
+
+        Instance {
+            xopt,
+            fopt,
+            r,
+            q,
+            peaks,
+        }
+    }
+
+    fn shifted(&self, x: &[f64]) -> Vec<f64> {
+        x.iter().zip(&self.xopt).map(|(x, o)| x - o).collect()
+    }
+
+    // Compute the value of `function` at `x`, without the optimal value.
+    pub fn evaluate(&self, function: usize, x: &[f64]) -> f64 {
+        let dim = x.len();
+        let (r, q) = (&self.r, &self.q);
+        match function {
+            1 => self.shifted(x).iter().map(|z| z * z).sum(),
+            2 => {
+                let mut z = self.shifted(x);
+                t_osz(&mut z);
+                z.iter()
+                    .enumerate()
+                    .map(|(i, z)| 10f64.powf(6.0 * frac(i, dim)) * z * z)
+                    .sum()
+            }
+            3 => {
+                let mut z = self.shifted(x);
+                t_osz(&mut z);
+                t_asy(&mut z, 0.2);
+                lambda(&mut z, 10.0);
+                rastrigin(&z)
+            }
+            4 => {
+                let mut z = self.shifted(x);
+                t_osz(&mut z);
+                for (i, z) in z.iter_mut().enumerate() {
+                    let scale = 10f64.powf(0.5 * frac(i, dim));
+                    *z *= if *z > 0.0 && i % 2 == 0 {
+                        10.0 * scale
+                    } else {
+                        scale
+                    };
+                }
+                rastrigin(&z) + 100.0 * penalty(x)
+            }
+            5 => x
+                .iter()
+                .zip(&self.xopt)
+                .enumerate()
+                .map(|(i, (&x, &o))| {
+                    let z = if x * o < 25.0 { x } else { o };
+                    let s = o.signum() * 10f64.powf(frac(i, dim));
+                    5.0 * s.abs() - s * z
+                })
+                .sum(),
+            6 => {
+                let mut z = mul(r, &self.shifted(x));
+                lambda(&mut z, 10.0);
+                let z = mul(q, &z);
+                let sum: f64 = z
+                    .iter()
+                    .zip(&self.xopt)
+                    .map(|(z, o)| {
+                        if z * o > 0.0 {
+                            (100.0 * z).powi(2)
+                        } else {
+                            z * z
+                        }
+                    })
+                    .sum();
+                t_osz_scalar(sum).powf(0.9)
+            }
+            7 => {
+                let mut z_hat = mul(r, &self.shifted(x));
+                lambda(&mut z_hat, 10.0);
+                let rounded: Vec<f64> = z_hat
+                    .iter()
+                    .map(|&z| {
+                        if z.abs() > 0.5 {
+                            (0.5 + z).floor()
+                        } else {
+                            (0.5 + 10.0 * z).floor() / 10.0
+                        }
+                    })
+                    .collect();
+                let z = mul(q, &rounded);
+                let sum: f64 = z
+                    .iter()
+                    .enumerate()
+                    .map(|(i, z)| 10f64.powf(2.0 * frac(i, dim)) * z * z)
+                    .sum();
+                0.1 * (z_hat[0].abs() / 1e4).max(sum) + penalty(x)
+            }
+            8 => {
+                let factor = rosenbrock_factor(dim);
+                let z: Vec<f64> = self.shifted(x).iter().map(|z| factor * z + 1.0).collect();
+                rosenbrock(&z)
+            }
+            9 => {
+                let factor = rosenbrock_factor(dim);
+                let z: Vec<f64> = mul(r, x).iter().map(|z| factor * z + 0.5).collect();
+                rosenbrock(&z)
+            }
+            10 | 11 => {
+                let mut z = mul(r, &self.shifted(x));
+                t_osz(&mut z);
+                if function == 10 {
+                    z.iter()
+                        .enumerate()
+                        .map(|(i, z)| 10f64.powf(6.0 * frac(i, dim)) * z * z)
+                        .sum()
+                } else {
+                    1e6 * z[0] * z[0] + z[1..].iter().map(|z| z * z).sum::<f64>()
+                }
+            }
+            12 => {
+                let mut z = mul(r, &self.shifted(x));
+                t_asy(&mut z, 0.5);
+                let z = mul(r, &z);
+                z[0] * z[0] + 1e6 * z[1..].iter().map(|z| z * z).sum::<f64>()
+            }
+            13 => {
+                let mut z = mul(r, &self.shifted(x));
+                lambda(&mut z, 10.0);
+                let z = mul(q, &z);
+                z[0] * z[0] + 100.0 * z[1..].iter().map(|z| z * z).sum::<f64>().sqrt()
+            }
+            14 => {
+                let z = mul(r, &self.shifted(x));
+                z.iter()
+                    .enumerate()
+                    .map(|(i, z)| z.abs().powf(2.0 + 4.0 * frac(i, dim)))
+                    .sum::<f64>()
+                    .sqrt()
+            }
+            15 => {
+                let mut z = mul(r, &self.shifted(x));
+                t_osz(&mut z);
+                t_asy(&mut z, 0.2);
+                let mut z = mul(q, &z);
+                lambda(&mut z, 10.0);
+                rastrigin(&mul(r, &z))
+            }
+            16 => {
+                let mut z = mul(r, &self.shifted(x));
+                t_osz(&mut z);
+                let mut z = mul(q, &z);
+                lambda(&mut z, 0.01);
+                let z = mul(r, &z);
+                let weierstrass = |z: f64| {
+                    (0..12)
+                        .map(|k| 0.5f64.powi(k) * (2.0 * PI * 3f64.powi(k) * (z + 0.5)).cos())
+                        .sum::<f64>()
+                };
+                let f0 = weierstrass(0.0);
+                let sum: f64 = z.iter().map(|&z| weierstrass(z) - f0).sum();
+                10.0 * (sum / dim as f64).powi(3) + 10.0 / dim as f64 * penalty(x)
+            }
+            17 | 18 => {
+                let mut z = mul(r, &self.shifted(x));
+                t_asy(&mut z, 0.5);
+                let mut z = mul(q, &z);
+                lambda(&mut z, if function == 17 { 10.0 } else { 1000.0 });
+                schaffer(&z) + 10.0 * penalty(x)
+            }
+            19 => {
+                let factor = rosenbrock_factor(dim);
+                let z: Vec<f64> = mul(r, x).iter().map(|z| factor * z + 0.5).collect();
+                let n = dim.saturating_sub(1).max(1) as f64;
+                let sum: f64 = z
+                    .windows(2)
+                    .map(|w| {
+                        let s = 100.0 * (w[0] * w[0] - w[1]).powi(2) + (w[0] - 1.0).powi(2);
+                        s / 4000.0 - s.cos()
+                    })
+                    .sum();
+                10.0 * sum / n + 10.0
+            }
+            20 => {
+                let x_hat: Vec<f64> = x
+                    .iter()
+                    .zip(&self.xopt)
+                    .map(|(x, o)| 2.0 * o.signum() * x)
+                    .collect();
+                let two_opt: Vec<f64> = self.xopt.iter().map(|o| 2.0 * o.abs()).collect();
+                let mut z_hat = x_hat.clone();
+                for i in 1..dim {
+                    z_hat[i] += 0.25 * (x_hat[i - 1] - two_opt[i - 1]);
+                }
+                let mut centered: Vec<f64> =
+                    z_hat.iter().zip(&two_opt).map(|(z, o)| z - o).collect();
+                lambda(&mut centered, 10.0);
+                let z: Vec<f64> = centered
+                    .iter()
+                    .zip(&two_opt)
+                    .map(|(z, o)| 100.0 * (z + o))
+                    .collect();
+                let sum: f64 = z.iter().map(|z| z * z.abs().sqrt().sin()).sum();
+                let scaled: Vec<f64> = z.iter().map(|z| z / 100.0).collect();
+                -sum / (100.0 * dim as f64) + 4.189828872724339 + 100.0 * penalty(&scaled)
+            }
+            21 | 22 => {
+                let best = self
+                    .peaks
+                    .iter()
+                    .map(|peak| {
+                        let diff: Vec<f64> = x.iter().zip(&peak.y).map(|(x, y)| x - y).collect();
+                        let z = mul(r, &diff);
+                        let quad: f64 = z.iter().zip(&peak.scales).map(|(z, c)| c * z * z).sum();
+                        peak.weight * (-quad / (2.0 * dim as f64)).exp()
+                    })
+                    .fold(f64::NEG_INFINITY, f64::max);
+                t_osz_scalar(10.0 - best).powi(2) + penalty(x)
+            }
+            23 => {
+                let mut z = mul(r, &self.shifted(x));
+                lambda(&mut z, 100.0);
+                let z = mul(q, &z);
+                let d = dim as f64;
+                let exponent = 10.0 / d.powf(1.2);
+                let product: f64 = z
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &z)| {
+                        let sum: f64 = (1..=32)
+                            .map(|j| {
+                                let p = 2f64.powi(j);
+                                (p * z - (p * z).round()).abs() / p
+                            })
+                            .sum();
+                        (1.0 + (i + 1) as f64 * sum).powf(exponent)
+                    })
+                    .product();
+                10.0 / (d * d) * product - 10.0 / (d * d) + penalty(x)
+            }
+            24 => {
+                let (mu0, d) = (2.5, 1.0);
+                let s = 1.0 - 1.0 / (2.0 * (dim as f64 + 20.0).sqrt() - 8.2);
+                let mu1 = -((mu0 * mu0 - d) / s).sqrt();
+                let x_hat: Vec<f64> = x
+                    .iter()
+                    .zip(&self.xopt)
+                    .map(|(x, o)| 2.0 * o.signum() * x)
+                    .collect();
+                let sphere0: f64 = x_hat.iter().map(|x| (x - mu0).powi(2)).sum();
+                let sphere1: f64 = x_hat.iter().map(|x| (x - mu1).powi(2)).sum();
+                let centered: Vec<f64> = x_hat.iter().map(|x| x - mu0).collect();
+                let mut z = mul(r, &centered);
+                lambda(&mut z, 100.0);
+                let z = mul(q, &z);
+                let cosines: f64 = z.iter().map(|z| (2.0 * PI * z).cos()).sum();
+                sphere0.min(d * dim as f64 + s * sphere1)
+                    + 10.0 * (dim as f64 - cosines)
+                    + 1e4 * penalty(x)
+            }
+            _ => unreachable!("there are only 24 BBOB functions"),
+        }
+    }
+}
@@ -0,0 +1,498 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use super::{BbobProblem, BbobSuite};
+use crate::{
+    genome::{real, RealVector},
+    Solution,
+};
+
+static ACTIVE: RwLock<Option<Arc<ObservedProblem>>> = RwLock::new(None);
+
+fn active() -> Arc<ObservedProblem> {
+    ACTIVE
+        .read()
+        .unwrap()
+        .clone()
+        .expect("BbobSolution can only be used inside Experiment::run")
+}
+
+/// The precision at which a problem counts as solved.
+const PRECISION: f64 = 1e-8;
+
+// Format a number like C's `%+.*e`, e.g. `+1.500e-03`.
+fn c_exp(x: f64, precision: usize) -> String {
+    let formatted = format!("{:.*e}", precision, x);
+    let (mantissa, exponent) = formatted.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    let sign = if x.is_sign_negative() { "" } else { "+" };
+    format!(
+        "{}{}e{}{:02}",
+        sign,
+        mantissa,
+        if exponent < 0 { '-' } else { '+' },
+        exponent.abs()
+    )
+}
+
+struct State {
+    evaluations: usize,
+    best: f64,
+    best_level: Option<i64>,
+    next_trigger: usize,
+    dat: String,
+    tdat: String,
+    last_line: String,
+}
+
+/// A [`BbobProblem`] that is being run in an [`Experiment`]
+///
+/// This keeps track of every evaluation of the problem, and is passed to the closure given to [`Experiment::run`].
+///
+/// [`BbobProblem`]: ./struct.BbobProblem.html
+/// [`Experiment`]: ./struct.Experiment.html
+/// [`Experiment::run`]: ./struct.Experiment.html#method.run
+pub struct ObservedProblem {
+    problem: BbobProblem,
+    budget: usize,
+    state: Mutex<State>,
+}
+
+impl ObservedProblem {
+    fn new(problem: BbobProblem, budget: usize) -> Self {
+        ObservedProblem {
+            problem,
+            budget,
+            state: Mutex::new(State {
+                evaluations: 0,
+                best: f64::INFINITY,
+                best_level: None,
+                next_trigger: 1,
+                dat: String::new(),
+                tdat: String::new(),
+                last_line: String::new(),
+            }),
+        }
+    }
+
+    /// Get the number of evaluations this problem is given.
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Get the number of evaluations so far.
+    pub fn evaluations(&self) -> usize {
+        self.state.lock().unwrap().evaluations
+    }
+
+    /// Get the best value found so far.
+    pub fn best_value(&self) -> f64 {
+        self.state.lock().unwrap().best
+    }
+
+    /// Check whether the problem has been solved to a precision of 10⁻⁸.
+    pub fn is_solved(&self) -> bool {
+        self.best_value() - self.problem.fopt() < PRECISION
+    }
+
+    /// Check whether the run on this problem should stop,
+    /// i.e. if the budget has been used up or the problem has been solved.
+    pub fn is_done(&self) -> bool {
+        self.evaluations() >= self.budget || self.is_solved()
+    }
+
+    fn result(&self) -> ProblemResult {
+        ProblemResult {
+            function: self.function(),
+            instance: self.instance(),
+            dim: self.dim(),
+            evaluations: self.evaluations(),
+            best_delta: self.best_value() - self.fopt(),
+        }
+    }
+
+    /// Evaluate the problem at `x`, recording the evaluation.
+    pub fn evaluate(&self, x: &[f64]) -> f64 {
+        let value = self.problem.value(x);
+        let fopt = self.problem.fopt();
+        let mut state = self.state.lock().unwrap();
+        state.evaluations += 1;
+        state.best = state.best.min(value);
+
+        let mut line = format!(
+            "{} 0 {} {} {}",
+            state.evaluations,
+            c_exp(state.best - fopt, 9),
+            c_exp(value, 9),
+            c_exp(state.best, 9)
+        );
+        for x in x {
+            let _ = write!(line, " {}", c_exp(*x, 4));
+        }
+        line.push('\n');
+
+        // log to .dat whenever a new target of the form 10^(k/5) is reached
+        let level = match state.best - fopt {
+            delta if delta > 0.0 => (5.0 * delta.log10()).floor() as i64,
+            _ => i64::MIN,
+        };
+        if state.best_level.is_none_or(|best| level < best) {
+            state.best_level = Some(level);
+            state.dat.push_str(&line);
+        }
+        // log to .tdat after 1, 2, 5, 10, 20, 50, ... evaluations
+        if state.evaluations == state.next_trigger {
+            state.next_trigger = match state.next_trigger.to_string().as_bytes()[0] {
+                b'2' => state.next_trigger / 2 * 5,
+                _ => state.next_trigger * 2,
+            };
+            state.tdat.push_str(&line);
+        }
+        state.last_line = line;
+        value
+    }
+}
+
+fn observe_with(
+    problem: BbobProblem,
+    budget: usize,
+    optimize: impl FnOnce(&ObservedProblem),
+) -> Arc<ObservedProblem> {
+    let observed = Arc::new(ObservedProblem::new(problem, budget));
+    {
+        let mut active = ACTIVE.write().unwrap();
+        assert!(
+            active.is_none(),
+            "only one problem can be observed at a time"
+        );
+        *active = Some(observed.clone());
+    }
+    optimize(&observed);
+    ACTIVE.write().unwrap().take();
+    observed
+}
+
+/// Run `optimize` on a single problem with a budget of `budget` evaluations, without writing any logs.
+///
+/// This works just like one step of [`Experiment::run`], and is useful when the results
+/// are only needed in memory, e.g. to [compare] algorithms.
+///
+/// Panics
+/// ======
+/// Panics if an experiment or another call to `observe` is running at the same time.
+///
+/// [`Experiment::run`]: ./struct.Experiment.html#method.run
+/// [compare]: ../compare/index.html
+pub fn observe(
+    problem: BbobProblem,
+    budget: usize,
+    optimize: impl FnOnce(&ObservedProblem),
+) -> ProblemResult {
+    observe_with(problem, budget, optimize).result()
+}
+
+impl Deref for ObservedProblem {
+    type Target = BbobProblem;
+    fn deref(&self) -> &BbobProblem {
+        &self.problem
+    }
+}
+
+/// The [`Solution`] type for the problem that is currently being run in an [`Experiment`]
+///
+/// Solutions are generated uniformly within the problem's bounds, crossed over with
+/// [simulated binary crossover] with η = 20, and mutated with [polynomial mutation]
+/// with η = 20 and a probability of 1/`dim` for each variable.
+/// Fitness is the negated value of the problem, and every evaluation is recorded.
+/// This type derefs to the [`RealVector`] it contains.
+///
+/// Panics
+/// ======
+/// Generating or evaluating a `BbobSolution` panics outside of [`Experiment::run`].
+///
+/// [`Solution`]: ../../trait.Solution.html
+/// [`Experiment`]: ./struct.Experiment.html
+/// [simulated binary crossover]: ../../genome/real/fn.simulated_binary.html
+/// [polynomial mutation]: ../../genome/real/fn.polynomial.html
+/// [`RealVector`]: ../../genome/struct.RealVector.html
+/// [`Experiment::run`]: ./struct.Experiment.html#method.run
+#[derive(Clone, Debug, PartialEq)]
+pub struct BbobSolution(RealVector);
+
+impl BbobSolution {
+    /// Consume the solution, returning the vector it contained.
+    pub fn into_inner(self) -> RealVector {
+        self.0
+    }
+}
+
+impl Solution for BbobSolution {
+    type Fitness = f64;
+
+    fn generate() -> Self {
+        BbobSolution(RealVector::uniform(vec![
+            BbobProblem::BOUNDS;
+            active().dim()
+        ]))
+    }
+
+    fn evaluate(&self) -> f64 {
+        -active().evaluate(&self.0)
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        real::simulated_binary(&mut a.0, &mut b.0, 20.0);
+    }
+
+    fn mutate(&mut self) {
+        let indpb = 1.0 / self.0.len().max(1) as f64;
+        real::polynomial(&mut self.0, 20.0, indpb);
+    }
+}
+
+impl Deref for BbobSolution {
+    type Target = RealVector;
+    fn deref(&self) -> &RealVector {
+        &self.0
+    }
+}
+
+impl AsRef<RealVector> for BbobSolution {
+    fn as_ref(&self) -> &RealVector {
+        &self.0
+    }
+}
+
+/// The outcome of running one problem in an [`Experiment`]
+///
+/// [`Experiment`]: ./struct.Experiment.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProblemResult {
+    /// The function number.
+    pub function: usize,
+    /// The instance number.
+    pub instance: u64,
+    /// The number of dimensions.
+    pub dim: usize,
+    /// The number of evaluations used.
+    pub evaluations: usize,
+    /// The difference between the best value found and the optimal value.
+    pub best_delta: f64,
+}
+
+/// A COCO-style benchmarking experiment over a [`BbobSuite`]
+///
+/// See the [module-level documentation] for an example.
+///
+/// [`BbobSuite`]: ./struct.BbobSuite.html
+/// [module-level documentation]: ./index.html
+#[derive(Clone, Debug)]
+pub struct Experiment {
+    suite: BbobSuite,
+    output: PathBuf,
+    algorithm: String,
+    budget_multiplier: usize,
+}
+
+impl Experiment {
+    /// Create an experiment that runs the problems in `suite` and writes its results
+    /// to a folder named after `algorithm` inside `output`.
+    ///
+    /// If that folder already exists, a numbered suffix is added to the name, like COCO does.
+    pub fn new(suite: BbobSuite, output: impl Into<PathBuf>, algorithm: impl Into<String>) -> Self {
+        Experiment {
+            suite,
+            output: output.into(),
+            algorithm: algorithm.into(),
+            budget_multiplier: 1000,
+        }
+    }
+
+    /// Give each problem a budget of `multiplier` evaluations per dimension. The default is 1000.
+    pub fn with_budget_multiplier(mut self, multiplier: usize) -> Self {
+        self.budget_multiplier = multiplier;
+        self
+    }
+
+    /// Run the experiment, calling `optimize` once for each problem in the suite.
+    ///
+    /// `optimize` should run an algorithm on [`BbobSolution`] until
+    /// [`ObservedProblem::is_done`] returns `true`. Evaluations beyond the budget are still
+    /// recorded, so the budget is only exceeded by the final generation.
+    ///
+    /// Returns a summary of every problem's run.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if another experiment is running at the same time.
+    ///
+    /// [`BbobSolution`]: ./struct.BbobSolution.html
+    /// [`ObservedProblem::is_done`]: ./struct.ObservedProblem.html#method.is_done
+    pub fn run(
+        &self,
+        mut optimize: impl FnMut(&ObservedProblem),
+    ) -> io::Result<Vec<ProblemResult>> {
+        let folder = self.create_folder()?;
+        let mut results = Vec::new();
+        for problem in self.suite.problems() {
+            let budget = self.budget_multiplier * problem.dim();
+            let observed = observe_with(problem, budget, &mut optimize);
+            write_data(&folder, &observed)?;
+            results.push(observed.result());
+        }
+        self.write_info(&folder, &results)?;
+        Ok(results)
+    }
+
+    fn create_folder(&self) -> io::Result<PathBuf> {
+        let mut folder = self.output.join(&self.algorithm);
+        let mut suffix = 0;
+        while folder.exists() {
+            suffix += 1;
+            folder = self
+                .output
+                .join(format!("{}-{:03}", self.algorithm, suffix));
+        }
+        fs::create_dir_all(&folder)?;
+        Ok(folder)
+    }
+
+    fn write_info(&self, folder: &Path, results: &[ProblemResult]) -> io::Result<()> {
+        let mut grouped: BTreeMap<(usize, usize), Vec<&ProblemResult>> = BTreeMap::new();
+        for result in results {
+            grouped
+                .entry((result.function, result.dim))
+                .or_default()
+                .push(result);
+        }
+        for ((function, dim), runs) in grouped {
+            let mut info = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(folder.join(format!("bbobexp_f{}.info", function)))?;
+            writeln!(
+                info,
+                "suite = 'bbob', funcId = {}, DIM = {}, Precision = {:.3e}, algId = '{}', \
+                 coco_version = 'eviolite-{}', logger = 'bbob', data_format = 'bbob-new2'",
+                function,
+                dim,
+                PRECISION,
+                self.algorithm,
+                env!("CARGO_PKG_VERSION")
+            )?;
+            writeln!(info, "% run with eviolite")?;
+            write!(info, "data_f{0}/bbobexp_f{0}_DIM{1}.dat", function, dim)?;
+            for run in runs {
+                write!(
+                    info,
+                    ", {}:{}|{}",
+                    run.instance,
+                    run.evaluations,
+                    c_exp(run.best_delta, 1).trim_start_matches('+')
+                )?;
+            }
+            writeln!(info)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_data(folder: &Path, observed: &ObservedProblem) -> io::Result<()> {
+    let data_folder = folder.join(format!("data_f{}", observed.function()));
+    fs::create_dir_all(&data_folder)?;
+    let state = observed.state.lock().unwrap();
+    let header = format!(
+        "%% f evaluations | g evaluations | best noise-free fitness - Fopt ({}) + sum g_i+ \
+         | measured fitness | best measured fitness or single-digit g-values | x1 | x2...\n",
+        c_exp(observed.fopt(), 12)
+    );
+    for (extension, lines) in [("dat", &state.dat), ("tdat", &state.tdat)] {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(data_folder.join(format!(
+                "bbobexp_f{}_DIM{}.{}",
+                observed.function(),
+                observed.dim(),
+                extension
+            )))?;
+        file.write_all(header.as_bytes())?;
+        file.write_all(lines.as_bytes())?;
+        // the last evaluation is always logged
+        if !lines.ends_with(&state.last_line) {
+            file.write_all(state.last_line.as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{alg, hof, select, Evolution};
+
+    #[test]
+    fn experiment_writes_logs() {
+        assert_eq!(c_exp(0.0015, 3), "+1.500e-03");
+        assert_eq!(c_exp(-250.0, 1), "-2.5e+02");
+
+        let output = std::env::temp_dir().join(format!("eviolite-bbob-{}", std::process::id()));
+        let suite = BbobSuite::new()
+            .with_functions(vec![1, 8])
+            .with_instances(vec![1, 2])
+            .with_dimensions(vec![2]);
+        let results = Experiment::new(suite, &output, "test")
+            .with_budget_multiplier(50)
+            .run(|problem| {
+                let evo: Evolution<BbobSolution, _, _, ()> = Evolution::new(
+                    alg::MuPlusLambda::new(10, 20, 0.6, 0.3, select::Tournament::new(3)),
+                    hof::BestN::new(1),
+                );
+                evo.run_until(|_| problem.is_done());
+                assert!(problem.evaluations() >= problem.budget() || problem.is_solved());
+            })
+            .unwrap();
+        assert_eq!(results.len(), 4);
+
+        let folder = output.join("test");
+        let info = fs::read_to_string(folder.join("bbobexp_f8.info")).unwrap();
+        assert!(info.starts_with("suite = 'bbob', funcId = 8, DIM = 2"));
+        assert!(info.contains("data_f8/bbobexp_f8_DIM2.dat, 1:"));
+        let dat = fs::read_to_string(folder.join("data_f1/bbobexp_f1_DIM2.dat")).unwrap();
+        assert_eq!(dat.lines().filter(|line| line.starts_with('%')).count(), 2);
+        assert!(folder.join("data_f1/bbobexp_f1_DIM2.tdat").exists());
+        fs::remove_dir_all(output).unwrap();
+
+        // problems can also be observed without an experiment
+        let problem = BbobProblem::new(1, 1, 3).unwrap();
+        let xopt = problem.xopt().to_vec();
+        let result = observe(problem, 100, |problem| {
+            assert_eq!(problem.budget(), 100);
+            assert_eq!(problem.best_value(), f64::INFINITY);
+            let solution = BbobSolution::generate();
+            assert_eq!(solution.len(), 3);
+            assert_eq!(-solution.evaluate(), problem.best_value());
+            assert!(!problem.is_done());
+            problem.evaluate(&xopt);
+            assert_eq!(problem.evaluations(), 2);
+            assert!(problem.is_solved() && problem.is_done());
+        });
+        assert_eq!(
+            (
+                result.function,
+                result.instance,
+                result.dim,
+                result.evaluations
+            ),
+            (1, 1, 3, 2)
+        );
+        assert!(result.best_delta.abs() < 1e-9);
+    }
+}
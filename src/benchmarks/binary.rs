@@ -0,0 +1,475 @@
+//! Bit string test functions
+//!
+//! Each test function is a type implementing [`BitFunction`],
+//! and [`BitBenchmark<F, N>`] turns it into a [`Solution`] over a [`BitString`] of `N` bits,
+//! using two-point crossover and bit-flip mutation.
+//! Unlike the other benchmarks, these functions are traditionally maximized,
+//! so their fitness is simply their value.
+//!
+//! - [`OneMax`] counts the bits that are set. It has no local optima,
+//!   which makes it a baseline for how quickly selection pressure alone drives a population to convergence.
+//! - [`Trap<K>`] is made of deceptive blocks of `K` bits, where every block's gradient
+//!   leads away from its optimum. It rewards algorithms that keep diversity and recombine building blocks.
+//! - [`Nk<I>`] evaluates a randomly generated NK landscape[^1], whose ruggedness is tuned by `k`.
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::binary::{BitBenchmark, Trap};
+//!
+//! let evo: Evolution<BitBenchmark<Trap<4>, 40>, _, _, ()> = Evolution::new(
+//!     alg::Simple::new(100, 0.8, 0.2, select::Tournament::new(2)),
+//!     hof::BestN::new(1),
+//! );
+//! let log = evo.run_for(20);
+//! println!("best value: {}", log.hall_of_fame[0].as_ref().value());
+//! ```
+//!
+//! [^1]: Kauffman & Weinberger. "The NK model of rugged fitness landscapes
+//! and its application to maturation of the immune response." 1989.
+//! <https://doi.org/10.1016/S0022-5193(89)80019-0>
+//!
+//! [`BitFunction`]: ./trait.BitFunction.html
+//! [`BitBenchmark<F, N>`]: ./struct.BitBenchmark.html
+//! [`Solution`]: ../../trait.Solution.html
+//! [`BitString`]: ../../genome/struct.BitString.html
+//! [`OneMax`]: ./struct.OneMax.html
+//! [`Trap<K>`]: ./struct.Trap.html
+//! [`Nk<I>`]: ./struct.Nk.html
+
+use std::{fmt::Debug, marker::PhantomData, ops::Deref};
+
+use rand::{seq::index, Rng};
+
+use crate::{
+    genome::{bits, BitString},
+    repro_rng::thread_rng,
+    Solution,
+};
+
+/// A function of a bit string to be maximized
+pub trait BitFunction {
+    /// The name of the function.
+    const NAME: &'static str;
+
+    /// Compute the value of the function for `bits`.
+    fn value(bits: &[bool]) -> f64;
+
+    /// Get the highest possible value for bit strings of length `len`, if it is known.
+    fn max_value(len: usize) -> Option<f64>;
+}
+
+/// The OneMax function, which counts the bits that are set
+#[derive(Clone, Copy, Debug)]
+pub struct OneMax;
+
+impl BitFunction for OneMax {
+    const NAME: &'static str = "onemax";
+
+    fn value(bits: &[bool]) -> f64 {
+        bits.iter().filter(|&&bit| bit).count() as f64
+    }
+
+    fn max_value(len: usize) -> Option<f64> {
+        Some(len as f64)
+    }
+}
+
+/// Concatenated deceptive trap functions of order `K`
+///
+/// The bit string is split into consecutive blocks of `K` bits.
+/// A block with `u` bits set is worth `K` if `u = K`, and `K - 1 - u` otherwise,
+/// so every block's best local move is toward all zeros while its optimum is all ones.
+/// If the length isn't a multiple of `K`, the last block is a smaller trap made of the remaining bits.
+///
+/// Panics
+/// ======
+/// Evaluating a `Trap<0>` panics.
+#[derive(Clone, Copy, Debug)]
+pub struct Trap<const K: usize>;
+
+impl<const K: usize> BitFunction for Trap<K> {
+    const NAME: &'static str = "trap";
+
+    fn value(bits: &[bool]) -> f64 {
+        assert!(K > 0, "trap order must be positive");
+        bits.chunks(K)
+            .map(|block| {
+                let ones = block.iter().filter(|&&bit| bit).count();
+                if ones == block.len() {
+                    ones as f64
+                } else {
+                    (block.len() - 1 - ones) as f64
+                }
+            })
+            .sum()
+    }
+
+    fn max_value(len: usize) -> Option<f64> {
+        Some(len as f64)
+    }
+}
+
+/// How each bit's neighbors are chosen in an [`NkLandscape`]
+///
+/// [`NkLandscape`]: ./struct.NkLandscape.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Each bit interacts with the `k` bits after it, wrapping around at the end.
+    Adjacent,
+    /// Each bit interacts with `k` other bits chosen at random.
+    Random,
+}
+
+/// A randomly generated NK landscape
+///
+/// Each of the `n` bits contributes a value that depends on its own state and the states of
+/// `k` other bits, looked up in a random table drawn uniformly from `[0, 1)`.
+/// The value of a bit string is the mean of all contributions.
+/// With `k = 0` the landscape is smooth and separable,
+/// and as `k` approaches `n - 1` it becomes fully random.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NkLandscape {
+    k: usize,
+    neighbors: Vec<Vec<usize>>,
+    tables: Vec<Vec<f64>>,
+}
+
+impl NkLandscape {
+    /// Generate a landscape over `n` bits where each bit interacts with `k` others.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `k` is not less than `n`, or if `k` is so large that the tables can't be stored.
+    pub fn random(n: usize, k: usize, neighborhood: Neighborhood) -> Self {
+        assert!(k < n, "k must be less than n");
+        assert!(k < usize::BITS as usize - 1, "k is too large");
+        let mut rng = thread_rng();
+        let neighbors = (0..n)
+            .map(|i| match neighborhood {
+                Neighborhood::Adjacent => (1..=k).map(|j| (i + j) % n).collect(),
+                Neighborhood::Random => index::sample(&mut rng, n - 1, k)
+                    .into_iter()
+                    .map(|j| if j >= i { j + 1 } else { j })
+                    .collect(),
+            })
+            .collect();
+        let tables = (0..n)
+            .map(|_| (0..1usize << (k + 1)).map(|_| rng.gen()).collect())
+            .collect();
+        NkLandscape {
+            k,
+            neighbors,
+            tables,
+        }
+    }
+
+    /// Get the number of bits.
+    pub fn n(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    /// Get the number of other bits each bit interacts with.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Get the bits that each bit interacts with.
+    pub fn neighbors(&self) -> &[Vec<usize>] {
+        &self.neighbors
+    }
+
+    /// Compute the value of the landscape for `bits`, between 0 and 1.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `bits` doesn't have exactly `n` elements.
+    pub fn value(&self, bits: &[bool]) -> f64 {
+        assert_eq!(bits.len(), self.n(), "bit string has the wrong length");
+        let total: f64 = self
+            .neighbors
+            .iter()
+            .zip(self.tables.iter())
+            .enumerate()
+            .map(|(i, (neighbors, table))| {
+                let idx = neighbors
+                    .iter()
+                    .fold(bits[i] as usize, |idx, &j| (idx << 1) | bits[j] as usize);
+                table[idx]
+            })
+            .sum();
+        total / self.n().max(1) as f64
+    }
+}
+
+/// A type that provides an [`NkLandscape`] for [`Nk`]
+///
+/// [`NkLandscape`]: ./struct.NkLandscape.html
+/// [`Nk`]: ./struct.Nk.html
+pub trait NkInstance: 'static {
+    /// Get the landscape.
+    ///
+    /// This usually returns a reference to a lazily-initialized `static`.
+    fn landscape() -> &'static NkLandscape;
+}
+
+/// The value of `I`'s [`NkLandscape`]
+///
+/// ```
+/// use std::sync::OnceLock;
+/// use eviolite::benchmarks::binary::*;
+///
+/// struct Rugged;
+///
+/// impl NkInstance for Rugged {
+///     fn landscape() -> &'static NkLandscape {
+///         static LANDSCAPE: OnceLock<NkLandscape> = OnceLock::new();
+///         LANDSCAPE.get_or_init(|| NkLandscape::random(32, 4, Neighborhood::Random))
+///     }
+/// }
+///
+/// type Problem = BitBenchmark<Nk<Rugged>, 32>;
+/// ```
+///
+/// [`NkLandscape`]: ./struct.NkLandscape.html
+pub struct Nk<I>(PhantomData<fn() -> I>);
+
+impl<I: NkInstance> BitFunction for Nk<I> {
+    const NAME: &'static str = "nk";
+
+    fn value(bits: &[bool]) -> f64 {
+        I::landscape().value(bits)
+    }
+
+    fn max_value(_: usize) -> Option<f64> {
+        None
+    }
+}
+
+/// A [`Solution`] that maximizes the bit string function `F` with `N` bits
+///
+/// Solutions are generated uniformly at random, crossed over with [two-point crossover],
+/// and mutated with [bit-flip mutation] with a probability of 1/`N` for each bit.
+/// Fitness is the value of the function.
+/// This type derefs to the [`BitString`] it contains.
+///
+/// [`Solution`]: ../../trait.Solution.html
+/// [two-point crossover]: ../../genome/bits/fn.two_point.html
+/// [bit-flip mutation]: ../../genome/bits/fn.flip_each.html
+/// [`BitString`]: ../../genome/struct.BitString.html
+pub struct BitBenchmark<F, const N: usize> {
+    bits: BitString,
+    _phantom: PhantomData<fn() -> F>,
+}
+
+impl<F: BitFunction, const N: usize> BitBenchmark<F, N> {
+    /// Wrap an existing bit string.
+    ///
+    /// Returns `None` if `bits` doesn't have exactly `N` elements.
+    pub fn new(bits: BitString) -> Option<Self> {
+        (bits.len() == N).then_some(BitBenchmark {
+            bits,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Get the value of the function for this solution.
+    pub fn value(&self) -> f64 {
+        F::value(&self.bits)
+    }
+
+    /// Check whether this solution has the highest possible value, if that is known.
+    pub fn is_optimal(&self) -> Option<bool> {
+        F::max_value(N).map(|max| self.value() >= max)
+    }
+
+    /// Consume the solution, returning the bit string it contained.
+    pub fn into_inner(self) -> BitString {
+        self.bits
+    }
+}
+
+impl<F: BitFunction, const N: usize> Solution for BitBenchmark<F, N> {
+    type Fitness = f64;
+
+    fn generate() -> Self {
+        BitBenchmark {
+            bits: BitString::random(N),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn evaluate(&self) -> f64 {
+        self.value()
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        bits::two_point(&mut a.bits, &mut b.bits);
+    }
+
+    fn mutate(&mut self) {
+        bits::flip_each(&mut self.bits, 1.0 / N.max(1) as f64);
+    }
+}
+
+impl<F, const N: usize> Clone for BitBenchmark<F, N> {
+    fn clone(&self) -> Self {
+        BitBenchmark {
+            bits: self.bits.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, const N: usize> Deref for BitBenchmark<F, N> {
+    type Target = BitString;
+    fn deref(&self) -> &BitString {
+        &self.bits
+    }
+}
+
+impl<F, const N: usize> AsRef<BitString> for BitBenchmark<F, N> {
+    fn as_ref(&self) -> &BitString {
+        &self.bits
+    }
+}
+
+impl<F: BitFunction, const N: usize> Debug for BitBenchmark<F, N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bits: String = self
+            .bits
+            .iter()
+            .map(|&b| if b { '1' } else { '0' })
+            .collect();
+        f.debug_tuple(F::NAME).field(&bits).finish()
+    }
+}
+
+impl<F, const N: usize> PartialEq for BitBenchmark<F, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bits == other.bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use super::*;
+    use crate::{alg, hof, select, Evolution};
+
+    #[test]
+    fn values() {
+        let ones = vec![true; 10];
+        let zeros = vec![false; 10];
+        assert_eq!(OneMax::value(&ones), 10.0);
+        assert_eq!(Trap::<5>::value(&ones), 10.0);
+        assert_eq!(Trap::<5>::value(&zeros), 8.0);
+        assert_eq!(Trap::<4>::value(&zeros), 3.0 + 3.0 + 1.0);
+        let mut one_off = ones.clone();
+        one_off[0] = false;
+        assert_eq!(Trap::<5>::value(&one_off), 5.0);
+
+        for neighborhood in [Neighborhood::Adjacent, Neighborhood::Random] {
+            let nk = NkLandscape::random(12, 3, neighborhood);
+            for (i, neighbors) in nk.neighbors().iter().enumerate() {
+                assert_eq!(neighbors.len(), 3);
+                assert!(neighbors.iter().all(|&j| j != i && j < 12));
+            }
+            let value = nk.value(&BitString::random(12));
+            assert!((0.0..1.0).contains(&value));
+        }
+
+        let bench = BitBenchmark::<OneMax, 4>::new(BitString::new(vec![true; 4])).unwrap();
+        assert_eq!(bench.is_optimal(), Some(true));
+        assert!(BitBenchmark::<OneMax, 4>::new(BitString::zeros(3)).is_none());
+    }
+
+    #[test]
+    fn traps() {
+        let bits = |s: &str| s.chars().map(|c| c == '1').collect::<Vec<_>>();
+        // blocks of 3, with a smaller trap of 1 bit at the end
+        assert_eq!(Trap::<3>::value(&bits("1111111")), 7.0);
+        assert_eq!(Trap::<3>::value(&bits("1100010")), 0.0 + 1.0 + 0.0);
+        assert_eq!(Trap::<3>::value(&bits("0001110")), 2.0 + 3.0 + 0.0);
+        assert_eq!(Trap::<3>::max_value(7), Some(7.0));
+        assert_eq!(
+            Trap::<1>::value(&bits("0110")),
+            OneMax::value(&bits("0110"))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_trap() {
+        Trap::<0>::value(&[true]);
+    }
+
+    #[test]
+    fn nk_landscapes() {
+        let nk = NkLandscape::random(6, 2, Neighborhood::Adjacent);
+        assert_eq!((nk.n(), nk.k()), (6, 2));
+        assert_eq!(nk.neighbors()[0], [1, 2]);
+        assert_eq!(nk.neighbors()[5], [0, 1]);
+        // each bit looks up its own state followed by its neighbors' in its table
+        let bits = [true, false, true, true, false, false];
+        let expected = [0b101, 0b011, 0b110, 0b100, 0b001, 0b010]
+            .iter()
+            .enumerate()
+            .map(|(i, &idx)| nk.tables[i][idx])
+            .sum::<f64>()
+            / 6.0;
+        assert!((nk.value(&bits) - expected).abs() < 1e-12);
+
+        // with k = 0, flipping a bit only changes its own contribution
+        let smooth = NkLandscape::random(5, 0, Neighborhood::Random);
+        assert!(smooth.neighbors().iter().all(Vec::is_empty));
+        let mut bits = [false; 5];
+        let before = smooth.value(&bits);
+        bits[3] = true;
+        let change = (smooth.tables[3][1] - smooth.tables[3][0]) / 5.0;
+        assert!((smooth.value(&bits) - before - change).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nk_needs_k_below_n() {
+        NkLandscape::random(4, 4, Neighborhood::Adjacent);
+    }
+
+    struct Landscape;
+
+    impl NkInstance for Landscape {
+        fn landscape() -> &'static NkLandscape {
+            static LANDSCAPE: OnceLock<NkLandscape> = OnceLock::new();
+            LANDSCAPE.get_or_init(|| NkLandscape::random(16, 3, Neighborhood::Random))
+        }
+    }
+
+    #[test]
+    fn benchmarks() {
+        let nk = BitBenchmark::<Nk<Landscape>, 16>::generate();
+        assert_eq!(nk.value(), Landscape::landscape().value(&nk));
+        assert_eq!(nk.is_optimal(), None);
+
+        for _ in 0..20 {
+            let mut a = BitBenchmark::<OneMax, 12>::generate();
+            let mut b = BitBenchmark::<OneMax, 12>::generate();
+            let ones = a.value() + b.value();
+            BitBenchmark::crossover(&mut a, &mut b);
+            // two-point crossover only moves bits between the parents
+            assert_eq!(a.value() + b.value(), ones);
+            a.mutate();
+            assert_eq!((a.len(), b.len()), (12, 12));
+            assert_eq!(a.evaluate(), a.value());
+        }
+
+        let evo: Evolution<BitBenchmark<OneMax, 20>, _, _, ()> = Evolution::new(
+            alg::MuPlusLambda::new(20, 40, 0.6, 0.4, select::Tournament::new(3)),
+            hof::BestN::new(1),
+        );
+        let log = evo.run_until(|gen| {
+            gen.hall_of_fame[0].as_ref().is_optimal() == Some(true) || gen.gen >= 200
+        });
+        assert_eq!(log.hall_of_fame[0].as_ref().is_optimal(), Some(true));
+    }
+}
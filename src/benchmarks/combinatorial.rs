@@ -0,0 +1,928 @@
+//! Combinatorial test problems
+//!
+//! This module contains the 0/1 knapsack problem, solved with a [`BitString`],
+//! and the traveling salesman problem, solved with a [`Permutation`].
+//! Unlike the continuous benchmarks, these problems are defined by instance data,
+//! which can be built directly or parsed from a standard file format:
+//! [`Tsp::parse_tsplib`] reads TSPLIB[^1] files, and [`Knapsack::parse`] reads
+//! the plain format used by most published knapsack instances.
+//!
+//! Since a [`Solution`] can't carry a reference to its instance, an instance is made available
+//! by implementing [`TspInstance`] or [`KnapsackInstance`] on a marker type, usually by
+//! parsing it into a `static`. [`TspTour`] and [`KnapsackPacking`] are then ready-made solution types
+//! built on [`Individual`], which use the genome operators stored in the instance.
+//!
+//! ```
+//! use std::sync::OnceLock;
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::combinatorial::{Tsp, TspInstance, TspTour};
+//!
+//! const SQUARE: &str = "
+//! NAME: square
+//! TYPE: TSP
+//! DIMENSION: 4
+//! EDGE_WEIGHT_TYPE: EUC_2D
+//! NODE_COORD_SECTION
+//! 1 0 0
+//! 2 0 10
+//! 3 10 0
+//! 4 10 10
+//! EOF
+//! ";
+//!
+//! struct Square;
+//!
+//! impl TspInstance for Square {
+//!     fn tsp() -> &'static Tsp {
+//!         static TSP: OnceLock<Tsp> = OnceLock::new();
+//!         TSP.get_or_init(|| Tsp::parse_tsplib(SQUARE).unwrap())
+//!     }
+//! }
+//!
+//! let evo: Evolution<TspTour<Square>, _, _, ()> = Evolution::new(
+//!     alg::MuPlusLambda::new(20, 40, 0.5, 0.5, select::Tournament::new(3)),
+//!     hof::BestN::new(1),
+//! );
+//! let log = evo.run_for(20);
+//! assert_eq!(Square::tsp().tour_length(log.hall_of_fame[0].as_ref()), 40.0);
+//! ```
+//!
+//! [^1]: Reinelt. "TSPLIB—A Traveling Salesman Problem Library." 1991.
+//! <https://doi.org/10.1287/ijoc.3.4.376>
+//!
+//! [`BitString`]: ../../genome/struct.BitString.html
+//! [`Permutation`]: ../../genome/struct.Permutation.html
+//! [`Tsp::parse_tsplib`]: ./struct.Tsp.html#method.parse_tsplib
+//! [`Knapsack::parse`]: ./struct.Knapsack.html#method.parse
+//! [`Solution`]: ../../trait.Solution.html
+//! [`TspInstance`]: ./trait.TspInstance.html
+//! [`KnapsackInstance`]: ./trait.KnapsackInstance.html
+//! [`TspTour`]: ./type.TspTour.html
+//! [`KnapsackPacking`]: ./type.KnapsackPacking.html
+//! [`Individual`]: ../../genome/struct.Individual.html
+
+use std::{collections::HashMap, fmt, marker::PhantomData};
+
+use crate::genome::{
+    bits::{BitCrossover, BitMutation, BitStringConfig},
+    permutation::{PermutationConfig, PermutationCrossover, PermutationMutation},
+    BitString, Individual, Permutation, Problem,
+};
+
+/// An error encountered while parsing a problem instance
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line the error was found on, counting from 1, or 0 if it isn't tied to a line.
+    pub line: usize,
+    /// A description of the error.
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Split the input into whitespace-separated tokens, each with the line it appeared on.
+fn tokens(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .flat_map(|(i, line)| line.split_whitespace().map(move |token| (i + 1, token)))
+}
+
+fn parse_number<T: std::str::FromStr>(
+    token: Option<(usize, &str)>,
+    what: &str,
+) -> Result<T, ParseError> {
+    let (line, token) = token.ok_or_else(|| ParseError::new(0, format!("missing {}", what)))?;
+    token
+        .parse()
+        .map_err(|_| ParseError::new(line, format!("invalid {} `{}`", what, token)))
+}
+
+/// An instance of the 0/1 knapsack problem
+///
+/// The goal is to choose a subset of items with the largest total value
+/// whose total weight doesn't exceed the capacity.
+/// Solutions are [`BitString`]s with one bit for each item.
+/// By default, they use [two-point crossover] and [bit-flip mutation] with a probability of
+/// 1/`n` for each bit; this can be changed with [`.with_operators()`].
+///
+/// [`BitString`]: ../../genome/struct.BitString.html
+/// [two-point crossover]: ../../genome/bits/fn.two_point.html
+/// [bit-flip mutation]: ../../genome/bits/fn.flip_each.html
+/// [`.with_operators()`]: ./struct.Knapsack.html#method.with_operators
+#[derive(Clone, Debug, PartialEq)]
+pub struct Knapsack {
+    values: Vec<f64>,
+    weights: Vec<f64>,
+    capacity: f64,
+    config: BitStringConfig,
+}
+
+impl Knapsack {
+    /// Create an instance from the value and weight of each item, and the capacity of the knapsack.
+    ///
+    /// Returns `None` if `values` and `weights` have different lengths,
+    /// or if any value, weight, or the capacity is negative or not finite.
+    pub fn new(values: Vec<f64>, weights: Vec<f64>, capacity: f64) -> Option<Self> {
+        let valid = |x: &f64| x.is_finite() && *x >= 0.0;
+        if values.len() != weights.len()
+            || !values.iter().chain(weights.iter()).all(valid)
+            || !valid(&capacity)
+        {
+            return None;
+        }
+        let n = values.len();
+        Some(Knapsack {
+            values,
+            weights,
+            capacity,
+            config: BitStringConfig {
+                len: n,
+                crossover: BitCrossover::TwoPoint,
+                mutation: BitMutation::FlipEach {
+                    indpb: 1.0 / n.max(1) as f64,
+                },
+            },
+        })
+    }
+
+    /// Parse an instance from text.
+    ///
+    /// The text starts with the number of items and the capacity,
+    /// followed by the value and weight of each item, all separated by whitespace.
+    /// This is the format of the instances from the OR-Library and Pisinger's collection.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut tokens = tokens(input);
+        let n: usize = parse_number(tokens.next(), "number of items")?;
+        let capacity = parse_number(tokens.next(), "capacity")?;
+        let mut values = Vec::with_capacity(n);
+        let mut weights = Vec::with_capacity(n);
+        for _ in 0..n {
+            values.push(parse_number(tokens.next(), "item value")?);
+            weights.push(parse_number(tokens.next(), "item weight")?);
+        }
+        if let Some((line, token)) = tokens.next() {
+            return Err(ParseError::new(
+                line,
+                format!("unexpected `{}` after the last item", token),
+            ));
+        }
+        Knapsack::new(values, weights, capacity)
+            .ok_or_else(|| ParseError::new(0, "values, weights, and capacity must not be negative"))
+    }
+
+    /// Use different operators for crossover and mutation.
+    pub fn with_operators(mut self, crossover: BitCrossover, mutation: BitMutation) -> Self {
+        self.config.crossover = crossover;
+        self.config.mutation = mutation;
+        self
+    }
+
+    /// Get the number of items.
+    pub fn n_items(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Get the value of each item.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Get the weight of each item.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Get the capacity of the knapsack.
+    pub fn capacity(&self) -> f64 {
+        self.capacity
+    }
+
+    /// Get the configuration used to generate and vary solutions.
+    pub fn config(&self) -> &BitStringConfig {
+        &self.config
+    }
+
+    /// Get the total value of the chosen items.
+    pub fn total_value(&self, chosen: &[bool]) -> f64 {
+        self.total(&self.values, chosen)
+    }
+
+    /// Get the total weight of the chosen items.
+    pub fn total_weight(&self, chosen: &[bool]) -> f64 {
+        self.total(&self.weights, chosen)
+    }
+
+    fn total(&self, of: &[f64], chosen: &[bool]) -> f64 {
+        of.iter()
+            .zip(chosen)
+            .filter(|(_, &chosen)| chosen)
+            .map(|(x, _)| x)
+            .sum()
+    }
+
+    /// Compute the fitness of a choice of items.
+    ///
+    /// This is the total value of the items if they fit in the knapsack.
+    /// Otherwise, it is the negated amount by which they exceed the capacity,
+    /// so every feasible choice is better than every infeasible one.
+    pub fn fitness(&self, chosen: &[bool]) -> f64 {
+        let weight = self.total_weight(chosen);
+        if weight <= self.capacity {
+            self.total_value(chosen)
+        } else {
+            self.capacity - weight
+        }
+    }
+
+    /// Make a choice of items feasible by removing items until they fit in the knapsack,
+    /// starting with the lowest ratio of value to weight.
+    pub fn repair(&self, chosen: &mut BitString) {
+        let mut weight = self.total_weight(chosen);
+        if weight <= self.capacity {
+            return;
+        }
+        let mut order: Vec<usize> = (0..chosen.len().min(self.n_items()))
+            .filter(|&i| chosen[i])
+            .collect();
+        order.sort_by(|&i, &j| {
+            let ratio = |i: usize| self.values[i] / self.weights[i];
+            ratio(i).total_cmp(&ratio(j))
+        });
+        for i in order {
+            if weight <= self.capacity {
+                break;
+            }
+            chosen[i] = false;
+            weight -= self.weights[i];
+        }
+    }
+}
+
+/// A traveling salesman problem instance
+///
+/// The goal is to find the shortest tour that visits every city exactly once
+/// and returns to where it started.
+/// Solutions are [`Permutation`]s giving the order in which cities are visited.
+/// By default, they use [ordered crossover] and [inversion mutation];
+/// this can be changed with [`.with_operators()`].
+///
+/// [`Permutation`]: ../../genome/struct.Permutation.html
+/// [ordered crossover]: ../../genome/permutation/fn.ordered.html
+/// [inversion mutation]: ../../genome/permutation/fn.inversion.html
+/// [`.with_operators()`]: ./struct.Tsp.html#method.with_operators
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tsp {
+    name: String,
+    n_cities: usize,
+    distances: Vec<f64>,
+    coords: Option<Vec<(f64, f64)>>,
+    config: PermutationConfig,
+}
+
+impl Tsp {
+    fn from_parts(
+        name: String,
+        n_cities: usize,
+        distances: Vec<f64>,
+        coords: Option<Vec<(f64, f64)>>,
+    ) -> Self {
+        Tsp {
+            name,
+            n_cities,
+            distances,
+            coords,
+            config: PermutationConfig {
+                len: n_cities,
+                crossover: PermutationCrossover::Ordered,
+                mutation: PermutationMutation::Inversion,
+            },
+        }
+    }
+
+    /// Create an instance from a matrix of distances, where `matrix[i][j]`
+    /// is the distance from city `i` to city `j`. The matrix doesn't need to be symmetric.
+    ///
+    /// Returns `None` if the matrix isn't square.
+    pub fn from_matrix(matrix: Vec<Vec<f64>>) -> Option<Self> {
+        let n = matrix.len();
+        if matrix.iter().any(|row| row.len() != n) {
+            return None;
+        }
+        Some(Tsp::from_parts(
+            String::new(),
+            n,
+            matrix.into_iter().flatten().collect(),
+            None,
+        ))
+    }
+
+    /// Create an instance from the coordinates of each city,
+    /// using the exact Euclidean distance between them.
+    pub fn euclidean(coords: Vec<(f64, f64)>) -> Self {
+        let n = coords.len();
+        let distances = coords
+            .iter()
+            .flat_map(|&(x1, y1)| coords.iter().map(move |&(x2, y2)| (x1 - x2).hypot(y1 - y2)))
+            .collect();
+        Tsp::from_parts(String::new(), n, distances, Some(coords))
+    }
+
+    /// Parse an instance in the TSPLIB format.
+    ///
+    /// Both symmetric (`TSP`) and asymmetric (`ATSP`) instances are supported.
+    /// Distances are computed using the rounding rules of the TSPLIB specification,
+    /// so tour lengths can be compared against published optima.
+    /// The supported edge weight types are `EUC_2D`, `CEIL_2D`, `MAN_2D`, `MAX_2D`, `ATT`, `GEO`,
+    /// and `EXPLICIT` with any of the `FULL_MATRIX`, `*_ROW`, and `*_COL` formats.
+    pub fn parse_tsplib(input: &str) -> Result<Self, ParseError> {
+        let mut spec = HashMap::new();
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()));
+        let mut coords = None;
+        let mut weights = None;
+
+        while let Some((line_no, line)) = lines.next() {
+            if line.is_empty() {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                spec.insert(key.trim().to_uppercase(), value.trim().to_string());
+                continue;
+            }
+            let n: usize = match spec.get("DIMENSION") {
+                Some(dim) => dim
+                    .parse()
+                    .map_err(|_| ParseError::new(line_no, "invalid DIMENSION"))?,
+                None if line == "EOF" => break,
+                None => {
+                    return Err(ParseError::new(
+                        line_no,
+                        "DIMENSION must come before any section",
+                    ))
+                }
+            };
+            match line {
+                "NODE_COORD_SECTION" => {
+                    let mut section = vec![None; n];
+                    for _ in 0..n {
+                        let (line_no, line) = lines
+                            .next()
+                            .ok_or_else(|| ParseError::new(0, "NODE_COORD_SECTION ended early"))?;
+                        let mut tokens = line.split_whitespace().map(|t| (line_no, t));
+                        let id: usize = parse_number(tokens.next(), "node number")?;
+                        let x = parse_number(tokens.next(), "coordinate")?;
+                        let y = parse_number(tokens.next(), "coordinate")?;
+                        match section.get_mut(id.wrapping_sub(1)) {
+                            Some(slot @ None) => *slot = Some((x, y)),
+                            _ => {
+                                return Err(ParseError::new(
+                                    line_no,
+                                    format!("invalid node number {}", id),
+                                ))
+                            }
+                        }
+                    }
+                    coords = Some(section.into_iter().map(Option::unwrap).collect::<Vec<_>>());
+                }
+                "EDGE_WEIGHT_SECTION" => {
+                    let format = spec
+                        .get("EDGE_WEIGHT_FORMAT")
+                        .map(String::as_str)
+                        .unwrap_or("FULL_MATRIX");
+                    let count = match format {
+                        "FULL_MATRIX" => n * n,
+                        "UPPER_ROW" | "LOWER_ROW" | "UPPER_COL" | "LOWER_COL" => {
+                            n * n.saturating_sub(1) / 2
+                        }
+                        "UPPER_DIAG_ROW" | "LOWER_DIAG_ROW" | "UPPER_DIAG_COL"
+                        | "LOWER_DIAG_COL" => n * (n + 1) / 2,
+                        _ => {
+                            return Err(ParseError::new(
+                                line_no,
+                                format!("unsupported EDGE_WEIGHT_FORMAT `{}`", format),
+                            ))
+                        }
+                    };
+                    let mut values = Vec::with_capacity(count);
+                    while values.len() < count {
+                        let (line_no, line) = lines
+                            .next()
+                            .ok_or_else(|| ParseError::new(0, "EDGE_WEIGHT_SECTION ended early"))?;
+                        for token in line.split_whitespace() {
+                            values.push(parse_number(Some((line_no, token)), "edge weight")?);
+                        }
+                    }
+                    if values.len() > count {
+                        return Err(ParseError::new(
+                            line_no,
+                            "EDGE_WEIGHT_SECTION has too many values",
+                        ));
+                    }
+                    weights = Some(explicit_matrix(format, n, &values));
+                }
+                "EOF" => break,
+                _ if line.ends_with("_SECTION") => {
+                    // other sections, like DISPLAY_DATA_SECTION, have one line per node
+                    for _ in 0..n {
+                        lines.next();
+                    }
+                }
+                _ => return Err(ParseError::new(line_no, format!("unexpected `{}`", line))),
+            }
+        }
+
+        match spec.get("TYPE").map(String::as_str) {
+            Some("TSP" | "ATSP") | None => {}
+            Some(other) => return Err(ParseError::new(0, format!("unsupported TYPE `{}`", other))),
+        }
+        let n: usize = spec
+            .get("DIMENSION")
+            .ok_or_else(|| ParseError::new(0, "missing DIMENSION"))?
+            .parse()
+            .map_err(|_| ParseError::new(0, "invalid DIMENSION"))?;
+        let name = spec.remove("NAME").unwrap_or_default();
+        let weight_type = spec
+            .get("EDGE_WEIGHT_TYPE")
+            .ok_or_else(|| ParseError::new(0, "missing EDGE_WEIGHT_TYPE"))?;
+
+        if weight_type == "EXPLICIT" {
+            let distances =
+                weights.ok_or_else(|| ParseError::new(0, "missing EDGE_WEIGHT_SECTION"))?;
+            return Ok(Tsp::from_parts(name, n, distances, coords));
+        }
+        let distance: fn((f64, f64), (f64, f64)) -> f64 = match weight_type.as_str() {
+            "EUC_2D" => |a, b| (a.0 - b.0).hypot(a.1 - b.1).round(),
+            "CEIL_2D" => |a, b| (a.0 - b.0).hypot(a.1 - b.1).ceil(),
+            "MAN_2D" => |a, b| ((a.0 - b.0).abs() + (a.1 - b.1).abs()).round(),
+            "MAX_2D" => |a, b| (a.0 - b.0).abs().round().max((a.1 - b.1).abs().round()),
+            "ATT" => att_distance,
+            "GEO" => geo_distance,
+            other => {
+                return Err(ParseError::new(
+                    0,
+                    format!("unsupported EDGE_WEIGHT_TYPE `{}`", other),
+                ))
+            }
+        };
+        let coords = coords.ok_or_else(|| ParseError::new(0, "missing NODE_COORD_SECTION"))?;
+        let distances = coords
+            .iter()
+            .flat_map(|&a| coords.iter().map(move |&b| distance(a, b)))
+            .collect();
+        Ok(Tsp::from_parts(name, n, distances, Some(coords)))
+    }
+
+    /// Use different operators for crossover and mutation.
+    pub fn with_operators(
+        mut self,
+        crossover: PermutationCrossover,
+        mutation: PermutationMutation,
+    ) -> Self {
+        self.config.crossover = crossover;
+        self.config.mutation = mutation;
+        self
+    }
+
+    /// Get the name of the instance, or an empty string if it doesn't have one.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the number of cities.
+    pub fn n_cities(&self) -> usize {
+        self.n_cities
+    }
+
+    /// Get the coordinates of each city, if the instance has them.
+    pub fn coords(&self) -> Option<&[(f64, f64)]> {
+        self.coords.as_deref()
+    }
+
+    /// Get the configuration used to generate and vary solutions.
+    pub fn config(&self) -> &PermutationConfig {
+        &self.config
+    }
+
+    /// Get the distance from city `from` to city `to`.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if either city is out of bounds.
+    pub fn distance(&self, from: usize, to: usize) -> f64 {
+        assert!(
+            from < self.n_cities && to < self.n_cities,
+            "city out of bounds"
+        );
+        self.distances[from * self.n_cities + to]
+    }
+
+    /// Get the length of a tour that visits the cities in the given order and then returns to the first one.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if any city is out of bounds.
+    pub fn tour_length(&self, tour: &[usize]) -> f64 {
+        match (tour.first(), tour.last()) {
+            (Some(&first), Some(&last)) => {
+                tour.windows(2)
+                    .map(|w| self.distance(w[0], w[1]))
+                    .sum::<f64>()
+                    + self.distance(last, first)
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+// Expand the values of an EDGE_WEIGHT_SECTION into a full matrix.
+fn explicit_matrix(format: &str, n: usize, values: &[f64]) -> Vec<f64> {
+    if format == "FULL_MATRIX" {
+        return values.to_vec();
+    }
+    // a column-wise upper triangle is the same as a row-wise lower triangle, and vice versa
+    let (upper, diagonal) = match format {
+        "UPPER_ROW" | "LOWER_COL" => (true, false),
+        "LOWER_ROW" | "UPPER_COL" => (false, false),
+        "UPPER_DIAG_ROW" | "LOWER_DIAG_COL" => (true, true),
+        _ => (false, true),
+    };
+    let mut matrix = vec![0.0; n * n];
+    let mut values = values.iter();
+    for i in 0..n {
+        let cols = if upper {
+            (if diagonal { i } else { i + 1 })..n
+        } else {
+            0..(if diagonal { i + 1 } else { i })
+        };
+        for j in cols {
+            let value = *values.next().unwrap();
+            matrix[i * n + j] = value;
+            matrix[j * n + i] = value;
+        }
+    }
+    matrix
+}
+
+// The pseudo-Euclidean distance used by the att48 and att532 instances.
+fn att_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let r = (((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)) / 10.0).sqrt();
+    let t = r.round();
+    if t < r {
+        t + 1.0
+    } else {
+        t
+    }
+}
+
+// The geographical distance, where coordinates are latitude and longitude in DDD.MM format.
+fn geo_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    // TSPLIB uses this approximation of pi
+    #[allow(clippy::approx_constant)]
+    const PI_APPROX: f64 = 3.141592;
+    const RADIUS: f64 = 6378.388;
+    let radians = |x: f64| {
+        let degrees = x.trunc();
+        PI_APPROX * (degrees + 5.0 * (x - degrees) / 3.0) / 180.0
+    };
+    let (lat_a, lon_a, lat_b, lon_b) = (radians(a.0), radians(a.1), radians(b.0), radians(b.1));
+    let q1 = (lon_a - lon_b).cos();
+    let q2 = (lat_a - lat_b).cos();
+    let q3 = (lat_a + lat_b).cos();
+    (RADIUS * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0).trunc()
+}
+
+/// A type that provides a [`Tsp`] instance for [`TspTour`]
+///
+/// [`Tsp`]: ./struct.Tsp.html
+/// [`TspTour`]: ./type.TspTour.html
+pub trait TspInstance: 'static {
+    /// Get the instance.
+    ///
+    /// This usually returns a reference to a lazily-initialized `static`.
+    fn tsp() -> &'static Tsp;
+}
+
+/// A type that provides a [`Knapsack`] instance for [`KnapsackPacking`]
+///
+/// [`Knapsack`]: ./struct.Knapsack.html
+/// [`KnapsackPacking`]: ./type.KnapsackPacking.html
+pub trait KnapsackInstance: 'static {
+    /// Get the instance.
+    ///
+    /// This usually returns a reference to a lazily-initialized `static`.
+    fn knapsack() -> &'static Knapsack;
+}
+
+/// The [`Problem`] of finding the shortest tour of `I`'s [`Tsp`] instance
+///
+/// Fitness is the negated tour length.
+///
+/// [`Problem`]: ../../genome/trait.Problem.html
+/// [`Tsp`]: ./struct.Tsp.html
+pub struct TspProblem<I>(PhantomData<fn() -> I>);
+
+impl<I: TspInstance> Problem for TspProblem<I> {
+    type Genome = Permutation;
+    type Fitness = f64;
+
+    fn config() -> &'static PermutationConfig {
+        &I::tsp().config
+    }
+
+    fn evaluate(tour: &Permutation) -> f64 {
+        -I::tsp().tour_length(tour)
+    }
+}
+
+/// The [`Problem`] of packing `I`'s [`Knapsack`] instance
+///
+/// Fitness is given by [`Knapsack::fitness`].
+///
+/// [`Problem`]: ../../genome/trait.Problem.html
+/// [`Knapsack`]: ./struct.Knapsack.html
+/// [`Knapsack::fitness`]: ./struct.Knapsack.html#method.fitness
+pub struct KnapsackProblem<I>(PhantomData<fn() -> I>);
+
+impl<I: KnapsackInstance> Problem for KnapsackProblem<I> {
+    type Genome = BitString;
+    type Fitness = f64;
+
+    fn config() -> &'static BitStringConfig {
+        &I::knapsack().config
+    }
+
+    fn evaluate(chosen: &BitString) -> f64 {
+        I::knapsack().fitness(chosen)
+    }
+}
+
+/// A [`Solution`] to `I`'s traveling salesman problem instance
+///
+/// [`Solution`]: ../../trait.Solution.html
+pub type TspTour<I> = Individual<TspProblem<I>>;
+
+/// A [`Solution`] to `I`'s knapsack problem instance
+///
+/// [`Solution`]: ../../trait.Solution.html
+pub type KnapsackPacking<I> = Individual<KnapsackProblem<I>>;
+
+#[cfg(test)]
+mod tests {
+    use std::{f64::consts::PI, sync::OnceLock};
+
+    use super::*;
+    use crate::{alg, hof, select, Evolution, Solution};
+
+    const TRIANGLE: &str = "NAME: tri
+TYPE: TSP
+DIMENSION: 3
+EDGE_WEIGHT_TYPE: EXPLICIT
+EDGE_WEIGHT_FORMAT: UPPER_ROW
+EDGE_WEIGHT_SECTION
+ 3 4
+ 5
+EOF
+";
+
+    struct Circle;
+
+    impl TspInstance for Circle {
+        fn tsp() -> &'static Tsp {
+            static TSP: OnceLock<Tsp> = OnceLock::new();
+            TSP.get_or_init(|| {
+                Tsp::euclidean(
+                    (0..8)
+                        .map(|i| {
+                            let angle = [0, 3, 6, 1, 4, 7, 2, 5][i] as f64 * PI / 4.0;
+                            (angle.cos(), angle.sin())
+                        })
+                        .collect(),
+                )
+                .with_operators(
+                    PermutationCrossover::PartiallyMapped,
+                    PermutationMutation::Swap { indpb: 0.2 },
+                )
+            })
+        }
+    }
+
+    #[test]
+    fn instances() {
+        let tri = Tsp::parse_tsplib(TRIANGLE).unwrap();
+        assert_eq!(tri.name(), "tri");
+        assert_eq!(tri.distance(2, 1), 5.0);
+        assert_eq!(tri.tour_length(&[0, 1, 2]), 12.0);
+        assert!(Tsp::parse_tsplib("DIMENSION: 2\nEDGE_WEIGHT_TYPE: EUC_2D\nEOF").is_err());
+
+        let knapsack = Knapsack::parse("3 10\n6 5\n5 5\n4 6\n").unwrap();
+        assert_eq!(knapsack.fitness(&[true, true, false]), 11.0);
+        assert_eq!(knapsack.fitness(&[true, true, true]), -6.0);
+        let mut all = BitString::new(vec![true; 3]);
+        knapsack.repair(&mut all);
+        assert_eq!(&*all, &[true, true, false]);
+        assert_eq!(Knapsack::parse("2 10\n1 1\n").unwrap_err().line, 0);
+
+        // the best tour goes around the circle
+        let evo: Evolution<TspTour<Circle>, _, _, ()> = Evolution::new(
+            alg::MuPlusLambda::new(30, 60, 0.6, 0.4, select::Tournament::new(3)),
+            hof::BestN::new(1),
+        );
+        let log = evo.run_for(100);
+        let optimum = 16.0 * (PI / 8.0).sin();
+        let best = Circle::tsp().tour_length(log.hall_of_fame[0].as_ref());
+        assert!(best - optimum < 1e-9, "{} vs {}", best, optimum);
+    }
+
+    fn tsplib(header: &str, body: &str) -> Result<Tsp, ParseError> {
+        Tsp::parse_tsplib(&format!("{}\n{}\nEOF\n", header, body))
+    }
+
+    fn matrix(tsp: &Tsp) -> Vec<Vec<f64>> {
+        (0..tsp.n_cities())
+            .map(|i| (0..tsp.n_cities()).map(|j| tsp.distance(i, j)).collect())
+            .collect()
+    }
+
+    #[test]
+    fn explicit_formats() {
+        let expected = vec![
+            vec![0.0, 1.0, 2.0, 3.0],
+            vec![1.0, 0.0, 4.0, 5.0],
+            vec![2.0, 4.0, 0.0, 6.0],
+            vec![3.0, 5.0, 6.0, 0.0],
+        ];
+        let header = |format: &str| {
+            format!(
+                "DIMENSION: 4\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_FORMAT: {}\nEDGE_WEIGHT_SECTION",
+                format
+            )
+        };
+        for (format, body) in [
+            ("FULL_MATRIX", "0 1 2 3\n1 0 4 5\n2 4 0 6\n3 5 6 0"),
+            ("UPPER_ROW", "1 2 3\n4 5\n6"),
+            ("LOWER_COL", "1 2 3 4 5 6"),
+            ("LOWER_ROW", "1\n2 4\n3 5 6"),
+            ("UPPER_COL", "1 2 4 3 5 6"),
+            ("UPPER_DIAG_ROW", "0 1 2 3\n0 4 5\n0 6\n0"),
+            ("LOWER_DIAG_ROW", "0\n1 0\n2 4 0\n3 5 6 0"),
+        ] {
+            let tsp = tsplib(&header(format), body).unwrap();
+            assert_eq!(matrix(&tsp), expected, "{}", format);
+        }
+        assert_eq!(
+            Tsp::from_matrix(expected.clone()).map(|tsp| matrix(&tsp)),
+            Some(expected)
+        );
+        assert!(Tsp::from_matrix(vec![vec![0.0, 1.0], vec![1.0]]).is_none());
+    }
+
+    #[test]
+    fn coordinate_distances() {
+        let coords = "NODE_COORD_SECTION\n1 0 0\n2 3.4 4.4\n3 10 0";
+        let distances = |weight_type: &str| {
+            let header = format!("DIMENSION: 3\nEDGE_WEIGHT_TYPE: {}", weight_type);
+            matrix(&tsplib(&header, coords).unwrap())
+        };
+        // |(3.4, 4.4)| ≈ 5.56
+        assert_eq!(distances("EUC_2D")[0][1], 6.0);
+        assert_eq!(distances("CEIL_2D")[0][1], 6.0);
+        assert_eq!(distances("EUC_2D")[0][2], 10.0);
+        assert_eq!(distances("MAN_2D")[0][1], 8.0);
+        assert_eq!(distances("MAX_2D")[0][1], 4.0);
+        // √(10² / 10) ≈ 3.16 is rounded up
+        assert_eq!(distances("ATT")[0][2], 4.0);
+        let geo = distances("GEO");
+        for (i, row) in geo.iter().enumerate() {
+            // TSPLIB's formula gives 1 for the distance from a city to itself
+            assert_eq!(row[i], 1.0);
+            assert!(row.iter().zip(&geo).all(|(d, other)| *d == other[i]));
+        }
+        // 10 degrees of longitude along the equator is about 1113.2 km, plus 1 and truncated
+        assert_eq!(geo[0][2], 1114.0);
+
+        let tsp = Tsp::euclidean(vec![(0.0, 0.0), (3.0, 4.0)]);
+        assert_eq!(tsp.distance(0, 1), 5.0);
+        assert_eq!(tsp.tour_length(&[1, 0]), 10.0);
+        assert_eq!(tsp.tour_length(&[]), 0.0);
+        assert_eq!(tsp.coords(), Some(&[(0.0, 0.0), (3.0, 4.0)][..]));
+    }
+
+    #[test]
+    fn tsplib_errors() {
+        let error = |input: &str| Tsp::parse_tsplib(input).unwrap_err();
+        assert_eq!(error("NODE_COORD_SECTION\n1 0 0").line, 1);
+        let err = error("DIMENSION: 2\nEDGE_WEIGHT_TYPE: EUC_2D\nNODE_COORD_SECTION\n1 0 0\n3 1 1");
+        assert_eq!(
+            (err.line, err.message.as_str()),
+            (5, "invalid node number 3")
+        );
+        let err = error("DIMENSION: 2\nEDGE_WEIGHT_TYPE: EUC_2D\nNODE_COORD_SECTION\n1 0 0\n2 x 1");
+        assert_eq!(err.line, 5);
+        assert!(err.to_string().starts_with("line 5: invalid coordinate"));
+        assert_eq!(
+            error("DIMENSION: 2\nEDGE_WEIGHT_TYPE: EUC_2D\nSTUFF").line,
+            3
+        );
+        assert_eq!(
+            error("TYPE: CVRP\nDIMENSION: 1\nEDGE_WEIGHT_TYPE: EUC_2D\nNODE_COORD_SECTION\n1 0 0\nEOF").message,
+            "unsupported TYPE `CVRP`"
+        );
+        assert_eq!(
+            error("DIMENSION: 2\nEDGE_WEIGHT_TYPE: EXPLICIT\nEDGE_WEIGHT_SECTION\n0 1\n1 0 5")
+                .message,
+            "EDGE_WEIGHT_SECTION has too many values"
+        );
+        assert_eq!(
+            error("DIMENSION: 2\nEDGE_WEIGHT_TYPE: EXPLICIT\nEOF").message,
+            "missing EDGE_WEIGHT_SECTION"
+        );
+        assert_eq!(
+            error("DIMENSION: 2\nEOF").message,
+            "missing EDGE_WEIGHT_TYPE"
+        );
+        // other sections are skipped
+        let tsp = Tsp::parse_tsplib(
+            "DIMENSION: 2\nEDGE_WEIGHT_TYPE: EUC_2D\nNODE_COORD_SECTION\n1 0 0\n2 0 7\n\
+             DISPLAY_DATA_SECTION\n1 5 5\n2 6 6\nEOF",
+        )
+        .unwrap();
+        assert_eq!(tsp.distance(0, 1), 7.0);
+    }
+
+    struct Items;
+
+    impl KnapsackInstance for Items {
+        fn knapsack() -> &'static Knapsack {
+            static KNAPSACK: OnceLock<Knapsack> = OnceLock::new();
+            // the best packing is items 1, 2, and 4, with a value of 22
+            KNAPSACK.get_or_init(|| {
+                Knapsack::new(
+                    vec![5.0, 8.0, 6.0, 4.0, 8.0, 3.0],
+                    vec![4.0, 5.0, 4.0, 3.0, 5.0, 4.0],
+                    14.0,
+                )
+                .unwrap()
+            })
+        }
+    }
+
+    #[test]
+    fn knapsack() {
+        let knapsack = Items::knapsack();
+        assert_eq!(knapsack.n_items(), 6);
+        assert_eq!(knapsack.config().len, 6);
+        let chosen = [true, false, false, true, false, true];
+        assert_eq!(knapsack.total_value(&chosen), 12.0);
+        assert_eq!(knapsack.total_weight(&chosen), 11.0);
+
+        // items 5, 0, and 3 have the lowest value to weight ratios
+        let mut all = BitString::new(vec![true; 6]);
+        knapsack.repair(&mut all);
+        assert_eq!(&*all, &[false, true, true, false, true, false]);
+        assert_eq!(knapsack.total_weight(&all), 14.0);
+        // feasible choices are left alone
+        let mut some = BitString::new(chosen.to_vec());
+        knapsack.repair(&mut some);
+        assert_eq!(&*some, &chosen);
+
+        assert!(Knapsack::new(vec![1.0], vec![1.0, 2.0], 3.0).is_none());
+        assert!(Knapsack::new(vec![-1.0], vec![1.0], 3.0).is_none());
+        assert!(Knapsack::new(vec![1.0], vec![1.0], f64::NAN).is_none());
+        let err = Knapsack::parse("1 10\n1 1\n2 2\n").unwrap_err();
+        assert_eq!(err.line, 3);
+        let err = Knapsack::parse("1 10\n1 x\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: invalid item weight `x`");
+        let err = Knapsack::parse("1 -10\n1 1\n").unwrap_err();
+        assert_eq!(err.line, 0);
+
+        let evo: Evolution<KnapsackPacking<Items>, _, _, ()> = Evolution::new(
+            alg::MuPlusLambda::new(20, 40, 0.5, 0.5, select::Tournament::new(3)),
+            hof::BestN::new(1),
+        );
+        let best = &evo.run_for(30).hall_of_fame[0];
+        assert_eq!(&best.as_ref()[..], &[false, true, true, false, true, false]);
+        assert_eq!(best.evaluate(), 22.0);
+    }
+}
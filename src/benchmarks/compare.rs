@@ -0,0 +1,662 @@
+//! Comparing algorithms on a set of problems
+//!
+//! A [`Comparison`] runs every algorithm configuration on every problem once for each of a list of seeds,
+//! re-seeding the [reproducible RNG] before each run, and collects one final score per run,
+//! such as the best objective value found within the budget.
+//! The resulting [`Results`] can be summarized, compared pairwise on each problem with the
+//! [Wilcoxon signed-rank test], compared across all problems with the [Friedman test],
+//! and printed as a table in the style of most papers on evolutionary algorithms.
+//!
+//! Since every algorithm is run with the same seeds, the runs of two algorithms on one problem
+//! are treated as paired samples.
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::bbob::{self, BbobProblem, BbobSolution};
+//! use eviolite::benchmarks::compare::Comparison;
+//!
+//! let results = Comparison::new()
+//!     .with_algorithm("(10 + 20)", (10, 20))
+//!     .with_algorithm("(20 + 40)", (20, 40))
+//!     .with_problem("f1", BbobProblem::new(1, 1, 2).unwrap())
+//!     .with_problem("f8", BbobProblem::new(8, 1, 2).unwrap())
+//!     .with_seeds(0..5)
+//!     .minimizing()
+//!     .run(|&(mu, lambda), problem| {
+//!         bbob::observe(problem.clone(), 200, |observed| {
+//!             let evo: Evolution<BbobSolution, _, _, ()> = Evolution::new(
+//!                 alg::MuPlusLambda::new(mu, lambda, 0.6, 0.3, select::Tournament::new(3)),
+//!                 hof::BestN::new(1),
+//!             );
+//!             evo.run_until(|_| observed.is_done());
+//!         })
+//!         .best_delta
+//!     });
+//! println!("{}", results);
+//! ```
+//!
+//! [`Comparison`]: ./struct.Comparison.html
+//! [`Results`]: ./struct.Results.html
+//! [reproducible RNG]: ../../repro_rng/index.html
+//! [Wilcoxon signed-rank test]: ./fn.wilcoxon_signed_rank.html
+//! [Friedman test]: ./fn.friedman.html
+
+use std::{
+    cmp::Ordering,
+    f64::consts::{PI, SQRT_2},
+    fmt::{self, Display},
+};
+
+use crate::repro_rng;
+
+/// A set of algorithm configurations and problems to compare them on
+///
+/// Algorithms and problems can be of any type; they are only ever passed to the closure given to [`.run()`],
+/// which decides what they mean.
+///
+/// [`.run()`]: ./struct.Comparison.html#method.run
+#[derive(Clone, Debug)]
+pub struct Comparison<A, P> {
+    algorithms: Vec<(String, A)>,
+    problems: Vec<(String, P)>,
+    seeds: Vec<u64>,
+    minimize: bool,
+}
+
+impl<A, P> Comparison<A, P> {
+    /// Create an empty comparison, using seeds 0 to 29 and treating higher scores as better.
+    pub fn new() -> Self {
+        Comparison {
+            algorithms: Vec::new(),
+            problems: Vec::new(),
+            seeds: (0..30).collect(),
+            minimize: false,
+        }
+    }
+
+    /// Add an algorithm configuration.
+    pub fn with_algorithm(mut self, name: impl Into<String>, algorithm: A) -> Self {
+        self.algorithms.push((name.into(), algorithm));
+        self
+    }
+
+    /// Add a problem.
+    pub fn with_problem(mut self, name: impl Into<String>, problem: P) -> Self {
+        self.problems.push((name.into(), problem));
+        self
+    }
+
+    /// Run every algorithm on every problem once with each of `seeds`.
+    pub fn with_seeds(mut self, seeds: impl IntoIterator<Item = u64>) -> Self {
+        self.seeds = seeds.into_iter().collect();
+        self
+    }
+
+    /// Treat lower scores as better, e.g. when scores are errors or objective values to be minimized.
+    pub fn minimizing(mut self) -> Self {
+        self.minimize = true;
+        self
+    }
+
+    /// Run the comparison, calling `run` once for every combination of problem, algorithm and seed.
+    ///
+    /// Before each call, this thread's [reproducible RNG] is re-seeded with the seed of the run.
+    /// `run` should perform one complete run and return its final score.
+    ///
+    /// [reproducible RNG]: ../../repro_rng/fn.reseed.html
+    pub fn run(&self, mut run: impl FnMut(&A, &P) -> f64) -> Results {
+        let scores = self
+            .problems
+            .iter()
+            .map(|(_, problem)| {
+                self.algorithms
+                    .iter()
+                    .map(|(_, algorithm)| {
+                        self.seeds
+                            .iter()
+                            .map(|&seed| {
+                                repro_rng::reseed(seed);
+                                run(algorithm, problem)
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        Results {
+            algorithms: self
+                .algorithms
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect(),
+            problems: self.problems.iter().map(|(name, _)| name.clone()).collect(),
+            seeds: self.seeds.clone(),
+            scores,
+            minimize: self.minimize,
+        }
+    }
+}
+
+impl<A, P> Default for Comparison<A, P> {
+    fn default() -> Self {
+        Comparison::new()
+    }
+}
+
+/// Descriptive statistics of the scores of one algorithm on one problem
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    /// The mean score.
+    pub mean: f64,
+    /// The sample standard deviation of the scores.
+    pub stdev: f64,
+    /// The median score.
+    pub median: f64,
+    /// The best score.
+    pub best: f64,
+    /// The worst score.
+    pub worst: f64,
+}
+
+/// The outcome of a statistical test
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestResult {
+    /// The test statistic.
+    pub statistic: f64,
+    /// The p-value of the test.
+    pub p_value: f64,
+}
+
+/// The outcome of the [Friedman test] on a set of [`Results`]
+///
+/// [Friedman test]: ./fn.friedman.html
+/// [`Results`]: ./struct.Results.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ranking {
+    /// The statistic and p-value of the test.
+    pub test: TestResult,
+    /// The mean rank of every algorithm over all problems, where 1 is the best.
+    pub mean_ranks: Vec<f64>,
+}
+
+/// The scores collected by running a [`Comparison`]
+///
+/// Printing this with `Display` shows the [table] with the first algorithm as the baseline
+/// and a significance level of 0.05.
+///
+/// [`Comparison`]: ./struct.Comparison.html
+/// [table]: ./struct.Results.html#method.table
+#[derive(Clone, Debug, PartialEq)]
+pub struct Results {
+    algorithms: Vec<String>,
+    problems: Vec<String>,
+    seeds: Vec<u64>,
+    scores: Vec<Vec<Vec<f64>>>,
+    minimize: bool,
+}
+
+impl Results {
+    /// Get the names of the algorithms, in the order they were added.
+    pub fn algorithms(&self) -> &[String] {
+        &self.algorithms
+    }
+
+    /// Get the names of the problems, in the order they were added.
+    pub fn problems(&self) -> &[String] {
+        &self.problems
+    }
+
+    /// Get the seeds every algorithm was run with.
+    pub fn seeds(&self) -> &[u64] {
+        &self.seeds
+    }
+
+    /// Get the scores of one algorithm on one problem, one per seed.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if either index is out of range.
+    pub fn scores(&self, problem: usize, algorithm: usize) -> &[f64] {
+        &self.scores[problem][algorithm]
+    }
+
+    /// Summarize the scores of one algorithm on one problem.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if either index is out of range.
+    pub fn summary(&self, problem: usize, algorithm: usize) -> Summary {
+        let scores = self.scores(problem, algorithm);
+        let n = scores.len() as f64;
+        let mean = scores.iter().sum::<f64>() / n;
+        let variance = scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+        let mut sorted = scores.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let median = match sorted.len() {
+            0 => f64::NAN,
+            len if len.is_multiple_of(2) => (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0,
+            len => sorted[len / 2],
+        };
+        let (lowest, highest) = (
+            sorted.first().copied().unwrap_or(f64::NAN),
+            sorted.last().copied().unwrap_or(f64::NAN),
+        );
+        let (best, worst) = if self.minimize {
+            (lowest, highest)
+        } else {
+            (highest, lowest)
+        };
+        Summary {
+            mean,
+            stdev: variance.sqrt(),
+            median,
+            best,
+            worst,
+        }
+    }
+
+    /// Compare two algorithms on one problem with the [Wilcoxon signed-rank test],
+    /// pairing their runs by seed.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if any index is out of range.
+    ///
+    /// [Wilcoxon signed-rank test]: ./fn.wilcoxon_signed_rank.html
+    pub fn wilcoxon(&self, problem: usize, a: usize, b: usize) -> TestResult {
+        wilcoxon_signed_rank(self.scores(problem, a), self.scores(problem, b))
+    }
+
+    /// Rank the algorithms over all problems with the [Friedman test],
+    /// using each algorithm's median score on each problem.
+    ///
+    /// [Friedman test]: ./fn.friedman.html
+    pub fn friedman(&self) -> Ranking {
+        let blocks: Vec<Vec<f64>> = (0..self.problems.len())
+            .map(|p| {
+                (0..self.algorithms.len())
+                    .map(|a| self.oriented(self.summary(p, a).median))
+                    .collect()
+            })
+            .collect();
+        let (test, mean_ranks) = friedman(&blocks);
+        Ranking { test, mean_ranks }
+    }
+
+    /// Format the results as a table with one row per problem and one column per algorithm.
+    ///
+    /// Each cell shows the mean and standard deviation of the scores.
+    /// Every algorithm other than `baseline` is compared to it on each problem
+    /// with the Wilcoxon signed-rank test, and marked with `+` if it is significantly better
+    /// at the significance level `alpha`, `-` if it is significantly worse, and `≈` otherwise.
+    /// The last rows count these markers and show the mean Friedman rank of every algorithm,
+    /// followed by the result of the Friedman test.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `baseline` is out of range.
+    pub fn table(&self, baseline: usize, alpha: f64) -> String {
+        assert!(
+            baseline < self.algorithms.len(),
+            "baseline algorithm index out of range"
+        );
+        let mut rows = vec![std::iter::once("problem".to_owned())
+            .chain(self.algorithms.iter().enumerate().map(|(a, name)| {
+                if a == baseline {
+                    format!("{} (baseline)", name)
+                } else {
+                    name.clone()
+                }
+            }))
+            .collect::<Vec<_>>()];
+        let mut counts = vec![[0usize; 3]; self.algorithms.len()];
+
+        for (p, problem) in self.problems.iter().enumerate() {
+            let base = self.summary(p, baseline);
+            let mut row = vec![problem.clone()];
+            for (a, count) in counts.iter_mut().enumerate() {
+                let summary = self.summary(p, a);
+                let mut cell = format!("{:.3e} ± {:.2e}", summary.mean, summary.stdev);
+                if a != baseline {
+                    let significant = self.wilcoxon(p, a, baseline).p_value < alpha;
+                    let marker = match self
+                        .oriented(summary.mean)
+                        .partial_cmp(&self.oriented(base.mean))
+                    {
+                        Some(Ordering::Greater) if significant => 0,
+                        Some(Ordering::Less) if significant => 2,
+                        _ => 1,
+                    };
+                    count[marker] += 1;
+                    cell.push(' ');
+                    cell.push(['+', '≈', '-'][marker]);
+                }
+                row.push(cell);
+            }
+            rows.push(row);
+        }
+
+        let ranking = self.friedman();
+        rows.push(
+            std::iter::once("+/≈/-".to_owned())
+                .chain(counts.iter().enumerate().map(|(a, [w, t, l])| {
+                    if a == baseline {
+                        String::new()
+                    } else {
+                        format!("{}/{}/{}", w, t, l)
+                    }
+                }))
+                .collect(),
+        );
+        rows.push(
+            std::iter::once("mean rank".to_owned())
+                .chain(ranking.mean_ranks.iter().map(|r| format!("{:.2}", r)))
+                .collect(),
+        );
+
+        let widths: Vec<usize> = (0..rows[0].len())
+            .map(|c| rows.iter().map(|row| row[c].chars().count()).max().unwrap())
+            .collect();
+        let mut table = String::new();
+        for row in rows {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{:<1$}", cell, width))
+                .collect();
+            table.push_str(cells.join(" | ").trim_end());
+            table.push('\n');
+        }
+        table.push_str(&format!(
+            "Friedman test: χ² = {:.3}, p = {:.3e}\n",
+            ranking.test.statistic, ranking.test.p_value
+        ));
+        table
+    }
+
+    // Turn a score into one where higher is always better.
+    fn oriented(&self, score: f64) -> f64 {
+        if self.minimize {
+            -score
+        } else {
+            score
+        }
+    }
+}
+
+impl Display for Results {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.table(0, 0.05))
+    }
+}
+
+/// Perform the two-sided Wilcoxon signed-rank test[^1] on the paired samples `a` and `b`.
+///
+/// Pairs with no difference are discarded.
+/// The statistic is the smaller of the sums of the ranks of positive and negative differences.
+/// If there are at most 50 nonzero differences and none of them are tied,
+/// the p-value is computed exactly; otherwise, it uses the normal approximation
+/// with a continuity correction and a correction for ties.
+///
+/// Panics
+/// ======
+/// Panics if `a` and `b` have different lengths.
+///
+/// [^1]: Wilcoxon, F. "Individual Comparisons by Ranking Methods." 1945. <https://doi.org/10.2307/3001968>
+pub fn wilcoxon_signed_rank(a: &[f64], b: &[f64]) -> TestResult {
+    assert_eq!(a.len(), b.len(), "samples must be paired");
+    let differences: Vec<f64> = a
+        .iter()
+        .zip(b)
+        .map(|(a, b)| a - b)
+        .filter(|d| *d != 0.0)
+        .collect();
+    let n = differences.len();
+    if n == 0 {
+        return TestResult {
+            statistic: 0.0,
+            p_value: 1.0,
+        };
+    }
+
+    let magnitudes: Vec<f64> = differences.iter().map(|d| d.abs()).collect();
+    let (ranks, ties) = average_ranks(&magnitudes);
+    let positive: f64 = ranks
+        .iter()
+        .zip(&differences)
+        .filter(|(_, d)| **d > 0.0)
+        .map(|(r, _)| r)
+        .sum();
+    let total = (n * (n + 1)) as f64 / 2.0;
+    let statistic = positive.min(total - positive);
+
+    let p_value = if ties.is_empty() && n <= 50 {
+        // count the subsets of ranks 1..=n by their sum
+        let max = n * (n + 1) / 2;
+        let mut counts = vec![0.0; max + 1];
+        counts[0] = 1.0;
+        for rank in 1..=n {
+            for sum in (rank..=max).rev() {
+                counts[sum] += counts[sum - rank];
+            }
+        }
+        let at_most: f64 = counts[..=statistic as usize].iter().sum();
+        2.0 * at_most / 2f64.powi(n as i32)
+    } else {
+        let mean = total / 2.0;
+        let tie_correction: f64 = ties.iter().map(|&t| (t * t * t - t) as f64).sum::<f64>() / 48.0;
+        let variance = (n * (n + 1) * (2 * n + 1)) as f64 / 24.0 - tie_correction;
+        if variance <= 0.0 {
+            1.0
+        } else {
+            let z = (statistic - mean + 0.5).min(0.0) / variance.sqrt();
+            erfc(-z / SQRT_2)
+        }
+    };
+    TestResult {
+        statistic,
+        p_value: p_value.min(1.0),
+    }
+}
+
+/// Perform the Friedman test[^1] on a set of blocks, each containing one value per treatment,
+/// where higher values are better.
+///
+/// Treatments are ranked within each block, with rank 1 for the highest value and
+/// tied values getting the average of their ranks. Returns the result of the test, whose p-value
+/// uses the χ² approximation, along with the mean rank of every treatment.
+///
+/// Panics
+/// ======
+/// Panics if the blocks don't all have the same length.
+///
+/// [^1]: Friedman, M. "The Use of Ranks to Avoid the Assumption of Normality Implicit in the Analysis of Variance." 1937. <https://doi.org/10.1080/01621459.1937.10503522>
+pub fn friedman(blocks: &[Vec<f64>]) -> (TestResult, Vec<f64>) {
+    let k = blocks.first().map_or(0, Vec::len);
+    assert!(
+        blocks.iter().all(|block| block.len() == k),
+        "every block must have a value for every treatment"
+    );
+    let n = blocks.len();
+    let mut mean_ranks = vec![0.0; k];
+    for block in blocks {
+        let negated: Vec<f64> = block.iter().map(|v| -v).collect();
+        for (mean, rank) in mean_ranks.iter_mut().zip(average_ranks(&negated).0) {
+            *mean += rank / n as f64;
+        }
+    }
+    if n == 0 || k < 2 {
+        return (
+            TestResult {
+                statistic: 0.0,
+                p_value: 1.0,
+            },
+            mean_ranks,
+        );
+    }
+
+    let (n, k) = (n as f64, k as f64);
+    let statistic = 12.0 * n / (k * (k + 1.0))
+        * (mean_ranks.iter().map(|r| r * r).sum::<f64>() - k * (k + 1.0).powi(2) / 4.0);
+    let statistic = statistic.max(0.0);
+    (
+        TestResult {
+            statistic,
+            p_value: gamma_q((k - 1.0) / 2.0, statistic / 2.0),
+        },
+        mean_ranks,
+    )
+}
+
+// Rank values from 1 for the lowest, averaging the ranks of ties.
+// Also returns the size of every group of ties.
+fn average_ranks(values: &[f64]) -> (Vec<f64>, Vec<usize>) {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut ties = Vec::new();
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = rank;
+        }
+        if end - start > 1 {
+            ties.push(end - start);
+        }
+        start = end;
+    }
+    (ranks, ties)
+}
+
+// The logarithm of the gamma function, using the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 8] = [
+        676.5203681218851,
+        -1259.1392167224028,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507343278686905,
+        -0.13857109526572012,
+        9.984_369_578_019_572e-6,
+        1.5056327351493116e-7,
+    ];
+    if x < 0.5 {
+        (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x)
+    } else {
+        let x = x - 1.0;
+        let t = x + 7.5;
+        let sum = COEFFICIENTS
+            .iter()
+            .enumerate()
+            .fold(0.999_999_999_999_809_9, |sum, (i, c)| {
+                sum + c / (x + i as f64 + 1.0)
+            });
+        0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+    }
+}
+
+// The regularized upper incomplete gamma function Q(a, x).
+fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefactor = (a * x.ln() - x - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        // series for P(a, x)
+        let (mut term, mut sum, mut ap) = (1.0 / a, 1.0 / a, a);
+        for _ in 0..500 {
+            ap += 1.0;
+            term *= x / ap;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        (1.0 - sum * prefactor).max(0.0)
+    } else {
+        // Lentz's continued fraction for Q(a, x)
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..500 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-15 {
+                break;
+            }
+        }
+        (h * prefactor).min(1.0)
+    }
+}
+
+// The complementary error function.
+fn erfc(x: f64) -> f64 {
+    if x < 0.0 {
+        2.0 - erfc(-x)
+    } else {
+        gamma_q(0.5, x * x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tests_and_table() {
+        // all ten differences positive and distinct: T = 0, p = 2 / 2¹⁰
+        let a: Vec<f64> = (1..=10).map(|i| i as f64).collect();
+        let zeros = vec![0.0; 10];
+        let result = wilcoxon_signed_rank(&a, &zeros);
+        assert_eq!(result.statistic, 0.0);
+        assert!((result.p_value - 2.0 / 1024.0).abs() < 1e-12);
+        assert_eq!(wilcoxon_signed_rank(&a, &a).p_value, 1.0);
+        assert!((erfc(1.0) - 0.157_299_207_050_285_13).abs() < 1e-12);
+
+        // three treatments always ranked in the same order over four blocks:
+        // χ² = 8 with 2 degrees of freedom, p = e⁻⁴
+        let blocks = vec![vec![3.0, 2.0, 1.0]; 4];
+        let (result, ranks) = friedman(&blocks);
+        assert_eq!(ranks, vec![1.0, 2.0, 3.0]);
+        assert!((result.statistic - 8.0).abs() < 1e-12);
+        assert!((result.p_value - (-4.0f64).exp()).abs() < 1e-9);
+
+        let comparison = Comparison::new()
+            .with_algorithm("good", 0.0)
+            .with_algorithm("bad", 1.0)
+            .with_problem("first", 1.0)
+            .with_problem("second", 2.0)
+            .with_seeds(0..12)
+            .minimizing();
+        let run = |offset: &f64, scale: &f64| offset + scale * repro_rng::random::<f64>();
+        let results = comparison.run(run);
+        assert_eq!(results, comparison.run(run));
+        assert_eq!(results.friedman().mean_ranks, vec![1.0, 2.0]);
+        assert!(results.wilcoxon(0, 0, 1).p_value < 0.01);
+        let summary = results.summary(1, 0);
+        assert!(summary.best <= summary.median && summary.median <= summary.worst);
+
+        let table = results.to_string();
+        assert_eq!(table.lines().count(), 6);
+        assert!(table.contains("good (baseline)"));
+        assert!(table.contains("0/0/2"));
+    }
+}
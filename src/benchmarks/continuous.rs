@@ -0,0 +1,353 @@
+//! Single-objective continuous test functions
+//!
+//! Each test function is a unit struct implementing [`TestFunction`],
+//! and [`Benchmark<F, D>`] turns it into a [`Solution`] over a [`RealVector`] of `D` dimensions,
+//! using the function's standard bounds, simulated binary crossover, and polynomial mutation.
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::{Benchmark, Rastrigin};
+//!
+//! let evo: Evolution<Benchmark<Rastrigin, 5>, _, _, ()> = Evolution::new(
+//!     alg::MuPlusLambda::new(50, 100, 0.6, 0.3, select::Tournament::new(3)),
+//!     hof::BestN::new(1),
+//! );
+//! let log = evo.run_for(20);
+//! println!("best value: {}", log.hall_of_fame[0].as_ref().value());
+//! ```
+//!
+//! [`TestFunction`]: ./trait.TestFunction.html
+//! [`Benchmark<F, D>`]: ./struct.Benchmark.html
+//! [`Solution`]: ../../trait.Solution.html
+//! [`RealVector`]: ../../genome/struct.RealVector.html
+
+use std::{
+    f64::consts::{E, PI},
+    fmt::Debug,
+    marker::PhantomData,
+    ops::Deref,
+};
+
+use crate::{
+    genome::{real, RealVector},
+    Solution,
+};
+
+/// A continuous function to be minimized
+pub trait TestFunction {
+    /// The name of the function.
+    const NAME: &'static str;
+
+    /// The standard `(lower, upper)` bounds of every dimension.
+    const BOUNDS: (f64, f64);
+
+    /// Compute the value of the function at `x`.
+    fn value(x: &[f64]) -> f64;
+
+    /// Get the location of the global minimum in `dim` dimensions.
+    fn optimum(dim: usize) -> Vec<f64>;
+}
+
+/// The sphere function, `Σ xᵢ²`
+///
+/// Unimodal and separable; the easiest possible test.
+#[derive(Clone, Copy, Debug)]
+pub struct Sphere;
+
+impl TestFunction for Sphere {
+    const NAME: &'static str = "sphere";
+    const BOUNDS: (f64, f64) = (-5.12, 5.12);
+
+    fn value(x: &[f64]) -> f64 {
+        x.iter().map(|x| x * x).sum()
+    }
+
+    fn optimum(dim: usize) -> Vec<f64> {
+        vec![0.0; dim]
+    }
+}
+
+/// The Rastrigin function, `10n + Σ (xᵢ² - 10 cos(2π xᵢ))`
+///
+/// Highly multimodal, with a regular grid of local minima.
+#[derive(Clone, Copy, Debug)]
+pub struct Rastrigin;
+
+impl TestFunction for Rastrigin {
+    const NAME: &'static str = "rastrigin";
+    const BOUNDS: (f64, f64) = (-5.12, 5.12);
+
+    fn value(x: &[f64]) -> f64 {
+        10.0 * x.len() as f64
+            + x.iter()
+                .map(|x| x * x - 10.0 * (2.0 * PI * x).cos())
+                .sum::<f64>()
+    }
+
+    fn optimum(dim: usize) -> Vec<f64> {
+        vec![0.0; dim]
+    }
+}
+
+/// The Rosenbrock function, `Σ (100 (xᵢ₊₁ - xᵢ²)² + (1 - xᵢ)²)`
+///
+/// Unimodal in low dimensions, with the minimum at the bottom of a long, curved, flat valley.
+#[derive(Clone, Copy, Debug)]
+pub struct Rosenbrock;
+
+impl TestFunction for Rosenbrock {
+    const NAME: &'static str = "rosenbrock";
+    const BOUNDS: (f64, f64) = (-5.0, 10.0);
+
+    fn value(x: &[f64]) -> f64 {
+        x.windows(2)
+            .map(|w| 100.0 * (w[1] - w[0] * w[0]).powi(2) + (1.0 - w[0]).powi(2))
+            .sum()
+    }
+
+    fn optimum(dim: usize) -> Vec<f64> {
+        vec![1.0; dim]
+    }
+}
+
+/// The Ackley function
+///
+/// Multimodal, with a nearly flat outer region and a deep hole at the center.
+#[derive(Clone, Copy, Debug)]
+pub struct Ackley;
+
+impl TestFunction for Ackley {
+    const NAME: &'static str = "ackley";
+    const BOUNDS: (f64, f64) = (-32.768, 32.768);
+
+    fn value(x: &[f64]) -> f64 {
+        if x.is_empty() {
+            return 0.0;
+        }
+        let n = x.len() as f64;
+        let sum_sq = x.iter().map(|x| x * x).sum::<f64>() / n;
+        let sum_cos = x.iter().map(|x| (2.0 * PI * x).cos()).sum::<f64>() / n;
+        -20.0 * (-0.2 * sum_sq.sqrt()).exp() - sum_cos.exp() + 20.0 + E
+    }
+
+    fn optimum(dim: usize) -> Vec<f64> {
+        vec![0.0; dim]
+    }
+}
+
+/// The Griewank function, `1 + Σ xᵢ² / 4000 - Π cos(xᵢ / √i)`
+///
+/// Multimodal, with many widespread, regularly distributed local minima.
+#[derive(Clone, Copy, Debug)]
+pub struct Griewank;
+
+impl TestFunction for Griewank {
+    const NAME: &'static str = "griewank";
+    const BOUNDS: (f64, f64) = (-600.0, 600.0);
+
+    fn value(x: &[f64]) -> f64 {
+        let sum = x.iter().map(|x| x * x).sum::<f64>() / 4000.0;
+        let prod = x
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (x / ((i + 1) as f64).sqrt()).cos())
+            .product::<f64>();
+        1.0 + sum - prod
+    }
+
+    fn optimum(dim: usize) -> Vec<f64> {
+        vec![0.0; dim]
+    }
+}
+
+/// The Schwefel function, `418.9829n - Σ xᵢ sin(√|xᵢ|)`
+///
+/// Multimodal and deceptive: the second-best minimum is far from the global one.
+#[derive(Clone, Copy, Debug)]
+pub struct Schwefel;
+
+impl TestFunction for Schwefel {
+    const NAME: &'static str = "schwefel";
+    const BOUNDS: (f64, f64) = (-500.0, 500.0);
+
+    fn value(x: &[f64]) -> f64 {
+        418.9828872724338 * x.len() as f64 - x.iter().map(|x| x * x.abs().sqrt().sin()).sum::<f64>()
+    }
+
+    fn optimum(dim: usize) -> Vec<f64> {
+        vec![420.968746; dim]
+    }
+}
+
+/// A [`Solution`] that minimizes the test function `F` in `D` dimensions
+///
+/// Solutions are generated uniformly within `F::BOUNDS`, crossed over with
+/// [simulated binary crossover] with η = 20, and mutated with [polynomial mutation]
+/// with η = 20 and a probability of 1/`D` for each element.
+/// Fitness is the negated value of the function, so the best possible fitness is 0.
+/// This type derefs to the [`RealVector`] it contains.
+///
+/// [`Solution`]: ../../trait.Solution.html
+/// [simulated binary crossover]: ../../genome/real/fn.simulated_binary.html
+/// [polynomial mutation]: ../../genome/real/fn.polynomial.html
+/// [`RealVector`]: ../../genome/struct.RealVector.html
+pub struct Benchmark<F, const D: usize> {
+    x: RealVector,
+    _phantom: PhantomData<fn() -> F>,
+}
+
+impl<F: TestFunction, const D: usize> Benchmark<F, D> {
+    /// Wrap an existing vector.
+    ///
+    /// Returns `None` if `x` doesn't have exactly `D` elements, all within `F::BOUNDS`.
+    pub fn new(x: Vec<f64>) -> Option<Self> {
+        Some(Benchmark {
+            x: RealVector::new(x, vec![F::BOUNDS; D])?,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Get the value of the test function at this solution.
+    pub fn value(&self) -> f64 {
+        F::value(&self.x)
+    }
+
+    /// Consume the solution, returning the vector it contained.
+    pub fn into_inner(self) -> RealVector {
+        self.x
+    }
+}
+
+impl<F: TestFunction, const D: usize> Solution for Benchmark<F, D> {
+    type Fitness = f64;
+
+    fn generate() -> Self {
+        Benchmark {
+            x: RealVector::uniform(vec![F::BOUNDS; D]),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn evaluate(&self) -> f64 {
+        -self.value()
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        real::simulated_binary(&mut a.x, &mut b.x, 20.0);
+    }
+
+    fn mutate(&mut self) {
+        real::polynomial(&mut self.x, 20.0, 1.0 / D.max(1) as f64);
+    }
+}
+
+impl<F, const D: usize> Clone for Benchmark<F, D> {
+    fn clone(&self) -> Self {
+        Benchmark {
+            x: self.x.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, const D: usize> Deref for Benchmark<F, D> {
+    type Target = RealVector;
+    fn deref(&self) -> &RealVector {
+        &self.x
+    }
+}
+
+impl<F, const D: usize> AsRef<RealVector> for Benchmark<F, D> {
+    fn as_ref(&self) -> &RealVector {
+        &self.x
+    }
+}
+
+impl<F: TestFunction, const D: usize> Debug for Benchmark<F, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple(F::NAME).field(&&self.x[..]).finish()
+    }
+}
+
+impl<F, const D: usize> PartialEq for Benchmark<F, D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn check<F: TestFunction>() {
+        for dim in [1, 2, 10] {
+            let value = F::value(&F::optimum(dim));
+            assert!(value.abs() < 1e-3, "{} at optimum was {}", F::NAME, value);
+        }
+        let bench = Benchmark::<F, 4>::generate();
+        assert!(bench.value() >= -1e-3);
+    }
+
+    #[test]
+    fn optima() {
+        check::<Sphere>();
+        check::<Rastrigin>();
+        check::<Rosenbrock>();
+        check::<Ackley>();
+        check::<Griewank>();
+        check::<Schwefel>();
+        assert!(Benchmark::<Sphere, 3>::new(vec![0.0; 2]).is_none());
+    }
+
+    #[test]
+    fn values() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        assert!(close(Sphere::value(&[1.0, -2.0]), 5.0));
+        // cos(2πx) = 1 at every integer
+        assert!(close(Rastrigin::value(&[1.0, -2.0]), 5.0));
+        assert!(close(Rosenbrock::value(&[0.0, 0.0]), 1.0));
+        assert!(close(Rosenbrock::value(&[1.0, 2.0]), 100.0));
+        assert!(close(Ackley::value(&[1.0]), 20.0 - 20.0 * (-0.2f64).exp()));
+        assert!(close(Griewank::value(&[0.0, 0.0]), 0.0));
+        assert!(close(Griewank::value(&[PI]), 1.0 + PI * PI / 4000.0 + 1.0));
+        assert!(close(Schwefel::value(&[0.0; 3]), 3.0 * 418.9828872724338));
+    }
+
+    fn check_operators<F: TestFunction>() {
+        let (lower, upper) = F::BOUNDS;
+        assert!(F::optimum(3).iter().all(|x| (lower..=upper).contains(x)));
+        let in_bounds = |bench: &Benchmark<F, 5>| {
+            bench.len() == 5 && bench.iter().all(|x| (lower..=upper).contains(x))
+        };
+        for _ in 0..50 {
+            let mut a = Benchmark::<F, 5>::generate();
+            let mut b = Benchmark::<F, 5>::generate();
+            assert!(in_bounds(&a) && in_bounds(&b));
+            Benchmark::crossover(&mut a, &mut b);
+            a.mutate();
+            assert!(in_bounds(&a) && in_bounds(&b), "{:?} or {:?}", a, b);
+            assert_eq!(a.evaluate(), -a.value());
+        }
+    }
+
+    #[test]
+    fn operators_stay_in_bounds() {
+        check_operators::<Sphere>();
+        check_operators::<Rastrigin>();
+        check_operators::<Rosenbrock>();
+        check_operators::<Ackley>();
+        check_operators::<Griewank>();
+        check_operators::<Schwefel>();
+    }
+
+    #[test]
+    fn optimize_sphere() {
+        let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> = Evolution::new(
+            alg::MuPlusLambda::new(30, 60, 0.6, 0.3, select::Tournament::new(3)),
+            hof::BestN::new(1),
+        );
+        let best = evo.run_for(40).hall_of_fame[0].as_ref().value();
+        assert!(best < 0.01, "best value was {}", best);
+    }
+}
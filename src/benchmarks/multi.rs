@@ -0,0 +1,969 @@
+//! Multi-objective test problems
+//!
+//! This module contains the ZDT[^1], DTLZ[^2], and WFG[^3] test suites.
+//! Each problem is a unit struct implementing [`MultiTestFunction`],
+//! and [`MultiBenchmark<F, D, M>`] turns it into a [`Solution`] with `D` decision variables
+//! and `M` objectives, using simulated binary crossover and polynomial mutation.
+//!
+//! Every problem can also produce a sampled [reference front], the set of objective vectors
+//! of its Pareto-optimal solutions, which is what quality indicators like IGD and hypervolume
+//! are computed against.
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::multi::{MultiBenchmark, Zdt1};
+//!
+//! type Problem = MultiBenchmark<Zdt1, 30, 2>;
+//!
+//! let evo: Evolution<Problem, _, _, ()> = Evolution::new(
+//!     alg::NSGA2::new(40, 0.9, 0.1),
+//!     hof::BestPareto::new(),
+//! );
+//! let log = evo.run_for(10);
+//! let reference = Problem::reference_fitnesses(100);
+//! assert_eq!(reference.len(), 100);
+//! # let _ = log;
+//! ```
+//!
+//! ZDT5 is left out, since it is defined over bit strings.
+//!
+//! [^1]: Zitzler, Deb, & Thiele.
+//! "Comparison of Multiobjective Evolutionary Algorithms: Empirical Results."
+//! 2000. <https://doi.org/10.1162/106365600568202>
+//!
+//! [^2]: Deb, Thiele, Laumanns, & Zitzler.
+//! "Scalable Test Problems for Evolutionary Multiobjective Optimization."
+//! 2005. <https://doi.org/10.1007/1-84628-137-7_6>
+//!
+//! [^3]: Huband, Hingston, Barone, & While.
+//! "A review of multiobjective test problems and a scalable test problem toolkit."
+//! 2006. <https://doi.org/10.1109/TEVC.2005.861417>
+//!
+//! [`MultiTestFunction`]: ./trait.MultiTestFunction.html
+//! [`MultiBenchmark<F, D, M>`]: ./struct.MultiBenchmark.html
+//! [`Solution`]: ../../trait.Solution.html
+//! [reference front]: ./trait.MultiTestFunction.html#method.reference_front
+
+use std::{f64::consts::PI, fmt::Debug, marker::PhantomData, ops::Deref};
+
+use crate::{
+    fitness::MultiObjective,
+    genome::{real, RealVector},
+    Solution,
+};
+
+/// A multi-objective problem with `M` objectives, all to be minimized
+pub trait MultiTestFunction<const M: usize> {
+    /// The name of the problem.
+    const NAME: &'static str;
+
+    /// Get the `(lower, upper)` bounds of each variable in `dim` dimensions.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if the problem isn't defined for `dim` dimensions and `M` objectives.
+    fn bounds(dim: usize) -> Vec<(f64, f64)>;
+
+    /// Compute the objective values at `x`.
+    fn values(x: &[f64]) -> [f64; M];
+
+    /// Get a Pareto-optimal solution in `dim` dimensions.
+    ///
+    /// `position` has `M - 1` elements between 0 and 1, which together pick a point along the front.
+    fn pareto_optimal(position: &[f64], dim: usize) -> Vec<f64>;
+
+    /// Sample the Pareto front in `dim` dimensions.
+    ///
+    /// Positions are taken from an evenly spaced grid with `points_per_dim` points
+    /// along each of the `M - 1` position dimensions, and any resulting objective vectors
+    /// that are dominated by others are removed.
+    fn reference_front(points_per_dim: usize, dim: usize) -> Vec<[f64; M]> {
+        let grid = M.saturating_sub(1);
+        let n_points = points_per_dim.pow(grid as u32);
+        let step = 1.0 / (points_per_dim.max(2) - 1) as f64;
+        let points: Vec<[f64; M]> = (0..n_points)
+            .map(|mut idx| {
+                let position: Vec<f64> = (0..grid)
+                    .map(|_| {
+                        let coord = (idx % points_per_dim) as f64 * step;
+                        idx /= points_per_dim;
+                        coord.min(1.0)
+                    })
+                    .collect();
+                Self::values(&Self::pareto_optimal(&position, dim))
+            })
+            .collect();
+        nondominated(points)
+    }
+}
+
+// Remove every point that is dominated by another one, assuming minimization.
+fn nondominated<const M: usize>(points: Vec<[f64; M]>) -> Vec<[f64; M]> {
+    let dominates = |a: &[f64; M], b: &[f64; M]| a.iter().zip(b).all(|(a, b)| a <= b) && a != b;
+    points
+        .iter()
+        .filter(|p| !points.iter().any(|q| dominates(q, p)))
+        .copied()
+        .collect()
+}
+
+/// A [`Solution`] that minimizes the objectives of `F`, with `D` variables and `M` objectives
+///
+/// Solutions are generated uniformly within `F`'s bounds, crossed over with
+/// [simulated binary crossover] with η = 20, and mutated with [polynomial mutation]
+/// with η = 20 and a probability of 1/`D` for each variable.
+/// Fitness is the negated objective values.
+/// This type derefs to the [`RealVector`] it contains.
+///
+/// [`Solution`]: ../../trait.Solution.html
+/// [simulated binary crossover]: ../../genome/real/fn.simulated_binary.html
+/// [polynomial mutation]: ../../genome/real/fn.polynomial.html
+/// [`RealVector`]: ../../genome/struct.RealVector.html
+pub struct MultiBenchmark<F, const D: usize, const M: usize> {
+    x: RealVector,
+    _phantom: PhantomData<fn() -> F>,
+}
+
+impl<F: MultiTestFunction<M>, const D: usize, const M: usize> MultiBenchmark<F, D, M> {
+    /// Wrap an existing vector.
+    ///
+    /// Returns `None` if `x` doesn't have exactly `D` elements, all within `F`'s bounds.
+    pub fn new(x: Vec<f64>) -> Option<Self> {
+        Some(MultiBenchmark {
+            x: RealVector::new(x, F::bounds(D))?,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Get the objective values of this solution.
+    pub fn values(&self) -> [f64; M] {
+        F::values(&self.x)
+    }
+
+    /// Consume the solution, returning the vector it contained.
+    pub fn into_inner(self) -> RealVector {
+        self.x
+    }
+
+    /// Sample the Pareto front as fitness values, i.e. with every objective negated.
+    ///
+    /// See [`MultiTestFunction::reference_front`].
+    ///
+    /// [`MultiTestFunction::reference_front`]: ./trait.MultiTestFunction.html#method.reference_front
+    pub fn reference_fitnesses(points_per_dim: usize) -> Vec<MultiObjective<M>> {
+        F::reference_front(points_per_dim, D)
+            .into_iter()
+            .map(|f| MultiObjective::new_unweighted(f.map(|v| -v)))
+            .collect()
+    }
+}
+
+impl<F: MultiTestFunction<M>, const D: usize, const M: usize> Solution for MultiBenchmark<F, D, M> {
+    type Fitness = MultiObjective<M>;
+
+    fn generate() -> Self {
+        MultiBenchmark {
+            x: RealVector::uniform(F::bounds(D)),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn evaluate(&self) -> Self::Fitness {
+        MultiObjective::new_unweighted(self.values().map(|v| -v))
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        real::simulated_binary(&mut a.x, &mut b.x, 20.0);
+    }
+
+    fn mutate(&mut self) {
+        real::polynomial(&mut self.x, 20.0, 1.0 / D.max(1) as f64);
+    }
+}
+
+impl<F, const D: usize, const M: usize> Clone for MultiBenchmark<F, D, M> {
+    fn clone(&self) -> Self {
+        MultiBenchmark {
+            x: self.x.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<F, const D: usize, const M: usize> Deref for MultiBenchmark<F, D, M> {
+    type Target = RealVector;
+    fn deref(&self) -> &RealVector {
+        &self.x
+    }
+}
+
+impl<F, const D: usize, const M: usize> AsRef<RealVector> for MultiBenchmark<F, D, M> {
+    fn as_ref(&self) -> &RealVector {
+        &self.x
+    }
+}
+
+impl<F: MultiTestFunction<M>, const D: usize, const M: usize> Debug for MultiBenchmark<F, D, M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple(F::NAME).field(&&self.x[..]).finish()
+    }
+}
+
+impl<F, const D: usize, const M: usize> PartialEq for MultiBenchmark<F, D, M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x
+    }
+}
+
+// ZDT
+
+macro_rules! zdt {
+    ($(#[$doc:meta])* $name:ident, $str:literal, $bounds:expr, |$x:ident| $values:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug)]
+        pub struct $name;
+
+        impl MultiTestFunction<2> for $name {
+            const NAME: &'static str = $str;
+
+            fn bounds(dim: usize) -> Vec<(f64, f64)> {
+                assert!(dim >= 2, "ZDT problems need at least 2 variables");
+                let rest: (f64, f64) = $bounds;
+                std::iter::once((0.0, 1.0))
+                    .chain(std::iter::repeat(rest).take(dim - 1))
+                    .collect()
+            }
+
+            fn values($x: &[f64]) -> [f64; 2] {
+                $values
+            }
+
+            fn pareto_optimal(position: &[f64], dim: usize) -> Vec<f64> {
+                let mut x = vec![0.0; dim];
+                x[0] = position[0];
+                x
+            }
+        }
+    };
+}
+
+fn zdt_g(x: &[f64]) -> f64 {
+    1.0 + 9.0 * x[1..].iter().sum::<f64>() / (x.len() - 1) as f64
+}
+
+zdt!(
+    /// ZDT1, which has a convex front
+    Zdt1, "zdt1", (0.0, 1.0), |x| {
+        let g = zdt_g(x);
+        [x[0], g * (1.0 - (x[0] / g).sqrt())]
+    }
+);
+
+zdt!(
+    /// ZDT2, which has a concave front
+    Zdt2, "zdt2", (0.0, 1.0), |x| {
+        let g = zdt_g(x);
+        [x[0], g * (1.0 - (x[0] / g).powi(2))]
+    }
+);
+
+zdt!(
+    /// ZDT3, which has a front made of several disconnected convex parts
+    Zdt3, "zdt3", (0.0, 1.0), |x| {
+        let g = zdt_g(x);
+        let r = x[0] / g;
+        [x[0], g * (1.0 - r.sqrt() - r * (10.0 * PI * x[0]).sin())]
+    }
+);
+
+zdt!(
+    /// ZDT4, which has a convex front and many local fronts
+    Zdt4, "zdt4", (-5.0, 5.0), |x| {
+        let g = 1.0
+            + 10.0 * (x.len() - 1) as f64
+            + x[1..]
+                .iter()
+                .map(|x| x * x - 10.0 * (4.0 * PI * x).cos())
+                .sum::<f64>();
+        [x[0], g * (1.0 - (x[0] / g).sqrt())]
+    }
+);
+
+zdt!(
+    /// ZDT6, which has a concave front and solutions that are unevenly distributed along it
+    Zdt6, "zdt6", (0.0, 1.0), |x| {
+        let f1 = 1.0 - (-4.0 * x[0]).exp() * (6.0 * PI * x[0]).sin().powi(6);
+        let g = 1.0 + 9.0 * (x[1..].iter().sum::<f64>() / (x.len() - 1) as f64).powf(0.25);
+        [f1, g * (1.0 - (f1 / g).powi(2))]
+    }
+);
+
+// DTLZ
+
+fn dtlz_bounds(dim: usize, m: usize) -> Vec<(f64, f64)> {
+    assert!(
+        dim >= m,
+        "DTLZ problems need at least as many variables as objectives"
+    );
+    vec![(0.0, 1.0); dim]
+}
+
+fn dtlz_optimal(position: &[f64], dim: usize) -> Vec<f64> {
+    let mut x = vec![0.5; dim];
+    x[..position.len()].copy_from_slice(position);
+    x
+}
+
+fn dtlz1_g(distance: &[f64]) -> f64 {
+    100.0
+        * (distance.len() as f64
+            + distance
+                .iter()
+                .map(|x| (x - 0.5).powi(2) - (20.0 * PI * (x - 0.5)).cos())
+                .sum::<f64>())
+}
+
+fn dtlz2_g(distance: &[f64]) -> f64 {
+    distance.iter().map(|x| (x - 0.5).powi(2)).sum()
+}
+
+// The spherical front shared by DTLZ2-4, with positions raised to the power `alpha`.
+fn dtlz_sphere<const M: usize>(x: &[f64], g: f64, alpha: f64) -> [f64; M] {
+    let mut f = [1.0 + g; M];
+    for (m, f) in f.iter_mut().enumerate() {
+        for &x in &x[..M - 1 - m] {
+            *f *= (x.powf(alpha) * PI / 2.0).cos();
+        }
+        if m > 0 {
+            *f *= (x[M - 1 - m].powf(alpha) * PI / 2.0).sin();
+        }
+    }
+    f
+}
+
+/// DTLZ1, which has a linear front and many local fronts
+#[derive(Clone, Copy, Debug)]
+pub struct Dtlz1;
+
+impl<const M: usize> MultiTestFunction<M> for Dtlz1 {
+    const NAME: &'static str = "dtlz1";
+
+    fn bounds(dim: usize) -> Vec<(f64, f64)> {
+        dtlz_bounds(dim, M)
+    }
+
+    fn values(x: &[f64]) -> [f64; M] {
+        let g = dtlz1_g(&x[M - 1..]);
+        let mut f = [0.5 * (1.0 + g); M];
+        for (m, f) in f.iter_mut().enumerate() {
+            for &x in &x[..M - 1 - m] {
+                *f *= x;
+            }
+            if m > 0 {
+                *f *= 1.0 - x[M - 1 - m];
+            }
+        }
+        f
+    }
+
+    fn pareto_optimal(position: &[f64], dim: usize) -> Vec<f64> {
+        dtlz_optimal(position, dim)
+    }
+}
+
+/// DTLZ2, which has a spherical front
+#[derive(Clone, Copy, Debug)]
+pub struct Dtlz2;
+
+impl<const M: usize> MultiTestFunction<M> for Dtlz2 {
+    const NAME: &'static str = "dtlz2";
+
+    fn bounds(dim: usize) -> Vec<(f64, f64)> {
+        dtlz_bounds(dim, M)
+    }
+
+    fn values(x: &[f64]) -> [f64; M] {
+        dtlz_sphere(x, dtlz2_g(&x[M - 1..]), 1.0)
+    }
+
+    fn pareto_optimal(position: &[f64], dim: usize) -> Vec<f64> {
+        dtlz_optimal(position, dim)
+    }
+}
+
+/// DTLZ3, which has a spherical front and many local fronts
+#[derive(Clone, Copy, Debug)]
+pub struct Dtlz3;
+
+impl<const M: usize> MultiTestFunction<M> for Dtlz3 {
+    const NAME: &'static str = "dtlz3";
+
+    fn bounds(dim: usize) -> Vec<(f64, f64)> {
+        dtlz_bounds(dim, M)
+    }
+
+    fn values(x: &[f64]) -> [f64; M] {
+        dtlz_sphere(x, dtlz1_g(&x[M - 1..]), 1.0)
+    }
+
+    fn pareto_optimal(position: &[f64], dim: usize) -> Vec<f64> {
+        dtlz_optimal(position, dim)
+    }
+}
+
+/// DTLZ4, which has a spherical front and solutions that are unevenly distributed along it
+#[derive(Clone, Copy, Debug)]
+pub struct Dtlz4;
+
+impl<const M: usize> MultiTestFunction<M> for Dtlz4 {
+    const NAME: &'static str = "dtlz4";
+
+    fn bounds(dim: usize) -> Vec<(f64, f64)> {
+        dtlz_bounds(dim, M)
+    }
+
+    fn values(x: &[f64]) -> [f64; M] {
+        dtlz_sphere(x, dtlz2_g(&x[M - 1..]), 100.0)
+    }
+
+    fn pareto_optimal(position: &[f64], dim: usize) -> Vec<f64> {
+        // undo the bias, so the sampled front is spread evenly
+        dtlz_optimal(
+            &position.iter().map(|p| p.powf(0.01)).collect::<Vec<_>>(),
+            dim,
+        )
+    }
+}
+
+// WFG
+//
+// All WFG problems here use k = 2(M - 1) position variables and l = D - k distance variables.
+// Variable i (counting from 0) has bounds [0, 2(i + 1)].
+
+fn wfg_params(dim: usize, m: usize, even_l: bool) -> (usize, usize) {
+    assert!(m >= 2, "WFG problems need at least 2 objectives");
+    let k = 2 * (m - 1);
+    assert!(
+        dim > k,
+        "WFG problems with {} objectives need more than {} variables",
+        m,
+        k
+    );
+    let l = dim - k;
+    assert!(
+        !even_l || l.is_multiple_of(2),
+        "this WFG problem needs an even number of distance variables"
+    );
+    (k, l)
+}
+
+fn wfg_bounds(dim: usize) -> Vec<(f64, f64)> {
+    (0..dim).map(|i| (0.0, 2.0 * (i + 1) as f64)).collect()
+}
+
+fn wfg_normalize(z: &[f64]) -> Vec<f64> {
+    z.iter()
+        .enumerate()
+        .map(|(i, z)| z / (2.0 * (i + 1) as f64))
+        .collect()
+}
+
+fn clamp01(y: f64) -> f64 {
+    y.clamp(0.0, 1.0)
+}
+
+fn s_linear(y: f64, a: f64) -> f64 {
+    clamp01((y - a).abs() / ((a - y).floor() + a).abs())
+}
+
+fn s_deceptive(y: f64, a: f64, b: f64, c: f64) -> f64 {
+    let t1 = (y - a + b).floor() * (1.0 - c + (a - b) / b) / (a - b);
+    let t2 = (a + b - y).floor() * (1.0 - c + (1.0 - a - b) / b) / (1.0 - a - b);
+    clamp01(1.0 + ((y - a).abs() - b) * (t1 + t2 + 1.0 / b))
+}
+
+fn s_multi(y: f64, a: f64, b: f64, c: f64) -> f64 {
+    let t1 = (y - c).abs() / (2.0 * ((c - y).floor() + c));
+    let t2 = (4.0 * a + 2.0) * PI * (0.5 - t1);
+    clamp01((1.0 + t2.cos() + 4.0 * b * t1 * t1) / (b + 2.0))
+}
+
+fn b_flat(y: f64, a: f64, b: f64, c: f64) -> f64 {
+    clamp01(
+        a + (y - b).floor().min(0.0) * (a * (b - y) / b)
+            - (c - y).floor().min(0.0) * ((1.0 - a) * (y - c) / (1.0 - c)),
+    )
+}
+
+fn b_poly(y: f64, alpha: f64) -> f64 {
+    clamp01(y.powf(alpha))
+}
+
+const PARAM_A: f64 = 0.98 / 49.98;
+const PARAM_B: f64 = 0.02;
+const PARAM_C: f64 = 50.0;
+
+fn b_param_exponent(u: f64) -> f64 {
+    PARAM_B
+        + (PARAM_C - PARAM_B) * (PARAM_A - (1.0 - 2.0 * u) * ((0.5 - u).floor() + PARAM_A).abs())
+}
+
+fn b_param(y: f64, u: f64) -> f64 {
+    clamp01(y.powf(b_param_exponent(u)))
+}
+
+// The value of y for which b_param(y, u) = 0.35.
+fn b_param_inverse(u: f64) -> f64 {
+    0.35f64.powf(1.0 / b_param_exponent(u))
+}
+
+fn mean(y: &[f64]) -> f64 {
+    y.iter().sum::<f64>() / y.len() as f64
+}
+
+fn r_nonsep(y: &[f64], a: usize) -> f64 {
+    let n = y.len();
+    let mut num = 0.0;
+    for j in 0..n {
+        num += y[j];
+        for k in 0..a.saturating_sub(1) {
+            num += (y[j] - y[(j + k + 1) % n]).abs();
+        }
+    }
+    let half = (a as f64 / 2.0).ceil();
+    clamp01(num / ((n as f64 / a as f64) * half * (1.0 + 2.0 * a as f64 - 2.0 * half)))
+}
+
+// Reduce the k position variables to M - 1 values, one group at a time, and the rest to one value.
+fn wfg_reduce<const M: usize>(
+    y: &[f64],
+    k: usize,
+    reduce: impl Fn(&[f64], usize) -> f64,
+) -> [f64; M] {
+    let group = k / (M - 1);
+    let mut t = [0.0; M];
+    for (i, t) in t[..M - 1].iter_mut().enumerate() {
+        *t = reduce(&y[i * group..(i + 1) * group], i * group);
+    }
+    t[M - 1] = reduce(&y[k..], k);
+    t
+}
+
+// Weighted sum where the weight of the variable at index i (counting from 0) is 2(i + 1).
+fn r_sum_increasing(y: &[f64], start: usize) -> f64 {
+    let weights = (start..start + y.len()).map(|i| 2.0 * (i + 1) as f64);
+    let total: f64 = weights.clone().sum();
+    clamp01(y.iter().zip(weights).map(|(y, w)| y * w).sum::<f64>() / total)
+}
+
+type ShapeFn = fn(f64) -> f64;
+
+#[derive(Clone, Copy)]
+enum Shape {
+    Linear,
+    Convex,
+    Concave,
+}
+
+fn wfg_shape<const M: usize>(x: &[f64], shape: Shape) -> [f64; M] {
+    let (outer, last): (ShapeFn, ShapeFn) = match shape {
+        Shape::Linear => (|x| x, |x| 1.0 - x),
+        Shape::Convex => (
+            |x| 1.0 - (x * PI / 2.0).cos(),
+            |x| 1.0 - (x * PI / 2.0).sin(),
+        ),
+        Shape::Concave => (|x| (x * PI / 2.0).sin(), |x| (x * PI / 2.0).cos()),
+    };
+    let mut h = [1.0; M];
+    for (m, h) in h.iter_mut().enumerate() {
+        for &x in &x[..M - 1 - m] {
+            *h *= outer(x);
+        }
+        if m > 0 {
+            *h *= last(x[M - 1 - m]);
+        }
+    }
+    h
+}
+
+// Turn the reduced values into objectives, given which shape to use.
+// `last` optionally replaces the shape function for the last objective.
+fn wfg_finish<const M: usize>(
+    t: [f64; M],
+    degenerate: bool,
+    shape: Shape,
+    last: Option<ShapeFn>,
+) -> [f64; M] {
+    let mut x = t;
+    for (i, x) in x[..M - 1].iter_mut().enumerate() {
+        let a = if degenerate && i > 0 { 0.0 } else { 1.0 };
+        *x = t[M - 1].max(a) * (t[i] - 0.5) + 0.5;
+    }
+    let mut h = wfg_shape::<M>(&x, shape);
+    if let Some(last) = last {
+        h[M - 1] = last(x[0]);
+    }
+    let mut f = [0.0; M];
+    for (m, f) in f.iter_mut().enumerate() {
+        *f = x[M - 1] + 2.0 * (m + 1) as f64 * h[m];
+    }
+    f
+}
+
+// A Pareto-optimal solution where every distance variable is normalized to 0.35,
+// which is the optimum for WFG1-7.
+fn wfg_optimal(position: &[f64], dim: usize, m: usize) -> Vec<f64> {
+    let k = 2 * (m - 1);
+    let group = k / (m - 1);
+    (0..dim)
+        .map(|i| {
+            let y = if i < k { position[i / group] } else { 0.35 };
+            2.0 * (i + 1) as f64 * y
+        })
+        .collect()
+}
+
+// The WFG2 and WFG3 transformations, which reduce pairs of distance variables non-separably.
+fn wfg2_transform<const M: usize>(z: &[f64], k: usize, l: usize) -> [f64; M] {
+    let mut y = wfg_normalize(z);
+    for y in y[k..].iter_mut() {
+        *y = s_linear(*y, 0.35);
+    }
+    let mut t: Vec<f64> = y[..k].to_vec();
+    t.extend((0..l / 2).map(|i| r_nonsep(&y[k + 2 * i..k + 2 * i + 2], 2)));
+    wfg_reduce::<M>(&t, k, |y, _| mean(y))
+}
+
+macro_rules! wfg {
+    ($(#[$doc:meta])* $name:ident, $str:literal, even_l: $even:expr, |$z:ident, $k:ident, $l:ident| $values:expr, optimal: $optimal:expr) => {
+        $(#[$doc])*
+        #[derive(Clone, Copy, Debug)]
+        pub struct $name;
+
+        impl<const M: usize> MultiTestFunction<M> for $name {
+            const NAME: &'static str = $str;
+
+            fn bounds(dim: usize) -> Vec<(f64, f64)> {
+                wfg_params(dim, M, $even);
+                wfg_bounds(dim)
+            }
+
+            #[allow(unused_variables)]
+            fn values($z: &[f64]) -> [f64; M] {
+                let ($k, $l) = wfg_params($z.len(), M, $even);
+                $values
+            }
+
+            fn pareto_optimal(position: &[f64], dim: usize) -> Vec<f64> {
+                let optimal: fn(&[f64], usize, usize) -> Vec<f64> = $optimal;
+                optimal(position, dim, M)
+            }
+        }
+    };
+}
+
+wfg!(
+    /// WFG1, which has a front with both convex and mixed parts, and a flat region and polynomial bias
+    Wfg1, "wfg1", even_l: false, |z, k, l| {
+        let mut y = wfg_normalize(z);
+        for y in y[k..].iter_mut() {
+            *y = b_flat(s_linear(*y, 0.35), 0.8, 0.75, 0.85);
+        }
+        for y in y.iter_mut() {
+            *y = b_poly(*y, 0.02);
+        }
+        let t = wfg_reduce::<M>(&y, k, r_sum_increasing);
+        wfg_finish(t, false, Shape::Convex, Some(|x| {
+            1.0 - x - (10.0 * PI * x + PI / 2.0).cos() / (10.0 * PI)
+        }))
+    },
+    optimal: |position, dim, m| {
+        // undo the polynomial bias, so the sampled front is spread more evenly
+        let position: Vec<f64> = position.iter().map(|p| p.powf(50.0)).collect();
+        wfg_optimal(&position, dim, m)
+    }
+);
+
+wfg!(
+    /// WFG2, which has a disconnected convex front and non-separable distance variables
+    Wfg2, "wfg2", even_l: true, |z, k, l| {
+        wfg_finish(wfg2_transform::<M>(z, k, l), false, Shape::Convex, Some(|x| {
+            1.0 - x * (5.0 * PI * x).cos().powi(2)
+        }))
+    },
+    optimal: wfg_optimal
+);
+
+wfg!(
+    /// WFG3, which has a degenerate linear front and non-separable distance variables
+    Wfg3, "wfg3", even_l: true, |z, k, l| {
+        wfg_finish(wfg2_transform::<M>(z, k, l), true, Shape::Linear, None)
+    },
+    optimal: wfg_optimal
+);
+
+wfg!(
+    /// WFG4, which has a concave front and is highly multimodal
+    Wfg4, "wfg4", even_l: false, |z, k, l| {
+        let y: Vec<f64> = wfg_normalize(z).into_iter().map(|y| s_multi(y, 30.0, 10.0, 0.35)).collect();
+        wfg_finish(wfg_reduce::<M>(&y, k, |y, _| mean(y)), false, Shape::Concave, None)
+    },
+    optimal: wfg_optimal
+);
+
+wfg!(
+    /// WFG5, which has a concave front and is deceptive
+    Wfg5, "wfg5", even_l: false, |z, k, l| {
+        let y: Vec<f64> = wfg_normalize(z).into_iter().map(|y| s_deceptive(y, 0.35, 0.001, 0.05)).collect();
+        wfg_finish(wfg_reduce::<M>(&y, k, |y, _| mean(y)), false, Shape::Concave, None)
+    },
+    optimal: wfg_optimal
+);
+
+wfg!(
+    /// WFG6, which has a concave front and non-separable variables
+    Wfg6, "wfg6", even_l: false, |z, k, l| {
+        let mut y = wfg_normalize(z);
+        for y in y[k..].iter_mut() {
+            *y = s_linear(*y, 0.35);
+        }
+        let group = k / (M - 1);
+        let t = wfg_reduce::<M>(&y, k, |y, start| r_nonsep(y, if start < k { group } else { l }));
+        wfg_finish(t, false, Shape::Concave, None)
+    },
+    optimal: wfg_optimal
+);
+
+wfg!(
+    /// WFG7, which has a concave front and position variables biased by the distance variables
+    Wfg7, "wfg7", even_l: false, |z, k, l| {
+        let mut y = wfg_normalize(z);
+        for i in 0..k {
+            y[i] = b_param(y[i], mean(&y[i + 1..]));
+        }
+        for y in y[k..].iter_mut() {
+            *y = s_linear(*y, 0.35);
+        }
+        wfg_finish(wfg_reduce::<M>(&y, k, |y, _| mean(y)), false, Shape::Concave, None)
+    },
+    optimal: wfg_optimal
+);
+
+wfg!(
+    /// WFG8, which has a concave front and distance variables biased by the position variables
+    Wfg8, "wfg8", even_l: false, |z, k, l| {
+        let y0 = wfg_normalize(z);
+        let mut y = y0.clone();
+        for i in k..y.len() {
+            y[i] = s_linear(b_param(y0[i], mean(&y0[..i])), 0.35);
+        }
+        wfg_finish(wfg_reduce::<M>(&y, k, |y, _| mean(y)), false, Shape::Concave, None)
+    },
+    optimal: |position, dim, m| {
+        let mut z = wfg_optimal(position, dim, m);
+        let mut y = wfg_normalize(&z);
+        for i in 2 * (m - 1)..dim {
+            y[i] = b_param_inverse(mean(&y[..i]));
+            z[i] = 2.0 * (i + 1) as f64 * y[i];
+        }
+        z
+    }
+);
+
+wfg!(
+    /// WFG9, which has a concave front, non-separable variables, and is deceptive and multimodal
+    Wfg9, "wfg9", even_l: false, |z, k, l| {
+        let y0 = wfg_normalize(z);
+        let n = y0.len();
+        let mut y = y0.clone();
+        for i in 0..n - 1 {
+            y[i] = b_param(y0[i], mean(&y0[i + 1..]));
+        }
+        for (i, y) in y.iter_mut().enumerate() {
+            *y = if i < k {
+                s_deceptive(*y, 0.35, 0.001, 0.05)
+            } else {
+                s_multi(*y, 30.0, 95.0, 0.35)
+            };
+        }
+        let group = k / (M - 1);
+        let t = wfg_reduce::<M>(&y, k, |y, start| r_nonsep(y, if start < k { group } else { l }));
+        wfg_finish(t, false, Shape::Concave, None)
+    },
+    optimal: |position, dim, m| {
+        let mut z = wfg_optimal(position, dim, m);
+        let mut y = wfg_normalize(&z);
+        for i in (2 * (m - 1)..dim - 1).rev() {
+            y[i] = b_param_inverse(mean(&y[i + 1..]));
+            z[i] = 2.0 * (i + 1) as f64 * y[i];
+        }
+        z
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn front<F: MultiTestFunction<M>, const M: usize>(dim: usize) -> Vec<[f64; M]> {
+        let front = F::reference_front(10, dim);
+        assert!(!front.is_empty());
+        for f in front.iter() {
+            assert!(f.iter().all(|v| v.is_finite()), "{}: {:?}", F::NAME, f);
+        }
+        front
+    }
+
+    #[test]
+    fn fronts_have_known_shapes() {
+        for f in front::<Zdt1, 2>(30).into_iter().chain(front::<Zdt4, 2>(10)) {
+            assert!((f[1] - (1.0 - f[0].sqrt())).abs() < 1e-9);
+        }
+        for f in front::<Zdt2, 2>(30) {
+            assert!((f[1] - (1.0 - f[0] * f[0])).abs() < 1e-9);
+        }
+        front::<Zdt3, 2>(30);
+        front::<Zdt6, 2>(10);
+
+        for f in front::<Dtlz1, 3>(7) {
+            assert!((f.iter().sum::<f64>() - 0.5).abs() < 1e-9);
+        }
+        for f in front::<Dtlz2, 3>(12)
+            .into_iter()
+            .chain(front::<Dtlz3, 3>(12))
+            .chain(front::<Dtlz4, 3>(12))
+        {
+            assert!((f.iter().map(|v| v * v).sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+
+        front::<Wfg1, 2>(24);
+        front::<Wfg2, 2>(24);
+        front::<Wfg3, 3>(24);
+        let on_sphere = |f: [f64; 3]| {
+            let r: f64 = f
+                .iter()
+                .enumerate()
+                .map(|(m, v)| (v / (2.0 * (m + 1) as f64)).powi(2))
+                .sum();
+            (r - 1.0).abs() < 1e-6
+        };
+        for f in front::<Wfg4, 3>(24)
+            .into_iter()
+            .chain(front::<Wfg5, 3>(24))
+            .chain(front::<Wfg6, 3>(24))
+            .chain(front::<Wfg7, 3>(24))
+            .chain(front::<Wfg8, 3>(24))
+            .chain(front::<Wfg9, 3>(24))
+        {
+            assert!(on_sphere(f), "{:?}", f);
+        }
+    }
+
+    #[test]
+    fn values() {
+        let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-9);
+        let mut x = vec![0.25; 30];
+        x[1..].fill(0.0);
+        assert!(close(&Zdt1::values(&x), &[0.25, 0.5]));
+        assert!(close(&Zdt2::values(&x), &[0.25, 0.9375]));
+        // g = 10 when every distance variable is 1
+        x[1..].fill(1.0);
+        assert!(close(&Zdt1::values(&x), &[0.25, 10.0 - 0.5 * 10f64.sqrt()]));
+
+        let x = [0.5; 4];
+        assert!(close(
+            &<Dtlz1 as MultiTestFunction<3>>::values(&x),
+            &[0.125, 0.125, 0.25]
+        ));
+        let x = [0.0, 1.0, 0.5];
+        assert!(close(
+            &<Dtlz2 as MultiTestFunction<3>>::values(&x),
+            &[0.0, 1.0, 0.0]
+        ));
+        // moving a distance variable away from 0.5 moves off the front
+        let x = [0.0, 1.0, 1.0];
+        assert!(close(
+            &<Dtlz2 as MultiTestFunction<3>>::values(&x),
+            &[0.0, 1.25, 0.0]
+        ));
+    }
+
+    fn undominated<F: MultiTestFunction<M>, const D: usize, const M: usize>() {
+        let front = F::reference_front(8, D);
+        for _ in 0..200 {
+            let random = MultiBenchmark::<F, D, M>::generate().values();
+            for f in &front {
+                let dominates = random.iter().zip(f).all(|(r, f)| *r < f - 1e-9);
+                assert!(!dominates, "{}: {:?} dominates {:?}", F::NAME, random, f);
+            }
+        }
+    }
+
+    #[test]
+    fn random_solutions_never_dominate_the_front() {
+        undominated::<Zdt1, 10, 2>();
+        undominated::<Zdt2, 10, 2>();
+        undominated::<Zdt3, 10, 2>();
+        undominated::<Zdt4, 10, 2>();
+        undominated::<Zdt6, 10, 2>();
+        undominated::<Dtlz1, 7, 3>();
+        undominated::<Dtlz2, 12, 3>();
+        undominated::<Dtlz3, 12, 3>();
+        undominated::<Dtlz4, 12, 3>();
+        undominated::<Wfg1, 24, 2>();
+        undominated::<Wfg2, 24, 2>();
+        undominated::<Wfg4, 24, 3>();
+        undominated::<Wfg9, 24, 3>();
+    }
+
+    #[test]
+    fn benchmark_solutions() {
+        type Problem = MultiBenchmark<Zdt1, 10, 2>;
+        let bounds = Zdt1::bounds(10);
+        let in_bounds = |x: &Problem| {
+            x.iter()
+                .zip(&bounds)
+                .all(|(x, (lower, upper))| (*lower..=*upper).contains(x))
+        };
+        for _ in 0..50 {
+            let (mut a, mut b) = (Problem::generate(), Problem::generate());
+            Problem::crossover(&mut a, &mut b);
+            a.mutate();
+            assert!(in_bounds(&a) && in_bounds(&b));
+            assert_eq!(
+                a.evaluate(),
+                MultiObjective::new_unweighted(a.values().map(|v| -v))
+            );
+        }
+        assert!(Problem::new(vec![0.5; 9]).is_none());
+        assert!(Problem::new(vec![1.5; 10]).is_none());
+
+        let reference = Problem::reference_fitnesses(11);
+        let front = Zdt1::reference_front(11, 10);
+        assert_eq!(reference.len(), 11);
+        for (fitness, values) in reference.iter().zip(&front) {
+            assert_eq!(*fitness, MultiObjective::new_unweighted(values.map(|v| -v)));
+        }
+    }
+
+    #[test]
+    fn nondominated_points() {
+        let points = vec![[1.0, 2.0], [2.0, 1.0], [2.0, 2.0], [1.0, 2.0], [0.5, 3.0]];
+        assert_eq!(
+            nondominated(points),
+            [[1.0, 2.0], [2.0, 1.0], [1.0, 2.0], [0.5, 3.0]]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn zdt_needs_two_variables() {
+        Zdt1::bounds(1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn dtlz_needs_enough_variables() {
+        <Dtlz2 as MultiTestFunction<3>>::bounds(2);
+    }
+}
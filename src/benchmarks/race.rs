@@ -0,0 +1,305 @@
+//! Choosing between algorithm configurations by racing them
+//!
+//! A [`Race`] runs a set of candidate configurations on one problem instance after another,
+//! the way F-Race[^1] does, and drops candidates as soon as there's enough evidence that they're
+//! worse than the best one, so that the remaining runs are spent on the candidates that are still
+//! in contention. This usually takes a fraction of the runs a full [`Comparison`] would.
+//!
+//! Every candidate that is still racing is run once on each block, a pair of a problem instance
+//! and a seed. From the `first_test`th block on, the candidates are compared after each block
+//! with the [Friedman test], and if it finds a difference, every candidate that the
+//! [Wilcoxon signed-rank test] finds to be worse than the one with the best mean rank is eliminated.
+//! The original F-Race uses Conover's post-hoc test for that second step instead.
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::bbob::{self, BbobProblem, BbobSolution};
+//! use eviolite::benchmarks::race::Race;
+//!
+//! let results = Race::new()
+//!     .with_candidate("(5 + 10)", (5, 10))
+//!     .with_candidate("(10 + 20)", (10, 20))
+//!     .with_candidate("(20 + 40)", (20, 40))
+//!     .with_instance("f1", BbobProblem::new(1, 1, 2).unwrap())
+//!     .with_instance("f8", BbobProblem::new(8, 1, 2).unwrap())
+//!     .with_seeds(0..5)
+//!     .minimizing()
+//!     .run(|&(mu, lambda), problem| {
+//!         bbob::observe(problem.clone(), 200, |observed| {
+//!             let evo: Evolution<BbobSolution, _, _, ()> = Evolution::new(
+//!                 alg::MuPlusLambda::new(mu, lambda, 0.6, 0.3, select::Tournament::new(3)),
+//!                 hof::BestN::new(1),
+//!             );
+//!             evo.run_until(|_| observed.is_done());
+//!         })
+//!         .best_delta
+//!     });
+//! println!("{} won after {} runs", results.candidates()[results.best()], results.runs());
+//! ```
+//!
+//! [^1]: Birattari, M., Stützle, T., Paquete, L., and Varrentrapp, K. "A Racing Algorithm for Configuring Metaheuristics." 2002.
+//!
+//! [`Race`]: ./struct.Race.html
+//! [`Comparison`]: ../compare/struct.Comparison.html
+//! [Friedman test]: ../compare/fn.friedman.html
+//! [Wilcoxon signed-rank test]: ../compare/fn.wilcoxon_signed_rank.html
+
+use crate::{
+    benchmarks::compare::{friedman, wilcoxon_signed_rank},
+    repro_rng,
+};
+
+/// A set of candidate configurations to race on a set of problem instances
+///
+/// Candidates and instances can be of any type; they are only ever passed to the closure given to [`.run()`],
+/// which decides what they mean.
+///
+/// [`.run()`]: ./struct.Race.html#method.run
+#[derive(Clone, Debug)]
+pub struct Race<C, P> {
+    candidates: Vec<(String, C)>,
+    instances: Vec<(String, P)>,
+    seeds: Vec<u64>,
+    first_test: usize,
+    alpha: f64,
+    budget: Option<usize>,
+    minimize: bool,
+}
+
+impl<C, P> Race<C, P> {
+    /// Create an empty race, using seeds 0 to 29, testing from the 5th block on
+    /// at a significance level of 0.05, and treating higher scores as better.
+    pub fn new() -> Self {
+        Race {
+            candidates: Vec::new(),
+            instances: Vec::new(),
+            seeds: (0..30).collect(),
+            first_test: 5,
+            alpha: 0.05,
+            budget: None,
+            minimize: false,
+        }
+    }
+
+    /// Add a candidate configuration.
+    pub fn with_candidate(mut self, name: impl Into<String>, candidate: C) -> Self {
+        self.candidates.push((name.into(), candidate));
+        self
+    }
+
+    /// Add a problem instance.
+    pub fn with_instance(mut self, name: impl Into<String>, instance: P) -> Self {
+        self.instances.push((name.into(), instance));
+        self
+    }
+
+    /// Run the candidates on every instance with each of `seeds`, in that order:
+    /// all instances with the first seed, then all instances with the second seed, and so on.
+    pub fn with_seeds(mut self, seeds: impl IntoIterator<Item = u64>) -> Self {
+        self.seeds = seeds.into_iter().collect();
+        self
+    }
+
+    /// Start eliminating candidates once they've been run on `first_test` blocks.
+    pub fn with_first_test(mut self, first_test: usize) -> Self {
+        self.first_test = first_test;
+        self
+    }
+
+    /// Use `alpha` as the significance level of the tests.
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Stop the race before the total number of runs would exceed `budget`.
+    pub fn with_budget(mut self, budget: usize) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Treat lower scores as better, e.g. when scores are errors or objective values to be minimized.
+    pub fn minimizing(mut self) -> Self {
+        self.minimize = true;
+        self
+    }
+
+    /// Run the race, calling `run` once for every candidate that is still racing on each block.
+    ///
+    /// The race ends when only one candidate is left, every block has been run, or the budget is used up.
+    /// Before each call, this thread's [reproducible RNG] is re-seeded with the seed of the block.
+    /// `run` should perform one complete run and return its final score.
+    ///
+    /// [reproducible RNG]: ../../repro_rng/fn.reseed.html
+    pub fn run(&self, mut run: impl FnMut(&C, &P) -> f64) -> RaceResults {
+        let k = self.candidates.len();
+        let mut scores: Vec<Vec<f64>> = vec![Vec::new(); k];
+        let mut eliminated: Vec<Option<usize>> = vec![None; k];
+        let mut blocks = 0;
+        let mut runs = 0;
+
+        let oriented = |score: f64| if self.minimize { -score } else { score };
+        let mut racing: Vec<usize> = (0..k).collect();
+        'race: for &seed in &self.seeds {
+            for (_, instance) in &self.instances {
+                if racing.len() <= 1 || self.budget.is_some_and(|b| runs + racing.len() > b) {
+                    break 'race;
+                }
+                for &c in &racing {
+                    repro_rng::reseed(seed);
+                    scores[c].push(run(&self.candidates[c].1, instance));
+                    runs += 1;
+                }
+                blocks += 1;
+                if blocks < self.first_test.max(2) {
+                    continue;
+                }
+
+                // every candidate that is still racing has a score for every block so far
+                let matrix: Vec<Vec<f64>> = (0..blocks)
+                    .map(|b| racing.iter().map(|&c| oriented(scores[c][b])).collect())
+                    .collect();
+                let (test, mean_ranks) = friedman(&matrix);
+                if racing.len() > 2 && test.p_value >= self.alpha {
+                    continue;
+                }
+                let best = best_rank(&mean_ranks);
+                let best_scores = &scores[racing[best]];
+                let worse: Vec<usize> = racing
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, &c)| {
+                        i != best
+                            && wilcoxon_signed_rank(best_scores, &scores[c]).p_value < self.alpha
+                    })
+                    .map(|(_, &c)| c)
+                    .collect();
+                for c in worse {
+                    eliminated[c] = Some(blocks);
+                    racing.retain(|&r| r != c);
+                }
+            }
+        }
+
+        let matrix: Vec<Vec<f64>> = (0..blocks)
+            .map(|b| racing.iter().map(|&c| oriented(scores[c][b])).collect())
+            .collect();
+        let best = match racing.len() {
+            0 => 0,
+            _ => racing[best_rank(&friedman(&matrix).1)],
+        };
+        RaceResults {
+            candidates: self
+                .candidates
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect(),
+            scores,
+            eliminated,
+            best,
+            blocks,
+            runs,
+        }
+    }
+}
+
+impl<C, P> Default for Race<C, P> {
+    fn default() -> Self {
+        Race::new()
+    }
+}
+
+// The index of the lowest mean rank, which is the best; ties go to the first one
+fn best_rank(mean_ranks: &[f64]) -> usize {
+    (0..mean_ranks.len())
+        .min_by(|&a, &b| mean_ranks[a].total_cmp(&mean_ranks[b]))
+        .unwrap_or(0)
+}
+
+/// The outcome of a [`Race`]
+///
+/// [`Race`]: ./struct.Race.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct RaceResults {
+    candidates: Vec<String>,
+    scores: Vec<Vec<f64>>,
+    eliminated: Vec<Option<usize>>,
+    best: usize,
+    blocks: usize,
+    runs: usize,
+}
+
+impl RaceResults {
+    /// Get the names of the candidates, in the order they were added.
+    pub fn candidates(&self) -> &[String] {
+        &self.candidates
+    }
+
+    /// Get the index of the best candidate, which is the one with the best mean rank
+    /// among those that were never eliminated.
+    pub fn best(&self) -> usize {
+        self.best
+    }
+
+    /// Get the indices of the candidates that were never eliminated.
+    pub fn survivors(&self) -> Vec<usize> {
+        (0..self.candidates.len())
+            .filter(|&c| self.eliminated[c].is_none())
+            .collect()
+    }
+
+    /// Get the number of blocks after which a candidate was eliminated,
+    /// or `None` if it never was.
+    pub fn eliminated_after(&self, candidate: usize) -> Option<usize> {
+        self.eliminated[candidate]
+    }
+
+    /// Get the scores of a candidate, one for every block it was run on, in order.
+    pub fn scores(&self, candidate: usize) -> &[f64] {
+        &self.scores[candidate]
+    }
+
+    /// Get the number of blocks that were run.
+    pub fn blocks(&self) -> usize {
+        self.blocks
+    }
+
+    /// Get the total number of runs.
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repro_rng::thread_rng;
+    use rand::Rng;
+
+    #[test]
+    fn poor_candidates_are_dropped_early() {
+        let race = Race::new()
+            .with_candidate("a", 0.0)
+            .with_candidate("b", 0.1)
+            .with_candidate("c", 3.0)
+            .with_candidate("d", 3.05)
+            .with_instance("x", 0.0)
+            .with_instance("y", 1.0)
+            .with_seeds(0..15)
+            .minimizing();
+        let noisy = |&mean: &f64, &offset: &f64| mean + offset + thread_rng().gen_range(-0.5..0.5);
+        let results = race.run(noisy);
+
+        assert!(results.runs() < 4 * 30);
+        assert!(results.eliminated_after(2).is_some());
+        assert!(results.eliminated_after(3).is_some());
+        assert!(results.survivors().contains(&results.best()));
+        assert!(results.best() < 2);
+        assert_eq!(results.scores(0).len(), results.blocks());
+
+        let capped = race.with_budget(10).run(noisy);
+        assert_eq!(capped.runs(), 8);
+        assert_eq!(capped.blocks(), 2);
+        assert!(capped.survivors().len() == 4);
+    }
+}
@@ -0,0 +1,317 @@
+//! Tuning the parameters of an algorithm by evolving them
+//!
+//! A [`Tuner`] runs a small evolutionary algorithm of its own over [`Params`]:
+//! the population size, crossover and mutation probabilities, and tournament size
+//! of an inner run. Each set of parameters is scored by a closure that performs one complete
+//! inner run with them and returns a meta-objective, such as the best fitness found
+//! within an evaluation budget. Like a [`Comparison`], every set of parameters is run
+//! once with each of a list of seeds, and its score is the mean over those runs.
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::{Benchmark, Sphere};
+//! use eviolite::benchmarks::tune::{ParamSpace, Tuner};
+//!
+//! let tuning = Tuner::new(ParamSpace {
+//!     pop_size: 10..=50,
+//!     ..ParamSpace::default()
+//! })
+//! .with_population(4)
+//! .with_generations(2)
+//! .with_seeds(0..2)
+//! .run(|params| {
+//!     // the best fitness found with 500 evaluations
+//!     let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> =
+//!         Evolution::new(params.simple(), hof::BestN::new(1));
+//!     let log = evo.run_for_evaluations(500);
+//!     log.hall_of_fame[0].evaluate()
+//! });
+//! println!("{:?} scored {}", tuning.best(), tuning.score());
+//! ```
+//!
+//! [`Tuner`]: ./struct.Tuner.html
+//! [`Params`]: ./struct.Params.html
+//! [`Comparison`]: ../compare/struct.Comparison.html
+
+use std::ops::RangeInclusive;
+
+use rand::{Rng, SeedableRng};
+use rand_distr::StandardNormal;
+use rand_xoshiro::Xoshiro256PlusPlus;
+
+use crate::{alg, repro_rng, select::Tournament, Solution};
+
+/// The parameters of an algorithm that a [`Tuner`] evolves
+///
+/// [`Tuner`]: ./struct.Tuner.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Params {
+    /// The population size.
+    pub pop_size: usize,
+    /// The probability of crossover.
+    pub cxpb: f64,
+    /// The probability of mutation.
+    pub mutpb: f64,
+    /// The number of participants in each round of tournament selection.
+    pub tournament_size: usize,
+}
+
+impl Params {
+    /// Create a [`Tournament`] selector with these parameters.
+    ///
+    /// [`Tournament`]: ../../select/struct.Tournament.html
+    pub fn tournament(&self) -> Tournament {
+        Tournament::new(self.tournament_size)
+    }
+
+    /// Create a [`Simple`] algorithm with these parameters.
+    ///
+    /// [`Simple`]: ../../alg/struct.Simple.html
+    pub fn simple<T>(&self) -> alg::Simple<T, Tournament>
+    where
+        T: Solution,
+        T::Fitness: Into<f64>,
+    {
+        alg::Simple::new(self.pop_size, self.cxpb, self.mutpb, self.tournament())
+    }
+}
+
+/// The ranges a [`Tuner`] searches for each of the [`Params`]
+///
+/// The default ranges are 10 to 200 for the population size, 0 to 1 for both probabilities,
+/// and 2 to 7 for the tournament size.
+///
+/// [`Tuner`]: ./struct.Tuner.html
+/// [`Params`]: ./struct.Params.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParamSpace {
+    /// The range of population sizes.
+    pub pop_size: RangeInclusive<usize>,
+    /// The range of crossover probabilities.
+    pub cxpb: RangeInclusive<f64>,
+    /// The range of mutation probabilities.
+    pub mutpb: RangeInclusive<f64>,
+    /// The range of tournament sizes.
+    pub tournament_size: RangeInclusive<usize>,
+}
+
+impl Default for ParamSpace {
+    fn default() -> Self {
+        ParamSpace {
+            pop_size: 10..=200,
+            cxpb: 0.0..=1.0,
+            mutpb: 0.0..=1.0,
+            tournament_size: 2..=7,
+        }
+    }
+}
+
+impl ParamSpace {
+    fn sample<R: Rng>(&self, rng: &mut R) -> Params {
+        Params {
+            pop_size: rng.gen_range(self.pop_size.clone()),
+            cxpb: rng.gen_range(self.cxpb.clone()),
+            mutpb: rng.gen_range(self.mutpb.clone()),
+            tournament_size: rng.gen_range(self.tournament_size.clone()),
+        }
+    }
+
+    // Uniform crossover of the two parents, followed by a Gaussian step in every parameter
+    // with a standard deviation of a tenth of its range.
+    fn offspring<R: Rng>(&self, rng: &mut R, a: &Params, b: &Params) -> Params {
+        let mut pick = |x: f64, y: f64| if rng.gen_bool(0.5) { x } else { y };
+        let pop_size = pick(a.pop_size as f64, b.pop_size as f64);
+        let cxpb = pick(a.cxpb, b.cxpb);
+        let mutpb = pick(a.mutpb, b.mutpb);
+        let tournament_size = pick(a.tournament_size as f64, b.tournament_size as f64);
+
+        let mut step = |x: f64, range: (f64, f64)| {
+            let z: f64 = rng.sample(StandardNormal);
+            (x + z * (range.1 - range.0) / 10.0).clamp(range.0, range.1)
+        };
+        let int = |range: &RangeInclusive<usize>| (*range.start() as f64, *range.end() as f64);
+        let float = |range: &RangeInclusive<f64>| (*range.start(), *range.end());
+        Params {
+            pop_size: step(pop_size, int(&self.pop_size)).round() as usize,
+            cxpb: step(cxpb, float(&self.cxpb)),
+            mutpb: step(mutpb, float(&self.mutpb)),
+            tournament_size: step(tournament_size, int(&self.tournament_size)).round() as usize,
+        }
+    }
+}
+
+/// A meta-evolutionary search for the [`Params`] that score best
+///
+/// The search is a (μ + μ) evolutionary algorithm: each generation, every set of parameters
+/// in the population has one offspring made by uniform crossover with another set chosen by
+/// binary tournament and a small Gaussian mutation, and the best half of the parents and offspring
+/// survive. Its own randomness is drawn from a stream seeded once from the [reproducible RNG],
+/// so the search is reproducible even though the RNG is re-seeded before every inner run.
+///
+/// [`Params`]: ./struct.Params.html
+/// [reproducible RNG]: ../../repro_rng/index.html
+#[derive(Clone, Debug)]
+pub struct Tuner {
+    space: ParamSpace,
+    pop_size: usize,
+    generations: usize,
+    seeds: Vec<u64>,
+    minimize: bool,
+}
+
+impl Tuner {
+    /// Create a tuner that searches `space` with a population of 8 for 5 generations,
+    /// using seeds 0 to 4 and treating higher scores as better.
+    pub fn new(space: ParamSpace) -> Self {
+        Tuner {
+            space,
+            pop_size: 8,
+            generations: 5,
+            seeds: (0..5).collect(),
+            minimize: false,
+        }
+    }
+
+    /// Keep `pop_size` sets of parameters in the population.
+    pub fn with_population(mut self, pop_size: usize) -> Self {
+        self.pop_size = pop_size;
+        self
+    }
+
+    /// Evolve the parameters for `generations` generations after the initial random population.
+    pub fn with_generations(mut self, generations: usize) -> Self {
+        self.generations = generations;
+        self
+    }
+
+    /// Score each set of parameters by running it once with each of `seeds`.
+    pub fn with_seeds(mut self, seeds: impl IntoIterator<Item = u64>) -> Self {
+        self.seeds = seeds.into_iter().collect();
+        self
+    }
+
+    /// Treat lower scores as better, e.g. when scores are errors or objective values to be minimized.
+    pub fn minimizing(mut self) -> Self {
+        self.minimize = true;
+        self
+    }
+
+    /// Run the search, calling `run` once for every set of parameters and seed.
+    ///
+    /// Before each call, this thread's [reproducible RNG] is re-seeded with the seed of the run.
+    /// `run` should perform one complete run with the parameters and return its score.
+    ///
+    /// [reproducible RNG]: ../../repro_rng/fn.reseed.html
+    pub fn run(&self, mut run: impl FnMut(&Params) -> f64) -> Tuning {
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(repro_rng::thread_rng().gen());
+        let mut history = Vec::new();
+        let mut score = |params: Params| {
+            let total: f64 = self
+                .seeds
+                .iter()
+                .map(|&seed| {
+                    repro_rng::reseed(seed);
+                    run(&params)
+                })
+                .sum();
+            let score = total / self.seeds.len() as f64;
+            history.push((params, score));
+            (params, score)
+        };
+        let better = |a: f64, b: f64| if self.minimize { a < b } else { a > b };
+
+        let mut pop: Vec<(Params, f64)> = (0..self.pop_size.max(1))
+            .map(|_| score(self.space.sample(&mut rng)))
+            .collect();
+        for _ in 0..self.generations {
+            let mut offspring = Vec::with_capacity(pop.len());
+            for (a, _) in &pop {
+                let x = &pop[rng.gen_range(0..pop.len())];
+                let y = &pop[rng.gen_range(0..pop.len())];
+                let b = if better(y.1, x.1) { &y.0 } else { &x.0 };
+                offspring.push(self.space.offspring(&mut rng, a, b));
+            }
+            pop.extend(offspring.into_iter().map(&mut score));
+            pop.sort_by(|a, b| {
+                let order = a.1.total_cmp(&b.1);
+                if self.minimize {
+                    order
+                } else {
+                    order.reverse()
+                }
+            });
+            pop.truncate(self.pop_size.max(1));
+        }
+
+        let (best, score) = pop[0];
+        Tuning {
+            best,
+            score,
+            history,
+        }
+    }
+}
+
+/// The outcome of a [`Tuner`] search
+///
+/// [`Tuner`]: ./struct.Tuner.html
+#[derive(Clone, Debug)]
+pub struct Tuning {
+    best: Params,
+    score: f64,
+    history: Vec<(Params, f64)>,
+}
+
+impl Tuning {
+    /// Get the best parameters that were found.
+    pub fn best(&self) -> &Params {
+        &self.best
+    }
+
+    /// Get the mean score of the best parameters.
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+
+    /// Get every set of parameters that was tried, with its mean score, in the order they were tried.
+    pub fn history(&self) -> &[(Params, f64)] {
+        &self.history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tuner_improves_score() {
+        repro_rng::reseed(5);
+        let space = ParamSpace::default();
+        let tuning = Tuner::new(space.clone())
+            .with_population(6)
+            .with_generations(10)
+            .with_seeds(0..2)
+            .minimizing()
+            .run(|params| {
+                (params.cxpb - 0.7).powi(2)
+                    + (params.mutpb - 0.2).powi(2)
+                    + (params.pop_size as f64 - 50.0).abs() / 100.0
+            });
+
+        assert_eq!(tuning.history().len(), 6 * 11);
+        let first = tuning.history()[..6]
+            .iter()
+            .map(|(_, score)| *score)
+            .fold(f64::INFINITY, f64::min);
+        assert!(tuning.score() <= first);
+        assert!(tuning
+            .history()
+            .iter()
+            .all(|(_, score)| *score >= tuning.score()));
+
+        let best = tuning.best();
+        assert!(space.pop_size.contains(&best.pop_size));
+        assert!(space.cxpb.contains(&best.cxpb));
+        assert!(space.tournament_size.contains(&best.tournament_size));
+    }
+}
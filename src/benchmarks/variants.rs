@@ -0,0 +1,569 @@
+//! Noisy and dynamic versions of other benchmarks
+//!
+//! [`Noisy<S, L>`] adds Gaussian noise to the fitness of any solution with `f64` fitness,
+//! and [`Dynamic<B, S>`] turns a [`Benchmark`] or [`BitBenchmark`] into a problem whose
+//! optimum moves every few generations. These are controlled testbeds for techniques that deal
+//! with uncertain fitness and changing environments.
+//!
+//! A dynamic problem reads the current generation from a [`Clock`], which has to be advanced
+//! by the caller, usually from the callback of [`Evolution::run_for_with`].
+//! Every `PERIOD` generations the problem changes to a new random shift, which is derived
+//! from the [reproducible seed] and the number of changes so far, so runs can be reproduced.
+//! Fitness values already stored in [`Cached`] solutions are not invalidated when the problem changes
+//! unless the run is told about the changes with [`Evolution::with_environment_changes`].
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::{Benchmark, Sphere};
+//! use eviolite::benchmarks::variants::{Clock, Dynamic, Schedule};
+//!
+//! struct EveryTen;
+//!
+//! impl Schedule for EveryTen {
+//!     const PERIOD: usize = 10;
+//!     const SEVERITY: f64 = 1.0;
+//!
+//!     fn clock() -> &'static Clock {
+//!         static CLOCK: Clock = Clock::new();
+//!         &CLOCK
+//!     }
+//! }
+//!
+//! let evo: Evolution<Dynamic<Benchmark<Sphere, 5>, EveryTen>, _, _, ()> = Evolution::new(
+//!     alg::MuPlusLambda::new(20, 40, 0.6, 0.3, select::Tournament::new(3)),
+//!     hof::BestN::new(1),
+//! )
+//! .with_environment_changes(EveryTen::PERIOD);
+//! evo.run_for_with(30, |generation| EveryTen::clock().set(generation.gen + 1));
+//! ```
+//!
+//! [`Noisy<S, L>`]: ./struct.Noisy.html
+//! [`Dynamic<B, S>`]: ./struct.Dynamic.html
+//! [`Benchmark`]: ../struct.Benchmark.html
+//! [`BitBenchmark`]: ../binary/struct.BitBenchmark.html
+//! [`Clock`]: ./struct.Clock.html
+//! [`Evolution::run_for_with`]: ../../struct.Evolution.html#method.run_for_with
+//! [`Evolution::with_environment_changes`]: ../../struct.Evolution.html#method.with_environment_changes
+//! [reproducible seed]: ../../repro_rng/fn.seed.html
+//! [`Cached`]: ../../struct.Cached.html
+
+use std::{
+    fmt::Debug,
+    marker::PhantomData,
+    ops::Deref,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use rand::{seq::index, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+use rand_xoshiro::Xoshiro256PlusPlus;
+
+use crate::{
+    benchmarks::{
+        binary::{BitBenchmark, BitFunction},
+        continuous::{Benchmark, TestFunction},
+    },
+    fitness::Direction,
+    repro_rng::{seed, thread_rng},
+    Solution,
+};
+
+/// The standard deviation of the noise added by [`Noisy`]
+///
+/// [`Noisy`]: ./struct.Noisy.html
+pub trait NoiseLevel: 'static {
+    /// The standard deviation of the noise.
+    const SIGMA: f64;
+}
+
+/// A [`NoiseLevel`] of `MILLIS / 1000`
+///
+/// [`NoiseLevel`]: ./trait.NoiseLevel.html
+#[derive(Clone, Copy, Debug)]
+pub struct Sigma<const MILLIS: u32>;
+
+impl<const MILLIS: u32> NoiseLevel for Sigma<MILLIS> {
+    const SIGMA: f64 = MILLIS as f64 / 1000.0;
+}
+
+/// A [`Solution`] whose fitness has Gaussian noise added to it
+///
+/// Every evaluation adds a fresh sample from a normal distribution with mean 0
+/// and standard deviation `L::SIGMA` to the fitness of the wrapped solution.
+/// Generation and variation are passed through unchanged.
+/// This type derefs to the solution it contains.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Rastrigin};
+/// use eviolite::benchmarks::variants::{Noisy, Sigma};
+///
+/// // noise with a standard deviation of 0.5
+/// type Problem = Noisy<Benchmark<Rastrigin, 10>, Sigma<500>>;
+/// # let _ = Problem::generate();
+/// ```
+///
+/// [`Solution`]: ../../trait.Solution.html
+pub struct Noisy<S, L> {
+    inner: S,
+    _phantom: PhantomData<fn() -> L>,
+}
+
+impl<S: Solution<Fitness = f64>, L: NoiseLevel> Noisy<S, L> {
+    /// Wrap an existing solution.
+    pub fn new(inner: S) -> Self {
+        Noisy {
+            inner,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get the fitness of the wrapped solution without any noise.
+    pub fn true_fitness(&self) -> f64 {
+        self.inner.evaluate()
+    }
+
+    /// Consume the wrapper, returning the solution it contained.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Solution<Fitness = f64>, L: NoiseLevel> Solution for Noisy<S, L> {
+    type Fitness = f64;
+    const DIRECTIONS: &'static [Direction] = S::DIRECTIONS;
+
+    fn generate() -> Self {
+        Noisy::new(S::generate())
+    }
+
+    fn evaluate(&self) -> f64 {
+        let noise: f64 = thread_rng().sample(StandardNormal);
+        self.inner.evaluate() + L::SIGMA * noise
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        S::crossover(&mut a.inner, &mut b.inner);
+    }
+
+    fn mutate(&mut self) {
+        self.inner.mutate();
+    }
+
+    fn repair(&mut self) {
+        self.inner.repair();
+    }
+}
+
+/// A generation counter shared by every solution of a [`Dynamic`] problem
+///
+/// Clocks can be created in a `const` context, so they can be stored directly in a `static`.
+///
+/// [`Dynamic`]: ./struct.Dynamic.html
+#[derive(Debug, Default)]
+pub struct Clock {
+    generation: AtomicUsize,
+}
+
+impl Clock {
+    /// Create a clock starting at generation 0.
+    pub const fn new() -> Self {
+        Clock {
+            generation: AtomicUsize::new(0),
+        }
+    }
+
+    /// Get the current generation.
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    /// Set the current generation.
+    pub fn set(&self, generation: usize) {
+        self.generation.store(generation, Ordering::Relaxed);
+    }
+
+    /// Advance the clock by one generation, returning the new generation.
+    pub fn tick(&self) -> usize {
+        self.generation.fetch_add(1, Ordering::Relaxed) + 1
+    }
+}
+
+/// How often and how much a [`Dynamic`] problem changes
+///
+/// [`Dynamic`]: ./struct.Dynamic.html
+pub trait Schedule: 'static {
+    /// The number of generations between changes. If this is 0, the problem never changes.
+    const PERIOD: usize;
+
+    /// How much the problem changes each time. See [`Shift`] for what this means for each benchmark.
+    ///
+    /// [`Shift`]: ./trait.Shift.html
+    const SEVERITY: f64;
+
+    /// Get the clock that tracks the current generation.
+    fn clock() -> &'static Clock;
+
+    /// Get the number of times the problem has changed so far.
+    fn epoch() -> u64 {
+        match Self::PERIOD {
+            0 => 0,
+            period => (Self::clock().generation() / period) as u64,
+        }
+    }
+}
+
+/// A benchmark that can be evaluated on a randomly shifted version of its problem
+pub trait Shift: Solution<Fitness = f64> {
+    /// Evaluate the fitness of this solution in a version of the problem changed by a random shift
+    /// of the given `severity`, drawn using `rng`.
+    ///
+    /// Every call with an identically seeded `rng` and the same `severity`
+    /// must describe the same shift.
+    fn evaluate_shifted<R: Rng>(&self, rng: &mut R, severity: f64) -> f64;
+}
+
+/// Shifts the optimum by `severity` in a random direction, staying within the bounds.
+impl<F: TestFunction, const D: usize> Shift for Benchmark<F, D> {
+    fn evaluate_shifted<R: Rng>(&self, rng: &mut R, severity: f64) -> f64 {
+        let direction: Vec<f64> = (0..D).map(|_| rng.sample(StandardNormal)).collect();
+        let norm = direction.iter().map(|x| x * x).sum::<f64>().sqrt();
+        let (lo, hi) = F::BOUNDS;
+        let shifted: Vec<f64> = self
+            .iter()
+            .zip(F::optimum(D))
+            .zip(direction)
+            .map(|((&x, opt), dir)| {
+                let moved = if norm > 0.0 {
+                    (opt + severity * dir / norm).clamp(lo, hi)
+                } else {
+                    opt
+                };
+                x - (moved - opt)
+            })
+            .collect();
+        -F::value(&shifted)
+    }
+}
+
+/// Flips a random subset of `severity * N` bits before evaluating, i.e. the XOR dynamic problem generator.
+impl<F: BitFunction, const N: usize> Shift for BitBenchmark<F, N> {
+    fn evaluate_shifted<R: Rng>(&self, rng: &mut R, severity: f64) -> f64 {
+        let amount = ((severity.clamp(0.0, 1.0) * N as f64).round() as usize).min(N);
+        let mut bits: Vec<bool> = self.to_vec();
+        for i in index::sample(rng, N, amount) {
+            bits[i] = !bits[i];
+        }
+        F::value(&bits)
+    }
+}
+
+/// A [`Shift`] benchmark whose problem changes according to the [`Schedule`] `S`
+///
+/// During the first `S::PERIOD` generations, fitness is the same as that of the wrapped benchmark.
+/// After that, a new shift is used for each period.
+/// This type derefs to the benchmark it contains.
+///
+/// [`Shift`]: ./trait.Shift.html
+/// [`Schedule`]: ./trait.Schedule.html
+pub struct Dynamic<B, S> {
+    inner: B,
+    _phantom: PhantomData<fn() -> S>,
+}
+
+impl<B: Shift, S: Schedule> Dynamic<B, S> {
+    /// Wrap an existing benchmark.
+    pub fn new(inner: B) -> Self {
+        Dynamic {
+            inner,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Consume the wrapper, returning the benchmark it contained.
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+impl<B: Shift, S: Schedule> Solution for Dynamic<B, S> {
+    type Fitness = f64;
+    const DIRECTIONS: &'static [Direction] = B::DIRECTIONS;
+
+    fn generate() -> Self {
+        Dynamic::new(B::generate())
+    }
+
+    fn evaluate(&self) -> f64 {
+        match S::epoch() {
+            0 => self.inner.evaluate(),
+            epoch => {
+                let mut rng = Xoshiro256PlusPlus::seed_from_u64(
+                    seed() ^ epoch.wrapping_mul(0x9e37_79b9_7f4a_7c15),
+                );
+                self.inner.evaluate_shifted(&mut rng, S::SEVERITY)
+            }
+        }
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        B::crossover(&mut a.inner, &mut b.inner);
+    }
+
+    fn mutate(&mut self) {
+        self.inner.mutate();
+    }
+
+    fn repair(&mut self) {
+        self.inner.repair();
+    }
+}
+
+macro_rules! wrapper_impls {
+    ($name:ident, $param:ident) => {
+        impl<S: Clone, $param> Clone for $name<S, $param> {
+            fn clone(&self) -> Self {
+                $name {
+                    inner: self.inner.clone(),
+                    _phantom: PhantomData,
+                }
+            }
+        }
+
+        impl<S, $param> Deref for $name<S, $param> {
+            type Target = S;
+            fn deref(&self) -> &S {
+                &self.inner
+            }
+        }
+
+        impl<S, $param> AsRef<S> for $name<S, $param> {
+            fn as_ref(&self) -> &S {
+                &self.inner
+            }
+        }
+
+        impl<S: Debug, $param> Debug for $name<S, $param> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.inner).finish()
+            }
+        }
+
+        impl<S: PartialEq, $param> PartialEq for $name<S, $param> {
+            fn eq(&self, other: &Self) -> bool {
+                self.inner == other.inner
+            }
+        }
+    };
+}
+
+wrapper_impls!(Noisy, L);
+wrapper_impls!(Dynamic, T);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmarks::{binary::OneMax, Sphere};
+
+    struct Fast;
+
+    impl Schedule for Fast {
+        const PERIOD: usize = 2;
+        const SEVERITY: f64 = 0.5;
+
+        fn clock() -> &'static Clock {
+            static CLOCK: Clock = Clock::new();
+            &CLOCK
+        }
+    }
+
+    #[test]
+    fn variants() {
+        let sphere = Benchmark::<Sphere, 3>::new(vec![0.0; 3]).unwrap();
+        let noisy = Noisy::<_, Sigma<1000>>::new(sphere.clone());
+        assert_eq!(noisy.true_fitness(), 0.0);
+        assert!((0..10).any(|_| noisy.evaluate() != 0.0));
+
+        let dynamic = Dynamic::<_, Fast>::new(sphere);
+        let bits = Dynamic::<_, Fast>::new(
+            BitBenchmark::<OneMax, 10>::new(vec![true; 10].into_iter().collect()).unwrap(),
+        );
+        assert_eq!(dynamic.evaluate(), 0.0);
+        assert_eq!(bits.evaluate(), 10.0);
+        Fast::clock().tick();
+        assert_eq!(dynamic.evaluate(), 0.0);
+        Fast::clock().tick();
+        // the optimum moved by exactly 0.5, and the shift is the same every time
+        let moved = dynamic.evaluate();
+        assert!((moved + 0.25).abs() < 1e-9);
+        assert_eq!(dynamic.evaluate(), moved);
+        assert_eq!(bits.evaluate(), 5.0);
+    }
+
+    #[test]
+    fn environment_changes_reevaluate() {
+        use crate::{alg::MuPlusLambda, hof::BestN, prelude::*, testutils::Walk};
+
+        // The optimum of a walk moves by one after every three generations
+        static TARGET: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Clone)]
+        struct Drifting(Walk);
+
+        impl Solution for Drifting {
+            type Fitness = f64;
+
+            fn generate() -> Self {
+                Drifting(Walk::generate())
+            }
+
+            fn evaluate(&self) -> f64 {
+                -(self.0 .0 - TARGET.load(Ordering::Relaxed) as f64).abs()
+            }
+
+            fn crossover(a: &mut Self, b: &mut Self) {
+                Walk::crossover(&mut a.0, &mut b.0);
+            }
+
+            fn mutate(&mut self) {
+                self.0.mutate();
+            }
+        }
+
+        let is_current = |ind: &Cached<Drifting>| ind.evaluate() == ind.as_ref().evaluate();
+        let evo: Evolution<Drifting, _, _, ()> = Evolution::new(
+            MuPlusLambda::new(10, 20, 0.5, 0.4, select::Tournament::new(3)),
+            BestN::new(3),
+        )
+        .with_environment_changes(3)
+        .with_hypermutation(0.5);
+        let log = evo.run_for_with(9, |generation| {
+            assert!(generation.pop.iter().all(is_current));
+            TARGET.store((generation.gen + 1) / 3, Ordering::Relaxed);
+        });
+        assert!(log.hall_of_fame.iter().all(is_current));
+    }
+
+    #[test]
+    fn noise() {
+        assert_eq!(Sigma::<250>::SIGMA, 0.25);
+        let sphere = Benchmark::<Sphere, 2>::new(vec![1.0, 2.0]).unwrap();
+        let noisy = Noisy::<_, Sigma<250>>::new(sphere.clone());
+        assert_eq!(noisy.true_fitness(), -5.0);
+        let samples: Vec<f64> = (0..4000).map(|_| noisy.evaluate()).collect();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let var = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+        assert!((mean + 5.0).abs() < 0.02, "mean was {}", mean);
+        assert!(
+            (var.sqrt() - 0.25).abs() < 0.02,
+            "standard deviation was {}",
+            var.sqrt()
+        );
+        assert!(noisy.into_inner() == sphere);
+
+        // variation is passed through to the wrapped solution
+        let ones = BitBenchmark::<OneMax, 12>::new(vec![true; 12].into_iter().collect()).unwrap();
+        let zeros = BitBenchmark::<OneMax, 12>::new(vec![false; 12].into_iter().collect()).unwrap();
+        let (mut a, mut b) = (
+            Noisy::<_, Sigma<0>>::new(ones),
+            Noisy::<_, Sigma<0>>::new(zeros),
+        );
+        Noisy::crossover(&mut a, &mut b);
+        // two-point crossover only moves bits between the parents
+        assert_eq!(a.evaluate() + b.evaluate(), 12.0);
+    }
+
+    #[test]
+    fn clocks() {
+        let clock = Clock::new();
+        assert_eq!(clock.generation(), 0);
+        assert_eq!(clock.tick(), 1);
+        clock.set(10);
+        assert_eq!(clock.tick(), 11);
+        assert_eq!(clock.generation(), 11);
+
+        struct Never;
+
+        impl Schedule for Never {
+            const PERIOD: usize = 0;
+            const SEVERITY: f64 = 1.0;
+
+            fn clock() -> &'static Clock {
+                static CLOCK: Clock = Clock::new();
+                &CLOCK
+            }
+        }
+
+        Never::clock().set(100);
+        assert_eq!(Never::epoch(), 0);
+        let sphere = Benchmark::<Sphere, 2>::new(vec![0.0; 2]).unwrap();
+        assert_eq!(Dynamic::<_, Never>::new(sphere).evaluate(), 0.0);
+    }
+
+    #[test]
+    fn shifts() {
+        let rng = || Xoshiro256PlusPlus::seed_from_u64(21);
+        let origin = Benchmark::<Sphere, 3>::new(vec![0.0; 3]).unwrap();
+        let value = origin.evaluate_shifted(&mut rng(), 2.0);
+        // the optimum moved by exactly the severity
+        assert!((value + 4.0).abs() < 1e-9);
+        let point = Benchmark::<Sphere, 3>::new(vec![1.0, -1.0, 0.5]).unwrap();
+        assert_eq!(
+            point.evaluate_shifted(&mut rng(), 2.0),
+            point.evaluate_shifted(&mut rng(), 2.0)
+        );
+        assert_eq!(point.evaluate_shifted(&mut rng(), 0.0), point.evaluate());
+        // the optimum can't move out of bounds
+        let corner = Benchmark::<Sphere, 3>::new(vec![5.12; 3]).unwrap();
+        let shifted = corner.evaluate_shifted(&mut rng(), 100.0);
+        assert!(shifted >= -3.0 * (2.0f64 * 5.12).powi(2));
+
+        let bits = BitBenchmark::<OneMax, 10>::new(
+            [
+                true, true, true, false, false, true, false, true, true, true,
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+        assert_eq!(bits.evaluate_shifted(&mut rng(), 0.0), 7.0);
+        assert_eq!(bits.evaluate_shifted(&mut rng(), 1.0), 3.0);
+        assert_eq!(bits.evaluate_shifted(&mut rng(), 2.0), 3.0);
+        // flipping 3 bits changes the count by an odd amount of at most 3
+        let flipped = bits.evaluate_shifted(&mut rng(), 0.3);
+        assert!([4.0, 6.0, 8.0, 10.0].contains(&flipped));
+    }
+
+    #[test]
+    fn epochs() {
+        struct Slow;
+
+        impl Schedule for Slow {
+            const PERIOD: usize = 5;
+            const SEVERITY: f64 = 1.0;
+
+            fn clock() -> &'static Clock {
+                static CLOCK: Clock = Clock::new();
+                &CLOCK
+            }
+        }
+
+        let dynamic = Dynamic::<_, Slow>::new(Benchmark::<Sphere, 4>::new(vec![0.5; 4]).unwrap());
+        let mut values = Vec::new();
+        for generation in [0, 4, 5, 9, 10, 14] {
+            Slow::clock().set(generation);
+            assert_eq!(Slow::epoch(), generation as u64 / 5);
+            values.push(dynamic.evaluate());
+        }
+        assert_eq!(values[0], -1.0);
+        // fitness only changes between periods, and every period has its own shift
+        assert_eq!(values[0], values[1]);
+        assert_eq!(values[2], values[3]);
+        assert_eq!(values[4], values[5]);
+        assert!(values[1] != values[2] && values[3] != values[4]);
+
+        // and the shifts can be reproduced from the seed
+        Slow::clock().set(7);
+        assert_eq!(dynamic.evaluate(), values[2]);
+    }
+}
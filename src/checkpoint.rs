@@ -0,0 +1,531 @@
+//! Saving and resuming runs
+//!
+//! A [`Checkpoint`] holds everything needed to continue a run exactly where it left off:
+//! the population, the hall of fame, the index of the generation, and the state of the
+//! [reproducible RNG]. Write one from the callback passed to one of [`Evolution`]'s `run_*_with`
+//! methods every few generations, and if the run is interrupted, [`Checkpoint::load`] the latest one
+//! and pass it to [`Evolution::resume`]. As long as the program is otherwise deterministic,
+//! the resumed run produces the same results as one that was never interrupted.
+//!
+//! Checkpoints use a compact binary format, which is much smaller and faster to write
+//! than a textual format for large populations:
+//!
+//! - the magic bytes `EVCK`
+//! - the format version as a little-endian `u16`, followed by two reserved bytes
+//! - the length of the payload as a little-endian `u64`
+//! - the payload, encoded with [`bincode`] using fixed-width little-endian integers
+//! - the CRC-32 of the payload as a little-endian `u32`
+//!
+//! Truncated or corrupted checkpoints are detected with the checksum and rejected.
+//! The format version is bumped whenever the payload changes, and checkpoints
+//! written in an older version of the format are upgraded when they're read,
+//! so a checkpoint written by one version of the crate can be resumed by any later version.
+//!
+//! Only the statistics of the generations after the checkpoint end up in the [`Log`] of
+//! a resumed run. Resets are not carried over either.
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::checkpoint::Checkpoint;
+//!
+//! #[derive(Clone, serde::Serialize, serde::Deserialize)]
+//! struct Guess(f64);
+//!
+//! impl Solution for Guess {
+//!     type Fitness = f64;
+//!
+//!     fn generate() -> Self {
+//!         Guess(thread_rng().gen_range(-10.0..10.0))
+//!     }
+//!
+//!     fn evaluate(&self) -> f64 {
+//!         -(self.0 - 3.0).abs()
+//!     }
+//!
+//!     fn crossover(a: &mut Self, b: &mut Self) {
+//!         std::mem::swap(a, b);
+//!     }
+//!
+//!     fn mutate(&mut self) {
+//!         self.0 += thread_rng().gen_range(-1.0..1.0);
+//!     }
+//! }
+//!
+//! let alg = || alg::MuPlusLambda::new(20, 40, 0.6, 0.3, select::Tournament::new(3));
+//! let mut saved = Vec::new();
+//! let evo: Evolution<Guess, _, _, ()> = Evolution::new(alg(), hof::BestN::new(5));
+//! evo.run_for_with(10, |generation| {
+//!     if generation.gen % 5 == 4 {
+//!         saved.clear();
+//!         Checkpoint::write(generation, &mut saved).unwrap();
+//!     }
+//! });
+//!
+//! let checkpoint: Checkpoint<Guess, hof::BestN<Guess>> = Checkpoint::read(&saved[..]).unwrap();
+//! assert_eq!(checkpoint.gen(), 9);
+//! let evo: Evolution<Guess, _, _, ()> = Evolution::resume(alg(), checkpoint);
+//! evo.run_for(10);
+//! ```
+//!
+//! [`Checkpoint`]: ./struct.Checkpoint.html
+//! [`Checkpoint::load`]: ./struct.Checkpoint.html#method.load
+//! [reproducible RNG]: ../repro_rng/index.html
+//! [`Evolution`]: ../struct.Evolution.html
+//! [`Evolution::resume`]: ../struct.Evolution.html#method.resume
+//! [`Log`]: ../struct.Log.html
+
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use bincode::Options;
+use rand_xoshiro::Xoshiro256StarStar;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    hof::HallOfFame,
+    repro_rng::{thread_rng, RngState},
+    stats::GenerationStats,
+    utils::encoding::{bincode_error, options},
+    Cached, Generation, Solution,
+};
+
+/// The version of the checkpoint format written by this version of the crate
+pub const FORMAT_VERSION: u16 = 1;
+
+const MAGIC: &[u8; 4] = b"EVCK";
+
+/// The state of a run at the end of one generation
+///
+/// See the [module-level documentation](./index.html) for how to use it.
+pub struct Checkpoint<T: Solution, Hof: HallOfFame<T>> {
+    pub(crate) gen: usize,
+    pub(crate) population: Vec<Cached<T>>,
+    pub(crate) hall_of_fame: Hof,
+    pub(crate) rng: RngState,
+}
+
+// The payload of version 1 of the format. It's written from borrowed data,
+// so that writing a checkpoint never has to clone the population.
+#[derive(Serialize)]
+struct PayloadRef<'a, P, H> {
+    gen: u64,
+    population: &'a [P],
+    hall_of_fame: &'a H,
+    seed: u64,
+    rng: Xoshiro256StarStar,
+}
+
+#[derive(Deserialize)]
+struct Payload<P, H> {
+    gen: u64,
+    population: Vec<P>,
+    hall_of_fame: H,
+    seed: u64,
+    rng: Xoshiro256StarStar,
+}
+
+impl<T, Hof> Checkpoint<T, Hof>
+where
+    T: Solution,
+    Hof: HallOfFame<T>,
+{
+    /// Get the index of the generation the checkpoint was written at.
+    pub fn gen(&self) -> usize {
+        self.gen
+    }
+
+    /// Get the population as of the generation the checkpoint was written at.
+    pub fn population(&self) -> &[Cached<T>] {
+        &self.population
+    }
+
+    /// Get the hall of fame as of the generation the checkpoint was written at.
+    pub fn hall_of_fame(&self) -> &Hof {
+        &self.hall_of_fame
+    }
+
+    /// Get the state of the [reproducible RNG] as of the generation the checkpoint was written at,
+    /// which [`Evolution::resume`] restores.
+    ///
+    /// [reproducible RNG]: ../repro_rng/index.html
+    /// [`Evolution::resume`]: ../struct.Evolution.html#method.resume
+    pub fn rng_state(&self) -> &RngState {
+        &self.rng
+    }
+
+    /// Write a checkpoint of `generation` to `writer`.
+    ///
+    /// This must be called on the thread the run is happening on,
+    /// since that's the thread whose RNG is saved.
+    pub fn write<Stat, W>(generation: Generation<T, Hof, Stat>, writer: W) -> io::Result<()>
+    where
+        T: Serialize,
+        T::Fitness: Serialize,
+        Hof: Serialize,
+        Stat: GenerationStats<T>,
+        W: Write,
+    {
+        let RngState { seed, rng } = thread_rng().state();
+        let payload = PayloadRef {
+            gen: generation.gen as u64,
+            population: generation.pop,
+            hall_of_fame: generation.hall_of_fame,
+            seed,
+            rng,
+        };
+        let len = options()
+            .serialized_size(&payload)
+            .map_err(|error| bincode_error(*error))?;
+
+        let mut writer = BufWriter::new(writer);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&[0, 0])?;
+        writer.write_all(&len.to_le_bytes())?;
+        let mut checksummed = Crc32::new(&mut writer);
+        options()
+            .serialize_into(&mut checksummed, &payload)
+            .map_err(|error| bincode_error(*error))?;
+        let crc = checksummed.finish();
+        writer.write_all(&crc.to_le_bytes())?;
+        writer.flush()
+    }
+
+    /// Write a checkpoint of `generation` to a file at `path`, replacing it if it exists.
+    /// See [`.write()`](#method.write).
+    ///
+    /// The checkpoint is first written to a temporary file next to `path`, which then replaces it,
+    /// so the previous checkpoint stays intact if the program is interrupted while writing.
+    pub fn save<Stat>(
+        generation: Generation<T, Hof, Stat>,
+        path: impl AsRef<Path>,
+    ) -> io::Result<()>
+    where
+        T: Serialize,
+        T::Fitness: Serialize,
+        Hof: Serialize,
+        Stat: GenerationStats<T>,
+    {
+        let path = path.as_ref();
+        let mut temp_name = OsString::from(".");
+        temp_name.push(path.file_name().unwrap_or_default());
+        temp_name.push(".tmp");
+        let temp = path.with_file_name(temp_name);
+
+        let file = File::create(&temp)?;
+        Self::write(generation, &file)?;
+        file.sync_all()?;
+        fs::rename(temp, path)
+    }
+
+    /// Read a checkpoint from `reader`.
+    ///
+    /// Returns an error of kind [`InvalidData`] if the data isn't a checkpoint, is corrupted,
+    /// was written in a newer version of the format, or doesn't match the types being read.
+    ///
+    /// [`InvalidData`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidData
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self>
+    where
+        T: DeserializeOwned,
+        T::Fitness: DeserializeOwned,
+        Hof: DeserializeOwned,
+    {
+        let mut header = [0; 16];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid("not an eviolite checkpoint".to_owned()));
+        }
+        let version = u16::from_le_bytes([header[4], header[5]]);
+        let len = u64::from_le_bytes(header[8..].try_into().unwrap());
+
+        let mut checksummed = Crc32::new((&mut reader).take(len));
+        let payload = match version {
+            // Older versions of the format get their own arm here,
+            // decoding their own payload and converting it to the current one.
+            1 => options()
+                .with_limit(len)
+                .deserialize_from::<_, Payload<Cached<T>, Hof>>(&mut checksummed),
+            _ => {
+                return Err(invalid(format!(
+                    "checkpoint format version {} is newer than the supported version {}",
+                    version, FORMAT_VERSION
+                )))
+            }
+        };
+
+        // Check the checksum before anything else, since corrupted data
+        // is the most likely reason for the payload failing to decode.
+        io::copy(&mut checksummed, &mut io::sink())?;
+        let complete = checksummed.inner.limit() == 0;
+        let crc = checksummed.finish();
+        let mut trailer = [0; 4];
+        if !complete || reader.read_exact(&mut trailer).is_err() {
+            return Err(invalid("checkpoint is truncated".to_owned()));
+        }
+        if u32::from_le_bytes(trailer) != crc {
+            return Err(invalid("checkpoint checksum mismatch".to_owned()));
+        }
+
+        let payload = payload.map_err(|error| bincode_error(*error))?;
+        Ok(Checkpoint {
+            gen: payload.gen as usize,
+            population: payload.population,
+            hall_of_fame: payload.hall_of_fame,
+            rng: RngState {
+                seed: payload.seed,
+                rng: payload.rng,
+            },
+        })
+    }
+
+    /// Read a checkpoint from a file at `path`. See [`.read()`](#method.read).
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self>
+    where
+        T: DeserializeOwned,
+        T::Fitness: DeserializeOwned,
+        Hof: DeserializeOwned,
+    {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+// CRC-32 (IEEE), computed over everything that passes through the wrapped reader or writer
+struct Crc32<I> {
+    inner: I,
+    crc: u32,
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+impl<I> Crc32<I> {
+    fn new(inner: I) -> Self {
+        Crc32 {
+            inner,
+            crc: 0xffffffff,
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.crc = CRC_TABLE[((self.crc ^ byte as u32) & 0xff) as usize] ^ (self.crc >> 8);
+        }
+    }
+
+    fn finish(self) -> u32 {
+        !self.crc
+    }
+}
+
+impl<W: Write> Write for Crc32<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<R: Read> Read for Crc32<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{alg::MuPlusLambda, hof::BestN, prelude::*, testutils::Walk, Evolution};
+
+    fn evolution() -> Evolution<Walk, MuPlusLambda<Walk, select::Tournament>, BestN<Walk>, ()> {
+        Evolution::new(
+            MuPlusLambda::new(10, 20, 0.5, 0.4, select::Tournament::new(3)),
+            BestN::new(3),
+        )
+    }
+
+    fn values(pop: &[Cached<Walk>]) -> Vec<f64> {
+        pop.iter().map(|ind| ind.as_ref().0).collect()
+    }
+
+    #[test]
+    fn resume_matches_uninterrupted_run() {
+        crate::repro_rng::reseed(7);
+        let mut saved = Vec::new();
+        let full = evolution().run_for_with(12, |generation| {
+            if generation.gen == 5 {
+                Checkpoint::write(generation, &mut saved).unwrap();
+            }
+        });
+
+        crate::repro_rng::reseed(1234);
+        let checkpoint: Checkpoint<Walk, BestN<Walk>> = Checkpoint::read(&saved[..]).unwrap();
+        assert_eq!(checkpoint.gen(), 5);
+        let mut gens = Vec::new();
+        let resumed = Evolution::<_, _, _, ()>::resume(
+            MuPlusLambda::new(10, 20, 0.5, 0.4, select::Tournament::new(3)),
+            checkpoint,
+        )
+        .run_for_with(6, |generation| gens.push(generation.gen));
+
+        assert_eq!(gens, (6..12).collect::<Vec<_>>());
+        assert_eq!(
+            values(&resumed.final_population),
+            values(&full.final_population)
+        );
+        assert_eq!(values(&resumed.hall_of_fame), values(&full.hall_of_fame));
+    }
+
+    #[test]
+    fn appended_segments_match_uninterrupted_run() {
+        use crate::stats::FitnessSummary;
+
+        let evolution = || -> Evolution<Walk, _, BestN<Walk>, FitnessSummary> {
+            Evolution::new(
+                MuPlusLambda::new(10, 20, 0.5, 0.4, select::Tournament::new(3)),
+                BestN::new(3),
+            )
+            .with_best_solution_history()
+        };
+        crate::repro_rng::reseed(7);
+        let full = evolution().run_for(12);
+
+        // the first segment carries on past its checkpoint before it's interrupted
+        crate::repro_rng::reseed(7);
+        let mut saved = Vec::new();
+        let mut log = evolution().run_for_with(8, |generation| {
+            if generation.gen == 5 {
+                Checkpoint::write(generation, &mut saved).unwrap();
+            }
+        });
+        let checkpoint = Checkpoint::read(&saved[..]).unwrap();
+        let resumed = Evolution::resume(
+            MuPlusLambda::new(10, 20, 0.5, 0.4, select::Tournament::new(3)),
+            checkpoint,
+        )
+        .with_best_solution_history()
+        .run_for(6);
+        assert_eq!(resumed.first_generation, 6);
+
+        log.append(resumed);
+        let means = |log: &crate::Log<Walk, BestN<Walk>, FitnessSummary>| -> Vec<f64> {
+            log.stats.iter().map(FitnessSummary::mean).collect()
+        };
+        assert_eq!(means(&log), means(&full));
+        assert_eq!(log.best_fitness, full.best_fitness);
+        assert_eq!(values(&log.best_solutions), values(&full.best_solutions));
+        assert_eq!(values(&log.hall_of_fame), values(&full.hall_of_fame));
+        assert_eq!(
+            values(&log.final_population),
+            values(&full.final_population)
+        );
+
+        let islands = full.stats.len();
+        let mut merged = evolution().run_for(12);
+        merged.merge_with(evolution().run_for(12), |ours, theirs| {
+            if theirs.max() > ours.max() {
+                *ours = theirs;
+            }
+        });
+        assert_eq!(merged.stats.len(), islands);
+        let maxima: Vec<f64> = merged.stats.iter().map(FitnessSummary::max).collect();
+        assert_eq!(merged.best_fitness, maxima);
+        assert_eq!(merged.best_solutions.len(), islands);
+        assert_eq!(merged.final_population.len(), 20);
+        assert_eq!(merged.hall_of_fame.len(), 3);
+    }
+
+    #[test]
+    fn rejects_corruption() {
+        let mut saved = Vec::new();
+        evolution().run_for_with(1, |generation| {
+            Checkpoint::write(generation, &mut saved).unwrap();
+        });
+        let read = |bytes: &[u8]| Checkpoint::<Walk, BestN<Walk>>::read(bytes).map(|_| ());
+
+        assert!(read(&saved).is_ok());
+
+        let mut corrupted = saved.clone();
+        corrupted[40] ^= 0x10;
+        let error = read(&corrupted).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("checksum"));
+
+        let error = read(&saved[..saved.len() - 10]).unwrap_err();
+        assert!(error.to_string().contains("truncated"));
+
+        let mut newer = saved.clone();
+        newer[4] = 2;
+        assert!(read(&newer).unwrap_err().to_string().contains("version 2"));
+
+        assert!(read(b"PAR1 definitely not a checkpoint").is_err());
+    }
+
+    #[test]
+    fn callbacks_control_the_run() {
+        use crate::Control;
+        use std::{cell::RefCell, rc::Rc};
+
+        let saved = Rc::new(RefCell::new(Vec::new()));
+        let writer = Rc::clone(&saved);
+        let log = evolution()
+            .with_checkpointer(move |generation| {
+                Checkpoint::write(generation, &mut *writer.borrow_mut()).unwrap();
+            })
+            .run_for_with(20, |generation| match generation.gen {
+                2 => Control::Checkpoint,
+                3 | 4 => Control::SkipStats,
+                6 => Control::Stop,
+                _ => Control::Continue,
+            });
+        assert_eq!(log.stats.len(), 5);
+
+        let checkpoint: Checkpoint<Walk, BestN<Walk>> =
+            Checkpoint::read(&saved.borrow()[..]).unwrap();
+        assert_eq!(checkpoint.gen(), 2);
+
+        let mut gens = Vec::new();
+        evolution().run_until_with(
+            |generation| generation.gen == 10,
+            |generation| {
+                gens.push(generation.gen);
+                if generation.gen == 3 {
+                    Control::Stop
+                } else {
+                    Control::Continue
+                }
+            },
+        );
+        assert_eq!(gens, [0, 1, 2, 3]);
+    }
+}
@@ -0,0 +1,321 @@
+//! Competitive coevolution of two populations
+//!
+//! In competitive coevolution, there's no fixed fitness function. Instead, two populations,
+//! like strategies and the test cases that try to break them, or predators and prey,
+//! are scored by playing games against each other, so each one keeps raising the bar for the other.
+//! [`Coevolution`] runs two algorithms side by side, and before every step it plays
+//! the games chosen by [`Opponents`] and gives each solution its mean score as its fitness.
+//!
+#![cfg_attr(feature = "std", doc = "```")]
+#![cfg_attr(not(feature = "std"), doc = "```ignore")]
+//! use eviolite::prelude::*;
+//! use eviolite::coevolution::{Coevolution, Opponents};
+//! # #[derive(Clone, Debug)]
+//! # struct Guess(f64);
+//! # impl Solution for Guess {
+//! #     type Fitness = f64;
+//! #     fn generate() -> Self { Guess(random()) }
+//! #     fn evaluate(&self) -> f64 { 0.0 }
+//! #     fn crossover(a: &mut Self, b: &mut Self) { std::mem::swap(&mut a.0, &mut b.0) }
+//! #     fn mutate(&mut self) { self.0 = (self.0 + thread_rng().gen_range(-0.1..0.1)).clamp(0.0, 1.0) }
+//! # }
+//!
+//! // guessers score by getting close to the hider's number, hiders by staying far from guesses
+//! let result = Coevolution::new(
+//!     alg::Simple::new(30, 0.5, 0.3, select::Tournament::new(3)),
+//!     alg::Simple::new(30, 0.5, 0.3, select::Tournament::new(3)),
+//!     |guess: &Guess, hider: &Guess| {
+//!         let distance = (guess.0 - hider.0).abs();
+//!         (-distance, distance)
+//!     },
+//! )
+//! .with_opponents(Opponents::Random(5))
+//! .run_for(20);
+//! assert_eq!(result.champions_a.len(), 20);
+//! ```
+//!
+//! Only the solutions in each population are evaluated by playing games, so the algorithms should only compare
+//! solutions that have been evaluated, like [`alg::Simple`] does. Algorithms that evaluate their offspring
+//! in the middle of a step, like [`alg::MuPlusLambda`], would evaluate them with [`Solution::evaluate`] instead.
+//!
+//! [`Coevolution`]: ./struct.Coevolution.html
+//! [`Opponents`]: ./enum.Opponents.html
+//! [`alg::Simple`]: ../alg/struct.Simple.html
+//! [`alg::MuPlusLambda`]: ../alg/struct.MuPlusLambda.html
+//! [`Solution::evaluate`]: ../trait.Solution.html#tymethod.evaluate
+
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+use rand::{seq::index::sample, Rng};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{
+    alg::Algorithm,
+    fitness::Direction,
+    repro_rng::{self, thread_rng},
+    Cached, Solution,
+};
+
+/// Which solutions of the other population each solution plays against every generation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Opponents {
+    /// Every solution plays against every solution of the other population.
+    /// Each game counts for both players.
+    AllVsAll,
+    /// Every solution plays against this many solutions of the other population, chosen at random.
+    Random(usize),
+    /// Every solution plays against this many of the most recent champions of the other population,
+    /// i.e. the best solution of each of its past generations, which keeps solutions from
+    /// forgetting how to beat strategies that have gone out of fashion.
+    /// In the first generation, when there are no champions yet,
+    /// the opponents are chosen at random like with [`Opponents::Random`].
+    ///
+    /// [`Opponents::Random`]: #variant.Random
+    HallOfFame(usize),
+}
+
+/// A driver for competitive coevolution of two populations
+///
+/// See the [module-level documentation](./index.html) for an example.
+pub struct Coevolution<A, B, AlgA, AlgB, F>
+where
+    A: Solution,
+    B: Solution,
+{
+    alg_a: AlgA,
+    alg_b: AlgB,
+    play: F,
+    opponents: Opponents,
+    _phantom: PhantomData<(A, B)>,
+}
+
+/// The result of a [`Coevolution`] run
+///
+/// [`Coevolution`]: ./struct.Coevolution.html
+pub struct Coevolved<A: Solution, B: Solution> {
+    /// The first population after the last generation, with its fitness from that generation's games.
+    pub population_a: Vec<Cached<A>>,
+    /// The second population after the last generation, with its fitness from that generation's games.
+    pub population_b: Vec<Cached<B>>,
+    /// The best solution of the first population in each generation, in order.
+    pub champions_a: Vec<Cached<A>>,
+    /// The best solution of the second population in each generation, in order.
+    pub champions_b: Vec<Cached<B>>,
+}
+
+impl<A, B, AlgA, AlgB, F> Coevolution<A, B, AlgA, AlgB, F>
+where
+    A: Solution,
+    A::Fitness: From<f64> + Into<f64>,
+    B: Solution,
+    B::Fitness: From<f64> + Into<f64>,
+    AlgA: Algorithm<A>,
+    AlgB: Algorithm<B>,
+    F: Fn(&A, &B) -> (f64, f64) + Sync,
+{
+    /// Create a new driver that evolves a population with each algorithm,
+    /// scoring a game between two solutions with `play`, which returns the score of each of them.
+    ///
+    /// The fitness of a solution is its mean score, so [`Solution::DIRECTIONS`] should
+    /// maximize it. By default, every solution plays against every solution of the other population.
+    ///
+    /// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+    pub fn new(alg_a: AlgA, alg_b: AlgB, play: F) -> Self {
+        Coevolution {
+            alg_a,
+            alg_b,
+            play,
+            opponents: Opponents::AllVsAll,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Choose the opponents of each solution with `opponents`.
+    pub fn with_opponents(mut self, opponents: Opponents) -> Self {
+        self.opponents = opponents;
+        self
+    }
+
+    /// Run both algorithms for `n_gens` generations, starting from randomly generated populations.
+    pub fn run_for(self, n_gens: usize) -> Coevolved<A, B> {
+        let mut result = Coevolved {
+            population_a: (0..self.alg_a.pop_size())
+                .map(|_| Cached::generate())
+                .collect(),
+            population_b: (0..self.alg_b.pop_size())
+                .map(|_| Cached::generate())
+                .collect(),
+            champions_a: Vec::with_capacity(n_gens),
+            champions_b: Vec::with_capacity(n_gens),
+        };
+        let (mut scratch_a, mut scratch_b) = (Vec::new(), Vec::new());
+
+        for generation in 0..n_gens {
+            self.compete(&mut result);
+            result.champions_a.push(champion(&result.population_a));
+            result.champions_b.push(champion(&result.population_b));
+
+            // the final populations keep the fitness from their games
+            if generation + 1 < n_gens {
+                self.alg_a
+                    .step_with_scratch(&mut result.population_a, &mut scratch_a);
+                self.alg_b
+                    .step_with_scratch(&mut result.population_b, &mut scratch_b);
+            }
+        }
+        result
+    }
+
+    // Play this generation's games and give every solution its mean score
+    fn compete(&self, result: &mut Coevolved<A, B>) {
+        let (pop_a, pop_b) = (&result.population_a, &result.population_b);
+        let mut rng = thread_rng();
+        let mut random = |n: usize, len: usize| sample(&mut rng, len, n.min(len)).into_vec();
+
+        let mut games: Vec<Game<A, B>> = Vec::new();
+        match self.opponents {
+            Opponents::AllVsAll => {
+                for (i, a) in pop_a.iter().enumerate() {
+                    for (j, b) in pop_b.iter().enumerate() {
+                        games.push((a.as_ref(), b.as_ref(), Some(i), Some(j)));
+                    }
+                }
+            }
+            Opponents::Random(k) => {
+                for (i, a) in pop_a.iter().enumerate() {
+                    for j in random(k, pop_b.len()) {
+                        games.push((a.as_ref(), pop_b[j].as_ref(), Some(i), None));
+                    }
+                }
+                for (j, b) in pop_b.iter().enumerate() {
+                    for i in random(k, pop_a.len()) {
+                        games.push((pop_a[i].as_ref(), b.as_ref(), None, Some(j)));
+                    }
+                }
+            }
+            Opponents::HallOfFame(k) => {
+                let recent_b = &result.champions_b[result.champions_b.len().saturating_sub(k)..];
+                for (i, a) in pop_a.iter().enumerate() {
+                    if recent_b.is_empty() {
+                        for j in random(k, pop_b.len()) {
+                            games.push((a.as_ref(), pop_b[j].as_ref(), Some(i), None));
+                        }
+                    }
+                    for b in recent_b {
+                        games.push((a.as_ref(), b.as_ref(), Some(i), None));
+                    }
+                }
+                let recent_a = &result.champions_a[result.champions_a.len().saturating_sub(k)..];
+                for (j, b) in pop_b.iter().enumerate() {
+                    if recent_a.is_empty() {
+                        for i in random(k, pop_a.len()) {
+                            games.push((pop_a[i].as_ref(), b.as_ref(), None, Some(j)));
+                        }
+                    }
+                    for a in recent_a {
+                        games.push((a.as_ref(), b.as_ref(), None, Some(j)));
+                    }
+                }
+            }
+        }
+
+        // every game gets its own stream of random numbers, like in `par_var_and()`
+        let base: u64 = rng.gen();
+        let play_game = &self.play;
+        let play = |(k, &(a, b, _, _)): (usize, &Game<A, B>)| {
+            repro_rng::with_stream(base.wrapping_add(k as u64), || play_game(a, b))
+        };
+        #[cfg(feature = "parallel")]
+        let scores: Vec<(f64, f64)> =
+            crate::utils::thread_pool::install(|| games.par_iter().enumerate().map(play).collect());
+        #[cfg(not(feature = "parallel"))]
+        let scores: Vec<(f64, f64)> = games.iter().enumerate().map(play).collect();
+
+        let mut totals_a = vec![(0.0, 0usize); pop_a.len()];
+        let mut totals_b = vec![(0.0, 0usize); pop_b.len()];
+        for (&(_, _, i, j), (score_a, score_b)) in games.iter().zip(scores) {
+            if let Some(i) = i {
+                totals_a[i].0 += score_a;
+                totals_a[i].1 += 1;
+            }
+            if let Some(j) = j {
+                totals_b[j].0 += score_b;
+                totals_b[j].1 += 1;
+            }
+        }
+        set_means(&mut result.population_a, &totals_a);
+        set_means(&mut result.population_b, &totals_b);
+    }
+}
+
+// A pair of players, and the indices of the ones whose score counts
+type Game<'a, A, B> = (&'a A, &'a B, Option<usize>, Option<usize>);
+
+fn set_means<T>(pop: &mut [Cached<T>], totals: &[(f64, usize)])
+where
+    T: Solution,
+    T::Fitness: From<f64>,
+{
+    for (ind, &(total, games)) in pop.iter_mut().zip(totals) {
+        ind.replace_fitness((total / games.max(1) as f64).into());
+    }
+}
+
+fn champion<T>(pop: &[Cached<T>]) -> Cached<T>
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+{
+    let direction = Direction::of::<T>(0);
+    pop.iter()
+        .reduce(|best, ind| {
+            if direction.is_better(
+                ind.fitness_unchecked().into(),
+                best.fitness_unchecked().into(),
+            ) {
+                ind
+            } else {
+                best
+            }
+        })
+        .expect("the populations can't be empty")
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{alg::Simple, select::Tournament, testutils::Walk};
+
+    #[test]
+    fn opponents() {
+        let simple = || Simple::new(6, 0.5, 0.5, Tournament::new(2));
+        let games = AtomicUsize::new(0);
+        let play = |a: &Walk, b: &Walk| {
+            games.fetch_add(1, Ordering::Relaxed);
+            (a.0 - b.0, b.0 - a.0)
+        };
+
+        let result = Coevolution::new(simple(), simple(), play).run_for(3);
+        assert_eq!(games.swap(0, Ordering::Relaxed), 3 * 36);
+        assert_eq!(result.champions_a.len(), 3);
+        // the games are zero-sum, and with everyone playing everyone, so are the mean scores
+        let total = |pop: &[Cached<Walk>]| pop.iter().map(Cached::fitness_unchecked).sum::<f64>();
+        assert!((total(&result.population_a) + total(&result.population_b)).abs() < 1e-9);
+
+        Coevolution::new(simple(), simple(), play)
+            .with_opponents(Opponents::Random(2))
+            .run_for(3);
+        assert_eq!(games.swap(0, Ordering::Relaxed), 3 * 2 * 6 * 2);
+
+        // one champion to play in the second generation, and two in the third
+        Coevolution::new(simple(), simple(), play)
+            .with_opponents(Opponents::HallOfFame(2))
+            .run_for(3);
+        assert_eq!(games.swap(0, Ordering::Relaxed), 2 * 6 * (2 + 1 + 2));
+    }
+}
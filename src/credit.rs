@@ -0,0 +1,382 @@
+//! Tracking which variation operators produce good offspring
+//!
+//! Wrapping a solution in [`Tracked`] records how each offspring was made — by crossover,
+//! mutation, both, or not at all — along with the fitness of the better of its parents,
+//! so that it's known whether the offspring improved on them once it's evaluated.
+//! [`OperatorCredit`] sums this up for every generation, which is useful both for seeing
+//! which operators pull their weight and as the reward signal for adaptive operator selection.
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::{Benchmark, Sphere};
+//! use eviolite::credit::{Tracked, Variation};
+//!
+//! let evo: Evolution<Tracked<Benchmark<Sphere, 3>>, _, _, stats::OperatorCredit> = Evolution::new(
+//!     alg::Simple::new(50, 0.5, 0.3, select::Tournament::new(3)),
+//!     hof::BestN::new(1),
+//! );
+//! let log = evo.run_for(10);
+//! for stat in &log.stats[1..] {
+//!     let mutation = stat.variation(Variation::Mutation);
+//!     println!("{} of {} mutants improved", mutation.improved, mutation.applied);
+//! }
+//! ```
+//!
+//! When a solution chooses between several crossover or mutation operators itself,
+//! it can call [`note_operator()`] with the name of the one it used, and the credit is
+//! also given to that name.
+//!
+//! [`Tracked`]: ./struct.Tracked.html
+//! [`OperatorCredit`]: ../stats/struct.OperatorCredit.html
+//! [`note_operator()`]: ./fn.note_operator.html
+
+use std::{
+    cell::Cell,
+    fmt::{self, Debug},
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+use crate::{fitness::Direction, Solution};
+
+thread_local! {
+    static OPERATOR: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+/// Name the operator that is being applied, from inside [`Solution::crossover`] or [`Solution::mutate`].
+///
+/// A [`Tracked`] solution attributes the offspring to the last name given while its crossover
+/// or mutation ran. Outside of a `Tracked` solution, this does nothing.
+///
+/// ```
+/// # use eviolite::prelude::*;
+/// use eviolite::credit::note_operator;
+/// # #[derive(Clone)]
+/// # struct Point(f64);
+/// # impl Solution for Point {
+/// #     type Fitness = f64;
+/// #     fn generate() -> Self { Point(random()) }
+/// #     fn evaluate(&self) -> f64 { -self.0.abs() }
+/// #     fn crossover(_: &mut Self, _: &mut Self) {}
+///
+/// fn mutate(&mut self) {
+///     if random::<bool>() {
+///         note_operator("small step");
+///         self.0 += random::<f64>() * 0.1 - 0.05;
+///     } else {
+///         note_operator("reset");
+///         self.0 = random();
+///     }
+/// }
+/// # }
+/// ```
+///
+/// [`Solution::crossover`]: ../trait.Solution.html#tymethod.crossover
+/// [`Solution::mutate`]: ../trait.Solution.html#tymethod.mutate
+/// [`Tracked`]: ./struct.Tracked.html
+pub fn note_operator(name: &'static str) {
+    OPERATOR.with(|operator| operator.set(Some(name)));
+}
+
+// Run a variation operator, returning the name it noted, if any
+fn noting(vary: impl FnOnce()) -> Option<&'static str> {
+    OPERATOR.with(|operator| operator.set(None));
+    vary();
+    OPERATOR.with(|operator| operator.take())
+}
+
+/// How a [`Tracked`] solution came about
+///
+/// [`Tracked`]: ./struct.Tracked.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Variation {
+    /// Generated from scratch.
+    Generated,
+    /// Carried over unchanged from an earlier generation, either itself or as a copy.
+    Cloned,
+    /// Made by crossover alone.
+    Crossover,
+    /// Made by mutation alone.
+    Mutation,
+    /// Made by crossover followed by mutation.
+    CrossoverAndMutation,
+}
+
+impl Variation {
+    /// Every kind of variation, in order.
+    pub const ALL: [Variation; 5] = [
+        Variation::Generated,
+        Variation::Cloned,
+        Variation::Crossover,
+        Variation::Mutation,
+        Variation::CrossoverAndMutation,
+    ];
+}
+
+/// How many offspring an operator made, and how many of them improved on their parents
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Credit {
+    /// The number of offspring.
+    pub applied: usize,
+    /// The number of offspring that are better than the better of their parents.
+    pub improved: usize,
+}
+
+impl Credit {
+    /// Get the fraction of offspring that improved on their parents, or 0 if there weren't any.
+    pub fn rate(&self) -> f64 {
+        if self.applied == 0 {
+            0.0
+        } else {
+            self.improved as f64 / self.applied as f64
+        }
+    }
+}
+
+/// A [`Solution`] that remembers how it was made and how fit its parents were
+///
+/// Everything is passed through to the wrapped solution. Fitness must be convertible to `f64`,
+/// and offspring are compared to their parents in the direction of the first objective,
+/// see [`Solution::DIRECTIONS`].
+///
+/// [`Solution`]: ../trait.Solution.html
+/// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+pub struct Tracked<T: Solution> {
+    solution: T,
+    variation: Variation,
+    operator: Option<&'static str>,
+    // The fitness of the solution, as the bits of an `f64`, or NaN if it changed since it was evaluated
+    fitness: AtomicU64,
+    // The fitness of the better parent, or NaN if there were none
+    parent: f64,
+    // Whether the solution has been counted by `OperatorCredit` since it was made
+    counted: AtomicBool,
+}
+
+impl<T: Solution> Tracked<T> {
+    /// Wrap an existing solution, which counts as [generated].
+    ///
+    /// [generated]: ./enum.Variation.html#variant.Generated
+    pub fn new(solution: T) -> Self {
+        Tracked {
+            solution,
+            variation: Variation::Generated,
+            operator: None,
+            fitness: AtomicU64::new(f64::NAN.to_bits()),
+            parent: f64::NAN,
+            counted: AtomicBool::new(false),
+        }
+    }
+
+    /// Get the solution.
+    pub fn solution(&self) -> &T {
+        &self.solution
+    }
+
+    /// Consume the `Tracked`, returning the solution.
+    pub fn into_inner(self) -> T {
+        self.solution
+    }
+
+    /// Get how the solution was made.
+    pub fn variation(&self) -> Variation {
+        self.variation
+    }
+
+    /// Get the name of the operator that made the solution, if it [noted] one.
+    ///
+    /// [noted]: ./fn.note_operator.html
+    pub fn operator(&self) -> Option<&'static str> {
+        self.operator
+    }
+
+    /// Get the fitness of the better of the solution's parents,
+    /// or `None` if it was generated from scratch.
+    pub fn parent_fitness(&self) -> Option<f64> {
+        Some(self.parent).filter(|parent| !parent.is_nan())
+    }
+
+    /// Returns `true` if the solution has been evaluated and is better than the better of its parents,
+    /// or `None` if it hasn't been evaluated since it changed, or has no parents.
+    pub fn improved(&self) -> Option<bool> {
+        let fitness = self.fitness()?;
+        let parent = self.parent_fitness()?;
+        Some(Direction::of::<T>(0).is_better(fitness, parent))
+    }
+
+    // Mark the solution as counted, returning whether it already was
+    pub(crate) fn count(&self) -> bool {
+        self.counted.swap(true, Ordering::Relaxed)
+    }
+
+    fn fitness(&self) -> Option<f64> {
+        Some(f64::from_bits(self.fitness.load(Ordering::Relaxed))).filter(|fit| !fit.is_nan())
+    }
+
+    // The fitness the solution's offspring are compared to:
+    // its own, or if it has already been varied in this generation, that of its parents
+    fn as_parent(&self) -> f64 {
+        self.fitness().unwrap_or(self.parent)
+    }
+
+    fn varied(&mut self, variation: Variation, operator: Option<&'static str>, parent: f64) {
+        self.variation = variation;
+        self.operator = operator;
+        self.parent = parent;
+        self.fitness = AtomicU64::new(f64::NAN.to_bits());
+        self.counted = AtomicBool::new(false);
+    }
+}
+
+impl<T> Solution for Tracked<T>
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+{
+    type Fitness = T::Fitness;
+    const DIRECTIONS: &'static [Direction] = T::DIRECTIONS;
+    const DOMINANCE_TOLERANCE: f64 = T::DOMINANCE_TOLERANCE;
+
+    fn generate() -> Self {
+        Tracked::new(T::generate())
+    }
+
+    fn evaluate(&self) -> Self::Fitness {
+        let fitness = self.solution.evaluate();
+        self.fitness
+            .store(fitness.into().to_bits(), Ordering::Relaxed);
+        fitness
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        let (x, y) = (a.as_parent(), b.as_parent());
+        let parent = if y.is_nan() || Direction::of::<T>(0).is_better(x, y) {
+            x
+        } else {
+            y
+        };
+        let operator = noting(|| T::crossover(&mut a.solution, &mut b.solution));
+        a.varied(Variation::Crossover, operator, parent);
+        b.varied(Variation::Crossover, operator, parent);
+    }
+
+    fn mutate(&mut self) {
+        let operator = noting(|| self.solution.mutate());
+        match self.fitness() {
+            Some(fitness) => self.varied(Variation::Mutation, operator, fitness),
+            None => {
+                let variation = match self.variation {
+                    Variation::Crossover => Variation::CrossoverAndMutation,
+                    variation => variation,
+                };
+                self.varied(variation, operator.or(self.operator), self.parent);
+            }
+        }
+    }
+
+    fn repair(&mut self) {
+        self.solution.repair();
+        self.fitness = AtomicU64::new(f64::NAN.to_bits());
+    }
+
+    fn evaluation_cost(&self) -> Option<f64> {
+        self.solution.evaluation_cost()
+    }
+}
+
+impl<T: Solution> Clone for Tracked<T> {
+    fn clone(&self) -> Self {
+        Tracked {
+            solution: self.solution.clone(),
+            variation: self.variation,
+            operator: self.operator,
+            fitness: AtomicU64::new(self.fitness.load(Ordering::Relaxed)),
+            parent: self.parent,
+            counted: AtomicBool::new(self.counted.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl<T: Solution + Debug> Debug for Tracked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracked")
+            .field("solution", &self.solution)
+            .field("variation", &self.variation)
+            .field("operator", &self.operator)
+            .field("parent_fitness", &self.parent_fitness())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{stats::GenerationStats, stats::OperatorCredit, testutils::Walk, Cached};
+
+    #[derive(Clone, Debug)]
+    struct Labelled(Walk);
+
+    impl Solution for Labelled {
+        type Fitness = f64;
+
+        fn generate() -> Self {
+            Labelled(Walk::generate())
+        }
+
+        fn evaluate(&self) -> f64 {
+            self.0.evaluate()
+        }
+
+        fn crossover(a: &mut Self, b: &mut Self) {
+            Walk::crossover(&mut a.0, &mut b.0);
+        }
+
+        fn mutate(&mut self) {
+            note_operator("towards zero");
+            self.0 .0 /= 2.0;
+        }
+    }
+
+    #[test]
+    fn offspring_are_credited() {
+        let pop: Vec<Cached<Tracked<Labelled>>> = [4.0, -2.0, 1.0]
+            .into_iter()
+            .map(|x| Cached::new(Tracked::new(Labelled(Walk(x)))))
+            .collect();
+        let first = OperatorCredit::default().analyze(&pop);
+        assert_eq!(first.variation(Variation::Generated).applied, 3);
+
+        let mut next = pop.clone();
+        next[0].mutate();
+        let (head, tail) = next.split_at_mut(2);
+        Cached::crossover(&mut head[1], &mut tail[0]);
+        next[2].mutate();
+        assert_eq!(next[0].as_ref().variation(), Variation::Mutation);
+        assert_eq!(next[1].as_ref().variation(), Variation::Crossover);
+        assert_eq!(
+            next[2].as_ref().variation(),
+            Variation::CrossoverAndMutation
+        );
+        assert_eq!(next[1].as_ref().parent_fitness(), Some(-1.0));
+        assert_eq!(next[2].as_ref().operator(), Some("towards zero"));
+        assert_eq!(next[1].as_ref().operator(), None);
+
+        let stat = OperatorCredit::default().analyze(&next);
+        assert_eq!(
+            stat.variation(Variation::Mutation),
+            Credit {
+                applied: 1,
+                improved: 1
+            }
+        );
+        assert_eq!(stat.variation(Variation::Crossover).applied, 1);
+        assert_eq!(stat.operator("towards zero").applied, 2);
+        assert_eq!(next[0].as_ref().improved(), Some(true));
+
+        // counted solutions that survive are clones in the next generation
+        let again = OperatorCredit::default().analyze(&next);
+        assert_eq!(again.variation(Variation::Cloned).applied, 3);
+        assert_eq!(again.operators().len(), 0);
+    }
+}
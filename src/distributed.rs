@@ -0,0 +1,393 @@
+//! Evaluating populations on other machines
+//!
+//! A [`Coordinator`] ships the solutions that need to be evaluated to worker processes over TCP,
+//! splitting them evenly between the workers, and stores the fitness values they send back
+//! in the solutions' caches. Each worker runs [`serve`] with the same solution type
+//! as the coordinator, and evaluates the solutions it's sent in parallel.
+//!
+//! If a worker can't be reached, disconnects, or sends back something invalid,
+//! its share of the solutions is handed to the remaining workers, up to a
+//! [number of times](./struct.Coordinator.html#method.with_retries).
+//! Solutions that still haven't been evaluated after that are evaluated by the coordinator itself,
+//! so a run keeps going even if every worker is lost.
+//! Workers that were lost are reconnected to the next time a population is evaluated.
+//!
+//! Messages in both directions are the length of the payload as a little-endian `u64`,
+//! followed by the payload, encoded with [`bincode`] using fixed-width little-endian integers.
+//! The coordinator sends a sequence of solutions, and the worker answers with
+//! a sequence of their fitness values in the same order.
+//!
+//! ```no_run
+//! use std::net::TcpListener;
+//! use eviolite::prelude::*;
+//! use eviolite::distributed::{serve, Coordinator};
+//! # #[derive(Clone, serde::Serialize, serde::Deserialize)]
+//! # struct Expensive;
+//! # impl Solution for Expensive {
+//! #     type Fitness = f64;
+//! #     fn generate() -> Self { Expensive }
+//! #     fn evaluate(&self) -> f64 { 0.0 }
+//! #     fn crossover(_: &mut Self, _: &mut Self) {}
+//! #     fn mutate(&mut self) {}
+//! # }
+//!
+//! if std::env::args().any(|arg| arg == "--worker") {
+//!     serve::<Expensive>(TcpListener::bind("0.0.0.0:7878").unwrap()).unwrap();
+//! } else {
+//!     let coordinator = Coordinator::new(["node1:7878", "node2:7878"]).unwrap();
+//!     let evo: Evolution<Expensive, _, _, ()> = Evolution::new(
+//!         alg::Simple::new(100, 0.5, 0.2, select::Tournament::new(3)),
+//!         hof::BestN::new(1),
+//!     )
+//!     .with_coordinator(coordinator);
+//!     evo.run_for(50);
+//! }
+//! ```
+//!
+//! [`Coordinator`]: ./struct.Coordinator.html
+//! [`serve`]: ./fn.serve.html
+
+use std::{
+    io::{self, BufReader, BufWriter, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    thread,
+    time::Duration,
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use bincode::Options;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    utils::{
+        counter,
+        encoding::{bincode_error, options},
+    },
+    Cached, Solution,
+};
+
+/// The sending end of distributed evaluation, which hands solutions out to workers
+///
+/// See the [module-level documentation](./index.html) for how to use it.
+pub struct Coordinator {
+    workers: Vec<Worker>,
+    timeout: Option<Duration>,
+    retries: usize,
+}
+
+struct Worker {
+    addr: SocketAddr,
+    stream: Option<TcpStream>,
+}
+
+impl Coordinator {
+    /// Create a new `Coordinator` that sends solutions to the workers listening at `addrs`.
+    ///
+    /// The workers are only connected to once a population is evaluated,
+    /// so they don't need to be running yet.
+    /// Returns an error if any of the addresses can't be resolved.
+    pub fn new<A: ToSocketAddrs>(addrs: impl IntoIterator<Item = A>) -> io::Result<Self> {
+        let mut workers = Vec::new();
+        for addr in addrs {
+            for addr in addr.to_socket_addrs()? {
+                workers.push(Worker { addr, stream: None });
+            }
+        }
+        Ok(Coordinator {
+            workers,
+            timeout: None,
+            retries: 2,
+        })
+    }
+
+    /// Give up on a worker when connecting to it or a single read or write takes longer than `timeout`.
+    ///
+    /// By default, there's no timeout, so a worker that hangs without disconnecting stalls the run.
+    /// The timeout has to be longer than the slowest batch of evaluations takes.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Hand the solutions of a lost worker to the remaining workers up to `retries` times
+    /// in each generation, before evaluating the rest on the coordinator. The default is 2.
+    pub fn with_retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Get the number of workers that are currently connected.
+    pub fn connected(&self) -> usize {
+        self.workers.iter().filter(|w| w.stream.is_some()).count()
+    }
+
+    /// Evaluate the fitness of every solution in a population that hasn't been evaluated yet
+    /// on the workers.
+    ///
+    /// The fitness values are cached and counted by [`evaluation_count()`]
+    /// just like they would be by [`par_evaluate()`].
+    ///
+    /// [`evaluation_count()`]: ../fitness/fn.evaluation_count.html
+    /// [`par_evaluate()`]: ../fitness/fn.par_evaluate.html
+    pub fn evaluate<T>(&mut self, pop: &[Cached<T>])
+    where
+        T: Solution + Serialize,
+        T::Fitness: DeserializeOwned,
+    {
+        let mut pending: Vec<&Cached<T>> = pop.iter().filter(|ind| ind.fitness().is_none()).collect();
+        if pending.is_empty() {
+            return;
+        }
+        for worker in &mut self.workers {
+            if worker.stream.is_none() {
+                worker.stream = connect(worker.addr, self.timeout).ok();
+            }
+        }
+
+        for _ in 0..=self.retries {
+            let mut live: Vec<&mut Worker> = self
+                .workers
+                .iter_mut()
+                .filter(|w| w.stream.is_some())
+                .collect();
+            if pending.is_empty() || live.is_empty() {
+                break;
+            }
+
+            let batch_size = pending.len().div_ceil(live.len());
+            // The fitness values the workers send back are counted in the run that's evaluating
+            let run = counter::current();
+            pending = thread::scope(|scope| {
+                let handles: Vec<_> = live
+                    .iter_mut()
+                    .zip(pending.chunks(batch_size))
+                    .map(|(worker, batch)| {
+                        let run = run.clone();
+                        scope.spawn(move || {
+                            let _run = counter::enter(run);
+                            worker.exchange(batch)
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().unwrap())
+                    .collect()
+            });
+        }
+
+        // Whatever the workers couldn't evaluate is evaluated here
+        #[cfg(feature = "parallel")]
+        crate::utils::thread_pool::install(|| {
+            pending
+                .par_iter()
+                .for_each(counter::carry(|ind: &&Cached<T>| {
+                    ind.evaluate();
+                }))
+        });
+        #[cfg(not(feature = "parallel"))]
+        for ind in pending {
+            ind.evaluate();
+        }
+    }
+}
+
+impl Worker {
+    // Have the worker evaluate `batch`, returning the solutions it failed to evaluate
+    fn exchange<'a, T>(&mut self, batch: &[&'a Cached<T>]) -> Vec<&'a Cached<T>>
+    where
+        T: Solution + Serialize,
+        T::Fitness: DeserializeOwned,
+    {
+        let stream = self.stream.as_ref().unwrap();
+        let solutions: Vec<&T> = batch.iter().map(|&ind| ind.as_ref()).collect();
+        let result = write_frame(stream, &solutions)
+            .and_then(|()| read_frame::<Vec<T::Fitness>>(stream))
+            .and_then(|fitness| {
+                if fitness.len() == batch.len() {
+                    Ok(fitness)
+                } else {
+                    Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "worker returned the wrong number of fitness values",
+                    ))
+                }
+            });
+
+        match result {
+            Ok(fitness) => {
+                for (ind, fitness) in batch.iter().zip(fitness) {
+                    let _ = ind.set_fitness(fitness);
+                }
+                Vec::new()
+            }
+            Err(_) => {
+                self.stream = None;
+                batch.to_vec()
+            }
+        }
+    }
+}
+
+/// Run a worker that evaluates the solutions sent to it through `listener` by a [`Coordinator`].
+///
+/// Every connection is served on its own thread, and the solutions in each batch
+/// are evaluated in parallel if the `parallel` crate feature is enabled.
+/// This only returns if accepting a connection fails.
+///
+/// [`Coordinator`]: ./struct.Coordinator.html
+pub fn serve<T>(listener: TcpListener) -> io::Result<()>
+where
+    T: Solution + DeserializeOwned,
+    T::Fitness: Serialize + Send,
+{
+    loop {
+        let (stream, _) = listener.accept()?;
+        thread::spawn(move || serve_connection::<T>(stream));
+    }
+}
+
+fn serve_connection<T>(stream: TcpStream) -> io::Result<()>
+where
+    T: Solution + DeserializeOwned,
+    T::Fitness: Serialize + Send,
+{
+    stream.set_nodelay(true)?;
+    loop {
+        let solutions: Vec<T> = match read_frame(&stream) {
+            Ok(solutions) => solutions,
+            // the coordinator hung up between batches
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(error) => return Err(error),
+        };
+        #[cfg(feature = "parallel")]
+        let fitness: Vec<T::Fitness> = solutions.par_iter().map(T::evaluate).collect();
+        #[cfg(not(feature = "parallel"))]
+        let fitness: Vec<T::Fitness> = solutions.iter().map(T::evaluate).collect();
+        write_frame(&stream, &fitness)?;
+    }
+}
+
+fn connect(addr: SocketAddr, timeout: Option<Duration>) -> io::Result<TcpStream> {
+    let stream = match timeout {
+        Some(timeout) => TcpStream::connect_timeout(&addr, timeout)?,
+        None => TcpStream::connect(addr)?,
+    };
+    stream.set_read_timeout(timeout)?;
+    stream.set_write_timeout(timeout)?;
+    stream.set_nodelay(true)?;
+    Ok(stream)
+}
+
+fn write_frame<S: Serialize>(stream: &TcpStream, message: &S) -> io::Result<()> {
+    let len = options()
+        .serialized_size(message)
+        .map_err(|error| bincode_error(*error))?;
+    let mut writer = BufWriter::new(stream);
+    writer.write_all(&len.to_le_bytes())?;
+    options()
+        .serialize_into(&mut writer, message)
+        .map_err(|error| bincode_error(*error))?;
+    writer.flush()
+}
+
+fn read_frame<D: DeserializeOwned>(stream: &TcpStream) -> io::Result<D> {
+    let mut reader = BufReader::new(stream);
+    let mut len = [0; 8];
+    reader.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+    options()
+        .with_limit(len)
+        .deserialize_from(reader.take(len))
+        .map_err(|error| bincode_error(*error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutils::Walk;
+
+    fn worker() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve::<Walk>(listener));
+        addr
+    }
+
+    // A worker that accepts connections and hangs up on them right away
+    fn broken_worker() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                drop(stream);
+            }
+        });
+        addr
+    }
+
+    fn population() -> Vec<Cached<Walk>> {
+        (0..50)
+            .map(|i| Cached::new(Walk(i as f64 - 25.0)))
+            .collect()
+    }
+
+    fn assert_evaluated(pop: &[Cached<Walk>]) {
+        for ind in pop {
+            assert!(ind.fitness().is_some());
+            assert_eq!(ind.evaluate(), ind.as_ref().evaluate());
+        }
+    }
+
+    #[test]
+    fn workers_evaluate() {
+        let mut coordinator = Coordinator::new([worker(), worker()]).unwrap();
+        let pop = population();
+        coordinator.evaluate(&pop);
+        assert_eq!(coordinator.connected(), 2);
+        assert_evaluated(&pop);
+    }
+
+    #[test]
+    fn lost_workers_are_replaced() {
+        let unreachable = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let mut coordinator =
+            Coordinator::new([broken_worker(), unreachable, worker(), broken_worker()]).unwrap();
+        let pop = population();
+        coordinator.evaluate(&pop);
+        assert_eq!(coordinator.connected(), 1);
+        assert_evaluated(&pop);
+
+        // with no workers at all, the coordinator evaluates everything itself
+        let mut coordinator = Coordinator::new([unreachable]).unwrap();
+        let pop = population();
+        coordinator.evaluate(&pop);
+        assert_evaluated(&pop);
+    }
+
+    #[test]
+    fn runs_count_remote_evaluations() {
+        let evo = |workers: &[SocketAddr]| -> crate::Evolution<Walk, _, _, ()> {
+            crate::Evolution::new(
+                crate::alg::Simple::new(10, 0.5, 0.5, crate::select::Tournament::new(2)),
+                crate::hof::BestN::new(1),
+            )
+            .with_coordinator(Coordinator::new(workers.iter().copied()).unwrap())
+        };
+        let log = evo(&[worker(), worker()]).run_for_evaluations(100);
+        assert!(log.evaluations >= 100);
+
+        // and the evaluations the coordinator falls back on
+        let unreachable = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let log = evo(&[unreachable]).run_for_evaluations(100);
+        assert!(log.evaluations >= 100);
+    }
+}
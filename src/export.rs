@@ -0,0 +1,260 @@
+//! Exporting results to other formats
+//!
+//! This module contains writers that turn populations, halls of fame, and other results
+//! into formats that can be read by other tools, such as spreadsheets or data analysis libraries.
+//!
+//! Fitness values are converted automatically through the [`FitnessColumns`] trait,
+//! which is implemented for `f64`, the other primitive numeric types, and [`MultiObjective`].
+//! Per-generation statistics are converted through [`StatsColumns`],
+//! which is implemented for the built-in [statistics] and tuples of them, and a run's statistics can be written as CSV
+//! with [`Log::write_stats_csv()`], or streamed to a CSV file during the run with [`StatsCsvWriter`].
+//!
+//! Any [`LogSink`], such as [`JsonLines`], can record a run generation by generation while it happens.
+//! [`Logbook`] prints a table like DEAP's logbook, for watching a run on the console.
+//!
+//! The `parquet` crate feature enables [`ParquetLog`], which writes per-generation statistics
+//! to [Parquet](https://parquet.apache.org/) files that can be read with Arrow, pandas, polars, and the like.
+//! It also enables [`ArrowLog`], which streams the same columns as
+//! [Arrow IPC](https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format) record batches.
+//!
+//! The `tensorboard` crate feature enables [`TensorBoardLog`], which writes per-generation scalars
+//! to [TensorBoard](https://www.tensorflow.org/tensorboard) event files.
+//!
+//! The `plotters` crate feature enables [`Log::plot_convergence()`], which plots how fitness changed
+//! over a run, and the final Pareto front of runs with two objectives, to a PNG or SVG image.
+//!
+//! [`FitnessColumns`]: ./trait.FitnessColumns.html
+//! [`StatsColumns`]: ./trait.StatsColumns.html
+//! [statistics]: ../stats/index.html
+//! [`Log::write_stats_csv()`]: ../struct.Log.html#method.write_stats_csv
+//! [`StatsCsvWriter`]: ./struct.StatsCsvWriter.html
+//! [`Log::plot_convergence()`]: ../struct.Log.html#method.plot_convergence
+//! [`ParquetLog`]: ./struct.ParquetLog.html
+//! [`ArrowLog`]: ./struct.ArrowLog.html
+//! [`TensorBoardLog`]: ./struct.TensorBoardLog.html
+//! [`LogSink`]: ./trait.LogSink.html
+//! [`JsonLines`]: ./struct.JsonLines.html
+//! [`Logbook`]: ./struct.Logbook.html
+//! [`MultiObjective`]: ../fitness/struct.MultiObjective.html
+
+#[cfg(feature = "parquet")]
+mod arrow;
+mod csv;
+mod jsonl;
+mod logbook;
+#[cfg(feature = "parquet")]
+mod parquet;
+#[cfg(feature = "plotters")]
+mod plot;
+#[cfg(feature = "tensorboard")]
+mod tensorboard;
+
+#[cfg(feature = "parquet")]
+#[cfg_attr(docsrs, doc(cfg(feature = "parquet")))]
+pub use arrow::{ArrowLog, ArrowStreamWriter};
+pub use csv::{CsvExporter, StatsCsvWriter};
+pub use jsonl::JsonLines;
+pub use logbook::Logbook;
+#[cfg(feature = "parquet")]
+#[cfg_attr(docsrs, doc(cfg(feature = "parquet")))]
+pub use parquet::{ColumnType, ParquetLog, ParquetWriter, Value};
+#[cfg(feature = "plotters")]
+#[cfg_attr(docsrs, doc(cfg(feature = "plotters")))]
+pub use plot::ConvergenceStats;
+#[cfg(feature = "tensorboard")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tensorboard")))]
+pub use tensorboard::TensorBoardLog;
+
+use std::io;
+
+use crate::{
+    fitness::MultiObjective,
+    hof::HallOfFame,
+    stats::{
+        DiversitySummary, EvalCounts, FitnessBasic, FitnessBasicMulti, FitnessSummary,
+        GenerationStats, SpeciesSummary,
+    },
+    Generation, Solution,
+};
+
+/// A destination that records every generation of a run as it happens
+///
+/// Call [`.record()`] from the callback passed to one of [`Evolution`]'s `run_*_with` methods.
+///
+/// [`.record()`]: ./trait.LogSink.html#tymethod.record
+/// [`Evolution`]: ../struct.Evolution.html
+pub trait LogSink<T, Hof, Stat>
+where
+    T: Solution,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+{
+    /// Record a generation.
+    fn record(&mut self, generation: Generation<T, Hof, Stat>) -> io::Result<()>;
+
+    /// Make sure everything recorded so far has reached its destination.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A fitness type that can be written as one or more named columns
+pub trait FitnessColumns {
+    /// Get the names of the columns.
+    fn headers() -> Vec<String>;
+
+    /// Get the values of the columns for this fitness.
+    fn values(&self) -> Vec<f64>;
+}
+
+macro_rules! scalar_columns {
+    ($($ty:ty),*) => {
+        $(
+            impl FitnessColumns for $ty {
+                fn headers() -> Vec<String> {
+                    vec!["fitness".to_owned()]
+                }
+
+                fn values(&self) -> Vec<f64> {
+                    vec![*self as f64]
+                }
+            }
+        )*
+    };
+}
+
+scalar_columns!(f64, f32, i32, i64, u32, u64, usize);
+
+/// The columns are named `fitness_0`, `fitness_1`, and so on, and contain the weighted values.
+impl<const M: usize> FitnessColumns for MultiObjective<M> {
+    fn headers() -> Vec<String> {
+        (0..M).map(|m| format!("fitness_{}", m)).collect()
+    }
+
+    fn values(&self) -> Vec<f64> {
+        self.to_vec()
+    }
+}
+
+/// Per-generation statistics that can be written as one or more named columns
+pub trait StatsColumns {
+    /// Get the names of the columns.
+    fn headers() -> Vec<String>;
+
+    /// Get the values of the columns for these statistics.
+    fn values(&self) -> Vec<f64>;
+}
+
+impl StatsColumns for () {
+    fn headers() -> Vec<String> {
+        Vec::new()
+    }
+
+    fn values(&self) -> Vec<f64> {
+        Vec::new()
+    }
+}
+
+macro_rules! tuple_columns {
+    ($($name:ident $idx:tt),+) => {
+        /// The columns are those of each of the statistics in order.
+        impl<$($name: StatsColumns),+> StatsColumns for ($($name,)+) {
+            fn headers() -> Vec<String> {
+                let mut headers = Vec::new();
+                $(headers.extend($name::headers());)+
+                headers
+            }
+
+            fn values(&self) -> Vec<f64> {
+                let mut values = Vec::new();
+                $(values.extend(self.$idx.values());)+
+                values
+            }
+        }
+    };
+}
+
+tuple_columns!(A 0, B 1);
+tuple_columns!(A 0, B 1, C 2);
+tuple_columns!(A 0, B 1, C 2, D 3);
+
+/// The columns are `mean`, `variance`, and `stdev`.
+impl StatsColumns for FitnessBasic {
+    fn headers() -> Vec<String> {
+        vec!["mean".to_owned(), "variance".to_owned(), "stdev".to_owned()]
+    }
+
+    fn values(&self) -> Vec<f64> {
+        vec![self.mean(), self.variance(), self.stdev()]
+    }
+}
+
+/// The columns are `avg`, `std`, `min`, and `max`, named like the ones in DEAP's examples.
+impl StatsColumns for FitnessSummary {
+    fn headers() -> Vec<String> {
+        ["avg", "std", "min", "max"].map(str::to_owned).to_vec()
+    }
+
+    fn values(&self) -> Vec<f64> {
+        vec![self.mean(), self.stdev(), self.min(), self.max()]
+    }
+}
+
+/// The columns are `species` and `largest_species`.
+impl StatsColumns for SpeciesSummary {
+    fn headers() -> Vec<String> {
+        vec!["species".to_owned(), "largest_species".to_owned()]
+    }
+
+    fn values(&self) -> Vec<f64> {
+        vec![self.count() as f64, self.largest() as f64]
+    }
+}
+
+/// The columns are `mean_distance` and `min_distance`.
+impl StatsColumns for DiversitySummary {
+    fn headers() -> Vec<String> {
+        vec!["mean_distance".to_owned(), "min_distance".to_owned()]
+    }
+
+    fn values(&self) -> Vec<f64> {
+        vec![self.mean(), self.min()]
+    }
+}
+
+/// The columns are `evaluated`, `cached`, `hit_rate`, and `evaluation_secs`.
+impl StatsColumns for EvalCounts {
+    fn headers() -> Vec<String> {
+        ["evaluated", "cached", "hit_rate", "evaluation_secs"]
+            .map(str::to_owned)
+            .to_vec()
+    }
+
+    fn values(&self) -> Vec<f64> {
+        vec![
+            self.evaluated() as f64,
+            self.cached() as f64,
+            self.hit_rate(),
+            self.evaluation_time().as_secs_f64(),
+        ]
+    }
+}
+
+/// The columns are `mean_0`, `variance_0`, `stdev_0`, `mean_1`, and so on.
+impl<const M: usize> StatsColumns for FitnessBasicMulti<M> {
+    fn headers() -> Vec<String> {
+        (0..M)
+            .flat_map(|m| {
+                ["mean", "variance", "stdev"]
+                    .into_iter()
+                    .map(move |name| format!("{}_{}", name, m))
+            })
+            .collect()
+    }
+
+    fn values(&self) -> Vec<f64> {
+        (0..M)
+            .flat_map(|m| [self.mean()[m], self.variance()[m], self.stdev()[m]])
+            .collect()
+    }
+}
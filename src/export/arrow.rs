@@ -0,0 +1,704 @@
+use std::{
+    io::{self, Write},
+    marker::PhantomData,
+};
+
+use super::{
+    parquet::{individual_rows, individuals_schema, stats_row, stats_schema},
+    ColumnType, FitnessColumns, LogSink, StatsColumns, Value,
+};
+use crate::{hof::HallOfFame, stats::GenerationStats, Generation, Solution};
+
+/// A minimal streaming writer for Arrow IPC streams
+///
+/// The stream starts with the schema and continues with one record batch every [batch size] rows,
+/// so arbitrarily many rows can be written with bounded memory. Every column is non-nullable.
+/// This is the format read by `pyarrow.ipc.open_stream()`, `arrow_ipc::reader::StreamReader`,
+/// and `polars.read_ipc_stream()`.
+/// The stream is only complete after [`.finish()`] has been called.
+///
+/// [batch size]: ./struct.ArrowStreamWriter.html#method.with_batch_size
+/// [`.finish()`]: ./struct.ArrowStreamWriter.html#method.finish
+pub struct ArrowStreamWriter<W: Write> {
+    writer: W,
+    schema: Vec<(String, ColumnType)>,
+    buffers: Vec<Vec<u8>>,
+    buffered_rows: usize,
+    batch_size: usize,
+}
+
+// Version 5 of the metadata, the current one
+const METADATA_VERSION: i16 = 4;
+const SCHEMA: u8 = 1;
+const RECORD_BATCH: u8 = 3;
+const INT: u8 = 2;
+const FLOATING_POINT: u8 = 3;
+const DOUBLE: i16 = 2;
+
+impl<W: Write> ArrowStreamWriter<W> {
+    /// Start writing a stream with the given column names and types.
+    pub fn new(mut writer: W, schema: Vec<(String, ColumnType)>) -> io::Result<Self> {
+        write_message(&mut writer, &schema_message(&schema), &[])?;
+        Ok(ArrowStreamWriter {
+            writer,
+            buffers: vec![Vec::new(); schema.len()],
+            schema,
+            buffered_rows: 0,
+            batch_size: 1 << 16,
+        })
+    }
+
+    /// Write a record batch every `rows` rows. The default is 2¹⁶ rows.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `rows` is 0.
+    pub fn with_batch_size(mut self, rows: usize) -> Self {
+        assert!(rows > 0, "record batches must contain at least one row");
+        self.batch_size = rows;
+        self
+    }
+
+    /// Get the columns of the stream.
+    pub fn schema(&self) -> &[(String, ColumnType)] {
+        &self.schema
+    }
+
+    /// Append a row to the stream.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `row` doesn't have exactly one value of the right type for each column.
+    pub fn write_row(&mut self, row: &[Value]) -> io::Result<()> {
+        assert_eq!(row.len(), self.schema.len(), "row has the wrong length");
+        for ((buffer, (name, column_type)), value) in
+            self.buffers.iter_mut().zip(&self.schema).zip(row)
+        {
+            match (column_type, value) {
+                (ColumnType::Int64, Value::Int64(value)) => {
+                    buffer.extend_from_slice(&value.to_le_bytes())
+                }
+                (ColumnType::Double, Value::Double(value)) => {
+                    buffer.extend_from_slice(&value.to_le_bytes())
+                }
+                _ => panic!("value {:?} has the wrong type for column {}", value, name),
+            }
+        }
+        self.buffered_rows += 1;
+        if self.buffered_rows == self.batch_size {
+            self.flush_batch()?;
+        }
+        Ok(())
+    }
+
+    /// Write any buffered rows and the end-of-stream marker,
+    /// returning the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.buffered_rows > 0 {
+            self.flush_batch()?;
+        }
+        self.writer.write_all(&CONTINUATION)?;
+        self.writer.write_all(&0i32.to_le_bytes())?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn flush_batch(&mut self) -> io::Result<()> {
+        // every value is 8 bytes, so the buffers stay aligned without padding
+        let body: Vec<u8> = self.buffers.concat();
+        let metadata = record_batch_message(self.buffered_rows, &self.buffers, body.len());
+        write_message(&mut self.writer, &metadata, &body)?;
+        for buffer in &mut self.buffers {
+            buffer.clear();
+        }
+        self.buffered_rows = 0;
+        Ok(())
+    }
+}
+
+const CONTINUATION: [u8; 4] = [0xff; 4];
+
+// An encapsulated message: a marker, the length of the metadata, the metadata padded so that
+// the body starts at a multiple of 8 bytes, and the body.
+fn write_message<W: Write>(writer: &mut W, metadata: &[u8], body: &[u8]) -> io::Result<()> {
+    let padded = metadata.len().div_ceil(8) * 8;
+    writer.write_all(&CONTINUATION)?;
+    writer.write_all(&(padded as i32).to_le_bytes())?;
+    writer.write_all(metadata)?;
+    writer.write_all(&vec![0; padded - metadata.len()])?;
+    writer.write_all(body)
+}
+
+fn schema_message(schema: &[(String, ColumnType)]) -> Vec<u8> {
+    let mut fb = FlatBuffer::default();
+    let message = fb.table(&[
+        Scalar::I16(METADATA_VERSION),
+        Scalar::U8(SCHEMA),
+        Scalar::Offset,
+        Scalar::I64(0),
+    ]);
+    fb.root(message.0);
+
+    let header = fb.table(&[Scalar::I16(0), Scalar::Offset]); // little-endian
+    fb.patch(message.1[0], header.0);
+    let fields = fb.offset_vector(schema.len());
+    fb.patch(header.1[0], fields.0);
+    for ((name, column_type), slot) in schema.iter().zip(fields.1) {
+        let type_type = match column_type {
+            ColumnType::Int64 => INT,
+            ColumnType::Double => FLOATING_POINT,
+        };
+        let field = fb.table(&[
+            Scalar::Offset,
+            Scalar::Bool(false),
+            Scalar::U8(type_type),
+            Scalar::Offset,
+            Scalar::Absent,
+            Scalar::Offset,
+        ]);
+        fb.patch(slot, field.0);
+        let name = fb.string(name);
+        fb.patch(field.1[0], name);
+        let column_type = match column_type {
+            ColumnType::Int64 => fb.table(&[Scalar::I32(64), Scalar::Bool(true)]),
+            ColumnType::Double => fb.table(&[Scalar::I16(DOUBLE)]),
+        };
+        fb.patch(field.1[1], column_type.0);
+        // readers expect a list of child fields even for types that have none
+        let children = fb.offset_vector(0);
+        fb.patch(field.1[2], children.0);
+    }
+    fb.bytes
+}
+
+fn record_batch_message(rows: usize, buffers: &[Vec<u8>], body_len: usize) -> Vec<u8> {
+    let mut fb = FlatBuffer::default();
+    let message = fb.table(&[
+        Scalar::I16(METADATA_VERSION),
+        Scalar::U8(RECORD_BATCH),
+        Scalar::Offset,
+        Scalar::I64(body_len as i64),
+    ]);
+    fb.root(message.0);
+
+    let header = fb.table(&[Scalar::I64(rows as i64), Scalar::Offset, Scalar::Offset]);
+    fb.patch(message.1[0], header.0);
+    // each column has a length and a null count of 0
+    let nodes: Vec<[i64; 2]> = vec![[rows as i64, 0]; buffers.len()];
+    let nodes = fb.struct_vector(&nodes);
+    fb.patch(header.1[0], nodes);
+    // and an empty validity buffer, since there are no nulls, followed by its values
+    let mut offset = 0;
+    let mut locations = Vec::with_capacity(2 * buffers.len());
+    for buffer in buffers {
+        locations.push([offset, 0]);
+        locations.push([offset, buffer.len() as i64]);
+        offset += buffer.len() as i64;
+    }
+    let locations = fb.struct_vector(&locations);
+    fb.patch(header.1[1], locations);
+    fb.bytes
+}
+
+#[derive(Clone, Copy)]
+enum Scalar {
+    Absent,
+    Bool(bool),
+    U8(u8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    // A reference to another object, filled in later with `.patch()`
+    Offset,
+}
+
+impl Scalar {
+    fn size(self) -> usize {
+        match self {
+            Scalar::Absent => 0,
+            Scalar::Bool(_) | Scalar::U8(_) => 1,
+            Scalar::I16(_) => 2,
+            Scalar::I32(_) | Scalar::Offset => 4,
+            Scalar::I64(_) => 8,
+        }
+    }
+}
+
+// Just enough of the FlatBuffers format to write Arrow IPC metadata, laid out front to back:
+// every table is preceded by its vtable, and followed by the objects it refers to,
+// since references can only point forward.
+struct FlatBuffer {
+    bytes: Vec<u8>,
+}
+
+impl Default for FlatBuffer {
+    // Starts with room for the reference to the root table
+    fn default() -> Self {
+        FlatBuffer { bytes: vec![0; 4] }
+    }
+}
+
+impl FlatBuffer {
+    fn pad(&mut self, align: usize, remainder: usize) {
+        while self.bytes.len() % align != remainder {
+            self.bytes.push(0);
+        }
+    }
+
+    fn root(&mut self, table: usize) {
+        self.patch(0, table);
+    }
+
+    // Point the reference at `at` to `target`
+    fn patch(&mut self, at: usize, target: usize) {
+        let offset = (target - at) as u32;
+        self.bytes[at..at + 4].copy_from_slice(&offset.to_le_bytes());
+    }
+
+    // Write a table whose fields, numbered in order, are `fields`,
+    // returning where it starts and where each of its references is
+    fn table(&mut self, fields: &[Scalar]) -> (usize, Vec<usize>) {
+        // Laying the fields out from largest to smallest after the 4-byte reference to the vtable,
+        // starting 4 bytes before a multiple of 8, aligns every one of them.
+        let mut order: Vec<usize> = (0..fields.len()).collect();
+        order.sort_by_key(|&i| core::cmp::Reverse(fields[i].size()));
+        let mut positions = vec![0; fields.len()];
+        let mut size = 4;
+        for &i in &order {
+            if fields[i].size() > 0 {
+                positions[i] = size;
+                size += fields[i].size();
+            }
+        }
+
+        self.pad(2, 0);
+        let vtable = self.bytes.len();
+        self.bytes
+            .extend_from_slice(&(4 + 2 * fields.len() as u16).to_le_bytes());
+        self.bytes.extend_from_slice(&(size as u16).to_le_bytes());
+        for &position in &positions {
+            self.bytes
+                .extend_from_slice(&(position as u16).to_le_bytes());
+        }
+
+        self.pad(8, 4);
+        let table = self.bytes.len();
+        self.bytes
+            .extend_from_slice(&((table - vtable) as i32).to_le_bytes());
+        let mut references = Vec::new();
+        for &i in &order {
+            match fields[i] {
+                Scalar::Absent => {}
+                Scalar::Bool(value) => self.bytes.push(value as u8),
+                Scalar::U8(value) => self.bytes.push(value),
+                Scalar::I16(value) => self.bytes.extend_from_slice(&value.to_le_bytes()),
+                Scalar::I32(value) => self.bytes.extend_from_slice(&value.to_le_bytes()),
+                Scalar::I64(value) => self.bytes.extend_from_slice(&value.to_le_bytes()),
+                Scalar::Offset => self.bytes.extend_from_slice(&[0; 4]),
+            }
+        }
+        for (i, field) in fields.iter().enumerate() {
+            if let Scalar::Offset = field {
+                references.push(table + positions[i]);
+            }
+        }
+        (table, references)
+    }
+
+    fn string(&mut self, value: &str) -> usize {
+        self.pad(4, 0);
+        let start = self.bytes.len();
+        self.bytes
+            .extend_from_slice(&(value.len() as u32).to_le_bytes());
+        self.bytes.extend_from_slice(value.as_bytes());
+        self.bytes.push(0);
+        start
+    }
+
+    // Write a vector of `len` references, returning where it starts and where each reference is
+    fn offset_vector(&mut self, len: usize) -> (usize, Vec<usize>) {
+        self.pad(4, 0);
+        let start = self.bytes.len();
+        self.bytes.extend_from_slice(&(len as u32).to_le_bytes());
+        let references = (0..len).map(|i| start + 4 + 4 * i).collect();
+        self.bytes.resize(start + 4 + 4 * len, 0);
+        (start, references)
+    }
+
+    // Write a vector of structs made of two 64-bit integers, aligned to 8 bytes
+    fn struct_vector(&mut self, elements: &[[i64; 2]]) -> usize {
+        self.pad(8, 4);
+        let start = self.bytes.len();
+        self.bytes
+            .extend_from_slice(&(elements.len() as u32).to_le_bytes());
+        for value in elements.iter().flatten() {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        start
+    }
+}
+
+/// Streams per-generation statistics, and optionally the fitness of every individual, as Arrow IPC streams
+///
+/// This writes the same columns as [`ParquetLog`], as record batches that can be read
+/// while the run is still going, e.g. by another process reading from a pipe.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+/// use eviolite::export::ArrowLog;
+///
+/// # fn main() -> std::io::Result<()> {
+/// let evo: Evolution<Benchmark<Sphere, 2>, _, _, stats::FitnessBasic> = Evolution::new(
+///     alg::MuPlusLambda::new(20, 40, 0.6, 0.3, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// );
+/// let mut arrow = ArrowLog::new(Vec::new())?.with_batch_size(4);
+/// let mut result = Ok(());
+/// evo.run_for_with(10, |generation| {
+///     if result.is_ok() {
+///         result = arrow.record(generation);
+///     }
+/// });
+/// result?;
+/// let (stats, _) = arrow.finish()?;
+/// assert!(stats.ends_with(&[0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0]));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`ParquetLog`]: ./struct.ParquetLog.html
+pub struct ArrowLog<T, S, W: Write> {
+    stats: ArrowStreamWriter<W>,
+    individuals: Option<ArrowStreamWriter<W>>,
+    _marker: PhantomData<fn(&T, &S)>,
+}
+
+impl<T, S, W> ArrowLog<T, S, W>
+where
+    T: Solution,
+    T::Fitness: FitnessColumns,
+    S: GenerationStats<T>,
+    S::Output: StatsColumns,
+    W: Write,
+{
+    /// Start streaming statistics to `stats`.
+    pub fn new(stats: W) -> io::Result<Self> {
+        Ok(ArrowLog {
+            stats: ArrowStreamWriter::new(stats, stats_schema::<T, S>())?,
+            individuals: None,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Also stream the fitness of every individual in every generation to `individuals`.
+    pub fn with_individuals(mut self, individuals: W) -> io::Result<Self> {
+        self.individuals = Some(ArrowStreamWriter::new(
+            individuals,
+            individuals_schema::<T>(),
+        )?);
+        Ok(self)
+    }
+
+    /// Write a record batch to each stream every `rows` rows, so statistics are written every `rows` generations.
+    /// Call this after [`.with_individuals()`] for it to apply to both streams. The default is 2¹⁶ rows.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `rows` is 0.
+    ///
+    /// [`.with_individuals()`]: ./struct.ArrowLog.html#method.with_individuals
+    pub fn with_batch_size(mut self, rows: usize) -> Self {
+        self.stats = self.stats.with_batch_size(rows);
+        self.individuals = self
+            .individuals
+            .map(|individuals| individuals.with_batch_size(rows));
+        self
+    }
+
+    /// Record a generation.
+    pub fn record<Hof: HallOfFame<T>>(
+        &mut self,
+        generation: Generation<T, Hof, S>,
+    ) -> io::Result<()> {
+        self.stats.write_row(&stats_row(&generation))?;
+        if let Some(individuals) = &mut self.individuals {
+            for row in individual_rows(&generation) {
+                individuals.write_row(&row)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Finish both streams, returning the underlying writers.
+    pub fn finish(self) -> io::Result<(W, Option<W>)> {
+        Ok((
+            self.stats.finish()?,
+            self.individuals
+                .map(ArrowStreamWriter::finish)
+                .transpose()?,
+        ))
+    }
+}
+
+impl<T, Hof, S, W> LogSink<T, Hof, S> for ArrowLog<T, S, W>
+where
+    T: Solution,
+    T::Fitness: FitnessColumns,
+    Hof: HallOfFame<T>,
+    S: GenerationStats<T>,
+    S::Output: StatsColumns,
+    W: Write,
+{
+    fn record(&mut self, generation: Generation<T, Hof, S>) -> io::Result<()> {
+        ArrowLog::record(self, generation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hof::BestN, stats::FitnessBasicMulti, testutils::Foo, Cached};
+
+    fn read<const N: usize>(bytes: &[u8], at: usize) -> [u8; N] {
+        bytes[at..at + N].try_into().unwrap()
+    }
+
+    fn read_u32(bytes: &[u8], at: usize) -> usize {
+        u32::from_le_bytes(read(bytes, at)) as usize
+    }
+
+    fn read_i64(bytes: &[u8], at: usize) -> i64 {
+        i64::from_le_bytes(read(bytes, at))
+    }
+
+    // The position of field `id` of the table at `table`, checking that it's aligned to `size`
+    fn field(bytes: &[u8], table: usize, id: usize, size: usize) -> Option<usize> {
+        let vtable = table - i32::from_le_bytes(read(bytes, table)) as usize;
+        let vtable_len = u16::from_le_bytes(read(bytes, vtable)) as usize;
+        if 4 + 2 * id >= vtable_len {
+            return None;
+        }
+        match u16::from_le_bytes(read(bytes, vtable + 4 + 2 * id)) as usize {
+            0 => None,
+            offset => {
+                assert_eq!((table + offset) % size, 0, "field {} is misaligned", id);
+                Some(table + offset)
+            }
+        }
+    }
+
+    fn reference(bytes: &[u8], table: usize, id: usize) -> usize {
+        let at = field(bytes, table, id, 4).unwrap();
+        at + read_u32(bytes, at)
+    }
+
+    fn vector(bytes: &[u8], at: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..read_u32(bytes, at)).map(move |i| at + 4 + 4 * i + read_u32(bytes, at + 4 + 4 * i))
+    }
+
+    // The schema, the values of each column, and the number of rows in each batch
+    type Stream = (Vec<(String, ColumnType)>, Vec<Vec<Value>>, Vec<i64>);
+
+    fn read_stream(mut bytes: &[u8]) -> Stream {
+        let mut schema = Vec::new();
+        let mut columns: Vec<Vec<Value>> = Vec::new();
+        let mut batches = Vec::new();
+        loop {
+            assert_eq!(bytes[..4], CONTINUATION);
+            let len = i32::from_le_bytes(read(bytes, 4)) as usize;
+            if len == 0 {
+                assert_eq!(bytes.len(), 8, "data after the end of the stream");
+                break;
+            }
+            assert_eq!(len % 8, 0);
+            let metadata = &bytes[8..8 + len];
+            let message = read_u32(metadata, 0);
+            let version = field(metadata, message, 0, 2).unwrap();
+            assert_eq!(read::<2>(metadata, version), METADATA_VERSION.to_le_bytes());
+            let header_type = metadata[field(metadata, message, 1, 1).unwrap()];
+            let header = reference(metadata, message, 2);
+            let body_len = field(metadata, message, 3, 8).map_or(0, |at| read_i64(metadata, at));
+            let body = &bytes[8 + len..8 + len + body_len as usize];
+            match header_type {
+                SCHEMA => {
+                    assert!(schema.is_empty(), "more than one schema");
+                    for field_table in vector(metadata, reference(metadata, header, 1)) {
+                        let name = reference(metadata, field_table, 0);
+                        let name_len = read_u32(metadata, name);
+                        let name =
+                            std::str::from_utf8(&metadata[name + 4..name + 4 + name_len]).unwrap();
+                        assert_eq!(
+                            metadata[name.len() + 4 + reference(metadata, field_table, 0)],
+                            0
+                        );
+                        let type_type = metadata[field(metadata, field_table, 2, 1).unwrap()];
+                        let type_table = reference(metadata, field_table, 3);
+                        let column_type = match type_type {
+                            INT => {
+                                let bits = field(metadata, type_table, 0, 4).unwrap();
+                                assert_eq!(read::<4>(metadata, bits), 64i32.to_le_bytes());
+                                let signed = field(metadata, type_table, 1, 1).unwrap();
+                                assert_eq!(metadata[signed], 1);
+                                ColumnType::Int64
+                            }
+                            FLOATING_POINT => {
+                                let precision = field(metadata, type_table, 0, 2).unwrap();
+                                assert_eq!(read::<2>(metadata, precision), DOUBLE.to_le_bytes());
+                                ColumnType::Double
+                            }
+                            other => panic!("unexpected type {}", other),
+                        };
+                        assert_eq!(
+                            vector(metadata, reference(metadata, field_table, 5)).count(),
+                            0
+                        );
+                        schema.push((name.to_owned(), column_type));
+                    }
+                    columns = vec![Vec::new(); schema.len()];
+                }
+                RECORD_BATCH => {
+                    let rows = read_i64(metadata, field(metadata, header, 0, 8).unwrap());
+                    batches.push(rows);
+                    let nodes = reference(metadata, header, 1);
+                    assert_eq!(read_u32(metadata, nodes), schema.len());
+                    assert_eq!((nodes + 4) % 8, 0);
+                    for i in 0..schema.len() {
+                        assert_eq!(read_i64(metadata, nodes + 4 + 16 * i), rows);
+                        assert_eq!(read_i64(metadata, nodes + 12 + 16 * i), 0);
+                    }
+                    let buffers = reference(metadata, header, 2);
+                    assert_eq!(read_u32(metadata, buffers), 2 * schema.len());
+                    assert_eq!((buffers + 4) % 8, 0);
+                    for (i, (column, (_, column_type))) in
+                        columns.iter_mut().zip(&schema).enumerate()
+                    {
+                        let validity = buffers + 4 + 32 * i;
+                        assert_eq!(read_i64(metadata, validity + 8), 0);
+                        let offset = read_i64(metadata, validity + 16) as usize;
+                        let len = read_i64(metadata, validity + 24) as usize;
+                        assert_eq!(offset % 8, 0);
+                        assert_eq!(len, rows as usize * 8);
+                        column.extend(body[offset..offset + len].chunks(8).map(|value| {
+                            let value = value.try_into().unwrap();
+                            match column_type {
+                                ColumnType::Int64 => Value::Int64(i64::from_le_bytes(value)),
+                                ColumnType::Double => Value::Double(f64::from_le_bytes(value)),
+                            }
+                        }));
+                    }
+                }
+                other => panic!("unexpected message type {}", other),
+            }
+            bytes = &bytes[8 + len + body_len as usize..];
+        }
+        (schema, columns, batches)
+    }
+
+    #[test]
+    fn round_trip() {
+        let schema: Vec<_> = (0..5)
+            .map(|i| {
+                let column_type = if i % 2 == 0 {
+                    ColumnType::Int64
+                } else {
+                    ColumnType::Double
+                };
+                (format!("column_{}", "x".repeat(i)), column_type)
+            })
+            .collect();
+        let mut writer = ArrowStreamWriter::new(Vec::new(), schema.clone())
+            .unwrap()
+            .with_batch_size(4);
+        let rows: Vec<Vec<Value>> = (0..10)
+            .map(|row| {
+                schema
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (_, column_type))| match column_type {
+                        ColumnType::Int64 => Value::Int64(row * 10 + i as i64 - 20),
+                        ColumnType::Double => Value::Double(row as f64 / 4.0 - i as f64),
+                    })
+                    .collect()
+            })
+            .collect();
+        for row in &rows {
+            writer.write_row(row).unwrap();
+        }
+        let bytes = writer.finish().unwrap();
+
+        let (read_schema, columns, batches) = read_stream(&bytes);
+        assert_eq!(read_schema, schema);
+        assert_eq!(batches, [4, 4, 2]);
+        for (i, column) in columns.iter().enumerate() {
+            assert!(column.iter().eq(rows.iter().map(|row| &row[i])));
+        }
+    }
+
+    #[test]
+    fn empty_stream() {
+        let schema = vec![("generation".to_owned(), ColumnType::Int64)];
+        let bytes = ArrowStreamWriter::new(Vec::new(), schema.clone())
+            .unwrap()
+            .finish()
+            .unwrap();
+        let (read_schema, columns, batches) = read_stream(&bytes);
+        assert_eq!(read_schema, schema);
+        assert_eq!(columns, [[]]);
+        assert!(batches.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrong_type() {
+        let schema = vec![("generation".to_owned(), ColumnType::Int64)];
+        let mut writer = ArrowStreamWriter::new(Vec::new(), schema).unwrap();
+        let _ = writer.write_row(&[Value::Double(0.0)]);
+    }
+
+    #[test]
+    fn arrow_log() {
+        let pop = [Cached::new(Foo([1.0, -3.0])), Cached::new(Foo([0.5, 2.5]))];
+        let hof: BestN<Foo> = BestN::new(1);
+        crate::fitness::par_evaluate(&pop);
+        let stats = FitnessBasicMulti::default().analyze(&pop);
+        let mut log: ArrowLog<Foo, FitnessBasicMulti<2>, Vec<u8>> = ArrowLog::new(Vec::new())
+            .unwrap()
+            .with_individuals(Vec::new())
+            .unwrap()
+            .with_batch_size(1);
+        for gen in [0, 1] {
+            log.record(Generation::<_, _, FitnessBasicMulti<2>> {
+                gen,
+                pop: &pop,
+                hall_of_fame: &hof,
+                stats: &stats,
+                evaluations: 0,
+            })
+            .unwrap();
+        }
+        let (stats_stream, individuals_stream) = log.finish().unwrap();
+
+        let (schema, columns, batches) = read_stream(&stats_stream);
+        assert_eq!(schema, stats_schema::<Foo, FitnessBasicMulti<2>>());
+        assert_eq!(batches, [1, 1]);
+        assert_eq!(columns[0], [Value::Int64(0), Value::Int64(1)]);
+        for (column, value) in columns[1..].iter().zip(stats.values()) {
+            assert_eq!(column, &[Value::Double(value); 2]);
+        }
+
+        let (schema, columns, batches) = read_stream(&individuals_stream.unwrap());
+        let names: Vec<&str> = schema.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["generation", "individual", "fitness_0", "fitness_1"]
+        );
+        assert_eq!(batches, [1; 4]);
+        let int = |values: [i64; 4]| values.map(Value::Int64);
+        let double = |values: [f64; 4]| values.map(Value::Double);
+        assert_eq!(columns[0], int([0, 0, 1, 1]));
+        assert_eq!(columns[1], int([0, 1, 0, 1]));
+        assert_eq!(columns[2], double([1.0, 0.5, 1.0, 0.5]));
+        assert_eq!(columns[3], double([-3.0, 2.5, -3.0, 2.5]));
+    }
+}
@@ -0,0 +1,302 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use super::{FitnessColumns, StatsColumns};
+use crate::{
+    hof::HallOfFame,
+    stats::{GenerationStats, StatsSink},
+    Cached, Log, Solution,
+};
+
+/// Writes solutions to CSV, one row per solution
+///
+/// Each row starts with the columns produced by a projection closure, which turns a solution
+/// and its fitness into a list of values, followed by the columns of the fitness itself
+/// as given by [`FitnessColumns`]. Fields are quoted only when necessary.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+/// use eviolite::export::CsvExporter;
+///
+/// let evo: Evolution<Benchmark<Sphere, 2>, _, _, ()> = Evolution::new(
+///     alg::MuPlusLambda::new(20, 40, 0.6, 0.3, select::Tournament::new(3)),
+///     hof::BestN::new(5),
+/// );
+/// let log = evo.run_for(10);
+///
+/// let exporter = CsvExporter::new(["x", "y"], |sol: &Benchmark<Sphere, 2>, _| {
+///     sol.iter().map(|x| x.to_string()).collect()
+/// });
+/// let mut csv = Vec::new();
+/// exporter.write(&mut csv, &log.hall_of_fame).unwrap();
+/// assert!(String::from_utf8(csv).unwrap().starts_with("x,y,fitness\n"));
+/// ```
+///
+/// [`FitnessColumns`]: ./trait.FitnessColumns.html
+pub struct CsvExporter<T, F>
+where
+    T: Solution,
+    F: Fn(&T, &T::Fitness) -> Vec<String>,
+{
+    headers: Vec<String>,
+    project: F,
+    _marker: std::marker::PhantomData<fn(&T)>,
+}
+
+impl<T, F> CsvExporter<T, F>
+where
+    T: Solution,
+    T::Fitness: FitnessColumns,
+    F: Fn(&T, &T::Fitness) -> Vec<String>,
+{
+    /// Create an exporter whose rows start with the columns named by `headers`,
+    /// filled in by `project`.
+    ///
+    /// `project` should always return exactly one value per header.
+    pub fn new<H: Into<String>>(headers: impl IntoIterator<Item = H>, project: F) -> Self {
+        CsvExporter {
+            headers: headers.into_iter().map(Into::into).collect(),
+            project,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Write a header row followed by one row for every solution in `solutions`.
+    ///
+    /// Solutions whose fitness isn't cached yet are evaluated.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if the projection returns the wrong number of values.
+    pub fn write<W: Write>(&self, mut writer: W, solutions: &[Cached<T>]) -> io::Result<()> {
+        let headers: Vec<String> = self
+            .headers
+            .iter()
+            .cloned()
+            .chain(T::Fitness::headers())
+            .collect();
+        write_row(&mut writer, &headers)?;
+        for solution in solutions {
+            let fitness = solution.evaluate();
+            let mut row = (self.project)(solution.as_ref(), &fitness);
+            assert_eq!(
+                row.len(),
+                self.headers.len(),
+                "projection returned the wrong number of values"
+            );
+            row.extend(fitness.values().iter().map(f64::to_string));
+            write_row(&mut writer, &row)?;
+        }
+        writer.flush()
+    }
+
+    /// Write the solutions to a new file at `path`, replacing it if it exists.
+    /// See [`.write()`](#method.write).
+    pub fn write_file(&self, path: impl AsRef<Path>, solutions: &[Cached<T>]) -> io::Result<()> {
+        self.write(BufWriter::new(File::create(path)?), solutions)
+    }
+}
+
+impl<T, Hof, Stat> Log<T, Hof, Stat>
+where
+    T: Solution,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+    Stat::Output: StatsColumns,
+{
+    /// Write the per-generation statistics as CSV, with a header row followed by one row per generation.
+    ///
+    /// The first column is `generation`, numbered from [`first_generation`](#structfield.first_generation)
+    /// in steps of [`stats_interval`](#structfield.stats_interval),
+    /// followed by the columns of the statistics as given by [`StatsColumns`].
+    /// The output can be read directly by pandas, R, or a spreadsheet.
+    ///
+    /// ```
+    /// use eviolite::prelude::*;
+    /// use eviolite::benchmarks::{Benchmark, Sphere};
+    ///
+    /// let evo: Evolution<Benchmark<Sphere, 2>, _, _, stats::FitnessSummary> = Evolution::new(
+    ///     alg::Simple::new(20, 0.5, 0.2, select::Tournament::new(3)),
+    ///     hof::BestN::new(1),
+    /// );
+    /// let log = evo.run_for(10);
+    ///
+    /// let mut csv = Vec::new();
+    /// log.write_stats_csv(&mut csv).unwrap();
+    /// let csv = String::from_utf8(csv).unwrap();
+    /// assert!(csv.starts_with("generation,avg,std,min,max\n0,"));
+    /// assert_eq!(csv.lines().count(), 11);
+    /// ```
+    ///
+    /// [`StatsColumns`]: ./export/trait.StatsColumns.html
+    pub fn write_stats_csv<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = BufWriter::new(writer);
+        let headers: Vec<String> = std::iter::once("generation".to_owned())
+            .chain(<Stat::Output as StatsColumns>::headers())
+            .collect();
+        write_row(&mut writer, &headers)?;
+        for (i, stat) in self.stats.iter().enumerate() {
+            let row: Vec<String> =
+                std::iter::once((self.first_generation + i * self.stats_interval).to_string())
+                    .chain(stat.values().iter().map(f64::to_string))
+                    .collect();
+            write_row(&mut writer, &row)?;
+        }
+        writer.flush()
+    }
+}
+
+/// A [`StatsSink`] that writes the statistics of every generation as a CSV row as soon as they're pushed,
+/// without keeping any of them in memory
+///
+/// The rows are the same as those written by [`Log::write_stats_csv()`], and the header row
+/// is written along with the first generation.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+/// use eviolite::export::StatsCsvWriter;
+///
+/// let path = std::env::temp_dir().join("stats.csv");
+/// let evo: Evolution<Benchmark<Sphere, 2>, _, _, stats::FitnessSummary> = Evolution::new(
+///     alg::Simple::new(20, 0.5, 0.2, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// )
+/// .with_stats_sink(StatsCsvWriter::create(&path).unwrap());
+/// let log = evo.run_for(10);
+/// assert!(log.stats.is_empty());
+/// assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 11);
+/// ```
+///
+/// Panics
+/// ======
+/// Since the run can't be told about them, the sink panics if the statistics can't be written.
+///
+/// [`StatsSink`]: ../stats/trait.StatsSink.html
+/// [`Log::write_stats_csv()`]: ../struct.Log.html#method.write_stats_csv
+pub struct StatsCsvWriter<W: Write> {
+    writer: W,
+    wrote_headers: bool,
+}
+
+impl StatsCsvWriter<BufWriter<File>> {
+    /// Create the file at `path` to write to, replacing it if it exists.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> StatsCsvWriter<W> {
+    /// Write the statistics to `writer`.
+    pub fn new(writer: W) -> Self {
+        StatsCsvWriter {
+            writer,
+            wrote_headers: false,
+        }
+    }
+
+    fn write<S: StatsColumns>(&mut self, gen: usize, stats: &S) -> io::Result<()> {
+        if !self.wrote_headers {
+            let headers: Vec<String> = std::iter::once("generation".to_owned())
+                .chain(S::headers())
+                .collect();
+            write_row(&mut self.writer, &headers)?;
+            self.wrote_headers = true;
+        }
+        let row: Vec<String> = std::iter::once(gen.to_string())
+            .chain(stats.values().iter().map(f64::to_string))
+            .collect();
+        write_row(&mut self.writer, &row)
+    }
+}
+
+impl<S: StatsColumns, W: Write> StatsSink<S> for StatsCsvWriter<W> {
+    fn push(&mut self, gen: usize, stats: S) {
+        self.write(gen, &stats)
+            .expect("failed to write the statistics");
+    }
+
+    fn finish(&mut self) -> Vec<S> {
+        self.writer.flush().expect("failed to write the statistics");
+        Vec::new()
+    }
+}
+
+fn write_row<W: Write>(writer: &mut W, fields: &[String]) -> io::Result<()> {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            write!(writer, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            writer.write_all(field.as_bytes())?;
+        }
+    }
+    writer.write_all(b"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alg::Simple,
+        hof::BestN,
+        select::Tournament,
+        stats::{FitnessBasic, FitnessSummary},
+        testutils::{Foo, Walk},
+        Evolution,
+    };
+
+    #[test]
+    fn csv_rows() {
+        let solutions = [Cached::new(Foo([1.5, -2.0])), Cached::new(Foo([0.0, 0.25]))];
+        let exporter = CsvExporter::new(["label"], |foo: &Foo, fit| {
+            vec![format!("sum, \"{}\"", foo.0[0] + fit[1])]
+        });
+        let mut csv = Vec::new();
+        exporter.write(&mut csv, &solutions).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "label,fitness_0,fitness_1\n\
+             \"sum, \"\"-0.5\"\"\",1.5,-2\n\
+             \"sum, \"\"0.25\"\"\",0,0.25\n"
+        );
+    }
+
+    #[test]
+    fn streamed_stats_match_log() {
+        let evolution = || -> Evolution<Walk, _, _, FitnessSummary> {
+            Evolution::new(Simple::new(10, 0.5, 0.3, Tournament::new(2)), BestN::new(1))
+        };
+        crate::repro_rng::reseed(3);
+        let mut kept = Vec::new();
+        evolution().run_for(8).write_stats_csv(&mut kept).unwrap();
+
+        crate::repro_rng::reseed(3);
+        let path = std::env::temp_dir().join("eviolite-streamed-stats.csv");
+        let log = evolution()
+            .with_stats_sink(StatsCsvWriter::create(&path).unwrap())
+            .with_best_history()
+            .run_for(8);
+        assert!(log.stats.is_empty());
+        assert_eq!(log.best_fitness.len(), 8);
+        assert_eq!(std::fs::read(&path).unwrap(), kept);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn stats_csv_with_tuple() {
+        let evo: Evolution<Walk, _, _, (FitnessBasic, FitnessSummary)> =
+            Evolution::new(Simple::new(10, 0.5, 0.3, Tournament::new(2)), BestN::new(1));
+        let mut csv = Vec::new();
+        evo.run_for(3).write_stats_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("generation,mean,variance,stdev,avg,std,min,max\n"));
+        assert!(csv.lines().skip(1).all(|row| row.split(',').count() == 8));
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+};
+
+use super::{FitnessColumns, LogSink, StatsColumns};
+use crate::{fitness::Direction, hof::HallOfFame, stats::GenerationStats, Generation, Solution};
+
+/// A [`LogSink`] that writes one JSON object per generation, each on its own line
+///
+/// Every object has the following fields:
+/// - `gen`: the index of the generation
+/// - `evaluations`: the number of fitness evaluations since the run started,
+///   as given by [`Generation::evaluations`]
+/// - `best`: the best value of each fitness column in the population, as given by [`FitnessColumns`];
+///   a single number if there is only one column, or an array otherwise
+/// - `stats`: an object with one field per statistics column, as given by [`StatsColumns`]
+///
+/// Non-finite numbers are written as `null`.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+/// use eviolite::export::{JsonLines, LogSink};
+///
+/// let evo: Evolution<Benchmark<Sphere, 2>, _, _, stats::FitnessBasic> = Evolution::new(
+///     alg::MuPlusLambda::new(20, 40, 0.6, 0.3, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// );
+/// let mut sink = JsonLines::new(Vec::new());
+/// evo.run_for_with(5, |generation| sink.record(generation).unwrap());
+/// let output = String::from_utf8(sink.into_inner()).unwrap();
+/// assert_eq!(output.lines().count(), 5);
+/// assert!(output.starts_with(r#"{"gen":0,"evaluations":20,"best":"#));
+/// ```
+///
+/// [`LogSink`]: ./trait.LogSink.html
+/// [`Generation::evaluations`]: ../struct.Generation.html#structfield.evaluations
+/// [`FitnessColumns`]: ./trait.FitnessColumns.html
+/// [`StatsColumns`]: ./trait.StatsColumns.html
+pub struct JsonLines<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> JsonLines<W> {
+    /// Create a sink that writes to `writer`.
+    pub fn new(writer: W) -> Self {
+        JsonLines { writer }
+    }
+
+    /// Consume the sink, returning the writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<T, Hof, Stat, W> LogSink<T, Hof, Stat> for JsonLines<W>
+where
+    T: Solution,
+    T::Fitness: FitnessColumns,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+    Stat::Output: StatsColumns,
+    W: Write,
+{
+    fn record(&mut self, generation: Generation<T, Hof, Stat>) -> io::Result<()> {
+        let mut line = format!(
+            "{{\"gen\":{},\"evaluations\":{},\"best\":",
+            generation.gen, generation.evaluations
+        );
+
+        let directions: Vec<Direction> = (0..T::Fitness::headers().len())
+            .map(Direction::of::<T>)
+            .collect();
+        let mut best: Vec<f64> = directions.iter().map(|d| d.worst()).collect();
+        for ind in generation.pop {
+            for ((best, value), direction) in best
+                .iter_mut()
+                .zip(ind.evaluate().values())
+                .zip(&directions)
+            {
+                *best = direction.best(*best, value);
+            }
+        }
+        match best.as_slice() {
+            [single] => number(&mut line, *single),
+            values => {
+                line.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        line.push(',');
+                    }
+                    number(&mut line, *value);
+                }
+                line.push(']');
+            }
+        }
+
+        line.push_str(",\"stats\":{");
+        for (i, (name, value)) in <Stat::Output as StatsColumns>::headers()
+            .iter()
+            .zip(generation.stats.values())
+            .enumerate()
+        {
+            if i > 0 {
+                line.push(',');
+            }
+            string(&mut line, name);
+            line.push(':');
+            number(&mut line, value);
+        }
+        line.push_str("}}\n");
+        self.writer.write_all(line.as_bytes())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+fn number(out: &mut String, value: f64) {
+    if value.is_finite() {
+        let _ = write!(out, "{:?}", value);
+    } else {
+        out.push_str("null");
+    }
+}
+
+fn string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hof::BestN, stats::FitnessBasicMulti, testutils::Foo, Cached};
+
+    #[test]
+    fn json_lines() {
+        let pop = [Cached::new(Foo([1.0, -3.0])), Cached::new(Foo([0.5, 2.5]))];
+        let hof: BestN<Foo> = BestN::new(1);
+        crate::fitness::par_evaluate(&pop);
+        let stats = FitnessBasicMulti::default().analyze(&pop);
+        let mut sink = JsonLines::new(Vec::new());
+        sink.record(Generation::<_, _, FitnessBasicMulti<2>> {
+            gen: 3,
+            pop: &pop,
+            hall_of_fame: &hof,
+            stats: &stats,
+            evaluations: 2,
+        })
+        .unwrap();
+
+        let line = String::from_utf8(sink.into_inner()).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(json["gen"], 3);
+        assert_eq!(json["evaluations"], 2);
+        assert_eq!(json["best"], serde_json::json!([1.0, 2.5]));
+        assert_eq!(json["stats"]["mean_0"], 0.75);
+        assert!(line.ends_with("}\n"));
+
+        let mut escaped = String::new();
+        string(&mut escaped, "a\"b\\\n\u{1}");
+        assert_eq!(escaped, r#""a\"b\\\n\u0001""#);
+    }
+}
@@ -0,0 +1,165 @@
+use std::io::{self, Write};
+
+use super::{LogSink, StatsColumns};
+use crate::{hof::HallOfFame, stats::GenerationStats, Generation, Solution};
+
+/// A [`LogSink`] that prints a fixed-width table with one row per generation, like DEAP's logbook
+///
+/// The first two columns are `gen`, the index of the generation, and `nevals`,
+/// the number of fitness evaluations performed since the previous row (from [`Generation::evaluations`]).
+/// They're followed by one column per statistic, as given by [`StatsColumns`].
+/// With [`FitnessSummary`], this gives the same `gen`, `nevals`, `avg`, `std`, `min`, and `max`
+/// columns as most of DEAP's examples.
+///
+/// Just like DEAP's, columns are left-aligned, padded to the width of their header,
+/// and separated by tabs. The header is printed along with the first row.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+/// use eviolite::export::{Logbook, LogSink};
+///
+/// let evo: Evolution<Benchmark<Sphere, 2>, _, _, stats::FitnessSummary> = Evolution::new(
+///     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// );
+/// let mut logbook = Logbook::new(Vec::new());
+/// evo.run_for_with(5, |generation| logbook.record(generation).unwrap());
+/// let output = String::from_utf8(logbook.into_inner()).unwrap();
+/// assert!(output.starts_with("gen\tnevals\tavg"));
+/// assert!(output.lines().nth(1).unwrap().starts_with("0  \t50    \t"));
+/// ```
+///
+/// [`LogSink`]: ./trait.LogSink.html
+/// [`Generation::evaluations`]: ../struct.Generation.html#structfield.evaluations
+/// [`StatsColumns`]: ./trait.StatsColumns.html
+/// [`FitnessSummary`]: ../stats/struct.FitnessSummary.html
+pub struct Logbook<W: Write> {
+    writer: W,
+    precision: usize,
+    widths: Vec<usize>,
+    evaluations: u64,
+}
+
+impl<W: Write> Logbook<W> {
+    /// Create a logbook that prints to `writer`.
+    ///
+    /// Pass [`std::io::stdout()`] to print to the console.
+    pub fn new(writer: W) -> Self {
+        Logbook {
+            writer,
+            precision: 4,
+            widths: Vec::new(),
+            evaluations: 0,
+        }
+    }
+
+    /// Print statistics with `precision` digits after the decimal point, instead of the default 4.
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Consume the logbook, returning the writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_row(&mut self, cells: &[String]) -> io::Result<()> {
+        let mut line = String::new();
+        for (i, (cell, &width)) in cells.iter().zip(&self.widths).enumerate() {
+            if i > 0 {
+                line.push('\t');
+            }
+            line.push_str(&format!("{:<width$}", cell, width = width));
+        }
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())
+    }
+}
+
+impl<T, Hof, Stat, W> LogSink<T, Hof, Stat> for Logbook<W>
+where
+    T: Solution,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+    Stat::Output: StatsColumns,
+    W: Write,
+{
+    fn record(&mut self, generation: Generation<T, Hof, Stat>) -> io::Result<()> {
+        if self.widths.is_empty() {
+            let headers: Vec<String> = ["gen".to_owned(), "nevals".to_owned()]
+                .into_iter()
+                .chain(<Stat::Output as StatsColumns>::headers())
+                .collect();
+            // Leave room for a sign, a few integer digits, and the decimal point in statistics
+            self.widths = headers
+                .iter()
+                .enumerate()
+                .map(|(i, header)| match i {
+                    0 | 1 => header.len(),
+                    _ => header.len().max(self.precision + 4),
+                })
+                .collect();
+            self.write_row(&headers)?;
+        }
+
+        // A new or resumed run starts counting from 0 again
+        if generation.evaluations < self.evaluations {
+            self.evaluations = 0;
+        }
+        let mut cells = vec![
+            generation.gen.to_string(),
+            (generation.evaluations - self.evaluations).to_string(),
+        ];
+        self.evaluations = generation.evaluations;
+        cells.extend(
+            generation
+                .stats
+                .values()
+                .into_iter()
+                .map(|value| format!("{:.*}", self.precision, value)),
+        );
+        self.write_row(&cells)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hof::BestN, stats::FitnessSummary, testutils::One, Cached};
+
+    #[test]
+    fn logbook_table() {
+        let pop = [Cached::new(One(1.0)), Cached::new(One(3.0))];
+        let hof: BestN<One> = BestN::new(1);
+        let stats = FitnessSummary::default().analyze(&pop);
+        let mut logbook = Logbook::new(Vec::new()).with_precision(2);
+        for (gen, evaluations) in [(0, 2), (1, 6)] {
+            logbook
+                .record(Generation::<_, _, FitnessSummary> {
+                    gen,
+                    pop: &pop,
+                    hall_of_fame: &hof,
+                    stats: &stats,
+                    evaluations,
+                })
+                .unwrap();
+        }
+
+        let output = String::from_utf8(logbook.into_inner()).unwrap();
+        let lines: Vec<Vec<&str>> = output.lines().map(|l| l.split('\t').collect()).collect();
+        assert_eq!(
+            lines[0],
+            ["gen", "nevals", "avg   ", "std   ", "min   ", "max   "]
+        );
+        assert_eq!(lines[1][..2], ["0  ", "2     "]);
+        assert_eq!(lines[1][2..], ["2.00  ", "1.00  ", "1.00  ", "3.00  "]);
+        assert_eq!(lines[2][..2], ["1  ", "4     "]);
+        assert_eq!(lines.len(), 3);
+    }
+}
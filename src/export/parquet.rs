@@ -0,0 +1,793 @@
+use std::{
+    io::{self, Write},
+    marker::PhantomData,
+};
+
+use super::{FitnessColumns, LogSink, StatsColumns};
+use crate::{hof::HallOfFame, stats::GenerationStats, Generation, Solution};
+
+/// The physical type of a column in a [`ParquetWriter`]
+///
+/// [`ParquetWriter`]: ./struct.ParquetWriter.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnType {
+    /// 64-bit signed integers
+    Int64,
+    /// 64-bit floating point numbers
+    Double,
+}
+
+/// A single value in a row written to a [`ParquetWriter`]
+///
+/// [`ParquetWriter`]: ./struct.ParquetWriter.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    /// A value for an [`Int64`](./enum.ColumnType.html#variant.Int64) column
+    Int64(i64),
+    /// A value for a [`Double`](./enum.ColumnType.html#variant.Double) column
+    Double(f64),
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Int64(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Double(value)
+    }
+}
+
+/// A minimal streaming writer for Parquet files
+///
+/// Every column is required (non-nullable), plainly encoded, and uncompressed.
+/// Rows are buffered in memory and written out as a row group whenever
+/// the [row group size] is reached, so arbitrarily many rows can be written with bounded memory.
+/// The file is only valid after [`.finish()`] has been called.
+///
+/// [row group size]: ./struct.ParquetWriter.html#method.with_row_group_size
+/// [`.finish()`]: ./struct.ParquetWriter.html#method.finish
+pub struct ParquetWriter<W: Write> {
+    writer: W,
+    offset: u64,
+    schema: Vec<(String, ColumnType)>,
+    buffers: Vec<Vec<u8>>,
+    buffered_rows: usize,
+    row_group_size: usize,
+    row_groups: Vec<RowGroup>,
+}
+
+struct ColumnChunk {
+    offset: u64,
+    size: u64,
+}
+
+struct RowGroup {
+    columns: Vec<ColumnChunk>,
+    rows: usize,
+}
+
+const MAGIC: &[u8] = b"PAR1";
+
+impl<W: Write> ParquetWriter<W> {
+    /// Start writing a Parquet file with the given column names and types.
+    pub fn new(mut writer: W, schema: Vec<(String, ColumnType)>) -> io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        Ok(ParquetWriter {
+            writer,
+            offset: MAGIC.len() as u64,
+            buffers: vec![Vec::new(); schema.len()],
+            schema,
+            buffered_rows: 0,
+            row_group_size: 1 << 20,
+            row_groups: Vec::new(),
+        })
+    }
+
+    /// Write a row group every `rows` rows. The default is 2²⁰ rows.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `rows` is 0.
+    pub fn with_row_group_size(mut self, rows: usize) -> Self {
+        assert!(rows > 0, "row groups must contain at least one row");
+        self.row_group_size = rows;
+        self
+    }
+
+    /// Get the columns of the file.
+    pub fn schema(&self) -> &[(String, ColumnType)] {
+        &self.schema
+    }
+
+    /// Append a row to the file.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `row` doesn't have exactly one value of the right type for each column.
+    pub fn write_row(&mut self, row: &[Value]) -> io::Result<()> {
+        assert_eq!(row.len(), self.schema.len(), "row has the wrong length");
+        for ((buffer, (name, column_type)), value) in
+            self.buffers.iter_mut().zip(&self.schema).zip(row)
+        {
+            match (column_type, value) {
+                (ColumnType::Int64, Value::Int64(value)) => {
+                    buffer.extend_from_slice(&value.to_le_bytes())
+                }
+                (ColumnType::Double, Value::Double(value)) => {
+                    buffer.extend_from_slice(&value.to_le_bytes())
+                }
+                _ => panic!("value {:?} has the wrong type for column {}", value, name),
+            }
+        }
+        self.buffered_rows += 1;
+        if self.buffered_rows == self.row_group_size {
+            self.flush_row_group()?;
+        }
+        Ok(())
+    }
+
+    /// Write any buffered rows and the file footer,
+    /// returning the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.buffered_rows > 0 {
+            self.flush_row_group()?;
+        }
+        let footer = self.footer();
+        self.writer.write_all(&footer)?;
+        self.writer
+            .write_all(&(footer.len() as u32).to_le_bytes())?;
+        self.writer.write_all(MAGIC)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn flush_row_group(&mut self) -> io::Result<()> {
+        let rows = self.buffered_rows;
+        let mut columns = Vec::with_capacity(self.buffers.len());
+        for buffer in self.buffers.iter_mut() {
+            // a single data page with no levels, since every column is required
+            let mut header = Compact::default();
+            header.i32(1, 0); // DATA_PAGE
+            header.i32(2, buffer.len() as i32);
+            header.i32(3, buffer.len() as i32);
+            header.begin_struct(5);
+            header.i32(1, rows as i32);
+            header.i32(2, 0); // PLAIN
+            header.i32(3, 3); // RLE
+            header.i32(4, 3); // RLE
+            header.end_struct();
+            header.stop();
+
+            self.writer.write_all(&header.bytes)?;
+            self.writer.write_all(buffer)?;
+            let size = (header.bytes.len() + buffer.len()) as u64;
+            columns.push(ColumnChunk {
+                offset: self.offset,
+                size,
+            });
+            self.offset += size;
+            buffer.clear();
+        }
+        self.row_groups.push(RowGroup { columns, rows });
+        self.buffered_rows = 0;
+        Ok(())
+    }
+
+    fn footer(&self) -> Vec<u8> {
+        let mut meta = Compact::default();
+        meta.i32(1, 1);
+        meta.list(2, STRUCT, self.schema.len() + 1);
+        meta.begin_element();
+        meta.binary(4, b"schema");
+        meta.i32(5, self.schema.len() as i32);
+        meta.end_element();
+        for (name, column_type) in &self.schema {
+            meta.begin_element();
+            meta.i32(1, physical_type(*column_type));
+            meta.i32(3, 0); // REQUIRED
+            meta.binary(4, name.as_bytes());
+            meta.end_element();
+        }
+        meta.i64(
+            3,
+            self.row_groups.iter().map(|group| group.rows as i64).sum(),
+        );
+        meta.list(4, STRUCT, self.row_groups.len());
+        for group in &self.row_groups {
+            meta.begin_element();
+            meta.list(1, STRUCT, group.columns.len());
+            for (chunk, (name, column_type)) in group.columns.iter().zip(&self.schema) {
+                meta.begin_element();
+                meta.i64(2, chunk.offset as i64);
+                meta.begin_struct(3);
+                meta.i32(1, physical_type(*column_type));
+                meta.list(2, I32, 2);
+                meta.element_i32(0); // PLAIN
+                meta.element_i32(3); // RLE
+                meta.list(3, BINARY, 1);
+                meta.element_binary(name.as_bytes());
+                meta.i32(4, 0); // UNCOMPRESSED
+                meta.i64(5, group.rows as i64);
+                meta.i64(6, chunk.size as i64);
+                meta.i64(7, chunk.size as i64);
+                meta.i64(9, chunk.offset as i64);
+                meta.end_struct();
+                meta.end_element();
+            }
+            meta.i64(2, group.columns.iter().map(|chunk| chunk.size as i64).sum());
+            meta.i64(3, group.rows as i64);
+            meta.end_element();
+        }
+        meta.binary(
+            6,
+            format!("eviolite version {}", env!("CARGO_PKG_VERSION")).as_bytes(),
+        );
+        meta.stop();
+        meta.bytes
+    }
+}
+
+fn physical_type(column_type: ColumnType) -> i32 {
+    match column_type {
+        ColumnType::Int64 => 2,
+        ColumnType::Double => 5,
+    }
+}
+
+const I32: u8 = 5;
+const I64: u8 = 6;
+const BINARY: u8 = 8;
+const LIST: u8 = 9;
+const STRUCT: u8 = 12;
+
+// Just enough of the Thrift compact protocol to write Parquet metadata.
+#[derive(Default)]
+struct Compact {
+    bytes: Vec<u8>,
+    last_field: i16,
+    field_stack: Vec<i16>,
+}
+
+impl Compact {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.bytes.push(value as u8);
+    }
+
+    fn zigzag(&mut self, value: i64) {
+        self.varint(((value << 1) ^ (value >> 63)) as u64);
+    }
+
+    fn field(&mut self, id: i16, kind: u8) {
+        let delta = id - self.last_field;
+        if (1..=15).contains(&delta) {
+            self.bytes.push((delta as u8) << 4 | kind);
+        } else {
+            self.bytes.push(kind);
+            self.zigzag(id as i64);
+        }
+        self.last_field = id;
+    }
+
+    fn i32(&mut self, id: i16, value: i32) {
+        self.field(id, I32);
+        self.zigzag(value as i64);
+    }
+
+    fn i64(&mut self, id: i16, value: i64) {
+        self.field(id, I64);
+        self.zigzag(value);
+    }
+
+    fn binary(&mut self, id: i16, value: &[u8]) {
+        self.field(id, BINARY);
+        self.element_binary(value);
+    }
+
+    fn list(&mut self, id: i16, kind: u8, len: usize) {
+        self.field(id, LIST);
+        if len < 15 {
+            self.bytes.push((len as u8) << 4 | kind);
+        } else {
+            self.bytes.push(0xf0 | kind);
+            self.varint(len as u64);
+        }
+    }
+
+    fn element_i32(&mut self, value: i32) {
+        self.zigzag(value as i64);
+    }
+
+    fn element_binary(&mut self, value: &[u8]) {
+        self.varint(value.len() as u64);
+        self.bytes.extend_from_slice(value);
+    }
+
+    fn begin_struct(&mut self, id: i16) {
+        self.field(id, STRUCT);
+        self.begin_element();
+    }
+
+    fn end_struct(&mut self) {
+        self.end_element();
+    }
+
+    fn begin_element(&mut self) {
+        self.field_stack.push(self.last_field);
+        self.last_field = 0;
+    }
+
+    fn end_element(&mut self) {
+        self.stop();
+        self.last_field = self.field_stack.pop().unwrap();
+    }
+
+    fn stop(&mut self) {
+        self.bytes.push(0);
+    }
+}
+
+/// Writes per-generation statistics, and optionally the fitness of every individual, to Parquet files
+///
+/// Statistics are written with a `generation` column followed by the columns given by [`StatsColumns`].
+/// If enabled with [`.with_individuals()`], every individual's fitness is written to a second file
+/// with `generation` and `individual` columns followed by the columns given by [`FitnessColumns`].
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+/// use eviolite::export::ParquetLog;
+///
+/// # fn main() -> std::io::Result<()> {
+/// let evo: Evolution<Benchmark<Sphere, 2>, _, _, stats::FitnessBasic> = Evolution::new(
+///     alg::MuPlusLambda::new(20, 40, 0.6, 0.3, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// );
+/// let mut parquet = ParquetLog::new(Vec::new())?.with_individuals(Vec::new())?;
+/// let mut result = Ok(());
+/// evo.run_for_with(10, |generation| {
+///     if result.is_ok() {
+///         result = parquet.record(generation);
+///     }
+/// });
+/// result?;
+/// let (stats, individuals) = parquet.finish()?;
+/// assert!(stats.starts_with(b"PAR1") && individuals.unwrap().ends_with(b"PAR1"));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`StatsColumns`]: ./trait.StatsColumns.html
+/// [`FitnessColumns`]: ./trait.FitnessColumns.html
+/// [`.with_individuals()`]: ./struct.ParquetLog.html#method.with_individuals
+pub struct ParquetLog<T, S, W: Write> {
+    stats: ParquetWriter<W>,
+    individuals: Option<ParquetWriter<W>>,
+    _marker: PhantomData<fn(&T, &S)>,
+}
+
+impl<T, S, W> ParquetLog<T, S, W>
+where
+    T: Solution,
+    T::Fitness: FitnessColumns,
+    S: GenerationStats<T>,
+    S::Output: StatsColumns,
+    W: Write,
+{
+    /// Start writing statistics to `stats`.
+    pub fn new(stats: W) -> io::Result<Self> {
+        Ok(ParquetLog {
+            stats: ParquetWriter::new(stats, stats_schema::<T, S>())?,
+            individuals: None,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Also write the fitness of every individual in every generation to `individuals`.
+    pub fn with_individuals(mut self, individuals: W) -> io::Result<Self> {
+        self.individuals = Some(ParquetWriter::new(individuals, individuals_schema::<T>())?);
+        Ok(self)
+    }
+
+    /// Record a generation.
+    pub fn record<Hof: HallOfFame<T>>(
+        &mut self,
+        generation: Generation<T, Hof, S>,
+    ) -> io::Result<()> {
+        self.stats.write_row(&stats_row(&generation))?;
+        if let Some(individuals) = &mut self.individuals {
+            for row in individual_rows(&generation) {
+                individuals.write_row(&row)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Finish both files, returning the underlying writers.
+    pub fn finish(self) -> io::Result<(W, Option<W>)> {
+        Ok((
+            self.stats.finish()?,
+            self.individuals.map(ParquetWriter::finish).transpose()?,
+        ))
+    }
+}
+
+// The columns of the statistics written by `ParquetLog` and `ArrowLog`
+pub(super) fn stats_schema<T, S>() -> Vec<(String, ColumnType)>
+where
+    T: Solution,
+    S: GenerationStats<T>,
+    S::Output: StatsColumns,
+{
+    std::iter::once(("generation".to_owned(), ColumnType::Int64))
+        .chain(
+            <S::Output as StatsColumns>::headers()
+                .into_iter()
+                .map(|name| (name, ColumnType::Double)),
+        )
+        .collect()
+}
+
+// The columns of the individuals written by `ParquetLog` and `ArrowLog`
+pub(super) fn individuals_schema<T>() -> Vec<(String, ColumnType)>
+where
+    T: Solution,
+    T::Fitness: FitnessColumns,
+{
+    [
+        ("generation".to_owned(), ColumnType::Int64),
+        ("individual".to_owned(), ColumnType::Int64),
+    ]
+    .into_iter()
+    .chain(
+        T::Fitness::headers()
+            .into_iter()
+            .map(|name| (name, ColumnType::Double)),
+    )
+    .collect()
+}
+
+pub(super) fn stats_row<T, Hof, S>(generation: &Generation<T, Hof, S>) -> Vec<Value>
+where
+    T: Solution,
+    Hof: HallOfFame<T>,
+    S: GenerationStats<T>,
+    S::Output: StatsColumns,
+{
+    std::iter::once(Value::Int64(generation.gen as i64))
+        .chain(generation.stats.values().into_iter().map(Value::Double))
+        .collect()
+}
+
+pub(super) fn individual_rows<'a, T, Hof, S>(
+    generation: &'a Generation<T, Hof, S>,
+) -> impl Iterator<Item = Vec<Value>> + 'a
+where
+    T: Solution,
+    T::Fitness: FitnessColumns,
+    Hof: HallOfFame<T>,
+    S: GenerationStats<T>,
+{
+    let gen = Value::Int64(generation.gen as i64);
+    generation.pop.iter().enumerate().map(move |(i, ind)| {
+        [gen, Value::Int64(i as i64)]
+            .into_iter()
+            .chain(ind.evaluate().values().into_iter().map(Value::Double))
+            .collect()
+    })
+}
+
+impl<T, Hof, S, W> LogSink<T, Hof, S> for ParquetLog<T, S, W>
+where
+    T: Solution,
+    T::Fitness: FitnessColumns,
+    Hof: HallOfFame<T>,
+    S: GenerationStats<T>,
+    S::Output: StatsColumns,
+    W: Write,
+{
+    fn record(&mut self, generation: Generation<T, Hof, S>) -> io::Result<()> {
+        ParquetLog::record(self, generation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hof::BestN, stats::FitnessBasicMulti, testutils::Foo, Cached};
+
+    // A decoded Thrift compact protocol value, with every integer widened to `i64`
+    #[derive(Debug, PartialEq)]
+    enum Thrift {
+        Int(i64),
+        Binary(Vec<u8>),
+        List(Vec<Thrift>),
+        Struct(Vec<(i16, Thrift)>),
+    }
+
+    impl Thrift {
+        fn field(&self, id: i16) -> &Thrift {
+            match self {
+                Thrift::Struct(fields) => &fields.iter().find(|(field, _)| *field == id).unwrap().1,
+                _ => panic!("{:?} isn't a struct", self),
+            }
+        }
+
+        fn int(&self) -> i64 {
+            match self {
+                Thrift::Int(value) => *value,
+                _ => panic!("{:?} isn't an integer", self),
+            }
+        }
+
+        fn list(&self) -> &[Thrift] {
+            match self {
+                Thrift::List(values) => values,
+                _ => panic!("{:?} isn't a list", self),
+            }
+        }
+
+        fn string(&self) -> &str {
+            match self {
+                Thrift::Binary(bytes) => std::str::from_utf8(bytes).unwrap(),
+                _ => panic!("{:?} isn't binary", self),
+            }
+        }
+    }
+
+    fn read_varint(bytes: &mut &[u8]) -> u64 {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = bytes[0];
+            *bytes = &bytes[1..];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return value;
+            }
+            shift += 7;
+        }
+    }
+
+    fn read_zigzag(bytes: &mut &[u8]) -> i64 {
+        let value = read_varint(bytes);
+        (value >> 1) as i64 ^ -((value & 1) as i64)
+    }
+
+    fn read_value(bytes: &mut &[u8], kind: u8) -> Thrift {
+        match kind {
+            I32 | I64 => Thrift::Int(read_zigzag(bytes)),
+            BINARY => {
+                let len = read_varint(bytes) as usize;
+                let (value, rest) = bytes.split_at(len);
+                *bytes = rest;
+                Thrift::Binary(value.to_vec())
+            }
+            LIST => {
+                let header = bytes[0];
+                *bytes = &bytes[1..];
+                let len = match header >> 4 {
+                    15 => read_varint(bytes) as usize,
+                    len => len as usize,
+                };
+                Thrift::List((0..len).map(|_| read_value(bytes, header & 0xf)).collect())
+            }
+            STRUCT => read_struct(bytes),
+            _ => panic!("unexpected type {}", kind),
+        }
+    }
+
+    // Read a struct, leaving `bytes` at whatever follows it
+    fn read_struct(bytes: &mut &[u8]) -> Thrift {
+        let mut fields = Vec::new();
+        let mut last = 0;
+        loop {
+            let header = bytes[0];
+            *bytes = &bytes[1..];
+            if header == 0 {
+                return Thrift::Struct(fields);
+            }
+            let id = match header >> 4 {
+                0 => read_zigzag(bytes) as i16,
+                delta => last + delta as i16,
+            };
+            last = id;
+            fields.push((id, read_value(bytes, header & 0xf)));
+        }
+    }
+
+    // Read a whole file back through its footer, like a Parquet reader would:
+    // returns the name and physical type of each column, and the values of each column
+    fn read_file(bytes: &[u8]) -> (Vec<(String, i64)>, Vec<Vec<Value>>) {
+        assert_eq!(&bytes[..4], MAGIC);
+        assert_eq!(&bytes[bytes.len() - 4..], MAGIC);
+        let footer_len =
+            u32::from_le_bytes(bytes[bytes.len() - 8..bytes.len() - 4].try_into().unwrap())
+                as usize;
+        let mut footer = &bytes[bytes.len() - 8 - footer_len..bytes.len() - 8];
+        let meta = read_struct(&mut footer);
+        assert!(footer.is_empty());
+        assert_eq!(meta.field(1).int(), 1);
+
+        let schema = meta.field(2).list();
+        assert_eq!(schema[0].field(4).string(), "schema");
+        assert_eq!(schema[0].field(5).int() as usize, schema.len() - 1);
+        let columns: Vec<(String, i64)> = schema[1..]
+            .iter()
+            .map(|element| {
+                assert_eq!(element.field(3).int(), 0);
+                (element.field(4).string().to_owned(), element.field(1).int())
+            })
+            .collect();
+
+        let mut values = vec![Vec::new(); columns.len()];
+        let mut rows = 0;
+        for group in meta.field(4).list() {
+            let group_rows = group.field(3).int();
+            let chunks = group.field(1).list();
+            assert_eq!(chunks.len(), columns.len());
+            let mut group_size = 0;
+            for ((chunk, (name, physical_type)), values) in
+                chunks.iter().zip(&columns).zip(&mut values)
+            {
+                let meta = chunk.field(3);
+                assert_eq!(meta.field(1).int(), *physical_type);
+                assert_eq!(meta.field(3).list(), [Thrift::Binary(name.clone().into())]);
+                assert_eq!(meta.field(4).int(), 0);
+                assert_eq!(meta.field(5).int(), group_rows);
+                let size = meta.field(7).int() as usize;
+                group_size += size;
+
+                let start = meta.field(9).int() as usize;
+                let mut page = &bytes[start..start + size];
+                let header = read_struct(&mut page);
+                assert_eq!(header.field(1).int(), 0);
+                assert_eq!(header.field(3).int() as usize, page.len());
+                let data_page = header.field(5);
+                assert_eq!(data_page.field(1).int(), group_rows);
+                assert_eq!(data_page.field(2).int(), 0);
+                for value in page.chunks_exact(8) {
+                    let value: [u8; 8] = value.try_into().unwrap();
+                    values.push(match *physical_type {
+                        2 => Value::Int64(i64::from_le_bytes(value)),
+                        5 => Value::Double(f64::from_le_bytes(value)),
+                        other => panic!("unexpected physical type {}", other),
+                    });
+                }
+            }
+            assert_eq!(group.field(2).int() as usize, group_size);
+            rows += group_rows;
+        }
+        assert_eq!(meta.field(3).int(), rows);
+        (columns, values)
+    }
+
+    #[test]
+    fn round_trip() {
+        // enough columns that the schema needs the long form of a list header
+        let schema: Vec<(String, ColumnType)> =
+            std::iter::once(("gen".to_owned(), ColumnType::Int64))
+                .chain((0..15).map(|i| (format!("value_{}", i), ColumnType::Double)))
+                .collect();
+        let mut writer = ParquetWriter::new(Vec::new(), schema)
+            .unwrap()
+            .with_row_group_size(4);
+        let rows: Vec<Vec<Value>> = (0..10)
+            .map(|gen| {
+                std::iter::once(Value::Int64(gen - 3))
+                    .chain((0..15).map(|i| Value::Double(gen as f64 * 1.5 - i as f64)))
+                    .collect()
+            })
+            .collect();
+        for row in &rows {
+            writer.write_row(row).unwrap();
+        }
+        let bytes = writer.finish().unwrap();
+
+        let (columns, values) = read_file(&bytes);
+        assert_eq!(columns.len(), 16);
+        assert_eq!(columns[0], ("gen".to_owned(), 2));
+        assert_eq!(columns[15], ("value_14".to_owned(), 5));
+        for (c, column) in values.iter().enumerate() {
+            let expected: Vec<Value> = rows.iter().map(|row| row[c]).collect();
+            assert_eq!(column, &expected);
+        }
+    }
+
+    #[test]
+    fn parquet_log() {
+        let pop = [Cached::new(Foo([1.0, -3.0])), Cached::new(Foo([0.5, 2.5]))];
+        let hof: BestN<Foo> = BestN::new(1);
+        crate::fitness::par_evaluate(&pop);
+        let stats = FitnessBasicMulti::default().analyze(&pop);
+        let mut log: ParquetLog<Foo, FitnessBasicMulti<2>, Vec<u8>> = ParquetLog::new(Vec::new())
+            .unwrap()
+            .with_individuals(Vec::new())
+            .unwrap();
+        for gen in [0, 1] {
+            log.record(Generation::<_, _, FitnessBasicMulti<2>> {
+                gen,
+                pop: &pop,
+                hall_of_fame: &hof,
+                stats: &stats,
+                evaluations: 0,
+            })
+            .unwrap();
+        }
+        let (stats_file, individuals_file) = log.finish().unwrap();
+
+        let (columns, values) = read_file(&stats_file);
+        let headers =
+            <<FitnessBasicMulti<2> as GenerationStats<Foo>>::Output as StatsColumns>::headers();
+        assert_eq!(columns.len(), headers.len() + 1);
+        assert_eq!(columns[0].0, "generation");
+        assert!(columns[1..].iter().map(|(name, _)| name).eq(&headers));
+        assert_eq!(values[0], [Value::Int64(0), Value::Int64(1)]);
+        for (column, value) in values[1..].iter().zip(stats.values()) {
+            assert_eq!(column, &[Value::Double(value); 2]);
+        }
+
+        let (columns, values) = read_file(&individuals_file.unwrap());
+        let names: Vec<&str> = columns.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["generation", "individual", "fitness_0", "fitness_1"]
+        );
+        let int = |values: [i64; 4]| values.map(Value::Int64);
+        let double = |values: [f64; 4]| values.map(Value::Double);
+        assert_eq!(values[0], int([0, 0, 1, 1]));
+        assert_eq!(values[1], int([0, 1, 0, 1]));
+        assert_eq!(values[2], double([1.0, 0.5, 1.0, 0.5]));
+        assert_eq!(values[3], double([-3.0, 2.5, -3.0, 2.5]));
+    }
+
+    #[test]
+    fn file_layout() {
+        let mut writer = ParquetWriter::new(
+            Vec::new(),
+            vec![
+                ("gen".to_owned(), ColumnType::Int64),
+                ("value".to_owned(), ColumnType::Double),
+            ],
+        )
+        .unwrap()
+        .with_row_group_size(2);
+        for i in 0..3 {
+            writer
+                .write_row(&[Value::Int64(i), Value::Double(i as f64 / 2.0)])
+                .unwrap();
+        }
+        let bytes = writer.finish().unwrap();
+
+        assert_eq!(&bytes[..4], MAGIC);
+        assert_eq!(&bytes[bytes.len() - 4..], MAGIC);
+        let footer_len =
+            u32::from_le_bytes(bytes[bytes.len() - 8..bytes.len() - 4].try_into().unwrap())
+                as usize;
+        let footer = &bytes[bytes.len() - 8 - footer_len..bytes.len() - 8];
+        // version 1, then a list of 3 schema elements
+        assert_eq!(&footer[..3], &[0x15, 0x02, 0x19]);
+        assert_eq!(footer[3], 0x3c);
+        assert_eq!(footer.last(), Some(&0));
+
+        // the first page holds the integers 0 and 1, right after its header
+        let header = [
+            0x15, 0x00, 0x15, 0x20, 0x15, 0x20, 0x2c, 0x15, 0x04, 0x15, 0x00, 0x15, 0x06, 0x15,
+            0x06, 0x00, 0x00,
+        ];
+        assert_eq!(&bytes[4..21], &header);
+        assert_eq!(
+            &bytes[21..37],
+            &[0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0]
+        );
+        // 3 rows in total, in a list of 2 row groups
+        let rows = footer.windows(2).position(|w| w == [0x16, 0x06]).unwrap();
+        assert_eq!(&footer[rows + 2..rows + 4], &[0x19, 0x2c]);
+    }
+}
@@ -0,0 +1,385 @@
+use std::{io, ops::Range, path::Path};
+
+use plotters::{
+    coord::Shift,
+    prelude::{
+        BitMapBackend, ChartBuilder, Circle, DrawingArea, DrawingAreaErrorKind, DrawingBackend,
+        IntoDrawingArea, LineSeries, PathElement, SVGBackend, BLACK, BLUE, RED, WHITE,
+    },
+    style::{Color, RGBColor},
+};
+
+use super::FitnessColumns;
+use crate::{
+    fitness::Direction,
+    hof::HallOfFame,
+    stats::{FitnessBasic, FitnessBasicMulti, FitnessSummary, GenerationStats},
+    Log, Solution,
+};
+
+type DrawResult<DB> = Result<(), DrawingAreaErrorKind<<DB as DrawingBackend>::ErrorType>>;
+type Points = Vec<(f64, f64)>;
+
+const GREY: RGBColor = RGBColor(160, 160, 160);
+
+/// Per-generation statistics that can be plotted by [`Log::plot_convergence()`]
+///
+/// [`Log::plot_convergence()`]: ../struct.Log.html#method.plot_convergence
+pub trait ConvergenceStats {
+    /// Get the mean fitness of the generation, in the first objective if there are several.
+    fn mean_fitness(&self) -> f64;
+
+    /// Get the best fitness in the generation, in the first objective if there are several,
+    /// if these statistics keep track of it.
+    fn best_fitness(&self, direction: Direction) -> Option<f64> {
+        let _ = direction;
+        None
+    }
+}
+
+impl ConvergenceStats for FitnessBasic {
+    fn mean_fitness(&self) -> f64 {
+        self.mean()
+    }
+}
+
+impl ConvergenceStats for FitnessSummary {
+    fn mean_fitness(&self) -> f64 {
+        self.mean()
+    }
+
+    fn best_fitness(&self, direction: Direction) -> Option<f64> {
+        Some(match direction {
+            Direction::Maximize => self.max(),
+            Direction::Minimize => self.min(),
+        })
+    }
+}
+
+impl<const M: usize> ConvergenceStats for FitnessBasicMulti<M> {
+    fn mean_fitness(&self) -> f64 {
+        self.mean()[0]
+    }
+}
+
+impl<T, Hof, Stat> Log<T, Hof, Stat>
+where
+    T: Solution,
+    T::Fitness: FitnessColumns,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+    Stat::Output: ConvergenceStats,
+{
+    /// Plot how the fitness changed over the run and save the plot as an image at `path`,
+    /// replacing it if it exists.
+    ///
+    /// The plot shows the mean fitness of every generation, and the best fitness if the statistics
+    /// keep track of it, as with [`FitnessSummary`], or the run recorded it with [`.with_best_history()`]. For runs with two objectives, a second plot
+    /// next to it shows the fitness of the final population, with its nondominated solutions highlighted.
+    ///
+    /// The image is an SVG file if `path` ends in `.svg`, and otherwise a bitmap
+    /// in the format given by the extension, such as PNG.
+    ///
+    /// ```
+    /// use eviolite::prelude::*;
+    /// use eviolite::benchmarks::{Benchmark, Sphere};
+    ///
+    /// let evo: Evolution<Benchmark<Sphere, 2>, _, _, stats::FitnessSummary> = Evolution::new(
+    ///     alg::Simple::new(20, 0.5, 0.2, select::Tournament::new(3)),
+    ///     hof::BestN::new(1),
+    /// );
+    /// let log = evo.run_for(10);
+    /// log.plot_convergence(std::env::temp_dir().join("convergence.svg")).unwrap();
+    /// ```
+    ///
+    /// Errors
+    /// ======
+    /// Returns an error if the image can't be drawn or written.
+    ///
+    /// [`FitnessSummary`]: ./stats/struct.FitnessSummary.html
+    /// [`.with_best_history()`]: ./struct.Evolution.html#method.with_best_history
+    #[cfg_attr(docsrs, doc(cfg(feature = "plotters")))]
+    pub fn plot_convergence(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let two_objectives = T::Fitness::headers().len() == 2;
+        let size = if two_objectives {
+            (1280, 480)
+        } else {
+            (640, 480)
+        };
+        if path.extension().is_some_and(|ext| ext == "svg") {
+            self.draw(
+                SVGBackend::new(path, size).into_drawing_area(),
+                two_objectives,
+            )
+            .map_err(to_io)
+        } else {
+            self.draw(
+                BitMapBackend::new(path, size).into_drawing_area(),
+                two_objectives,
+            )
+            .map_err(to_io)
+        }
+    }
+
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: DrawingArea<DB, Shift>,
+        two_objectives: bool,
+    ) -> DrawResult<DB> {
+        root.fill(&WHITE)?;
+        if two_objectives {
+            let (left, right) = root.split_horizontally(root.dim_in_pixel().0 / 2);
+            self.draw_convergence(&left)?;
+            self.draw_front(&right)?;
+        } else {
+            self.draw_convergence(&root)?;
+        }
+        root.present()
+    }
+
+    fn draw_convergence<DB: DrawingBackend>(
+        &self,
+        area: &DrawingArea<DB, Shift>,
+    ) -> DrawResult<DB> {
+        let direction = Direction::of::<T>(0);
+        let generations =
+            (0..self.stats.len()).map(|i| (self.first_generation + i * self.stats_interval) as f64);
+        let mean: Points = generations
+            .clone()
+            .zip(&self.stats)
+            .map(|(gen, stat)| (gen, stat.mean_fitness()))
+            .collect();
+        let best: Option<Points> = generations
+            .clone()
+            .zip(&self.stats)
+            .map(|(gen, stat)| Some((gen, stat.best_fitness(direction)?)))
+            .collect();
+        // the history recorded by `with_best_history` stands in for statistics that don't have the best
+        let best = best.or_else(|| {
+            (self.best_fitness.len() == self.stats.len()).then(|| {
+                generations
+                    .clone()
+                    .zip(self.best_fitness.iter().copied())
+                    .collect()
+            })
+        });
+
+        let mut chart = ChartBuilder::on(area)
+            .caption("Convergence", ("sans-serif", 24))
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(
+                padded(generations),
+                padded(
+                    mean.iter()
+                        .chain(best.iter().flatten())
+                        .map(|&(_, fitness)| fitness),
+                ),
+            )?;
+        chart
+            .configure_mesh()
+            .x_desc("generation")
+            .y_desc("fitness")
+            .draw()?;
+        chart
+            .draw_series(LineSeries::new(mean, &BLUE))?
+            .label("mean")
+            .legend(|(x, y)| PathElement::new([(x, y), (x + 20, y)], BLUE));
+        if let Some(best) = best {
+            chart
+                .draw_series(LineSeries::new(best, &RED))?
+                .label("best")
+                .legend(|(x, y)| PathElement::new([(x, y), (x + 20, y)], RED));
+        }
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()
+    }
+
+    fn draw_front<DB: DrawingBackend>(&self, area: &DrawingArea<DB, Shift>) -> DrawResult<DB> {
+        let directions = [Direction::of::<T>(0), Direction::of::<T>(1)];
+        let points: Points = self
+            .final_population
+            .iter()
+            .map(|ind| {
+                let values = ind.evaluate().values();
+                (values[0], values[1])
+            })
+            .collect();
+        let (front, dominated): (Points, Points) = points.iter().partition(|&&point| {
+            !points
+                .iter()
+                .any(|&other| dominates(directions, other, point))
+        });
+
+        let headers = T::Fitness::headers();
+        let mut chart = ChartBuilder::on(area)
+            .caption("Final population", ("sans-serif", 24))
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d(
+                padded(points.iter().map(|&(x, _)| x)),
+                padded(points.iter().map(|&(_, y)| y)),
+            )?;
+        chart
+            .configure_mesh()
+            .x_desc(headers[0].as_str())
+            .y_desc(headers[1].as_str())
+            .draw()?;
+        chart
+            .draw_series(
+                dominated
+                    .into_iter()
+                    .map(|point| Circle::new(point, 3, GREY.filled())),
+            )?
+            .label("dominated")
+            .legend(|point| Circle::new(point, 3, GREY.filled()));
+        chart
+            .draw_series(
+                front
+                    .into_iter()
+                    .map(|point| Circle::new(point, 4, RED.filled())),
+            )?
+            .label("nondominated")
+            .legend(|point| Circle::new(point, 4, RED.filled()));
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()
+    }
+}
+
+// Whether `a` is at least as good as `b` in both objectives and better in one
+fn dominates(directions: [Direction; 2], a: (f64, f64), b: (f64, f64)) -> bool {
+    let [dx, dy] = directions;
+    !dx.is_better(b.0, a.0)
+        && !dy.is_better(b.1, a.1)
+        && (dx.is_better(a.0, b.0) || dy.is_better(a.1, b.1))
+}
+
+// The range of `values` with some room around it, so that points aren't drawn on the edges
+fn padded(values: impl Iterator<Item = f64>) -> Range<f64> {
+    let (min, max) = values
+        .filter(|value| value.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    if min > max {
+        return 0.0..1.0;
+    }
+    let pad = if max > min {
+        (max - min) * 0.05
+    } else {
+        min.abs().max(1.0) * 0.05
+    };
+    min - pad..max + pad
+}
+
+fn to_io<E: std::error::Error + Send + Sync + 'static>(err: DrawingAreaErrorKind<E>) -> io::Error {
+    io::Error::other(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{
+        alg::{Simple, NSGA2},
+        benchmarks::multi::{MultiBenchmark, Zdt1},
+        hof::{BestN, BestPareto},
+        select::Tournament,
+        testutils::Walk,
+        Evolution,
+    };
+
+    #[test]
+    fn plots_are_written() {
+        let dir = std::env::temp_dir().join("eviolite-plot-test");
+        fs::create_dir_all(&dir).unwrap();
+
+        let evo: Evolution<Walk, _, _, FitnessSummary> =
+            Evolution::new(Simple::new(20, 0.5, 0.3, Tournament::new(2)), BestN::new(1));
+        let path = dir.join("single.svg");
+        evo.run_for(10).plot_convergence(&path).unwrap();
+        let svg = fs::read_to_string(&path).unwrap();
+        assert!(svg.contains("Convergence") && !svg.contains("Final population"));
+
+        let evo: Evolution<MultiBenchmark<Zdt1, 5, 2>, _, _, FitnessBasicMulti<2>> =
+            Evolution::new(NSGA2::new(20, 0.5, 0.3), BestPareto::new()).with_best_history();
+        let path = dir.join("pareto.svg");
+        evo.run_for(10).plot_convergence(&path).unwrap();
+        let svg = fs::read_to_string(&path).unwrap();
+        assert!(svg.contains("Final population") && svg.contains("fitness_1"));
+        assert!(
+            svg.contains("\nbest\n"),
+            "the best fitness comes from the history"
+        );
+    }
+
+    #[test]
+    fn bitmaps_and_errors() {
+        let dir = std::env::temp_dir().join("eviolite-plot-test");
+        fs::create_dir_all(&dir).unwrap();
+
+        // without the best fitness, only the mean is drawn
+        let evo: Evolution<Walk, _, _, FitnessBasic> =
+            Evolution::new(Simple::new(20, 0.5, 0.3, Tournament::new(2)), BestN::new(1));
+        let log = evo.run_for(5);
+        let path = dir.join("mean.svg");
+        log.plot_convergence(&path).unwrap();
+        let svg = fs::read_to_string(&path).unwrap();
+        assert!(svg.contains("\nmean\n") && !svg.contains("\nbest\n"));
+
+        let path = dir.join("mean.png");
+        log.plot_convergence(&path).unwrap();
+        let png = fs::read(&path).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+
+        assert!(log
+            .plot_convergence(dir.join("missing").join("mean.svg"))
+            .is_err());
+    }
+
+    #[test]
+    fn convergence_stats() {
+        let evo: Evolution<Walk, _, _, FitnessSummary> =
+            Evolution::new(Simple::new(20, 0.5, 0.3, Tournament::new(2)), BestN::new(1));
+        let log = evo.run_for(1);
+        let stats = &log.stats[0];
+        assert_eq!(stats.mean_fitness(), stats.mean());
+        assert_eq!(stats.best_fitness(Direction::Maximize), Some(stats.max()));
+        assert_eq!(stats.best_fitness(Direction::Minimize), Some(stats.min()));
+
+        let evo: Evolution<Walk, _, _, FitnessBasic> =
+            Evolution::new(Simple::new(20, 0.5, 0.3, Tournament::new(2)), BestN::new(1));
+        let log = evo.run_for(1);
+        assert_eq!(log.stats[0].mean_fitness(), log.stats[0].mean());
+        assert_eq!(log.stats[0].best_fitness(Direction::Maximize), None);
+    }
+
+    #[test]
+    fn dominance() {
+        let directions = [Direction::Maximize, Direction::Minimize];
+        assert!(dominates(directions, (1.0, 0.0), (0.0, 1.0)));
+        assert!(dominates(directions, (1.0, 1.0), (0.0, 1.0)));
+        assert!(!dominates(directions, (1.0, 1.0), (1.0, 1.0)));
+        assert!(!dominates(directions, (1.0, 1.0), (0.0, 0.0)));
+        assert_eq!(padded([2.0, 2.0].into_iter()), 1.9..2.1);
+        assert_eq!(padded([0.0, 0.0].into_iter()), -0.05..0.05);
+        assert_eq!(padded([0.0, 10.0].into_iter()), -0.5..10.5);
+        // non-finite values are left out, and there's always some range
+        assert_eq!(
+            padded([f64::NAN, 0.0, f64::INFINITY, 10.0].into_iter()),
+            -0.5..10.5
+        );
+        assert_eq!(padded(std::iter::empty()), 0.0..1.0);
+        assert_eq!(padded([f64::NEG_INFINITY].into_iter()), 0.0..1.0);
+    }
+}
@@ -0,0 +1,403 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::{FitnessColumns, LogSink, StatsColumns};
+use crate::{fitness::Direction, hof::HallOfFame, stats::GenerationStats, Generation, Solution};
+
+/// A [`LogSink`] that writes per-generation scalars as a TensorBoard event file
+///
+/// For every generation, using the index of the generation as the step, it writes:
+/// - `fitness/best` and `fitness/mean`, the best and mean value of the fitness
+///   in the population, or `fitness_0/best`, `fitness_0/mean`, and so on if the fitness
+///   has more than one column, as given by [`FitnessColumns`]
+/// - `evaluations`, the number of fitness evaluations since the run started,
+///   as given by [`Generation::evaluations`]
+/// - `stats/` followed by the name of each statistics column, as given by [`StatsColumns`]
+///
+/// Any other metric, such as the hypervolume of a multi-objective run
+/// or the diversity of the population, can either be computed by the run's statistics type,
+/// or computed separately and written with [`.add_scalar()`].
+///
+/// ```no_run
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+/// use eviolite::export::{LogSink, TensorBoardLog};
+/// use std::io::Write;
+///
+/// # fn main() -> std::io::Result<()> {
+/// let evo: Evolution<Benchmark<Sphere, 2>, _, _, stats::FitnessSummary> = Evolution::new(
+///     alg::MuPlusLambda::new(20, 40, 0.6, 0.3, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// );
+/// let mut log = TensorBoardLog::create("runs/sphere")?;
+/// evo.run_for_with(100, |generation| log.record(generation).unwrap());
+/// log.into_inner().flush()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Then run `tensorboard --logdir runs` to see the results.
+///
+/// [`LogSink`]: ./trait.LogSink.html
+/// [`FitnessColumns`]: ./trait.FitnessColumns.html
+/// [`Generation::evaluations`]: ../struct.Generation.html#structfield.evaluations
+/// [`StatsColumns`]: ./trait.StatsColumns.html
+/// [`.add_scalar()`]: ./struct.TensorBoardLog.html#method.add_scalar
+pub struct TensorBoardLog<W: Write> {
+    writer: W,
+}
+
+impl TensorBoardLog<BufWriter<File>> {
+    /// Create a new event file in the directory `log_dir`, creating the directory if needed.
+    ///
+    /// Each run should get its own directory, since TensorBoard treats every directory as one run.
+    pub fn create(log_dir: impl AsRef<Path>) -> io::Result<Self> {
+        let log_dir = log_dir.as_ref();
+        fs::create_dir_all(log_dir)?;
+        let name = format!(
+            "events.out.tfevents.{}.eviolite.{}",
+            wall_time() as u64,
+            std::process::id()
+        );
+        Self::new(BufWriter::new(File::create(log_dir.join(name))?))
+    }
+}
+
+impl<W: Write> TensorBoardLog<W> {
+    /// Start writing events to `writer`.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        let mut event = event(0);
+        event.string(3, b"brain.Event:2");
+        write_record(&mut writer, &event.bytes)?;
+        Ok(TensorBoardLog { writer })
+    }
+
+    /// Write a single scalar with the name `tag` at the given step.
+    ///
+    /// Tags containing a `/` are grouped by everything before it in TensorBoard.
+    pub fn add_scalar(&mut self, tag: &str, step: usize, value: f64) -> io::Result<()> {
+        self.add_scalars(step, &[(tag.to_owned(), value)])
+    }
+
+    /// Write a list of named scalars at the given step, as a single event.
+    pub fn add_scalars(&mut self, step: usize, scalars: &[(String, f64)]) -> io::Result<()> {
+        let mut summary = Proto::default();
+        for (tag, value) in scalars {
+            let mut entry = Proto::default();
+            entry.string(1, tag.as_bytes());
+            entry.f32(2, *value as f32);
+            summary.message(1, &entry);
+        }
+        let mut event = event(step as i64);
+        event.message(5, &summary);
+        write_record(&mut self.writer, &event.bytes)
+    }
+
+    /// Consume the log, returning the writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<T, Hof, Stat, W> LogSink<T, Hof, Stat> for TensorBoardLog<W>
+where
+    T: Solution,
+    T::Fitness: FitnessColumns,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+    Stat::Output: StatsColumns,
+    W: Write,
+{
+    fn record(&mut self, generation: Generation<T, Hof, Stat>) -> io::Result<()> {
+        let headers = T::Fitness::headers();
+        let directions: Vec<Direction> = (0..headers.len()).map(Direction::of::<T>).collect();
+        let mut best: Vec<f64> = directions.iter().map(|d| d.worst()).collect();
+        let mut sum = vec![0.0; headers.len()];
+        for ind in generation.pop {
+            for (m, value) in ind.evaluate().values().into_iter().enumerate() {
+                best[m] = directions[m].best(best[m], value);
+                sum[m] += value;
+            }
+        }
+
+        let len = generation.pop.len() as f64;
+        let mut scalars = Vec::new();
+        for (m, header) in headers.iter().enumerate() {
+            scalars.push((format!("{}/best", header), best[m]));
+            scalars.push((format!("{}/mean", header), sum[m] / len));
+        }
+        scalars.push(("evaluations".to_owned(), generation.evaluations as f64));
+        for (header, value) in <Stat::Output as StatsColumns>::headers()
+            .into_iter()
+            .zip(generation.stats.values())
+        {
+            scalars.push((format!("stats/{}", header), value));
+        }
+        self.add_scalars(generation.gen, &scalars)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+fn wall_time() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |time| time.as_secs_f64())
+}
+
+// An `Event` message with `wall_time` and `step` set
+fn event(step: i64) -> Proto {
+    let mut event = Proto::default();
+    event.f64(1, wall_time());
+    event.int64(2, step);
+    event
+}
+
+// Event files are TFRecord files: each record is its length, a checksum of the length,
+// the data, and a checksum of the data.
+fn write_record<W: Write>(writer: &mut W, data: &[u8]) -> io::Result<()> {
+    let len = (data.len() as u64).to_le_bytes();
+    writer.write_all(&len)?;
+    writer.write_all(&masked_crc(&len).to_le_bytes())?;
+    writer.write_all(data)?;
+    writer.write_all(&masked_crc(data).to_le_bytes())
+}
+
+fn masked_crc(bytes: &[u8]) -> u32 {
+    let crc = crc32c(bytes);
+    crc.rotate_right(15).wrapping_add(0xa282ead8)
+}
+
+// CRC-32C (Castagnoli), bit by bit since event files are small
+fn crc32c(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0x82f63b78
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+// A minimal Protocol Buffers encoder, with just the field types used by event files
+#[derive(Default)]
+struct Proto {
+    bytes: Vec<u8>,
+}
+
+impl Proto {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.bytes.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.bytes.push(value as u8);
+    }
+
+    fn key(&mut self, field: u32, wire_type: u8) {
+        self.varint(((field as u64) << 3) | wire_type as u64);
+    }
+
+    fn int64(&mut self, field: u32, value: i64) {
+        self.key(field, 0);
+        self.varint(value as u64);
+    }
+
+    fn f64(&mut self, field: u32, value: f64) {
+        self.key(field, 1);
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn string(&mut self, field: u32, value: &[u8]) {
+        self.key(field, 2);
+        self.varint(value.len() as u64);
+        self.bytes.extend_from_slice(value);
+    }
+
+    fn message(&mut self, field: u32, value: &Proto) {
+        self.string(field, &value.bytes);
+    }
+
+    fn f32(&mut self, field: u32, value: f32) {
+        self.key(field, 5);
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hof::BestN, stats::FitnessSummary, testutils::One, Cached};
+
+    // Split a TFRecord stream back into records, checking every checksum.
+    fn records(mut bytes: &[u8]) -> Vec<&[u8]> {
+        let mut records = Vec::new();
+        while !bytes.is_empty() {
+            let len = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+            assert_eq!(&bytes[8..12], masked_crc(&bytes[..8]).to_le_bytes());
+            let data = &bytes[12..12 + len];
+            assert_eq!(&bytes[12 + len..16 + len], masked_crc(data).to_le_bytes());
+            records.push(data);
+            bytes = &bytes[16 + len..];
+        }
+        records
+    }
+
+    // A decoded protobuf field: the field number and either a varint, a fixed-size value, or bytes.
+    #[derive(Debug, PartialEq)]
+    enum Field<'a> {
+        Varint(u32, u64),
+        Fixed64(u32, [u8; 8]),
+        Bytes(u32, &'a [u8]),
+        Fixed32(u32, [u8; 4]),
+    }
+
+    fn read_varint(bytes: &mut &[u8]) -> u64 {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = bytes[0];
+            *bytes = &bytes[1..];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return value;
+            }
+            shift += 7;
+        }
+    }
+
+    fn decode(mut bytes: &[u8]) -> Vec<Field<'_>> {
+        let mut fields = Vec::new();
+        while !bytes.is_empty() {
+            let key = read_varint(&mut bytes);
+            let field = (key >> 3) as u32;
+            fields.push(match key & 7 {
+                0 => Field::Varint(field, read_varint(&mut bytes)),
+                1 => {
+                    let (value, rest) = bytes.split_at(8);
+                    bytes = rest;
+                    Field::Fixed64(field, value.try_into().unwrap())
+                }
+                2 => {
+                    let len = read_varint(&mut bytes) as usize;
+                    let (value, rest) = bytes.split_at(len);
+                    bytes = rest;
+                    Field::Bytes(field, value)
+                }
+                5 => {
+                    let (value, rest) = bytes.split_at(4);
+                    bytes = rest;
+                    Field::Fixed32(field, value.try_into().unwrap())
+                }
+                wire_type => panic!("unexpected wire type {}", wire_type),
+            });
+        }
+        fields
+    }
+
+    #[test]
+    fn crc_vectors() {
+        // From RFC 3720, appendix B.4
+        assert_eq!(crc32c(b""), 0);
+        assert_eq!(crc32c(b"123456789"), 0xe3069283);
+        assert_eq!(crc32c(&[0; 32]), 0x8a9136aa);
+        assert_eq!(crc32c(&[0xff; 32]), 0x62a8ab43);
+        let ascending: Vec<u8> = (0..32).collect();
+        assert_eq!(crc32c(&ascending), 0x46dd794e);
+        let descending: Vec<u8> = (0..32).rev().collect();
+        assert_eq!(crc32c(&descending), 0x113fdb5c);
+
+        assert_eq!(masked_crc(b""), 0xa282ead8);
+        assert_eq!(masked_crc(b"123456789"), 0xc78ab0e5);
+    }
+
+    #[test]
+    fn proto_fields() {
+        let mut proto = Proto::default();
+        proto.int64(2, 300);
+        proto.int64(3, -1);
+        proto.f64(1, 1.5);
+        proto.string(4, b"tag");
+        proto.f32(2, 0.25);
+        let mut inner = Proto::default();
+        inner.int64(1, 1);
+        proto.message(5, &inner);
+
+        assert_eq!(&proto.bytes[..3], [0x10, 0xac, 0x02]);
+        assert_eq!(
+            decode(&proto.bytes),
+            vec![
+                Field::Varint(2, 300),
+                Field::Varint(3, u64::MAX),
+                Field::Fixed64(1, 1.5f64.to_le_bytes()),
+                Field::Bytes(4, b"tag"),
+                Field::Fixed32(2, 0.25f32.to_le_bytes()),
+                Field::Bytes(5, &[0x08, 0x01]),
+            ]
+        );
+    }
+
+    #[test]
+    fn event_records() {
+        let pop = [Cached::new(One(1.0)), Cached::new(One(3.0))];
+        let hof: BestN<One> = BestN::new(1);
+        let stats = FitnessSummary::default().analyze(&pop);
+        let mut log = TensorBoardLog::new(Vec::new()).unwrap();
+        log.record(Generation::<_, _, FitnessSummary> {
+            gen: 300,
+            pop: &pop,
+            hall_of_fame: &hof,
+            stats: &stats,
+            evaluations: 42,
+        })
+        .unwrap();
+
+        let bytes = log.into_inner();
+        let records = records(&bytes);
+        assert_eq!(records.len(), 2);
+        assert_eq!(decode(records[0])[2], Field::Bytes(3, b"brain.Event:2"));
+
+        // wall_time, step, then a summary with one value per scalar
+        let event = decode(records[1]);
+        assert!(matches!(event[0], Field::Fixed64(1, _)));
+        assert_eq!(event[1], Field::Varint(2, 300));
+        let summary = match event[2] {
+            Field::Bytes(5, summary) => decode(summary),
+            ref field => panic!("expected a summary, got {:?}", field),
+        };
+        let scalars: Vec<(&[u8], f32)> = summary
+            .iter()
+            .map(|value| match value {
+                Field::Bytes(1, value) => match decode(value)[..] {
+                    [Field::Bytes(1, tag), Field::Fixed32(2, simple_value)] => {
+                        (tag, f32::from_le_bytes(simple_value))
+                    }
+                    ref fields => panic!("unexpected value {:?}", fields),
+                },
+                field => panic!("unexpected field {:?}", field),
+            })
+            .collect();
+        assert_eq!(
+            &scalars[..3],
+            [
+                (&b"fitness_0/best"[..], 3.0),
+                (&b"fitness_0/mean"[..], 2.0),
+                (&b"evaluations"[..], 42.0),
+            ]
+        );
+        assert!(scalars[3..]
+            .iter()
+            .all(|(tag, _)| tag.starts_with(b"stats/")));
+    }
+}
@@ -3,17 +3,42 @@
 //! This module contains [`MultiObjective`].
 //! You should use either `f64` or [`MultiObjective`]
 //! as your [`Solution`]'s fitness type for simple applications.
+//! Higher fitness is better unless the solution says otherwise through [`Solution::DIRECTIONS`],
+//! which lists the [`Direction`] each objective is optimized in.
 //!
 //! This module also contains [`par_evaluate`], a function that uses
-//! [`rayon`]'s parallel iterators to efficiently evaluate a population.
+//! [`rayon`]'s parallel iterators to efficiently evaluate a population
+//! (or evaluates it on the current thread if the `parallel` feature is disabled),
+//! [`par_evaluate_dedup`], which only evaluates one of each group of identical solutions,
+//! and [`evaluation_count`], which counts the evaluations performed so far.
+//!
+//! To evaluate whole populations at once on a GPU or another device,
+//! implement [`DeviceEvaluator`] and use [`device_evaluate`].
 //!
 //! [`Solution`]: ../trait.Solution.html
+//! [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+//! [`Direction`]: ./enum.Direction.html
 //! [`MultiObjective`]: ./struct.MultiObjective.html
+//! [`par_evaluate`]: ./fn.par_evaluate.html
+//! [`par_evaluate_dedup`]: ./fn.par_evaluate_dedup.html
+//! [`evaluation_count`]: ./fn.evaluation_count.html
+//! [`DeviceEvaluator`]: ./trait.DeviceEvaluator.html
+//! [`device_evaluate`]: ./fn.device_evaluate.html
 
-use std::ops::Deref;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "parallel")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::{borrow::Borrow, ops::Deref};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
+#[cfg(feature = "parallel")]
+use crate::utils::counter;
 use crate::{Cached, Solution};
 
 /// Type that represents fitness values in multi-objective optimization
@@ -21,7 +46,13 @@ use crate::{Cached, Solution};
 /// This type includes support for weighted fitness values,
 /// which can then be collapsed into a single combined fitness.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct MultiObjective<const M: usize> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::serde_array"))]
     weighted: [f64; M],
 }
 
@@ -96,14 +127,435 @@ impl PartialEq<f64> for MultiObjective<1> {
     }
 }
 
+/// The direction in which an objective is optimized
+///
+/// Solutions declare the direction of each of their objectives with [`Solution::DIRECTIONS`],
+/// and everything in the crate that compares fitness values, such as selection operators,
+/// halls of fame, and nondominated ranking, respects it.
+/// The fitness values themselves are never changed, so there's no need to negate objectives
+/// that should be minimized, and reports show them as they are.
+///
+/// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Higher values are better. This is the default.
+    #[default]
+    Maximize,
+    /// Lower values are better.
+    Minimize,
+}
+
+impl Direction {
+    /// Get the direction of objective `m` of `T`'s fitness.
+    ///
+    /// Objectives that aren't listed in [`Solution::DIRECTIONS`] are maximized.
+    /// Fitness types that are a single number, such as `f64`, use the direction of objective 0.
+    ///
+    /// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+    pub fn of<T: Solution>(m: usize) -> Self {
+        T::DIRECTIONS.get(m).copied().unwrap_or_default()
+    }
+
+    /// Returns `true` if `a` is strictly better than `b`.
+    pub fn is_better(self, a: f64, b: f64) -> bool {
+        match self {
+            Direction::Maximize => a > b,
+            Direction::Minimize => a < b,
+        }
+    }
+
+    /// Compare two values, where [`Greater`] means that `a` is better than `b`.
+    ///
+    /// This uses [`f64::total_cmp`], so it can be used to sort values from worst to best.
+    ///
+    /// [`Greater`]: https://doc.rust-lang.org/core/cmp/enum.Ordering.html#variant.Greater
+    pub fn compare(self, a: f64, b: f64) -> core::cmp::Ordering {
+        match self {
+            Direction::Maximize => a.total_cmp(&b),
+            Direction::Minimize => b.total_cmp(&a),
+        }
+    }
+
+    /// Get whichever of `a` and `b` is better.
+    pub fn best(self, a: f64, b: f64) -> f64 {
+        match self {
+            Direction::Maximize => a.max(b),
+            Direction::Minimize => a.min(b),
+        }
+    }
+
+    /// Get the worst possible value, which is the starting point when looking for the best of many.
+    pub fn worst(self) -> f64 {
+        match self {
+            Direction::Maximize => f64::NEG_INFINITY,
+            Direction::Minimize => f64::INFINITY,
+        }
+    }
+}
+
+/// Get the total number of fitness evaluations performed by [`Cached`] so far in this program.
+///
+/// Only evaluations that were actually computed are counted, not those served from the cache.
+/// The count is shared by every run in the program; each run's own evaluations are counted
+/// separately in [`Generation::evaluations`].
+///
+/// [`Cached`]: ../struct.Cached.html
+/// [`Generation::evaluations`]: ../struct.Generation.html#structfield.evaluations
+pub fn evaluation_count() -> u64 {
+    crate::utils::counter::total().evaluations()
+}
+
 /// Evaluate the fitness of every solution in a population in parallel.
 ///
 /// For good performance, you should only ever evaluate solutions using this function, not
 /// using the [`.evaluate()`] method directly.
 ///
+/// If the `parallel` crate feature is disabled, the solutions are evaluated one after another
+/// on the current thread instead, which is what targets without threads
+/// such as `wasm32-unknown-unknown` need.
+///
+/// Otherwise, if any of the solutions have an [evaluation cost] estimate,
+/// the most costly solutions are evaluated first.
+///
+/// During a run, the evaluations happen in the thread pool given to
+/// [`Evolution::with_thread_pool()`], if any, and in rayon's global pool otherwise.
+///
+/// [evaluation cost]: ../trait.Solution.html#method.evaluation_cost
+/// [`Evolution::with_thread_pool()`]: ../struct.Evolution.html#method.with_thread_pool
 /// [`.evaluate()`]: ../trait.Solution.html#tymethod.evaluate
 pub fn par_evaluate<T: Solution>(pop: &[Cached<T>]) {
-    pop.par_iter().for_each(|ind| {
-        ind.evaluate();
+    evaluate_each(pop);
+}
+
+/// Evaluate the fitness of every distinct solution in a population in parallel,
+/// sharing the result between identical copies.
+///
+/// This works like [`par_evaluate()`], except that solutions that are equal to each other
+/// are only evaluated once, and the others get a copy of the fitness value.
+/// If one of the copies already has a cached fitness value, none of them are evaluated.
+/// Only the evaluations that actually happen are counted by [`evaluation_count()`].
+///
+/// This is worth it when evaluations are expensive and offspring are often identical
+/// to each other or to the rest of the population, e.g. with small genomes or low mutation rates.
+/// [`Evolution::with_dedup_evaluation()`] evaluates every generation of a run this way.
+///
+/// [`par_evaluate()`]: ./fn.par_evaluate.html
+/// [`evaluation_count()`]: ./fn.evaluation_count.html
+/// [`Evolution::with_dedup_evaluation()`]: ../struct.Evolution.html#method.with_dedup_evaluation
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn par_evaluate_dedup<T>(pop: &[Cached<T>])
+where
+    T: Solution + Hash + Eq,
+{
+    // Each distinct solution is represented by a copy that's already evaluated, if there is one,
+    // or by the first copy otherwise
+    let mut representatives: HashMap<&T, &Cached<T>> = HashMap::with_capacity(pop.len());
+    for ind in pop.iter().filter(|ind| ind.fitness().is_some()) {
+        representatives.entry(ind.as_ref()).or_insert(ind);
+    }
+    let mut pending: Vec<&Cached<T>> = Vec::new();
+    for ind in pop.iter().filter(|ind| ind.fitness().is_none()) {
+        representatives.entry(ind.as_ref()).or_insert_with(|| {
+            pending.push(ind);
+            ind
+        });
+    }
+
+    evaluate_each(&pending);
+
+    for ind in pop {
+        ind.share_fitness(representatives[ind.as_ref()]);
+    }
+}
+
+fn evaluate_each<T, C>(pop: &[C])
+where
+    T: Solution,
+    C: Borrow<Cached<T>> + Sync,
+{
+    fn get<T: Solution>(ind: &impl Borrow<Cached<T>>) -> &Cached<T> {
+        ind.borrow()
+    }
+
+    #[cfg(feature = "parallel")]
+    crate::utils::thread_pool::install(|| {
+        // Solutions without a cost estimate go first, since they could take the longest
+        let mut pending: Vec<(usize, f64)> = pop
+            .iter()
+            .map(get)
+            .enumerate()
+            .filter(|(_, ind)| ind.fitness().is_none())
+            .map(|(i, ind)| (i, ind.evaluation_cost().unwrap_or(f64::INFINITY)))
+            .collect();
+        if pending.iter().all(|(_, cost)| *cost == f64::INFINITY) {
+            pop.par_iter().for_each(counter::carry(|ind| {
+                get(ind).evaluate();
+            }));
+            return;
+        }
+
+        // Longest first, with each thread taking the next solution as soon as it's done with one
+        pending.sort_unstable_by(|(_, a), (_, b)| f64::total_cmp(b, a));
+        let next = AtomicUsize::new(0);
+        // The threads count their evaluations in the run that's evaluating the population
+        let run = counter::current();
+        rayon::scope(|scope| {
+            for _ in 0..rayon::current_num_threads() {
+                scope.spawn(|_| {
+                    let _run = counter::enter(run.clone());
+                    while let Some((i, _)) = pending.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        get(&pop[*i]).evaluate();
+                    }
+                });
+            }
+        });
     });
+    #[cfg(not(feature = "parallel"))]
+    for ind in pop {
+        get(ind).evaluate();
+    }
+}
+
+/// A way to evaluate a whole population at once on a device like a GPU.
+///
+/// The genomes of every solution that still needs to be evaluated are packed
+/// one after another into a single flat buffer, which is handed to [`.evaluate()`]
+/// to run a kernel on, and the fitness values it reads back are stored in the solutions' caches.
+/// Talking to the device, e.g. through `wgpu` or CUDA, is entirely up to the implementor.
+///
+/// Use it with [`device_evaluate()`], or give it to [`Evolution::with_device_evaluator()`]
+/// to evaluate every generation of a run with it.
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use eviolite::prelude::*;
+/// use eviolite::fitness::{device_evaluate, DeviceEvaluator};
+/// # #[derive(Clone)]
+/// # struct Point([f32; 2]);
+/// # impl Solution for Point {
+/// #     type Fitness = f64;
+/// #     fn generate() -> Self { Point([random(), random()]) }
+/// #     fn evaluate(&self) -> f64 { unreachable!() }
+/// #     fn crossover(_: &mut Self, _: &mut Self) {}
+/// #     fn mutate(&mut self) {}
+/// # }
+///
+/// struct Kernel;
+///
+/// impl DeviceEvaluator<Point> for Kernel {
+///     type Element = f32;
+///
+///     fn pack(&self, solution: &Point, buffer: &mut Vec<f32>) {
+///         buffer.extend_from_slice(&solution.0);
+///     }
+///
+///     fn evaluate(&mut self, genomes: &[f32], count: usize) -> Vec<f64> {
+///         // upload `genomes`, dispatch a kernel with `count` invocations, and read back the results
+///         # genomes.chunks(genomes.len() / count).map(|g| -(g[0] * g[0] + g[1] * g[1]) as f64).collect()
+///     }
+/// }
+///
+/// let pop: Vec<Cached<Point>> = (0..64).map(|_| Cached::generate()).collect();
+/// device_evaluate(&pop, &mut Kernel);
+/// ```
+///
+/// [`.evaluate()`]: ./trait.DeviceEvaluator.html#tymethod.evaluate
+/// [`device_evaluate()`]: ./fn.device_evaluate.html
+/// [`Evolution::with_device_evaluator()`]: ../struct.Evolution.html#method.with_device_evaluator
+pub trait DeviceEvaluator<T: Solution> {
+    /// The type of the elements of the buffer that genomes are packed into, e.g. `f32`.
+    type Element: Copy;
+
+    /// Append the genome of `solution` to the end of `buffer`.
+    ///
+    /// Every solution must append the same number of elements,
+    /// so that the genomes can be told apart on the device.
+    fn pack(&self, solution: &T, buffer: &mut Vec<Self::Element>);
+
+    /// Evaluate `count` solutions whose genomes were packed one after another into `genomes`.
+    ///
+    /// Must return exactly one fitness value per solution, in the same order as the genomes.
+    fn evaluate(&mut self, genomes: &[Self::Element], count: usize) -> Vec<T::Fitness>;
+}
+
+/// Evaluate the fitness of every solution in a population that hasn't been evaluated yet
+/// in one batch, using a [`DeviceEvaluator`].
+///
+/// The fitness values are cached and counted by [`evaluation_count()`]
+/// just like they would be by [`par_evaluate()`].
+///
+/// # Panics
+/// Panics if the solutions weren't all packed into the same number of elements,
+/// or if the device returns a different number of fitness values than there were solutions.
+///
+/// [`DeviceEvaluator`]: ./trait.DeviceEvaluator.html
+/// [`evaluation_count()`]: ./fn.evaluation_count.html
+/// [`par_evaluate()`]: ./fn.par_evaluate.html
+pub fn device_evaluate<T, D>(pop: &[Cached<T>], device: &mut D)
+where
+    T: Solution,
+    D: DeviceEvaluator<T>,
+{
+    let pending: Vec<&Cached<T>> = pop.iter().filter(|ind| ind.fitness().is_none()).collect();
+    if pending.is_empty() {
+        return;
+    }
+
+    let mut genomes = Vec::new();
+    let mut genome_len = None;
+    for ind in &pending {
+        let start = genomes.len();
+        device.pack(ind.as_ref(), &mut genomes);
+        let len = genomes.len() - start;
+        assert_eq!(
+            *genome_len.get_or_insert(len),
+            len,
+            "every solution must be packed into the same number of elements"
+        );
+    }
+
+    let fitness = device.evaluate(&genomes, pending.len());
+    assert_eq!(
+        fitness.len(),
+        pending.len(),
+        "the device must return one fitness value per solution"
+    );
+    for (ind, fitness) in pending.into_iter().zip(fitness) {
+        let _ = ind.set_fitness(fitness);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "parallel")]
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::testutils::Walk;
+    #[cfg(feature = "parallel")]
+    use crate::utils::thread_pool;
+
+    #[cfg(feature = "parallel")]
+    static EVALUATED: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+    #[cfg(feature = "parallel")]
+    #[derive(Clone)]
+    struct Costly(u32);
+
+    #[cfg(feature = "parallel")]
+    impl Solution for Costly {
+        type Fitness = f64;
+
+        fn generate() -> Self {
+            unreachable!()
+        }
+
+        fn evaluate(&self) -> f64 {
+            EVALUATED.lock().unwrap().push(self.0);
+            self.0 as f64
+        }
+
+        fn crossover(_: &mut Self, _: &mut Self) {
+            unreachable!()
+        }
+
+        fn mutate(&mut self) {
+            self.0 += 10;
+        }
+
+        fn evaluation_cost(&self) -> Option<f64> {
+            (self.0 != 0).then_some(self.0 as f64)
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn costly_solutions_first() {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(1).build();
+        let _pool = thread_pool::enter(Some(Arc::new(pool.unwrap())));
+
+        let mut pop: Vec<_> = [2, 0, 5, 3].map(|n| Cached::new(Costly(n))).into();
+        pop[1].evaluate();
+        par_evaluate(&pop);
+        assert_eq!(*EVALUATED.lock().unwrap(), [0, 5, 3, 2]);
+
+        // offspring are estimated by their parent's timing if they have no estimate of their own
+        assert!(pop[1].evaluation_cost().is_some());
+        pop[1].mutate();
+        assert!(pop[1].evaluation_cost().is_some());
+    }
+
+    struct Negate {
+        batches: Vec<usize>,
+    }
+
+    impl DeviceEvaluator<Walk> for Negate {
+        type Element = f64;
+
+        fn pack(&self, solution: &Walk, buffer: &mut Vec<f64>) {
+            buffer.push(solution.0);
+        }
+
+        fn evaluate(&mut self, genomes: &[f64], count: usize) -> Vec<f64> {
+            self.batches.push(count);
+            genomes.iter().map(|x| -x.abs()).collect()
+        }
+    }
+
+    #[test]
+    fn device_fills_caches() {
+        let pop: Vec<_> = [1.0, -2.0, 3.0].map(|x| Cached::new(Walk(x))).into();
+        pop[1].evaluate();
+        let mut device = Negate {
+            batches: Vec::new(),
+        };
+        device_evaluate(&pop, &mut device);
+        device_evaluate(&pop, &mut device);
+        assert_eq!(device.batches, [2]);
+        for ind in &pop {
+            assert!(ind.fitness().is_some());
+            assert_eq!(ind.evaluate(), ind.as_ref().evaluate());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn identical_solutions_share_evaluations() {
+        use std::sync::atomic::AtomicUsize;
+
+        static EVALUATIONS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Clone, PartialEq, Eq, Hash)]
+        struct Small(u8);
+
+        impl Solution for Small {
+            type Fitness = f64;
+
+            fn generate() -> Self {
+                unreachable!()
+            }
+
+            fn evaluate(&self) -> f64 {
+                EVALUATIONS.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+                self.0 as f64
+            }
+
+            fn crossover(_: &mut Self, _: &mut Self) {
+                unreachable!()
+            }
+
+            fn mutate(&mut self) {
+                unreachable!()
+            }
+        }
+
+        let pop: Vec<_> = [1, 2, 1, 3, 2, 1, 3].map(|n| Cached::new(Small(n))).into();
+        pop[6].set_fitness(3.0).unwrap();
+        par_evaluate_dedup(&pop);
+        assert_eq!(EVALUATIONS.load(core::sync::atomic::Ordering::Relaxed), 2);
+        for ind in &pop {
+            assert_eq!(ind.fitness(), Some(ind.as_ref().0 as f64));
+        }
+    }
 }
@@ -0,0 +1,319 @@
+//! Ready-made genome types
+//!
+//! This module contains genome types for common problem representations,
+//! each of which comes with generation, crossover, and mutation operators that are guaranteed
+//! to keep it valid. All of them implement the [`Genome`] trait,
+//! which bundles those operators together with a configuration type that controls them.
+//!
+//! To optimize a genome, implement [`Problem`] on a type describing your problem,
+//! and use [`Individual`] as your solution type. [`Individual`] implements [`Solution`]
+//! for any [`Problem`], using the genome's operators for generation and variation
+//! and your problem's [`evaluate`] for evaluation.
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::genome::{permutation::*, Individual, Problem};
+//!
+//! // find the ordering of the numbers 0 to 9 with the smallest sum of differences between neighbors
+//! struct Ordering;
+//!
+//! impl Problem for Ordering {
+//!     type Genome = Permutation;
+//!     type Fitness = f64;
+//!
+//!     fn config() -> &'static PermutationConfig {
+//!         const CONFIG: PermutationConfig = PermutationConfig {
+//!             len: 10,
+//!             crossover: PermutationCrossover::Ordered,
+//!             mutation: PermutationMutation::Inversion,
+//!         };
+//!         &CONFIG
+//!     }
+//!
+//!     fn evaluate(perm: &Permutation) -> f64 {
+//!         -perm
+//!             .windows(2)
+//!             .map(|w| (w[0] as f64 - w[1] as f64).abs())
+//!             .sum::<f64>()
+//!     }
+//! }
+//!
+//! let evo: Evolution<Individual<Ordering>, _, _, ()> = Evolution::new(
+//!     alg::Simple::new(50, 0.5, 0.3, select::Tournament::new(3)),
+//!     hof::BestN::new(1),
+//! );
+//! let log = evo.run_for(10);
+//! assert_eq!(log.hall_of_fame[0].as_ref().len(), 10);
+//! ```
+//!
+//! [`Genome`]: ./trait.Genome.html
+//! [`Problem`]: ./trait.Problem.html
+//! [`Individual`]: ./struct.Individual.html
+//! [`Solution`]: ../trait.Solution.html
+//! [`evaluate`]: ./trait.Problem.html#tymethod.evaluate
+
+use std::{fmt::Debug, marker::PhantomData, ops::Deref};
+
+use crate::{fitness::Direction, Solution};
+
+pub mod bits;
+pub mod graph;
+pub mod int;
+pub mod permutation;
+pub mod real;
+
+mod slice;
+
+pub use bits::BitString;
+pub use graph::Graph;
+pub use int::IntVector;
+pub use permutation::Permutation;
+pub use real::RealVector;
+
+/// A genome type with built-in generation and variation operators
+///
+/// This trait is very similar to [`Solution`], except that every operation
+/// has access to a configuration value, and it has no notion of fitness.
+///
+/// [`Solution`]: ../trait.Solution.html
+pub trait Genome: Clone + Sync {
+    /// The type that controls how this genome is generated and varied,
+    /// e.g. its length and which operators to use.
+    type Config: Sync;
+
+    /// Randomly generate a new genome according to `config`.
+    fn generate(config: &Self::Config) -> Self;
+
+    /// Apply crossover between two genomes according to `config`.
+    fn crossover(a: &mut Self, b: &mut Self, config: &Self::Config);
+
+    /// Mutate the genome according to `config`.
+    fn mutate(&mut self, config: &Self::Config);
+}
+
+/// A genome configuration for vectors whose elements all share the same bounds
+///
+/// This is implemented by the configurations of the vector genomes in this module,
+/// and is what the `len` and `bounds` options of `#[derive(Solution)]` use to build a configuration.
+pub trait VectorConfig: Sized {
+    /// The bounds shared by every element, or `()` if the genome has none.
+    type Bounds;
+    /// The crossover operator type.
+    type Crossover;
+    /// The mutation operator type.
+    type Mutation;
+
+    /// Create a configuration for vectors of length `len`, where every element has the same bounds.
+    fn vector_config(
+        len: usize,
+        bounds: Self::Bounds,
+        crossover: Self::Crossover,
+        mutation: Self::Mutation,
+    ) -> Self;
+}
+
+impl VectorConfig for real::RealVectorConfig {
+    type Bounds = (f64, f64);
+    type Crossover = real::RealCrossover;
+    type Mutation = real::RealMutation;
+
+    fn vector_config(
+        len: usize,
+        bounds: (f64, f64),
+        crossover: real::RealCrossover,
+        mutation: real::RealMutation,
+    ) -> Self {
+        real::RealVectorConfig::uniform_bounds(len, bounds, crossover, mutation)
+    }
+}
+
+impl VectorConfig for int::IntVectorConfig {
+    type Bounds = (i64, i64);
+    type Crossover = int::IntCrossover;
+    type Mutation = int::IntMutation;
+
+    fn vector_config(
+        len: usize,
+        bounds: (i64, i64),
+        crossover: int::IntCrossover,
+        mutation: int::IntMutation,
+    ) -> Self {
+        int::IntVectorConfig {
+            ranges: vec![bounds; len].into(),
+            crossover,
+            mutation,
+        }
+    }
+}
+
+impl VectorConfig for permutation::PermutationConfig {
+    type Bounds = ();
+    type Crossover = permutation::PermutationCrossover;
+    type Mutation = permutation::PermutationMutation;
+
+    fn vector_config(
+        len: usize,
+        _: (),
+        crossover: permutation::PermutationCrossover,
+        mutation: permutation::PermutationMutation,
+    ) -> Self {
+        permutation::PermutationConfig {
+            len,
+            crossover,
+            mutation,
+        }
+    }
+}
+
+impl VectorConfig for bits::BitStringConfig {
+    type Bounds = ();
+    type Crossover = bits::BitCrossover;
+    type Mutation = bits::BitMutation;
+
+    fn vector_config(
+        len: usize,
+        _: (),
+        crossover: bits::BitCrossover,
+        mutation: bits::BitMutation,
+    ) -> Self {
+        bits::BitStringConfig {
+            len,
+            crossover,
+            mutation,
+        }
+    }
+}
+
+/// A problem that can be solved by evolving a [`Genome`]
+///
+/// [`Genome`]: ./trait.Genome.html
+pub trait Problem: 'static {
+    /// The genome that represents a solution to this problem.
+    type Genome: Genome;
+
+    /// The type that represents a solution's fitness. See [`Solution::Fitness`].
+    ///
+    /// [`Solution::Fitness`]: ../trait.Solution.html#associatedtype.Fitness
+    type Fitness: Copy + Send + Sync;
+
+    /// The direction in which each objective is optimized. See [`Solution::DIRECTIONS`].
+    ///
+    /// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+    const DIRECTIONS: &'static [Direction] = &[];
+
+    /// The tolerance for equal objective values. See [`Solution::DOMINANCE_TOLERANCE`].
+    ///
+    /// [`Solution::DOMINANCE_TOLERANCE`]: ../trait.Solution.html#associatedconstant.DOMINANCE_TOLERANCE
+    const DOMINANCE_TOLERANCE: f64 = 0.0;
+
+    /// Get the configuration used to generate and vary genomes.
+    ///
+    /// This usually returns a reference to a `const` or a lazily-initialized `static`.
+    fn config() -> &'static <Self::Genome as Genome>::Config;
+
+    /// Evaluate the fitness of a genome. See [`Solution::evaluate`].
+    ///
+    /// [`Solution::evaluate`]: ../trait.Solution.html#tymethod.evaluate
+    fn evaluate(genome: &Self::Genome) -> Self::Fitness;
+
+    /// Repair a genome that violates the problem's constraints. See [`Solution::repair`].
+    ///
+    /// The genome operators already keep genomes valid, so this is only needed
+    /// for constraints of the problem itself, like a capacity. The default does nothing.
+    ///
+    /// [`Solution::repair`]: ../trait.Solution.html#method.repair
+    fn repair(genome: &mut Self::Genome) {
+        let _ = genome;
+    }
+}
+
+/// A [`Solution`] made from a [`Problem`]'s genome
+///
+/// This type derefs to the genome it contains.
+///
+/// [`Solution`]: ../trait.Solution.html
+/// [`Problem`]: ./trait.Problem.html
+pub struct Individual<P: Problem> {
+    genome: P::Genome,
+    _phantom: PhantomData<fn() -> P>,
+}
+
+impl<P: Problem> Individual<P> {
+    /// Wrap an existing genome.
+    pub fn new(genome: P::Genome) -> Self {
+        Individual {
+            genome,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Consume the `Individual`, returning the genome it contained.
+    pub fn into_inner(self) -> P::Genome {
+        self.genome
+    }
+}
+
+impl<P: Problem> Solution for Individual<P> {
+    type Fitness = P::Fitness;
+    const DIRECTIONS: &'static [Direction] = P::DIRECTIONS;
+    const DOMINANCE_TOLERANCE: f64 = P::DOMINANCE_TOLERANCE;
+
+    fn generate() -> Self {
+        Individual::new(P::Genome::generate(P::config()))
+    }
+
+    fn evaluate(&self) -> Self::Fitness {
+        P::evaluate(&self.genome)
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        P::Genome::crossover(&mut a.genome, &mut b.genome, P::config());
+    }
+
+    fn mutate(&mut self) {
+        self.genome.mutate(P::config());
+    }
+
+    fn repair(&mut self) {
+        P::repair(&mut self.genome);
+    }
+}
+
+impl<P: Problem> Clone for Individual<P> {
+    fn clone(&self) -> Self {
+        Individual::new(self.genome.clone())
+    }
+}
+
+impl<P: Problem> Deref for Individual<P> {
+    type Target = P::Genome;
+    fn deref(&self) -> &Self::Target {
+        &self.genome
+    }
+}
+
+impl<P: Problem> AsRef<P::Genome> for Individual<P> {
+    fn as_ref(&self) -> &P::Genome {
+        &self.genome
+    }
+}
+
+impl<P> Debug for Individual<P>
+where
+    P: Problem,
+    P::Genome: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.genome.fmt(f)
+    }
+}
+
+impl<P> PartialEq for Individual<P>
+where
+    P: Problem,
+    P::Genome: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.genome == other.genome
+    }
+}
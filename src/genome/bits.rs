@@ -0,0 +1,238 @@
+//! Strings of bits
+//!
+//! This module contains [`BitString`] and a collection of crossover and mutation operators for it.
+//! Bit strings are the classic genetic algorithm representation, and the natural one for
+//! subset selection problems like the knapsack problem, where each bit says whether an item is included.
+//!
+//! [`BitString`]: ./struct.BitString.html
+
+use std::ops::{Deref, DerefMut};
+
+use rand::Rng;
+
+use crate::{
+    genome::{slice, Genome},
+    repro_rng::thread_rng,
+};
+
+/// A fixed-length string of bits
+///
+/// Every combination of bits is valid, so this type derefs both immutably and mutably
+/// to a slice of `bool`s.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitString(Vec<bool>);
+
+impl BitString {
+    /// Create a bit string from a `Vec`.
+    pub fn new(bits: Vec<bool>) -> Self {
+        BitString(bits)
+    }
+
+    /// Create a bit string of length `len` with every bit unset.
+    pub fn zeros(len: usize) -> Self {
+        BitString(vec![false; len])
+    }
+
+    /// Create a bit string of length `len` where each bit is set with probability 0.5.
+    pub fn random(len: usize) -> Self {
+        let mut rng = thread_rng();
+        BitString((0..len).map(|_| rng.gen()).collect())
+    }
+
+    /// Count how many bits are set.
+    pub fn count_ones(&self) -> usize {
+        self.0.iter().filter(|&&bit| bit).count()
+    }
+
+    /// Flip the bit at `idx`.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `idx` is out of bounds.
+    pub fn flip(&mut self, idx: usize) {
+        self.0[idx] = !self.0[idx];
+    }
+
+    /// Consume the bit string, returning the `Vec` it contained.
+    pub fn into_inner(self) -> Vec<bool> {
+        self.0
+    }
+}
+
+impl Deref for BitString {
+    type Target = [bool];
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for BitString {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl AsRef<[bool]> for BitString {
+    fn as_ref(&self) -> &[bool] {
+        &self.0
+    }
+}
+
+impl FromIterator<bool> for BitString {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        BitString(iter.into_iter().collect())
+    }
+}
+
+/// A crossover operator for bit strings, used in [`BitStringConfig`]
+///
+/// [`BitStringConfig`]: ./struct.BitStringConfig.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BitCrossover {
+    /// [`one_point`](./fn.one_point.html) crossover
+    OnePoint,
+    /// [`two_point`](./fn.two_point.html) crossover
+    TwoPoint,
+    /// [`uniform`](./fn.uniform.html) crossover
+    Uniform {
+        /// The probability of swapping each bit.
+        indpb: f64,
+    },
+}
+
+/// A mutation operator for bit strings, used in [`BitStringConfig`]
+///
+/// [`BitStringConfig`]: ./struct.BitStringConfig.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BitMutation {
+    /// [`flip_each`](./fn.flip_each.html) mutation
+    FlipEach {
+        /// The probability of flipping each bit.
+        indpb: f64,
+    },
+    /// [`flip_one`](./fn.flip_one.html) mutation
+    FlipOne,
+}
+
+/// Configuration for the [`Genome`] implementation of [`BitString`]
+///
+/// [`Genome`]: ../trait.Genome.html
+/// [`BitString`]: ./struct.BitString.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BitStringConfig {
+    /// The length of generated bit strings.
+    pub len: usize,
+    /// The crossover operator to use.
+    pub crossover: BitCrossover,
+    /// The mutation operator to use.
+    pub mutation: BitMutation,
+}
+
+impl Genome for BitString {
+    type Config = BitStringConfig;
+
+    fn generate(config: &Self::Config) -> Self {
+        BitString::random(config.len)
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self, config: &Self::Config) {
+        match config.crossover {
+            BitCrossover::OnePoint => one_point(a, b),
+            BitCrossover::TwoPoint => two_point(a, b),
+            BitCrossover::Uniform { indpb } => uniform(a, b, indpb),
+        }
+    }
+
+    fn mutate(&mut self, config: &Self::Config) {
+        match config.mutation {
+            BitMutation::FlipEach { indpb } => flip_each(self, indpb),
+            BitMutation::FlipOne => flip_one(self),
+        }
+    }
+}
+
+/// One-point crossover
+///
+/// Swaps every bit after a randomly chosen pivot.
+///
+/// Panics
+/// ======
+/// Panics if the bit strings have different lengths.
+pub fn one_point(a: &mut BitString, b: &mut BitString) {
+    assert_eq!(a.len(), b.len(), "bit strings must have the same length");
+    slice::one_point(&mut a.0, &mut b.0);
+}
+
+/// Two-point crossover
+///
+/// Swaps every bit between two randomly chosen pivots.
+///
+/// Panics
+/// ======
+/// Panics if the bit strings have different lengths.
+pub fn two_point(a: &mut BitString, b: &mut BitString) {
+    assert_eq!(a.len(), b.len(), "bit strings must have the same length");
+    slice::two_point(&mut a.0, &mut b.0);
+}
+
+/// Uniform crossover
+///
+/// Swaps each pair of bits with probability `indpb`.
+///
+/// Panics
+/// ======
+/// Panics if the bit strings have different lengths.
+pub fn uniform(a: &mut BitString, b: &mut BitString, indpb: f64) {
+    assert_eq!(a.len(), b.len(), "bit strings must have the same length");
+    slice::uniform(&mut a.0, &mut b.0, indpb);
+}
+
+/// Bit-flip mutation
+///
+/// Flips each bit with probability `indpb`. A common choice is `1 / len`.
+pub fn flip_each(bits: &mut BitString, indpb: f64) {
+    let mut rng = thread_rng();
+    for bit in bits.0.iter_mut() {
+        if rng.gen_bool(indpb) {
+            *bit = !*bit;
+        }
+    }
+}
+
+/// Single bit-flip mutation
+///
+/// Flips exactly one randomly chosen bit. Does nothing to an empty bit string.
+pub fn flip_one(bits: &mut BitString) {
+    if !bits.is_empty() {
+        let idx = thread_rng().gen_range(0..bits.len());
+        bits.flip(idx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators() {
+        for _ in 0..200 {
+            let mut a = BitString::random(20);
+            let mut b = BitString::random(20);
+            let (a0, b0) = (a.clone(), b.clone());
+
+            one_point(&mut a, &mut b);
+            two_point(&mut a, &mut b);
+            uniform(&mut a, &mut b, 0.5);
+            for i in 0..a.len() {
+                assert!((a[i] == a0[i] && b[i] == b0[i]) || (a[i] == b0[i] && b[i] == a0[i]));
+            }
+
+            let ones = a.count_ones();
+            flip_one(&mut a);
+            assert_eq!(ones.abs_diff(a.count_ones()), 1);
+            let before = b.clone();
+            flip_each(&mut b, 1.0);
+            assert!(b.iter().zip(before.iter()).all(|(x, y)| x != y));
+        }
+    }
+}
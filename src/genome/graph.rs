@@ -0,0 +1,550 @@
+//! Directed graphs with weighted edges
+//!
+//! This module contains [`Graph`], a genome representing a directed graph whose nodes carry
+//! user-defined payloads and whose edges carry weights, along with structural mutation operators
+//! and an alignment-based crossover. This is the foundation for evolving topologies such as
+//! neural networks, processing pipelines, and circuits, in the style of NEAT[^1].
+//!
+//! Node identities
+//! ---------------
+//! Every node has a [`NodeId`], and every edge is identified by the IDs of the nodes it connects.
+//! New nodes are always created by splitting an existing edge,
+//! and their IDs are assigned by a shared [`Innovations`] tracker,
+//! so that splitting the same edge in two different graphs produces a node with the same ID.
+//! This is what allows [`align`] to line up the corresponding parts of two graphs.
+//!
+//! [^1]: Stanley & Miikkulainen.
+//! "Evolving Neural Networks through Augmenting Topologies."
+//! 2002. <https://doi.org/10.1162/106365602320169811>
+//!
+//! [`Graph`]: ./struct.Graph.html
+//! [`NodeId`]: ./struct.NodeId.html
+//! [`Innovations`]: ./struct.Innovations.html
+//! [`align`]: ./fn.align.html
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use rand::{seq::IteratorRandom, Rng};
+use rand_distr::{Distribution, StandardNormal};
+
+use crate::{genome::Genome, repro_rng::thread_rng};
+
+/// The identity of a node in a [`Graph`]
+///
+/// [`Graph`]: ./struct.Graph.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(pub u64);
+
+#[derive(Clone, Debug, PartialEq)]
+struct NodeData<N> {
+    payload: N,
+    fixed: bool,
+}
+
+/// A directed graph genome
+///
+/// Nodes carry a payload of type `N`, and edges carry an `f64` weight.
+/// There is at most one edge in each direction between any two nodes, and no edges from a node to itself.
+///
+/// Nodes that the graph was created with are *fixed*, and are never removed by mutation.
+/// This makes them suitable for representing things like the inputs and outputs of a network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Graph<N> {
+    nodes: BTreeMap<NodeId, NodeData<N>>,
+    edges: BTreeMap<(NodeId, NodeId), f64>,
+}
+
+impl<N> Graph<N> {
+    /// Create a graph containing the fixed nodes `0..payloads.len()`, with the given payloads and no edges.
+    pub fn new(payloads: impl IntoIterator<Item = N>) -> Self {
+        Graph {
+            nodes: payloads
+                .into_iter()
+                .enumerate()
+                .map(|(i, payload)| {
+                    (
+                        NodeId(i as u64),
+                        NodeData {
+                            payload,
+                            fixed: true,
+                        },
+                    )
+                })
+                .collect(),
+            edges: BTreeMap::new(),
+        }
+    }
+
+    /// Get the number of nodes in the graph.
+    pub fn n_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Get the number of edges in the graph.
+    pub fn n_edges(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Iterate over the nodes in the graph and their payloads, in order of ID.
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &N)> {
+        self.nodes.iter().map(|(&id, data)| (id, &data.payload))
+    }
+
+    /// Iterate over the edges in the graph as `(from, to, weight)`, in order of `(from, to)`.
+    pub fn edges(&self) -> impl Iterator<Item = (NodeId, NodeId, f64)> + '_ {
+        self.edges.iter().map(|(&(from, to), &w)| (from, to, w))
+    }
+
+    /// Get the payload of a node, if it exists.
+    pub fn node(&self, id: NodeId) -> Option<&N> {
+        self.nodes.get(&id).map(|data| &data.payload)
+    }
+
+    /// Get a mutable reference to the payload of a node, if it exists.
+    pub fn node_mut(&mut self, id: NodeId) -> Option<&mut N> {
+        self.nodes.get_mut(&id).map(|data| &mut data.payload)
+    }
+
+    /// Get the weight of the edge from `from` to `to`, if it exists.
+    pub fn edge(&self, from: NodeId, to: NodeId) -> Option<f64> {
+        self.edges.get(&(from, to)).copied()
+    }
+
+    /// Check whether a node is fixed, i.e. was part of the graph when it was created.
+    pub fn is_fixed(&self, id: NodeId) -> bool {
+        self.nodes.get(&id).is_some_and(|data| data.fixed)
+    }
+
+    /// Insert an edge, or change its weight if it already exists.
+    ///
+    /// Returns `false` and does nothing if either node doesn't exist or `from == to`.
+    pub fn set_edge(&mut self, from: NodeId, to: NodeId, weight: f64) -> bool {
+        if from == to || !self.nodes.contains_key(&from) || !self.nodes.contains_key(&to) {
+            return false;
+        }
+        self.edges.insert((from, to), weight);
+        true
+    }
+
+    /// Remove an edge, returning its weight if it existed.
+    pub fn remove_edge(&mut self, from: NodeId, to: NodeId) -> Option<f64> {
+        self.edges.remove(&(from, to))
+    }
+
+    /// Check whether there is a path from `from` to `to` following edge directions.
+    pub fn has_path(&self, from: NodeId, to: NodeId) -> bool {
+        let mut stack = vec![from];
+        let mut seen = HashSet::new();
+        while let Some(node) = stack.pop() {
+            if node == to {
+                return true;
+            }
+            if seen.insert(node) {
+                stack.extend(self.successors(node));
+            }
+        }
+        false
+    }
+
+    /// Iterate over the nodes that `id` has an edge to.
+    pub fn successors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges
+            .range((id, NodeId(0))..=(id, NodeId(u64::MAX)))
+            .map(|(&(_, to), _)| to)
+    }
+
+    /// Get the nodes in an order where every edge points from an earlier node to a later one.
+    ///
+    /// Returns `None` if the graph contains a cycle.
+    pub fn topological_order(&self) -> Option<Vec<NodeId>> {
+        let mut in_degree: BTreeMap<NodeId, usize> = self.nodes.keys().map(|&id| (id, 0)).collect();
+        for &(_, to) in self.edges.keys() {
+            *in_degree.get_mut(&to).unwrap() += 1;
+        }
+        let mut ready: Vec<NodeId> = in_degree
+            .iter()
+            .filter(|(_, &deg)| deg == 0)
+            .map(|(&id, _)| id)
+            .rev()
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(id) = ready.pop() {
+            order.push(id);
+            for to in self.successors(id) {
+                let deg = in_degree.get_mut(&to).unwrap();
+                *deg -= 1;
+                if *deg == 0 {
+                    ready.push(to);
+                }
+            }
+        }
+        if order.len() == self.nodes.len() {
+            Some(order)
+        } else {
+            None
+        }
+    }
+}
+
+/// A shared record of which edges have been split, used to give new nodes consistent IDs
+///
+/// See the [module-level documentation](./index.html#node-identities) for more information.
+#[derive(Debug)]
+pub struct Innovations {
+    inner: Mutex<InnovationsInner>,
+}
+
+#[derive(Debug)]
+struct InnovationsInner {
+    next_id: u64,
+    splits: HashMap<(NodeId, NodeId), NodeId>,
+}
+
+impl Innovations {
+    /// Create a tracker that will assign new node IDs starting at `first_id`.
+    ///
+    /// `first_id` should be greater than the ID of every node the graphs were created with,
+    /// which is the number of fixed nodes for graphs created with [`Graph::new`].
+    ///
+    /// [`Graph::new`]: ./struct.Graph.html#method.new
+    pub fn new(first_id: u64) -> Self {
+        Innovations {
+            inner: Mutex::new(InnovationsInner {
+                next_id: first_id,
+                splits: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Get the ID of the node created by splitting the edge from `from` to `to`,
+    /// assigning a new one if that edge has never been split before.
+    pub fn split(&self, from: NodeId, to: NodeId) -> NodeId {
+        let mut inner = self.inner.lock().unwrap();
+        let InnovationsInner { next_id, splits } = &mut *inner;
+        *splits.entry((from, to)).or_insert_with(|| {
+            let id = NodeId(*next_id);
+            *next_id += 1;
+            id
+        })
+    }
+}
+
+/// Probabilities of applying each structural mutation, used in [`GraphConfig`]
+///
+/// [`GraphConfig`]: ./struct.GraphConfig.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GraphMutationRates {
+    /// Probability of applying [`add_node`](./fn.add_node.html).
+    pub add_node: f64,
+    /// Probability of applying [`remove_node`](./fn.remove_node.html).
+    pub remove_node: f64,
+    /// Probability of applying [`add_edge`](./fn.add_edge.html).
+    pub add_edge: f64,
+    /// Probability of applying [`remove_edge`](./fn.remove_edge.html).
+    pub remove_edge: f64,
+    /// Probability of applying [`perturb_weights`](./fn.perturb_weights.html).
+    pub perturb_weights: f64,
+}
+
+impl Default for GraphMutationRates {
+    fn default() -> Self {
+        GraphMutationRates {
+            add_node: 0.03,
+            remove_node: 0.01,
+            add_edge: 0.05,
+            remove_edge: 0.02,
+            perturb_weights: 0.8,
+        }
+    }
+}
+
+/// Configuration for the [`Genome`] implementation of [`Graph`]
+///
+/// [`Genome`]: ../trait.Genome.html
+/// [`Graph`]: ./struct.Graph.html
+pub struct GraphConfig<N> {
+    /// The number of fixed nodes every graph starts with.
+    pub initial_nodes: usize,
+    /// The probability of each possible edge between the initial nodes being present in a generated graph.
+    pub initial_edge_prob: f64,
+    /// The range initial and newly added edge weights are drawn uniformly from.
+    pub weight_range: (f64, f64),
+    /// The standard deviation of the noise added by [`perturb_weights`](./fn.perturb_weights.html).
+    pub weight_sigma: f64,
+    /// Whether mutation is allowed to introduce cycles.
+    pub allow_cycles: bool,
+    /// How likely each structural mutation is.
+    pub rates: GraphMutationRates,
+    /// Produces the payload for a node, given its ID.
+    pub payload: Arc<dyn Fn(NodeId) -> N + Send + Sync>,
+    /// The tracker used to assign IDs to new nodes.
+    pub innovations: Innovations,
+}
+
+impl<N> GraphConfig<N> {
+    /// Create a configuration for graphs with `initial_nodes` fixed nodes, with sensible defaults:
+    /// no initial edges, weights between -1 and 1, no cycles, and the default [`GraphMutationRates`].
+    ///
+    /// [`GraphMutationRates`]: ./struct.GraphMutationRates.html
+    pub fn new<F>(initial_nodes: usize, payload: F) -> Self
+    where
+        F: Fn(NodeId) -> N + Send + Sync + 'static,
+    {
+        GraphConfig {
+            initial_nodes,
+            initial_edge_prob: 0.0,
+            weight_range: (-1.0, 1.0),
+            weight_sigma: 0.1,
+            allow_cycles: false,
+            rates: Default::default(),
+            payload: Arc::new(payload),
+            innovations: Innovations::new(initial_nodes as u64),
+        }
+    }
+
+    fn random_weight(&self) -> f64 {
+        let (lo, hi) = self.weight_range;
+        if lo >= hi {
+            lo
+        } else {
+            thread_rng().gen_range(lo..hi)
+        }
+    }
+}
+
+impl<N> Genome for Graph<N>
+where
+    N: Clone + Sync,
+{
+    type Config = GraphConfig<N>;
+
+    fn generate(config: &Self::Config) -> Self {
+        let mut graph =
+            Graph::new((0..config.initial_nodes as u64).map(|i| (config.payload)(NodeId(i))));
+        let mut rng = thread_rng();
+        for from in 0..config.initial_nodes as u64 {
+            for to in 0..config.initial_nodes as u64 {
+                if from != to
+                    && rng.gen_bool(config.initial_edge_prob)
+                    && (config.allow_cycles || !graph.has_path(NodeId(to), NodeId(from)))
+                {
+                    graph.set_edge(NodeId(from), NodeId(to), config.random_weight());
+                }
+            }
+        }
+        graph
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self, _: &Self::Config) {
+        align(a, b);
+    }
+
+    fn mutate(&mut self, config: &Self::Config) {
+        let mut rng = thread_rng();
+        let rates = config.rates;
+        if rng.gen_bool(rates.add_node) {
+            add_node(self, &config.innovations, |id| (config.payload)(id));
+        }
+        if rng.gen_bool(rates.remove_node) {
+            remove_node(self);
+        }
+        if rng.gen_bool(rates.add_edge) {
+            add_edge(self, config.random_weight(), config.allow_cycles);
+        }
+        if rng.gen_bool(rates.remove_edge) {
+            remove_edge(self);
+        }
+        if rng.gen_bool(rates.perturb_weights) {
+            perturb_weights(self, config.weight_sigma, 1.0);
+        }
+    }
+}
+
+/// Alignment crossover
+///
+/// Lines up the nodes and edges that the two graphs have in common by their IDs,
+/// and swaps the payload of each matching node and the weight of each matching edge
+/// between the graphs with probability 0.5.
+/// Parts of the graphs that don't match stay where they are,
+/// so both offspring keep their own parent's topology.
+pub fn align<N>(a: &mut Graph<N>, b: &mut Graph<N>) {
+    let mut rng = thread_rng();
+    for (id, node_a) in a.nodes.iter_mut() {
+        if let Some(node_b) = b.nodes.get_mut(id) {
+            if rng.gen_bool(0.5) {
+                std::mem::swap(&mut node_a.payload, &mut node_b.payload);
+            }
+        }
+    }
+    for (key, weight_a) in a.edges.iter_mut() {
+        if let Some(weight_b) = b.edges.get_mut(key) {
+            if rng.gen_bool(0.5) {
+                std::mem::swap(weight_a, weight_b);
+            }
+        }
+    }
+}
+
+/// Add-node mutation
+///
+/// Splits a randomly chosen edge `from -> to` by inserting a new node in the middle of it.
+/// The new node's ID is assigned by `innovations`, and its payload is produced by calling `payload` with that ID.
+/// The edge `from -> new` gets a weight of 1, and `new -> to` keeps the original edge's weight,
+/// so the graph initially behaves much like it did before.
+///
+/// Does nothing if the graph has no edges, or if it already contains the node that the chosen edge splits into.
+pub fn add_node<N>(
+    graph: &mut Graph<N>,
+    innovations: &Innovations,
+    payload: impl FnOnce(NodeId) -> N,
+) {
+    let (from, to, weight) = match graph.edges().choose(&mut thread_rng()) {
+        Some(edge) => edge,
+        None => return,
+    };
+    let id = innovations.split(from, to);
+    if graph.nodes.contains_key(&id) {
+        return;
+    }
+    graph.edges.remove(&(from, to));
+    graph.nodes.insert(
+        id,
+        NodeData {
+            payload: payload(id),
+            fixed: false,
+        },
+    );
+    graph.edges.insert((from, id), 1.0);
+    graph.edges.insert((id, to), weight);
+}
+
+/// Remove-node mutation
+///
+/// Removes a randomly chosen node that isn't fixed, along with every edge connected to it.
+/// Does nothing if every node is fixed.
+pub fn remove_node<N>(graph: &mut Graph<N>) {
+    let chosen = graph
+        .nodes
+        .iter()
+        .filter(|(_, data)| !data.fixed)
+        .map(|(&id, _)| id)
+        .choose(&mut thread_rng());
+    if let Some(id) = chosen {
+        graph.nodes.remove(&id);
+        graph.edges.retain(|&(from, to), _| from != id && to != id);
+    }
+}
+
+/// Add-edge mutation
+///
+/// Adds an edge with the given weight between a randomly chosen pair of nodes that aren't already connected.
+/// If `allow_cycles` is `false`, only edges that wouldn't create a cycle are considered.
+/// Does nothing if no such pair exists.
+pub fn add_edge<N>(graph: &mut Graph<N>, weight: f64, allow_cycles: bool) {
+    let candidates: Vec<(NodeId, NodeId)> = graph
+        .nodes
+        .keys()
+        .flat_map(|&from| graph.nodes.keys().map(move |&to| (from, to)))
+        .filter(|&(from, to)| {
+            from != to
+                && !graph.edges.contains_key(&(from, to))
+                && (allow_cycles || !graph.has_path(to, from))
+        })
+        .collect();
+    if let Some(&(from, to)) = candidates.iter().choose(&mut thread_rng()) {
+        graph.edges.insert((from, to), weight);
+    }
+}
+
+/// Remove-edge mutation
+///
+/// Removes a randomly chosen edge. Does nothing if the graph has no edges.
+pub fn remove_edge<N>(graph: &mut Graph<N>) {
+    let chosen = graph.edges.keys().copied().choose(&mut thread_rng());
+    if let Some(key) = chosen {
+        graph.edges.remove(&key);
+    }
+}
+
+/// Weight perturbation mutation
+///
+/// Does a random roll of probability `indpb` for each edge.
+/// If the roll succeeds, that edge's weight has noise added to it,
+/// drawn from a normal distribution with mean 0 and standard deviation `sigma`.
+///
+/// Panics
+/// ======
+/// Panics if `sigma` is infinite, `NaN`, or negative.
+pub fn perturb_weights<N>(graph: &mut Graph<N>, sigma: f64, indpb: f64) {
+    assert!(
+        sigma.is_finite() && sigma >= 0.0,
+        "{:?} is not a valid standard deviation",
+        sigma
+    );
+    let mut rng = thread_rng();
+    for weight in graph.edges.values_mut() {
+        if rng.gen_bool(indpb) {
+            let noise: f64 = StandardNormal.sample(&mut rng);
+            *weight += sigma * noise;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structural_mutations() {
+        let innovations = Innovations::new(3);
+        let mut a: Graph<u8> = Graph::new([0, 1, 2]);
+        assert!(a.set_edge(NodeId(0), NodeId(2), 0.5));
+        assert!(!a.set_edge(NodeId(0), NodeId(0), 0.5));
+        let mut b = a.clone();
+
+        add_node(&mut a, &innovations, |_| 7);
+        add_node(&mut b, &innovations, |_| 9);
+        // both graphs split the same edge, so the new node has the same ID in both
+        assert_eq!(a.node(NodeId(3)), Some(&7));
+        assert_eq!(b.node(NodeId(3)), Some(&9));
+        assert_eq!(a.edge(NodeId(0), NodeId(3)), Some(1.0));
+        assert_eq!(a.edge(NodeId(3), NodeId(2)), Some(0.5));
+        assert_eq!(a.edge(NodeId(0), NodeId(2)), None);
+
+        for _ in 0..20 {
+            add_edge(&mut a, 1.0, false);
+        }
+        assert!(a.topological_order().is_some());
+
+        for _ in 0..10 {
+            align(&mut a, &mut b);
+            let payload = *a.node(NodeId(3)).unwrap();
+            assert!(payload == 7 || payload == 9);
+        }
+
+        remove_node(&mut a);
+        assert_eq!(a.n_nodes(), 3);
+        assert!(a.edges().all(|(from, to, _)| from.0 < 3 && to.0 < 3));
+        remove_node(&mut a);
+        assert_eq!(a.n_nodes(), 3);
+    }
+
+    #[test]
+    fn topological_order() {
+        let mut g: Graph<()> = Graph::new([(); 4]);
+        g.set_edge(NodeId(3), NodeId(1), 1.0);
+        g.set_edge(NodeId(1), NodeId(0), 1.0);
+        g.set_edge(NodeId(2), NodeId(0), 1.0);
+        let order = g.topological_order().unwrap();
+        let pos = |id: u64| order.iter().position(|&n| n == NodeId(id)).unwrap();
+        assert!(pos(3) < pos(1) && pos(1) < pos(0) && pos(2) < pos(0));
+
+        g.set_edge(NodeId(0), NodeId(3), 1.0);
+        assert!(g.topological_order().is_none());
+        assert!(g.has_path(NodeId(1), NodeId(3)));
+    }
+}
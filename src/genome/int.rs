@@ -0,0 +1,274 @@
+//! Bounded vectors of integers
+//!
+//! This module contains [`IntVector`] and a collection of crossover and mutation operators
+//! that always keep every element within its range.
+//! This is a good representation for integer parameters such as hyperparameters or resource counts.
+//!
+//! [`IntVector`]: ./struct.IntVector.html
+
+use std::{ops::Deref, sync::Arc};
+
+use rand::Rng;
+
+use crate::{
+    genome::{slice, Genome},
+    repro_rng::thread_rng,
+};
+
+/// A vector of integers, each with its own inclusive range
+///
+/// The ranges are shared between all vectors created from the same set of ranges,
+/// so cloning an `IntVector` only copies its values.
+/// It derefs to a slice of its values, and can only be modified in ways
+/// that keep every value within its range.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IntVector {
+    values: Vec<i64>,
+    ranges: Arc<[(i64, i64)]>,
+}
+
+impl IntVector {
+    /// Create a vector with the given values and ranges.
+    /// Each element of `ranges` is a pair of inclusive `(lowest, highest)` values.
+    ///
+    /// Returns `None` if `values` and `ranges` have different lengths,
+    /// or if any value is outside its range.
+    pub fn new(values: Vec<i64>, ranges: impl Into<Arc<[(i64, i64)]>>) -> Option<Self> {
+        let ranges = ranges.into();
+        if values.len() != ranges.len()
+            || values
+                .iter()
+                .zip(ranges.iter())
+                .any(|(x, (lo, hi))| !(lo..=hi).contains(&x))
+        {
+            return None;
+        }
+        Some(IntVector { values, ranges })
+    }
+
+    /// Create a vector where each value is drawn uniformly from its range.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if the lowest value of any range is greater than its highest value.
+    pub fn uniform(ranges: impl Into<Arc<[(i64, i64)]>>) -> Self {
+        let ranges = ranges.into();
+        let mut rng = thread_rng();
+        let values = ranges
+            .iter()
+            .map(|&(lo, hi)| {
+                assert!(lo <= hi, "({}, {}) is not a valid range", lo, hi);
+                rng.gen_range(lo..=hi)
+            })
+            .collect();
+        IntVector { values, ranges }
+    }
+
+    /// Get the range of each element.
+    pub fn ranges(&self) -> &[(i64, i64)] {
+        &self.ranges
+    }
+
+    /// Set the element at `idx` to `value`, clamped to that element's range.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `idx` is out of bounds.
+    pub fn set(&mut self, idx: usize, value: i64) {
+        let (lo, hi) = self.ranges[idx];
+        self.values[idx] = value.clamp(lo, hi);
+    }
+
+    /// Modify the values directly with a closure, then clamp each of them to its range.
+    pub fn modify(&mut self, f: impl FnOnce(&mut [i64])) {
+        f(&mut self.values);
+        for (x, &(lo, hi)) in self.values.iter_mut().zip(self.ranges.iter()) {
+            *x = (*x).clamp(lo, hi);
+        }
+    }
+
+    /// Consume the vector, returning its values.
+    pub fn into_inner(self) -> Vec<i64> {
+        self.values
+    }
+}
+
+impl Deref for IntVector {
+    type Target = [i64];
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl AsRef<[i64]> for IntVector {
+    fn as_ref(&self) -> &[i64] {
+        &self.values
+    }
+}
+
+/// A crossover operator for integer vectors, used in [`IntVectorConfig`]
+///
+/// [`IntVectorConfig`]: ./struct.IntVectorConfig.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntCrossover {
+    /// [`one_point`](./fn.one_point.html) crossover
+    OnePoint,
+    /// [`two_point`](./fn.two_point.html) crossover
+    TwoPoint,
+    /// [`uniform`](./fn.uniform.html) crossover
+    Uniform {
+        /// The probability of swapping each element.
+        indpb: f64,
+    },
+}
+
+/// A mutation operator for integer vectors, used in [`IntVectorConfig`]
+///
+/// [`IntVectorConfig`]: ./struct.IntVectorConfig.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IntMutation {
+    /// [`creep`](./fn.creep.html) mutation
+    Creep {
+        /// The largest amount an element can change by.
+        step: i64,
+        /// The probability of mutating each element.
+        indpb: f64,
+    },
+    /// [`reset`](./fn.reset.html) mutation
+    Reset {
+        /// The probability of mutating each element.
+        indpb: f64,
+    },
+}
+
+/// Configuration for the [`Genome`] implementation of [`IntVector`]
+///
+/// [`Genome`]: ../trait.Genome.html
+/// [`IntVector`]: ./struct.IntVector.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntVectorConfig {
+    /// The inclusive `(lowest, highest)` range of each element.
+    pub ranges: Arc<[(i64, i64)]>,
+    /// The crossover operator to use.
+    pub crossover: IntCrossover,
+    /// The mutation operator to use.
+    pub mutation: IntMutation,
+}
+
+impl Genome for IntVector {
+    type Config = IntVectorConfig;
+
+    fn generate(config: &Self::Config) -> Self {
+        IntVector::uniform(config.ranges.clone())
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self, config: &Self::Config) {
+        match config.crossover {
+            IntCrossover::OnePoint => one_point(a, b),
+            IntCrossover::TwoPoint => two_point(a, b),
+            IntCrossover::Uniform { indpb } => uniform(a, b, indpb),
+        }
+    }
+
+    fn mutate(&mut self, config: &Self::Config) {
+        match config.mutation {
+            IntMutation::Creep { step, indpb } => creep(self, step, indpb),
+            IntMutation::Reset { indpb } => reset(self, indpb),
+        }
+    }
+}
+
+/// One-point crossover
+///
+/// Swaps every element after a randomly chosen pivot.
+///
+/// Panics
+/// ======
+/// Panics if the vectors have different lengths.
+pub fn one_point(a: &mut IntVector, b: &mut IntVector) {
+    assert_eq!(a.len(), b.len(), "vectors must have the same length");
+    slice::one_point(&mut a.values, &mut b.values);
+}
+
+/// Two-point crossover
+///
+/// Swaps every element between two randomly chosen pivots.
+///
+/// Panics
+/// ======
+/// Panics if the vectors have different lengths.
+pub fn two_point(a: &mut IntVector, b: &mut IntVector) {
+    assert_eq!(a.len(), b.len(), "vectors must have the same length");
+    slice::two_point(&mut a.values, &mut b.values);
+}
+
+/// Uniform crossover
+///
+/// Swaps each pair of elements with probability `indpb`.
+///
+/// Panics
+/// ======
+/// Panics if the vectors have different lengths.
+pub fn uniform(a: &mut IntVector, b: &mut IntVector, indpb: f64) {
+    assert_eq!(a.len(), b.len(), "vectors must have the same length");
+    slice::uniform(&mut a.values, &mut b.values, indpb);
+}
+
+/// Creep mutation
+///
+/// Does a random roll of probability `indpb` for each element.
+/// If the roll succeeds, a random amount between `-step` and `step` is added to it,
+/// and the result is clamped to the element's range.
+///
+/// Panics
+/// ======
+/// Panics if `step` is negative.
+pub fn creep(vec: &mut IntVector, step: i64, indpb: f64) {
+    assert!(step >= 0, "step must not be negative");
+    let mut rng = thread_rng();
+    for (x, &(lo, hi)) in vec.values.iter_mut().zip(vec.ranges.iter()) {
+        if rng.gen_bool(indpb) {
+            *x = x.saturating_add(rng.gen_range(-step..=step)).clamp(lo, hi);
+        }
+    }
+}
+
+/// Random reset mutation
+///
+/// Does a random roll of probability `indpb` for each element.
+/// If the roll succeeds, it is replaced with a value drawn uniformly from its range.
+pub fn reset(vec: &mut IntVector, indpb: f64) {
+    let mut rng = thread_rng();
+    for (x, &(lo, hi)) in vec.values.iter_mut().zip(vec.ranges.iter()) {
+        if rng.gen_bool(indpb) {
+            *x = rng.gen_range(lo..=hi);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators_stay_in_range() {
+        let ranges: Arc<[(i64, i64)]> = vec![(0, 1), (-10, 10), (3, 3), (0, 1000)].into();
+        let in_range = |v: &IntVector| IntVector::new(v.values.clone(), ranges.clone()).is_some();
+        for _ in 0..200 {
+            let mut a = IntVector::uniform(ranges.clone());
+            let mut b = IntVector::uniform(ranges.clone());
+            let (a0, b0) = (a.clone(), b.clone());
+
+            one_point(&mut a, &mut b);
+            two_point(&mut a, &mut b);
+            uniform(&mut a, &mut b, 0.5);
+            for i in 0..a.len() {
+                assert!((a[i] == a0[i] && b[i] == b0[i]) || (a[i] == b0[i] && b[i] == a0[i]));
+            }
+
+            creep(&mut a, 5, 0.5);
+            reset(&mut b, 0.5);
+            assert!(in_range(&a) && in_range(&b));
+        }
+    }
+}
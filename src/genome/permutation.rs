@@ -0,0 +1,376 @@
+//! Permutations of the integers `0..n`
+//!
+//! This module contains [`Permutation`] and a collection of crossover and mutation operators
+//! that always produce valid permutations. Permutations are the natural representation
+//! for ordering problems like routing (e.g. the traveling salesman problem), scheduling, and assignment.
+//!
+//! [`Permutation`]: ./struct.Permutation.html
+
+use std::ops::Deref;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{genome::Genome, repro_rng::thread_rng};
+
+/// A permutation of the integers `0..n`
+///
+/// This type is a wrapper around a `Vec<usize>` that is guaranteed to contain
+/// every integer from `0` to `n - 1` exactly once.
+/// It derefs to a slice, so it can be read like one, but it can only be modified
+/// through the operators in this module, which always leave it valid.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Permutation(Vec<usize>);
+
+impl Permutation {
+    /// Create a permutation from a `Vec`.
+    ///
+    /// Returns `None` if `vec` doesn't contain every integer from `0` to `vec.len() - 1` exactly once.
+    pub fn new(vec: Vec<usize>) -> Option<Self> {
+        let mut seen = vec![false; vec.len()];
+        for &x in vec.iter() {
+            if x >= vec.len() || seen[x] {
+                return None;
+            }
+            seen[x] = true;
+        }
+        Some(Permutation(vec))
+    }
+
+    /// Create the identity permutation of length `n`, i.e. `[0, 1, ..., n - 1]`.
+    pub fn identity(n: usize) -> Self {
+        Permutation((0..n).collect())
+    }
+
+    /// Create a uniformly random permutation of length `n`.
+    pub fn random(n: usize) -> Self {
+        let mut perm = Permutation::identity(n);
+        perm.0.shuffle(&mut thread_rng());
+        perm
+    }
+
+    /// Consume the permutation, returning the `Vec` it contained.
+    pub fn into_inner(self) -> Vec<usize> {
+        self.0
+    }
+
+    /// Get the inverse permutation, i.e. the position of each integer in this permutation.
+    pub fn inverse(&self) -> Permutation {
+        let mut inv = vec![0; self.0.len()];
+        for (i, &x) in self.0.iter().enumerate() {
+            inv[x] = i;
+        }
+        Permutation(inv)
+    }
+}
+
+impl Deref for Permutation {
+    type Target = [usize];
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[usize]> for Permutation {
+    fn as_ref(&self) -> &[usize] {
+        &self.0
+    }
+}
+
+/// A crossover operator for permutations, used in [`PermutationConfig`]
+///
+/// [`PermutationConfig`]: ./struct.PermutationConfig.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PermutationCrossover {
+    /// [`ordered`](./fn.ordered.html) crossover
+    Ordered,
+    /// [`partially_mapped`](./fn.partially_mapped.html) crossover
+    PartiallyMapped,
+    /// [`cycle`](./fn.cycle.html) crossover
+    Cycle,
+}
+
+/// A mutation operator for permutations, used in [`PermutationConfig`]
+///
+/// [`PermutationConfig`]: ./struct.PermutationConfig.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PermutationMutation {
+    /// [`swap`](./fn.swap.html) mutation with the given probability for each element
+    Swap {
+        /// The probability of swapping each element.
+        indpb: f64,
+    },
+    /// [`inversion`](./fn.inversion.html) mutation
+    Inversion,
+    /// [`scramble`](./fn.scramble.html) mutation
+    Scramble,
+    /// [`insertion`](./fn.insertion.html) mutation
+    Insertion,
+}
+
+/// Configuration for the [`Genome`] implementation of [`Permutation`]
+///
+/// [`Genome`]: ../trait.Genome.html
+/// [`Permutation`]: ./struct.Permutation.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PermutationConfig {
+    /// The length of generated permutations.
+    pub len: usize,
+    /// The crossover operator to use.
+    pub crossover: PermutationCrossover,
+    /// The mutation operator to use.
+    pub mutation: PermutationMutation,
+}
+
+impl Genome for Permutation {
+    type Config = PermutationConfig;
+
+    fn generate(config: &Self::Config) -> Self {
+        Permutation::random(config.len)
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self, config: &Self::Config) {
+        match config.crossover {
+            PermutationCrossover::Ordered => ordered(a, b),
+            PermutationCrossover::PartiallyMapped => partially_mapped(a, b),
+            PermutationCrossover::Cycle => cycle(a, b),
+        }
+    }
+
+    fn mutate(&mut self, config: &Self::Config) {
+        match config.mutation {
+            PermutationMutation::Swap { indpb } => swap(self, indpb),
+            PermutationMutation::Inversion => inversion(self),
+            PermutationMutation::Scramble => scramble(self),
+            PermutationMutation::Insertion => insertion(self),
+        }
+    }
+}
+
+// Choose two cut points `i <= j` in `0..=len`.
+fn cut_points(len: usize) -> (usize, usize) {
+    let mut rng = thread_rng();
+    let i = rng.gen_range(0..=len);
+    let j = rng.gen_range(0..=len);
+    (i.min(j), i.max(j))
+}
+
+/// Ordered crossover (OX1)
+///
+/// Chooses a random slice of positions. Each offspring keeps its own parent's elements in that slice,
+/// and fills the rest of its positions with the missing elements in the order
+/// they appear in the other parent, starting after the slice and wrapping around.
+///
+/// Panics
+/// ======
+/// Panics if the permutations have different lengths.
+pub fn ordered(a: &mut Permutation, b: &mut Permutation) {
+    assert_eq!(a.len(), b.len(), "permutations must have the same length");
+    let len = a.len();
+    if len < 2 {
+        return;
+    }
+    let (start, end) = cut_points(len);
+
+    let fill = |keep: &[usize], from: &[usize]| -> Vec<usize> {
+        let mut kept = vec![false; len];
+        for &x in keep[start..end].iter() {
+            kept[x] = true;
+        }
+        let mut child = keep.to_vec();
+        let mut pos = end % len;
+        for k in 0..len {
+            let x = from[(end + k) % len];
+            if !kept[x] {
+                child[pos] = x;
+                pos = (pos + 1) % len;
+            }
+        }
+        child
+    };
+
+    let child_a = fill(&a.0, &b.0);
+    let child_b = fill(&b.0, &a.0);
+    a.0 = child_a;
+    b.0 = child_b;
+}
+
+/// Partially mapped crossover (PMX)
+///
+/// Chooses a random slice of positions and swaps that slice between the two parents.
+/// Elements outside the slice that would then be duplicated are replaced
+/// according to the mapping between the two swapped slices.
+///
+/// Panics
+/// ======
+/// Panics if the permutations have different lengths.
+pub fn partially_mapped(a: &mut Permutation, b: &mut Permutation) {
+    assert_eq!(a.len(), b.len(), "permutations must have the same length");
+    let (start, end) = cut_points(a.len());
+
+    let mut pos_a = a.inverse().0;
+    let mut pos_b = b.inverse().0;
+
+    for i in start..end {
+        let x = a.0[i];
+        let y = b.0[i];
+
+        // put y at position i in a, moving the x that was there to where y was
+        a.0.swap(i, pos_a[y]);
+        pos_a.swap(x, y);
+
+        // and vice versa in b
+        b.0.swap(i, pos_b[x]);
+        pos_b.swap(x, y);
+    }
+}
+
+/// Cycle crossover (CX)
+///
+/// Splits the positions into cycles, where each cycle is a set of positions
+/// that contain the same set of elements in both parents.
+/// Every other cycle is then swapped between the parents, so every element of
+/// each offspring stays in a position it had in one of the parents.
+///
+/// This operator is deterministic.
+///
+/// Panics
+/// ======
+/// Panics if the permutations have different lengths.
+pub fn cycle(a: &mut Permutation, b: &mut Permutation) {
+    assert_eq!(a.len(), b.len(), "permutations must have the same length");
+    let pos_a = a.inverse().0;
+    let mut cycle_of = vec![usize::MAX; a.len()];
+    let mut n_cycles: usize = 0;
+
+    for start in 0..a.len() {
+        if cycle_of[start] != usize::MAX {
+            continue;
+        }
+        let mut i = start;
+        while cycle_of[i] == usize::MAX {
+            cycle_of[i] = n_cycles;
+            i = pos_a[b.0[i]];
+        }
+        n_cycles += 1;
+    }
+
+    for (i, cycle) in cycle_of.into_iter().enumerate() {
+        if cycle % 2 == 1 {
+            std::mem::swap(&mut a.0[i], &mut b.0[i]);
+        }
+    }
+}
+
+/// Swap mutation
+///
+/// Does a random roll of probability `indpb` for each element.
+/// If the roll succeeds, that element is swapped with another randomly chosen element.
+pub fn swap(perm: &mut Permutation, indpb: f64) {
+    let mut rng = thread_rng();
+    let len = perm.len();
+    if len < 2 {
+        return;
+    }
+    for i in 0..len {
+        if rng.gen_bool(indpb) {
+            let mut j = rng.gen_range(0..len - 1);
+            if j >= i {
+                j += 1;
+            }
+            perm.0.swap(i, j);
+        }
+    }
+}
+
+/// Inversion mutation
+///
+/// Reverses the order of a randomly chosen slice.
+pub fn inversion(perm: &mut Permutation) {
+    let (start, end) = cut_points(perm.len());
+    perm.0[start..end].reverse();
+}
+
+/// Scramble mutation
+///
+/// Randomly shuffles a randomly chosen slice.
+pub fn scramble(perm: &mut Permutation) {
+    let (start, end) = cut_points(perm.len());
+    perm.0[start..end].shuffle(&mut thread_rng());
+}
+
+/// Insertion mutation
+///
+/// Removes a randomly chosen element and reinserts it at a random position.
+pub fn insertion(perm: &mut Permutation) {
+    if perm.is_empty() {
+        return;
+    }
+    let mut rng = thread_rng();
+    let from = rng.gen_range(0..perm.len());
+    let to = rng.gen_range(0..perm.len());
+    let x = perm.0.remove(from);
+    perm.0.insert(to, x);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_valid(perm: &Permutation) -> bool {
+        Permutation::new(perm.0.clone()).is_some()
+    }
+
+    #[test]
+    fn validation() {
+        assert!(Permutation::new(vec![2, 0, 1]).is_some());
+        assert!(Permutation::new(vec![2, 0, 0]).is_none());
+        assert!(Permutation::new(vec![3, 0, 1]).is_none());
+        assert_eq!(
+            Permutation::new(vec![2, 0, 1]).unwrap().inverse(),
+            Permutation::new(vec![1, 2, 0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn crossovers_stay_valid() {
+        let ops: [fn(&mut Permutation, &mut Permutation); 3] = [ordered, partially_mapped, cycle];
+        for op in ops {
+            for len in [0, 1, 2, 5, 20] {
+                for _ in 0..50 {
+                    let mut a = Permutation::random(len);
+                    let mut b = Permutation::random(len);
+                    op(&mut a, &mut b);
+                    assert!(is_valid(&a));
+                    assert!(is_valid(&b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cycle_keeps_positions() {
+        let a0 = Permutation::new(vec![0, 1, 2, 3, 4, 5, 6, 7]).unwrap();
+        let b0 = Permutation::new(vec![1, 0, 3, 2, 5, 4, 7, 6]).unwrap();
+        let (mut a, mut b) = (a0.clone(), b0.clone());
+        cycle(&mut a, &mut b);
+        for i in 0..8 {
+            assert!(a[i] == a0[i] || a[i] == b0[i]);
+            assert!(b[i] == a0[i] || b[i] == b0[i]);
+        }
+        assert_ne!(a, a0);
+    }
+
+    #[test]
+    fn mutations_stay_valid() {
+        for len in [0, 1, 2, 5, 20] {
+            for _ in 0..50 {
+                let mut perm = Permutation::random(len);
+                swap(&mut perm, 0.3);
+                inversion(&mut perm);
+                scramble(&mut perm);
+                insertion(&mut perm);
+                assert!(is_valid(&perm));
+            }
+        }
+    }
+}
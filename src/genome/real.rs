@@ -0,0 +1,386 @@
+//! Bounded vectors of real numbers
+//!
+//! This module contains [`RealVector`] and a collection of crossover and mutation operators
+//! that always keep every element within its bounds.
+//! This is the usual representation for continuous optimization problems.
+//!
+//! [`RealVector`]: ./struct.RealVector.html
+
+use std::{ops::Deref, sync::Arc};
+
+use rand::Rng;
+use rand_distr::{Distribution, StandardNormal};
+
+use crate::{genome::Genome, repro_rng::thread_rng};
+
+/// A vector of real numbers, each with its own lower and upper bound
+///
+/// The bounds are shared between all vectors created from the same set of bounds,
+/// so cloning a `RealVector` only copies its values.
+/// It derefs to a slice of its values, and can only be modified in ways
+/// that keep every value within its bounds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RealVector {
+    values: Vec<f64>,
+    bounds: Arc<[(f64, f64)]>,
+}
+
+impl RealVector {
+    /// Create a vector with the given values and bounds.
+    /// Each element of `bounds` is a pair of inclusive `(lower, upper)` bounds.
+    ///
+    /// Returns `None` if `values` and `bounds` have different lengths,
+    /// or if any value is outside its bounds.
+    pub fn new(values: Vec<f64>, bounds: impl Into<Arc<[(f64, f64)]>>) -> Option<Self> {
+        let bounds = bounds.into();
+        if values.len() != bounds.len()
+            || values
+                .iter()
+                .zip(bounds.iter())
+                .any(|(x, (lo, hi))| !(lo..=hi).contains(&x))
+        {
+            return None;
+        }
+        Some(RealVector { values, bounds })
+    }
+
+    /// Create a vector where each value is drawn uniformly from within its bounds.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if any lower bound is greater than its upper bound, or any bound is not finite.
+    pub fn uniform(bounds: impl Into<Arc<[(f64, f64)]>>) -> Self {
+        let bounds = bounds.into();
+        let mut rng = thread_rng();
+        let values = bounds
+            .iter()
+            .map(|&(lo, hi)| {
+                assert!(
+                    lo.is_finite() && hi.is_finite() && lo <= hi,
+                    "({}, {}) are not valid bounds",
+                    lo,
+                    hi
+                );
+                if lo == hi {
+                    lo
+                } else {
+                    rng.gen_range(lo..=hi)
+                }
+            })
+            .collect();
+        RealVector { values, bounds }
+    }
+
+    /// Get the bounds of each element.
+    pub fn bounds(&self) -> &[(f64, f64)] {
+        &self.bounds
+    }
+
+    /// Set the element at `idx` to `value`, clamped to that element's bounds.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `idx` is out of bounds.
+    pub fn set(&mut self, idx: usize, value: f64) {
+        let (lo, hi) = self.bounds[idx];
+        self.values[idx] = value.clamp(lo, hi);
+    }
+
+    /// Modify the values directly with a closure, then clamp each of them to its bounds.
+    pub fn modify(&mut self, f: impl FnOnce(&mut [f64])) {
+        f(&mut self.values);
+        self.clamp();
+    }
+
+    /// Consume the vector, returning its values.
+    pub fn into_inner(self) -> Vec<f64> {
+        self.values
+    }
+
+    fn clamp(&mut self) {
+        for (x, &(lo, hi)) in self.values.iter_mut().zip(self.bounds.iter()) {
+            *x = x.clamp(lo, hi);
+        }
+    }
+}
+
+impl Deref for RealVector {
+    type Target = [f64];
+    fn deref(&self) -> &Self::Target {
+        &self.values
+    }
+}
+
+impl AsRef<[f64]> for RealVector {
+    fn as_ref(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+/// A crossover operator for real vectors, used in [`RealVectorConfig`]
+///
+/// [`RealVectorConfig`]: ./struct.RealVectorConfig.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RealCrossover {
+    /// [`simulated_binary`](./fn.simulated_binary.html) crossover
+    SimulatedBinary {
+        /// The distribution index.
+        eta: f64,
+    },
+    /// [`blend`](./fn.blend.html) crossover
+    Blend {
+        /// The amount to extend the sampling interval on each side.
+        alpha: f64,
+    },
+}
+
+/// A mutation operator for real vectors, used in [`RealVectorConfig`]
+///
+/// [`RealVectorConfig`]: ./struct.RealVectorConfig.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RealMutation {
+    /// [`polynomial`](./fn.polynomial.html) mutation
+    Polynomial {
+        /// The distribution index.
+        eta: f64,
+        /// The probability of mutating each element.
+        indpb: f64,
+    },
+    /// [`gaussian`](./fn.gaussian.html) mutation
+    Gaussian {
+        /// The standard deviation, as a fraction of each element's range.
+        sigma: f64,
+        /// The probability of mutating each element.
+        indpb: f64,
+    },
+}
+
+/// Configuration for the [`Genome`] implementation of [`RealVector`]
+///
+/// [`Genome`]: ../trait.Genome.html
+/// [`RealVector`]: ./struct.RealVector.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct RealVectorConfig {
+    /// The `(lower, upper)` bounds of each element.
+    pub bounds: Arc<[(f64, f64)]>,
+    /// The crossover operator to use.
+    pub crossover: RealCrossover,
+    /// The mutation operator to use.
+    pub mutation: RealMutation,
+}
+
+impl RealVectorConfig {
+    /// Create a configuration where every one of `len` elements has the same bounds.
+    pub fn uniform_bounds(
+        len: usize,
+        bounds: (f64, f64),
+        crossover: RealCrossover,
+        mutation: RealMutation,
+    ) -> Self {
+        RealVectorConfig {
+            bounds: vec![bounds; len].into(),
+            crossover,
+            mutation,
+        }
+    }
+}
+
+impl Genome for RealVector {
+    type Config = RealVectorConfig;
+
+    fn generate(config: &Self::Config) -> Self {
+        RealVector::uniform(config.bounds.clone())
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self, config: &Self::Config) {
+        match config.crossover {
+            RealCrossover::SimulatedBinary { eta } => simulated_binary(a, b, eta),
+            RealCrossover::Blend { alpha } => blend(a, b, alpha),
+        }
+    }
+
+    fn mutate(&mut self, config: &Self::Config) {
+        match config.mutation {
+            RealMutation::Polynomial { eta, indpb } => polynomial(self, eta, indpb),
+            RealMutation::Gaussian { sigma, indpb } => gaussian(self, sigma, indpb),
+        }
+    }
+}
+
+/// Bounded simulated binary crossover (SBX)
+///
+/// Each pair of elements is crossed with a probability of 0.5, producing two offspring values
+/// whose spread around the parents mimics that of single-point crossover on binary strings[^1].
+/// Higher values of `eta` produce offspring closer to their parents.
+///
+/// Panics
+/// ======
+/// Panics if the vectors have different lengths.
+///
+/// [^1]: Deb & Agrawal.
+/// "Simulated Binary Crossover for Continuous Search Space."
+/// 1995. <https://www.complex-systems.com/abstracts/v09_i02_a02/>
+pub fn simulated_binary(a: &mut RealVector, b: &mut RealVector, eta: f64) {
+    assert_eq!(a.len(), b.len(), "vectors must have the same length");
+    let mut rng = thread_rng();
+    let exp = 1.0 / (eta + 1.0);
+
+    for i in 0..a.len() {
+        let (lo, hi) = a.bounds[i];
+        let (x1, x2) = (a.values[i].min(b.values[i]), a.values[i].max(b.values[i]));
+        if !rng.gen_bool(0.5) || x2 - x1 <= 1e-14 {
+            continue;
+        }
+
+        let spread = |beta: f64, u: f64| {
+            let alpha = 2.0 - beta.powf(-(eta + 1.0));
+            if u <= 1.0 / alpha {
+                (u * alpha).powf(exp)
+            } else {
+                (1.0 / (2.0 - u * alpha)).powf(exp)
+            }
+        };
+
+        let u: f64 = rng.gen();
+        let beta_q = spread(1.0 + 2.0 * (x1 - lo) / (x2 - x1), u);
+        let c1 = (0.5 * (x1 + x2 - beta_q * (x2 - x1))).clamp(lo, hi);
+        let beta_q = spread(1.0 + 2.0 * (hi - x2) / (x2 - x1), u);
+        let c2 = (0.5 * (x1 + x2 + beta_q * (x2 - x1))).clamp(lo, hi);
+
+        if rng.gen_bool(0.5) {
+            a.values[i] = c2;
+            b.values[i] = c1;
+        } else {
+            a.values[i] = c1;
+            b.values[i] = c2;
+        }
+    }
+}
+
+/// Blend crossover (BLX-α)
+///
+/// For each pair of elements `x1 <= x2`, both offspring values are drawn uniformly from
+/// `[x1 - alpha * (x2 - x1), x2 + alpha * (x2 - x1)]`, clamped to the element's bounds.
+///
+/// Panics
+/// ======
+/// Panics if the vectors have different lengths or `alpha` is negative.
+pub fn blend(a: &mut RealVector, b: &mut RealVector, alpha: f64) {
+    assert_eq!(a.len(), b.len(), "vectors must have the same length");
+    assert!(alpha >= 0.0, "alpha must not be negative");
+    let mut rng = thread_rng();
+
+    for i in 0..a.len() {
+        let (lo, hi) = a.bounds[i];
+        let (x1, x2) = (a.values[i].min(b.values[i]), a.values[i].max(b.values[i]));
+        let d = x2 - x1;
+        if d == 0.0 {
+            continue;
+        }
+        let low = x1 - alpha * d;
+        let high = x2 + alpha * d;
+        a.values[i] = rng.gen_range(low..=high).clamp(lo, hi);
+        b.values[i] = rng.gen_range(low..=high).clamp(lo, hi);
+    }
+}
+
+/// Bounded polynomial mutation
+///
+/// Does a random roll of probability `indpb` for each element.
+/// If the roll succeeds, that element is perturbed according to a polynomial distribution
+/// that never leaves its bounds[^1]. Higher values of `eta` produce smaller perturbations.
+///
+/// [^1]: Deb & Goyal.
+/// "A Combined Genetic Adaptive Search (GeneAS) for Engineering Design."
+/// 1996.
+pub fn polynomial(vec: &mut RealVector, eta: f64, indpb: f64) {
+    let mut rng = thread_rng();
+    let exp = 1.0 / (eta + 1.0);
+
+    for (x, &(lo, hi)) in vec.values.iter_mut().zip(vec.bounds.iter()) {
+        if !rng.gen_bool(indpb) || hi <= lo {
+            continue;
+        }
+        let range = hi - lo;
+        let delta_1 = (*x - lo) / range;
+        let delta_2 = (hi - *x) / range;
+        let u: f64 = rng.gen();
+
+        let delta_q = if u < 0.5 {
+            let val = 2.0 * u + (1.0 - 2.0 * u) * (1.0 - delta_1).powf(eta + 1.0);
+            val.powf(exp) - 1.0
+        } else {
+            let val = 2.0 * (1.0 - u) + 2.0 * (u - 0.5) * (1.0 - delta_2).powf(eta + 1.0);
+            1.0 - val.powf(exp)
+        };
+
+        *x = (*x + delta_q * range).clamp(lo, hi);
+    }
+}
+
+/// Bounded Gaussian mutation
+///
+/// Does a random roll of probability `indpb` for each element.
+/// If the roll succeeds, that element has noise added to it, drawn from a normal distribution
+/// with mean 0 and standard deviation `sigma` times the width of its bounds.
+/// The result is then clamped to the element's bounds.
+///
+/// Panics
+/// ======
+/// Panics if `sigma` is infinite, `NaN`, or negative.
+pub fn gaussian(vec: &mut RealVector, sigma: f64, indpb: f64) {
+    assert!(
+        sigma.is_finite() && sigma >= 0.0,
+        "{:?} is not a valid standard deviation",
+        sigma
+    );
+    let mut rng = thread_rng();
+
+    for (x, &(lo, hi)) in vec.values.iter_mut().zip(vec.bounds.iter()) {
+        if rng.gen_bool(indpb) {
+            let noise: f64 = StandardNormal.sample(&mut rng);
+            *x = (*x + sigma * (hi - lo) * noise).clamp(lo, hi);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn in_bounds(vec: &RealVector) -> bool {
+        RealVector::new(vec.values.clone(), vec.bounds.clone()).is_some()
+    }
+
+    #[test]
+    fn operators_stay_in_bounds() {
+        let bounds: Arc<[(f64, f64)]> =
+            vec![(-1.0, 1.0), (0.0, 10.0), (5.0, 5.0), (-3.0, -2.0)].into();
+        for _ in 0..200 {
+            let mut a = RealVector::uniform(bounds.clone());
+            let mut b = RealVector::uniform(bounds.clone());
+            assert!(in_bounds(&a));
+
+            simulated_binary(&mut a, &mut b, 2.0);
+            assert!(in_bounds(&a) && in_bounds(&b));
+            blend(&mut a, &mut b, 0.5);
+            assert!(in_bounds(&a) && in_bounds(&b));
+            polynomial(&mut a, 20.0, 0.5);
+            gaussian(&mut b, 0.5, 0.5);
+            assert!(in_bounds(&a) && in_bounds(&b));
+        }
+    }
+
+    #[test]
+    fn validation() {
+        assert!(RealVector::new(vec![0.5], vec![(0.0, 1.0)]).is_some());
+        assert!(RealVector::new(vec![1.5], vec![(0.0, 1.0)]).is_none());
+        assert!(RealVector::new(vec![0.5, 0.5], vec![(0.0, 1.0)]).is_none());
+
+        let mut v = RealVector::new(vec![0.5], vec![(0.0, 1.0)]).unwrap();
+        v.set(0, 3.0);
+        assert_eq!(v[0], 1.0);
+        v.modify(|vals| vals[0] = -3.0);
+        assert_eq!(v[0], 0.0);
+    }
+}
@@ -0,0 +1,42 @@
+// Crossover operators shared by genomes that are plain sequences of values.
+
+use rand::Rng;
+
+use crate::repro_rng::thread_rng;
+
+// Swap every element after a random pivot.
+pub fn one_point<T>(a: &mut [T], b: &mut [T]) {
+    debug_assert_eq!(a.len(), b.len());
+    if a.len() < 2 {
+        return;
+    }
+    let pivot = thread_rng().gen_range(1..a.len());
+    a[pivot..].swap_with_slice(&mut b[pivot..]);
+}
+
+// Swap every element between two distinct random pivots.
+pub fn two_point<T>(a: &mut [T], b: &mut [T]) {
+    debug_assert_eq!(a.len(), b.len());
+    if a.len() < 3 {
+        return one_point(a, b);
+    }
+    let mut rng = thread_rng();
+    let i = rng.gen_range(1..a.len());
+    let mut j = rng.gen_range(1..a.len() - 1);
+    if j >= i {
+        j += 1;
+    }
+    let (start, end) = (i.min(j), i.max(j));
+    a[start..end].swap_with_slice(&mut b[start..end]);
+}
+
+// Swap each element with probability `indpb`.
+pub fn uniform<T>(a: &mut [T], b: &mut [T], indpb: f64) {
+    debug_assert_eq!(a.len(), b.len());
+    let mut rng = thread_rng();
+    for (x, y) in a.iter_mut().zip(b.iter_mut()) {
+        if rng.gen_bool(indpb) {
+            std::mem::swap(x, y);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Genetic programming with expression trees
+//!
+//! This module contains [`Tree`], a genome representing a program as an expression tree,
+//! along with everything needed to evolve it: the [`PrimitiveSet`] describing the
+//! functions and terminals a tree can be built from, generation methods
+//! (including ramped half-and-half), and subtree crossover, subtree mutation, and point mutation.
+//!
+//! Trees don't implement [`Solution`] on their own, since they need a [`PrimitiveSet`] to do anything.
+//! The usual pattern is to store the primitive set in a `static` and wrap [`Tree`] in your own type:
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::gp::{self, Limits, PrimitiveSet, Tree};
+//!
+//! lazy_static::lazy_static! {
+//!     static ref PSET: PrimitiveSet<f64> = {
+//!         let mut pset = PrimitiveSet::new(1);
+//!         pset.add_primitive("add", 2, |args| args[0] + args[1]);
+//!         pset.add_primitive("mul", 2, |args| args[0] * args[1]);
+//!         pset.add_terminal("1", 1.0);
+//!         pset
+//!     };
+//! }
+//!
+//! const LIMITS: Limits = Limits { max_depth: 17, max_size: usize::MAX };
+//!
+//! #[derive(Clone)]
+//! struct Program(Tree<f64>);
+//!
+//! impl Solution for Program {
+//!     type Fitness = f64;
+//!
+//!     fn generate() -> Self {
+//!         Program(Tree::ramped_half_and_half(&PSET, 1, 3))
+//!     }
+//!
+//!     fn evaluate(&self) -> f64 {
+//!         // try to find x² + x + 1
+//!         -(0..10)
+//!             .map(|x| x as f64)
+//!             .map(|x| (self.0.evaluate(&PSET, &[x]) - (x * x + x + 1.0)).abs())
+//!             .sum::<f64>()
+//!     }
+//!
+//!     fn crossover(a: &mut Self, b: &mut Self) {
+//!         gp::subtree_crossover(&PSET, &mut a.0, &mut b.0, &LIMITS);
+//!     }
+//!
+//!     fn mutate(&mut self) {
+//!         gp::subtree_mutation(&PSET, &mut self.0, 0, 2, &LIMITS);
+//!     }
+//! }
+//! # let _ = Program::generate().evaluate();
+//! ```
+//!
+//! Cartesian genetic programming, which represents programs as grids of nodes
+//! instead of trees, is available in the [`cgp`] submodule.
+//! Ready-made tools for symbolic regression are available in the [`symreg`] submodule.
+//! Grammatical evolution, which maps integer genomes to programs through a BNF grammar,
+//! is available in the [`grammatical`] submodule.
+//!
+//! Typed trees
+//! -----------
+//! Every primitive, terminal, and argument in a [`PrimitiveSet`] has a type tag,
+//! which is `()` for untyped sets created with [`PrimitiveSet::new`].
+//! Sets created with [`PrimitiveSet::typed`] can use any `Copy + Eq` type as a tag,
+//! and all generation and variation operators will only ever connect a node to
+//! an argument slot with the same tag. This is also known as strongly-typed genetic programming.
+//!
+//! [`Solution`]: ../trait.Solution.html
+//! [`cgp`]: ./cgp/index.html
+//! [`symreg`]: ./symreg/index.html
+//! [`grammatical`]: ./grammatical/index.html
+//! [`Tree`]: ./struct.Tree.html
+//! [`PrimitiveSet`]: ./struct.PrimitiveSet.html
+//! [`PrimitiveSet::new`]: ./struct.PrimitiveSet.html#method.new
+//! [`PrimitiveSet::typed`]: ./struct.PrimitiveSet.html#method.typed
+
+pub mod cgp;
+pub mod grammatical;
+pub mod symreg;
+
+pub(crate) mod ops;
+pub(crate) mod primitives;
+pub(crate) mod tree;
+
+pub use ops::{point_mutation, subtree_crossover, subtree_mutation, Limits};
+pub use primitives::PrimitiveSet;
+pub use tree::{Node, Tree};
@@ -0,0 +1,363 @@
+//! Cartesian genetic programming
+//!
+//! This module contains [`Cgp`], a genome that represents a program as a grid of function nodes,
+//! encoded as a fixed-length vector of integers[^1]. Each node has one gene choosing its function
+//! and one gene for each of its inputs, which point at either a program input or a node in an
+//! earlier column. Finally, one gene for each program output points at the node that produces it.
+//!
+//! Nodes that no output depends on are *inactive*. They don't affect the program's behavior,
+//! but they can still be changed by mutation, which lets the population drift
+//! through neutral variations of the same program. To take advantage of this, CGP is usually
+//! evolved with mutation alone, using the (1 + λ) algorithm in [`alg::OnePlusLambda`],
+//! which always replaces the parent with an offspring that is at least as good.
+//!
+//! The functions available to the nodes come from the primitives of a [`PrimitiveSet`].
+//! The program inputs are the set's arguments, followed by its terminals as constant inputs.
+//! Ephemeral constants are not used.
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::genome::{Individual, Problem};
+//! use eviolite::gp::{cgp::{Cgp, CgpConfig}, PrimitiveSet};
+//!
+//! lazy_static::lazy_static! {
+//!     static ref CONFIG: CgpConfig<f64> = {
+//!         let mut pset = PrimitiveSet::new(1);
+//!         pset.add_primitive("add", 2, |args| args[0] + args[1]);
+//!         pset.add_primitive("mul", 2, |args| args[0] * args[1]);
+//!         pset.add_terminal("1", 1.0);
+//!         CgpConfig::new(pset, 1, 10, 1)
+//!     };
+//! }
+//!
+//! // try to find x² + x + 1
+//! struct Quadratic;
+//!
+//! impl Problem for Quadratic {
+//!     type Genome = Cgp<f64>;
+//!     type Fitness = f64;
+//!
+//!     fn config() -> &'static CgpConfig<f64> {
+//!         &CONFIG
+//!     }
+//!
+//!     fn evaluate(cgp: &Cgp<f64>) -> f64 {
+//!         -(0..10)
+//!             .map(|x| x as f64)
+//!             .map(|x| (cgp.evaluate(&CONFIG, &[x])[0] - (x * x + x + 1.0)).abs())
+//!             .sum::<f64>()
+//!     }
+//! }
+//!
+//! let evo: Evolution<Individual<Quadratic>, _, _, ()> = Evolution::new(
+//!     alg::OnePlusLambda::new(4),
+//!     hof::BestN::new(1),
+//! );
+//! let log = evo.run_for(20);
+//! assert_eq!(log.final_population.len(), 1);
+//! ```
+//!
+//! [^1]: Miller & Thomson.
+//! "Cartesian Genetic Programming."
+//! 2000. <https://doi.org/10.1007/978-3-540-46239-2_9>
+//!
+//! [`Cgp`]: ./struct.Cgp.html
+//! [`alg::OnePlusLambda`]: ../../alg/struct.OnePlusLambda.html
+//! [`PrimitiveSet`]: ../struct.PrimitiveSet.html
+
+use std::{fmt::Debug, marker::PhantomData};
+
+use rand::Rng;
+
+use crate::{genome::Genome, gp::PrimitiveSet, repro_rng::thread_rng};
+
+/// The shape of a CGP grid and the functions its nodes can use
+///
+/// This is the [`Genome::Config`] for [`Cgp`].
+///
+/// [`Genome::Config`]: ../../genome/trait.Genome.html#associatedtype.Config
+/// [`Cgp`]: ./struct.Cgp.html
+pub struct CgpConfig<V> {
+    /// The functions and inputs available to the program.
+    pub pset: PrimitiveSet<V>,
+    /// The number of nodes in each column.
+    pub rows: usize,
+    /// The number of columns.
+    pub cols: usize,
+    /// How many columns back a node can take its inputs from.
+    pub levels_back: usize,
+    /// The number of values the program outputs.
+    pub n_outputs: usize,
+    /// The probability of each gene being changed by [`point_mutation`](./fn.point_mutation.html).
+    pub mutation_rate: f64,
+}
+
+impl<V> CgpConfig<V> {
+    /// Create a configuration for a grid of `rows` by `cols` nodes with `n_outputs` outputs.
+    /// A single row is the most common layout.
+    ///
+    /// Nodes can connect to any earlier column, and the mutation rate is set so that
+    /// on average one node's worth of genes is changed per mutation.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `pset` has no primitives, or if it has no arguments and no terminals.
+    pub fn new(pset: PrimitiveSet<V>, rows: usize, cols: usize, n_outputs: usize) -> Self {
+        assert!(
+            !pset.primitives.is_empty(),
+            "primitive set must contain at least one primitive"
+        );
+        assert!(
+            !pset.arguments.is_empty() || !pset.terminals.is_empty(),
+            "primitive set must contain at least one argument or terminal"
+        );
+        let mut config = CgpConfig {
+            pset,
+            rows,
+            cols,
+            levels_back: cols,
+            n_outputs,
+            mutation_rate: 0.0,
+        };
+        config.mutation_rate = (config.max_arity() + 1) as f64 / config.n_genes().max(1) as f64;
+        config
+    }
+
+    /// Get the number of inputs to the program, which is its arguments plus its constants.
+    pub fn n_inputs(&self) -> usize {
+        self.pset.arguments.len() + self.pset.terminals.len()
+    }
+
+    /// Get the number of function nodes in the grid.
+    pub fn n_nodes(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    /// Get the total number of genes in a genome with this configuration.
+    pub fn n_genes(&self) -> usize {
+        self.n_nodes() * (self.max_arity() + 1) + self.n_outputs
+    }
+
+    fn max_arity(&self) -> usize {
+        self.pset
+            .primitives
+            .iter()
+            .map(|p| p.args.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Get a random value for the gene at `idx`.
+    fn random_gene(&self, idx: usize) -> usize {
+        let mut rng = thread_rng();
+        let node_len = self.max_arity() + 1;
+        let node = idx / node_len;
+        if node >= self.n_nodes() {
+            // output gene, which can point at any input or node
+            return rng.gen_range(0..self.n_inputs() + self.n_nodes());
+        }
+        if idx.is_multiple_of(node_len) {
+            return rng.gen_range(0..self.pset.primitives.len());
+        }
+        let col = node / self.rows;
+        let first_col = col.saturating_sub(self.levels_back);
+        let n_inputs = self.n_inputs();
+        let n_choices = n_inputs + (col - first_col) * self.rows;
+        let choice = rng.gen_range(0..n_choices);
+        if choice < n_inputs {
+            choice
+        } else {
+            n_inputs + first_col * self.rows + (choice - n_inputs)
+        }
+    }
+}
+
+/// A program represented as a grid of function nodes
+///
+/// Addresses used by the connection and output genes count the program inputs first,
+/// so address `i` is input `i` if `i < n_inputs`, and node `i - n_inputs` otherwise.
+/// See the [module-level documentation](./index.html) for more information.
+pub struct Cgp<V> {
+    genes: Vec<usize>,
+    _phantom: PhantomData<fn() -> V>,
+}
+
+impl<V> Debug for Cgp<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Cgp").field(&self.genes).finish()
+    }
+}
+
+impl<V> PartialEq for Cgp<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.genes == other.genes
+    }
+}
+
+impl<V> Eq for Cgp<V> {}
+
+impl<V> Clone for Cgp<V> {
+    fn clone(&self) -> Self {
+        Cgp {
+            genes: self.genes.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<V> Cgp<V> {
+    /// Create a random genome with the shape given by `config`.
+    pub fn random(config: &CgpConfig<V>) -> Self {
+        Cgp {
+            genes: (0..config.n_genes())
+                .map(|i| config.random_gene(i))
+                .collect(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get the genes of this genome.
+    pub fn genes(&self) -> &[usize] {
+        &self.genes
+    }
+
+    /// Find which nodes any of the outputs depend on.
+    ///
+    /// Returns one `bool` for each node in the grid.
+    pub fn active_nodes(&self, config: &CgpConfig<V>) -> Vec<bool> {
+        let n_inputs = config.n_inputs();
+        let node_len = config.max_arity() + 1;
+        let mut active = vec![false; config.n_nodes()];
+        let mut stack: Vec<usize> = self.genes[config.n_nodes() * node_len..].to_vec();
+        while let Some(addr) = stack.pop() {
+            if addr < n_inputs || active[addr - n_inputs] {
+                continue;
+            }
+            let node = addr - n_inputs;
+            active[node] = true;
+            let start = node * node_len;
+            let arity = config.pset.primitives[self.genes[start]].args.len();
+            stack.extend_from_slice(&self.genes[start + 1..start + 1 + arity]);
+        }
+        active
+    }
+
+    /// Run the program on `args`, returning one value for each output.
+    ///
+    /// Only active nodes are evaluated.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if the length of `args` doesn't match the number of arguments in the primitive set.
+    pub fn evaluate(&self, config: &CgpConfig<V>, args: &[V]) -> Vec<V>
+    where
+        V: Clone,
+    {
+        assert_eq!(
+            args.len(),
+            config.pset.arguments.len(),
+            "wrong number of arguments"
+        );
+        let node_len = config.max_arity() + 1;
+        let mut values: Vec<Option<V>> = args
+            .iter()
+            .cloned()
+            .chain(config.pset.terminals.iter().map(|t| t.value.clone()))
+            .map(Some)
+            .collect();
+        let mut inputs = Vec::with_capacity(node_len);
+        for (node, active) in self.active_nodes(config).into_iter().enumerate() {
+            if !active {
+                values.push(None);
+                continue;
+            }
+            let start = node * node_len;
+            let prim = &config.pset.primitives[self.genes[start]];
+            inputs.clear();
+            inputs.extend(
+                self.genes[start + 1..start + 1 + prim.args.len()]
+                    .iter()
+                    .map(|&addr| values[addr].clone().unwrap()),
+            );
+            values.push(Some((prim.func)(&inputs)));
+        }
+        self.genes[config.n_nodes() * node_len..]
+            .iter()
+            .map(|&addr| values[addr].clone().unwrap())
+            .collect()
+    }
+}
+
+impl<V> Genome for Cgp<V>
+where
+    V: Sync + 'static,
+{
+    type Config = CgpConfig<V>;
+
+    fn generate(config: &Self::Config) -> Self {
+        Cgp::random(config)
+    }
+
+    /// CGP doesn't use crossover, so this does nothing.
+    fn crossover(_: &mut Self, _: &mut Self, _: &Self::Config) {}
+
+    fn mutate(&mut self, config: &Self::Config) {
+        point_mutation(config, self, config.mutation_rate);
+    }
+}
+
+/// Point mutation
+///
+/// Does a random roll of probability `indpb` for each gene.
+/// If the roll succeeds, that gene is replaced with a random valid value.
+pub fn point_mutation<V>(config: &CgpConfig<V>, cgp: &mut Cgp<V>, indpb: f64) {
+    let mut rng = thread_rng();
+    for i in 0..cgp.genes.len() {
+        if rng.gen_bool(indpb) {
+            cgp.genes[i] = config.random_gene(i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluate_and_mutate() {
+        let mut pset: PrimitiveSet<i64> = PrimitiveSet::new(2);
+        pset.add_primitive("add", 2, |args| args[0] + args[1]);
+        pset.add_primitive("neg", 1, |args| -args[0]);
+        let mut config = CgpConfig::new(pset, 2, 3, 1);
+        config.levels_back = 1;
+
+        // inputs are addresses 0-1, and the nodes are addresses 2-7, laid out in columns of 2
+        // node 0 (addr 2): add(x, y); node 2 (addr 4): neg(node 0); output: node 2
+        let mut genes = vec![0; config.n_genes()];
+        genes[0..3].copy_from_slice(&[0, 0, 1]);
+        genes[6..9].copy_from_slice(&[1, 2, 2]);
+        genes[18] = 4;
+        let cgp: Cgp<i64> = Cgp {
+            genes,
+            _phantom: PhantomData,
+        };
+        assert_eq!(cgp.evaluate(&config, &[3, 4]), vec![-7]);
+        assert_eq!(
+            cgp.active_nodes(&config),
+            vec![true, false, true, false, false, false]
+        );
+
+        for _ in 0..100 {
+            let mut cgp = Cgp::random(&config);
+            point_mutation(&config, &mut cgp, 0.5);
+            // connections only ever point at inputs or the previous column
+            for node in 0..config.n_nodes() {
+                let col = node / config.rows;
+                for &addr in &cgp.genes[node * 3 + 1..node * 3 + 3] {
+                    assert!(addr < 2 || (addr - 2) / config.rows + 1 == col);
+                }
+            }
+            cgp.evaluate(&config, &[1, 2]);
+        }
+    }
+}
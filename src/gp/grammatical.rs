@@ -0,0 +1,692 @@
+//! Grammatical evolution
+//!
+//! This module contains [`Codons`], a genome that represents a program as a string of integers,
+//! which are mapped to a program's source text through a [`Grammar`] written in BNF[^1].
+//! Starting from the first rule of the grammar, the leftmost nonterminal is repeatedly replaced
+//! with one of its productions, chosen by the next codon modulo the number of productions.
+//! Rules with a single production don't use up a codon. If the codons run out before the program
+//! is complete, the mapping wraps around to the first codon again, up to a configurable
+//! number of times, after which the genome is invalid and has no phenotype.
+//!
+//! Since the phenotype is just text, evaluating it is up to you, e.g. by interpreting it,
+//! or by matching it against examples.
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::genome::{Individual, Problem};
+//! use eviolite::gp::grammatical::{Codons, GeConfig, Grammar};
+//!
+//! lazy_static::lazy_static! {
+//!     static ref CONFIG: GeConfig = GeConfig::new(
+//!         Grammar::parse(
+//!             "<bits> ::= <bit> | <bit><bits>
+//!              <bit> ::= 0 | 1",
+//!         )
+//!         .unwrap(),
+//!     );
+//! }
+//!
+//! // try to find 10101010
+//! struct Alternating;
+//!
+//! impl Problem for Alternating {
+//!     type Genome = Codons;
+//!     type Fitness = f64;
+//!
+//!     fn config() -> &'static GeConfig {
+//!         &CONFIG
+//!     }
+//!
+//!     fn evaluate(codons: &Codons) -> f64 {
+//!         match codons.phenotype(&CONFIG) {
+//!             Some(bits) => {
+//!                 let wrong = bits.chars().zip("10101010".chars()).filter(|(a, b)| a != b).count();
+//!                 -((wrong + bits.len().abs_diff(8)) as f64)
+//!             }
+//!             None => -100.0,
+//!         }
+//!     }
+//! }
+//!
+//! let evo: Evolution<Individual<Alternating>, _, _, ()> = Evolution::new(
+//!     alg::Simple::new(50, 0.7, 0.3, select::Tournament::new(3)),
+//!     hof::BestN::new(1),
+//! );
+//! let log = evo.run_for(20);
+//! assert!(log.hall_of_fame[0].as_ref().phenotype(&CONFIG).is_some());
+//! ```
+//!
+//! Grammars
+//! --------
+//! Each rule has the form `<name> ::= production | production | ...`, and a rule can be continued on
+//! the following lines, which usually start with `|`. Within a production, `<name>` is a nonterminal,
+//! text in double or single quotes is copied as it is, which is how to write `<`, `>`, `|`, or
+//! leading and trailing spaces, and any other text is copied as it is, including the spaces between symbols,
+//! but not the whitespace at the start and end of the production.
+//! Lines starting with `#` are comments.
+//!
+//! [^1]: O'Neill & Ryan.
+//! "Grammatical Evolution."
+//! 2001. <https://doi.org/10.1109/4235.942529>
+//!
+//! [`Codons`]: ./struct.Codons.html
+//! [`Grammar`]: ./struct.Grammar.html
+
+use std::{collections::HashMap, fmt::Display};
+
+use rand::Rng;
+
+use crate::{genome::Genome, repro_rng::thread_rng};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Symbol {
+    Terminal(String),
+    NonTerminal(usize),
+}
+
+#[derive(Clone, Debug)]
+struct Production {
+    symbols: Vec<Symbol>,
+    // the depth of the shallowest derivation tree rooted at this production
+    min_depth: usize,
+    // whether any of its nonterminals can derive themselves
+    recursive: bool,
+}
+
+// The line number, text, and whether it starts the rule, of each piece of a rule's body
+type Pieces<'a> = Vec<(usize, &'a str, bool)>;
+
+#[derive(Clone, Debug)]
+struct Rule {
+    name: String,
+    productions: Vec<Production>,
+}
+
+/// A context-free grammar in BNF, which maps [`Codons`] to programs
+///
+/// See the [module-level documentation](./index.html) for the syntax.
+///
+/// [`Codons`]: ./struct.Codons.html
+#[derive(Clone, Debug)]
+pub struct Grammar {
+    rules: Vec<Rule>,
+}
+
+/// An error in the text of a [`Grammar`]
+///
+/// [`Grammar`]: ./struct.Grammar.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GrammarError {
+    /// The grammar has no rules.
+    Empty,
+    /// A line that isn't a comment or the continuation of a rule doesn't start with `<name> ::=`.
+    Syntax {
+        /// The line number, starting from 1.
+        line: usize,
+    },
+    /// A `<` or a quote isn't closed on the same line.
+    Unclosed {
+        /// The line number, starting from 1.
+        line: usize,
+    },
+    /// A nonterminal is used but has no rule.
+    Undefined(String),
+    /// A nonterminal can't derive any program that's complete, e.g. because all of its productions
+    /// refer back to it.
+    NonTerminating(String),
+}
+
+impl Display for GrammarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrammarError::Empty => write!(f, "grammar has no rules"),
+            GrammarError::Syntax { line } => {
+                write!(f, "line {} must start with `<name> ::=` or `|`", line)
+            }
+            GrammarError::Unclosed { line } => write!(f, "unclosed `<` or quote on line {}", line),
+            GrammarError::Undefined(name) => write!(f, "<{}> is used but has no rule", name),
+            GrammarError::NonTerminating(name) => {
+                write!(f, "<{}> can't derive a complete program", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GrammarError {}
+
+/// The result of mapping [`Codons`] through a [`Grammar`]
+///
+/// [`Codons`]: ./struct.Codons.html
+/// [`Grammar`]: ./struct.Grammar.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Derivation {
+    /// The program.
+    pub phenotype: String,
+    /// How many codons were used to choose productions, counting each one again every time it was wrapped around to.
+    pub used_codons: usize,
+}
+
+impl Grammar {
+    /// Parse a grammar written in BNF. The first rule is the start symbol.
+    ///
+    /// Rules with the same name are merged, as if all the productions were written in the first one.
+    pub fn parse(src: &str) -> Result<Self, GrammarError> {
+        // the text of each rule's productions, with the line each piece came from
+        // and whether it's the start of a rule
+        let mut bodies: Vec<(String, Pieces)> = Vec::new();
+        for (i, line) in src.lines().enumerate() {
+            let line_no = i + 1;
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            match trimmed.split_once("::=") {
+                Some((head, body))
+                    if head.trim().starts_with('<') && head.trim().ends_with('>') =>
+                {
+                    let head = head.trim();
+                    let name = head[1..head.len() - 1].to_owned();
+                    match bodies.iter_mut().find(|(existing, _)| *existing == name) {
+                        Some((_, pieces)) => pieces.push((line_no, body, true)),
+                        None => bodies.push((name, vec![(line_no, body, true)])),
+                    }
+                }
+                _ => match bodies.last_mut() {
+                    Some((_, pieces)) => pieces.push((line_no, trimmed, false)),
+                    None => return Err(GrammarError::Syntax { line: line_no }),
+                },
+            }
+        }
+        if bodies.is_empty() {
+            return Err(GrammarError::Empty);
+        }
+
+        let names: HashMap<&str, usize> = bodies
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| (name.as_str(), i))
+            .collect();
+        let mut rules = Vec::with_capacity(bodies.len());
+        for (name, pieces) in &bodies {
+            let mut productions: Vec<Vec<Symbol>> = Vec::new();
+            for &(line, piece, start) in pieces {
+                let piece = piece.trim();
+                // a continuation that starts with `|` adds to the productions, any other one extends the last
+                let (piece, new) = match piece.strip_prefix('|') {
+                    Some(rest) if !start => (rest, true),
+                    _ => (piece, start),
+                };
+                for (n, text) in split_productions(piece, line)?.into_iter().enumerate() {
+                    let symbols = tokenize(text, line, &names)?;
+                    match productions.last_mut() {
+                        Some(last) if n == 0 && !new => last.extend(symbols),
+                        _ => productions.push(symbols),
+                    }
+                }
+            }
+            rules.push(Rule {
+                name: name.clone(),
+                productions: productions
+                    .into_iter()
+                    .map(|symbols| Production {
+                        symbols,
+                        min_depth: usize::MAX,
+                        recursive: false,
+                    })
+                    .collect(),
+            });
+        }
+
+        let mut grammar = Grammar { rules };
+        grammar.analyze()?;
+        Ok(grammar)
+    }
+
+    // Fill in the minimum depth and recursiveness of every production
+    fn analyze(&mut self) -> Result<(), GrammarError> {
+        let n = self.rules.len();
+        let mut depths = vec![usize::MAX; n];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for r in 0..n {
+                for p in 0..self.rules[r].productions.len() {
+                    let depth = self.rules[r].productions[p]
+                        .nonterminals()
+                        .map(|nt| depths[nt])
+                        .max()
+                        .unwrap_or(0)
+                        .saturating_add(1);
+                    self.rules[r].productions[p].min_depth = depth;
+                    if depth < depths[r] {
+                        depths[r] = depth;
+                        changed = true;
+                    }
+                }
+            }
+        }
+        if let Some(r) = depths.iter().position(|&depth| depth == usize::MAX) {
+            return Err(GrammarError::NonTerminating(self.rules[r].name.clone()));
+        }
+
+        // a rule is recursive if it can be reached from its own productions
+        let recursive: Vec<bool> = (0..n)
+            .map(|start| {
+                let mut seen = vec![false; n];
+                let mut stack: Vec<usize> = self.rules[start]
+                    .productions
+                    .iter()
+                    .flat_map(Production::nonterminals)
+                    .collect();
+                while let Some(r) = stack.pop() {
+                    if r == start {
+                        return true;
+                    }
+                    if !seen[r] {
+                        seen[r] = true;
+                        stack.extend(
+                            self.rules[r]
+                                .productions
+                                .iter()
+                                .flat_map(Production::nonterminals),
+                        );
+                    }
+                }
+                false
+            })
+            .collect();
+        for rule in &mut self.rules {
+            for production in &mut rule.productions {
+                let reaches_recursive = production.nonterminals().any(|nt| recursive[nt]);
+                production.recursive = reaches_recursive;
+            }
+        }
+        Ok(())
+    }
+
+    /// Map `codons` to a program, wrapping around to the first codon at most `max_wraps` times.
+    ///
+    /// Returns `None` if the program still isn't complete after that, so the genome is invalid.
+    pub fn derive(&self, codons: &[u32], max_wraps: usize) -> Option<Derivation> {
+        let mut phenotype = String::new();
+        let mut stack = vec![Symbol::NonTerminal(0)];
+        let mut next = 0;
+        let mut used = 0;
+        while let Some(symbol) = stack.pop() {
+            let rule = match symbol {
+                Symbol::Terminal(text) => {
+                    phenotype.push_str(&text);
+                    continue;
+                }
+                Symbol::NonTerminal(r) => &self.rules[r],
+            };
+            let choice = if rule.productions.len() == 1 {
+                0
+            } else {
+                if next == codons.len() {
+                    if codons.is_empty() || used / codons.len() > max_wraps {
+                        return None;
+                    }
+                    next = 0;
+                }
+                if used >= codons.len() * (max_wraps + 1) {
+                    return None;
+                }
+                let codon = codons[next];
+                next += 1;
+                used += 1;
+                codon as usize % rule.productions.len()
+            };
+            stack.extend(rule.productions[choice].symbols.iter().rev().cloned());
+        }
+        Some(Derivation {
+            phenotype,
+            used_codons: used,
+        })
+    }
+
+    // Append codons that derive a random program from the rule `r` in at most `depth` levels,
+    // preferring recursive productions if `full`
+    fn sensible(&self, r: usize, depth: usize, full: bool, codon_size: u32, codons: &mut Vec<u32>) {
+        let mut rng = thread_rng();
+        let productions = &self.rules[r].productions;
+        let fitting = |p: &&Production| p.min_depth <= depth;
+        let shallowest = productions.iter().map(|p| p.min_depth).min().unwrap();
+        let mut candidates: Vec<usize> = (0..productions.len())
+            .filter(|&p| fitting(&&productions[p]) || productions[p].min_depth == shallowest)
+            .collect();
+        if full && candidates.iter().any(|&p| productions[p].recursive) {
+            candidates.retain(|&p| productions[p].recursive);
+        }
+        let choice = candidates[rng.gen_range(0..candidates.len())];
+
+        if productions.len() > 1 {
+            // any codon that picks the same production works, so pick one of them at random
+            let n = productions.len() as u32;
+            let copies = (codon_size.max(n) - 1 - choice as u32) / n;
+            codons.push(choice as u32 + n * rng.gen_range(0..=copies));
+        }
+        for nt in productions[choice].nonterminals() {
+            self.sensible(nt, depth.saturating_sub(1), full, codon_size, codons);
+        }
+    }
+}
+
+impl Production {
+    fn nonterminals(&self) -> impl Iterator<Item = usize> + '_ {
+        self.symbols.iter().filter_map(|symbol| match symbol {
+            Symbol::NonTerminal(nt) => Some(*nt),
+            Symbol::Terminal(_) => None,
+        })
+    }
+}
+
+// Split a rule body at every `|` that isn't inside `<>` or quotes
+fn split_productions(body: &str, line: usize) -> Result<Vec<&str>, GrammarError> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut close = None;
+    for (i, c) in body.char_indices() {
+        match (close, c) {
+            (Some(end), _) if c == end => close = None,
+            (Some(_), _) => {}
+            (None, '<') => close = Some('>'),
+            (None, '"' | '\'') => close = Some(c),
+            (None, '|') => {
+                pieces.push(&body[start..i]);
+                start = i + 1;
+            }
+            (None, _) => {}
+        }
+    }
+    if close.is_some() {
+        return Err(GrammarError::Unclosed { line });
+    }
+    pieces.push(&body[start..]);
+    Ok(pieces)
+}
+
+fn tokenize(
+    production: &str,
+    line: usize,
+    names: &HashMap<&str, usize>,
+) -> Result<Vec<Symbol>, GrammarError> {
+    let mut symbols = Vec::new();
+    let mut rest = production.trim();
+    while !rest.is_empty() {
+        let (symbol, len) = match rest.chars().next().unwrap() {
+            '<' => {
+                let end = rest.find('>').ok_or(GrammarError::Unclosed { line })?;
+                let name = &rest[1..end];
+                let nt = *names
+                    .get(name)
+                    .ok_or_else(|| GrammarError::Undefined(name.to_owned()))?;
+                (Symbol::NonTerminal(nt), end + 1)
+            }
+            quote @ ('"' | '\'') => {
+                let end = rest[1..]
+                    .find(quote)
+                    .ok_or(GrammarError::Unclosed { line })?;
+                (Symbol::Terminal(rest[1..end + 1].to_owned()), end + 2)
+            }
+            _ => {
+                let end = rest.find(['<', '"', '\'']).unwrap_or(rest.len());
+                let text = &rest[..end];
+                // whitespace at the edges of the production isn't part of the text
+                let text = if end == rest.len() {
+                    text.trim_end()
+                } else {
+                    text
+                };
+                (Symbol::Terminal(text.to_owned()), end)
+            }
+        };
+        symbols.push(symbol);
+        rest = &rest[len..];
+    }
+    Ok(symbols)
+}
+
+/// How [`Codons`] are generated
+///
+/// [`Codons`]: ./struct.Codons.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Initialization {
+    /// Uniformly random codons, with a length chosen uniformly from `min_len..=max_len`.
+    /// Many of the genomes will be invalid or map to tiny programs.
+    Random {
+        /// The shortest genome length.
+        min_len: usize,
+        /// The longest genome length.
+        max_len: usize,
+    },
+    /// Sensible initialization, the equivalent of ramped half-and-half for grammars.
+    /// A derivation tree is grown with a depth limit chosen uniformly up to `max_depth`,
+    /// either using recursive productions wherever possible or choosing any production that fits,
+    /// with equal chance, and encoded as codons. A tail of random codons half as long is added,
+    /// so crossover and mutation have some unused material to work with.
+    /// Every generated genome is valid.
+    Sensible {
+        /// The deepest derivation tree to generate.
+        max_depth: usize,
+    },
+}
+
+/// The grammar and settings used to generate and vary [`Codons`]
+///
+/// This is the [`Genome::Config`] for [`Codons`].
+///
+/// [`Codons`]: ./struct.Codons.html
+/// [`Genome::Config`]: ../../genome/trait.Genome.html#associatedtype.Config
+#[derive(Clone, Debug)]
+pub struct GeConfig {
+    /// The grammar that maps codons to programs.
+    pub grammar: Grammar,
+    /// How many times mapping can wrap around to the first codon.
+    pub max_wraps: usize,
+    /// Codons are generated between 0 and `codon_size - 1`.
+    pub codon_size: u32,
+    /// How genomes are generated.
+    pub initialization: Initialization,
+    /// Genomes that grow longer than this are truncated by crossover.
+    pub max_len: usize,
+    /// The probability of each used codon being replaced by [`int_flip`](./fn.int_flip.html) in mutation,
+    /// or `None` to change one codon on average.
+    pub mutation_rate: Option<f64>,
+}
+
+impl GeConfig {
+    /// Create a configuration for `grammar`, with 8-bit codons, up to 2 wraps,
+    /// sensible initialization up to a depth of 6, a maximum length of 1000,
+    /// and mutations that change one used codon on average.
+    pub fn new(grammar: Grammar) -> Self {
+        GeConfig {
+            grammar,
+            max_wraps: 2,
+            codon_size: 256,
+            initialization: Initialization::Sensible { max_depth: 6 },
+            max_len: 1000,
+            mutation_rate: None,
+        }
+    }
+
+    // The number of codons that variation should act on
+    fn effective_len(&self, codons: &Codons) -> usize {
+        self.grammar
+            .derive(&codons.0, self.max_wraps)
+            .map_or(codons.0.len(), |derivation| {
+                derivation.used_codons.min(codons.0.len())
+            })
+    }
+}
+
+/// A program represented as a string of integer codons
+///
+/// See the [module-level documentation](./index.html) for more information.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Codons(Vec<u32>);
+
+impl Codons {
+    /// Wrap a string of codons.
+    pub fn new(codons: Vec<u32>) -> Self {
+        Codons(codons)
+    }
+
+    /// Get the codons of this genome.
+    pub fn codons(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// Map the codons to a program with the grammar in `config`,
+    /// or return `None` if the genome is invalid.
+    pub fn derive(&self, config: &GeConfig) -> Option<Derivation> {
+        config.grammar.derive(&self.0, config.max_wraps)
+    }
+
+    /// Map the codons to the text of a program with the grammar in `config`,
+    /// or return `None` if the genome is invalid.
+    pub fn phenotype(&self, config: &GeConfig) -> Option<String> {
+        self.derive(config).map(|derivation| derivation.phenotype)
+    }
+}
+
+impl Genome for Codons {
+    type Config = GeConfig;
+
+    fn generate(config: &Self::Config) -> Self {
+        let mut rng = thread_rng();
+        match config.initialization {
+            Initialization::Random { min_len, max_len } => {
+                let len = rng.gen_range(min_len..=max_len.max(min_len));
+                Codons(
+                    (0..len)
+                        .map(|_| rng.gen_range(0..config.codon_size))
+                        .collect(),
+                )
+            }
+            Initialization::Sensible { max_depth } => {
+                let shallowest = config.grammar.rules[0]
+                    .productions
+                    .iter()
+                    .map(|p| p.min_depth)
+                    .min()
+                    .unwrap();
+                let depth = rng.gen_range(shallowest..=max_depth.max(shallowest));
+                let full = rng.gen_bool(0.5);
+                let mut codons = Vec::new();
+                config
+                    .grammar
+                    .sensible(0, depth, full, config.codon_size, &mut codons);
+                let tail = codons.len() / 2;
+                codons.extend((0..tail).map(|_| rng.gen_range(0..config.codon_size)));
+                Codons(codons)
+            }
+        }
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self, config: &Self::Config) {
+        effective_crossover(config, a, b);
+    }
+
+    fn mutate(&mut self, config: &Self::Config) {
+        let rate = config
+            .mutation_rate
+            .unwrap_or_else(|| 1.0 / config.effective_len(self).max(1) as f64);
+        int_flip(config, self, rate);
+    }
+}
+
+/// Effective one-point crossover
+///
+/// Picks a crossover point in the used part of each genome, which can be at different positions,
+/// and swaps everything after the points. Crossing over in the unused tail would make no difference
+/// to either program. Children longer than [`GeConfig::max_len`] are truncated.
+///
+/// [`GeConfig::max_len`]: ./struct.GeConfig.html#structfield.max_len
+pub fn effective_crossover(config: &GeConfig, a: &mut Codons, b: &mut Codons) {
+    let mut rng = thread_rng();
+    let point_a = rng.gen_range(0..=config.effective_len(a));
+    let point_b = rng.gen_range(0..=config.effective_len(b));
+    let tail_a = a.0.split_off(point_a);
+    let tail_b = b.0.split_off(point_b);
+    a.0.extend(tail_b);
+    b.0.extend(tail_a);
+    a.0.truncate(config.max_len);
+    b.0.truncate(config.max_len);
+}
+
+/// Integer flip mutation
+///
+/// Does a random roll of probability `indpb` for each codon in the used part of the genome.
+/// If the roll succeeds, that codon is replaced with a random one.
+pub fn int_flip(config: &GeConfig, codons: &mut Codons, indpb: f64) {
+    let mut rng = thread_rng();
+    for i in 0..config.effective_len(codons) {
+        if rng.gen_bool(indpb) {
+            codons.0[i] = rng.gen_range(0..config.codon_size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPR: &str = "
+        # arithmetic on x
+        <e> ::= <e> <o> <e> | \"(\" <e> \")\"
+              | <v>
+        <o> ::= + | '|' | *
+        <v> ::= x | 1";
+
+    #[test]
+    fn parse_and_derive() {
+        let grammar = Grammar::parse(EXPR).unwrap();
+        assert_eq!(grammar.rules.len(), 3);
+        assert_eq!(grammar.rules[0].productions.len(), 3);
+        assert_eq!(grammar.rules[1].productions.len(), 3);
+
+        // <e> -> <e> <o> <e> -> <v> <o> <e> -> x <o> <e> -> x | <e> -> x | <v> -> x | 1
+        let derivation = grammar.derive(&[0, 2, 0, 1, 5, 7], 0).unwrap();
+        assert_eq!(derivation.phenotype, "x | 1");
+        assert_eq!(derivation.used_codons, 6);
+        // the same with wrapping around once
+        assert_eq!(grammar.derive(&[0, 2, 0], 0), None);
+        assert_eq!(grammar.derive(&[0, 2, 0], 1).unwrap().phenotype, "x + x");
+        // spaces between symbols are kept
+        assert_eq!(grammar.derive(&[1, 2, 0], 0).unwrap().phenotype, "( x )");
+
+        assert_eq!(Grammar::parse("# nothing").err(), Some(GrammarError::Empty));
+        assert_eq!(
+            Grammar::parse("<a> ::= <b>").err(),
+            Some(GrammarError::Undefined("b".to_owned()))
+        );
+        assert_eq!(
+            Grammar::parse("<a> ::= x <a>").err(),
+            Some(GrammarError::NonTerminating("a".to_owned()))
+        );
+        assert_eq!(
+            Grammar::parse("<a> ::= \"x").err(),
+            Some(GrammarError::Unclosed { line: 1 })
+        );
+        assert_eq!(
+            Grammar::parse("x\n<a> ::= x").err(),
+            Some(GrammarError::Syntax { line: 1 })
+        );
+    }
+
+    #[test]
+    fn sensible_initialization_is_valid() {
+        let config = GeConfig::new(Grammar::parse(EXPR).unwrap());
+        for _ in 0..100 {
+            let mut a = Codons::generate(&config);
+            let mut b = Codons::generate(&config);
+            assert!(a.derive(&config).is_some());
+            assert!(a.codons().iter().all(|&codon| codon < 256));
+
+            Codons::crossover(&mut a, &mut b, &config);
+            a.mutate(&config);
+            assert!(a.codons().len() <= config.max_len);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::fmt::Debug;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    gp::{tree::generate_subtree, Node, PrimitiveSet, Tree},
+    repro_rng::thread_rng,
+};
+
+/// Size limits for trees produced by variation operators
+///
+/// Without limits, trees tend to grow without bound over the course of a run,
+/// a phenomenon known as *bloat*. If an operator produces a tree that exceeds
+/// either limit, that tree is discarded and the original is kept instead.
+///
+/// The [`Default`] implementation uses a maximum depth of 17, following Koza,
+/// and no limit on the number of nodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum depth of a tree, where a single terminal has a depth of 0.
+    pub max_depth: usize,
+    /// The maximum number of nodes in a tree.
+    pub max_size: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_depth: 17,
+            max_size: usize::MAX,
+        }
+    }
+}
+
+impl Limits {
+    fn allow<V, K>(&self, pset: &PrimitiveSet<V, K>, tree: &Tree<V>) -> bool
+    where
+        V: Clone,
+        K: Copy + Eq + Debug,
+    {
+        tree.len() <= self.max_size && tree.depth(pset) <= self.max_depth
+    }
+}
+
+/// Subtree crossover
+///
+/// Randomly chooses a node in `a` and a node with the same type in `b`,
+/// and swaps the subtrees rooted at those two nodes.
+/// If either resulting tree exceeds `limits`, that tree is left unchanged.
+/// If `b` has no node with the same type as the one chosen in `a`, nothing happens.
+pub fn subtree_crossover<V, K>(
+    pset: &PrimitiveSet<V, K>,
+    a: &mut Tree<V>,
+    b: &mut Tree<V>,
+    limits: &Limits,
+) where
+    V: Clone,
+    K: Copy + Eq + Debug,
+{
+    let mut rng = thread_rng();
+
+    let a_root = rng.gen_range(0..a.len());
+    let ty = pset.node_type(&a.nodes[a_root]);
+    let candidates: Vec<usize> = (0..b.len())
+        .filter(|&i| pset.node_type(&b.nodes[i]) == ty)
+        .collect();
+    let b_root = match candidates.choose(&mut rng) {
+        Some(&i) => i,
+        None => return,
+    };
+
+    let a_range = a.subtree(pset, a_root);
+    let b_range = b.subtree(pset, b_root);
+
+    let new_a = splice(a, a_range.clone(), &b.nodes[b_range.clone()]);
+    let new_b = splice(b, b_range, &a.nodes[a_range]);
+
+    if limits.allow(pset, &new_a) {
+        *a = new_a;
+    }
+    if limits.allow(pset, &new_b) {
+        *b = new_b;
+    }
+}
+
+/// Subtree mutation
+///
+/// Randomly chooses a node in `tree` and replaces the subtree rooted at it
+/// with a newly grown subtree of the same type,
+/// whose depth is between `min_depth` and `max_depth`.
+/// If the resulting tree exceeds `limits`, it is left unchanged.
+///
+/// Panics
+/// ======
+/// Panics if `min_depth > max_depth`.
+pub fn subtree_mutation<V, K>(
+    pset: &PrimitiveSet<V, K>,
+    tree: &mut Tree<V>,
+    min_depth: usize,
+    max_depth: usize,
+    limits: &Limits,
+) where
+    V: Clone,
+    K: Copy + Eq + Debug,
+{
+    let root = thread_rng().gen_range(0..tree.len());
+    let ty = pset.node_type(&tree.nodes[root]);
+    let range = tree.subtree(pset, root);
+    let replacement = generate_subtree(pset, ty, min_depth, max_depth);
+
+    let mutated = splice(tree, range, &replacement);
+    if limits.allow(pset, &mutated) {
+        *tree = mutated;
+    }
+}
+
+/// Point mutation
+///
+/// Does a random roll of probability `indpb` for each node in `tree`.
+/// If the roll succeeds, the node is replaced with a random node of the same type and arity:
+/// primitives are replaced with primitives that take the same argument types,
+/// and terminals, ephemeral constants, and arguments are replaced with any of the three.
+/// Since the shape of the tree doesn't change, this never violates any size limits.
+pub fn point_mutation<V, K>(pset: &PrimitiveSet<V, K>, tree: &mut Tree<V>, indpb: f64)
+where
+    V: Clone,
+    K: Copy + Eq + Debug,
+{
+    let mut rng = thread_rng();
+    for node in tree.nodes.iter_mut() {
+        if !rng.gen_bool(indpb) {
+            continue;
+        }
+        let ty = pset.node_type(node);
+        let replacement = match node {
+            Node::Primitive(i) => pset
+                .random_primitive(ty, Some(&pset.primitives[*i].args))
+                .map(Node::Primitive),
+            _ => pset.random_terminal(ty),
+        };
+        if let Some(replacement) = replacement {
+            *node = replacement;
+        }
+    }
+}
+
+fn splice<V: Clone>(tree: &Tree<V>, range: std::ops::Range<usize>, with: &[Node<V>]) -> Tree<V> {
+    let mut nodes = Vec::with_capacity(tree.len() - range.len() + with.len());
+    nodes.extend_from_slice(&tree.nodes[..range.start]);
+    nodes.extend_from_slice(with);
+    nodes.extend_from_slice(&tree.nodes[range.end..]);
+    Tree { nodes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators_keep_trees_valid() {
+        let mut pset: PrimitiveSet<f64> = PrimitiveSet::new(2);
+        pset.add_primitive("add", 2, |args| args[0] + args[1]);
+        pset.add_primitive("mul", 2, |args| args[0] * args[1]);
+        pset.add_primitive("neg", 1, |args| -args[0]);
+        pset.add_ephemeral("rand", || thread_rng().gen_range(-1.0..1.0));
+
+        let limits = Limits {
+            max_depth: 6,
+            max_size: 40,
+        };
+
+        for _ in 0..200 {
+            let mut a = Tree::ramped_half_and_half(&pset, 1, 4);
+            let mut b = Tree::ramped_half_and_half(&pset, 1, 4);
+
+            subtree_crossover(&pset, &mut a, &mut b, &limits);
+            subtree_mutation(&pset, &mut a, 0, 3, &limits);
+            point_mutation(&pset, &mut b, 0.3);
+
+            for tree in [a, b] {
+                assert!(Tree::from_nodes(&pset, tree.nodes.clone()).is_some());
+                assert!(tree.depth(&pset) <= 6);
+                assert!(tree.len() <= 40);
+                assert!(tree.evaluate(&pset, &[0.5, -0.5]).is_finite());
+            }
+        }
+    }
+}
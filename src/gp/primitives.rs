@@ -0,0 +1,269 @@
+use std::{fmt::Debug, sync::Arc};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{gp::Node, repro_rng::thread_rng};
+
+type Func<V> = Arc<dyn Fn(&[V]) -> V + Send + Sync>;
+type Gen<V> = Arc<dyn Fn() -> V + Send + Sync>;
+
+pub(crate) struct Primitive<V, K> {
+    pub(crate) name: String,
+    pub(crate) func: Func<V>,
+    pub(crate) args: Vec<K>,
+    pub(crate) ret: K,
+}
+
+pub(crate) struct Terminal<V, K> {
+    pub(crate) name: String,
+    pub(crate) value: V,
+    pub(crate) ret: K,
+}
+
+pub(crate) struct Ephemeral<V, K> {
+    pub(crate) name: String,
+    pub(crate) gen: Gen<V>,
+    pub(crate) ret: K,
+}
+
+pub(crate) struct Argument<K> {
+    pub(crate) name: String,
+    pub(crate) ret: K,
+}
+
+/// The set of building blocks that a [`Tree`] can be made from
+///
+/// A primitive set contains:
+/// - *primitives*, functions that take one or more arguments
+/// - *terminals*, named constant values
+/// - *ephemeral constants*, terminals whose value is randomly generated
+///   each time one is inserted into a tree, and then stays fixed for that node
+/// - *arguments*, the inputs that are passed to [`Tree::evaluate`]
+///
+/// `V` is the type of value that the tree's nodes evaluate to,
+/// and `K` is the type used to tag each building block with a type.
+/// See the [module-level documentation] for more information on typing.
+///
+/// [`Tree`]: ./struct.Tree.html
+/// [`Tree::evaluate`]: ./struct.Tree.html#method.evaluate
+/// [module-level documentation]: ./index.html#typed-trees
+pub struct PrimitiveSet<V, K = ()> {
+    pub(crate) primitives: Vec<Primitive<V, K>>,
+    pub(crate) terminals: Vec<Terminal<V, K>>,
+    pub(crate) ephemerals: Vec<Ephemeral<V, K>>,
+    pub(crate) arguments: Vec<Argument<K>>,
+    ret: K,
+}
+
+impl<V> PrimitiveSet<V, ()> {
+    /// Create a new, empty, untyped primitive set for trees that take `n_args` arguments.
+    ///
+    /// The arguments are named `ARG0`, `ARG1`, and so on.
+    /// Use [`.rename_argument()`] to give them more meaningful names.
+    ///
+    /// [`.rename_argument()`]: ./struct.PrimitiveSet.html#method.rename_argument
+    pub fn new(n_args: usize) -> Self {
+        PrimitiveSet::typed(vec![(); n_args], ())
+    }
+
+    /// Add a primitive that takes `arity` arguments.
+    ///
+    /// `func` will always be called with a slice of exactly `arity` values.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `arity` is 0. Use [`.add_terminal()`] or [`.add_ephemeral()`] for constants.
+    ///
+    /// [`.add_terminal()`]: ./struct.PrimitiveSet.html#method.add_terminal
+    /// [`.add_ephemeral()`]: ./struct.PrimitiveSet.html#method.add_ephemeral
+    pub fn add_primitive<F>(&mut self, name: impl Into<String>, arity: usize, func: F)
+    where
+        F: Fn(&[V]) -> V + Send + Sync + 'static,
+    {
+        self.add_typed_primitive(name, vec![(); arity], (), func);
+    }
+
+    /// Add a terminal that always evaluates to `value`.
+    pub fn add_terminal(&mut self, name: impl Into<String>, value: V) {
+        self.add_typed_terminal(name, value, ());
+    }
+
+    /// Add an ephemeral constant, whose value is generated by calling `gen`
+    /// every time it is inserted into a tree.
+    pub fn add_ephemeral<F>(&mut self, name: impl Into<String>, gen: F)
+    where
+        F: Fn() -> V + Send + Sync + 'static,
+    {
+        self.add_typed_ephemeral(name, (), gen);
+    }
+}
+
+impl<V, K> PrimitiveSet<V, K>
+where
+    K: Copy + Eq + Debug,
+{
+    /// Create a new, empty primitive set for trees that take arguments with
+    /// the types in `arg_types`, and whose root evaluates to the type `ret`.
+    pub fn typed(arg_types: impl IntoIterator<Item = K>, ret: K) -> Self {
+        PrimitiveSet {
+            primitives: Vec::new(),
+            terminals: Vec::new(),
+            ephemerals: Vec::new(),
+            arguments: arg_types
+                .into_iter()
+                .enumerate()
+                .map(|(i, ret)| Argument {
+                    name: format!("ARG{}", i),
+                    ret,
+                })
+                .collect(),
+            ret,
+        }
+    }
+
+    /// Change the name of the argument at index `idx`.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if there is no argument at index `idx`.
+    pub fn rename_argument(&mut self, idx: usize, name: impl Into<String>) {
+        self.arguments[idx].name = name.into();
+    }
+
+    /// Add a primitive whose arguments have the types in `args`, and that returns the type `ret`.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `args` is empty. Use [`.add_typed_terminal()`] or [`.add_typed_ephemeral()`] for constants.
+    ///
+    /// [`.add_typed_terminal()`]: ./struct.PrimitiveSet.html#method.add_typed_terminal
+    /// [`.add_typed_ephemeral()`]: ./struct.PrimitiveSet.html#method.add_typed_ephemeral
+    pub fn add_typed_primitive<F>(&mut self, name: impl Into<String>, args: Vec<K>, ret: K, func: F)
+    where
+        F: Fn(&[V]) -> V + Send + Sync + 'static,
+    {
+        assert!(
+            !args.is_empty(),
+            "primitives must take at least one argument"
+        );
+        self.primitives.push(Primitive {
+            name: name.into(),
+            func: Arc::new(func),
+            args,
+            ret,
+        });
+    }
+
+    /// Add a terminal of type `ret` that always evaluates to `value`.
+    pub fn add_typed_terminal(&mut self, name: impl Into<String>, value: V, ret: K) {
+        self.terminals.push(Terminal {
+            name: name.into(),
+            value,
+            ret,
+        });
+    }
+
+    /// Add an ephemeral constant of type `ret`, whose value is generated by calling `gen`
+    /// every time it is inserted into a tree.
+    pub fn add_typed_ephemeral<F>(&mut self, name: impl Into<String>, ret: K, gen: F)
+    where
+        F: Fn() -> V + Send + Sync + 'static,
+    {
+        self.ephemerals.push(Ephemeral {
+            name: name.into(),
+            gen: Arc::new(gen),
+            ret,
+        });
+    }
+
+    /// Get the number of arguments that trees built from this set take.
+    pub fn n_args(&self) -> usize {
+        self.arguments.len()
+    }
+
+    /// Get the type that trees built from this set evaluate to.
+    pub fn ret(&self) -> K {
+        self.ret
+    }
+
+    pub(crate) fn arity(&self, node: &Node<V>) -> usize {
+        match node {
+            Node::Primitive(i) => self.primitives[*i].args.len(),
+            _ => 0,
+        }
+    }
+
+    pub(crate) fn node_type(&self, node: &Node<V>) -> K {
+        match node {
+            Node::Primitive(i) => self.primitives[*i].ret,
+            Node::Terminal(i) => self.terminals[*i].ret,
+            Node::Ephemeral(i, _) => self.ephemerals[*i].ret,
+            Node::Argument(i) => self.arguments[*i].ret,
+        }
+    }
+
+    pub(crate) fn node_name(&self, node: &Node<V>) -> &str {
+        match node {
+            Node::Primitive(i) => &self.primitives[*i].name,
+            Node::Terminal(i) => &self.terminals[*i].name,
+            Node::Ephemeral(i, _) => &self.ephemerals[*i].name,
+            Node::Argument(i) => &self.arguments[*i].name,
+        }
+    }
+
+    pub(crate) fn n_terminals_of(&self, ty: K) -> usize {
+        self.terminals.iter().filter(|t| t.ret == ty).count()
+            + self.ephemerals.iter().filter(|e| e.ret == ty).count()
+            + self.arguments.iter().filter(|a| a.ret == ty).count()
+    }
+
+    pub(crate) fn n_primitives_of(&self, ty: K) -> usize {
+        self.primitives.iter().filter(|p| p.ret == ty).count()
+    }
+
+    // Choose a random terminal-like node (terminal, ephemeral, or argument) of type `ty`.
+    pub(crate) fn random_terminal(&self, ty: K) -> Option<Node<V>> {
+        let n = self.n_terminals_of(ty);
+        if n == 0 {
+            return None;
+        }
+        let mut choice = thread_rng().gen_range(0..n);
+        for (i, t) in self.terminals.iter().enumerate() {
+            if t.ret == ty {
+                if choice == 0 {
+                    return Some(Node::Terminal(i));
+                }
+                choice -= 1;
+            }
+        }
+        for (i, e) in self.ephemerals.iter().enumerate() {
+            if e.ret == ty {
+                if choice == 0 {
+                    return Some(Node::Ephemeral(i, (e.gen)()));
+                }
+                choice -= 1;
+            }
+        }
+        for (i, a) in self.arguments.iter().enumerate() {
+            if a.ret == ty {
+                if choice == 0 {
+                    return Some(Node::Argument(i));
+                }
+                choice -= 1;
+            }
+        }
+        unreachable!()
+    }
+
+    // Choose a random primitive index whose return type is `ty`,
+    // optionally also requiring its argument types to match `args`.
+    pub(crate) fn random_primitive(&self, ty: K, args: Option<&[K]>) -> Option<usize> {
+        let candidates: Vec<usize> = (0..self.primitives.len())
+            .filter(|&i| {
+                let p = &self.primitives[i];
+                p.ret == ty && args.is_none_or(|args| p.args == args)
+            })
+            .collect();
+        candidates.choose(&mut thread_rng()).copied()
+    }
+}
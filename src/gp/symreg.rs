@@ -0,0 +1,505 @@
+//! Symbolic regression
+//!
+//! Symbolic regression is the search for a mathematical expression that fits a dataset,
+//! and is the most common application of genetic programming.
+//! This module contains the pieces needed to set one up with [`Tree`]:
+//! a [`Dataset`] container, ready-made primitive sets built from [protected operators]
+//! that never produce `NaN` or infinity, the error [`Metric`]s MSE, RMSE, and MAE,
+//! and [`Parsimony`] pressure to keep expressions from growing without bound.
+//! [`SymbolicRegression`] ties them all together.
+//! For a complete program with a custom primitive set, see
+//! [`examples/symbolic_regression.rs`](https://github.com/jqkul/eviolite/blob/main/examples/symbolic_regression.rs).
+//!
+//! ```
+//! use eviolite::prelude::*;
+//! use eviolite::gp::{self, symreg::*, Limits, Tree};
+//!
+//! lazy_static::lazy_static! {
+//!     static ref PROBLEM: SymbolicRegression = SymbolicRegression::new(
+//!         arithmetic(1),
+//!         Dataset::from_fn((-10..=10).map(|x| vec![x as f64 / 5.0]), |x| x[0] * x[0] - 2.0 * x[0]),
+//!     )
+//!     .with_parsimony(Parsimony::Penalty { per_node: 0.001 });
+//! }
+//!
+//! #[derive(Clone)]
+//! struct Expr(Tree<f64>);
+//!
+//! impl Solution for Expr {
+//!     type Fitness = f64;
+//!
+//!     fn generate() -> Self {
+//!         Expr(PROBLEM.generate())
+//!     }
+//!
+//!     fn evaluate(&self) -> f64 {
+//!         PROBLEM.fitness(&self.0)
+//!     }
+//!
+//!     fn crossover(a: &mut Self, b: &mut Self) {
+//!         gp::subtree_crossover(&PROBLEM.pset, &mut a.0, &mut b.0, &PROBLEM.limits);
+//!     }
+//!
+//!     fn mutate(&mut self) {
+//!         gp::subtree_mutation(&PROBLEM.pset, &mut self.0, 0, 2, &PROBLEM.limits);
+//!     }
+//! }
+//!
+//! let evo: Evolution<Expr, _, _, ()> = Evolution::new(
+//!     alg::Simple::new(100, 0.5, 0.2, select::Tournament::new(3)),
+//!     hof::BestN::new(1),
+//! );
+//! let log = evo.run_for(10);
+//! println!("{}", log.hall_of_fame[0].as_ref().0.display(&PROBLEM.pset));
+//! ```
+//!
+//! [`Tree`]: ../struct.Tree.html
+//! [`Dataset`]: ./struct.Dataset.html
+//! [protected operators]: ./fn.protected_div.html
+//! [`Metric`]: ./enum.Metric.html
+//! [`Parsimony`]: ./enum.Parsimony.html
+//! [`SymbolicRegression`]: ./struct.SymbolicRegression.html
+
+use rand::Rng;
+
+use crate::{
+    gp::{Limits, PrimitiveSet, Tree},
+    repro_rng::thread_rng,
+};
+
+/// A set of input rows and the target value for each of them
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dataset {
+    inputs: Vec<Vec<f64>>,
+    targets: Vec<f64>,
+}
+
+impl Dataset {
+    /// Create a dataset from rows of inputs and their targets.
+    ///
+    /// Returns `None` if `inputs` and `targets` have different lengths,
+    /// or if the rows don't all have the same number of features.
+    pub fn new(inputs: Vec<Vec<f64>>, targets: Vec<f64>) -> Option<Self> {
+        if inputs.len() != targets.len() || inputs.windows(2).any(|w| w[0].len() != w[1].len()) {
+            return None;
+        }
+        Some(Dataset { inputs, targets })
+    }
+
+    /// Create a dataset by applying `f` to each row of inputs to get its target.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if the rows don't all have the same number of features.
+    pub fn from_fn(inputs: impl IntoIterator<Item = Vec<f64>>, f: impl Fn(&[f64]) -> f64) -> Self {
+        let inputs: Vec<Vec<f64>> = inputs.into_iter().collect();
+        let targets = inputs.iter().map(|row| f(row)).collect();
+        Dataset::new(inputs, targets).expect("rows must all have the same number of features")
+    }
+
+    /// Get the number of rows.
+    pub fn len(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Check whether the dataset has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    /// Get the number of features in each row, or 0 if the dataset is empty.
+    pub fn n_features(&self) -> usize {
+        self.inputs.first().map_or(0, Vec::len)
+    }
+
+    /// Get the input rows.
+    pub fn inputs(&self) -> &[Vec<f64>] {
+        &self.inputs
+    }
+
+    /// Get the targets.
+    pub fn targets(&self) -> &[f64] {
+        &self.targets
+    }
+
+    /// Iterate over the rows as `(inputs, target)` pairs.
+    pub fn rows(&self) -> impl Iterator<Item = (&[f64], f64)> {
+        self.inputs
+            .iter()
+            .map(Vec::as_slice)
+            .zip(self.targets.iter().copied())
+    }
+}
+
+/// Protected division, which returns 1 when the denominator is too close to 0.
+pub fn protected_div(a: f64, b: f64) -> f64 {
+    if b.abs() < 1e-6 {
+        1.0
+    } else {
+        a / b
+    }
+}
+
+/// Protected natural logarithm, which takes the logarithm of the absolute value
+/// and returns 0 for inputs too close to 0.
+pub fn protected_ln(x: f64) -> f64 {
+    if x.abs() < 1e-6 {
+        0.0
+    } else {
+        x.abs().ln()
+    }
+}
+
+/// Protected square root, which takes the square root of the absolute value.
+pub fn protected_sqrt(x: f64) -> f64 {
+    x.abs().sqrt()
+}
+
+/// Protected exponential, which clamps its input to keep the result finite.
+pub fn protected_exp(x: f64) -> f64 {
+    x.min(100.0).exp()
+}
+
+/// Create a primitive set with addition, subtraction, multiplication, [protected division],
+/// negation, and an ephemeral constant between -1 and 1.
+///
+/// The arguments are named `x0`, `x1`, and so on.
+///
+/// [protected division]: ./fn.protected_div.html
+pub fn arithmetic(n_args: usize) -> PrimitiveSet<f64> {
+    let mut pset = PrimitiveSet::new(n_args);
+    for i in 0..n_args {
+        pset.rename_argument(i, format!("x{}", i));
+    }
+    pset.add_primitive("add", 2, |args| args[0] + args[1]);
+    pset.add_primitive("sub", 2, |args| args[0] - args[1]);
+    pset.add_primitive("mul", 2, |args| args[0] * args[1]);
+    pset.add_primitive("div", 2, |args| protected_div(args[0], args[1]));
+    pset.add_primitive("neg", 1, |args| -args[0]);
+    pset.add_ephemeral("rand", || thread_rng().gen_range(-1.0..1.0));
+    pset
+}
+
+/// Create a primitive set with everything in [`arithmetic`], plus sine, cosine,
+/// and the protected [logarithm], [square root], and [exponential].
+///
+/// [`arithmetic`]: ./fn.arithmetic.html
+/// [logarithm]: ./fn.protected_ln.html
+/// [square root]: ./fn.protected_sqrt.html
+/// [exponential]: ./fn.protected_exp.html
+pub fn transcendental(n_args: usize) -> PrimitiveSet<f64> {
+    let mut pset = arithmetic(n_args);
+    pset.add_primitive("sin", 1, |args| args[0].sin());
+    pset.add_primitive("cos", 1, |args| args[0].cos());
+    pset.add_primitive("ln", 1, |args| protected_ln(args[0]));
+    pset.add_primitive("sqrt", 1, |args| protected_sqrt(args[0]));
+    pset.add_primitive("exp", 1, |args| protected_exp(args[0]));
+    pset
+}
+
+/// A measure of how far predictions are from their targets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    /// [`mse`](./fn.mse.html)
+    MeanSquaredError,
+    /// The square root of [`mse`](./fn.mse.html)
+    RootMeanSquaredError,
+    /// [`mae`](./fn.mae.html)
+    MeanAbsoluteError,
+}
+
+impl Metric {
+    /// Compute the error between `predictions` and `targets`.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `predictions` and `targets` have different lengths.
+    pub fn error(&self, predictions: &[f64], targets: &[f64]) -> f64 {
+        match self {
+            Metric::MeanSquaredError => mse(predictions, targets),
+            Metric::RootMeanSquaredError => mse(predictions, targets).sqrt(),
+            Metric::MeanAbsoluteError => mae(predictions, targets),
+        }
+    }
+}
+
+/// Mean squared error
+///
+/// Returns 0 if both slices are empty.
+///
+/// Panics
+/// ======
+/// Panics if `predictions` and `targets` have different lengths.
+pub fn mse(predictions: &[f64], targets: &[f64]) -> f64 {
+    mean_of(predictions, targets, |d| d * d)
+}
+
+/// Mean absolute error
+///
+/// Returns 0 if both slices are empty.
+///
+/// Panics
+/// ======
+/// Panics if `predictions` and `targets` have different lengths.
+pub fn mae(predictions: &[f64], targets: &[f64]) -> f64 {
+    mean_of(predictions, targets, f64::abs)
+}
+
+fn mean_of(predictions: &[f64], targets: &[f64], f: impl Fn(f64) -> f64) -> f64 {
+    assert_eq!(
+        predictions.len(),
+        targets.len(),
+        "predictions and targets must have the same length"
+    );
+    if targets.is_empty() {
+        return 0.0;
+    }
+    predictions
+        .iter()
+        .zip(targets)
+        .map(|(p, t)| f(p - t))
+        .sum::<f64>()
+        / targets.len() as f64
+}
+
+/// Pressure against large expressions, used in [`SymbolicRegression`]
+///
+/// Without parsimony pressure, expressions tend to keep growing over the course of a run
+/// without getting any more accurate, which is known as bloat.
+///
+/// [`SymbolicRegression`]: ./struct.SymbolicRegression.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Parsimony {
+    /// No pressure.
+    None,
+    /// Add `per_node` times the number of nodes in the expression to its error.
+    Penalty {
+        /// The penalty for each node.
+        per_node: f64,
+    },
+    /// Give the worst possible fitness to any expression with more than `max_size` nodes.
+    MaxSize {
+        /// The largest allowed number of nodes.
+        max_size: usize,
+    },
+}
+
+/// A complete symbolic regression problem
+///
+/// See the [module-level documentation](./index.html) for an example.
+pub struct SymbolicRegression {
+    /// The primitive set expressions are built from.
+    /// Its number of arguments must match the number of features in the dataset.
+    pub pset: PrimitiveSet<f64>,
+    /// The data to fit.
+    pub dataset: Dataset,
+    /// How errors are measured. Defaults to mean squared error.
+    pub metric: Metric,
+    /// Pressure against large expressions. Defaults to none.
+    pub parsimony: Parsimony,
+    /// Limits for the variation operators, for convenience. Defaults to [`Limits::default()`].
+    ///
+    /// [`Limits::default()`]: ../struct.Limits.html
+    pub limits: Limits,
+    /// The minimum and maximum depth of generated expressions. Defaults to `(1, 4)`.
+    pub init_depth: (usize, usize),
+}
+
+impl SymbolicRegression {
+    /// Create a problem with default settings.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if the number of arguments of `pset` doesn't match the number of features in `dataset`.
+    pub fn new(pset: PrimitiveSet<f64>, dataset: Dataset) -> Self {
+        assert!(
+            dataset.is_empty() || pset.n_args() == dataset.n_features(),
+            "primitive set takes {} arguments, but the dataset has {} features",
+            pset.n_args(),
+            dataset.n_features()
+        );
+        SymbolicRegression {
+            pset,
+            dataset,
+            metric: Metric::MeanSquaredError,
+            parsimony: Parsimony::None,
+            limits: Limits::default(),
+            init_depth: (1, 4),
+        }
+    }
+
+    /// Set the error metric.
+    pub fn with_metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Set the parsimony pressure.
+    pub fn with_parsimony(mut self, parsimony: Parsimony) -> Self {
+        self.parsimony = parsimony;
+        self
+    }
+
+    /// Generate a random expression using ramped half-and-half with the configured depths.
+    pub fn generate(&self) -> Tree<f64> {
+        Tree::ramped_half_and_half(&self.pset, self.init_depth.0, self.init_depth.1)
+    }
+
+    /// Get the predictions of `tree` for every row of the dataset.
+    pub fn predict(&self, tree: &Tree<f64>) -> Vec<f64> {
+        self.dataset
+            .inputs()
+            .iter()
+            .map(|row| tree.evaluate(&self.pset, row))
+            .collect()
+    }
+
+    /// Get the error of `tree` on the dataset, without parsimony pressure.
+    ///
+    /// Non-finite errors are replaced with `f64::MAX`, so they can always be compared.
+    pub fn error(&self, tree: &Tree<f64>) -> f64 {
+        let error = self
+            .metric
+            .error(&self.predict(tree), self.dataset.targets());
+        if error.is_finite() {
+            error
+        } else {
+            f64::MAX
+        }
+    }
+
+    /// Get the fitness of `tree`, which is its negated [error] with parsimony pressure applied.
+    /// Higher is better, as for every other fitness value in this crate.
+    ///
+    /// [error]: ./struct.SymbolicRegression.html#method.error
+    pub fn fitness(&self, tree: &Tree<f64>) -> f64 {
+        match self.parsimony {
+            Parsimony::None => -self.error(tree),
+            Parsimony::Penalty { per_node } => {
+                -(self.error(tree) + per_node * tree.len() as f64).min(f64::MAX)
+            }
+            Parsimony::MaxSize { max_size } => {
+                if tree.len() > max_size {
+                    f64::MIN
+                } else {
+                    -self.error(tree)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use super::*;
+    use crate::{
+        gp::{self, Node},
+        prelude::*,
+        repro_rng::reseed,
+    };
+
+    #[test]
+    fn error_and_parsimony() {
+        let data = Dataset::from_fn((0..5).map(|x| vec![x as f64]), |x| 2.0 * x[0]);
+        assert_eq!(data.n_features(), 1);
+        let problem = SymbolicRegression::new(arithmetic(1), data);
+
+        // add(x0, x0) fits exactly, x0 is off by x at each point
+        let exact = Tree::from_nodes(
+            &problem.pset,
+            vec![Node::Primitive(0), Node::Argument(0), Node::Argument(0)],
+        )
+        .unwrap();
+        let half = Tree::from_nodes(&problem.pset, vec![Node::Argument(0)]).unwrap();
+        assert_eq!(problem.error(&exact), 0.0);
+        assert_eq!(problem.error(&half), (0.0 + 1.0 + 4.0 + 9.0 + 16.0) / 5.0);
+
+        let problem = problem.with_metric(Metric::MeanAbsoluteError);
+        assert_eq!(problem.error(&half), 2.0);
+
+        let problem = problem.with_parsimony(Parsimony::Penalty { per_node: 0.5 });
+        assert_eq!(problem.fitness(&exact), -1.5);
+        let problem = problem.with_parsimony(Parsimony::MaxSize { max_size: 2 });
+        assert_eq!(problem.fitness(&exact), f64::MIN);
+        assert_eq!(problem.fitness(&half), -2.0);
+
+        assert_eq!(protected_div(1.0, 0.0), 1.0);
+        assert!(protected_ln(0.0).is_finite() && protected_exp(1e6).is_finite());
+    }
+
+    // x² + x + 1 from a primitive set with every kind of building block
+    fn problem() -> &'static SymbolicRegression {
+        static PROBLEM: OnceLock<SymbolicRegression> = OnceLock::new();
+        PROBLEM.get_or_init(|| {
+            let mut pset: PrimitiveSet<f64> = PrimitiveSet::new(1);
+            pset.add_primitive("add", 2, |args| args[0] + args[1]);
+            pset.add_primitive("mul", 2, |args| args[0] * args[1]);
+            pset.add_primitive("neg", 1, |args| -args[0]);
+            pset.add_terminal("one", 1.0);
+            pset.add_ephemeral("rand", || thread_rng().gen_range(-1.0..1.0));
+            let data = Dataset::from_fn((-10..=10).map(|x| vec![x as f64 / 5.0]), |x| {
+                x[0] * x[0] + x[0] + 1.0
+            });
+            SymbolicRegression::new(pset, data)
+        })
+    }
+
+    #[derive(Clone)]
+    struct Expr(Tree<f64>);
+
+    impl Solution for Expr {
+        type Fitness = f64;
+
+        fn generate() -> Self {
+            Expr(problem().generate())
+        }
+
+        fn evaluate(&self) -> f64 {
+            problem().fitness(&self.0)
+        }
+
+        fn crossover(a: &mut Self, b: &mut Self) {
+            gp::subtree_crossover(&problem().pset, &mut a.0, &mut b.0, &problem().limits);
+        }
+
+        fn mutate(&mut self) {
+            if thread_rng().gen_bool(0.5) {
+                gp::subtree_mutation(&problem().pset, &mut self.0, 0, 2, &problem().limits);
+            } else {
+                gp::point_mutation(&problem().pset, &mut self.0, 0.2);
+            }
+        }
+    }
+
+    #[test]
+    fn evolve_expression() {
+        reseed(7);
+        let initial: Vec<Expr> = (0..200).map(|_| Expr::generate()).collect();
+        assert!(initial
+            .iter()
+            .flat_map(|expr| expr.0.nodes())
+            .any(|node| matches!(node, Node::Ephemeral(..))));
+        let initial_error = initial
+            .iter()
+            .map(|expr| problem().error(&expr.0))
+            .fold(f64::INFINITY, f64::min);
+
+        let evo: Evolution<Expr, _, _, ()> = Evolution::new(
+            alg::Simple::new(200, 0.5, 0.2, select::Tournament::new(3)),
+            hof::BestN::new(1),
+        );
+        let log = evo.run_until(|gen| {
+            gen.gen >= 50 || problem().error(&gen.hall_of_fame[0].as_ref().0) < 1e-9
+        });
+        let best = &log.hall_of_fame[0].as_ref().0;
+        assert!(best.depth(&problem().pset) <= problem().limits.max_depth);
+        assert!(
+            problem().error(best) < initial_error.min(0.1),
+            "{} has error {}",
+            best.display(&problem().pset),
+            problem().error(best)
+        );
+    }
+}
@@ -0,0 +1,417 @@
+use std::fmt::{self, Debug, Display};
+
+use rand::Rng;
+
+use crate::{gp::PrimitiveSet, repro_rng::thread_rng};
+
+/// A single node in a [`Tree`]
+///
+/// Each variant refers to a building block in the [`PrimitiveSet`] the tree was built from by its index.
+///
+/// [`Tree`]: ./struct.Tree.html
+/// [`PrimitiveSet`]: ./struct.PrimitiveSet.html
+#[derive(Clone, Debug, PartialEq)]
+pub enum Node<V> {
+    /// A primitive, i.e. a function node with one or more children.
+    Primitive(usize),
+    /// A terminal with a fixed value.
+    Terminal(usize),
+    /// An ephemeral constant, along with the value generated for it when it was inserted.
+    Ephemeral(usize, V),
+    /// One of the arguments passed to [`Tree::evaluate`].
+    ///
+    /// [`Tree::evaluate`]: ./struct.Tree.html#method.evaluate
+    Argument(usize),
+}
+
+/// An expression tree genome
+///
+/// The tree is stored as a flat list of [`Node`]s in prefix order,
+/// so every subtree occupies a contiguous range of nodes.
+/// All methods that need to know anything about the nodes take a reference to the
+/// [`PrimitiveSet`] the tree was built from; using a tree with any other primitive set
+/// will give nonsensical results or panic.
+///
+/// [`Node`]: ./enum.Node.html
+/// [`PrimitiveSet`]: ./struct.PrimitiveSet.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tree<V> {
+    pub(crate) nodes: Vec<Node<V>>,
+}
+
+impl<V> Tree<V>
+where
+    V: Clone,
+{
+    /// Generate a tree where every branch has a depth of exactly `depth`.
+    ///
+    /// If the primitive set has no terminal of a type needed to finish a branch,
+    /// that branch will keep growing until it can be finished.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if the primitive set has neither a primitive nor a terminal of a type that is needed.
+    pub fn full<K>(pset: &PrimitiveSet<V, K>, depth: usize) -> Self
+    where
+        K: Copy + Eq + Debug,
+    {
+        Tree {
+            nodes: generate(pset, pset.ret(), depth, depth, false),
+        }
+    }
+
+    /// Generate a tree whose branches can have any depth between `min_depth` and `max_depth`.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `min_depth > max_depth`,
+    /// or if the primitive set has neither a primitive nor a terminal of a type that is needed.
+    pub fn grow<K>(pset: &PrimitiveSet<V, K>, min_depth: usize, max_depth: usize) -> Self
+    where
+        K: Copy + Eq + Debug,
+    {
+        Tree {
+            nodes: generate(pset, pset.ret(), min_depth, max_depth, true),
+        }
+    }
+
+    /// Generate a tree using the ramped half-and-half method.
+    ///
+    /// This picks either [`full`] or [`grow`] with equal probability,
+    /// with a maximum depth chosen uniformly between `min_depth` and `max_depth`.
+    /// Generating a whole population this way gives a good mix of tree shapes and sizes.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `min_depth > max_depth`,
+    /// or if the primitive set has neither a primitive nor a terminal of a type that is needed.
+    ///
+    /// [`full`]: ./struct.Tree.html#method.full
+    /// [`grow`]: ./struct.Tree.html#method.grow
+    pub fn ramped_half_and_half<K>(
+        pset: &PrimitiveSet<V, K>,
+        min_depth: usize,
+        max_depth: usize,
+    ) -> Self
+    where
+        K: Copy + Eq + Debug,
+    {
+        assert!(
+            min_depth <= max_depth,
+            "min_depth must not exceed max_depth"
+        );
+        let mut rng = thread_rng();
+        let depth = rng.gen_range(min_depth..=max_depth);
+        if rng.gen_bool(0.5) {
+            Tree::full(pset, depth)
+        } else {
+            Tree::grow(pset, min_depth, depth)
+        }
+    }
+
+    /// Create a tree directly from a list of nodes in prefix order.
+    ///
+    /// Returns `None` if the nodes don't form exactly one complete tree
+    /// according to the arities in `pset`.
+    pub fn from_nodes<K>(pset: &PrimitiveSet<V, K>, nodes: Vec<Node<V>>) -> Option<Self>
+    where
+        K: Copy + Eq + Debug,
+    {
+        let mut open: usize = 1;
+        for node in nodes.iter() {
+            if open == 0 {
+                return None;
+            }
+            open = open - 1 + pset.arity(node);
+        }
+        if open == 0 {
+            Some(Tree { nodes })
+        } else {
+            None
+        }
+    }
+
+    /// Get the tree's nodes in prefix order.
+    pub fn nodes(&self) -> &[Node<V>] {
+        &self.nodes
+    }
+
+    /// Get the number of nodes in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Check whether the tree has no nodes. This is never the case for a tree built by this module.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Get the depth of the tree, where a tree consisting of a single terminal has a depth of 0.
+    pub fn depth<K>(&self, pset: &PrimitiveSet<V, K>) -> usize
+    where
+        K: Copy + Eq + Debug,
+    {
+        let mut stack: Vec<usize> = vec![0];
+        let mut max_depth: usize = 0;
+        for node in self.nodes.iter() {
+            let depth = stack.pop().unwrap();
+            max_depth = max_depth.max(depth);
+            for _ in 0..pset.arity(node) {
+                stack.push(depth + 1);
+            }
+        }
+        max_depth
+    }
+
+    /// Get the range of node indices covered by the subtree whose root is at index `root`.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `root` is out of bounds.
+    pub fn subtree<K>(&self, pset: &PrimitiveSet<V, K>, root: usize) -> std::ops::Range<usize>
+    where
+        K: Copy + Eq + Debug,
+    {
+        let mut open: usize = 1;
+        let mut end = root;
+        while open > 0 {
+            open = open - 1 + pset.arity(&self.nodes[end]);
+            end += 1;
+        }
+        root..end
+    }
+
+    /// Evaluate the tree, passing `args` as its arguments.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `args` has fewer elements than the primitive set has arguments.
+    pub fn evaluate<K>(&self, pset: &PrimitiveSet<V, K>, args: &[V]) -> V
+    where
+        K: Copy + Eq + Debug,
+    {
+        let mut stack: Vec<V> = Vec::with_capacity(self.nodes.len());
+        let mut scratch: Vec<V> = Vec::new();
+        // Walking the prefix representation backwards means every primitive's
+        // arguments have already been evaluated by the time it is reached,
+        // with the first argument on top of the stack.
+        for node in self.nodes.iter().rev() {
+            let value = match node {
+                Node::Primitive(i) => {
+                    let prim = &pset.primitives[*i];
+                    scratch.clear();
+                    for _ in 0..prim.args.len() {
+                        scratch.push(stack.pop().unwrap());
+                    }
+                    (prim.func)(&scratch)
+                }
+                Node::Terminal(i) => pset.terminals[*i].value.clone(),
+                Node::Ephemeral(_, value) => value.clone(),
+                Node::Argument(i) => args[*i].clone(),
+            };
+            stack.push(value);
+        }
+        stack.pop().unwrap()
+    }
+
+    /// Get an object that displays the tree as a nested function call expression,
+    /// e.g. `add(x, mul(x, 2))`.
+    pub fn display<'a, K>(&'a self, pset: &'a PrimitiveSet<V, K>) -> impl Display + 'a
+    where
+        V: Display,
+        K: Copy + Eq + Debug,
+    {
+        TreeDisplay { tree: self, pset }
+    }
+}
+
+struct TreeDisplay<'a, V, K> {
+    tree: &'a Tree<V>,
+    pset: &'a PrimitiveSet<V, K>,
+}
+
+impl<'a, V, K> Display for TreeDisplay<'a, V, K>
+where
+    V: Clone + Display,
+    K: Copy + Eq + Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Each entry is the arity of an open primitive and the number of its arguments still to be written
+        let mut open: Vec<(usize, usize)> = Vec::new();
+        for node in self.tree.nodes.iter() {
+            if let Some((arity, remaining)) = open.last_mut() {
+                if remaining < arity {
+                    write!(f, ", ")?;
+                }
+                *remaining -= 1;
+            }
+            match node {
+                Node::Ephemeral(_, value) => write!(f, "{}", value)?,
+                _ => write!(f, "{}", self.pset.node_name(node))?,
+            }
+            let arity = self.pset.arity(node);
+            if arity > 0 {
+                write!(f, "(")?;
+                open.push((arity, arity));
+            } else {
+                while matches!(open.last(), Some((_, 0))) {
+                    write!(f, ")")?;
+                    open.pop();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Generate a list of nodes in prefix order representing a tree of type `ret`.
+fn generate<V, K>(
+    pset: &PrimitiveSet<V, K>,
+    ret: K,
+    min_depth: usize,
+    max_depth: usize,
+    grow: bool,
+) -> Vec<Node<V>>
+where
+    V: Clone,
+    K: Copy + Eq + Debug,
+{
+    assert!(
+        min_depth <= max_depth,
+        "min_depth must not exceed max_depth"
+    );
+    let mut rng = thread_rng();
+    let height = rng.gen_range(min_depth..=max_depth);
+    let mut nodes: Vec<Node<V>> = Vec::new();
+    let mut stack: Vec<(usize, K)> = vec![(0, ret)];
+    while let Some((depth, ty)) = stack.pop() {
+        let n_terms = pset.n_terminals_of(ty);
+        let n_prims = pset.n_primitives_of(ty);
+        let want_terminal = depth >= height
+            || n_prims == 0
+            || (grow
+                && depth >= min_depth
+                && rng.gen_bool(n_terms as f64 / (n_terms + n_prims) as f64));
+        if want_terminal {
+            match pset.random_terminal(ty) {
+                Some(node) => {
+                    nodes.push(node);
+                    continue;
+                }
+                None if n_prims == 0 => {
+                    panic!(
+                        "primitive set has no primitives or terminals of type {:?}",
+                        ty
+                    )
+                }
+                // No terminal of this type exists, so the branch has to grow past the limit
+                None => {}
+            }
+        }
+        let idx = pset.random_primitive(ty, None).unwrap();
+        nodes.push(Node::Primitive(idx));
+        for &arg in pset.primitives[idx].args.iter().rev() {
+            stack.push((depth + 1, arg));
+        }
+    }
+    nodes
+}
+
+// Generate a subtree of type `ret` for use by mutation operators.
+pub(crate) fn generate_subtree<V, K>(
+    pset: &PrimitiveSet<V, K>,
+    ret: K,
+    min_depth: usize,
+    max_depth: usize,
+) -> Vec<Node<V>>
+where
+    V: Clone,
+    K: Copy + Eq + Debug,
+{
+    generate(pset, ret, min_depth, max_depth, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pset() -> PrimitiveSet<f64> {
+        let mut pset: PrimitiveSet<f64> = PrimitiveSet::new(1);
+        pset.rename_argument(0, "x");
+        pset.add_primitive("add", 2, |args| args[0] + args[1]);
+        pset.add_primitive("sub", 2, |args| args[0] - args[1]);
+        pset.add_primitive("neg", 1, |args| -args[0]);
+        pset.add_terminal("2", 2.0);
+        pset
+    }
+
+    #[test]
+    fn evaluate_and_display() {
+        let pset = pset();
+        // sub(x, add(2, neg(x)))
+        let tree = Tree::from_nodes(
+            &pset,
+            vec![
+                Node::Primitive(1),
+                Node::Argument(0),
+                Node::Primitive(0),
+                Node::Terminal(0),
+                Node::Primitive(2),
+                Node::Argument(0),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(tree.evaluate(&pset, &[3.0]), 4.0);
+        assert_eq!(tree.depth(&pset), 3);
+        assert_eq!(tree.subtree(&pset, 2), 2..6);
+        assert_eq!(tree.display(&pset).to_string(), "sub(x, add(2, neg(x)))");
+
+        assert!(Tree::from_nodes(&pset, vec![Node::Primitive(0), Node::Argument(0)]).is_none());
+    }
+
+    #[test]
+    fn generation_respects_depth() {
+        let pset = pset();
+        for _ in 0..100 {
+            let full = Tree::full(&pset, 3);
+            assert_eq!(full.depth(&pset), 3);
+            let grown = Tree::ramped_half_and_half(&pset, 1, 4);
+            assert!(grown.depth(&pset) <= 4);
+            assert!(Tree::from_nodes(&pset, grown.nodes.clone()).is_some());
+        }
+    }
+
+    #[test]
+    fn typed_generation() {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        enum Ty {
+            Num,
+            Bool,
+        }
+
+        let mut pset: PrimitiveSet<f64, Ty> = PrimitiveSet::typed([Ty::Num], Ty::Num);
+        pset.add_typed_primitive("if", vec![Ty::Bool, Ty::Num, Ty::Num], Ty::Num, |args| {
+            if args[0] != 0.0 {
+                args[1]
+            } else {
+                args[2]
+            }
+        });
+        pset.add_typed_primitive("gt", vec![Ty::Num, Ty::Num], Ty::Bool, |args| {
+            (args[0] > args[1]) as u8 as f64
+        });
+        pset.add_typed_terminal("true", 1.0, Ty::Bool);
+
+        for _ in 0..100 {
+            let tree = Tree::full(&pset, 3);
+            let mut expected = vec![Ty::Num];
+            for node in tree.nodes() {
+                assert_eq!(pset.node_type(node), expected.pop().unwrap());
+                if let Node::Primitive(i) = node {
+                    expected.extend(pset.primitives[*i].args.iter().rev());
+                }
+            }
+        }
+    }
+}
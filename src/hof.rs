@@ -5,20 +5,101 @@
 //! record each successive generation and update its own records of the best solutions over time.
 //!
 //! This module also contains a few simple [`HallOfFame`] implementors that should work well for simple applications.
+//! [`BestN`] and [`BestPareto`] remember when each of their solutions was found as a [`Discovery`].
+//!
+//! [`BestN`]: ./struct.BestN.html
+//! [`BestPareto`]: ./struct.BestPareto.html
+//! [`Discovery`]: ./struct.Discovery.html
 
-use std::{fmt::Debug, ops::Deref};
+use alloc::vec::Vec;
+use core::{fmt::Debug, ops::Deref};
 
 use crate::{
-    fitness::MultiObjective,
-    select::{rank_nondominated, utils::retain_indices},
+    fitness::{Direction, MultiObjective},
+    select::{
+        nsga::{cmp_dom, sort_by_crowding_distance, DomOrdering},
+        rank_nondominated,
+    },
     Cached, Solution,
 };
 use itertools::Itertools;
 
 /// A trait that indicates a type can record certain solutions over successive generations.
 pub trait HallOfFame<T: Solution> {
-    /// Include the solutions of a generation in the record.
-    fn record(&mut self, generation: &[Cached<T>]);
+    /// Include the solutions of generation number `gen` in the record,
+    /// after the run had computed `evaluations` fitness evaluations.
+    fn record(&mut self, gen: usize, evaluations: u64, generation: &[Cached<T>]);
+
+    /// Update the record after the fitness function has changed,
+    /// which [`Evolution`] does when the [environment changes].
+    ///
+    /// By default, this does nothing, so the solutions keep the fitness they had when they were recorded.
+    ///
+    /// [`Evolution`]: ../struct.Evolution.html
+    /// [environment changes]: ../struct.Evolution.html#method.with_environment_changes
+    fn environment_changed(&mut self) {}
+
+    /// Include every solution recorded by `other`, a hall of fame of the same kind
+    /// from another run or another segment of the same run, as if this one had recorded them.
+    ///
+    /// By default, this records the solutions of `other` as a single generation numbered 0
+    /// after 0 evaluations, since there's no telling when they were found.
+    /// [`Log::append()`] and [`Log::merge_with()`] use this to combine the halls of fame of two logs.
+    ///
+    /// [`Log::append()`]: ../struct.Log.html#method.append
+    /// [`Log::merge_with()`]: ../struct.Log.html#method.merge_with
+    fn merge(&mut self, other: Self)
+    where
+        Self: Sized + IntoIterator<Item = Cached<T>>,
+    {
+        let solutions: Vec<Cached<T>> = other.into_iter().collect();
+        self.record(0, 0, &solutions);
+    }
+}
+
+/// When a solution in a hall of fame was found
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+///
+/// let evo: Evolution<Benchmark<Sphere, 2>, _, _, ()> = Evolution::new(
+///     alg::Simple::new(20, 0.5, 0.2, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// );
+/// let log = evo.run_for(10);
+/// let (best, found) = log.hall_of_fame.entries().next().unwrap();
+/// println!("best {} found at generation {}", best.evaluate(), found.generation());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Discovery {
+    generation: usize,
+    evaluations: u64,
+}
+
+impl Discovery {
+    // A discovery in generation `gen`, after the run had computed `evaluations` evaluations
+    fn new(gen: usize, evaluations: u64) -> Self {
+        Discovery {
+            generation: gen,
+            evaluations,
+        }
+    }
+
+    /// Get the number of the generation that the solution was first recorded in.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Get the number of evaluations the run had computed when the solution was recorded,
+    /// as counted by [`Generation::evaluations`].
+    ///
+    /// [`Generation::evaluations`]: ../struct.Generation.html#structfield.evaluations
+    pub fn evaluations(&self) -> u64 {
+        self.evaluations
+    }
 }
 
 /// Keeps a ranking of the best solutions across all generations
@@ -26,13 +107,24 @@ pub trait HallOfFame<T: Solution> {
 /// This type supports any solution whose fitness can be represented as a single number,
 /// enforced by the `T::Fitness: Into<f64>` requirement on its [`HallOfFame`] implementation.
 /// [`MultiObjective`] implements `Into<f64>` for convenience, taking weighting into account.
+/// Solutions are ranked in the direction of their first objective, see [`Solution::DIRECTIONS`].
 ///
+/// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
 /// [`HallOfFame`]: ./trait.HallOfFame.html
 /// [`MultiObjective`]: ../fitness/struct.MultiObjective.html
 #[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "Cached<T>: serde::Serialize",
+        deserialize = "Cached<T>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct BestN<T: Solution> {
     max: usize,
     best: Vec<Cached<T>>,
+    found: Vec<Discovery>,
     got_new_best: bool,
 }
 
@@ -43,11 +135,17 @@ impl<T: Solution> BestN<T> {
         BestN {
             max,
             best: Vec::with_capacity(max),
+            found: Vec::with_capacity(max),
             got_new_best: false,
         }
     }
 
-    /// Get a reference to the solution with the highest fitness
+    /// Iterate over the stored solutions from best to worst, each with when it was found.
+    pub fn entries(&self) -> impl Iterator<Item = (&Cached<T>, Discovery)> + '_ {
+        self.best.iter().zip(self.found.iter().copied())
+    }
+
+    /// Get a reference to the solution with the best fitness
     /// across all recorded generations, if it exists.
     ///
     /// Returns `None` if no solutions are stored.
@@ -71,16 +169,39 @@ where
     T: Solution,
     T::Fitness: Into<f64>,
 {
-    fn record(&mut self, generation: &[Cached<T>]) {
+    fn record(&mut self, gen: usize, evaluations: u64, generation: &[Cached<T>]) {
         self.got_new_best = false;
+        let found = Discovery::new(gen, evaluations);
         for ind in generation {
-            if let Some(idx) = self.find_index(ind) {
-                self.best.insert(idx, ind.clone());
-            } else if self.best.len() < self.max {
-                self.best.push(ind.clone());
+            self.insert(ind, found);
+        }
+    }
+
+    /// Re-evaluates the stored solutions and ranks them again. They keep when they were found.
+    fn environment_changed(&mut self) {
+        let mut old = core::mem::take(&mut self.best);
+        let found = core::mem::take(&mut self.found);
+        for ind in &mut old {
+            ind.clear_cache();
+        }
+        let got_new_best = self.got_new_best;
+        for (ind, found) in old.iter().zip(found) {
+            self.insert(ind, found);
+        }
+        self.got_new_best = got_new_best;
+    }
+
+    /// Skips the solutions of `other` with the same fitness as one that's already stored,
+    /// which are usually the same solution recorded by both, and ranks the rest in
+    /// along with when they were found.
+    fn merge(&mut self, other: Self) {
+        self.got_new_best = false;
+        for (ind, found) in other.best.iter().zip(other.found) {
+            let fit: f64 = ind.evaluate().into();
+            if !self.best.iter().any(|best| best.evaluate().into() == fit) {
+                self.insert(ind, found);
             }
         }
-        self.best.truncate(self.max);
     }
 }
 
@@ -99,7 +220,7 @@ where
     T: Solution,
     Cached<T>: Debug,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_list().entries(self.best.iter()).finish()
     }
 }
@@ -114,20 +235,35 @@ where
     }
 }
 
-impl<T, F> BestN<T>
+impl<T> BestN<T>
 where
-    T: Solution<Fitness = F>,
-    F: Into<f64>,
+    T: Solution,
+    T::Fitness: Into<f64>,
 {
+    fn insert(&mut self, ind: &Cached<T>, found: Discovery) {
+        if let Some(idx) = self.find_index(ind) {
+            self.best.insert(idx, ind.clone());
+            self.found.insert(idx, found);
+        } else if self.best.len() < self.max {
+            self.best.push(ind.clone());
+            self.found.push(found);
+        }
+        self.best.truncate(self.max);
+        self.found.truncate(self.max);
+    }
+
     fn find_index(&mut self, ind: &Cached<T>) -> Option<usize> {
+        let direction = Direction::of::<T>(0);
         let fit = ind.evaluate().into();
-        if self.best.is_empty() || fit > self.best[0].evaluate().into() {
+        if self.best.is_empty() || direction.is_better(fit, self.best[0].evaluate().into()) {
             self.got_new_best = true;
             return Some(0);
         }
 
         for (i, (a, b)) in self.best.iter().tuple_windows().enumerate() {
-            if fit > b.evaluate().into() && fit < a.evaluate().into() {
+            if direction.is_better(fit, b.evaluate().into())
+                && direction.is_better(a.evaluate().into(), fit)
+            {
                 return Some(i + 1);
             }
         }
@@ -140,15 +276,29 @@ where
 ///
 /// Stores a record of all solutions who are not dominated in the set of all solutions in every generation
 /// (also known as a [Pareto front](https://en.wikipedia.org/wiki/Pareto_front)).
-/// For more information on how this is calculated, see the documentation for [`rank_nondominated()`].
+/// For more information on dominance, see the documentation for [`rank_nondominated()`].
+///
+/// The front is kept up to date incrementally: each generation's own nondominated solutions
+/// are only compared against the current front, rather than re-ranking everything that was ever recorded.
+/// A solution with the same fitness as one already in the front isn't added again,
+/// so solutions that survive from one generation to the next are only stored once.
 ///
 /// [`rank_nondominated()`]: ../select/fn.rank_nondominated.html
 #[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "Cached<T>: serde::Serialize",
+        deserialize = "Cached<T>: serde::Deserialize<'de>"
+    ))
+)]
 pub struct BestPareto<T, const M: usize>
 where
     T: Solution<Fitness = MultiObjective<M>>,
 {
     front: Vec<Cached<T>>,
+    found: Vec<Discovery>,
 }
 
 impl<T, const M: usize> BestPareto<T, M>
@@ -158,7 +308,8 @@ where
     /// Create a new instance of `BestPareto` with no stored solutions.
     pub fn new() -> Self {
         BestPareto {
-            front: Default::default(),
+            front: Vec::new(),
+            found: Vec::new(),
         }
     }
 
@@ -166,6 +317,47 @@ where
     pub fn front(&self) -> &[Cached<T>] {
         &self.front
     }
+
+    /// Iterate over the globally nondominated solutions in the same order as [`.front()`],
+    /// each with when it was found.
+    ///
+    /// [`.front()`]: #method.front
+    pub fn entries(&self) -> impl Iterator<Item = (&Cached<T>, Discovery)> + '_ {
+        self.front.iter().zip(self.found.iter().copied())
+    }
+
+    // Add the nondominated solutions of `solutions` to the front, each found when `found` says
+    fn insert_front(&mut self, solutions: &[Cached<T>], found: impl Fn(usize) -> Discovery) {
+        let pareto = rank_nondominated(solutions);
+        for (i, rank) in pareto.ranks.into_iter().enumerate() {
+            if rank == 0 {
+                self.insert(&solutions[i], found(i));
+            }
+        }
+    }
+
+    // Add `ind` to the front unless it's dominated by or equal to a member,
+    // removing any members that it dominates.
+    fn insert(&mut self, ind: &Cached<T>, found: Discovery) {
+        let fit = ind.evaluate();
+        let rejected = self
+            .front
+            .iter()
+            .any(|member| member.evaluate() == fit || cmp_dom(ind, member) == DomOrdering::BOverA);
+        if !rejected {
+            let mut i = 0;
+            while i < self.front.len() {
+                if cmp_dom(&self.front[i], ind) == DomOrdering::BOverA {
+                    self.front.remove(i);
+                    self.found.remove(i);
+                } else {
+                    i += 1;
+                }
+            }
+            self.front.push(ind.clone());
+            self.found.push(found);
+        }
+    }
 }
 
 impl<T, const M: usize> Default for BestPareto<T, M>
@@ -173,7 +365,7 @@ where
     T: Solution<Fitness = MultiObjective<M>>,
 {
     fn default() -> Self {
-        BestPareto { front: Vec::new() }
+        BestPareto::new()
     }
 }
 
@@ -181,18 +373,27 @@ impl<T, const M: usize> HallOfFame<T> for BestPareto<T, M>
 where
     T: Solution<Fitness = MultiObjective<M>>,
 {
-    fn record(&mut self, generation: &[Cached<T>]) {
-        let pareto = rank_nondominated(generation);
-        for (ind, rank) in generation.iter().zip(pareto.ranks.into_iter()) {
-            if rank == 0 {
-                self.front.push(ind.clone());
-            }
+    fn record(&mut self, gen: usize, evaluations: u64, generation: &[Cached<T>]) {
+        let found = Discovery::new(gen, evaluations);
+        self.insert_front(generation, |_| found);
+    }
+
+    /// Re-evaluates the stored solutions, keeping only the ones that are still nondominated.
+    /// They keep when they were found.
+    fn environment_changed(&mut self) {
+        let mut old = core::mem::take(&mut self.front);
+        let found = core::mem::take(&mut self.found);
+        for ind in &mut old {
+            ind.clear_cache();
+        }
+        self.insert_front(&old, |i| found[i]);
+    }
+
+    /// Adds the solutions of `other` that are nondominated, along with when they were found.
+    fn merge(&mut self, other: Self) {
+        for (ind, found) in other.front.iter().zip(other.found) {
+            self.insert(ind, found);
         }
-        let pareto2 = rank_nondominated(&self.front);
-        let indices = (0..self.front.len())
-            .filter(|i| pareto2.ranks[*i] == 0)
-            .collect();
-        retain_indices(&mut self.front, indices);
     }
 }
 
@@ -214,14 +415,197 @@ where
     T: Solution<Fitness = MultiObjective<M>>,
     Cached<T>: Debug,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_list().entries(self.front.iter()).finish()
     }
 }
 
+/// Keeps the first nondominated front of every generation, as well as the globally nondominated solutions
+///
+/// This works like [`BestPareto`], but also takes a snapshot of each generation's own Pareto front
+/// before the population moves on, e.g. to animate how the front evolves over a run.
+/// Snapshots can be limited to every few generations with [`.with_interval()`],
+/// and to a number of solutions with [`.with_max_points()`], in which case the solutions
+/// with the largest crowding distance are kept so the snapshot still spans the whole front.
+/// Solutions with the same fitness as another in the same front are only kept once.
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use eviolite::prelude::*;
+/// use eviolite::hof::FrontHistory;
+/// # #[derive(Clone)]
+/// # struct Point([f64; 2]);
+/// # impl Solution for Point {
+/// #     type Fitness = MultiObjective<2>;
+/// #     fn generate() -> Self { Point(random()) }
+/// #     fn evaluate(&self) -> MultiObjective<2> { MultiObjective::new_unweighted([self.0[0], 1.0 - self.0[0] * self.0[1]]) }
+/// #     fn crossover(a: &mut Self, b: &mut Self) { std::mem::swap(&mut a.0[0], &mut b.0[0]) }
+/// #     fn mutate(&mut self) { self.0[1] = random() }
+/// # }
+///
+/// let evo: Evolution<Point, _, _, ()> = Evolution::new(
+///     alg::NSGA2::new(40, 0.8, 0.2),
+///     FrontHistory::new().with_interval(5).with_max_points(10),
+/// );
+/// let log = evo.run_for(20);
+/// for (gen, front) in log.hall_of_fame.snapshots() {
+///     assert!(gen % 5 == 0 && front.len() <= 10);
+/// }
+/// ```
+///
+/// [`BestPareto`]: ./struct.BestPareto.html
+/// [`.with_interval()`]: #method.with_interval
+/// [`.with_max_points()`]: #method.with_max_points
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "Cached<T>: serde::Serialize",
+        deserialize = "Cached<T>: serde::Deserialize<'de>"
+    ))
+)]
+pub struct FrontHistory<T, const M: usize>
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+{
+    best: BestPareto<T, M>,
+    snapshots: Vec<(usize, Vec<Cached<T>>)>,
+    interval: usize,
+    max_points: Option<usize>,
+}
+
+impl<T, const M: usize> FrontHistory<T, M>
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+{
+    /// Create a new `FrontHistory` that takes a snapshot of every generation.
+    pub fn new() -> Self {
+        FrontHistory {
+            best: BestPareto::new(),
+            snapshots: Vec::new(),
+            interval: 1,
+            max_points: None,
+        }
+    }
+
+    /// Only take a snapshot of the generations whose number is a multiple of `interval`.
+    pub fn with_interval(mut self, interval: usize) -> Self {
+        self.interval = interval.max(1);
+        self
+    }
+
+    /// Keep at most `max_points` solutions of each snapshot, preferring the least crowded ones.
+    pub fn with_max_points(mut self, max_points: usize) -> Self {
+        self.max_points = Some(max_points);
+        self
+    }
+
+    /// Get the snapshots that were taken, in order, each with the number of the generation it was taken of.
+    pub fn snapshots(&self) -> impl Iterator<Item = (usize, &[Cached<T>])> + '_ {
+        self.snapshots
+            .iter()
+            .map(|(gen, front)| (*gen, front.as_slice()))
+    }
+
+    /// Get a reference to the stored list of globally nondominated solutions, in arbitrary order.
+    pub fn front(&self) -> &[Cached<T>] {
+        self.best.front()
+    }
+
+    /// Iterate over the globally nondominated solutions, each with when it was found,
+    /// like [`BestPareto::entries()`].
+    ///
+    /// [`BestPareto::entries()`]: ./struct.BestPareto.html#method.entries
+    pub fn entries(&self) -> impl Iterator<Item = (&Cached<T>, Discovery)> + '_ {
+        self.best.entries()
+    }
+}
+
+impl<T, const M: usize> Default for FrontHistory<T, M>
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+{
+    fn default() -> Self {
+        FrontHistory::new()
+    }
+}
+
+impl<T, const M: usize> HallOfFame<T> for FrontHistory<T, M>
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+{
+    fn record(&mut self, gen: usize, evaluations: u64, generation: &[Cached<T>]) {
+        let pareto = rank_nondominated(generation);
+        let found = Discovery::new(gen, evaluations);
+        let mut front: Vec<usize> = Vec::new();
+        for (i, &rank) in pareto.ranks.iter().enumerate() {
+            if rank == 0 {
+                self.best.insert(&generation[i], found);
+                let fit = generation[i].evaluate();
+                if front.iter().all(|&j| generation[j].evaluate() != fit) {
+                    front.push(i);
+                }
+            }
+        }
+
+        if gen.is_multiple_of(self.interval) {
+            if let Some(max_points) = self.max_points {
+                if front.len() > max_points {
+                    sort_by_crowding_distance(&mut front, generation);
+                    front.truncate(max_points);
+                }
+            }
+            let snapshot = front.iter().map(|&i| generation[i].clone()).collect();
+            self.snapshots.push((gen, snapshot));
+        }
+    }
+
+    /// Re-evaluates the globally nondominated solutions, keeping only the ones that are still nondominated.
+    /// Snapshots keep the fitness values they were taken with.
+    fn environment_changed(&mut self) {
+        self.best.environment_changed();
+    }
+
+    /// Merges the globally nondominated solutions, and adds the snapshots of `other`,
+    /// which is taken to be a later segment of the same run: snapshots of this one
+    /// from the first generation of `other` on are replaced.
+    fn merge(&mut self, other: Self) {
+        self.best.merge(other.best);
+        if let Some(&(first, _)) = other.snapshots.first() {
+            self.snapshots.retain(|&(gen, _)| gen < first);
+        }
+        self.snapshots.extend(other.snapshots);
+    }
+}
+
+impl<T, const M: usize> IntoIterator for FrontHistory<T, M>
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+{
+    type Item = Cached<T>;
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.best.into_iter()
+    }
+}
+
+impl<T, const M: usize> Debug for FrontHistory<T, M>
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+    Cached<T>: Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FrontHistory")
+            .field("front", &self.best)
+            .field("snapshots", &self.snapshots)
+            .finish()
+    }
+}
+
 /// Iterator over the entries in a hall of fame
 pub struct IntoIter<T: Solution> {
-    inner: std::vec::IntoIter<Cached<T>>,
+    inner: alloc::vec::IntoIter<Cached<T>>,
 }
 
 impl<T: Solution> Iterator for IntoIter<T> {
@@ -256,11 +640,11 @@ mod tests {
     fn bestn_size_1() {
         let mut hof: BestN<One> = BestN::new(1);
 
-        hof.record(pop!(One, 1.0, 2.0, 3.0));
+        hof.record(0, 0, pop!(One, 1.0, 2.0, 3.0));
         assert_eq!(hof.best.len(), 1);
         assert_eq!(hof.best[0].evaluate(), 3.0);
 
-        hof.record(pop!(One, 1.5, 2.5, 3.5));
+        hof.record(1, 10, pop!(One, 1.5, 2.5, 3.5));
         assert_eq!(hof.best[0].evaluate(), 3.5);
     }
 
@@ -268,27 +652,51 @@ mod tests {
     fn bestn_size_3() {
         let mut hof: BestN<One> = BestN::new(3);
 
-        hof.record(pop!(One, 1.0, 2.0, 3.0, 4.0, 5.0));
+        hof.record(0, 0, pop!(One, 1.0, 2.0, 3.0, 4.0, 5.0));
         assert_eq!(hof.best.len(), 3);
         assert_eq!(hof.best[0].evaluate(), 5.0);
         assert_eq!(hof.best[1].evaluate(), 4.0);
         assert_eq!(hof.best[2].evaluate(), 3.0);
 
-        hof.record(pop!(One, 1.5, 2.5, 3.5, 4.5, 5.5));
+        hof.record(1, 10, pop!(One, 1.5, 2.5, 3.5, 4.5, 5.5));
         assert_eq!(hof.best.len(), 3);
         assert_eq!(hof.best[0].evaluate(), 5.5);
         assert_eq!(hof.best[1].evaluate(), 5.0);
         assert_eq!(hof.best[2].evaluate(), 4.5);
+
+        let gens: Vec<usize> = hof.entries().map(|(_, found)| found.generation()).collect();
+        assert_eq!(gens, [1, 0, 1]);
+        let evaluations: Vec<u64> = hof
+            .entries()
+            .map(|(_, found)| found.evaluations())
+            .collect();
+        assert_eq!(evaluations, [10, 0, 10]);
+        hof.environment_changed();
+        let gens: Vec<usize> = hof.entries().map(|(_, found)| found.generation()).collect();
+        assert_eq!(gens, [1, 0, 1]);
+    }
+
+    #[test]
+    fn bestn_minimize() {
+        let mut hof: BestN<Minimized<One>> = BestN::new(2);
+
+        hof.record(0, 0, pop!(Minimized, One(3.0), One(1.0), One(2.0)));
+        assert_eq!(hof.best[0].evaluate(), 1.0);
+        assert_eq!(hof.best[1].evaluate(), 2.0);
+
+        hof.record(1, 10, pop!(Minimized, One(1.5), One(0.5)));
+        assert_eq!(hof.best[0].evaluate(), 0.5);
+        assert_eq!(hof.best[1].evaluate(), 1.0);
     }
 
     #[test]
     fn bestpareto() {
         let mut hof: BestPareto<Foo, 2> = BestPareto::new();
 
-        hof.record(pop!(Foo, [1.0, 0.0], [0.0, 1.0], [0.5, 0.5]));
+        hof.record(0, 0, pop!(Foo, [1.0, 0.0], [0.0, 1.0], [0.5, 0.5]));
         assert_eq!(hof.front.len(), 3);
 
-        hof.record(pop!(Foo, [0.6, 0.6], [0.7, 0.7]));
+        hof.record(1, 10, pop!(Foo, [0.6, 0.6], [0.7, 0.7]));
         assert_eq!(hof.front.len(), 3);
 
         assert!(hof.front.contains(&Cached::new(Foo([0.7, 0.7]))));
@@ -297,5 +705,96 @@ mod tests {
 
         assert!(!hof.front.contains(&Cached::new(Foo([0.5, 0.5]))));
         assert!(!hof.front.contains(&Cached::new(Foo([0.6, 0.6]))));
+
+        // survivors recorded again aren't duplicated, and keep when they were found
+        hof.record(2, 20, pop!(Foo, [0.7, 0.7], [1.0, 0.0], [0.2, 0.2]));
+        assert_eq!(hof.front.len(), 3);
+        for (ind, found) in hof.entries() {
+            let expected = if ind.evaluate()[0] == 0.7 { 1 } else { 0 };
+            assert_eq!(found.generation(), expected);
+        }
+    }
+
+    #[test]
+    fn front_history() {
+        let mut hof: FrontHistory<Foo, 2> = FrontHistory::new().with_interval(2).with_max_points(3);
+        hof.record(
+            0,
+            0,
+            pop!(
+                Foo,
+                [1.0, 0.0],
+                [0.0, 1.0],
+                [0.5, 0.5],
+                [0.5, 0.5],
+                [0.2, 0.2]
+            ),
+        );
+        hof.record(1, 10, pop!(Foo, [0.6, 0.6], [0.7, 0.7]));
+        hof.record(
+            2,
+            20,
+            pop!(
+                Foo,
+                [2.0, 0.0],
+                [1.5, 0.5],
+                [1.0, 1.0],
+                [0.9, 1.1],
+                [0.0, 2.0]
+            ),
+        );
+
+        let snapshots: Vec<(usize, &[Cached<Foo>])> = hof.snapshots().collect();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].0, 0);
+        assert_eq!(snapshots[0].1.len(), 3);
+        assert_eq!(snapshots[1].0, 2);
+        let thinned = snapshots[1].1;
+        assert_eq!(thinned.len(), 3);
+        assert!(thinned.contains(&Cached::new(Foo([2.0, 0.0]))));
+        assert!(thinned.contains(&Cached::new(Foo([0.0, 2.0]))));
+        assert_eq!(hof.front().len(), 5);
+
+        let mut later: FrontHistory<Foo, 2> = FrontHistory::new();
+        later.record(2, 20, pop!(Foo, [3.0, 3.0]));
+        hof.merge(later);
+        let gens: Vec<usize> = hof.snapshots().map(|(gen, _)| gen).collect();
+        assert_eq!(gens, [0, 2]);
+        assert_eq!(hof.snapshots().last().unwrap().1.len(), 1);
+        assert_eq!(hof.front().len(), 1);
+    }
+
+    #[test]
+    fn merging_skips_duplicates() {
+        let mut hof: BestN<One> = BestN::new(4);
+        hof.record(0, 0, pop!(One, 1.0, 2.0));
+        let mut other: BestN<One> = BestN::new(4);
+        other.record(5, 50, pop!(One, 2.0, 3.0, 0.5));
+        hof.merge(other);
+        let fitness: Vec<f64> = hof.iter().map(|ind| ind.evaluate().into()).collect();
+        assert_eq!(fitness, [3.0, 2.0, 1.0, 0.5]);
+        let gens: Vec<usize> = hof.entries().map(|(_, found)| found.generation()).collect();
+        assert_eq!(gens, [5, 0, 0, 5]);
+
+        let mut front: BestPareto<Foo, 2> = BestPareto::new();
+        front.record(0, 0, pop!(Foo, [1.0, 0.0], [0.5, 0.5]));
+        let mut other: BestPareto<Foo, 2> = BestPareto::new();
+        other.record(1, 10, pop!(Foo, [0.5, 0.5], [0.6, 0.6], [0.0, 1.0]));
+        front.merge(other);
+        assert_eq!(front.front.len(), 3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let mut hof: BestPareto<Foo, 2> = BestPareto::new();
+        hof.record(0, 0, pop!(Foo, [1.0, 0.0], [0.0, 1.0]));
+
+        let json = serde_json::to_string(&hof).unwrap();
+        assert!(json.contains(r#"{"solution":[1.0,0.0],"fitness":[1.0,0.0]}"#));
+        let loaded: BestPareto<Foo, 2> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.front, hof.front);
+        let (_, fitness) = loaded.front[1].clone().into_inner();
+        assert_eq!(fitness.map(|fit| fit[1]), Some(1.0));
     }
 }
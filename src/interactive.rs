@@ -0,0 +1,314 @@
+//! Evaluating solutions by asking a person
+//!
+//! In interactive evolution, fitness comes from someone looking at candidates — designs,
+//! melodies, images — and judging them, which can't be done inside [`Solution::evaluate`].
+//! [`channel()`] creates the two ends of the conversation instead:
+//! an [`Interactive`] evaluator that the run uses through [`Evolution::with_interactive()`],
+//! and a [`Judge`] that the user interface holds on another thread.
+//!
+//! Whenever a generation needs evaluating, the solutions that don't have a fitness value yet
+//! are sent to the judge in [`Batch`]es, and the run blocks until every batch is answered,
+//! either with a score for each candidate or with pairwise preferences between them.
+//! Batches are buffered in the channel, so the judge can take them whenever it's ready.
+//! Fitness values are cached like any others, so solutions that survive to the next generation
+//! aren't shown again.
+//!
+//! ```
+//! use std::thread;
+//! use eviolite::prelude::*;
+//! use eviolite::interactive;
+//! # #[derive(Clone, Debug)]
+//! # struct Color([u8; 3]);
+//! # impl Solution for Color {
+//! #     type Fitness = f64;
+//! #     fn generate() -> Self { Color(random()) }
+//! #     fn evaluate(&self) -> f64 { 0.0 }
+//! #     fn crossover(a: &mut Self, b: &mut Self) { std::mem::swap(&mut a.0[0], &mut b.0[0]) }
+//! #     fn mutate(&mut self) { self.0[1] = random() }
+//! # }
+//!
+//! let (evaluator, judge) = interactive::channel::<Color>();
+//! let ui = thread::spawn(move || {
+//!     while let Some(batch) = judge.next_batch() {
+//!         // show `batch.candidates()` and collect a rating for each of them
+//!         let ratings = batch.candidates().iter().map(|color| color.0[0] as f64).collect();
+//!         batch.score(ratings);
+//!     }
+//! });
+//!
+//! let evo: Evolution<Color, _, _, ()> = Evolution::new(
+//!     alg::Simple::new(9, 0.5, 0.3, select::Tournament::new(2)),
+//!     hof::BestN::new(1),
+//! )
+//! .with_interactive(evaluator.with_batch_size(9));
+//! evo.run_for(5);
+//! ui.join().unwrap();
+//! ```
+//!
+//! If the judge hangs up, drops a batch without answering it, or takes longer than the
+//! [timeout](./struct.Interactive.html#method.with_timeout), the unanswered solutions
+//! fall back to [`Solution::evaluate`], which can be a heuristic or a constant.
+//!
+//! [`Solution::evaluate`]: ../trait.Solution.html#tymethod.evaluate
+//! [`channel()`]: ./fn.channel.html
+//! [`Interactive`]: ./struct.Interactive.html
+//! [`Evolution::with_interactive()`]: ../struct.Evolution.html#method.with_interactive
+//! [`Judge`]: ./struct.Judge.html
+//! [`Batch`]: ./struct.Batch.html
+
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    time::Duration,
+};
+
+use crate::{fitness::Direction, Cached, Solution};
+
+/// Create the two ends of interactive evaluation.
+///
+/// See the [module-level documentation](./index.html) for how to use them.
+pub fn channel<T: Solution>() -> (Interactive<T>, Judge<T>) {
+    let (batches, receiver) = mpsc::channel();
+    (
+        Interactive {
+            batches,
+            batch_size: None,
+            timeout: None,
+        },
+        Judge { batches: receiver },
+    )
+}
+
+/// The evaluating end of interactive evaluation, which sends solutions to a [`Judge`]
+///
+/// [`Judge`]: ./struct.Judge.html
+pub struct Interactive<T: Solution> {
+    batches: Sender<Batch<T>>,
+    batch_size: Option<usize>,
+    timeout: Option<Duration>,
+}
+
+impl<T: Solution> Interactive<T>
+where
+    T::Fitness: From<f64>,
+{
+    /// Send at most `batch_size` solutions in each batch.
+    ///
+    /// By default, all the solutions of a generation that need evaluating are sent in one batch.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(batch_size.max(1));
+        self
+    }
+
+    /// Stop waiting for a batch to be answered after `timeout`,
+    /// and evaluate its solutions with [`Solution::evaluate`] instead.
+    ///
+    /// By default, the run waits for as long as it takes.
+    ///
+    /// [`Solution::evaluate`]: ../trait.Solution.html#tymethod.evaluate
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Have the judge evaluate every solution in a population that hasn't been evaluated yet,
+    /// blocking until every batch has been answered.
+    ///
+    /// The fitness values are cached and counted by [`evaluation_count()`]
+    /// just like they would be by [`par_evaluate()`].
+    ///
+    /// [`evaluation_count()`]: ../fitness/fn.evaluation_count.html
+    /// [`par_evaluate()`]: ../fitness/fn.par_evaluate.html
+    pub fn evaluate(&mut self, pop: &[Cached<T>]) {
+        let pending: Vec<&Cached<T>> = pop.iter().filter(|ind| ind.fitness().is_none()).collect();
+        if pending.is_empty() {
+            return;
+        }
+
+        // All batches are sent up front, so the judge never waits on the run
+        let batch_size = self.batch_size.unwrap_or(pending.len());
+        let answers: Vec<Receiver<Vec<f64>>> = pending
+            .chunks(batch_size)
+            .map(|chunk| {
+                let (answer, receiver) = mpsc::channel();
+                let batch = Batch {
+                    candidates: chunk.iter().map(|ind| ind.as_ref().clone()).collect(),
+                    answer,
+                };
+                // if the judge is gone, the batch is dropped and the receiver reports it
+                let _ = self.batches.send(batch);
+                receiver
+            })
+            .collect();
+
+        for (chunk, receiver) in pending.chunks(batch_size).zip(answers) {
+            let scores = match self.timeout {
+                Some(timeout) => receiver.recv_timeout(timeout).ok(),
+                None => receiver.recv().ok(),
+            };
+            match scores {
+                Some(scores) => {
+                    for (ind, score) in chunk.iter().zip(scores) {
+                        let _ = ind.set_fitness(score.into());
+                    }
+                }
+                None => {
+                    for ind in chunk {
+                        ind.evaluate();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The judging end of interactive evaluation, which receives the [`Batch`]es to judge
+///
+/// [`Batch`]: ./struct.Batch.html
+pub struct Judge<T: Solution> {
+    batches: Receiver<Batch<T>>,
+}
+
+impl<T: Solution> Judge<T> {
+    /// Wait for the next batch of solutions to judge.
+    ///
+    /// Returns `None` once the [`Interactive`] end has been dropped, e.g. because the run finished.
+    ///
+    /// [`Interactive`]: ./struct.Interactive.html
+    pub fn next_batch(&self) -> Option<Batch<T>> {
+        self.batches.recv().ok()
+    }
+
+    /// Get the next batch of solutions to judge if there is one, without waiting.
+    pub fn try_next_batch(&self) -> Option<Batch<T>> {
+        self.batches.try_recv().ok()
+    }
+}
+
+/// A batch of solutions waiting to be judged
+///
+/// Answer it with either [`.score()`] or [`.prefer()`]. Dropping it without answering
+/// makes its solutions fall back to [`Solution::evaluate`].
+///
+/// [`.score()`]: #method.score
+/// [`.prefer()`]: #method.prefer
+/// [`Solution::evaluate`]: ../trait.Solution.html#tymethod.evaluate
+pub struct Batch<T: Solution> {
+    candidates: Vec<T>,
+    answer: Sender<Vec<f64>>,
+}
+
+impl<T: Solution> Batch<T> {
+    /// Get the solutions to judge.
+    pub fn candidates(&self) -> &[T] {
+        &self.candidates
+    }
+
+    /// Answer with a score for each candidate, in order, which becomes its fitness.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if there isn't exactly one score per candidate.
+    pub fn score(self, scores: Vec<f64>) {
+        assert_eq!(
+            scores.len(),
+            self.candidates.len(),
+            "there must be exactly one score per candidate"
+        );
+        let _ = self.answer.send(scores);
+    }
+
+    /// Answer with pairwise preferences, each a pair of the indices of the candidate that was
+    /// preferred and the one it was preferred over.
+    ///
+    /// Each candidate's score is the number of times it was preferred minus the number of times
+    /// another candidate was preferred over it, negated if [`Solution::DIRECTIONS`] says
+    /// to minimize. Candidates that weren't compared at all get a score of 0.
+    /// Not every pair has to be compared, but the fewer comparisons there are,
+    /// the less the scores mean.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if an index is out of range.
+    ///
+    /// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+    pub fn prefer(self, preferences: &[(usize, usize)]) {
+        let sign = match Direction::of::<T>(0) {
+            Direction::Maximize => 1.0,
+            Direction::Minimize => -1.0,
+        };
+        let mut scores = vec![0.0; self.candidates.len()];
+        for &(winner, loser) in preferences {
+            scores[winner] += sign;
+            scores[loser] -= sign;
+        }
+        let _ = self.answer.send(scores);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::{alg, hof, select, testutils::Walk, Evolution};
+
+    #[test]
+    fn judged_by_scores_and_preferences() {
+        let (mut evaluator, judge) = channel::<Walk>();
+        let ui = thread::spawn(move || {
+            let mut batches = 0;
+            while let Some(batch) = judge.next_batch() {
+                batches += 1;
+                if batches % 2 == 0 {
+                    // prefer every candidate over the ones to its right
+                    let n = batch.candidates().len();
+                    let pairs: Vec<(usize, usize)> = (0..n)
+                        .flat_map(|a| (a + 1..n).map(move |b| (a, b)))
+                        .collect();
+                    batch.prefer(&pairs);
+                } else {
+                    let scores = batch.candidates().iter().map(|w| w.0).collect();
+                    batch.score(scores);
+                }
+            }
+            batches
+        });
+
+        let pop: Vec<Cached<Walk>> = (0..5).map(|i| Cached::new(Walk(i as f64))).collect();
+        evaluator = evaluator.with_batch_size(3);
+        evaluator.evaluate(&pop);
+        let fitness: Vec<f64> = pop.iter().map(Cached::fitness_unchecked).collect();
+        assert_eq!(fitness, [0.0, 1.0, 2.0, 1.0, -1.0]);
+
+        // already evaluated solutions aren't sent again
+        evaluator.evaluate(&pop);
+        drop(evaluator);
+        assert_eq!(ui.join().unwrap(), 2);
+    }
+
+    #[test]
+    fn unanswered_batches_fall_back() {
+        let (evaluator, judge) = channel::<Walk>();
+        let ui = thread::spawn(move || {
+            while let Some(batch) = judge.next_batch() {
+                drop(batch);
+            }
+        });
+        let evo: Evolution<Walk, _, _, ()> = Evolution::new(
+            alg::Simple::new(10, 0.5, 0.3, select::Tournament::new(2)),
+            hof::BestN::new(1),
+        )
+        .with_interactive(evaluator);
+        let log = evo.run_for(3);
+        let best = &log.hall_of_fame[0];
+        assert_eq!(best.fitness_unchecked(), best.as_ref().evaluate());
+        ui.join().unwrap();
+
+        // a judge that has hung up entirely
+        let (mut evaluator, judge) = channel::<Walk>();
+        drop(judge);
+        let pop: Vec<Cached<Walk>> = (0..3).map(|_| Cached::generate()).collect();
+        evaluator.evaluate(&pop);
+        assert!(pop.iter().all(|ind| ind.fitness().is_some()));
+    }
+}
@@ -1,3 +1,4 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![warn(missing_docs)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
@@ -14,21 +15,106 @@
 //!
 //! The general workflow is to implement [`Solution`] for a type you wish to optimize,
 //! construct an instance of [`Evolution`], and call one of its `run_` methods.
+//! Fitness is maximized by default; objectives that should be minimized are declared
+//! with [`Solution::DIRECTIONS`] rather than by negating them.
+//! If your representation needs to be decoded into something else before it can be evaluated,
+//! implement [`Decode`] instead and use [`Decoded`] as your solution type,
+//! which keeps the decoded phenotype around for reporting.
+//! Other values derived from a solution, like a behavior descriptor, can be kept around
+//! the same way by implementing [`Memo`] and wrapping the solution in [`Memoized`].
 //!
 //! Features
 //! ========
+//! The `std` crate feature, which is enabled by default, can be disabled to use the crate
+//! without the standard library, e.g. on a microcontroller, as long as a global allocator is available.
+//! What remains is the core of the crate: [`Solution`], [`Cached`], [`Evolution`],
+//! the [algorithms][alg], [selection operators][select], and [halls of fame][hof],
+//! and the [`GenerationStats`][stats::GenerationStats] trait without its built-in implementors.
+//! The reproducible RNG has to be seeded with [`repro_rng::reseed`] before the first run,
+//! since there's no environment variable or OS randomness to seed it from.
+//! Every other feature requires `std`.
+//!
 //! The `ndarray` crate feature enables the [`crossover`] and [`mutation`] modules,
 //! which contain helpful functions for using Eviolite alongside the [`ndarray`] crate.
 //!
+//! The `derive` crate feature enables `#[derive(Solution)]` for structs made of [`genome`] types.
+//!
+//! The `serde` crate feature implements [`serde`]'s `Serialize` and `Deserialize` for [`Log`],
+//! [`Cached`] (including its cached fitness), [`MultiObjective`], and the built-in
+//! [halls of fame][hof] and [statistics][stats], so the results of a run can be saved and loaded.
+//!
+//! The `checkpoint` crate feature enables the [`checkpoint`] module, which saves runs
+//! to compact binary checkpoints that can be resumed later. It also enables `serde`.
+//!
+//! The `distributed` crate feature enables the [`distributed`] module, which evaluates
+//! populations on worker processes on other machines over TCP. It also enables `serde`.
+//!
+//! The `parquet` crate feature enables writing per-generation statistics to Parquet files
+//! and Arrow IPC streams; see the [`export`] module.
+//!
+//! The `tensorboard` crate feature enables writing per-generation scalars to TensorBoard event files;
+//! see the [`export`] module.
+//!
+//! The `plotters` crate feature enables plotting a run's convergence to PNG or SVG images;
+//! see the [`export`] module.
+//!
+//! The `indicatif` crate feature enables a progress bar for runs in the [`observer`] module.
+//!
+//! The `pyo3` crate feature enables the [`python`] module, which contains Python bindings.
+//!
+//! The `parallel` crate feature, which is enabled by default, evaluates populations
+//! on [`rayon`]'s thread pool. Without it, everything runs on the current thread.
+//!
+//! The `js` crate feature allows the reproducible RNG to seed itself on `wasm32-unknown-unknown`,
+//! using the browser's random number generator.
+//! See the [`repro_rng`] module for more details.
+//! To build for that target, which has no threads, disable the default features:
+//! `eviolite = { version = "0.1", default-features = false, features = ["js"] }`.
+//!
 //! [`.run()`]: ./struct.Evolution.html#method.run
+//! [`Solution::DIRECTIONS`]: ./trait.Solution.html#associatedconstant.DIRECTIONS
+//! [`MultiObjective`]: ./fitness/struct.MultiObjective.html
+
+extern crate alloc;
 
 pub mod alg;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod benchmarks;
+#[cfg(feature = "checkpoint")]
+#[cfg_attr(docsrs, doc(cfg(feature = "checkpoint")))]
+pub mod checkpoint;
+pub mod coevolution;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod credit;
+#[cfg(feature = "distributed")]
+#[cfg_attr(docsrs, doc(cfg(feature = "distributed")))]
+pub mod distributed;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod export;
 pub mod fitness;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod genome;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod gp;
 pub mod hof;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod interactive;
+pub mod observer;
 pub mod prelude;
 pub mod repro_rng;
 pub mod select;
+pub mod species;
 pub mod stats;
+pub mod termination;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod watch;
 
 #[cfg(feature = "ndarray")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ndarray")))]
@@ -36,19 +122,160 @@ pub mod crossover;
 #[cfg(feature = "ndarray")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ndarray")))]
 pub mod mutation;
+#[cfg(feature = "pyo3")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pyo3")))]
+pub mod python;
 
 mod utils;
 
-pub use utils::Cached;
+pub use utils::{Cached, CachedMut};
+#[cfg(feature = "std")]
+pub use utils::{Decode, Decoded, FnConfig, FnSolution, Memo, Memoized};
 
+/// Derive [`Solution`] for a struct made of [genomes].
+///
+/// Every field must implement [`Genome`], and is generated, crossed over, and mutated
+/// independently using its own configuration. The fitness type and evaluation function
+/// are given with attributes on the struct itself:
+///
+/// - `#[solution(fitness = T)]` sets the [`Fitness`](trait.Solution.html#associatedtype.Fitness) type.
+/// - `#[solution(evaluate = f)]` sets the function used to evaluate a solution,
+///   which must be callable as `f(&self) -> T`.
+/// - `#[solution(directions = [d, ...])]` optionally sets [`DIRECTIONS`](trait.Solution.html#associatedconstant.DIRECTIONS),
+///   the [`Direction`](fitness/enum.Direction.html) in which each objective is optimized.
+/// - `#[solution(dominance_tolerance = x)]` optionally sets
+///   [`DOMINANCE_TOLERANCE`](trait.Solution.html#associatedconstant.DOMINANCE_TOLERANCE).
+/// - `#[solution(repair = f)]` optionally sets the function used to [repair](trait.Solution.html#method.repair)
+///   a solution, which must be callable as `f(&mut self)`.
+///
+/// Each field's configuration is given with attributes on that field:
+///
+/// - `#[solution(config = expr)]` uses the result of `expr` as the field's configuration.
+/// - `#[solution(len = n, bounds = b, crossover = c, mutation = m)]` builds the configuration
+///   for one of the vector genomes through [`VectorConfig`].
+///   `bounds` is left out for [`Permutation`] and [`BitString`].
+/// - `#[solution(cxpb = p)]` and `#[solution(mutpb = p)]` only apply crossover or mutation to the
+///   field with probability `p` each time the whole solution is crossed over or mutated.
+///   By default, every field is always varied.
+/// - `#[solution(skip)]` leaves a field out entirely. It will be set to [`Default::default()`]
+///   when a solution is generated, and never changed afterward.
+///
+/// Configurations are built once, the first time they are needed, and shared for the rest of the program.
+/// The derive macro can't be used on generic structs.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::genome::{permutation::*, real::*, Permutation, RealVector};
+///
+/// #[derive(Clone, Solution)]
+/// #[solution(fitness = f64, evaluate = Schedule::cost, directions = [Direction::Minimize])]
+/// struct Schedule {
+///     #[solution(len = 5, crossover = PermutationCrossover::Ordered, mutation = PermutationMutation::Inversion)]
+///     order: Permutation,
+///     #[solution(
+///         len = 5,
+///         bounds = (0.0, 1.0),
+///         crossover = RealCrossover::Blend { alpha: 0.5 },
+///         mutation = RealMutation::Gaussian { sigma: 0.1, indpb: 0.2 },
+///         mutpb = 0.5,
+///     )]
+///     durations: RealVector,
+/// }
+///
+/// impl Schedule {
+///     fn cost(&self) -> f64 {
+///         // prefer short tasks early
+///         self.order.iter().enumerate().map(|(i, &t)| i as f64 * self.durations[t]).sum::<f64>()
+///     }
+/// }
+///
+/// let evo: Evolution<Schedule, _, _, ()> = Evolution::new(
+///     alg::Simple::new(20, 0.5, 0.3, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// );
+/// evo.run_for(5);
+/// ```
+///
+/// Any expression can be used as a configuration, and fields that aren't genomes can be skipped:
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::genome::{bits::*, BitString};
+///
+/// #[derive(Clone, Solution)]
+/// #[solution(fitness = f64, evaluate = Knapsack::value, repair = Knapsack::fit)]
+/// struct Knapsack {
+///     #[solution(
+///         config = BitStringConfig { len: 8, crossover: BitCrossover::TwoPoint, mutation: BitMutation::FlipOne },
+///         cxpb = 0.0,
+///     )]
+///     items: BitString,
+///     #[solution(skip)]
+///     repairs: u32,
+/// }
+///
+/// impl Knapsack {
+///     fn value(&self) -> f64 {
+///         self.items.iter().enumerate().filter(|(_, &taken)| taken).map(|(i, _)| i as f64).sum()
+///     }
+///
+///     // take at most 3 items
+///     fn fit(&mut self) {
+///         while self.items.count_ones() > 3 {
+///             let first = self.items.iter().position(|&taken| taken).unwrap();
+///             self.items.flip(first);
+///             self.repairs += 1;
+///         }
+///     }
+/// }
+///
+/// let mut knapsack = Knapsack::generate();
+/// assert_eq!((knapsack.items.len(), knapsack.repairs), (8, 0));
+///
+/// // crossover never happens with `cxpb = 0.0`
+/// let (mut a, mut b) = (knapsack.clone(), Knapsack::generate());
+/// Knapsack::crossover(&mut a, &mut b);
+/// assert_eq!(a.items.as_ref(), knapsack.items.as_ref());
+///
+/// knapsack.items = BitString::new(vec![true; 8]);
+/// knapsack.repair();
+/// assert_eq!((knapsack.items.count_ones(), knapsack.repairs), (3, 5));
+/// assert_eq!(knapsack.evaluate(), 5.0 + 6.0 + 7.0);
+/// ```
+///
+/// [`Solution`]: trait.Solution.html
+/// [genomes]: genome/index.html
+/// [`Genome`]: genome/trait.Genome.html
+/// [`VectorConfig`]: genome/trait.VectorConfig.html
+/// [`Permutation`]: genome/struct.Permutation.html
+/// [`BitString`]: genome/struct.BitString.html
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use eviolite_derive::Solution;
+
+#[cfg(test)]
 pub(crate) mod testutils;
 
+use alloc::vec::Vec;
+use core::{ops::ControlFlow, time::Duration};
+
+use rand::Rng;
+
 use alg::Algorithm;
 use fitness::par_evaluate;
 use hof::HallOfFame;
 use stats::GenerationStats;
 use utils::NFromFunction;
 
+type EvaluateFn<T> = alloc::boxed::Box<dyn FnMut(&[Cached<T>])>;
+type UniqueFn<T> = alloc::boxed::Box<dyn FnMut(&mut [Cached<T>])>;
+type InterventionFn<T> = alloc::boxed::Box<dyn FnMut(usize, &mut Vec<Cached<T>>)>;
+type CheckpointFn<T, Hof, Stat> = alloc::boxed::Box<dyn FnMut(Generation<T, Hof, Stat>)>;
+// Finds the best solution of a population, returning its index and fitness
+type BestFn<T> = alloc::boxed::Box<dyn Fn(&[Cached<T>]) -> Option<(usize, f64)>>;
+type StatsSinkBox<S> = alloc::boxed::Box<dyn stats::StatsSink<S>>;
+type ObserverBox<T, Hof, Stat> = alloc::boxed::Box<dyn observer::Observe<T, Hof, Stat>>;
+
 /// A trait that allows a type to be optimized using an evolutionary algorithm.
 ///
 /// The entire crate is generic over this trait; you must implement it on a type to get anything done.
@@ -57,8 +284,50 @@ pub trait Solution: Clone + Sync {
     /// For most applications, you'll want to use `f64` or [`MultiObjective`] for this,
     /// but you can use any type you want.
     ///
+    /// It has to be `Send` and `Sync` so that [`Cached`] solutions can be shared between threads.
+    ///
+    /// [`MultiObjective`]: ./fitness/struct.MultiObjective.html
+    /// [`Cached`]: ./struct.Cached.html
+    type Fitness: Copy + Send + Sync;
+
+    /// The direction in which each objective of the fitness is optimized, in order.
+    ///
+    /// Objectives that aren't listed are maximized, so the default of an empty slice
+    /// maximizes everything. For a fitness that is a single number, such as `f64`,
+    /// only the first direction is used. This is also the direction used for a [`MultiObjective`]
+    /// whose objectives are combined into one number, e.g. by [`Tournament`].
+    ///
+    /// ```
+    /// use eviolite::prelude::*;
+    ///
+    /// #[derive(Clone)]
+    /// struct Route(Vec<usize>);
+    ///
+    /// impl Solution for Route {
+    ///     // total distance and number of turns
+    ///     type Fitness = MultiObjective<2>;
+    ///     const DIRECTIONS: &'static [Direction] = &[Direction::Minimize, Direction::Minimize];
+    ///     # fn generate() -> Self { Route(vec![0, 1, 2]) }
+    ///     # fn evaluate(&self) -> Self::Fitness { MultiObjective::new_unweighted([self.0[0] as f64, 1.0]) }
+    ///     # fn crossover(_: &mut Self, _: &mut Self) {}
+    ///     # fn mutate(&mut self) { self.0.swap(0, 1) }
+    ///     // ...
+    /// }
+    /// ```
+    ///
     /// [`MultiObjective`]: ./fitness/struct.MultiObjective.html
-    type Fitness: Copy;
+    /// [`Tournament`]: ./select/struct.Tournament.html
+    const DIRECTIONS: &'static [fitness::Direction] = &[];
+
+    /// How far apart two values of an objective can be while still counting as equal
+    /// when comparing solutions for dominance, e.g. by [`rank_nondominated()`].
+    ///
+    /// The default of 0 only treats exactly equal values as equal, which is right for objectives
+    /// with discrete values such as counts. A small tolerance keeps solutions whose objectives only
+    /// differ by rounding errors from dominating each other.
+    ///
+    /// [`rank_nondominated()`]: ./select/fn.rank_nondominated.html
+    const DOMINANCE_TOLERANCE: f64 = 0.0;
 
     /// Randomly generate a new solution.
     fn generate() -> Self;
@@ -87,6 +356,35 @@ pub trait Solution: Clone + Sync {
     /// of the solution is different afterward, but drastically enough
     /// that it will produce notably different results when evaluated.
     fn mutate(&mut self);
+
+    /// Repair operator, which moves a solution that violates the problem's constraints
+    /// back into the feasible region, e.g. by clamping values to their bounds.
+    ///
+    /// [`Cached`] calls this after every crossover and mutation, before the changed solution
+    /// is evaluated, so the variation operators don't have to keep solutions feasible themselves,
+    /// and solutions that weren't changed are never repaired.
+    /// Repairing a solution that is already feasible should leave it unchanged.
+    ///
+    /// The default implementation does nothing.
+    ///
+    /// [`Cached`]: ./struct.Cached.html
+    fn repair(&mut self) {}
+
+    /// Estimate how long it will take to evaluate this solution, in any unit as long as it's consistent.
+    ///
+    /// When evaluation times vary a lot between solutions, [`par_evaluate()`] starts with the most costly
+    /// solutions so that the threads finish at about the same time, instead of some threads waiting
+    /// for a long evaluation that was started last. The estimate doesn't need to be precise,
+    /// e.g. the size of a genome is often a good enough estimate.
+    ///
+    /// The default implementation returns `None`, in which case [`Cached`] uses the time taken by the last
+    /// evaluation before the solution was changed, which for offspring is usually their parent's.
+    ///
+    /// [`par_evaluate()`]: ./fitness/fn.par_evaluate.html
+    /// [`Cached`]: ./struct.Cached.html
+    fn evaluation_cost(&self) -> Option<f64> {
+        None
+    }
 }
 
 /// A single run of an evolutionary algorithm.
@@ -98,10 +396,38 @@ where
     Stat: GenerationStats<T>,
 {
     population: Vec<Cached<T>>,
+    scratch: Vec<Cached<T>>,
     algorithm: Alg,
     hall_of_fame: Hof,
-    stats: Vec<Stat>,
+    stats_collector: Stat,
+    stats: Vec<Stat::Output>,
+    stats_sink: Option<StatsSinkBox<Stat::Output>>,
+    elapsed: Vec<Duration>,
+    best_of: Option<BestFn<T>>,
+    keep_best_solutions: bool,
+    best_fitness: Vec<f64>,
+    best_solutions: Vec<Cached<T>>,
     reset_interval: usize,
+    first_gen: usize,
+    #[cfg(feature = "parallel")]
+    thread_pool: Option<std::sync::Arc<rayon::ThreadPool>>,
+    evaluator: Option<EvaluateFn<T>>,
+    unique: Option<UniqueFn<T>>,
+    intervention: Option<InterventionFn<T>>,
+    checkpointer: Option<CheckpointFn<T, Hof, Stat>>,
+    watcher: Option<CheckpointFn<T, Hof, Stat>>,
+    observers: Vec<ObserverBox<T, Hof, Stat>>,
+    change_interval: usize,
+    hypermutation: f64,
+    // The evaluations of the current run, counted while it's entered on a thread
+    #[cfg(feature = "std")]
+    evaluation_counter: std::sync::Arc<utils::counter::EvalCounter>,
+    // Without `std`, the evaluation count when the run started
+    #[cfg(not(feature = "std"))]
+    evaluations_start: u64,
+    // When the current generation started, for timing it
+    #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+    generation_start: std::time::Instant,
 }
 
 impl<T, Alg, Hof, Stat> Evolution<T, Alg, Hof, Stat>
@@ -112,14 +438,21 @@ where
     Stat: GenerationStats<T>,
 {
     /// Create a new [`Evolution`] with the specified algorithm and hall of fame.
-    pub fn new(algorithm: Alg, hall_of_fame: Hof) -> Self {
-        Evolution {
-            population: Vec::n_from_function(algorithm.pop_size(), Cached::generate),
+    ///
+    /// The statistics are collected by `Stat::default()`; see [`.with_stats()`] to configure them.
+    ///
+    /// [`.with_stats()`]: ./struct.Evolution.html#method.with_stats
+    pub fn new(algorithm: Alg, hall_of_fame: Hof) -> Self
+    where
+        Stat: Default,
+    {
+        Self::from_parts(
+            Vec::n_from_function(algorithm.pop_size(), Cached::generate),
             algorithm,
             hall_of_fame,
-            stats: Vec::new(),
-            reset_interval: 0,
-        }
+            0,
+            0,
+        )
     }
 
     /// Create a new [`Evolution`] that will completely reset and re-generate its population
@@ -128,16 +461,531 @@ where
     /// tracking the best individuals across all resets.
     /// If you find yourself running your program over and over again hoping for a better result,
     /// consider using this feature to combine them all into one run.
-    pub fn with_resets(algorithm: Alg, hall_of_fame: Hof, reset_interval: usize) -> Self {
+    pub fn with_resets(algorithm: Alg, hall_of_fame: Hof, reset_interval: usize) -> Self
+    where
+        Stat: Default,
+    {
+        Self::from_parts(
+            Vec::n_from_function(algorithm.pop_size(), Cached::generate),
+            algorithm,
+            hall_of_fame,
+            reset_interval,
+            0,
+        )
+    }
+
+    /// Resume a run from a [`Checkpoint`] with the specified algorithm,
+    /// which should be configured the same way as the one in the original run.
+    ///
+    /// This restores the state of the current thread's [reproducible RNG] and advances
+    /// the population by one generation, so the first generation of the resumed run
+    /// is the one after the checkpoint, numbered accordingly.
+    ///
+    /// [`Checkpoint`]: ./checkpoint/struct.Checkpoint.html
+    /// [reproducible RNG]: ./repro_rng/index.html
+    #[cfg(feature = "checkpoint")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "checkpoint")))]
+    pub fn resume(algorithm: Alg, checkpoint: checkpoint::Checkpoint<T, Hof>) -> Self
+    where
+        Stat: Default,
+    {
+        repro_rng::thread_rng().set_state(checkpoint.rng);
+        let mut evolution = Self::from_parts(
+            checkpoint.population,
+            algorithm,
+            checkpoint.hall_of_fame,
+            0,
+            checkpoint.gen + 1,
+        );
+        evolution
+            .algorithm
+            .step_with_scratch(&mut evolution.population, &mut evolution.scratch);
+        evolution
+    }
+
+    // Every constructor goes through here, so new fields only need a default in one place
+    fn from_parts(
+        population: Vec<Cached<T>>,
+        algorithm: Alg,
+        hall_of_fame: Hof,
+        reset_interval: usize,
+        first_gen: usize,
+    ) -> Self
+    where
+        Stat: Default,
+    {
         Evolution {
-            population: Vec::n_from_function(algorithm.pop_size(), Cached::generate),
+            population,
+            scratch: Vec::new(),
             algorithm,
             hall_of_fame,
+            stats_collector: Stat::default(),
             stats: Vec::new(),
+            stats_sink: None,
+            elapsed: Vec::new(),
+            best_of: None,
+            keep_best_solutions: false,
+            best_fitness: Vec::new(),
+            best_solutions: Vec::new(),
             reset_interval,
+            first_gen,
+            #[cfg(feature = "parallel")]
+            thread_pool: None,
+            evaluator: None,
+            unique: None,
+            intervention: None,
+            checkpointer: None,
+            watcher: None,
+            observers: Vec::new(),
+            change_interval: 0,
+            hypermutation: 0.0,
+            #[cfg(feature = "std")]
+            evaluation_counter: Default::default(),
+            #[cfg(not(feature = "std"))]
+            evaluations_start: 0,
+            #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+            generation_start: std::time::Instant::now(),
         }
     }
 
+    /// Collect the statistics of each generation with `stats` instead of `Stat::default()`,
+    /// e.g. to give them configuration like a reference point.
+    /// See the [`stats`] module for an example.
+    ///
+    /// [`stats`]: ./stats/index.html
+    pub fn with_stats(mut self, stats: Stat) -> Self {
+        self.stats_collector = stats;
+        self
+    }
+
+    /// Hand the statistics of every generation to `sink` instead of keeping all of them in the [`Log`],
+    /// e.g. to bound the memory used by very long runs.
+    /// The log's `stats` are whatever the sink kept when the run ends.
+    /// See [`StatsSink`] for the sinks that are available.
+    ///
+    /// [`Log`]: ./struct.Log.html
+    /// [`StatsSink`]: ./stats/trait.StatsSink.html
+    pub fn with_stats_sink<S>(mut self, sink: S) -> Self
+    where
+        S: stats::StatsSink<Stat::Output> + 'static,
+    {
+        self.stats_sink = Some(alloc::boxed::Box::new(sink));
+        self
+    }
+
+    /// Record the best fitness of every generation in [`Log::best_fitness`],
+    /// whatever the hall of fame and statistics are, so a convergence curve is always available.
+    ///
+    /// The best solution is the one with the best fitness as an `f64`, in the direction of the first objective.
+    ///
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// use eviolite::prelude::*;
+    /// use eviolite::benchmarks::{Benchmark, Sphere};
+    ///
+    /// let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> = Evolution::new(
+    ///     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+    ///     hof::BestN::new(1),
+    /// )
+    /// .with_best_history();
+    /// let log = evo.run_for(10);
+    /// assert_eq!(log.best_fitness.len(), 10);
+    /// ```
+    ///
+    /// [`Log::best_fitness`]: ./struct.Log.html#structfield.best_fitness
+    pub fn with_best_history(mut self) -> Self
+    where
+        T::Fitness: Into<f64>,
+    {
+        self.best_of = Some(alloc::boxed::Box::new(|pop: &[Cached<T>]| {
+            let direction = fitness::Direction::of::<T>(0);
+            pop.iter()
+                .map(|ind| ind.evaluate().into())
+                .enumerate()
+                .reduce(|best, (idx, fitness)| {
+                    if direction.is_better(fitness, best.1) {
+                        (idx, fitness)
+                    } else {
+                        best
+                    }
+                })
+        }));
+        self
+    }
+
+    /// Record a clone of the best solution of every generation in [`Log::best_solutions`],
+    /// as well as its fitness like [`.with_best_history()`] does.
+    ///
+    /// [`Log::best_solutions`]: ./struct.Log.html#structfield.best_solutions
+    /// [`.with_best_history()`]: #method.with_best_history
+    pub fn with_best_solution_history(mut self) -> Self
+    where
+        T::Fitness: Into<f64>,
+    {
+        self.keep_best_solutions = true;
+        self.with_best_history()
+    }
+
+    /// Do all of the run's parallel work in `pool` instead of rayon's global thread pool.
+    ///
+    /// This covers evaluating the population with [`par_evaluate()`] as well as
+    /// eviolite's other parallel operations that the algorithm and hall of fame use,
+    /// like [`par_var_and()`] and the ranking of large populations by [`rank_nondominated()`].
+    /// Other work, like the callbacks and the selection itself, still happens on the thread that
+    /// started the run. The pool is shared, so it can be used for other things at the same time.
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use eviolite::prelude::*;
+    /// use eviolite::benchmarks::{Benchmark, Sphere};
+    ///
+    /// let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    /// let evo: Evolution<Benchmark<Sphere, 2>, _, _, ()> = Evolution::new(
+    ///     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+    ///     hof::BestN::new(1),
+    /// )
+    /// .with_thread_pool(Arc::new(pool));
+    /// evo.run_for(10);
+    /// ```
+    ///
+    /// [`par_evaluate()`]: ./fitness/fn.par_evaluate.html
+    /// [`par_var_and()`]: ./alg/fn.par_var_and.html
+    /// [`rank_nondominated()`]: ./select/fn.rank_nondominated.html
+    #[cfg(feature = "parallel")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "parallel")))]
+    pub fn with_thread_pool(mut self, pool: std::sync::Arc<rayon::ThreadPool>) -> Self {
+        self.thread_pool = Some(pool);
+        self
+    }
+
+    /// Evaluate each generation in one batch with `device`, e.g. on a GPU,
+    /// instead of one solution at a time with [`par_evaluate()`].
+    ///
+    /// See [`DeviceEvaluator`] for how the population is handed to the device.
+    ///
+    /// [`par_evaluate()`]: ./fitness/fn.par_evaluate.html
+    /// [`DeviceEvaluator`]: ./fitness/trait.DeviceEvaluator.html
+    pub fn with_device_evaluator<D>(mut self, mut device: D) -> Self
+    where
+        D: fitness::DeviceEvaluator<T> + 'static,
+    {
+        self.evaluator = Some(alloc::boxed::Box::new(move |pop: &[Cached<T>]| {
+            fitness::device_evaluate(pop, &mut device)
+        }));
+        self
+    }
+
+    /// Evaluate each generation on remote workers through `coordinator`
+    /// instead of on this machine with [`par_evaluate()`].
+    ///
+    /// See the [`distributed`] module for how to run the workers.
+    ///
+    /// [`par_evaluate()`]: ./fitness/fn.par_evaluate.html
+    /// [`distributed`]: ./distributed/index.html
+    #[cfg(feature = "distributed")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "distributed")))]
+    pub fn with_coordinator(mut self, mut coordinator: distributed::Coordinator) -> Self
+    where
+        T: serde::Serialize,
+        T::Fitness: serde::de::DeserializeOwned,
+    {
+        self.evaluator = Some(alloc::boxed::Box::new(move |pop: &[Cached<T>]| {
+            coordinator.evaluate(pop)
+        }));
+        self
+    }
+
+    /// Evaluate each generation by asking a person through `interactive`
+    /// instead of with [`par_evaluate()`].
+    ///
+    /// See the [`interactive`] module for how to answer.
+    ///
+    /// [`par_evaluate()`]: ./fitness/fn.par_evaluate.html
+    /// [`interactive`]: ./interactive/index.html
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn with_interactive(mut self, mut interactive: interactive::Interactive<T>) -> Self
+    where
+        T: 'static,
+        T::Fitness: From<f64>,
+    {
+        self.evaluator = Some(alloc::boxed::Box::new(move |pop: &[Cached<T>]| {
+            interactive.evaluate(pop)
+        }));
+        self
+    }
+
+    /// Evaluate only one of each group of identical solutions in every generation with
+    /// [`par_evaluate_dedup()`], sharing its fitness with the others, if `dedup` is `true`;
+    /// or go back to evaluating each solution with [`par_evaluate()`] if it's `false`.
+    ///
+    /// This replaces any other evaluator, like the one set by [`.with_device_evaluator()`].
+    ///
+    /// [`par_evaluate_dedup()`]: ./fitness/fn.par_evaluate_dedup.html
+    /// [`par_evaluate()`]: ./fitness/fn.par_evaluate.html
+    /// [`.with_device_evaluator()`]: ./struct.Evolution.html#method.with_device_evaluator
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn with_dedup_evaluation(mut self, dedup: bool) -> Self
+    where
+        T: core::hash::Hash + Eq + 'static,
+    {
+        self.evaluator = if dedup {
+            Some(alloc::boxed::Box::new(fitness::par_evaluate_dedup::<T>))
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Keep duplicates out of every generation, treating solutions as duplicates if
+    /// `key` returns the same value for both.
+    ///
+    /// Before each generation is evaluated, duplicates are mutated or regenerated until they're distinct
+    /// with [`alg::make_unique_by_key()`]. This counteracts the loss of diversity from selection
+    /// operators like [`Tournament`], which tend to fill the population with copies of a few good solutions.
+    ///
+    /// ```
+    /// use eviolite::prelude::*;
+    /// use eviolite::genome::BitString;
+    /// # #[derive(Clone)]
+    /// # struct Knapsack(BitString);
+    /// # impl Solution for Knapsack {
+    /// #     type Fitness = f64;
+    /// #     fn generate() -> Self { Knapsack(BitString::new(vec![false; 8])) }
+    /// #     fn evaluate(&self) -> f64 { self.0.iter().filter(|&&b| b).count() as f64 }
+    /// #     fn crossover(_: &mut Self, _: &mut Self) {}
+    /// #     fn mutate(&mut self) { let i = random::<usize>() % 8; self.0[i] = !self.0[i]; }
+    /// # }
+    ///
+    /// let evo: Evolution<Knapsack, _, _, ()> = Evolution::new(
+    ///     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+    ///     hof::BestN::new(1),
+    /// )
+    /// .with_unique_by_key(|knapsack: &Knapsack| knapsack.0.clone());
+    /// evo.run_for(10);
+    /// ```
+    ///
+    /// [`alg::make_unique_by_key()`]: ./alg/fn.make_unique_by_key.html
+    /// [`Tournament`]: ./select/struct.Tournament.html
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn with_unique_by_key<K, F>(mut self, mut key: F) -> Self
+    where
+        F: FnMut(&T) -> K + 'static,
+        K: core::hash::Hash + Eq,
+    {
+        self.unique = Some(alloc::boxed::Box::new(move |pop: &mut [Cached<T>]| {
+            alg::make_unique_by_key(pop, |ind: &Cached<T>| key(ind.as_ref()));
+        }));
+        self
+    }
+
+    /// Keep duplicates out of every generation, comparing whole solutions.
+    ///
+    /// This is the same as [`.with_unique_by_key()`] with a clone of each solution as its key.
+    ///
+    /// [`.with_unique_by_key()`]: ./struct.Evolution.html#method.with_unique_by_key
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn with_unique(self) -> Self
+    where
+        T: core::hash::Hash + Eq + 'static,
+    {
+        self.with_unique_by_key(T::clone)
+    }
+
+    /// Call `intervention` with the population of every new generation before it's evaluated,
+    /// so it can be changed in the middle of a run.
+    ///
+    /// `intervention` is passed the number of the generation that's about to be evaluated and
+    /// a mutable reference to its population, after the algorithm has made it from the previous one.
+    /// It can inject hand-crafted solutions, remove pathological ones, or repair them,
+    /// and changes to a solution should go through [`Cached::get_mut()`] so its fitness is re-evaluated.
+    /// It isn't called for the initial population.
+    ///
+    /// The population doesn't have to keep its size, as long as it isn't left empty.
+    /// The algorithm's next step starts from whatever is left, e.g. [`alg::Simple`]
+    /// selects its usual population size from it again.
+    ///
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// use eviolite::prelude::*;
+    /// use eviolite::benchmarks::{Benchmark, Sphere};
+    ///
+    /// let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> = Evolution::new(
+    ///     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+    ///     hof::BestN::new(1),
+    /// )
+    /// .with_intervention(|gen, pop| {
+    ///     // seed a known good solution every 10 generations
+    ///     if gen % 10 == 0 {
+    ///         pop[0] = Cached::new(Benchmark::new(vec![0.0; 3]).unwrap());
+    ///     }
+    /// });
+    /// let log = evo.run_for(20);
+    /// assert_eq!(log.hall_of_fame[0].evaluate(), 0.0);
+    /// ```
+    ///
+    /// [`Cached::get_mut()`]: ./struct.Cached.html#method.get_mut
+    /// [`alg::Simple`]: ./alg/struct.Simple.html
+    pub fn with_intervention<F>(mut self, intervention: F) -> Self
+    where
+        F: FnMut(usize, &mut Vec<Cached<T>>) + 'static,
+    {
+        self.intervention = Some(alloc::boxed::Box::new(intervention));
+        self
+    }
+
+    /// Call `checkpointer` with the current generation whenever a callback
+    /// returns [`Control::Checkpoint`].
+    ///
+    /// This is usually used to write a [`Checkpoint`], which lets whatever is driving the callbacks,
+    /// like a scheduler about to preempt the program, ask for a snapshot without knowing how it's saved.
+    ///
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// # use eviolite::prelude::*;
+    /// # use eviolite::Control;
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// # use eviolite::benchmarks::{Benchmark, Sphere};
+    /// static SNAPSHOT_REQUESTED: AtomicBool = AtomicBool::new(false);
+    ///
+    /// let evo: Evolution<Benchmark<Sphere, 2>, _, _, ()> = Evolution::new(
+    ///     alg::Simple::new(20, 0.5, 0.2, select::Tournament::new(3)),
+    ///     hof::BestN::new(1),
+    /// )
+    /// .with_checkpointer(|generation| {
+    ///     // e.g. `Checkpoint::save(generation, "run.ckpt").unwrap()`
+    ///     println!("saving generation {}", generation.gen);
+    /// });
+    /// evo.run_for_with(10, |_| {
+    ///     if SNAPSHOT_REQUESTED.swap(false, Ordering::Relaxed) {
+    ///         Control::Checkpoint
+    ///     } else {
+    ///         Control::Continue
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// [`Control::Checkpoint`]: ./enum.Control.html#variant.Checkpoint
+    /// [`Checkpoint`]: ./checkpoint/struct.Checkpoint.html
+    pub fn with_checkpointer<C>(mut self, checkpointer: C) -> Self
+    where
+        C: FnMut(Generation<T, Hof, Stat>) + 'static,
+    {
+        self.checkpointer = Some(alloc::boxed::Box::new(checkpointer));
+        self
+    }
+
+    /// Publish the hall of fame and statistics to `watch` after every generation,
+    /// so the run's progress can be followed from other threads.
+    ///
+    /// The watch is marked as finished when the run ends.
+    /// See the [`watch`] module for an example.
+    ///
+    /// [`watch`]: ./watch/index.html
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn with_watch(mut self, watch: &watch::Watch<Hof, Stat::Output>) -> Self
+    where
+        Hof: Clone + 'static,
+        Stat::Output: Clone + 'static,
+    {
+        let publisher = watch::Publisher(watch.clone());
+        self.watcher = Some(alloc::boxed::Box::new(
+            move |generation: Generation<T, Hof, Stat>| {
+                publisher.0.publish(watch::Progress {
+                    gen: generation.gen,
+                    hall_of_fame: generation.hall_of_fame.clone(),
+                    stats: generation.stats.clone(),
+                    evaluations: generation.evaluations,
+                });
+            },
+        ));
+        self
+    }
+
+    /// Register `observer` to be told about every generation, every improvement of the best solution,
+    /// and the end of the run. Any number of observers can be registered, and they're called
+    /// in the order they were registered. See the [`observer`] module for an example.
+    ///
+    /// [`observer`]: ./observer/index.html
+    pub fn with_observer<O>(mut self, observer: O) -> Self
+    where
+        O: observer::Observer<T, Hof, Stat> + 'static,
+        T: 'static,
+        T::Fitness: Into<f64>,
+        Hof: 'static,
+        Stat: 'static,
+    {
+        self.observers
+            .push(alloc::boxed::Box::new(observer::Observed {
+                observer,
+                best: None,
+            }));
+        self
+    }
+
+    /// Declare that the environment, and with it the fitness of every solution,
+    /// changes after every `change_interval` generations.
+    ///
+    /// This is for problems whose fitness function changes over time, like the
+    /// [`Dynamic`] benchmarks. Normally, [`Cached`] keeps a solution's fitness forever,
+    /// so after a change, the population would still be selected by how good it used to be.
+    /// When the environment changes, the fitness of the population is cleared and re-evaluated
+    /// before the next generation is made from it, the population is [hypermutated] if that's enabled,
+    /// and the hall of fame is told with [`HallOfFame::environment_changed()`].
+    ///
+    /// A change happens after the generation whose number plus one is a multiple of `change_interval`,
+    /// so it lines up with a clock set to the next generation in the callback.
+    /// A callback can also declare a change at any time by returning [`Control::EnvironmentChanged`].
+    /// If `change_interval` is 0, which is the default, the environment only changes when a callback says so.
+    ///
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// use eviolite::prelude::*;
+    /// use eviolite::benchmarks::{Benchmark, Sphere};
+    /// use eviolite::benchmarks::variants::{Clock, Dynamic, Schedule};
+    /// # struct EveryTen;
+    /// # impl Schedule for EveryTen {
+    /// #     const PERIOD: usize = 10;
+    /// #     const SEVERITY: f64 = 1.0;
+    /// #     fn clock() -> &'static Clock { static CLOCK: Clock = Clock::new(); &CLOCK }
+    /// # }
+    ///
+    /// let evo: Evolution<Dynamic<Benchmark<Sphere, 5>, EveryTen>, _, _, ()> = Evolution::new(
+    ///     alg::MuPlusLambda::new(20, 40, 0.6, 0.3, select::Tournament::new(3)),
+    ///     hof::BestN::new(1),
+    /// )
+    /// .with_environment_changes(EveryTen::PERIOD)
+    /// .with_hypermutation(0.5);
+    /// evo.run_for_with(30, |generation| EveryTen::clock().set(generation.gen + 1));
+    /// ```
+    ///
+    /// [`Dynamic`]: ./benchmarks/variants/struct.Dynamic.html
+    /// [`Cached`]: ./struct.Cached.html
+    /// [hypermutated]: ./struct.Evolution.html#method.with_hypermutation
+    /// [`HallOfFame::environment_changed()`]: ./hof/trait.HallOfFame.html#method.environment_changed
+    /// [`Control::EnvironmentChanged`]: ./enum.Control.html#variant.EnvironmentChanged
+    pub fn with_environment_changes(mut self, change_interval: usize) -> Self {
+        self.change_interval = change_interval;
+        self
+    }
+
+    /// Mutate each solution with probability `rate` whenever the environment changes,
+    /// before the population is re-evaluated.
+    ///
+    /// This is triggered hypermutation: a burst of diversity right after a change helps
+    /// the population move away from where the old optimum was.
+    /// The default rate is 0. See [`.with_environment_changes()`] for when the environment changes.
+    ///
+    /// [`.with_environment_changes()`]: ./struct.Evolution.html#method.with_environment_changes
+    pub fn with_hypermutation(mut self, rate: f64) -> Self {
+        self.hypermutation = rate;
+        self
+    }
+
     /// Run the algorithm for `n_gens` generations.
     /// Consumes the `Evolution` instance.
     ///
@@ -148,14 +996,73 @@ where
         self.run_for_with(n_gens, |_| {})
     }
 
+    /// Run the algorithm until `limit` has passed since the start of the run.
+    /// Consumes the `Evolution` instance.
+    ///
+    /// The time is checked between generations, so the run can go over `limit` by up to one generation.
+    /// When running under a hard time limit, like a cluster job's, leave enough of a margin for that
+    /// and for saving the results. [`Log::elapsed`] has the time each generation took.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use eviolite::prelude::*;
+    /// use eviolite::benchmarks::{Benchmark, Sphere};
+    ///
+    /// let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> = Evolution::new(
+    ///     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+    ///     hof::BestN::new(1),
+    /// );
+    /// let log = evo.run_for_duration(Duration::from_millis(100));
+    /// assert_eq!(log.elapsed.len(), log.stats.len());
+    /// ```
+    ///
+    /// [`Log::elapsed`]: ./struct.Log.html#structfield.elapsed
+    #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn run_for_duration(self, limit: Duration) -> Log<T, Hof, Stat> {
+        let start = std::time::Instant::now();
+        self.run_until(|_| start.elapsed() >= limit)
+    }
+
+    /// Run the algorithm until at least `budget` fitness evaluations have been computed.
+    /// Consumes the `Evolution` instance.
+    ///
+    /// This is the usual way to give algorithms the same budget when comparing them,
+    /// since they can use very different numbers of evaluations per generation.
+    /// The run ends after the generation that uses up the budget, so it can overshoot it
+    /// by up to one generation's worth of evaluations; [`Log::evaluations`] has the exact number.
+    /// Evaluations are counted as described in [`Generation::evaluations`].
+    ///
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// use eviolite::prelude::*;
+    /// use eviolite::benchmarks::{Benchmark, Sphere};
+    ///
+    /// let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> = Evolution::new(
+    ///     alg::MuPlusLambda::new(20, 40, 0.6, 0.3, select::Tournament::new(3)),
+    ///     hof::BestN::new(1),
+    /// );
+    /// let log = evo.run_for_evaluations(1000);
+    /// assert!(log.evaluations >= 1000);
+    /// ```
+    ///
+    /// [`Log::evaluations`]: ./struct.Log.html#structfield.evaluations
+    /// [`Generation::evaluations`]: ./struct.Generation.html#structfield.evaluations
+    pub fn run_for_evaluations(self, budget: u64) -> Log<T, Hof, Stat> {
+        self.run_until(|generation| generation.evaluations >= budget)
+    }
+
     /// Run the algorithm until the provided `predicate` closure returns `true`.
     /// Consumes the `Evolution` instance.
     ///
     /// The closure is passed a [`Generation`] instance referring to the most recent generation.
+    /// Common criteria, like reaching a target fitness or running out of time,
+    /// are available in the [`termination`] module.
     ///
     /// Returns an instance of [`Log`] containing the hall of fame and collected statistics for the run.
     ///  
     /// [`Generation`]: ./struct.Generation.html
+    /// [`termination`]: ./termination/index.html
     /// [`Log`]: ./struct.Log.html
     pub fn run_until<F>(self, predicate: F) -> Log<T, Hof, Stat>
     where
@@ -169,30 +1076,46 @@ where
     /// that you want to execute interleaved with the algorithm.
     ///
     /// The closure is passed a [`Generation`] instance referring to the most recent generation.
-    pub fn run_for_with<F>(mut self, n_gens: usize, mut callback: F) -> Log<T, Hof, Stat>
+    /// It can return a [`Control`] to stop the run early or change what happens to the generation,
+    /// a [`ControlFlow`] to either stop or continue, or nothing to always continue.
+    /// The [`Log`] of a run that was stopped early is complete up to and including the generation it stopped at.
+    ///
+    /// [`ControlFlow`]: https://doc.rust-lang.org/core/ops/enum.ControlFlow.html
+    /// [`Log`]: ./struct.Log.html
+    /// [`Control`]: ./enum.Control.html
+    pub fn run_for_with<F, C>(mut self, n_gens: usize, mut callback: F) -> Log<T, Hof, Stat>
     where
-        F: FnMut(Generation<T, Hof, Stat>),
+        F: FnMut(Generation<T, Hof, Stat>) -> C,
+        C: Into<Control>,
     {
-        for generation in 0..n_gens {
-            par_evaluate(&self.population);
-            self.hall_of_fame.record(&self.population);
-            let stat = Stat::analyze(&self.population);
-            callback(Generation {
+        #[cfg(feature = "parallel")]
+        let _pool = utils::thread_pool::enter(self.thread_pool.clone());
+        self.start_run();
+        #[cfg(feature = "std")]
+        let _run = self.enter();
+
+        for generation in self.first_gen..self.first_gen + n_gens {
+            self.evaluate();
+            let evaluations = self.evaluations();
+            self.hall_of_fame
+                .record(generation, evaluations, &self.population);
+            let stat = self.stats_collector.analyze(&self.population);
+            let control = callback(Generation {
                 gen: generation,
                 pop: &self.population,
                 hall_of_fame: &self.hall_of_fame,
                 stats: &stat,
+                evaluations: self.evaluations(),
             });
-            self.stats.push(stat);
+            if self.control(control.into(), generation, stat) {
+                break;
+            }
 
             self.reset_or_step(generation);
+            self.intervene(generation + 1);
         }
 
-        Log {
-            hall_of_fame: self.hall_of_fame,
-            stats: self.stats,
-            final_population: self.population,
-        }
+        self.into_log()
     }
 
     /// Run the algorithm until the provided `predicate` closure returns `true`,
@@ -200,65 +1123,405 @@ where
     /// Works the same way as [`.run_until()`] and [`.run_for_with()`].
     ///
     /// Both closures are passed a [`Generation`] instance referring to the most recent generation.
+    /// The callback can return a [`Control`], just like with [`.run_for_with()`].
     ///
     /// [`.run_until()`]: ./struct.Evolution.html#method.run_until
-    /// [`.run_for_with()`]: .struct.Evolution.html#method.run_for_with
-    pub fn run_until_with<F, G>(mut self, mut predicate: F, mut callback: G) -> Log<T, Hof, Stat>
+    /// [`.run_for_with()`]: ./struct.Evolution.html#method.run_for_with
+    /// [`Control`]: ./enum.Control.html
+    pub fn run_until_with<F, G, C>(mut self, mut predicate: F, mut callback: G) -> Log<T, Hof, Stat>
     where
         F: FnMut(Generation<T, Hof, Stat>) -> bool,
-        G: FnMut(Generation<T, Hof, Stat>),
+        G: FnMut(Generation<T, Hof, Stat>) -> C,
+        C: Into<Control>,
     {
-        let mut generation = 0;
-        let mut stat: Stat;
+        #[cfg(feature = "parallel")]
+        let _pool = utils::thread_pool::enter(self.thread_pool.clone());
+        self.start_run();
+        #[cfg(feature = "std")]
+        let _run = self.enter();
+
+        let mut generation = self.first_gen;
+        let mut stat: Stat::Output;
 
-        par_evaluate(&self.population);
-        self.hall_of_fame.record(&self.population);
-        stat = Stat::analyze(&self.population);
+        self.evaluate();
+        let evaluations = self.evaluations();
+        self.hall_of_fame
+            .record(generation, evaluations, &self.population);
+        stat = self.stats_collector.analyze(&self.population);
 
         while !predicate(Generation {
             gen: generation,
             pop: &self.population,
             hall_of_fame: &self.hall_of_fame,
             stats: &stat,
+            evaluations: self.evaluations(),
         }) {
-            callback(Generation {
+            let control = callback(Generation {
                 gen: generation,
                 pop: &self.population,
                 hall_of_fame: &self.hall_of_fame,
                 stats: &stat,
+                evaluations: self.evaluations(),
             });
-            self.stats.push(stat);
+            if self.control(control.into(), generation, stat) {
+                break;
+            }
 
             generation += 1;
 
             self.reset_or_step(generation);
+            self.intervene(generation);
 
-            par_evaluate(&self.population);
-            self.hall_of_fame.record(&self.population);
-            stat = Stat::analyze(&self.population);
+            self.evaluate();
+            let evaluations = self.evaluations();
+            self.hall_of_fame
+                .record(generation, evaluations, &self.population);
+            stat = self.stats_collector.analyze(&self.population);
         }
 
-        Log {
+        self.into_log()
+    }
+
+    /// Step through the run one generation at a time, instead of handing it a callback.
+    /// Consumes the `Evolution` instance.
+    ///
+    /// Each call to [`Steps::next()`] evolves the next generation and returns a [`Generation`] referring to it,
+    /// so the loop can be driven by the caller, interleaved with other work, or paused and stopped at any point.
+    /// [`Steps::finish()`] then returns the [`Log`] of the run so far.
+    /// A run stepped through this way produces the same results as one run with [`.run_for()`]
+    /// for the same number of generations.
+    ///
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// use eviolite::prelude::*;
+    /// use eviolite::benchmarks::{Benchmark, Sphere};
+    ///
+    /// let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> = Evolution::new(
+    ///     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+    ///     hof::BestN::new(1),
+    /// );
+    /// let mut steps = evo.iter();
+    /// while let Some(generation) = steps.next() {
+    ///     if generation.hall_of_fame[0].evaluate() > -0.01 || generation.gen == 99 {
+    ///         break;
+    ///     }
+    /// }
+    /// let log = steps.finish();
+    /// assert!(log.stats.len() <= 100);
+    /// ```
+    ///
+    /// [`Steps::next()`]: ./struct.Steps.html#method.next
+    /// [`Steps::finish()`]: ./struct.Steps.html#method.finish
+    /// [`Generation`]: ./struct.Generation.html
+    /// [`Log`]: ./struct.Log.html
+    /// [`.run_for()`]: #method.run_for
+    pub fn iter(mut self) -> Steps<T, Alg, Hof, Stat> {
+        self.start_run();
+        Steps {
+            gen: self.first_gen,
+            evolution: self,
+            current: None,
+            stopped: false,
+        }
+    }
+
+    fn into_log(mut self) -> Log<T, Hof, Stat> {
+        let mut observers = core::mem::take(&mut self.observers);
+        let (stats, stats_interval) = match &mut self.stats_sink {
+            Some(sink) => (sink.finish(), sink.interval()),
+            None => (core::mem::take(&mut self.stats), 1),
+        };
+        // everything else recorded per generation is thinned out to match the statistics
+        if stats_interval > 1 {
+            self.elapsed = self.elapsed.into_iter().step_by(stats_interval).collect();
+            self.best_fitness = self
+                .best_fitness
+                .into_iter()
+                .step_by(stats_interval)
+                .collect();
+            self.best_solutions = self
+                .best_solutions
+                .into_iter()
+                .step_by(stats_interval)
+                .collect();
+        }
+        let log = Log {
+            evaluations: self.evaluations(),
             hall_of_fame: self.hall_of_fame,
-            stats: self.stats,
+            stats,
+            stats_interval,
+            elapsed: self.elapsed,
+            best_fitness: self.best_fitness,
+            best_solutions: self.best_solutions,
+            first_generation: self.first_gen,
             final_population: self.population,
+        };
+        for observer in &mut observers {
+            observer.finish(&log);
+        }
+        log
+    }
+
+    fn start_run(&mut self) {
+        #[cfg(feature = "std")]
+        {
+            self.evaluation_counter = Default::default();
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            self.evaluations_start = fitness::evaluation_count();
+        }
+        #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+        {
+            self.generation_start = std::time::Instant::now();
+        }
+    }
+
+    // The number of evaluations since the run started
+    fn evaluations(&self) -> u64 {
+        #[cfg(feature = "std")]
+        return self.evaluation_counter.evaluations();
+        #[cfg(not(feature = "std"))]
+        return fitness::evaluation_count() - self.evaluations_start;
+    }
+
+    // Count the evaluations on this thread as the run's until the guard is dropped
+    #[cfg(feature = "std")]
+    fn enter(&self) -> utils::counter::RunGuard {
+        utils::counter::enter(Some(self.evaluation_counter.clone()))
+    }
+
+    // Called on every new generation, so it also removes duplicates first if that's enabled
+    fn evaluate(&mut self) {
+        if let Some(make_unique) = &mut self.unique {
+            make_unique(&mut self.population);
+        }
+        match &mut self.evaluator {
+            Some(evaluate) => evaluate(&self.population),
+            None => par_evaluate(&self.population),
+        }
+    }
+
+    // Act on what a callback returned for a generation, returning whether the run should stop
+    fn control(&mut self, control: Control, gen: usize, stat: Stat::Output) -> bool {
+        let evaluations = self.evaluations();
+        for observer in &mut self.observers {
+            observer.generation(Generation {
+                gen,
+                pop: &self.population,
+                hall_of_fame: &self.hall_of_fame,
+                stats: &stat,
+                evaluations,
+            });
+        }
+        if let Some(watcher) = &mut self.watcher {
+            watcher(Generation {
+                gen,
+                pop: &self.population,
+                hall_of_fame: &self.hall_of_fame,
+                stats: &stat,
+                evaluations,
+            });
         }
+        if control == Control::Checkpoint {
+            if let Some(checkpointer) = &mut self.checkpointer {
+                checkpointer(Generation {
+                    gen,
+                    pop: &self.population,
+                    hall_of_fame: &self.hall_of_fame,
+                    stats: &stat,
+                    evaluations,
+                });
+            }
+        }
+        if control != Control::SkipStats {
+            match &mut self.stats_sink {
+                Some(sink) => sink.push(gen, stat),
+                None => self.stats.push(stat),
+            }
+            #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+            self.elapsed.push(self.generation_start.elapsed());
+            if let Some((idx, fitness)) = self
+                .best_of
+                .as_ref()
+                .and_then(|best_of| best_of(&self.population))
+            {
+                self.best_fitness.push(fitness);
+                if self.keep_best_solutions {
+                    self.best_solutions.push(self.population[idx].clone());
+                }
+            }
+        }
+        #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+        {
+            self.generation_start = std::time::Instant::now();
+        }
+        if control == Control::Stop {
+            return true;
+        }
+        if control == Control::EnvironmentChanged
+            || (self.change_interval != 0 && (gen + 1).is_multiple_of(self.change_interval))
+        {
+            self.change_environment();
+        }
+        false
+    }
+
+    fn change_environment(&mut self) {
+        let mut rng = repro_rng::thread_rng();
+        for ind in &mut self.population {
+            if self.hypermutation > 0.0 && rng.gen_bool(self.hypermutation.min(1.0)) {
+                ind.mutate();
+            } else {
+                ind.clear_cache();
+            }
+        }
+        self.evaluate();
+        self.hall_of_fame.environment_changed();
     }
 
     fn reset(&mut self) {
         self.population = Vec::n_from_function(self.algorithm.pop_size(), Cached::generate);
     }
 
+    // Let the intervention change the population of generation `gen` before it's evaluated
+    fn intervene(&mut self, gen: usize) {
+        if let Some(intervention) = &mut self.intervention {
+            intervention(gen, &mut self.population);
+        }
+    }
+
     fn reset_or_step(&mut self, generation: usize) {
-        if self.reset_interval != 0 && generation != 0 && generation % self.reset_interval == 0 {
+        if self.reset_interval != 0
+            && generation != 0
+            && generation.is_multiple_of(self.reset_interval)
+        {
             self.reset();
         } else {
-            self.algorithm.step(&mut self.population);
+            self.algorithm
+                .step_with_scratch(&mut self.population, &mut self.scratch);
         }
     }
 }
 
+/// A run that is stepped through one generation at a time, returned from [`Evolution::iter()`]
+///
+/// This works like an iterator, except that each [`Generation`] borrows from it,
+/// so it's used with `while let Some(generation) = steps.next()` rather than a `for` loop.
+///
+/// [`Evolution::iter()`]: ./struct.Evolution.html#method.iter
+/// [`Generation`]: ./struct.Generation.html
+pub struct Steps<T, Alg, Hof, Stat>
+where
+    T: Solution,
+    Alg: Algorithm<T>,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+{
+    evolution: Evolution<T, Alg, Hof, Stat>,
+    gen: usize,
+    // The statistics of the generation last returned, and what to do with it before the next one
+    current: Option<(Stat::Output, Control)>,
+    stopped: bool,
+}
+
+impl<T, Alg, Hof, Stat> Steps<T, Alg, Hof, Stat>
+where
+    T: Solution,
+    Alg: Algorithm<T>,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+{
+    /// Evolve the next generation and return it, or return `None` if the run was stopped
+    /// with [`Control::Stop`]. The first call returns the initial generation.
+    ///
+    /// [`Control::Stop`]: ./enum.Control.html#variant.Stop
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<Generation<'_, T, Hof, Stat>> {
+        if self.stopped {
+            return None;
+        }
+        #[cfg(feature = "parallel")]
+        let _pool = utils::thread_pool::enter(self.evolution.thread_pool.clone());
+        #[cfg(feature = "std")]
+        let _run = self.evolution.enter();
+
+        if let Some((stat, control)) = self.current.take() {
+            if self.evolution.control(control, self.gen, stat) {
+                self.stopped = true;
+                return None;
+            }
+            self.evolution.reset_or_step(self.gen);
+            self.gen += 1;
+            self.evolution.intervene(self.gen);
+        }
+
+        self.evolution.evaluate();
+        let evaluations = self.evolution.evaluations();
+        self.evolution
+            .hall_of_fame
+            .record(self.gen, evaluations, &self.evolution.population);
+        let stat = self
+            .evolution
+            .stats_collector
+            .analyze(&self.evolution.population);
+        let (stat, _) = self.current.insert((stat, Control::Continue));
+        Some(Generation {
+            gen: self.gen,
+            pop: &self.evolution.population,
+            hall_of_fame: &self.evolution.hall_of_fame,
+            stats: stat,
+            evaluations: self.evolution.evaluations(),
+        })
+    }
+
+    /// Decide what happens to the generation last returned from [`.next()`], just like returning
+    /// a [`Control`] from the callback passed to [`Evolution::run_for_with()`] would.
+    /// It's applied on the following call to `.next()` or [`.finish()`]. The default is [`Control::Continue`].
+    ///
+    /// [`.next()`]: #method.next
+    /// [`.finish()`]: #method.finish
+    /// [`Control`]: ./enum.Control.html
+    /// [`Control::Continue`]: ./enum.Control.html#variant.Continue
+    /// [`Evolution::run_for_with()`]: ./struct.Evolution.html#method.run_for_with
+    pub fn control(&mut self, control: Control) {
+        if let Some((_, current)) = &mut self.current {
+            *current = control;
+        }
+    }
+
+    /// End the run after the generation last returned from [`.next()`].
+    ///
+    /// Returns an instance of [`Log`] containing the hall of fame and collected statistics for the run.
+    ///
+    /// [`.next()`]: #method.next
+    /// [`Log`]: ./struct.Log.html
+    pub fn finish(mut self) -> Log<T, Hof, Stat> {
+        if let Some((stat, control)) = self.current.take() {
+            #[cfg(feature = "parallel")]
+            let _pool = utils::thread_pool::enter(self.evolution.thread_pool.clone());
+            #[cfg(feature = "std")]
+            let _run = self.evolution.enter();
+            self.evolution.control(control, self.gen, stat);
+        }
+        self.evolution.into_log()
+    }
+}
+
 /// Container type for the results of a run
+///
+/// With the `serde` crate feature, this can be serialized and deserialized
+/// whenever the solution, its fitness, the hall of fame, and the statistics can.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "Cached<T>: serde::Serialize, Hof: serde::Serialize, \
+                     <Stat as GenerationStats<T>>::Output: serde::Serialize",
+        deserialize = "Cached<T>: serde::Deserialize<'de>, Hof: serde::Deserialize<'de>, \
+                       <Stat as GenerationStats<T>>::Output: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Log<T, Hof, Stat>
 where
     T: Solution,
@@ -269,8 +1532,253 @@ where
     pub final_population: Vec<Cached<T>>,
     /// The hall of fame that has recorded every solution in every generation of the run.
     pub hall_of_fame: Hof,
-    /// Statistics for each generation.
-    pub stats: Vec<Stat>,
+    /// Statistics for each generation, or only those that were kept by the run's [`StatsSink`].
+    ///
+    /// [`StatsSink`]: ./stats/trait.StatsSink.html
+    pub stats: Vec<Stat::Output>,
+    /// The number of the generation that the first entry of `stats` belongs to,
+    /// which is 0 unless the run was [resumed] from a checkpoint.
+    ///
+    /// [resumed]: ./struct.Evolution.html#method.resume
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub first_generation: usize,
+    /// How many generations apart the entries of `stats` are, which is 1 unless
+    /// the run's [`StatsSink`] downsampled them, like [`Downsample`] does.
+    ///
+    /// [`StatsSink`]: ./stats/trait.StatsSink.html
+    /// [`Downsample`]: ./stats/struct.Downsample.html
+    #[cfg_attr(feature = "serde", serde(default = "one"))]
+    pub stats_interval: usize,
+    /// The number of fitness evaluations computed during the run.
+    /// See [`Generation::evaluations`].
+    ///
+    /// [`Generation::evaluations`]: ./struct.Generation.html#structfield.evaluations
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub evaluations: u64,
+    /// How long each generation took, every `stats_interval` generations like `stats`,
+    /// even if the run's [`StatsSink`] didn't keep their statistics.
+    /// A generation's time is measured from the end of the previous one, or from the start of the run,
+    /// so it includes evaluation, callbacks, and making the next generation.
+    ///
+    /// This is empty without the `std` crate feature, or on WebAssembly, where there's no clock to measure with.
+    ///
+    /// [`StatsSink`]: ./stats/trait.StatsSink.html
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub elapsed: Vec<Duration>,
+    /// The best fitness of each generation, every `stats_interval` generations like `elapsed`.
+    ///
+    /// This is empty unless the run was started with [`.with_best_history()`].
+    ///
+    /// [`.with_best_history()`]: ./struct.Evolution.html#method.with_best_history
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub best_fitness: Vec<f64>,
+    /// The best solution of each generation, every `stats_interval` generations like `elapsed`.
+    ///
+    /// This is empty unless the run was started with [`.with_best_solution_history()`].
+    ///
+    /// [`.with_best_solution_history()`]: ./struct.Evolution.html#method.with_best_solution_history
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub best_solutions: Vec<Cached<T>>,
+}
+
+// The default `stats_interval` of logs that were saved without one
+#[cfg(feature = "serde")]
+fn one() -> usize {
+    1
+}
+
+impl<T, Hof, Stat> Log<T, Hof, Stat>
+where
+    T: Solution,
+    Hof: HallOfFame<T> + IntoIterator<Item = Cached<T>>,
+    Stat: GenerationStats<T>,
+{
+    /// Add the results of a later segment of the same run, e.g. one that was resumed from a checkpoint.
+    ///
+    /// The statistics of `later` are placed at their generation numbers: if the two segments overlap,
+    /// because the run was resumed from a checkpoint taken before this segment ended,
+    /// the statistics of this segment from `later.first_generation` on are replaced.
+    /// The halls of fame are [merged], the final population becomes the one of `later`,
+    /// and its evaluations are added to this segment's.
+    ///
+    /// This assumes that there's one entry in `stats` for every generation,
+    /// which doesn't hold if a callback returned [`Control::SkipStats`]
+    /// or the run had a [`StatsSink`] that didn't keep all of them.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `later` starts before this segment or after the generation following its last one.
+    ///
+    /// [merged]: ./hof/trait.HallOfFame.html#method.merge
+    /// [`Control::SkipStats`]: ./enum.Control.html#variant.SkipStats
+    /// [`StatsSink`]: ./stats/trait.StatsSink.html
+    pub fn append(&mut self, later: Self) {
+        let offset = self.offset_of(&later);
+        self.stats.truncate(offset);
+        self.stats.extend(later.stats);
+        self.elapsed.truncate(offset);
+        self.elapsed.extend(later.elapsed);
+        self.best_fitness.truncate(offset);
+        self.best_fitness.extend(later.best_fitness);
+        self.best_solutions.truncate(offset);
+        self.best_solutions.extend(later.best_solutions);
+        self.hall_of_fame.merge(later.hall_of_fame);
+        self.final_population = later.final_population;
+        self.evaluations += later.evaluations;
+    }
+
+    /// Add the results of another run of the same generations, e.g. another island of an island model.
+    ///
+    /// The statistics of each generation that both runs have are combined with `combine`,
+    /// and those of later generations that only `other` has are appended.
+    /// The time a generation took is the longer of the two, as if the runs happened side by side.
+    /// The best fitness and solution of a generation are those of the run that did better in it.
+    /// The halls of fame are [merged], and the final population and evaluations of `other` are added to this one's.
+    ///
+    /// Like [`.append()`], this assumes that there's one entry in `stats` for every generation.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `other` starts before this run or after the generation following its last one.
+    ///
+    /// [merged]: ./hof/trait.HallOfFame.html#method.merge
+    /// [`.append()`]: #method.append
+    pub fn merge_with(
+        &mut self,
+        other: Self,
+        mut combine: impl FnMut(&mut Stat::Output, Stat::Output),
+    ) {
+        let offset = self.offset_of(&other);
+        let mut stats = other.stats.into_iter();
+        for (ours, theirs) in self.stats[offset..].iter_mut().zip(&mut stats) {
+            combine(ours, theirs);
+        }
+        self.stats.extend(stats);
+        let mut elapsed = other.elapsed.into_iter();
+        for (ours, theirs) in self.elapsed.iter_mut().skip(offset).zip(&mut elapsed) {
+            *ours = (*ours).max(theirs);
+        }
+        self.elapsed.extend(elapsed);
+        self.merge_best(offset, other.best_fitness, other.best_solutions);
+        self.hall_of_fame.merge(other.hall_of_fame);
+        self.final_population.extend(other.final_population);
+        self.evaluations += other.evaluations;
+    }
+
+    // Keep the better of the two best solutions of each generation the logs share
+    fn merge_best(&mut self, offset: usize, fitness: Vec<f64>, solutions: Vec<Cached<T>>) {
+        let direction = fitness::Direction::of::<T>(0);
+        let mut solutions = solutions.into_iter();
+        for (gen, theirs) in fitness.into_iter().enumerate() {
+            let solution = solutions.next();
+            match self.best_fitness.get_mut(offset + gen) {
+                Some(ours) if direction.is_better(theirs, *ours) => {
+                    *ours = theirs;
+                    if let (Some(ours), Some(theirs)) =
+                        (self.best_solutions.get_mut(offset + gen), solution)
+                    {
+                        *ours = theirs;
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    self.best_fitness.push(theirs);
+                    self.best_solutions.extend(solution);
+                }
+            }
+        }
+    }
+
+    // The index in `self.stats` of the first generation of `other`
+    fn offset_of(&self, other: &Self) -> usize {
+        assert!(
+            other.first_generation >= self.first_generation
+                && other.first_generation <= self.first_generation + self.stats.len(),
+            "the logs' generations must overlap or follow on from each other"
+        );
+        other.first_generation - self.first_generation
+    }
+}
+
+/// What a run should do after a callback, returned from the callback passed to
+/// [`Evolution::run_for_with()`] or [`Evolution::run_until_with()`]
+///
+/// Callbacks that return `()` always continue.
+/// Callbacks can also return a [`ControlFlow`], where `Break` stops the run
+/// and `Continue` carries on, so they can use `?` on one:
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use std::ops::ControlFlow;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+///
+/// static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+///
+/// fn shutdown_requested() -> ControlFlow<()> {
+///     if SHUTDOWN.load(Ordering::Relaxed) {
+///         ControlFlow::Break(())
+///     } else {
+///         ControlFlow::Continue(())
+///     }
+/// }
+///
+/// let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> = Evolution::new(
+///     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// );
+/// let log = evo.run_for_with(100, |generation| {
+///     shutdown_requested()?;
+///     if generation.gen == 9 {
+///         SHUTDOWN.store(true, Ordering::Relaxed);
+///     }
+///     ControlFlow::Continue(())
+/// });
+/// assert_eq!(log.stats.len(), 11);
+/// ```
+///
+/// [`ControlFlow`]: https://doc.rust-lang.org/core/ops/enum.ControlFlow.html
+/// [`Evolution::run_for_with()`]: ./struct.Evolution.html#method.run_for_with
+/// [`Evolution::run_until_with()`]: ./struct.Evolution.html#method.run_until_with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Control {
+    /// Carry on with the next generation.
+    #[default]
+    Continue,
+    /// End the run after this generation. The final population in the [`Log`] is this generation's.
+    ///
+    /// [`Log`]: ./struct.Log.html
+    Stop,
+    /// Call the function given to [`Evolution::with_checkpointer()`] with this generation,
+    /// then carry on. Without one, this is the same as `Continue`.
+    ///
+    /// [`Evolution::with_checkpointer()`]: ./struct.Evolution.html#method.with_checkpointer
+    Checkpoint,
+    /// Carry on without keeping this generation's statistics in the [`Log`].
+    ///
+    /// [`Log`]: ./struct.Log.html
+    SkipStats,
+    /// Declare that the environment has changed, re-evaluating the population before carrying on.
+    /// See [`Evolution::with_environment_changes()`].
+    ///
+    /// [`Evolution::with_environment_changes()`]: ./struct.Evolution.html#method.with_environment_changes
+    EnvironmentChanged,
+}
+
+impl From<()> for Control {
+    fn from(_: ()) -> Self {
+        Control::Continue
+    }
+}
+
+impl From<ControlFlow<()>> for Control {
+    fn from(flow: ControlFlow<()>) -> Self {
+        match flow {
+            ControlFlow::Continue(()) => Control::Continue,
+            ControlFlow::Break(()) => Control::Stop,
+        }
+    }
 }
 
 /// Container type passed to callbacks
@@ -289,5 +1797,198 @@ where
     /// A reference to the hall of fame as of the current generation.
     pub hall_of_fame: &'a Hof,
     /// The calculated statistics for the generation this instance refers to.
-    pub stats: &'a Stat,
+    pub stats: &'a Stat::Output,
+    /// The number of fitness evaluations computed since the run started, up to and including this generation's.
+    ///
+    /// Unlike [`evaluation_count()`], this only counts the run's own evaluations, not those of other runs
+    /// in the program at the same time: the ones on the thread driving the run, and the ones [`par_evaluate()`]
+    /// does for it on other threads. Evaluations on threads the run doesn't know about, like those of
+    /// an evaluator that starts its own threads, aren't counted unless their fitness values are cached
+    /// with [`Cached::set_fitness()`] on the run's thread. Without the `std` feature, this is the difference
+    /// in [`evaluation_count()`] since the start of the run.
+    /// A run resumed from a checkpoint starts counting from 0 again.
+    ///
+    /// [`evaluation_count()`]: ./fitness/fn.evaluation_count.html
+    /// [`par_evaluate()`]: ./fitness/fn.par_evaluate.html
+    /// [`Cached::set_fitness()`]: ./struct.Cached.html#method.set_fitness
+    pub evaluations: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hof::BestN, repro_rng::reseed, testutils::Walk};
+
+    // Statistics that are just the number of generations analyzed before, i.e. the generation's index
+    #[derive(Default)]
+    struct Index(usize);
+
+    impl GenerationStats<Walk> for Index {
+        type Output = usize;
+
+        fn analyze(&mut self, _: &[Cached<Walk>]) -> usize {
+            self.0 += 1;
+            self.0 - 1
+        }
+    }
+
+    // A log whose best solution in each generation is a walk to `best`,
+    // and whose generations took as many seconds as their best is below 0
+    fn log(first_generation: usize, best: &[f64]) -> Log<Walk, BestN<Walk>, Index> {
+        Log {
+            final_population: vec![Cached::new(Walk(0.5))],
+            hall_of_fame: BestN::new(1),
+            stats: (first_generation..first_generation + best.len()).collect(),
+            first_generation,
+            stats_interval: 1,
+            evaluations: 10,
+            elapsed: best.iter().map(|f| Duration::from_secs_f64(-f)).collect(),
+            best_fitness: best.to_vec(),
+            best_solutions: best.iter().map(|&f| Cached::new(Walk(f))).collect(),
+        }
+    }
+
+    fn walks(log: &Log<Walk, BestN<Walk>, Index>) -> Vec<f64> {
+        log.best_solutions
+            .iter()
+            .map(|ind| ind.as_ref().0)
+            .collect()
+    }
+
+    #[test]
+    fn log_offsets() {
+        let first = log(2, &[-1.0, -1.0, -1.0]);
+        assert_eq!(first.offset_of(&log(2, &[-1.0])), 0);
+        assert_eq!(first.offset_of(&log(4, &[-1.0])), 2);
+        assert_eq!(first.offset_of(&log(5, &[-1.0])), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "must overlap or follow on")]
+    fn log_offset_before() {
+        log(2, &[-1.0]).offset_of(&log(1, &[-1.0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "must overlap or follow on")]
+    fn log_offset_after_gap() {
+        log(2, &[-1.0]).offset_of(&log(4, &[-1.0]));
+    }
+
+    #[test]
+    fn merge_best() {
+        let mut merged = log(0, &[-3.0, -2.0, -1.0]);
+        let other = log(1, &[-1.5, -2.5, -4.0]);
+        merged.merge_best(1, other.best_fitness, other.best_solutions);
+        assert_eq!(merged.best_fitness, [-3.0, -1.5, -1.0, -4.0]);
+        assert_eq!(walks(&merged), merged.best_fitness);
+
+        // without the solutions, only the fitness is merged
+        let mut merged = log(0, &[-3.0, -2.0]);
+        merged.best_solutions.clear();
+        merged.merge_best(0, vec![-1.0, -2.5, -0.5], Vec::new());
+        assert_eq!(merged.best_fitness, [-1.0, -2.0, -0.5]);
+        assert!(merged.best_solutions.is_empty());
+    }
+
+    #[test]
+    fn merge_with() {
+        let mut merged = log(0, &[-3.0, -2.0, -1.0]);
+        merged.merge_with(log(1, &[-1.5, -0.5, -4.0]), |ours, theirs| *ours += theirs);
+        assert_eq!(merged.stats, [0, 2, 4, 3]);
+        let elapsed: Vec<f64> = merged.elapsed.iter().map(Duration::as_secs_f64).collect();
+        assert_eq!(elapsed, [3.0, 2.0, 1.0, 4.0]);
+        assert_eq!(merged.best_fitness, [-3.0, -1.5, -0.5, -4.0]);
+        assert_eq!(walks(&merged), merged.best_fitness);
+        assert_eq!(merged.final_population.len(), 2);
+        assert_eq!(merged.evaluations, 20);
+        assert_eq!(merged.first_generation, 0);
+    }
+
+    #[test]
+    fn downsampled_log() {
+        reseed(12);
+        let evo: Evolution<Walk, _, _, Index> = Evolution::new(
+            alg::Simple::new(10, 0.5, 0.5, select::Tournament::new(2)),
+            BestN::new(1),
+        )
+        .with_stats_sink(stats::Downsample::new(4))
+        .with_best_solution_history();
+        let mut best = Vec::new();
+        let log = evo.run_for_with(20, |generation| {
+            best.push(
+                generation
+                    .pop
+                    .iter()
+                    .map(|ind| ind.evaluate())
+                    .fold(f64::NEG_INFINITY, f64::max),
+            );
+        });
+
+        // 4 generations fit at first, then every 2nd, every 4th, and every 8th
+        assert_eq!(log.stats, [0, 8, 16]);
+        assert_eq!(log.stats_interval, 8);
+        assert_eq!(log.best_fitness, [best[0], best[8], best[16]]);
+        let solutions: Vec<f64> = log
+            .best_solutions
+            .iter()
+            .map(|ind| ind.evaluate())
+            .collect();
+        assert_eq!(solutions, log.best_fitness);
+        if cfg!(all(feature = "std", not(target_arch = "wasm32"))) {
+            assert_eq!(log.elapsed.len(), 3);
+        }
+    }
+
+    #[test]
+    fn intervention_changes_size() {
+        let evo: Evolution<Walk, _, _, ()> = Evolution::new(
+            alg::Simple::new(10, 0.5, 0.5, select::Tournament::new(2)),
+            BestN::new(1),
+        )
+        .with_intervention(|gen, pop| match gen {
+            1 => pop.extend((0..5).map(|_| Cached::generate())),
+            2 => pop.truncate(3),
+            _ => {}
+        });
+        let mut sizes = Vec::new();
+        evo.run_for_with(4, |generation| sizes.push(generation.pop.len()));
+        assert_eq!(sizes, [10, 15, 3, 10]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn dedup_evaluation() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static EVALUATIONS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Clone, PartialEq, Eq, Hash)]
+        struct Same;
+
+        impl Solution for Same {
+            type Fitness = f64;
+
+            fn generate() -> Self {
+                Same
+            }
+
+            fn evaluate(&self) -> f64 {
+                EVALUATIONS.fetch_add(1, Ordering::Relaxed);
+                0.0
+            }
+
+            fn crossover(_: &mut Self, _: &mut Self) {}
+
+            fn mutate(&mut self) {}
+        }
+
+        let evo: Evolution<Same, _, _, ()> = Evolution::new(
+            alg::Simple::new(20, 1.0, 1.0, select::Tournament::new(3)),
+            hof::BestN::new(1),
+        )
+        .with_dedup_evaluation(true);
+        evo.run_for(3);
+        assert_eq!(EVALUATIONS.load(Ordering::Relaxed), 3);
+    }
 }
@@ -0,0 +1,195 @@
+//! Observing a run with several independent hooks
+//!
+//! An [`Observer`] is told about every generation of a run, every time the best solution improves,
+//! and when the run finishes. Any number of them can be registered on an [`Evolution`] with
+//! [`Evolution::with_observer()`], so a progress bar, a logger, and a plotter can each be written
+//! on their own instead of being squeezed into one callback.
+//!
+#![cfg_attr(feature = "std", doc = "```")]
+#![cfg_attr(not(feature = "std"), doc = "```ignore")]
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::{Benchmark, Sphere};
+//! use eviolite::observer::Observer;
+//! use eviolite::{Cached, Generation, Log};
+//!
+//! type Sphere3 = Benchmark<Sphere, 3>;
+//!
+//! struct Progress;
+//!
+//! type Hof = hof::BestN<Sphere3>;
+//!
+//! impl Observer<Sphere3, Hof, ()> for Progress {
+//!     fn on_improvement(&mut self, generation: Generation<Sphere3, Hof, ()>, best: &Cached<Sphere3>) {
+//!         println!("generation {}: new best {}", generation.gen, best.evaluate());
+//!     }
+//!
+//!     fn on_finish(&mut self, log: &Log<Sphere3, Hof, ()>) {
+//!         println!("done after {} evaluations", log.evaluations);
+//!     }
+//! }
+//!
+//! let evo: Evolution<Sphere3, _, _, ()> = Evolution::new(
+//!     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+//!     hof::BestN::new(1),
+//! )
+//! .with_observer(Progress);
+//! evo.run_for(20);
+//! ```
+//!
+//! The `indicatif` crate feature enables [`ProgressBar`], an observer that shows a run's progress
+//! on the terminal.
+//!
+//! [`Observer`]: ./trait.Observer.html
+//! [`ProgressBar`]: ./struct.ProgressBar.html
+//! [`Evolution`]: ../struct.Evolution.html
+//! [`Evolution::with_observer()`]: ../struct.Evolution.html#method.with_observer
+
+#[cfg(feature = "indicatif")]
+mod progress;
+
+#[cfg(feature = "indicatif")]
+#[cfg_attr(docsrs, doc(cfg(feature = "indicatif")))]
+pub use progress::ProgressBar;
+
+use crate::{
+    fitness::Direction, hof::HallOfFame, stats::GenerationStats, Cached, Generation, Log, Solution,
+};
+
+/// Hooks that are called as a run goes on
+///
+/// Every hook does nothing by default, so implementors only need the ones they use.
+/// See the [module-level documentation](./index.html) for an example.
+pub trait Observer<T, Hof, Stat>
+where
+    T: Solution,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+{
+    /// Called after every generation, once it has been evaluated and recorded in the hall of fame,
+    /// in the same place as the callback of [`Evolution::run_for_with()`].
+    ///
+    /// [`Evolution::run_for_with()`]: ../struct.Evolution.html#method.run_for_with
+    fn on_generation(&mut self, generation: Generation<T, Hof, Stat>) {
+        let _ = generation;
+    }
+
+    /// Called after [`.on_generation()`] whenever a generation contains a solution that's better
+    /// than any in the previous generations this observer saw, in the direction of the first objective
+    /// given by [`Solution::DIRECTIONS`]. This is always called for the first generation.
+    ///
+    /// [`.on_generation()`]: #method.on_generation
+    /// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+    fn on_improvement(&mut self, generation: Generation<T, Hof, Stat>, best: &Cached<T>) {
+        let _ = (generation, best);
+    }
+
+    /// Called once when the run ends, with its results.
+    fn on_finish(&mut self, log: &Log<T, Hof, Stat>) {
+        let _ = log;
+    }
+}
+
+// The object-safe part of an observer that `Evolution` keeps, which also keeps track
+// of the best fitness so far for `on_improvement`
+pub(crate) trait Observe<T, Hof, Stat>
+where
+    T: Solution,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+{
+    fn generation(&mut self, generation: Generation<T, Hof, Stat>);
+
+    fn finish(&mut self, log: &Log<T, Hof, Stat>);
+}
+
+pub(crate) struct Observed<O> {
+    pub(crate) observer: O,
+    pub(crate) best: Option<f64>,
+}
+
+impl<T, Hof, Stat, O> Observe<T, Hof, Stat> for Observed<O>
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+    O: Observer<T, Hof, Stat>,
+{
+    fn generation(&mut self, generation: Generation<T, Hof, Stat>) {
+        let direction = Direction::of::<T>(0);
+        let best = generation.pop.iter().reduce(|a, b| {
+            if direction.is_better(b.evaluate().into(), a.evaluate().into()) {
+                b
+            } else {
+                a
+            }
+        });
+        self.observer.on_generation(Generation { ..generation });
+        if let Some(best) = best {
+            let fitness = best.evaluate().into();
+            if self
+                .best
+                .is_none_or(|previous| direction.is_better(fitness, previous))
+            {
+                self.best = Some(fitness);
+                self.observer.on_improvement(generation, best);
+            }
+        }
+    }
+
+    fn finish(&mut self, log: &Log<T, Hof, Stat>) {
+        self.observer.on_finish(log);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use core::cell::RefCell;
+
+    use super::*;
+    use crate::{alg::Simple, hof::BestN, select::Tournament, testutils::Walk, Evolution};
+
+    #[derive(Default)]
+    struct Counts {
+        generations: usize,
+        improvements: Vec<f64>,
+        finished: usize,
+    }
+
+    struct Counter(Rc<RefCell<Counts>>);
+
+    impl Observer<Walk, BestN<Walk>, ()> for Counter {
+        fn on_generation(&mut self, _: Generation<Walk, BestN<Walk>, ()>) {
+            self.0.borrow_mut().generations += 1;
+        }
+
+        fn on_improvement(&mut self, _: Generation<Walk, BestN<Walk>, ()>, best: &Cached<Walk>) {
+            self.0.borrow_mut().improvements.push(best.evaluate());
+        }
+
+        fn on_finish(&mut self, log: &Log<Walk, BestN<Walk>, ()>) {
+            assert_eq!(log.stats.len(), 15);
+            self.0.borrow_mut().finished += 1;
+        }
+    }
+
+    #[test]
+    fn observers_are_called() {
+        let first = Rc::new(RefCell::new(Counts::default()));
+        let second = Rc::new(RefCell::new(Counts::default()));
+        let evo: Evolution<Walk, _, _, ()> =
+            Evolution::new(Simple::new(20, 0.5, 0.3, Tournament::new(2)), BestN::new(1))
+                .with_observer(Counter(first.clone()))
+                .with_observer(Counter(second.clone()));
+        evo.run_for(15);
+
+        for counts in [first, second] {
+            let counts = counts.borrow();
+            assert_eq!(counts.generations, 15);
+            assert_eq!(counts.finished, 1);
+            assert!(!counts.improvements.is_empty());
+            assert!(counts.improvements.windows(2).all(|pair| pair[1] > pair[0]));
+        }
+    }
+}
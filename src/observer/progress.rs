@@ -0,0 +1,73 @@
+use indicatif::ProgressStyle;
+
+use crate::{
+    hof::HallOfFame, observer::Observer, stats::GenerationStats, Cached, Generation, Log, Solution,
+};
+
+/// An [`Observer`] that shows a run's progress in a progress bar on the terminal
+///
+/// The bar shows the number of generations so far, the best fitness so far,
+/// the time since the start of the run, and an estimate of the time left.
+/// It's drawn with [`indicatif`] on standard error, and hidden if that isn't a terminal.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+/// use eviolite::observer::ProgressBar;
+///
+/// let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> = Evolution::new(
+///     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// )
+/// .with_observer(ProgressBar::new(100));
+/// evo.run_for(100);
+/// ```
+///
+/// [`Observer`]: ./trait.Observer.html
+pub struct ProgressBar {
+    bar: indicatif::ProgressBar,
+}
+
+impl ProgressBar {
+    /// Create a progress bar for a run of `n_gens` generations.
+    pub fn new(n_gens: u64) -> Self {
+        let style = ProgressStyle::with_template(
+            "{bar:40} {pos}/{len} generations, best {msg} [{elapsed_precise} < {eta_precise}]",
+        )
+        .expect("the template is valid");
+        ProgressBar::with_bar(indicatif::ProgressBar::new(n_gens).with_style(style))
+    }
+
+    /// Use a bar that was set up elsewhere, e.g. with a custom style or as part of a
+    /// [`MultiProgress`](https://docs.rs/indicatif/latest/indicatif/struct.MultiProgress.html).
+    ///
+    /// The best fitness is shown as the bar's message.
+    pub fn with_bar(bar: indicatif::ProgressBar) -> Self {
+        ProgressBar { bar }
+    }
+
+    /// Get the underlying bar.
+    pub fn bar(&self) -> &indicatif::ProgressBar {
+        &self.bar
+    }
+}
+
+impl<T, Hof, Stat> Observer<T, Hof, Stat> for ProgressBar
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+{
+    fn on_generation(&mut self, _: Generation<T, Hof, Stat>) {
+        self.bar.inc(1);
+    }
+
+    fn on_improvement(&mut self, _: Generation<T, Hof, Stat>, best: &Cached<T>) {
+        self.bar.set_message(format!("{}", best.evaluate().into()));
+    }
+
+    fn on_finish(&mut self, _: &Log<T, Hof, Stat>) {
+        self.bar.finish();
+    }
+}
@@ -2,12 +2,15 @@
 
 pub use crate::{
     alg, fitness,
-    fitness::MultiObjective,
+    fitness::{Direction, MultiObjective},
     hof,
     repro_rng::{random, thread_rng},
-    select, stats, Cached, Evolution, Solution,
+    select, species, stats, Cached, Evolution, Solution,
 };
 
+#[cfg(feature = "std")]
+pub use crate::{benchmarks, genome, gp};
+
 #[cfg(feature = "ndarray")]
 pub use crate::{crossover, mutation};
 
@@ -0,0 +1,660 @@
+//! Python bindings
+//!
+//! This module exposes [`Evolution`] and the pre-built single-objective algorithms to Python through [`pyo3`],
+//! with a solution type whose genome is an arbitrary Python object and whose operators are Python callables.
+//! This lets evaluation code stay in Python while selection, variation scheduling, and bookkeeping run in Rust.
+//!
+//! To build a Python extension module, create a `cdylib` crate that depends on Eviolite
+//! with the `pyo3` feature, and call [`register`] from its module initializer:
+//!
+//! ```ignore
+//! use pyo3::prelude::*;
+//!
+//! #[pymodule]
+//! fn eviolite(module: &Bound<'_, PyModule>) -> PyResult<()> {
+//!     eviolite::python::register(module)
+//! }
+//! ```
+//!
+//! From Python, the module is used like this:
+//!
+//! ```python
+//! import random
+//! import eviolite
+//!
+//! problem = eviolite.Problem(
+//!     generate=lambda: [random.uniform(-5, 5) for _ in range(10)],
+//!     evaluate=lambda x: -sum(v * v for v in x),
+//!     crossover=lambda a, b: (a[:5] + b[5:], b[:5] + a[5:]),
+//!     mutate=lambda x: [v + random.gauss(0, 0.1) for v in x],
+//! )
+//! algorithm = eviolite.Algorithm.mu_plus_lambda(50, 100, cxpb=0.6, mutpb=0.3)
+//! result = eviolite.Evolution(problem, algorithm, hall_of_fame=5).run_for(100)
+//! best_genome, best_fitness = result.hall_of_fame[0]
+//! ```
+//!
+//! Fitness is a single `float` and is maximized, like everywhere else in this crate.
+//! Operators must return new genomes rather than modifying their arguments,
+//! since the same genome object may be shared by several solutions.
+//! Evaluations run on Rust's worker threads but still take turns holding the GIL,
+//! so expensive evaluation functions should release it themselves (e.g. NumPy code) to benefit from parallelism.
+//!
+//! If a callback raises an exception, the run stops after the current generation
+//! and the exception is raised from `run_for` or `run_until`.
+//!
+//! [`Evolution`]: ../struct.Evolution.html
+//! [`register`]: ./fn.register.html
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use pyo3::{
+    exceptions::{PyRuntimeError, PyValueError},
+    prelude::*,
+    types::PyList,
+};
+
+use crate::{
+    alg,
+    hof::{self, BestN},
+    select::Tournament,
+    Cached, Solution,
+};
+
+struct Callbacks {
+    generate: PyObject,
+    evaluate: PyObject,
+    crossover: PyObject,
+    mutate: PyObject,
+    repair: Option<PyObject>,
+}
+
+static ACTIVE: RwLock<Option<Arc<Callbacks>>> = RwLock::new(None);
+static ERROR: Mutex<Option<PyErr>> = Mutex::new(None);
+
+fn active() -> Arc<Callbacks> {
+    ACTIVE
+        .read()
+        .unwrap()
+        .clone()
+        .expect("PySolution can only be used while a Python evolution is running")
+}
+
+// Keep the first exception raised by a callback, so it can be raised once the run stops.
+fn record_error(err: PyErr) {
+    let mut error = ERROR.lock().unwrap();
+    if error.is_none() {
+        *error = Some(err);
+    }
+}
+
+fn failed() -> bool {
+    ERROR.lock().unwrap().is_some()
+}
+
+/// A [`Solution`] whose genome is a Python object
+///
+/// Every operation calls the corresponding callback of the [`Problem`] that is currently being run.
+/// If a callback raises an exception, the genome is left unchanged or set to `None`,
+/// and the fitness is negative infinity.
+///
+/// Panics
+/// ======
+/// Generating or evaluating a `PySolution` panics outside of a run started from Python.
+///
+/// [`Solution`]: ../trait.Solution.html
+/// [`Problem`]: ./struct.Problem.html
+pub struct PySolution {
+    genome: PyObject,
+}
+
+impl PySolution {
+    /// Get the genome.
+    pub fn genome(&self) -> &PyObject {
+        &self.genome
+    }
+}
+
+impl Clone for PySolution {
+    fn clone(&self) -> Self {
+        Python::with_gil(|py| PySolution {
+            genome: self.genome.clone_ref(py),
+        })
+    }
+}
+
+impl Solution for PySolution {
+    type Fitness = f64;
+
+    fn generate() -> Self {
+        let callbacks = active();
+        Python::with_gil(|py| PySolution {
+            genome: callbacks.generate.call0(py).unwrap_or_else(|err| {
+                record_error(err);
+                py.None()
+            }),
+        })
+    }
+
+    fn evaluate(&self) -> f64 {
+        let callbacks = active();
+        Python::with_gil(|py| {
+            callbacks
+                .evaluate
+                .call1(py, (self.genome.clone_ref(py),))
+                .and_then(|fitness| fitness.extract(py))
+                .unwrap_or_else(|err| {
+                    record_error(err);
+                    f64::NEG_INFINITY
+                })
+        })
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        let callbacks = active();
+        Python::with_gil(|py| {
+            match callbacks
+                .crossover
+                .call1(py, (a.genome.clone_ref(py), b.genome.clone_ref(py)))
+                .and_then(|children| children.extract(py))
+            {
+                Ok((first, second)) => {
+                    a.genome = first;
+                    b.genome = second;
+                }
+                Err(err) => record_error(err),
+            }
+        })
+    }
+
+    fn mutate(&mut self) {
+        let callbacks = active();
+        Python::with_gil(
+            |py| match callbacks.mutate.call1(py, (self.genome.clone_ref(py),)) {
+                Ok(genome) => self.genome = genome,
+                Err(err) => record_error(err),
+            },
+        )
+    }
+
+    fn repair(&mut self) {
+        let callbacks = active();
+        if let Some(repair) = &callbacks.repair {
+            Python::with_gil(|py| match repair.call1(py, (self.genome.clone_ref(py),)) {
+                Ok(genome) => self.genome = genome,
+                Err(err) => record_error(err),
+            })
+        }
+    }
+}
+
+/// A problem defined by Python callables
+///
+/// In Python: `Problem(generate, evaluate, crossover, mutate, repair=None)`, where `generate()` returns a new genome,
+/// `evaluate(genome)` returns its fitness as a `float`, `crossover(a, b)` returns a tuple of two new genomes,
+/// and `mutate(genome)` returns a new genome. The optional `repair(genome)` returns a feasible version
+/// of a genome that was just crossed over or mutated.
+#[pyclass(module = "eviolite", frozen)]
+pub struct Problem {
+    callbacks: Arc<Callbacks>,
+}
+
+#[pymethods]
+impl Problem {
+    #[new]
+    #[pyo3(signature = (generate, evaluate, crossover, mutate, repair = None))]
+    fn new(
+        generate: PyObject,
+        evaluate: PyObject,
+        crossover: PyObject,
+        mutate: PyObject,
+        repair: Option<PyObject>,
+    ) -> Self {
+        Problem {
+            callbacks: Arc::new(Callbacks {
+                generate,
+                evaluate,
+                crossover,
+                mutate,
+                repair,
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum AlgorithmKind {
+    Simple {
+        pop_size: usize,
+        cxpb: f64,
+        mutpb: f64,
+        tournament_size: usize,
+    },
+    MuPlusLambda {
+        mu: usize,
+        lambda: usize,
+        cxpb: f64,
+        mutpb: f64,
+        tournament_size: usize,
+    },
+    MuCommaLambda {
+        mu: usize,
+        lambda: usize,
+        cxpb: f64,
+        mutpb: f64,
+        tournament_size: usize,
+    },
+    OnePlusLambda {
+        lambda: usize,
+    },
+}
+
+/// One of the pre-built [algorithms], with [tournament selection] where applicable
+///
+/// In Python, algorithms are created with the static methods `Algorithm.simple`, `Algorithm.mu_plus_lambda`,
+/// `Algorithm.mu_comma_lambda`, and `Algorithm.one_plus_lambda`, which take the same parameters
+/// as the corresponding Rust constructors, except that the selector is replaced by `tournament_size`,
+/// which defaults to 3.
+///
+/// [algorithms]: ../alg/index.html
+/// [tournament selection]: ../select/struct.Tournament.html
+#[pyclass(module = "eviolite", frozen)]
+pub struct Algorithm {
+    kind: AlgorithmKind,
+}
+
+// `exclusive` is for algorithms that apply either crossover or mutation, but never both.
+fn check_probabilities(cxpb: f64, mutpb: f64, exclusive: bool) -> PyResult<()> {
+    if !(0.0..=1.0).contains(&cxpb) || !(0.0..=1.0).contains(&mutpb) {
+        return Err(PyValueError::new_err(
+            "cxpb and mutpb must be probabilities",
+        ));
+    }
+    if exclusive && cxpb + mutpb > 1.0 {
+        return Err(PyValueError::new_err("cxpb + mutpb must be at most 1"));
+    }
+    Ok(())
+}
+
+#[pymethods]
+impl Algorithm {
+    #[staticmethod]
+    #[pyo3(signature = (pop_size, cxpb, mutpb, tournament_size = 3))]
+    fn simple(pop_size: usize, cxpb: f64, mutpb: f64, tournament_size: usize) -> PyResult<Self> {
+        check_probabilities(cxpb, mutpb, false)?;
+        Ok(Algorithm {
+            kind: AlgorithmKind::Simple {
+                pop_size,
+                cxpb,
+                mutpb,
+                tournament_size,
+            },
+        })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (mu, lambda_, cxpb, mutpb, tournament_size = 3))]
+    fn mu_plus_lambda(
+        mu: usize,
+        lambda_: usize,
+        cxpb: f64,
+        mutpb: f64,
+        tournament_size: usize,
+    ) -> PyResult<Self> {
+        check_probabilities(cxpb, mutpb, true)?;
+        Ok(Algorithm {
+            kind: AlgorithmKind::MuPlusLambda {
+                mu,
+                lambda: lambda_,
+                cxpb,
+                mutpb,
+                tournament_size,
+            },
+        })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (mu, lambda_, cxpb, mutpb, tournament_size = 3))]
+    fn mu_comma_lambda(
+        mu: usize,
+        lambda_: usize,
+        cxpb: f64,
+        mutpb: f64,
+        tournament_size: usize,
+    ) -> PyResult<Self> {
+        check_probabilities(cxpb, mutpb, true)?;
+        if mu > lambda_ {
+            return Err(PyValueError::new_err("(μ, λ) requires μ < λ"));
+        }
+        Ok(Algorithm {
+            kind: AlgorithmKind::MuCommaLambda {
+                mu,
+                lambda: lambda_,
+                cxpb,
+                mutpb,
+                tournament_size,
+            },
+        })
+    }
+
+    #[staticmethod]
+    fn one_plus_lambda(lambda_: usize) -> PyResult<Self> {
+        if lambda_ == 0 {
+            return Err(PyValueError::new_err("(1 + λ) requires λ > 0"));
+        }
+        Ok(Algorithm {
+            kind: AlgorithmKind::OnePlusLambda { lambda: lambda_ },
+        })
+    }
+}
+
+/// The results of a run started from Python
+///
+/// In Python, `hall_of_fame` and `final_population` are lists of `(genome, fitness)` tuples,
+/// with the hall of fame sorted from best to worst, and `best` and `mean` are lists
+/// with the best and mean fitness of every generation.
+#[pyclass(module = "eviolite", frozen)]
+pub struct RunResult {
+    #[pyo3(get)]
+    hall_of_fame: Py<PyList>,
+    #[pyo3(get)]
+    final_population: Py<PyList>,
+    #[pyo3(get)]
+    best: Vec<f64>,
+    #[pyo3(get)]
+    mean: Vec<f64>,
+}
+
+/// A single run of an evolutionary algorithm on a [`Problem`], driven from Python
+///
+/// In Python: `Evolution(problem, algorithm, hall_of_fame=1)`, where `hall_of_fame` is the number
+/// of best solutions to keep. `run_for(n_gens)` runs for a number of generations, and
+/// `run_until(predicate)` runs until `predicate(gen, best, mean)` returns `True`.
+/// Both return a `RunResult`.
+///
+/// [`Problem`]: ./struct.Problem.html
+#[pyclass(module = "eviolite", frozen)]
+pub struct Evolution {
+    callbacks: Arc<Callbacks>,
+    kind: AlgorithmKind,
+    hall_of_fame: usize,
+}
+
+type PyLog = crate::Log<PySolution, BestN<PySolution>, ()>;
+
+#[pymethods]
+impl Evolution {
+    #[new]
+    #[pyo3(signature = (problem, algorithm, hall_of_fame = 1))]
+    fn new(problem: &Problem, algorithm: &Algorithm, hall_of_fame: usize) -> Self {
+        Evolution {
+            callbacks: problem.callbacks.clone(),
+            kind: algorithm.kind,
+            hall_of_fame,
+        }
+    }
+
+    fn run_for(&self, py: Python<'_>, n_gens: usize) -> PyResult<RunResult> {
+        self.run(py, move |gen, _, _| Ok(gen >= n_gens))
+    }
+
+    fn run_until(&self, py: Python<'_>, predicate: PyObject) -> PyResult<RunResult> {
+        self.run(py, move |gen, best, mean| {
+            Python::with_gil(|py| predicate.call1(py, (gen, best, mean))?.is_truthy(py))
+        })
+    }
+}
+
+impl Evolution {
+    fn run(
+        &self,
+        py: Python<'_>,
+        stop: impl FnMut(usize, f64, f64) -> PyResult<bool> + Send,
+    ) -> PyResult<RunResult> {
+        {
+            let mut active = ACTIVE.write().unwrap();
+            if active.is_some() {
+                return Err(PyRuntimeError::new_err(
+                    "only one evolution can run at a time",
+                ));
+            }
+            *active = Some(self.callbacks.clone());
+        }
+        ERROR.lock().unwrap().take();
+
+        let (kind, hall_of_fame) = (self.kind, self.hall_of_fame);
+        let (log, best, mean) = py.allow_threads(move || match kind {
+            AlgorithmKind::Simple {
+                pop_size,
+                cxpb,
+                mutpb,
+                tournament_size,
+            } => run_with(
+                alg::Simple::new(pop_size, cxpb, mutpb, Tournament::new(tournament_size)),
+                hall_of_fame,
+                stop,
+            ),
+            AlgorithmKind::MuPlusLambda {
+                mu,
+                lambda,
+                cxpb,
+                mutpb,
+                tournament_size,
+            } => run_with(
+                alg::MuPlusLambda::new(mu, lambda, cxpb, mutpb, Tournament::new(tournament_size)),
+                hall_of_fame,
+                stop,
+            ),
+            AlgorithmKind::MuCommaLambda {
+                mu,
+                lambda,
+                cxpb,
+                mutpb,
+                tournament_size,
+            } => run_with(
+                alg::MuCommaLambda::new(mu, lambda, cxpb, mutpb, Tournament::new(tournament_size)),
+                hall_of_fame,
+                stop,
+            ),
+            AlgorithmKind::OnePlusLambda { lambda } => {
+                run_with(alg::OnePlusLambda::new(lambda), hall_of_fame, stop)
+            }
+        });
+
+        ACTIVE.write().unwrap().take();
+        if let Some(err) = ERROR.lock().unwrap().take() {
+            return Err(err);
+        }
+
+        let pairs = |solutions: &[Cached<PySolution>]| {
+            PyList::new(
+                py,
+                solutions
+                    .iter()
+                    .map(|sol| (sol.as_ref().genome.clone_ref(py), sol.evaluate())),
+            )
+            .map(Bound::unbind)
+        };
+        Ok(RunResult {
+            hall_of_fame: pairs(&log.hall_of_fame)?,
+            final_population: pairs(&log.final_population)?,
+            best,
+            mean,
+        })
+    }
+}
+
+fn run_with<A: alg::Algorithm<PySolution>>(
+    algorithm: A,
+    hall_of_fame: usize,
+    mut stop: impl FnMut(usize, f64, f64) -> PyResult<bool>,
+) -> (PyLog, Vec<f64>, Vec<f64>) {
+    let (mut best, mut mean) = (Vec::new(), Vec::new());
+    let evo: crate::Evolution<PySolution, _, _, ()> =
+        crate::Evolution::new(algorithm, hof::BestN::new(hall_of_fame));
+    let log = evo.run_until(|generation| {
+        if failed() {
+            return true;
+        }
+        let fitnesses: Vec<f64> = generation.pop.iter().map(|ind| ind.evaluate()).collect();
+        let gen_best = fitnesses.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let gen_mean = fitnesses.iter().sum::<f64>() / fitnesses.len() as f64;
+        match stop(generation.gen, gen_best, gen_mean) {
+            Ok(true) => true,
+            Ok(false) => {
+                best.push(gen_best);
+                mean.push(gen_mean);
+                false
+            }
+            Err(err) => {
+                record_error(err);
+                true
+            }
+        }
+    });
+    (log, best, mean)
+}
+
+/// Add the Python classes `Problem`, `Algorithm`, `Evolution`, and `RunResult` to `module`.
+pub fn register(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<Problem>()?;
+    module.add_class::<Algorithm>()?;
+    module.add_class::<Evolution>()?;
+    module.add_class::<RunResult>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use pyo3::{ffi::c_str, types::PyDict};
+
+    use super::*;
+
+    // Only one evolution can run at a time, so tests that start one take turns
+    static RUNNING: Mutex<()> = Mutex::new(());
+
+    // Run `code` with the bindings available as `eviolite`, returning its globals
+    fn run_python<'py>(py: Python<'py>, code: &CStr) -> PyResult<Bound<'py, PyDict>> {
+        let module = PyModule::new(py, "eviolite")?;
+        register(&module)?;
+        let globals = PyDict::new(py);
+        globals.set_item("eviolite", module)?;
+        py.run(code, Some(&globals), None)?;
+        Ok(globals)
+    }
+
+    #[test]
+    fn run_from_python() {
+        pyo3::prepare_freethreaded_python();
+        let _running = RUNNING.lock().unwrap_or_else(|err| err.into_inner());
+        Python::with_gil(|py| {
+            let globals = run_python(
+                py,
+                c_str!(
+                    r#"
+import random
+problem = eviolite.Problem(
+    generate=lambda: random.uniform(-5, 5),
+    evaluate=lambda x: -x * x,
+    crossover=lambda a, b: ((a + b) / 2, b),
+    mutate=lambda x: x + random.gauss(0, 0.1),
+    repair=lambda x: min(max(x, -5), 5),
+)
+algorithm = eviolite.Algorithm.mu_plus_lambda(20, 40, cxpb=0.5, mutpb=0.3)
+result = eviolite.Evolution(problem, algorithm, hall_of_fame=3).run_for(10)
+until = eviolite.Evolution(problem, eviolite.Algorithm.one_plus_lambda(4)).run_until(
+    lambda gen, best, mean: gen >= 5
+)
+"#
+                ),
+            )
+            .unwrap();
+
+            let result = globals.get_item("result").unwrap().unwrap();
+            let best: Vec<f64> = result.getattr("best").unwrap().extract().unwrap();
+            let mean: Vec<f64> = result.getattr("mean").unwrap().extract().unwrap();
+            assert_eq!(best.len(), 10);
+            assert_eq!(mean.len(), 10);
+            assert!(best.iter().zip(&mean).all(|(best, mean)| best >= mean));
+
+            let hall_of_fame: Vec<(f64, f64)> =
+                result.getattr("hall_of_fame").unwrap().extract().unwrap();
+            assert_eq!(hall_of_fame.len(), 3);
+            assert!(hall_of_fame.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+            for (genome, fitness) in &hall_of_fame {
+                assert!((-5.0..=5.0).contains(genome));
+                assert_eq!(*fitness, -genome * genome);
+            }
+            // the hall of fame keeps the best solution ever seen
+            assert!(best.iter().all(|&best| hall_of_fame[0].1 >= best));
+            let final_population: Vec<(f64, f64)> = result
+                .getattr("final_population")
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(final_population.len(), 20);
+
+            let until = globals.get_item("until").unwrap().unwrap();
+            let best: Vec<f64> = until.getattr("best").unwrap().extract().unwrap();
+            assert_eq!(best.len(), 5);
+        });
+    }
+
+    #[test]
+    fn callback_exceptions() {
+        pyo3::prepare_freethreaded_python();
+        let _running = RUNNING.lock().unwrap_or_else(|err| err.into_inner());
+        Python::with_gil(|py| {
+            let err = run_python(
+                py,
+                c_str!(
+                    r#"
+def evaluate(x):
+    raise KeyError("bad genome")
+
+problem = eviolite.Problem(
+    generate=lambda: 0.0,
+    evaluate=evaluate,
+    crossover=lambda a, b: (a, b),
+    mutate=lambda x: x,
+)
+eviolite.Evolution(problem, eviolite.Algorithm.simple(10, 0.5, 0.2)).run_for(100)
+"#
+                ),
+            )
+            .unwrap_err();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyKeyError>(py));
+
+            // the failed run doesn't stop the next one from starting
+            run_python(
+                py,
+                c_str!(
+                    r#"
+problem = eviolite.Problem(
+    generate=lambda: 0.0,
+    evaluate=lambda x: x,
+    crossover=lambda a, b: (a, b),
+    mutate=lambda x: x + 1,
+)
+eviolite.Evolution(problem, eviolite.Algorithm.simple(10, 0.5, 0.2)).run_for(2)
+"#
+                ),
+            )
+            .unwrap();
+        });
+    }
+
+    #[test]
+    fn algorithm_parameters() {
+        assert!(Algorithm::simple(10, 0.7, 0.7, 3).is_ok());
+        assert!(Algorithm::simple(10, 1.5, 0.2, 3).is_err());
+        assert!(Algorithm::mu_plus_lambda(10, 20, 0.5, 0.5, 3).is_ok());
+        assert!(Algorithm::mu_plus_lambda(10, 20, 0.7, 0.7, 3).is_err());
+        assert!(Algorithm::mu_comma_lambda(10, 20, 0.5, 0.2, 3).is_ok());
+        assert!(Algorithm::mu_comma_lambda(30, 20, 0.5, 0.2, 3).is_err());
+        assert!(Algorithm::mu_comma_lambda(10, 20, -0.1, 0.2, 3).is_err());
+        assert!(Algorithm::one_plus_lambda(4).is_ok());
+        assert!(Algorithm::one_plus_lambda(0).is_err());
+    }
+}
@@ -14,25 +14,88 @@
 //! If it fails, either in reading `EVIOLITE_SEED` or in parsing it as a `u64`, it will seed itself with
 //! a random number provided by the OS, and print the seed it used to standard error.
 //!
+//! The seed can also be set from within the program with [`set_seed`], which takes precedence
+//! over the environment variable.
+//!
 //! If you want to reproduce a run, **make sure to copy the seed from standard error and keep it.**
+//! The seed can also be retrieved programmatically with [`seed`].
 //! In addition, **make sure never to use randomness in your [`Solution`]'s [`evaluate()`] method.**
 //! Any sane fitness evaluation shouldn't be random, so this shouldn't be much of a limitation.
 //!
+//! When a run is split across several processes, e.g. one island per MPI rank, seed each of them
+//! with [`reseed_stream`] and its rank, so they all draw different values from the same seed.
+//!
+//! To pause a run and continue it later with the same random values, capture the RNG's position with
+//! [`ReproThreadRng::state`] and restore it with [`ReproThreadRng::set_state`].
+//! [Checkpoints](../checkpoint/index.html) do this automatically.
+//!
+//! WebAssembly
+//! -----------
+//! On `wasm32-unknown-unknown`, there is neither an environment to read `EVIOLITE_SEED` from
+//! nor a standard error stream to print the seed to, and OS randomness is only available through JavaScript.
+//! Enable the `js` crate feature to seed the RNG from the browser's `crypto.getRandomValues`,
+//! and use [`seed`] to retrieve the seed that was used.
+//!
+//! Without the standard library
+//! ----------------------------
+//! If the `std` crate feature is disabled, there is no environment, no OS randomness, and no threads.
+//! Instead of a thread-local RNG, there is a single global one,
+//! and it has to be seeded with [`reseed`] before anything draws from it;
+//! drawing from it before then panics. It is guarded by a spin lock, so it can be shared
+//! between cores and with interrupt handlers, but a handler must not draw from it
+//! while the code it interrupted might be drawing too, since it would wait for that draw forever.
+//! Mask the interrupt around draws in the main loop, or keep randomness out of handlers.
+//!
+//! Tracing
+//! -------
+//! If two runs that should be identical end up diverging, you can record a trace of every
+//! value drawn from the RNG by calling [`start_trace`] before the run and [`stop_trace`] after it.
+//! The resulting [`Trace`]s can be compared directly with [`Trace::divergence`], or written to
+//! a file with [`Trace::write_to`] and compared with any line-based diff tool.
+//! Use [`trace_mark`] to insert labels into the trace so that a divergence can be
+//! attributed to a specific part of your program.
+//!
+//! Tracing is per-thread, just like the RNG itself, and has no effect until it is started.
+//!
 //! [`random`]: ./fn.random.html
 //! [`thread_rng`]: ./fn.thread_rng.html
+//! [`seed`]: ./fn.seed.html
+//! [`reseed`]: ./fn.reseed.html
+//! [`set_seed`]: ./fn.set_seed.html
+//! [`reseed_stream`]: ./fn.reseed_stream.html
+//! [`ReproThreadRng::state`]: ./struct.ReproThreadRng.html#method.state
+//! [`ReproThreadRng::set_state`]: ./struct.ReproThreadRng.html#method.set_state
+//! [`start_trace`]: ./fn.start_trace.html
+//! [`stop_trace`]: ./fn.stop_trace.html
+//! [`trace_mark`]: ./fn.trace_mark.html
+//! [`Trace`]: ./struct.Trace.html
+//! [`Trace::divergence`]: ./struct.Trace.html#method.divergence
+//! [`Trace::write_to`]: ./struct.Trace.html#method.write_to
 //! [`Solution`]: ../trait.Solution.html
 //! [`evaluate()`]: ../trait.Solution.html#tymethod.evaluate
 
-use std::cell::UnsafeCell;
+use alloc::{string::String, vec::Vec};
+use core::cell::UnsafeCell;
+#[cfg(not(feature = "std"))]
+use core::marker::PhantomData;
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use std::io::{self, BufRead, Write};
+#[cfg(feature = "std")]
 use std::rc::Rc;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
 
 use rand::distributions::Standard;
 use rand::prelude::Distribution;
+#[cfg(feature = "std")]
 use rand::rngs::OsRng;
 use rand::Rng;
 use rand::{RngCore, SeedableRng};
-use rand_xoshiro::Xoshiro256StarStar;
+use rand_xoshiro::{SplitMix64, Xoshiro256StarStar};
 
+#[cfg(feature = "std")]
 const SEED_ENV_VAR_NAME: &str = "EVIOLITE_SEED";
 
 /// A reference to the thread-local reproducible RNG
@@ -41,25 +104,233 @@ const SEED_ENV_VAR_NAME: &str = "EVIOLITE_SEED";
 /// except that it can be seeded from an environment variable and uses a faster RNG.
 /// See the [module-level documentation][./index.html] for further information.
 pub struct ReproThreadRng {
-    rng: Rc<UnsafeCell<Xoshiro256StarStar>>,
+    #[cfg(feature = "std")]
+    rng: Rc<UnsafeCell<ThreadState>>,
+    #[cfg(not(feature = "std"))]
+    _marker: PhantomData<*mut ThreadState>,
+}
+
+/// A snapshot of the reproducible RNG's position in its random stream
+///
+/// Get one from [`ReproThreadRng::state`], and pass it to [`ReproThreadRng::set_state`]
+/// to continue drawing exactly the same values from that point on, e.g. after resuming a saved run.
+/// With the `serde` crate feature, it can be serialized along with the rest of the run.
+///
+/// [`ReproThreadRng::state`]: ./struct.ReproThreadRng.html#method.state
+/// [`ReproThreadRng::set_state`]: ./struct.ReproThreadRng.html#method.set_state
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RngState {
+    pub(crate) seed: u64,
+    pub(crate) rng: Xoshiro256StarStar,
+}
+
+impl RngState {
+    /// Get the seed that the RNG was initialized with, as returned by [`seed`].
+    ///
+    /// [`seed`]: ./fn.seed.html
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
 }
 
+struct ThreadState {
+    rng: Xoshiro256StarStar,
+    seed: u64,
+    trace: Option<Trace>,
+}
+
+impl ThreadState {
+    fn new(seed: u64) -> Self {
+        ThreadState {
+            rng: Xoshiro256StarStar::seed_from_u64(seed),
+            seed,
+            trace: None,
+        }
+    }
+
+    #[inline(always)]
+    fn record(&mut self, value: u64) {
+        if let Some(trace) = &mut self.trace {
+            trace.draws.push(value);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 thread_local! {
-    static THREAD_RNG_KEY: Rc<UnsafeCell<Xoshiro256StarStar>> = {
-        let seed: u64 = match std::env::var(SEED_ENV_VAR_NAME).map(|s| s.parse::<u64>()) {
-            Ok(Ok(seed)) => seed,
-            _ => {
-                eprintln!("eviolite: unable to read preset RNG seed from environment variable {}", SEED_ENV_VAR_NAME);
-                let seed = OsRng.next_u64();
-                eprintln!("eviolite: using OS-generated seed {}", seed);
-                seed
-            }
+    static THREAD_RNG_KEY: Rc<UnsafeCell<ThreadState>> = {
+        let seed: u64 = match (preset_seed(), std::env::var(SEED_ENV_VAR_NAME).map(|s| s.parse::<u64>())) {
+            (Some(seed), _) => seed,
+            (None, Ok(Ok(seed))) => seed,
+            _ => os_seed(),
         };
 
-        let rng = Xoshiro256StarStar::seed_from_u64(seed);
+        Rc::new(UnsafeCell::new(ThreadState::new(seed)))
+    }
+}
+
+// Without `std`, there's one state shared by every execution context, behind a spin lock.
+#[cfg(not(feature = "std"))]
+struct GlobalState {
+    locked: AtomicBool,
+    state: UnsafeCell<Option<ThreadState>>,
+}
+
+// SAFETY: the state is only accessed through `with_global_state`, which holds the lock while it is.
+#[cfg(not(feature = "std"))]
+unsafe impl Sync for GlobalState {}
+
+#[cfg(not(feature = "std"))]
+static GLOBAL_STATE: GlobalState = GlobalState {
+    locked: AtomicBool::new(false),
+    state: UnsafeCell::new(None),
+};
+
+// The seed set with `set_seed`, which takes precedence over the environment variable
+#[cfg(feature = "std")]
+static PRESET_SEED: Mutex<Option<u64>> = Mutex::new(None);
+
+#[cfg(feature = "std")]
+fn preset_seed() -> Option<u64> {
+    *PRESET_SEED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+fn os_seed() -> u64 {
+    eprintln!(
+        "eviolite: unable to read preset RNG seed from environment variable {}",
+        SEED_ENV_VAR_NAME
+    );
+    let seed = OsRng.next_u64();
+    eprintln!("eviolite: using OS-generated seed {}", seed);
+    seed
+}
+
+// There is no environment or standard error to speak of in the browser,
+// so the seed is only reported through `seed()`.
+#[cfg(all(feature = "std", target_arch = "wasm32"))]
+fn os_seed() -> u64 {
+    OsRng.next_u64()
+}
 
-        Rc::new(UnsafeCell::new(rng))
+/// Get the seed that this thread's reproducible RNG was initialized with.
+///
+/// This is the same seed that is printed to standard error when none is provided,
+/// and is the only way to retrieve it on targets without a standard error stream, such as WebAssembly.
+pub fn seed() -> u64 {
+    with_state(|state| state.seed)
+}
+
+/// Seed the reproducible RNG of every thread with `seed`, instead of reading it from `EVIOLITE_SEED`.
+///
+/// This is the programmatic equivalent of setting the environment variable, for libraries and tests
+/// that can't control the environment. Call it before anything draws from the RNG: threads that
+/// haven't used their RNG yet, including the worker threads used for parallel evaluation,
+/// are initialized with `seed`, and so is the current thread's RNG right away.
+/// Threads that have already used their RNG are unaffected; use [`reseed`] on them if needed.
+///
+/// Without the `std` crate feature, this is the same as [`reseed`].
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use eviolite::repro_rng::{random, seed, set_seed};
+///
+/// set_seed(42);
+/// assert_eq!(seed(), 42);
+/// let first: u64 = random();
+///
+/// std::thread::spawn(move || {
+///     assert_eq!(seed(), 42);
+///     assert_eq!(random::<u64>(), first);
+/// })
+/// .join()
+/// .unwrap();
+/// ```
+///
+/// [`reseed`]: ./fn.reseed.html
+pub fn set_seed(seed: u64) {
+    #[cfg(feature = "std")]
+    {
+        *PRESET_SEED
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(seed);
     }
+    reseed(seed);
+}
+
+/// Re-seed this thread's reproducible RNG, as if it had been initialized with `seed`.
+///
+/// This makes it possible to reproduce several runs within one program independently of each other,
+/// e.g. when repeating an experiment with a list of seeds. Only the current thread's RNG is affected,
+/// and [`seed`] returns the new seed afterwards.
+///
+/// [`seed`]: ./fn.seed.html
+pub fn reseed(seed: u64) {
+    replace_rng(seed, Xoshiro256StarStar::seed_from_u64(seed));
+}
+
+/// Re-seed this thread's reproducible RNG with one of many independent streams derived from `seed`.
+///
+/// This is meant for runs that are split across several processes or threads from a single seed,
+/// e.g. one island per MPI rank: giving each of them its own `stream`, like its rank,
+/// makes them draw different values that are still reproducible from `seed` alone.
+/// Every stream starts at its own pseudo-random point in the generator's period of 2<sup>256</sup> − 1 values,
+/// so any two of them are practically certain never to overlap, and any `stream`, e.g. a hash,
+/// takes the same constant time to derive.
+///
+/// Even stream `0` is derived like the others rather than being [`reseed`]`(seed)`,
+/// and [`seed`] returns `seed` afterwards.
+///
+/// [`reseed`]: ./fn.reseed.html
+/// [`seed`]: ./fn.seed.html
+pub fn reseed_stream(seed: u64, stream: u64) {
+    // The seed is scrambled before the stream is mixed in, so that e.g. stream 1 of seed 2
+    // and stream 2 of seed 1 are unrelated. For the same seed, different streams always
+    // start from different states, since seeding goes through SplitMix64, which is a bijection.
+    let mixed = SplitMix64::seed_from_u64(seed).next_u64() ^ stream;
+    replace_rng(seed, Xoshiro256StarStar::seed_from_u64(mixed));
+}
+
+fn replace_rng(seed: u64, rng: Xoshiro256StarStar) {
+    #[cfg(feature = "std")]
+    with_state(|state| {
+        state.rng = rng;
+        state.seed = seed;
+    });
+    #[cfg(not(feature = "std"))]
+    with_global_state(|global| match global {
+        Some(state) => {
+            state.rng = rng;
+            state.seed = seed;
+        }
+        empty => {
+            let mut state = ThreadState::new(seed);
+            state.rng = rng;
+            *empty = Some(state);
+        }
+    });
+}
+
+// Run `f` with this thread's RNG temporarily replaced by a fresh stream seeded with `seed`,
+// so that work which is spread across threads in a nondeterministic way still draws
+// deterministic values. The previous stream is restored afterward, even if `f` panics.
+pub(crate) fn with_stream<R>(seed: u64, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Xoshiro256StarStar>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let rng = self.0.take().unwrap();
+            with_state(|state| state.rng = rng);
+        }
+    }
+
+    let _restore = Restore(Some(with_state(|state| {
+        core::mem::replace(&mut state.rng, Xoshiro256StarStar::seed_from_u64(seed))
+    })));
+    f()
 }
 
 /// Generate a random value using the reproducible thread-local RNG.
@@ -83,32 +354,372 @@ pub fn thread_rng() -> ReproThreadRng {
 }
 
 impl Default for ReproThreadRng {
+    #[cfg(feature = "std")]
     fn default() -> Self {
         let rng = THREAD_RNG_KEY.with(|t| t.clone());
         ReproThreadRng { rng }
     }
+
+    #[cfg(not(feature = "std"))]
+    fn default() -> Self {
+        ReproThreadRng {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl ReproThreadRng {
+    /// Capture the RNG's current position in its random stream, along with its seed.
+    ///
+    /// Since every handle on a thread refers to the same RNG, this is the state of this thread's RNG.
+    pub fn state(&self) -> RngState {
+        with_state(|state| RngState {
+            seed: state.seed,
+            rng: state.rng.clone(),
+        })
+    }
+
+    /// Move the RNG to a position captured with [`.state()`], so it draws the same values
+    /// as it did from that point on, and [`seed`] returns the seed it was captured with.
+    ///
+    /// A trace being recorded isn't affected.
+    ///
+    /// [`.state()`]: #method.state
+    /// [`seed`]: ./fn.seed.html
+    pub fn set_state(&mut self, state: RngState) {
+        replace_rng(state.seed, state.rng);
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    fn with_thread_state<R>(&mut self, f: impl FnOnce(&mut ThreadState) -> R) -> R {
+        f(unsafe { &mut *self.rng.get() })
+    }
+
+    #[cfg(not(feature = "std"))]
+    #[inline(always)]
+    fn with_thread_state<R>(&mut self, f: impl FnOnce(&mut ThreadState) -> R) -> R {
+        with_state(f)
+    }
 }
 
 impl RngCore for ReproThreadRng {
     #[inline(always)]
     fn next_u32(&mut self) -> u32 {
-        let rng = unsafe { &mut *self.rng.get() };
-        rng.next_u32()
+        self.with_thread_state(|state| {
+            let value = state.rng.next_u32();
+            state.record(value as u64);
+            value
+        })
     }
 
     #[inline(always)]
     fn next_u64(&mut self) -> u64 {
-        let rng = unsafe { &mut *self.rng.get() };
-        rng.next_u64()
+        self.with_thread_state(|state| {
+            let value = state.rng.next_u64();
+            state.record(value);
+            value
+        })
     }
 
     fn fill_bytes(&mut self, dest: &mut [u8]) {
-        let rng = unsafe { &mut *self.rng.get() };
-        rng.fill_bytes(dest);
+        self.with_thread_state(|state| {
+            state.rng.fill_bytes(dest);
+            state.record(fold_bytes(dest));
+        })
     }
 
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
-        let rng = unsafe { &mut *self.rng.get() };
-        rng.try_fill_bytes(dest)
+        self.with_thread_state(|state| {
+            state.rng.try_fill_bytes(dest)?;
+            state.record(fold_bytes(dest));
+            Ok(())
+        })
+    }
+}
+
+// FNV-1a, used to record a whole `fill_bytes` call as a single trace entry
+fn fold_bytes(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// A record of the values drawn from the reproducible RNG on one thread
+///
+/// Every call to the RNG is recorded as a single `u64`
+/// (calls that fill a byte buffer are folded into one value),
+/// along with any labels inserted with [`trace_mark`].
+/// Two runs with the same seed should always produce identical traces,
+/// so the first position where two traces differ is where determinism was lost.
+///
+/// [`trace_mark`]: ./fn.trace_mark.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Trace {
+    draws: Vec<u64>,
+    marks: Vec<(usize, String)>,
+}
+
+/// The first point at which two [`Trace`]s differ
+///
+/// [`Trace`]: ./struct.Trace.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// The index of the first draw that differs between the two traces.
+    pub index: usize,
+    /// The most recent label inserted before the divergence in the first trace, if any.
+    pub last_mark: Option<String>,
+    /// The value drawn at `index` in the first trace, or `None` if it ended there.
+    pub left: Option<u64>,
+    /// The value drawn at `index` in the second trace, or `None` if it ended there.
+    pub right: Option<u64>,
+}
+
+impl Trace {
+    /// Get the number of draws recorded in the trace.
+    pub fn len(&self) -> usize {
+        self.draws.len()
+    }
+
+    /// Check whether the trace recorded no draws at all.
+    pub fn is_empty(&self) -> bool {
+        self.draws.is_empty()
+    }
+
+    /// Get the recorded draws in the order they happened.
+    pub fn draws(&self) -> &[u64] {
+        &self.draws
+    }
+
+    /// Get the labels inserted with [`trace_mark`], each paired with
+    /// the number of draws that had been recorded when it was inserted.
+    ///
+    /// [`trace_mark`]: ./fn.trace_mark.html
+    pub fn marks(&self) -> &[(usize, String)] {
+        &self.marks
+    }
+
+    /// Find the first point at which `self` and `other` differ.
+    ///
+    /// Returns `None` if the traces recorded exactly the same draws.
+    /// Labels are not compared, only the draws themselves.
+    pub fn divergence(&self, other: &Trace) -> Option<Divergence> {
+        let index = self
+            .draws
+            .iter()
+            .zip(other.draws.iter())
+            .position(|(a, b)| a != b)
+            .unwrap_or_else(|| self.draws.len().min(other.draws.len()));
+
+        if index == self.draws.len() && index == other.draws.len() {
+            return None;
+        }
+
+        Some(Divergence {
+            index,
+            last_mark: self
+                .marks
+                .iter()
+                .take_while(|(at, _)| *at <= index)
+                .last()
+                .map(|(_, label)| label.clone()),
+            left: self.draws.get(index).copied(),
+            right: other.draws.get(index).copied(),
+        })
+    }
+
+    /// Write the trace in a line-based text format.
+    ///
+    /// Each draw is written as a decimal number on its own line,
+    /// and each label is written on its own line prefixed with `#`.
+    /// This makes the output easy to compare with `diff` or similar tools.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut marks = self.marks.iter().peekable();
+        for (i, draw) in self.draws.iter().enumerate() {
+            while let Some((_, label)) = marks.next_if(|(at, _)| *at == i) {
+                writeln!(writer, "# {}", label)?;
+            }
+            writeln!(writer, "{}", draw)?;
+        }
+        for (_, label) in marks {
+            writeln!(writer, "# {}", label)?;
+        }
+        Ok(())
+    }
+
+    /// Read a trace in the format produced by [`.write_to()`].
+    ///
+    /// [`.write_to()`]: ./struct.Trace.html#method.write_to
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn read_from<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut trace = Trace::default();
+        for line in reader.lines() {
+            let line = line?;
+            if let Some(label) = line.strip_prefix('#') {
+                trace
+                    .marks
+                    .push((trace.draws.len(), label.trim_start().to_owned()));
+            } else if !line.trim().is_empty() {
+                let draw = line
+                    .trim()
+                    .parse()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                trace.draws.push(draw);
+            }
+        }
+        Ok(trace)
+    }
+}
+
+/// Start recording a [`Trace`] of every value drawn from this thread's reproducible RNG.
+///
+/// If a trace was already being recorded, it is discarded and a new one is started.
+///
+/// [`Trace`]: ./struct.Trace.html
+pub fn start_trace() {
+    with_state(|state| state.trace = Some(Trace::default()));
+}
+
+/// Stop recording and return the [`Trace`] recorded since the last call to [`start_trace`].
+///
+/// Returns `None` if no trace was being recorded on this thread.
+///
+/// [`Trace`]: ./struct.Trace.html
+/// [`start_trace`]: ./fn.start_trace.html
+pub fn stop_trace() -> Option<Trace> {
+    with_state(|state| state.trace.take())
+}
+
+/// Insert a label into the trace currently being recorded on this thread.
+///
+/// Does nothing if no trace is being recorded.
+pub fn trace_mark(label: impl Into<String>) {
+    with_state(|state| {
+        if let Some(trace) = &mut state.trace {
+            trace.marks.push((trace.draws.len(), label.into()));
+        }
+    });
+}
+
+#[cfg(feature = "std")]
+fn with_state<R>(f: impl FnOnce(&mut ThreadState) -> R) -> R {
+    THREAD_RNG_KEY.with(|t| f(unsafe { &mut *t.get() }))
+}
+
+#[cfg(not(feature = "std"))]
+fn with_state<R>(f: impl FnOnce(&mut ThreadState) -> R) -> R {
+    with_global_state(|state| {
+        f(state.as_mut().expect(
+            "eviolite: the RNG must be seeded with repro_rng::reseed before it is used without std",
+        ))
+    })
+}
+
+// Run `f` on the global state while holding its lock, so no other core or interrupt handler can touch it.
+// `f` never draws from the RNG itself, so the lock is never taken twice by the same context.
+#[cfg(not(feature = "std"))]
+fn with_global_state<R>(f: impl FnOnce(&mut Option<ThreadState>) -> R) -> R {
+    struct Unlock;
+
+    impl Drop for Unlock {
+        fn drop(&mut self) {
+            GLOBAL_STATE.locked.store(false, Ordering::Release);
+        }
+    }
+
+    while GLOBAL_STATE
+        .locked
+        .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+        .is_err()
+    {
+        core::hint::spin_loop();
+    }
+    let _unlock = Unlock;
+    // SAFETY: holding the lock gives exclusive access to the state until `_unlock` is dropped
+    f(unsafe { &mut *GLOBAL_STATE.state.get() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_roundtrip_and_divergence() {
+        start_trace();
+        let mut rng = thread_rng();
+        trace_mark("first");
+        let _: u64 = rng.gen();
+        let _: u32 = rng.gen();
+        trace_mark("second");
+        let _: f64 = rng.gen();
+        let trace = stop_trace().unwrap();
+        assert_eq!(trace.len(), 3);
+        assert!(stop_trace().is_none());
+
+        #[cfg(feature = "std")]
+        {
+            let mut buf = Vec::new();
+            trace.write_to(&mut buf).unwrap();
+            let read = Trace::read_from(&buf[..]).unwrap();
+            assert_eq!(read, trace);
+        }
+        assert_eq!(trace.divergence(&trace.clone()), None);
+
+        let mut other = trace.clone();
+        other.draws[2] ^= 1;
+        let div = trace.divergence(&other).unwrap();
+        assert_eq!(div.index, 2);
+        assert_eq!(div.last_mark.as_deref(), Some("second"));
+
+        other.draws.truncate(1);
+        let div = trace.divergence(&other).unwrap();
+        assert_eq!(div.index, 1);
+        assert_eq!(div.right, None);
+        assert_eq!(div.last_mark.as_deref(), Some("first"));
+    }
+
+    #[test]
+    fn independent_streams() {
+        let draws = |stream| {
+            reseed_stream(42, stream);
+            (0..4).map(|_| random()).collect::<Vec<u64>>()
+        };
+        let first = draws(0);
+        reseed(42);
+        assert_ne!((0..4).map(|_| random()).collect::<Vec<u64>>(), first);
+        assert_eq!(draws(3), draws(3));
+        assert_ne!(draws(1), first);
+        assert_ne!(draws(1), draws(2));
+        assert_eq!(seed(), 42);
+
+        // any stream is derived right away, and streams of other seeds are unrelated
+        assert_eq!(draws(u64::MAX), draws(u64::MAX));
+        assert_ne!(draws(u64::MAX), draws(u64::MAX - 1));
+        reseed_stream(1, 42);
+        let swapped: Vec<u64> = (0..4).map(|_| random()).collect();
+        assert_ne!(draws(1), swapped);
+    }
+
+    #[test]
+    fn state_roundtrip() {
+        reseed(7);
+        let mut rng = thread_rng();
+        let _: u64 = rng.gen();
+        let saved = rng.state();
+        let first: Vec<u64> = (0..4).map(|_| rng.gen()).collect();
+
+        reseed(8);
+        let mut other = thread_rng();
+        other.set_state(saved.clone());
+        assert_eq!(seed(), 7);
+        assert_eq!(saved.seed(), 7);
+        assert_eq!((0..4).map(|_| random()).collect::<Vec<u64>>(), first);
+        assert_ne!(rng.state(), saved);
     }
 }
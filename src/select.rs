@@ -6,13 +6,19 @@
 //!
 //! [`Tournament`]: ./struct.Tournament.html
 
+pub(crate) mod elitist;
 pub(crate) mod nsga;
+pub(crate) mod roulette;
 pub(crate) mod tournament;
 pub(crate) mod utils;
 
+pub use elitist::Elitist;
 pub use nsga::{rank_nondominated, ParetoFronts, NSGA2};
+pub use roulette::{NegativeFitness, Roulette};
 pub use tournament::Tournament;
 
+use alloc::vec::Vec;
+
 use crate::Cached;
 use crate::Solution;
 
@@ -20,6 +26,42 @@ use crate::Solution;
 pub trait Select<T: Solution> {
     /// Mutate `population` in place, leaving `amount` solutions in it.
     fn select(&self, amount: usize, population: &mut Vec<Cached<T>>);
+
+    /// Mutate `population` in place, leaving `amount` solutions in it,
+    /// using `spare` to avoid allocating and freeing solutions.
+    ///
+    /// The solutions that aren't selected are moved to the end of `spare` instead of being dropped,
+    /// and when a solution is selected more than once, the extra copies are made by
+    /// overwriting solutions taken from `spare` with [`Clone::clone_from`].
+    /// This way, solutions that own large heap allocations can keep reusing them
+    /// from one generation to the next, e.g. as the scratch space passed to
+    /// [`Algorithm::step_with_scratch()`].
+    ///
+    /// The default implementation ignores `spare` and calls [`.select()`].
+    ///
+    /// [`Algorithm::step_with_scratch()`]: ../alg/trait.Algorithm.html#method.step_with_scratch
+    /// [`.select()`]: #tymethod.select
+    fn select_reusing(
+        &self,
+        amount: usize,
+        population: &mut Vec<Cached<T>>,
+        spare: &mut Vec<Cached<T>>,
+    ) {
+        let _ = spare;
+        self.select(amount, population);
+    }
+
+    /// The number of solutions at the start of the population after selection
+    /// that the selector guarantees to keep, which algorithms that vary the selected
+    /// population in place, like [`Simple`], leave unchanged.
+    ///
+    /// The default implementation returns 0. See [`Elitist`].
+    ///
+    /// [`Simple`]: ../alg/struct.Simple.html
+    /// [`Elitist`]: ./struct.Elitist.html
+    fn elites(&self) -> usize {
+        0
+    }
 }
 
 /// Marker trait that indicates a selector uses randomness in its selection.
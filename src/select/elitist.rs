@@ -0,0 +1,127 @@
+use alloc::vec::Vec;
+
+use crate::select::Select;
+use crate::{fitness::Direction, Cached, Solution};
+
+use super::Stochastic;
+
+/// Elitism for any selector
+///
+/// This selector copies the `n` best solutions, in the direction of the first objective
+/// given by [`Solution::DIRECTIONS`], and fills the rest of the selection with the
+/// selector it wraps, which still chooses from the whole population.
+/// The copies of the elites are put at the start of the selection, and [`.elites()`] returns `n`,
+/// so [`Simple`] leaves them out of variation and they survive unchanged.
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+///
+/// let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> = Evolution::new(
+///     alg::Simple::new(50, 0.5, 0.2, select::Elitist::new(2, select::Tournament::new(3))),
+///     hof::BestN::new(1),
+/// );
+/// evo.run_for(20);
+/// ```
+///
+/// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+/// [`.elites()`]: ./trait.Select.html#method.elites
+/// [`Simple`]: ../alg/struct.Simple.html
+#[derive(Clone, Copy, Debug)]
+pub struct Elitist<S> {
+    n: usize,
+    selector: S,
+}
+
+impl<S: Stochastic> Stochastic for Elitist<S> {}
+
+impl<S> Elitist<S> {
+    /// Keep the `n` best solutions, and select the rest with `selector`.
+    pub fn new(n: usize, selector: S) -> Self {
+        Elitist { n, selector }
+    }
+
+    /// Get the selector that chooses the solutions other than the elites.
+    pub fn selector(&self) -> &S {
+        &self.selector
+    }
+}
+
+impl<T, S> Select<T> for Elitist<S>
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+    S: Select<T>,
+{
+    fn select(&self, amount: usize, pop: &mut Vec<Cached<T>>) {
+        self.select_reusing(amount, pop, &mut Vec::new());
+    }
+
+    fn select_reusing(&self, amount: usize, pop: &mut Vec<Cached<T>>, spare: &mut Vec<Cached<T>>) {
+        let n = self.n.min(amount).min(pop.len());
+        let direction = Direction::of::<T>(0);
+        let mut order: Vec<usize> = (0..pop.len()).collect();
+        order.sort_by(|&a, &b| {
+            direction
+                .compare(pop[a].evaluate().into(), pop[b].evaluate().into())
+                .reverse()
+        });
+
+        let mut selected: Vec<Cached<T>> = Vec::with_capacity(amount);
+        for &i in &order[..n] {
+            selected.push(match spare.pop() {
+                Some(mut slot) => {
+                    slot.clone_from(&pop[i]);
+                    slot
+                }
+                None => pop[i].clone(),
+            });
+        }
+
+        self.selector.select_reusing(amount - n, pop, spare);
+        selected.append(pop);
+        *pop = selected;
+    }
+
+    fn elites(&self) -> usize {
+        self.n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alg::{Algorithm, Simple},
+        fitness::par_evaluate,
+        select::Tournament,
+        testutils::Walk,
+    };
+
+    #[test]
+    fn elites_survive_variation() {
+        let mut pop: Vec<Cached<Walk>> = (0..10).map(|i| Cached::new(Walk(i as f64))).collect();
+        Elitist::new(2, Tournament::new(2)).select(6, &mut pop);
+        assert_eq!(pop.len(), 6);
+        assert_eq!(pop[0].as_ref(), &Walk(0.0));
+        assert_eq!(pop[1].as_ref(), &Walk(1.0));
+
+        // with crossover and mutation every time, only the elites can keep the best fitness
+        let simple = Simple::new(10, 1.0, 1.0, Elitist::new(1, Tournament::new(2)));
+        let mut pop: Vec<Cached<Walk>> = (0..10).map(|_| Cached::generate()).collect();
+        par_evaluate(&pop);
+        let best = |pop: &[Cached<Walk>]| {
+            pop.iter()
+                .map(|ind| ind.evaluate())
+                .fold(f64::MIN, f64::max)
+        };
+        for _ in 0..20 {
+            let before = best(&pop);
+            simple.step(&mut pop);
+            par_evaluate(&pop);
+            assert!(best(&pop) >= before);
+            assert_eq!(pop.len(), 10);
+        }
+    }
+}
@@ -1,8 +1,12 @@
-use std::fmt::Debug;
+use alloc::{vec, vec::Vec};
+use core::{cmp::Ordering, fmt::Debug};
 
 use crate::{
-    fitness::MultiObjective,
-    select::{utils::retain_indices, Select},
+    fitness::{Direction, MultiObjective},
+    select::{
+        utils::{retain_indices, retain_indices_reusing},
+        Select,
+    },
     Cached, Solution,
 };
 
@@ -25,6 +29,11 @@ where
         let indices = self.select_indices(k, pop).0;
         retain_indices(pop, indices);
     }
+
+    fn select_reusing(&self, k: usize, pop: &mut Vec<Cached<T>>, spare: &mut Vec<Cached<T>>) {
+        let indices = self.select_indices(k, pop).0;
+        retain_indices_reusing(pop, indices, spare);
+    }
 }
 
 impl NSGA2 {
@@ -108,34 +117,211 @@ impl ParetoFronts {
 /// population has an associated rank.
 ///
 /// This function implements the Best Order Sort algorithm for nondominated ranking[^1].
+/// With the `parallel` feature, populations of at least 2048 members are ranked
+/// with a parallel version of the same algorithm, which evaluates the population and sorts
+/// it by each objective in parallel, and splits large dominance checks between threads.
+/// Both versions give exactly the same result.
+///
+/// With a single objective, the ranks are simply the order of the distinct fitness values,
+/// so the population is just sorted by fitness instead.
+///
+/// Each objective is maximized or minimized according to [`Solution::DIRECTIONS`].
+/// Solutions with equal values in an objective don't outperform each other in it, so a solution
+/// only dominates another if it's at least as good in every objective and better in one.
+/// Values that differ by at most [`Solution::DOMINANCE_TOLERANCE`] count as equal,
+/// in which case a simpler algorithm that allows for the tolerance is used instead of Best Order Sort.
 ///
 /// [^1]: Roy, Islam, & Deb.
 /// "Best Order Sort: A New Algorithm to Non-dominated Sorting for Evolutionary Multi-objective Optimization."
 /// 2016. <https://doi.org/10.1145/2908961.2931684>
+///
+/// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+/// [`Solution::DOMINANCE_TOLERANCE`]: ../trait.Solution.html#associatedconstant.DOMINANCE_TOLERANCE
 pub fn rank_nondominated<T, const M: usize>(pop: &[T]) -> ParetoFronts
 where
     T: Solution<Fitness = MultiObjective<M>>,
 {
+    if M == 1 {
+        return rank_single_objective(pop);
+    }
+    if T::DOMINANCE_TOLERANCE > 0.0 {
+        return rank_with_tolerance(pop);
+    }
+
+    #[cfg(feature = "parallel")]
+    if pop.len() >= PARALLEL_THRESHOLD {
+        return crate::utils::thread_pool::install(|| par_rank_nondominated(pop));
+    }
+
     // Algorithm 1: Initialization
     let popsize = pop.len();
-    let mut l = vec![vec![Vec::<usize>::new(); M]; popsize];
-    let mut c = vec![(0..M).collect::<Vec<_>>(); popsize];
-    let mut is_ranked = vec![false; popsize];
-    let mut solutions_completed: usize = 0;
-    let mut rank_count: usize = 1;
-    let mut pareto = ParetoFronts::new(popsize);
-
     let mut q: Vec<Vec<usize>> = Vec::with_capacity(M);
     for j in 0..M {
         q.push({
             let mut q_j = (0..popsize).collect::<Vec<_>>();
             q_j.sort_unstable_by(|&a, &b| {
-                f64::total_cmp(&pop[b].evaluate()[j], &pop[a].evaluate()[j])
+                best_first::<T, M>(j, &pop[a].evaluate(), &pop[b].evaluate())
             });
             q_j
         });
     }
 
+    best_order_sort::<M>(q, |s, front| {
+        front
+            .iter()
+            .any(|&t| cmp_dom(&pop[s], &pop[t]) == DomOrdering::BOverA)
+    })
+}
+
+// Order two solutions by objective `j`, best first, breaking ties with the other objectives in order.
+// Best Order Sort relies on every solution coming after all of the solutions that dominate it.
+fn best_first<T, const M: usize>(j: usize, a: &[f64; M], b: &[f64; M]) -> Ordering
+where
+    T: Solution,
+{
+    core::iter::once(j)
+        .chain((0..M).filter(|&k| k != j))
+        .find(|&k| a[k] != b[k])
+        .map_or(Ordering::Equal, |k| {
+            Direction::of::<T>(k).compare(b[k], a[k])
+        })
+}
+
+// With one objective, a solution's rank is the number of distinct fitness values better than its own,
+// which is what Best Order Sort would find, without any of the dominance checks.
+// With a tolerance, each front is everything within the tolerance of the best solution that's left.
+fn rank_single_objective<T, const M: usize>(pop: &[T]) -> ParetoFronts
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+{
+    let direction = Direction::of::<T>(0);
+    let fits: Vec<f64> = pop.iter().map(|ind| ind.evaluate()[0]).collect();
+    let mut order: Vec<usize> = (0..pop.len()).collect();
+    order.sort_unstable_by(|&a, &b| direction.compare(fits[b], fits[a]));
+
+    let mut pareto = ParetoFronts::new(pop.len());
+    let mut rank: usize = 0;
+    let mut leader = order.first().copied().unwrap_or_default();
+    for &s in &order {
+        if advantage(direction, fits[leader], fits[s]) > T::DOMINANCE_TOLERANCE {
+            rank += 1;
+            leader = s;
+        }
+        pareto.add_ranking(s, rank);
+    }
+    pareto
+}
+
+// Deb's fast nondominated sort, for when objectives are compared with a tolerance,
+// which breaks Best Order Sort's assumption that a solution comes after everything that dominates it
+// in each objective's order.
+fn rank_with_tolerance<T, const M: usize>(pop: &[T]) -> ParetoFronts
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+{
+    let popsize = pop.len();
+    // the solutions each solution dominates, and how many solutions dominate it
+    let mut dominates: Vec<Vec<usize>> = vec![Vec::new(); popsize];
+    let mut dominated_by: Vec<usize> = vec![0; popsize];
+    for a in 0..popsize {
+        for b in a + 1..popsize {
+            match cmp_dom(&pop[a], &pop[b]) {
+                DomOrdering::AOverB => {
+                    dominates[a].push(b);
+                    dominated_by[b] += 1;
+                }
+                DomOrdering::BOverA => {
+                    dominates[b].push(a);
+                    dominated_by[a] += 1;
+                }
+                DomOrdering::Neither => {}
+            }
+        }
+    }
+
+    let mut pareto = ParetoFronts::new(popsize);
+    let mut rank: usize = 0;
+    let mut front: Vec<usize> = (0..popsize).filter(|&s| dominated_by[s] == 0).collect();
+    while !front.is_empty() {
+        let mut next = Vec::new();
+        for &s in &front {
+            pareto.add_ranking(s, rank);
+            for &t in &dominates[s] {
+                dominated_by[t] -= 1;
+                if dominated_by[t] == 0 {
+                    next.push(t);
+                }
+            }
+        }
+        front = next;
+        rank += 1;
+    }
+
+    // Dominance within the tolerance can go in circles, so whatever is left shares the last front
+    for (s, &count) in dominated_by.iter().enumerate() {
+        if count > 0 {
+            pareto.add_ranking(s, rank);
+        }
+    }
+    pareto
+}
+
+// Populations at least this large are ranked in parallel
+#[cfg(feature = "parallel")]
+const PARALLEL_THRESHOLD: usize = 2048;
+
+// Dominance checks against at least this many solutions are split between threads
+#[cfg(feature = "parallel")]
+const PARALLEL_CHECK_THRESHOLD: usize = 512;
+
+#[cfg(feature = "parallel")]
+fn par_rank_nondominated<T, const M: usize>(pop: &[T]) -> ParetoFronts
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+{
+    use rayon::prelude::*;
+
+    let fits: Vec<[f64; M]> = pop
+        .par_iter()
+        .map(crate::utils::counter::carry(|ind: &T| *ind.evaluate()))
+        .collect();
+    let q: Vec<Vec<usize>> = (0..M)
+        .into_par_iter()
+        .map(|j| {
+            let mut q_j = (0..pop.len()).collect::<Vec<_>>();
+            q_j.par_sort_unstable_by(|&a, &b| best_first::<T, M>(j, &fits[a], &fits[b]));
+            q_j
+        })
+        .collect();
+
+    best_order_sort::<M>(q, |s, front| {
+        let dominates = |&t: &usize| {
+            cmp_dom_f64_slices(&fits[s], &fits[t], T::DIRECTIONS, 0.0) == DomOrdering::BOverA
+        };
+        if front.len() >= PARALLEL_CHECK_THRESHOLD {
+            front.par_iter().any(dominates)
+        } else {
+            front.iter().any(dominates)
+        }
+    })
+}
+
+// The main loop of Best Order Sort, given each objective's sorted order `q`,
+// and a check for whether any of the solutions in `front` dominate solution `s`
+// Indexing loops are kept to mirror the notation used in the paper.
+#[allow(clippy::needless_range_loop)]
+fn best_order_sort<const M: usize>(
+    q: Vec<Vec<usize>>,
+    is_dominated: impl Fn(usize, &[usize]) -> bool,
+) -> ParetoFronts {
+    let popsize = q.first().map_or(0, Vec::len);
+    let mut l = vec![vec![Vec::<usize>::new(); M]; popsize];
+    let mut c = vec![(0..M).collect::<Vec<_>>(); popsize];
+    let mut is_ranked = vec![false; popsize];
+    let mut solutions_completed: usize = 0;
+    let mut rank_count: usize = 1;
+    let mut pareto = ParetoFronts::new(popsize);
+
     // Algorithm 2: Main Loop
     for i in 0..popsize {
         for j in 0..M {
@@ -147,14 +333,7 @@ where
                 // Algorithm 3: FindRank
                 let mut done = false;
                 for k in 0..rank_count {
-                    let mut check = false;
-                    for &t in l[k][j].iter() {
-                        check = cmp_dom(&pop[s], &pop[t]) == DomOrdering::BOverA;
-                        if check {
-                            break;
-                        }
-                    }
-                    if !check {
+                    if !is_dominated(s, &l[k][j]) {
                         pareto.add_ranking(s, k);
                         done = true;
                         l[k][j].push(s);
@@ -179,69 +358,96 @@ where
     pareto
 }
 
+/// Sort the members of a front, given as indices into `pop`, by descending crowding distance
+///
+/// The crowding distance of a solution measures how far it is from its neighbours in the front.
+/// The solutions at either end of each objective get an infinite distance.
+/// Since it only depends on the distances between neighbours, it's the same
+/// whether each objective is maximized or minimized.
 pub fn sort_by_crowding_distance<T, const M: usize>(front: &mut [usize], pop: &[Cached<T>])
 where
     T: Solution<Fitness = MultiObjective<M>>,
 {
-    let fit = |idx: usize, m: usize| Cached::fit(&pop[idx], m);
-
     let frontsize = front.len();
+    if frontsize == 0 {
+        return;
+    }
+
+    // Each objective's values for the whole front, stored one objective after another
+    let mut fits: Vec<f64> = vec![0.0; frontsize * M];
+    for (i, &idx) in front.iter().enumerate() {
+        for m in 0..M {
+            fits[m * frontsize + i] = Cached::fit(&pop[idx], m);
+        }
+    }
+
     let mut distances: Vec<f64> = vec![0.0; frontsize];
-    let mut front_enumerated = {
-        let mut v: Vec<(usize, usize)> = Vec::with_capacity(frontsize);
-        for i in 0..frontsize {
-            v.push((i, front[i]));
+    // Positions in the front, sorted by the current objective.
+    // It starts out sorted by the previous objective, which often needs little or no work.
+    let mut order: Vec<usize> = (0..frontsize).collect();
+    for col in fits.chunks_exact(frontsize) {
+        let cmp = |a: &usize, b: &usize| f64::total_cmp(&col[*a], &col[*b]);
+        if order.windows(2).all(|w| cmp(&w[0], &w[1]).is_le()) {
+            // already sorted
+        } else if order.windows(2).all(|w| cmp(&w[0], &w[1]).is_ge()) {
+            // common for two objectives, since the front is sorted in the opposite order
+            order.reverse();
+        } else {
+            order.sort_unstable_by(cmp);
         }
-        v
-    };
-    for m in 0..M {
-        front_enumerated
-            .sort_unstable_by(|(_, a), (_, b)| f64::total_cmp(&fit(*a, m), &fit(*b, m)));
-        let min_fit = fit(front_enumerated[0].1, m);
-        let max_fit = fit(front_enumerated[frontsize - 1].1, m);
-        let fit_range = max_fit - min_fit;
-        distances[front_enumerated[0].0] = f64::INFINITY;
-        distances[front_enumerated.last().unwrap().0] = f64::INFINITY;
-        for i in 1..frontsize - 1 {
-            let (j, _) = front_enumerated[i];
-            let prev_fit = fit(front_enumerated[i - 1].1, m);
-            let next_fit = fit(front_enumerated[i + 1].1, m);
-            distances[j] += (next_fit - prev_fit) / fit_range;
+
+        let fit_range = col[order[frontsize - 1]] - col[order[0]];
+        distances[order[0]] = f64::INFINITY;
+        distances[order[frontsize - 1]] = f64::INFINITY;
+        for w in order.windows(3) {
+            distances[w[1]] += (col[w[2]] - col[w[0]]) / fit_range;
         }
     }
 
-    front_enumerated
-        .sort_unstable_by(|(i, _), (j, _)| f64::total_cmp(&distances[*j], &distances[*i]));
+    order.sort_unstable_by(|&a, &b| f64::total_cmp(&distances[b], &distances[a]));
 
-    for i in 0..frontsize {
-        front[i] = front_enumerated[i].1;
+    let members: Vec<usize> = front.to_vec();
+    for (slot, pos) in front.iter_mut().zip(order) {
+        *slot = members[pos];
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum DomOrdering {
+pub(crate) enum DomOrdering {
     AOverB,
     BOverA,
     Neither,
 }
 
-fn cmp_dom<T, const M: usize>(a: &T, b: &T) -> DomOrdering
+pub(crate) fn cmp_dom<T, const M: usize>(a: &T, b: &T) -> DomOrdering
 where
     T: Solution<Fitness = MultiObjective<M>>,
 {
-    cmp_dom_f64_slices(&a.evaluate(), &b.evaluate())
+    cmp_dom_f64_slices(
+        &a.evaluate(),
+        &b.evaluate(),
+        T::DIRECTIONS,
+        T::DOMINANCE_TOLERANCE,
+    )
 }
 
-fn cmp_dom_f64_slices<const M: usize>(a: &[f64; M], b: &[f64; M]) -> DomOrdering {
+// `directions` is a solution's `DIRECTIONS`, where missing entries are maximized.
+// Values that differ by at most `tolerance` are equal, so neither solution wins that objective.
+fn cmp_dom_f64_slices<const M: usize>(
+    a: &[f64; M],
+    b: &[f64; M],
+    directions: &[Direction],
+    tolerance: f64,
+) -> DomOrdering {
     let mut a_win = false;
     let mut b_win = false;
     for i in 0..M {
-        if b[i] > a[i] {
-            b_win = true;
-        // no need for another condition here because
-        // floats are absurdly unlikely to compare equal
-        } else {
+        let direction = directions.get(i).copied().unwrap_or_default();
+        let advantage = advantage(direction, a[i], b[i]);
+        if advantage > tolerance {
             a_win = true;
+        } else if advantage < -tolerance {
+            b_win = true;
         }
     }
     if a_win && !b_win {
@@ -253,6 +459,14 @@ fn cmp_dom_f64_slices<const M: usize>(a: &[f64; M], b: &[f64; M]) -> DomOrdering
     }
 }
 
+// How much better `a` is than `b`, which is negative if it's worse
+fn advantage(direction: Direction, a: f64, b: f64) -> f64 {
+    match direction {
+        Direction::Maximize => a - b,
+        Direction::Minimize => b - a,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
@@ -280,6 +494,50 @@ mod tests {
         assert_eq!(counts, vec![3, 3]);
     }
 
+    #[test]
+    fn test_rank_single_objective() {
+        let pop = [0.5, 0.2, 0.5, 0.9, 0.2].map(One);
+
+        let pareto = rank_nondominated(&pop);
+        assert_eq!(pareto.ranks, vec![1, 2, 1, 0, 2]);
+        assert_eq!(pareto.counts, vec![1, 2, 2]);
+    }
+
+    #[test]
+    fn rank_nondominated_minimize() {
+        let pop = [
+            [0.6, 0.6],
+            [0.0, 1.0],
+            [0.75, 0.25],
+            [0.25, 0.75],
+            [1.0, 0.0],
+            [0.9, 0.9],
+        ]
+        .map(|fit| Minimized(Foo(fit)));
+
+        let pareto = rank_nondominated(&pop);
+        assert_eq!(pareto.ranks, vec![0, 0, 0, 0, 0, 1]);
+        assert_eq!(pareto.counts, vec![5, 1]);
+
+        let pop = [0.5, 0.2, 0.5, 0.9, 0.2].map(|x| Minimized(One(x)));
+
+        let pareto = rank_nondominated(&pop);
+        assert_eq!(pareto.ranks, vec![1, 0, 1, 2, 0]);
+        assert_eq!(pareto.counts, vec![2, 2, 1]);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn rank_single_objective_matches() {
+        // with plenty of ties between solutions
+        let pop: Vec<_> = (0..500).map(|i| One((i * 37 % 101) as f64)).collect();
+
+        let fast = rank_nondominated(&pop);
+        let bos = par_rank_nondominated(&pop);
+        assert_eq!(fast.ranks, bos.ranks);
+        assert_eq!(fast.counts, bos.counts);
+    }
+
     #[test]
     fn test_cmp_dom() {
         use DomOrdering::*;
@@ -288,9 +546,115 @@ mod tests {
         let arr2 = [-2.0f64, 3.0, 4.9];
         let arr3 = [-1.9f64, 2.0, 3.1];
 
-        assert_eq!(cmp_dom_f64_slices(&arr1, &arr2), AOverB);
-        assert_eq!(cmp_dom_f64_slices(&arr3, &arr1), BOverA);
-        assert_eq!(cmp_dom_f64_slices(&arr2, &arr3), Neither);
+        assert_eq!(cmp_dom_f64_slices(&arr1, &arr2, &[], 0.0), AOverB);
+        assert_eq!(cmp_dom_f64_slices(&arr3, &arr1, &[], 0.0), BOverA);
+        assert_eq!(cmp_dom_f64_slices(&arr2, &arr3, &[], 0.0), Neither);
+
+        // ties in some objectives
+        assert_eq!(
+            cmp_dom_f64_slices(&[1.0, 2.0], &[1.0, 3.0], &[], 0.0),
+            BOverA
+        );
+        assert_eq!(
+            cmp_dom_f64_slices(&[1.0, 2.0], &[1.0, 2.0], &[], 0.0),
+            Neither
+        );
+
+        // within the tolerance
+        assert_eq!(
+            cmp_dom_f64_slices(&[1.0, 2.0], &[1.05, 1.0], &[], 0.0),
+            Neither
+        );
+        assert_eq!(
+            cmp_dom_f64_slices(&[1.0, 2.0], &[1.05, 1.0], &[], 0.1),
+            AOverB
+        );
+        assert_eq!(
+            cmp_dom_f64_slices(&[1.0, 2.0], &[1.05, 2.05], &[], 0.1),
+            Neither
+        );
+    }
+
+    #[test]
+    fn rank_tied_objectives() {
+        let pop = [[1.0, 1.0], [1.0, 2.0], [2.0, 1.0], [1.0, 2.0], [0.0, 0.0]].map(Foo);
+
+        let pareto = rank_nondominated(&pop);
+        assert_eq!(pareto.ranks, vec![1, 0, 0, 0, 2]);
+        assert_eq!(pareto.counts, vec![3, 1, 1]);
+
+        // integer-valued objectives with plenty of ties, checked against the simpler algorithm
+        let pop: Vec<_> = (0..300)
+            .map(|i| Foo([(i * 7 % 5) as f64, (i * 3 % 4) as f64]))
+            .collect();
+        let bos = rank_nondominated(&pop);
+        let simple = rank_with_tolerance(&pop);
+        assert_eq!(bos.ranks, simple.ranks);
+        assert_eq!(bos.counts, simple.counts);
+    }
+
+    #[test]
+    fn rank_with_dominance_tolerance() {
+        #[derive(Clone)]
+        struct Rounded([f64; 2]);
+
+        impl Solution for Rounded {
+            type Fitness = MultiObjective<2>;
+            const DOMINANCE_TOLERANCE: f64 = 0.1;
+
+            fn generate() -> Self {
+                unreachable!()
+            }
+            fn evaluate(&self) -> Self::Fitness {
+                MultiObjective::new_unweighted(self.0)
+            }
+            fn crossover(_: &mut Self, _: &mut Self) {
+                unreachable!()
+            }
+            fn mutate(&mut self) {
+                unreachable!()
+            }
+        }
+
+        let pop = [[1.0, 1.0], [1.05, 0.95], [0.5, 0.98], [0.0, 0.0]].map(Rounded);
+        let pareto = rank_nondominated(&pop);
+        assert_eq!(pareto.ranks, vec![0, 0, 1, 2]);
+        assert_eq!(pareto.counts, vec![2, 1, 1]);
+    }
+
+    #[test]
+    fn test_crowding_distance() {
+        let pop: Vec<_> = [0.8, 0.0, 0.2, 1.0, 0.1]
+            .into_iter()
+            .map(|x| Cached::new(Foo([x, 1.0 - x])))
+            .collect();
+        for ind in &pop {
+            ind.evaluate();
+        }
+
+        let mut front = vec![0, 1, 2, 3, 4];
+        sort_by_crowding_distance(&mut front, &pop);
+        // both ends first, in either order, then 0.8, 0.2, and 0.1
+        assert_eq!(front[..2].iter().sorted().collect_vec(), [&1, &3]);
+        assert_eq!(front[2..], [0, 2, 4]);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn par_rank_nondominated_matches() {
+        // just below the threshold, so `rank_nondominated` takes the sequential path
+        let pop = Vec::n_from_function(PARALLEL_THRESHOLD - 1, Bar::generate);
+
+        let seq = rank_nondominated(&pop);
+        let par = par_rank_nondominated(&pop);
+        assert_eq!(par.ranks, seq.ranks);
+        assert_eq!(par.counts, seq.counts);
+
+        let pop: Vec<_> = pop.into_iter().map(Minimized).collect();
+        let seq = rank_nondominated(&pop);
+        let par = par_rank_nondominated(&pop);
+        assert_eq!(par.ranks, seq.ranks);
+        assert_eq!(par.counts, seq.counts);
     }
 
     // The following tests will always pass. They are intended for use with
@@ -302,7 +666,7 @@ mod tests {
         let pop = Vec::n_from_function(100, One::generate);
 
         let rankings = rank_nondominated(&pop).ranks;
-        let mut ranked: Vec<_> = rankings.into_iter().zip(pop.into_iter()).collect();
+        let mut ranked: Vec<_> = rankings.into_iter().zip(pop).collect();
         ranked.sort_unstable_by_key(|(rank, _)| *rank);
         for (rank, members) in &ranked.into_iter().group_by(|(rank, _)| *rank) {
             print!("rank {}: ", rank);
@@ -318,7 +682,7 @@ mod tests {
         let pop = Vec::n_from_function(1000, Bar::generate);
 
         let rankings = rank_nondominated(&pop).ranks;
-        let mut ranked: Vec<_> = rankings.into_iter().zip(pop.into_iter()).collect();
+        let mut ranked: Vec<_> = rankings.into_iter().zip(pop).collect();
         ranked.sort_unstable_by_key(|(rank, _)| *rank);
         for (rank, members) in &ranked.into_iter().group_by(|(rank, _)| *rank) {
             print!("rank {}: ", rank);
@@ -0,0 +1,157 @@
+use alloc::vec::Vec;
+
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+
+use crate::repro_rng::thread_rng;
+use crate::select::{utils::*, Select};
+use crate::{fitness::Direction, Cached, Solution};
+
+use super::Stochastic;
+
+/// Fitness-proportionate ("roulette wheel") selection
+///
+/// Each solution is selected with a chance proportional to its weight, with replacement,
+/// so fit solutions can be selected several times. A solution's weight is its fitness,
+/// negated if the first objective is minimized according to [`Solution::DIRECTIONS`],
+/// and adjusted as set by [`NegativeFitness`] so that no weight is negative.
+/// If every weight is zero, the selection is uniform.
+///
+/// Since weights have to be proportional to how good a solution is, this works best when fitness
+/// is a positive quantity being maximized. Otherwise, [`Tournament`] is usually a better choice,
+/// since it only depends on the order of the fitness values.
+///
+/// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+/// [`NegativeFitness`]: ./enum.NegativeFitness.html
+/// [`Tournament`]: ./struct.Tournament.html
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Roulette {
+    negative: NegativeFitness,
+}
+
+/// How [`Roulette`] turns fitness values into weights that can't be negative
+///
+/// [`Roulette`]: ./struct.Roulette.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NegativeFitness {
+    /// If any fitness value in the population is negative, subtract the lowest one from all of them,
+    /// so the worst solution has a weight of zero. Otherwise, use the fitness values as they are.
+    #[default]
+    Shift,
+    /// Always subtract the lowest fitness value in the population from all of them,
+    /// so the worst solution has a weight of zero. This keeps up the selection pressure
+    /// when the fitness values are large compared to the differences between them.
+    Window,
+}
+
+impl Stochastic for Roulette {}
+
+impl Roulette {
+    /// Create a new `Roulette` that handles negative fitness with [`NegativeFitness::Shift`].
+    ///
+    /// [`NegativeFitness::Shift`]: ./enum.NegativeFitness.html#variant.Shift
+    pub fn new() -> Self {
+        Roulette::default()
+    }
+
+    /// Turn fitness values into weights as set by `negative`.
+    pub fn with_negative(mut self, negative: NegativeFitness) -> Self {
+        self.negative = negative;
+        self
+    }
+
+    // The weight of each solution in `pop`, in order
+    fn weights<T>(&self, pop: &[Cached<T>]) -> Vec<f64>
+    where
+        T: Solution,
+        T::Fitness: Into<f64>,
+    {
+        let sign = match Direction::of::<T>(0) {
+            Direction::Maximize => 1.0,
+            Direction::Minimize => -1.0,
+        };
+        let mut weights: Vec<f64> = pop.iter().map(|ind| sign * ind.evaluate().into()).collect();
+        let lowest = weights.iter().copied().fold(f64::INFINITY, f64::min);
+        if self.negative == NegativeFitness::Window || lowest < 0.0 {
+            for weight in &mut weights {
+                *weight -= lowest;
+            }
+        }
+        weights
+    }
+}
+
+impl<T> Select<T> for Roulette
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+{
+    fn select(&self, amount: usize, pop: &mut Vec<Cached<T>>) {
+        self.select_reusing(amount, pop, &mut Vec::new());
+    }
+
+    fn select_reusing(&self, amount: usize, pop: &mut Vec<Cached<T>>, spare: &mut Vec<Cached<T>>) {
+        let mut rng = thread_rng();
+        let chosen: Vec<usize> = match WeightedIndex::new(self.weights(pop)) {
+            Ok(wheel) => (0..amount).map(|_| wheel.sample(&mut rng)).collect(),
+            // every weight is zero
+            Err(_) => (0..amount).map(|_| rng.gen_range(0..pop.len())).collect(),
+        };
+        retain_indices_reusing(pop, chosen, spare);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        repro_rng::reseed,
+        testutils::{Minimized, Walk},
+    };
+
+    fn counts<T>(roulette: Roulette, pop: &[Cached<T>]) -> Vec<usize>
+    where
+        T: Solution + PartialEq,
+        T::Fitness: Into<f64>,
+    {
+        reseed(3);
+        let mut selected = pop.to_vec();
+        roulette.select(4000, &mut selected);
+        pop.iter()
+            .map(|ind| {
+                selected
+                    .iter()
+                    .filter(|sel| sel.as_ref() == ind.as_ref())
+                    .count()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn proportionate_to_weights() {
+        // fitness -|x|: -3, -1, 0, so shifting gives weights 0, 2, 3
+        let pop: Vec<Cached<Walk>> = [3.0, 1.0, 0.0]
+            .iter()
+            .map(|&x| Cached::new(Walk(x)))
+            .collect();
+        let shifted = counts(Roulette::new(), &pop);
+        assert_eq!(shifted[0], 0);
+        assert!((shifted[1] as f64 / 4000.0 - 0.4).abs() < 0.03);
+
+        // minimizing the same fitness values negates them to weights 3, 1, 0, which aren't shifted
+        let minimized: Vec<Cached<Minimized<Walk>>> = [3.0, 1.0, 0.0]
+            .iter()
+            .map(|&x| Cached::new(Minimized(Walk(x))))
+            .collect();
+        let negated = counts(Roulette::new(), &minimized);
+        assert_eq!(negated[2], 0);
+        assert!((negated[0] as f64 / 4000.0 - 0.75).abs() < 0.03);
+
+        // equal fitness values are all windowed to zero, so selection is uniform
+        let equal: Vec<Cached<Walk>> = [2.0, -2.0].iter().map(|&x| Cached::new(Walk(x))).collect();
+        let uniform = counts(
+            Roulette::new().with_negative(NegativeFitness::Window),
+            &equal,
+        );
+        assert!(uniform.iter().all(|&count| count > 1800));
+    }
+}
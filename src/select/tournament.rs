@@ -1,10 +1,12 @@
-use std::cmp::Ordering;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 
 use rand::seq::index::sample;
 
+use crate::alg::{check_nonzero, ConfigError};
 use crate::repro_rng::thread_rng;
 use crate::select::{utils::*, Select};
-use crate::{Cached, Solution};
+use crate::{fitness::Direction, Cached, Solution};
 
 use super::Stochastic;
 
@@ -12,8 +14,11 @@ use super::Stochastic;
 ///
 /// This type's `.select()` method runs a series of tournaments.
 /// For each tournament, it randomly chooses `round_size` solutions from the population
-/// and chooses the one with the highest fitness.
+/// and chooses the one with the best fitness, in the direction of the first objective
+/// given by [`Solution::DIRECTIONS`].
 /// The new population is then composed of the winners.
+///
+/// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
 #[derive(Clone, Copy)]
 pub struct Tournament {
     round_size: usize,
@@ -34,6 +39,16 @@ impl Tournament {
         Tournament { round_size }
     }
 
+    /// Create a new `Tournament` with the provided round size,
+    /// returning an error instead of panicking if it's 0.
+    ///
+    /// The round size can't be checked against the size of the population here,
+    /// so selecting from a population smaller than the round size still panics.
+    pub fn try_new(round_size: usize) -> Result<Self, ConfigError> {
+        check_nonzero("round_size", round_size)?;
+        Ok(Tournament { round_size })
+    }
+
     /// Get this `Tournament`'s round size.
     pub fn round_size(&self) -> usize {
         self.round_size
@@ -51,26 +66,36 @@ impl Tournament {
     }
 }
 
-impl<T, F> Select<T> for Tournament
+impl<T> Select<T> for Tournament
 where
-    T: Solution<Fitness = F>,
-    F: Into<f64>,
+    T: Solution,
+    T::Fitness: Into<f64>,
 {
     fn select(&self, n_rounds: usize, pop: &mut Vec<Cached<T>>) {
+        self.select_reusing(n_rounds, pop, &mut Vec::new());
+    }
+
+    fn select_reusing(
+        &self,
+        n_rounds: usize,
+        pop: &mut Vec<Cached<T>>,
+        spare: &mut Vec<Cached<T>>,
+    ) {
         let mut winners: Vec<usize> = Vec::with_capacity(n_rounds);
 
         // Run `n_rounds` rounds. Each round does the following:
         // - randomly sample `round_size` distinct individuals from the population
-        // - choose the individual with the highest fitness as the winner
+        // - choose the individual with the best fitness as the winner
         // - append the winner's index to `winners`
+        let direction = Direction::of::<T>(0);
         for _ in 0..n_rounds {
             winners.push(self.round_idx(pop, |a, b| {
-                f64::partial_cmp(&a.evaluate().into(), &b.evaluate().into()).unwrap()
+                direction.compare(a.evaluate().into(), b.evaluate().into())
             }));
         }
 
-        // Delete every individual that didn't win a tournament
-        retain_indices(pop, winners);
+        // Remove every individual that didn't win a tournament
+        retain_indices_reusing(pop, winners, spare);
     }
 }
 
@@ -81,7 +106,9 @@ impl Tournament {
         cmp: impl Fn(&Cached<T>, &Cached<T>) -> Ordering,
     ) -> usize {
         let mut rng = thread_rng();
-        let mut participants = sample(&mut rng, pop.len(), self.round_size).into_iter();
+        // a population smaller than a round, like a small species, holds a single round with everyone
+        let round_size = self.round_size.min(pop.len());
+        let mut participants = sample(&mut rng, pop.len(), round_size).into_iter();
         let mut curr_max = participants.next().unwrap();
         for idx in participants {
             if cmp(&pop[idx], &pop[curr_max]).is_gt() {
@@ -1,26 +1,51 @@
+use alloc::vec::Vec;
+
 // Mutate `vec` in place, keeping only the elements at the positions
 // specified by `indices`. Clones elements only for duplicate indices.
-pub fn retain_indices<T>(vec: &mut Vec<T>, mut indices: Vec<usize>)
+pub fn retain_indices<T>(vec: &mut Vec<T>, indices: Vec<usize>)
 where
     T: Clone,
 {
-    let n_indices = indices.len();
-    indices.sort();
-
-    vec.swap(indices[0], 0);
-    let mut swap_to: usize = 1;
-    let mut i: usize = 1;
-    while i < indices.len() {
-        if indices[i] == indices[i - 1] {
-            indices.push(vec.len());
-            vec.push(vec[swap_to - 1].clone());
+    retain_indices_reusing(vec, indices, &mut Vec::new());
+}
+
+// Like `retain_indices`, but the elements that aren't kept are moved to the end of `spare`
+// instead of being dropped, and copies for duplicate indices are made by
+// overwriting elements taken from the end of `spare` with `clone_from` while there are any.
+//
+// The copies can't be put off until a duplicate is actually varied, since the population is handed
+// to the hall of fame, the statistics, and the callbacks as a plain slice, where every selected slot
+// has to be a solution of its own. Reusing `spare` keeps them from allocating instead.
+pub fn retain_indices_reusing<T>(vec: &mut Vec<T>, mut indices: Vec<usize>, spare: &mut Vec<T>)
+where
+    T: Clone,
+{
+    indices.sort_unstable();
+
+    // Swap each distinct index to the front, in order,
+    // noting where each duplicate's original ends up
+    let mut duplicates: Vec<usize> = Vec::new();
+    let mut swap_to: usize = 0;
+    for i in 0..indices.len() {
+        if i > 0 && indices[i] == indices[i - 1] {
+            duplicates.push(swap_to - 1);
         } else {
             vec.swap(indices[i], swap_to);
             swap_to += 1;
         }
-        i += 1;
     }
-    vec.truncate(n_indices);
+    spare.extend(vec.drain(swap_to..));
+
+    for original in duplicates {
+        let copy = match spare.pop() {
+            Some(mut slot) => {
+                slot.clone_from(&vec[original]);
+                slot
+            }
+            None => vec[original].clone(),
+        };
+        vec.push(copy);
+    }
 }
 
 #[cfg(test)]
@@ -43,4 +68,20 @@ mod tests {
         retain_indices(&mut myvec3, vec![0; 10]);
         assert_eq!(myvec3, vec!['a'; 10]);
     }
+
+    #[test]
+    fn test_retain_indices_reusing() {
+        let mut myvec = vec![vec![0], vec![1], vec![2], vec![3], vec![4]];
+        let removed = [myvec[0].as_ptr(), myvec[2].as_ptr(), myvec[4].as_ptr()];
+        let mut spare = vec![vec![5]];
+        retain_indices_reusing(&mut myvec, vec![3, 1, 3, 3], &mut spare);
+
+        myvec.sort();
+        assert_eq!(myvec, vec![vec![1], vec![3], vec![3], vec![3]]);
+        // two of the removed elements were overwritten to make the duplicates
+        let reused = myvec.iter().filter(|v| removed.contains(&v.as_ptr()));
+        assert_eq!(reused.count(), 2);
+        assert_eq!(spare.len(), 2);
+        assert_eq!(spare[0], vec![5]);
+    }
 }
@@ -0,0 +1,197 @@
+//! Speciation and fitness sharing
+//!
+//! On problems with several optima, selection tends to crowd the whole population around
+//! whichever optimum it finds first. Speciation counteracts that by sorting the population into
+//! species of similar solutions and making solutions compete mostly within their own species.
+//! This is what NEAT does to protect new network topologies, and it works just as well
+//! for ordinary genomes on multimodal problems.
+//!
+//! Implement [`Compatible`] to say how similar two solutions are, and then either use
+//! [`alg::Speciated`], which selects and varies each species separately, or build your own
+//! algorithm from [`speciate()`], [`shared_fitness()`], and [`allocate()`].
+//! [`SpeciesSummary`] keeps track of the number of species in each generation.
+//!
+#![cfg_attr(feature = "std", doc = "```")]
+#![cfg_attr(not(feature = "std"), doc = "```ignore")]
+//! use eviolite::prelude::*;
+//! use eviolite::species::Compatible;
+//! # #[derive(Clone)]
+//! # struct Point(f64);
+//! # impl Solution for Point {
+//! #     type Fitness = f64;
+//! #     fn generate() -> Self { Point(random::<f64>() * 10.0) }
+//! #     fn evaluate(&self) -> f64 { (self.0 * 3.0).sin() }
+//! #     fn crossover(a: &mut Self, b: &mut Self) { std::mem::swap(&mut a.0, &mut b.0) }
+//! #     fn mutate(&mut self) { self.0 += random::<f64>() - 0.5 }
+//! # }
+//!
+//! impl Compatible for Point {
+//!     const THRESHOLD: f64 = 1.0;
+//!
+//!     fn distance(a: &Self, b: &Self) -> f64 {
+//!         (a.0 - b.0).abs()
+//!     }
+//! }
+//!
+//! let evo: Evolution<Point, _, _, stats::SpeciesSummary> = Evolution::new(
+//!     alg::Speciated::new(50, 0.5, 0.3, select::Tournament::new(3)),
+//!     hof::BestN::new(5),
+//! );
+//! let log = evo.run_for(20);
+//! assert!(log.stats.iter().all(|stat| stat.count() >= 1));
+//! ```
+//!
+//! [`Compatible`]: ./trait.Compatible.html
+//! [`alg::Speciated`]: ../alg/struct.Speciated.html
+//! [`speciate()`]: ./fn.speciate.html
+//! [`shared_fitness()`]: ./fn.shared_fitness.html
+//! [`allocate()`]: ./fn.allocate.html
+//! [`SpeciesSummary`]: ../stats/struct.SpeciesSummary.html
+
+use alloc::{vec, vec::Vec};
+
+use crate::{fitness::Direction, Cached, Solution};
+
+/// A solution that can be sorted into species by how similar it is to other solutions
+pub trait Compatible: Solution {
+    /// The largest distance at which a solution still belongs to the same species as another.
+    const THRESHOLD: f64;
+
+    /// Get how different two solutions are.
+    ///
+    /// This should be symmetric, and 0 for identical solutions.
+    /// NEAT's compatibility distance, or the Euclidean distance between two vectors, are typical choices.
+    fn distance(a: &Self, b: &Self) -> f64;
+}
+
+/// Sort a population into species, returning the indices of each species' members.
+///
+/// Solutions are assigned in order: each one joins the first species whose representative,
+/// the first solution assigned to it, is within [`Compatible::THRESHOLD`] of it,
+/// or starts a new species if there isn't one.
+/// Every solution belongs to exactly one species, and no species is empty.
+///
+/// [`Compatible::THRESHOLD`]: ./trait.Compatible.html#associatedconstant.THRESHOLD
+pub fn speciate<T: Compatible>(pop: &[Cached<T>]) -> Vec<Vec<usize>> {
+    let mut species: Vec<Vec<usize>> = Vec::new();
+    for (i, ind) in pop.iter().enumerate() {
+        let found = species
+            .iter_mut()
+            .find(|members| T::distance(pop[members[0]].as_ref(), ind.as_ref()) <= T::THRESHOLD);
+        match found {
+            Some(members) => members.push(i),
+            None => species.push(vec![i]),
+        }
+    }
+    species
+}
+
+/// Compute the shared fitness of every solution in a population, given its species.
+///
+/// Each solution's fitness is first shifted so that the worst solution in the population has 0,
+/// taking [`Solution::DIRECTIONS`] into account so higher is always better,
+/// and is then divided by the size of its species, which is explicit fitness sharing as in NEAT.
+/// The sum of the shared fitness of a species is then the mean shifted fitness of its members,
+/// so a large species doesn't get more offspring just for being large.
+///
+/// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+pub fn shared_fitness<T>(pop: &[Cached<T>], species: &[Vec<usize>]) -> Vec<f64>
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+{
+    let direction = Direction::of::<T>(0);
+    let oriented: Vec<f64> = pop
+        .iter()
+        .map(|ind| match direction {
+            Direction::Maximize => ind.evaluate().into(),
+            Direction::Minimize => -ind.evaluate().into(),
+        })
+        .collect();
+    let worst = oriented.iter().copied().fold(f64::INFINITY, f64::min);
+
+    let mut shared = vec![0.0; pop.len()];
+    for members in species {
+        for &i in members {
+            shared[i] = (oriented[i] - worst) / members.len() as f64;
+        }
+    }
+    shared
+}
+
+/// Split `amount` offspring between species in proportion to their `weights`,
+/// usually the sum of their members' [shared fitness].
+///
+/// The counts always add up to `amount`. Fractions of an offspring are given out
+/// in order of the largest remainder. If no species has a positive weight,
+/// the offspring are split evenly instead.
+///
+/// [shared fitness]: ./fn.shared_fitness.html
+pub fn allocate(amount: usize, weights: &[f64]) -> Vec<usize> {
+    if weights.is_empty() {
+        return Vec::new();
+    }
+    let total: f64 = weights.iter().filter(|&&w| w > 0.0).sum();
+    let quotas: Vec<f64> = if total > 0.0 && total.is_finite() {
+        weights
+            .iter()
+            .map(|&w| w.max(0.0) / total * amount as f64)
+            .collect()
+    } else {
+        vec![amount as f64 / weights.len() as f64; weights.len()]
+    };
+
+    let mut counts: Vec<usize> = quotas.iter().map(|&q| q as usize).collect();
+    let given: usize = counts.iter().sum();
+    let mut order: Vec<usize> = (0..weights.len()).collect();
+    order.sort_by(|&a, &b| {
+        let rem = |i: usize| quotas[i] - counts[i] as f64;
+        rem(b).total_cmp(&rem(a))
+    });
+    for &i in order.iter().cycle().take(amount.saturating_sub(given)) {
+        counts[i] += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutils::Walk;
+
+    impl Compatible for Walk {
+        const THRESHOLD: f64 = 1.0;
+
+        fn distance(a: &Self, b: &Self) -> f64 {
+            (a.0 - b.0).abs()
+        }
+    }
+
+    #[test]
+    fn species_and_sharing() {
+        let pop: Vec<Cached<Walk>> = [0.0, 5.0, 0.5, 5.8, -0.9, 9.0]
+            .into_iter()
+            .map(|x| Cached::new(Walk(x)))
+            .collect();
+        let species = speciate(&pop);
+        assert_eq!(species, [vec![0, 2, 4], vec![1, 3], vec![5]]);
+
+        // fitness is -|x|, so the worst solution is at 9
+        let shared = shared_fitness(&pop, &species);
+        assert_eq!(shared[0], 9.0 / 3.0);
+        assert_eq!(shared[3], (9.0 - 5.8) / 2.0);
+        assert_eq!(shared[5], 0.0);
+
+        let weights: Vec<f64> = species
+            .iter()
+            .map(|members| members.iter().map(|&i| shared[i]).sum())
+            .collect();
+        let counts = allocate(10, &weights);
+        assert_eq!(counts.iter().sum::<usize>(), 10);
+        assert_eq!(counts[2], 0);
+        assert!(counts[0] > counts[1]);
+
+        assert_eq!(allocate(7, &[0.0, 0.0, 0.0]), [3, 2, 2]);
+        assert_eq!(allocate(3, &[1.0, 1.0, 1.0, 1.0]).iter().sum::<usize>(), 3);
+    }
+}
@@ -4,35 +4,269 @@
 //! You can implement this trait for a type to calculate whatever statistics you want about a given generation of solutions.
 //! When you call [`.run()`] on your [`Evolution`] instance, you'll receive a `Vec` of statistics about each successive generation.
 //!
-//! If you just want to get started quickly, this module also includes a few simple implementors.
+//! The statistics are calculated by a collector, which is created with `Default` unless one is given
+//! to [`Evolution::with_stats()`], so it can carry configuration and remember earlier generations.
+//! If you just want to get started quickly, this module also includes a few simple implementors,
+//! which need the `std` crate feature for floating-point math. They don't need any configuration,
+//! so each of them is its own collector and the statistics it calculates.
 //! [`GenerationStats`] is also implemented for `()` as a no-op,
-//! allowing you to opt-out of calculating any statistics.
+//! allowing you to opt-out of calculating any statistics,
+//! and for tuples of up to four statistics, which are all calculated for each generation:
+//!
+#![cfg_attr(feature = "std", doc = "```")]
+#![cfg_attr(not(feature = "std"), doc = "```ignore")]
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::{Benchmark, Sphere};
+//!
+//! type Stats = (stats::FitnessBasic, stats::FitnessSummary);
+//!
+//! let evo: Evolution<Benchmark<Sphere, 2>, _, _, Stats> = Evolution::new(
+//!     alg::Simple::new(20, 0.5, 0.2, select::Tournament::new(3)),
+//!     hof::BestN::new(1),
+//! );
+//! let log = evo.run_for(10);
+//! let (basic, summary) = &log.stats[9];
+//! println!("variance {}, best {}", basic.variance(), summary.max());
+//! ```
+//!
+//! For very long runs, a [`StatsSink`] such as [`Downsample`] can bound how many generations' statistics are kept.
 //!
 //! [`.run()`]: ../struct.Evolution.html#method.run
+//! [`StatsSink`]: ./trait.StatsSink.html
+//! [`Downsample`]: ./struct.Downsample.html
 //! [`Evolution`]: ../struct.Evolution.html
+//! [`Evolution::with_stats()`]: ../struct.Evolution.html#method.with_stats
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::{marker::PhantomData, ops::Deref, sync::Arc, time::Duration};
+
+#[cfg(feature = "std")]
+use rand::Rng;
 
-use crate::{fitness::MultiObjective, utils::Cached, Solution};
+#[cfg(feature = "std")]
+use crate::{
+    credit::{Credit, Tracked, Variation},
+    fitness::MultiObjective,
+    repro_rng::thread_rng,
+    select::rank_nondominated,
+    utils::counter::{self, EvalCounter},
+};
+use crate::{
+    species::{speciate, Compatible},
+    utils::Cached,
+    Solution,
+};
 
-/// Trait that indicates a type represents statistics about
-/// a generation of solutions
+/// Trait that indicates a type collects statistics about
+/// each generation of solutions
+///
+/// The collector is called once for every generation, in order, so it can keep track of
+/// how the run has been going, e.g. to smooth a statistic with an exponential moving average:
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+/// use eviolite::stats::GenerationStats;
+///
+/// struct SmoothedBest {
+///     alpha: f64,
+///     average: Option<f64>,
+/// }
+///
+/// impl Default for SmoothedBest {
+///     fn default() -> Self {
+///         SmoothedBest { alpha: 0.5, average: None }
+///     }
+/// }
+///
+/// impl<T: Solution<Fitness = f64>> GenerationStats<T> for SmoothedBest {
+///     type Output = f64;
+///
+///     fn analyze(&mut self, generation: &[Cached<T>]) -> f64 {
+///         let best = generation.iter().map(|ind| ind.evaluate()).fold(f64::MIN, f64::max);
+///         let average = match self.average {
+///             Some(average) => self.alpha * best + (1.0 - self.alpha) * average,
+///             None => best,
+///         };
+///         *self.average.insert(average)
+///     }
+/// }
+///
+/// let evo: Evolution<Benchmark<Sphere, 2>, _, _, SmoothedBest> = Evolution::new(
+///     alg::Simple::new(20, 0.5, 0.2, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// )
+/// .with_stats(SmoothedBest { alpha: 0.2, average: None });
+/// let log = evo.run_for(10);
+/// assert_eq!(log.stats.len(), 10);
+/// ```
 pub trait GenerationStats<T: Solution> {
+    /// The statistics about one generation, which are kept in the run's [`Log`].
+    ///
+    /// [`Log`]: ../struct.Log.html
+    type Output;
+
     /// Analyze the generation and generate statistics about it.
-    fn analyze(generation: &[Cached<T>]) -> Self;
+    fn analyze(&mut self, generation: &[Cached<T>]) -> Self::Output;
 }
 
 impl<T> GenerationStats<T> for ()
 where
     T: Solution,
 {
-    fn analyze(_: &[Cached<T>]) -> Self {}
+    type Output = ();
+
+    fn analyze(&mut self, _: &[Cached<T>]) {}
+}
+
+macro_rules! tuple_stats {
+    ($($name:ident $idx:tt),+) => {
+        impl<T, $($name),+> GenerationStats<T> for ($($name,)+)
+        where
+            T: Solution,
+            $($name: GenerationStats<T>,)+
+        {
+            type Output = ($(<$name as GenerationStats<T>>::Output,)+);
+
+            fn analyze(&mut self, generation: &[Cached<T>]) -> Self::Output {
+                ($(self.$idx.analyze(generation),)+)
+            }
+        }
+    };
+}
+
+tuple_stats!(A 0, B 1);
+tuple_stats!(A 0, B 1, C 2);
+tuple_stats!(A 0, B 1, C 2, D 3);
+
+/// A destination for the statistics of every generation of a run
+///
+/// By default, a run keeps the statistics of every generation in its [`Log`], which grows without bound
+/// over very long runs. Give [`Evolution::with_stats_sink()`] a sink to decide what happens to them instead:
+/// a `Vec` keeps all of them like the default, [`Downsample`] keeps a bounded number spread over the run,
+/// and [`StatsCsvWriter`] writes them to a file as they come without keeping any.
+///
+/// [`Log`]: ../struct.Log.html
+/// [`Evolution::with_stats_sink()`]: ../struct.Evolution.html#method.with_stats_sink
+/// [`Downsample`]: ./struct.Downsample.html
+/// [`StatsCsvWriter`]: ../export/struct.StatsCsvWriter.html
+pub trait StatsSink<S> {
+    /// Take the statistics of generation `gen`.
+    fn push(&mut self, gen: usize, stats: S);
+
+    /// Hand over the statistics that should be kept in the run's [`Log`] when it ends,
+    /// in the order they were pushed.
+    ///
+    /// [`Log`]: ../struct.Log.html
+    fn finish(&mut self) -> Vec<S>;
+
+    /// Get how many generations apart the statistics returned by [`.finish()`] are.
+    /// The default is 1, for sinks that keep every generation or none.
+    ///
+    /// [`.finish()`]: #tymethod.finish
+    fn interval(&self) -> usize {
+        1
+    }
+}
+
+impl<S> StatsSink<S> for Vec<S> {
+    fn push(&mut self, _: usize, stats: S) {
+        Vec::push(self, stats);
+    }
+
+    fn finish(&mut self) -> Vec<S> {
+        core::mem::take(self)
+    }
+}
+
+/// A [`StatsSink`] that keeps at most a fixed number of generations' statistics, evenly spread over the run
+///
+/// It starts by keeping every generation. Whenever it's full, it drops every other one it kept
+/// and from then on only keeps every other generation, so a run of any length ends up
+/// with between half of `capacity` and `capacity` entries, one every [`.interval()`] generations.
+///
+#[cfg_attr(feature = "std", doc = "```")]
+#[cfg_attr(not(feature = "std"), doc = "```ignore")]
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+///
+/// let evo: Evolution<Benchmark<Sphere, 2>, _, _, stats::FitnessSummary> = Evolution::new(
+///     alg::Simple::new(20, 0.5, 0.2, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// )
+/// .with_stats_sink(stats::Downsample::new(8));
+/// let log = evo.run_for(100);
+/// // generations 0, 16, 32, 48, 64, 80 and 96
+/// assert_eq!(log.stats.len(), 7);
+/// assert_eq!(log.stats_interval, 16);
+/// assert_eq!(log.elapsed.len(), 7);
+/// ```
+///
+/// [`StatsSink`]: ./trait.StatsSink.html
+/// [`.interval()`]: ./trait.StatsSink.html#method.interval
+#[derive(Clone, Debug)]
+pub struct Downsample<S> {
+    kept: Vec<S>,
+    capacity: usize,
+    interval: usize,
+    pushed: usize,
+}
+
+impl<S> Downsample<S> {
+    /// Keep the statistics of at most `capacity` generations.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `capacity` is less than 2.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity >= 2, "capacity must be at least 2");
+        Downsample {
+            kept: Vec::with_capacity(capacity),
+            capacity,
+            interval: 1,
+            pushed: 0,
+        }
+    }
+}
+
+impl<S> StatsSink<S> for Downsample<S> {
+    fn push(&mut self, _: usize, stats: S) {
+        if self.pushed.is_multiple_of(self.interval) && self.kept.len() == self.capacity {
+            let mut i = 0;
+            self.kept.retain(|_| {
+                i += 1;
+                i % 2 == 1
+            });
+            self.interval *= 2;
+        }
+        if self.pushed.is_multiple_of(self.interval) {
+            self.kept.push(stats);
+        }
+        self.pushed += 1;
+    }
+
+    fn finish(&mut self) -> Vec<S> {
+        core::mem::take(&mut self.kept)
+    }
+
+    fn interval(&self) -> usize {
+        self.interval
+    }
 }
 
 /// Mean and standard deviation for single-objective fitness
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FitnessBasic {
     mean: f64,
     variance: f64,
 }
 
+#[cfg(feature = "std")]
 impl FitnessBasic {
     /// Get the mean of the generation's fitness values.
     pub fn mean(&self) -> f64 {
@@ -50,12 +284,15 @@ impl FitnessBasic {
     }
 }
 
+#[cfg(feature = "std")]
 impl<T> GenerationStats<T> for FitnessBasic
 where
     T: Solution,
     T::Fitness: Into<f64>,
 {
-    fn analyze(generation: &[Cached<T>]) -> Self {
+    type Output = Self;
+
+    fn analyze(&mut self, generation: &[Cached<T>]) -> Self {
         let mean: f64 = generation.iter().map(|sol| sol.evaluate().into()).sum();
         let variance: f64 = generation
             .iter()
@@ -69,12 +306,20 @@ where
 /// Mean and standard deviation of each objective in a [`MultiObjective`]
 ///
 /// [`MultiObjective`]: ../fitness/struct.MultiObjective.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FitnessBasicMulti<const M: usize> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::serde_array"))]
     mean: [f64; M],
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::serde_array"))]
     variance: [f64; M],
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::serde_array"))]
     stdev: [f64; M],
 }
 
+#[cfg(feature = "std")]
 impl<const M: usize> FitnessBasicMulti<M> {
     /// Get the mean for each objective.
     pub fn mean(&self) -> &[f64] {
@@ -92,11 +337,25 @@ impl<const M: usize> FitnessBasicMulti<M> {
     }
 }
 
+#[cfg(feature = "std")]
+impl<const M: usize> Default for FitnessBasicMulti<M> {
+    fn default() -> Self {
+        FitnessBasicMulti {
+            mean: [0.0; M],
+            variance: [0.0; M],
+            stdev: [0.0; M],
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 impl<T, const M: usize> GenerationStats<T> for FitnessBasicMulti<M>
 where
     T: Solution<Fitness = MultiObjective<M>>,
 {
-    fn analyze(generation: &[Cached<T>]) -> Self {
+    type Output = Self;
+
+    fn analyze(&mut self, generation: &[Cached<T>]) -> Self {
         let len = generation.len() as f64;
         let mut mean = [0.0f64; M];
         let mut variance = [0.0f64; M];
@@ -123,3 +382,1072 @@ where
         }
     }
 }
+
+/// Mean, standard deviation, minimum, and maximum for single-objective fitness
+///
+/// These are the same statistics DEAP's examples usually record,
+/// which makes this type a good fit for a [`Logbook`].
+/// Unlike [`FitnessBasic`], the standard deviation is the population standard deviation,
+/// i.e. normalized by the number of solutions.
+///
+/// [`Logbook`]: ../export/struct.Logbook.html
+/// [`FitnessBasic`]: ./struct.FitnessBasic.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FitnessSummary {
+    mean: f64,
+    stdev: f64,
+    min: f64,
+    max: f64,
+}
+
+#[cfg(feature = "std")]
+impl FitnessSummary {
+    /// Get the mean of the generation's fitness values.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Get the standard deviation of the generation's fitness values.
+    pub fn stdev(&self) -> f64 {
+        self.stdev
+    }
+
+    /// Get the lowest of the generation's fitness values.
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// Get the highest of the generation's fitness values.
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> GenerationStats<T> for FitnessSummary
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+{
+    type Output = Self;
+
+    fn analyze(&mut self, generation: &[Cached<T>]) -> Self {
+        let len = generation.len() as f64;
+        let values = || generation.iter().map(|sol| sol.evaluate().into());
+        let mean = values().sum::<f64>() / len;
+        let variance = values().map(|value| (value - mean).powi(2)).sum::<f64>() / len;
+
+        FitnessSummary {
+            mean,
+            stdev: variance.sqrt(),
+            min: values().fold(f64::INFINITY, f64::min),
+            max: values().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+/// Inverted generational distance (IGD) of each generation's nondominated solutions from a reference front
+///
+/// This is the mean Euclidean distance from each point of the reference front to the closest
+/// nondominated solution in the generation, so lower is better. Since every part of the front
+/// has to be close to some solution, it measures both how close the solutions are to the front
+/// and how well they cover it. See [`GenerationalDistance`] for the other way around.
+///
+/// The reference front is given as fitness values, like those from [`MultiBenchmark::reference_fitnesses()`],
+/// and the nondominated solutions are found with [`rank_nondominated()`], respecting [`Solution::DIRECTIONS`].
+/// The default reference front is empty, which gives an IGD of NaN and a generational distance
+/// of infinity, so the collector should be given to the run with [`Evolution::with_stats()`]:
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::multi::{MultiBenchmark, Zdt1};
+/// use eviolite::stats::{GenerationalDistance, InvertedGenerationalDistance};
+///
+/// type Problem = MultiBenchmark<Zdt1, 10, 2>;
+/// type Stats = (InvertedGenerationalDistance<2>, GenerationalDistance<2>);
+///
+/// let reference = Problem::reference_fitnesses(100);
+/// let evo: Evolution<Problem, _, _, Stats> = Evolution::new(
+///     alg::NSGA2::new(40, 0.9, 0.1),
+///     hof::BestPareto::new(),
+/// )
+/// .with_stats((
+///     InvertedGenerationalDistance::new(reference.clone()),
+///     GenerationalDistance::new(reference),
+/// ));
+/// let log = evo.run_for(20);
+/// let (igd, gd) = log.stats[19];
+/// assert!(igd >= 0.0 && gd >= 0.0);
+/// ```
+///
+/// [`GenerationalDistance`]: ./struct.GenerationalDistance.html
+/// [`MultiBenchmark::reference_fitnesses()`]: ../benchmarks/multi/struct.MultiBenchmark.html#method.reference_fitnesses
+/// [`rank_nondominated()`]: ../select/fn.rank_nondominated.html
+/// [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+/// [`Evolution::with_stats()`]: ../struct.Evolution.html#method.with_stats
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Default)]
+pub struct InvertedGenerationalDistance<const M: usize> {
+    reference: Vec<MultiObjective<M>>,
+}
+
+#[cfg(feature = "std")]
+impl<const M: usize> InvertedGenerationalDistance<M> {
+    /// Measure the distance from `reference`, a sample of the Pareto front as fitness values.
+    pub fn new(reference: Vec<MultiObjective<M>>) -> Self {
+        InvertedGenerationalDistance { reference }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, const M: usize> GenerationStats<T> for InvertedGenerationalDistance<M>
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+{
+    type Output = f64;
+
+    fn analyze(&mut self, generation: &[Cached<T>]) -> f64 {
+        mean_distance(&self.reference, &nondominated_fitnesses(generation))
+    }
+}
+
+/// Generational distance (GD) of each generation's nondominated solutions from a reference front
+///
+/// This is the mean Euclidean distance from each nondominated solution in the generation
+/// to the closest point of the reference front, so lower is better. It only measures how close
+/// the solutions are to the front, not how well they cover it; see [`InvertedGenerationalDistance`] for that,
+/// and for how the reference front is given.
+///
+/// [`InvertedGenerationalDistance`]: ./struct.InvertedGenerationalDistance.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Default)]
+pub struct GenerationalDistance<const M: usize> {
+    reference: Vec<MultiObjective<M>>,
+}
+
+#[cfg(feature = "std")]
+impl<const M: usize> GenerationalDistance<M> {
+    /// Measure the distance to `reference`, a sample of the Pareto front as fitness values.
+    pub fn new(reference: Vec<MultiObjective<M>>) -> Self {
+        GenerationalDistance { reference }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, const M: usize> GenerationStats<T> for GenerationalDistance<M>
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+{
+    type Output = f64;
+
+    fn analyze(&mut self, generation: &[Cached<T>]) -> f64 {
+        mean_distance(&nondominated_fitnesses(generation), &self.reference)
+    }
+}
+
+/// Schott's spacing[^1] of each generation's nondominated solutions
+///
+/// For each nondominated solution, this finds the Manhattan distance in fitness to the closest other one,
+/// and gives the standard deviation of those distances. Lower is better, and 0 means that the solutions
+/// are evenly spaced along the front. A front with fewer than two solutions has a spacing of 0.
+///
+/// [^1]: Schott.
+/// "Fault Tolerant Design Using Single and Multicriteria Genetic Algorithm Optimization."
+/// Master's thesis, Massachusetts Institute of Technology, 1995.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FrontSpacing;
+
+#[cfg(feature = "std")]
+impl<T, const M: usize> GenerationStats<T> for FrontSpacing
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+{
+    type Output = f64;
+
+    fn analyze(&mut self, generation: &[Cached<T>]) -> f64 {
+        let front = nondominated_fitnesses(generation);
+        if front.len() < 2 {
+            return 0.0;
+        }
+        let distances: Vec<f64> = front
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                front
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .map(|(_, b)| a.iter().zip(b.iter()).map(|(x, y)| (x - y).abs()).sum())
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect();
+        let mean = distances.iter().sum::<f64>() / distances.len() as f64;
+        let variance = distances.iter().map(|d| (d - mean).powi(2)).sum::<f64>()
+            / (distances.len() - 1) as f64;
+        variance.sqrt()
+    }
+}
+
+/// The spread (Δ)[^1] of each generation's nondominated solutions for problems with two objectives
+///
+/// The solutions are sorted along the front, and Δ compares the distances between neighbors
+/// with their mean, also counting the distances from the ends of the front to the extreme points
+/// of a reference front if one is given with [`with_reference()`].
+/// Lower is better, and 0 means that the solutions are evenly spaced and reach the ends
+/// of the reference front. A front with fewer than two distinct solutions has a spread of 1.
+///
+/// Without a reference front, only how evenly the solutions are spaced is measured, not how far they reach.
+///
+/// [^1]: Deb, Pratap, Agarwal, & Meyarivan.
+/// "A fast and elitist multiobjective genetic algorithm: NSGA-II."
+/// 2002. <https://doi.org/10.1109/4235.996017>
+///
+/// [`with_reference()`]: #method.with_reference
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Default)]
+pub struct FrontSpread {
+    extremes: Option<[MultiObjective<2>; 2]>,
+}
+
+#[cfg(feature = "std")]
+impl FrontSpread {
+    /// Measure how far the front reaches towards the extreme points of `reference`,
+    /// a sample of the Pareto front as fitness values,
+    /// like those from [`MultiBenchmark::reference_fitnesses()`].
+    ///
+    /// Panics
+    /// ======
+    /// Panics if `reference` is empty.
+    ///
+    /// [`MultiBenchmark::reference_fitnesses()`]: ../benchmarks/multi/struct.MultiBenchmark.html#method.reference_fitnesses
+    pub fn with_reference(reference: &[MultiObjective<2>]) -> Self {
+        let by_first = |a: &&MultiObjective<2>, b: &&MultiObjective<2>| a[0].total_cmp(&b[0]);
+        let first = reference.iter().min_by(by_first);
+        let last = reference.iter().max_by(by_first);
+        let (first, last) = first.zip(last).expect("the reference front is empty");
+        FrontSpread {
+            extremes: Some([*first, *last]),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> GenerationStats<T> for FrontSpread
+where
+    T: Solution<Fitness = MultiObjective<2>>,
+{
+    type Output = f64;
+
+    fn analyze(&mut self, generation: &[Cached<T>]) -> f64 {
+        let distance = |a: &MultiObjective<2>, b: &MultiObjective<2>| {
+            ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+        };
+        let mut front = nondominated_fitnesses(generation);
+        if front.len() < 2 {
+            return 1.0;
+        }
+        front.sort_unstable_by(|a, b| a[0].total_cmp(&b[0]));
+        let (d_first, d_last) = match &self.extremes {
+            Some([first, last]) => (
+                distance(first, &front[0]),
+                distance(last, &front[front.len() - 1]),
+            ),
+            None => (0.0, 0.0),
+        };
+        let gaps: Vec<f64> = front
+            .windows(2)
+            .map(|pair| distance(&pair[0], &pair[1]))
+            .collect();
+        let mean = gaps.iter().sum::<f64>() / gaps.len() as f64;
+        let denominator = d_first + d_last + gaps.len() as f64 * mean;
+        if denominator == 0.0 {
+            return 1.0;
+        }
+        (d_first + d_last + gaps.iter().map(|gap| (gap - mean).abs()).sum::<f64>()) / denominator
+    }
+}
+
+// The fitness of each solution in the first Pareto front of the generation
+#[cfg(feature = "std")]
+fn nondominated_fitnesses<T, const M: usize>(generation: &[Cached<T>]) -> Vec<MultiObjective<M>>
+where
+    T: Solution<Fitness = MultiObjective<M>>,
+{
+    let pareto = rank_nondominated(generation);
+    generation
+        .iter()
+        .zip(pareto.ranks)
+        .filter(|&(_, rank)| rank == 0)
+        .map(|(ind, _)| ind.evaluate())
+        .collect()
+}
+
+// The mean distance from each point in `from` to the closest point in `to`
+#[cfg(feature = "std")]
+fn mean_distance<const M: usize>(from: &[MultiObjective<M>], to: &[MultiObjective<M>]) -> f64 {
+    let total: f64 = from
+        .iter()
+        .map(|a| {
+            to.iter()
+                .map(|b| {
+                    a.iter()
+                        .zip(b.iter())
+                        .map(|(x, y)| (x - y).powi(2))
+                        .sum::<f64>()
+                        .sqrt()
+                })
+                .fold(f64::INFINITY, f64::min)
+        })
+        .sum();
+    total / from.len() as f64
+}
+
+/// Minimum, maximum, median, and other quantiles for single-objective fitness
+///
+/// Quantiles are linearly interpolated between the two closest fitness values, the same way as
+/// numpy's default. Unlike the mean and variance, they show how the fitness values are distributed,
+/// e.g. whether a few outliers are far from the rest.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+/// use eviolite::stats::FitnessQuantiles;
+///
+/// let evo: Evolution<Benchmark<Sphere, 3>, _, _, FitnessQuantiles> = Evolution::new(
+///     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// )
+/// .with_stats(FitnessQuantiles::new(&[0.1, 0.9]));
+/// let log = evo.run_for(10);
+/// let last = &log.stats[9];
+/// assert!(last.min() <= last.quantile(0.1).unwrap());
+/// assert!(last.quantile(0.1).unwrap() <= last.median());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug)]
+pub struct FitnessQuantiles {
+    quantiles: Vec<f64>,
+}
+
+#[cfg(feature = "std")]
+impl FitnessQuantiles {
+    /// Calculate the quantiles in `quantiles` as well as the minimum, maximum, and median.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if any of `quantiles` isn't between 0 and 1.
+    pub fn new(quantiles: &[f64]) -> Self {
+        assert!(
+            quantiles.iter().all(|q| (0.0..=1.0).contains(q)),
+            "quantiles must be between 0 and 1"
+        );
+        FitnessQuantiles {
+            quantiles: quantiles.to_vec(),
+        }
+    }
+}
+
+/// The default calculates the quartiles, 0.25 and 0.75.
+#[cfg(feature = "std")]
+impl Default for FitnessQuantiles {
+    fn default() -> Self {
+        FitnessQuantiles::new(&[0.25, 0.75])
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> GenerationStats<T> for FitnessQuantiles
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+{
+    type Output = Quantiles;
+
+    fn analyze(&mut self, generation: &[Cached<T>]) -> Quantiles {
+        let mut values: Vec<f64> = generation.iter().map(|sol| sol.evaluate().into()).collect();
+        values.sort_unstable_by(f64::total_cmp);
+        let quantile = |q: f64| {
+            if values.is_empty() {
+                return f64::NAN;
+            }
+            let position = q * (values.len() - 1) as f64;
+            let (below, above) = (position.floor() as usize, position.ceil() as usize);
+            values[below] + (values[above] - values[below]) * (position - below as f64)
+        };
+
+        Quantiles {
+            min: quantile(0.0),
+            max: quantile(1.0),
+            median: quantile(0.5),
+            quantiles: self.quantiles.iter().map(|&q| (q, quantile(q))).collect(),
+        }
+    }
+}
+
+/// The statistics computed by [`FitnessQuantiles`]
+///
+/// Every value is NaN for an empty generation.
+///
+/// [`FitnessQuantiles`]: ./struct.FitnessQuantiles.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quantiles {
+    min: f64,
+    max: f64,
+    median: f64,
+    quantiles: Vec<(f64, f64)>,
+}
+
+#[cfg(feature = "std")]
+impl Quantiles {
+    /// Get the lowest of the generation's fitness values.
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+
+    /// Get the highest of the generation's fitness values.
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    /// Get the median of the generation's fitness values.
+    pub fn median(&self) -> f64 {
+        self.median
+    }
+
+    /// Get quantile `q`, or `None` if it wasn't one of those given to [`FitnessQuantiles::new()`].
+    ///
+    /// [`FitnessQuantiles::new()`]: ./struct.FitnessQuantiles.html#method.new
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        self.quantiles
+            .iter()
+            .find(|&&(quantile, _)| quantile == q)
+            .map(|&(_, value)| value)
+    }
+
+    /// Get each quantile given to [`FitnessQuantiles::new()`] with its value, in the same order.
+    ///
+    /// [`FitnessQuantiles::new()`]: ./struct.FitnessQuantiles.html#method.new
+    pub fn quantiles(&self) -> &[(f64, f64)] {
+        &self.quantiles
+    }
+}
+
+/// Counts how many fitness evaluations each generation needed, to show how well the fitness cache works
+///
+/// Every member of a generation either had to be evaluated or had its fitness cached from an earlier generation,
+/// e.g. because it survived selection or wasn't changed by any variation operator.
+/// If nearly every member is evaluated anew, the operators change almost every solution,
+/// which is worth knowing when evaluations are expensive.
+///
+/// The counts are the run's own, as counted by [`Generation::evaluations`], so other runs in the program
+/// don't affect them. They do include offspring that were evaluated but didn't make it into the generation,
+/// e.g. with [`MuPlusLambda`], in which case fewer members count as cached than really were.
+/// Outside of a run, every evaluation in the program is counted, like [`evaluation_count()`] does.
+/// The evaluation time is the wall-clock time spent inside [`Solution::evaluate()`],
+/// summed over every thread, so it can be longer than the generation took. It's always zero on `wasm32`,
+/// and it doesn't include fitness values computed outside of [`Cached`], e.g. by [`device_evaluate()`].
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Sphere};
+///
+/// let evo: Evolution<Benchmark<Sphere, 3>, _, _, stats::EvalCounters> = Evolution::new(
+///     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// );
+/// let log = evo.run_for(10);
+/// for counts in &log.stats {
+///     println!("{} evaluated, {:.0}% cached", counts.evaluated(), counts.hit_rate() * 100.0);
+/// }
+/// ```
+///
+/// [`Generation::evaluations`]: ../struct.Generation.html#structfield.evaluations
+/// [`MuPlusLambda`]: ../alg/struct.MuPlusLambda.html
+/// [`evaluation_count()`]: ../fitness/fn.evaluation_count.html
+/// [`Solution::evaluate()`]: ../trait.Solution.html#tymethod.evaluate
+/// [`Cached`]: ../struct.Cached.html
+/// [`device_evaluate()`]: ../fitness/fn.device_evaluate.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug)]
+pub struct EvalCounters {
+    // The counter of the run being analyzed, or `None` for every evaluation in the program
+    run: Option<Arc<EvalCounter>>,
+    evaluations: u64,
+    nanos: u64,
+}
+
+#[cfg(feature = "std")]
+impl EvalCounters {
+    // The counts of the run entered on this thread, if any
+    fn current() -> Self {
+        let run = counter::current();
+        let counter = run.as_deref().unwrap_or(counter::total());
+        EvalCounters {
+            evaluations: counter.evaluations(),
+            nanos: counter.nanos(),
+            run,
+        }
+    }
+}
+
+/// The default starts counting from when it's created, or from the start of the run it's used in.
+#[cfg(feature = "std")]
+impl Default for EvalCounters {
+    fn default() -> Self {
+        EvalCounters::current()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Solution> GenerationStats<T> for EvalCounters {
+    type Output = EvalCounts;
+
+    fn analyze(&mut self, generation: &[Cached<T>]) -> EvalCounts {
+        let current = EvalCounters::current();
+        let same_counter = match (&self.run, &current.run) {
+            (Some(previous), Some(run)) => Arc::ptr_eq(previous, run),
+            (None, None) => true,
+            _ => false,
+        };
+        // every run counts from 0 when it starts
+        let (evaluations, nanos) = match (same_counter, &current.run) {
+            (true, _) => (self.evaluations, self.nanos),
+            (false, Some(_)) => (0, 0),
+            (false, None) => (current.evaluations, current.nanos),
+        };
+        let evaluated = current.evaluations - evaluations;
+        let size = generation.len() as u64;
+        let evaluation_time = Duration::from_nanos(current.nanos.wrapping_sub(nanos));
+        *self = current;
+        EvalCounts {
+            evaluated,
+            cached: size.saturating_sub(evaluated),
+            size,
+            evaluation_time,
+        }
+    }
+}
+
+/// The statistics computed by [`EvalCounters`]
+///
+/// [`EvalCounters`]: ./struct.EvalCounters.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EvalCounts {
+    evaluated: u64,
+    cached: u64,
+    size: u64,
+    evaluation_time: Duration,
+}
+
+#[cfg(feature = "std")]
+impl EvalCounts {
+    /// Get the number of evaluations since the previous generation.
+    pub fn evaluated(&self) -> u64 {
+        self.evaluated
+    }
+
+    /// Get the number of members of the generation whose fitness didn't need to be evaluated again.
+    pub fn cached(&self) -> u64 {
+        self.cached
+    }
+
+    /// Get the fraction of the generation whose fitness was served from the cache, between 0 and 1.
+    ///
+    /// This is 0 for an empty generation.
+    pub fn hit_rate(&self) -> f64 {
+        if self.size == 0 {
+            0.0
+        } else {
+            self.cached as f64 / self.size as f64
+        }
+    }
+
+    /// Get the total time spent evaluating solutions since the previous generation.
+    pub fn evaluation_time(&self) -> Duration {
+        self.evaluation_time
+    }
+}
+
+/// The number of species in a generation and the size of the largest one
+///
+/// See the [`species`] module for how solutions are sorted into species.
+///
+/// [`species`]: ../species/index.html
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpeciesSummary {
+    count: usize,
+    largest: usize,
+}
+
+impl SpeciesSummary {
+    /// Get the number of species.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Get the number of solutions in the largest species.
+    pub fn largest(&self) -> usize {
+        self.largest
+    }
+}
+
+impl<T> GenerationStats<T> for SpeciesSummary
+where
+    T: Compatible,
+{
+    type Output = Self;
+
+    fn analyze(&mut self, generation: &[Cached<T>]) -> Self {
+        let species = speciate(generation);
+        SpeciesSummary {
+            count: species.len(),
+            largest: species
+                .iter()
+                .map(|members| members.len())
+                .max()
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// A way of measuring how different two solutions are, used by [`Diversity`]
+///
+/// Any closure or function that takes two solutions and returns a distance implements this trait.
+///
+/// [`Diversity`]: ./struct.Diversity.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub trait Distance<T> {
+    /// Get the distance between `a` and `b`.
+    ///
+    /// This should be symmetric, and 0 for identical solutions.
+    fn distance(&self, a: &T, b: &T) -> f64;
+}
+
+#[cfg(feature = "std")]
+impl<T, F> Distance<T> for F
+where
+    F: Fn(&T, &T) -> f64,
+{
+    fn distance(&self, a: &T, b: &T) -> f64 {
+        self(a, b)
+    }
+}
+
+/// The Euclidean distance between the real-valued genomes that two solutions deref to,
+/// like those of [`Benchmark`] and [`MultiBenchmark`]
+///
+/// [`Benchmark`]: ../benchmarks/struct.Benchmark.html
+/// [`MultiBenchmark`]: ../benchmarks/multi/struct.MultiBenchmark.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Euclidean;
+
+#[cfg(feature = "std")]
+impl<T> Distance<T> for Euclidean
+where
+    T: Deref,
+    T::Target: Deref<Target = [f64]>,
+{
+    fn distance(&self, a: &T, b: &T) -> f64 {
+        a.iter()
+            .zip(b.iter())
+            .map(|(x, y)| (x - y).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+/// The number of positions at which the genomes that two solutions deref to differ,
+/// like those of [`BitBenchmark`]
+///
+/// [`BitBenchmark`]: ../benchmarks/binary/struct.BitBenchmark.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Hamming;
+
+#[cfg(feature = "std")]
+impl<T, E> Distance<T> for Hamming
+where
+    T: Deref,
+    T::Target: Deref<Target = [E]>,
+    E: PartialEq,
+{
+    fn distance(&self, a: &T, b: &T) -> f64 {
+        let differing = a.iter().zip(b.iter()).filter(|(x, y)| x != y).count();
+        (differing + a.len().abs_diff(b.len())) as f64
+    }
+}
+
+/// The mean and smallest distance between the solutions of each generation, measured with `D`
+///
+/// A population whose distances shrink towards 0 has converged, which can happen long before
+/// its fitness stops improving, and too early if the run is stuck in a local optimum.
+///
+/// Every pair of solutions is measured if there are at most [`max_pairs`] of them.
+/// Otherwise that many pairs are sampled at random, so the mean is an estimate,
+/// and the smallest distance may be larger than the real one.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::benchmarks::{Benchmark, Rastrigin};
+/// use eviolite::stats::{Diversity, Euclidean};
+///
+/// type Problem = Benchmark<Rastrigin, 5>;
+///
+/// let evo: Evolution<Problem, _, _, Diversity<Problem, Euclidean>> = Evolution::new(
+///     alg::Simple::new(100, 0.5, 0.2, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// )
+/// .with_stats(Diversity::new(Euclidean).with_max_pairs(1000));
+/// let log = evo.run_for(20);
+/// assert!(log.stats[19].mean() < log.stats[0].mean());
+/// ```
+///
+/// [`max_pairs`]: #method.with_max_pairs
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub struct Diversity<T, D> {
+    distance: D,
+    max_pairs: usize,
+    _marker: PhantomData<fn(&T)>,
+}
+
+#[cfg(feature = "std")]
+impl<T, D> Diversity<T, D>
+where
+    T: Solution,
+    D: Distance<T>,
+{
+    /// Measure distances with `distance`, sampling at most 10,000 pairs of solutions per generation.
+    pub fn new(distance: D) -> Self {
+        Diversity {
+            distance,
+            max_pairs: 10_000,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sample at most `max_pairs` pairs of solutions per generation.
+    pub fn with_max_pairs(mut self, max_pairs: usize) -> Self {
+        self.max_pairs = max_pairs;
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, D> Default for Diversity<T, D>
+where
+    T: Solution,
+    D: Distance<T> + Default,
+{
+    fn default() -> Self {
+        Diversity::new(D::default())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, D> GenerationStats<T> for Diversity<T, D>
+where
+    T: Solution,
+    D: Distance<T>,
+{
+    type Output = DiversitySummary;
+
+    fn analyze(&mut self, generation: &[Cached<T>]) -> DiversitySummary {
+        let n = generation.len();
+        let distance = |i: usize, j: usize| {
+            self.distance
+                .distance(generation[i].as_ref(), generation[j].as_ref())
+        };
+        let distances: Vec<f64> = if n * n.saturating_sub(1) / 2 <= self.max_pairs {
+            (0..n)
+                .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+                .map(|(i, j)| distance(i, j))
+                .collect()
+        } else {
+            let mut rng = thread_rng();
+            (0..self.max_pairs)
+                .map(|_| {
+                    let i = rng.gen_range(0..n);
+                    let j = (i + rng.gen_range(1..n)) % n;
+                    distance(i, j)
+                })
+                .collect()
+        };
+        if distances.is_empty() {
+            return DiversitySummary::default();
+        }
+
+        DiversitySummary {
+            mean: distances.iter().sum::<f64>() / distances.len() as f64,
+            min: distances.iter().copied().fold(f64::INFINITY, f64::min),
+        }
+    }
+}
+
+/// The statistics computed by [`Diversity`]
+///
+/// Both are 0 for a generation with fewer than two solutions.
+///
+/// [`Diversity`]: ./struct.Diversity.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiversitySummary {
+    mean: f64,
+    min: f64,
+}
+
+#[cfg(feature = "std")]
+impl DiversitySummary {
+    /// Get the mean distance between two solutions.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Get the smallest distance between two solutions.
+    pub fn min(&self) -> f64 {
+        self.min
+    }
+}
+
+/// How many offspring each variation operator made in a generation,
+/// and how many of them improved on their parents
+///
+/// This is computed for [`Tracked`] solutions; see the [`credit`] module.
+/// Only the solutions in the generation being analyzed count, so in algorithms that select
+/// after evaluating, offspring that weren't selected are left out. Solutions that have been
+/// counted before, because they survived or were copied from an earlier generation,
+/// count as [`Variation::Cloned`].
+///
+/// [`Tracked`]: ../credit/struct.Tracked.html
+/// [`credit`]: ../credit/index.html
+/// [`Variation::Cloned`]: ../credit/enum.Variation.html#variant.Cloned
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OperatorCredit {
+    variations: [Credit; 5],
+    operators: Vec<(String, Credit)>,
+}
+
+#[cfg(feature = "std")]
+impl OperatorCredit {
+    /// Get the credit of a kind of variation.
+    pub fn variation(&self, variation: Variation) -> Credit {
+        self.variations[variation as usize]
+    }
+
+    /// Get the credit of the operator with a [noted] name, which is zero if it wasn't used.
+    ///
+    /// [noted]: ../credit/fn.note_operator.html
+    pub fn operator(&self, name: &str) -> Credit {
+        self.operators
+            .iter()
+            .find(|(operator, _)| operator == name)
+            .map(|&(_, credit)| credit)
+            .unwrap_or_default()
+    }
+
+    /// Get the credit of every operator with a [noted] name that was used, in order of first use.
+    ///
+    /// [noted]: ../credit/fn.note_operator.html
+    pub fn operators(&self) -> &[(String, Credit)] {
+        &self.operators
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> GenerationStats<Tracked<T>> for OperatorCredit
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+{
+    type Output = Self;
+
+    fn analyze(&mut self, generation: &[Cached<Tracked<T>>]) -> Self {
+        let mut stat = OperatorCredit {
+            variations: [Credit::default(); 5],
+            operators: Vec::new(),
+        };
+        for ind in generation {
+            ind.evaluate();
+            let tracked = ind.as_ref();
+            let variation = if tracked.count() {
+                Variation::Cloned
+            } else {
+                tracked.variation()
+            };
+            let improved = variation != Variation::Cloned && tracked.improved() == Some(true);
+            let add = |credit: &mut Credit| {
+                credit.applied += 1;
+                credit.improved += improved as usize;
+            };
+
+            add(&mut stat.variations[variation as usize]);
+            if let (Some(name), false) = (tracked.operator(), variation == Variation::Cloned) {
+                match stat
+                    .operators
+                    .iter_mut()
+                    .find(|(operator, _)| operator == name)
+                {
+                    Some((_, credit)) => add(credit),
+                    None => {
+                        let mut credit = Credit::default();
+                        add(&mut credit);
+                        stat.operators.push((name.to_owned(), credit));
+                    }
+                }
+            }
+        }
+        stat
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{
+        benchmarks::binary::{BitBenchmark, OneMax},
+        genome::BitString,
+        testutils::{Foo, Walk},
+    };
+
+    #[test]
+    fn generational_distance() {
+        let reference: Vec<MultiObjective<2>> = [[0.0, 2.0], [1.0, 1.0], [2.0, 0.0]]
+            .map(MultiObjective::new_unweighted)
+            .to_vec();
+        // the last solution is dominated, so it doesn't count
+        let pop = [[0.0, 2.0], [1.0, 0.0], [0.0, 0.0]].map(|x| Cached::new(Foo(x)));
+
+        let igd = InvertedGenerationalDistance::new(reference.clone()).analyze(&pop);
+        assert!((igd - 2.0 / 3.0).abs() < 1e-12);
+        let gd = GenerationalDistance::new(reference).analyze(&pop);
+        assert!((gd - 0.5).abs() < 1e-12);
+        assert!(GenerationalDistance::default().analyze(&pop).is_infinite());
+    }
+
+    #[test]
+    fn spacing_and_spread() {
+        let even = [[0.0, 3.0], [1.0, 2.0], [2.0, 1.0], [3.0, 0.0]].map(|x| Cached::new(Foo(x)));
+        let uneven = [[0.0, 3.0], [0.5, 2.5], [2.0, 1.0], [3.0, 0.0]].map(|x| Cached::new(Foo(x)));
+
+        assert_eq!(FrontSpacing.analyze(&even), 0.0);
+        assert!(FrontSpacing.analyze(&uneven) > 0.0);
+        assert_eq!(FrontSpacing.analyze(&even[..1]), 0.0);
+
+        let reference = [[-1.0, 4.0], [4.0, -1.0]].map(MultiObjective::new_unweighted);
+        assert_eq!(FrontSpread::default().analyze(&even), 0.0);
+        assert!(FrontSpread::default().analyze(&uneven) > 0.0);
+        let spread = FrontSpread::with_reference(&reference).analyze(&even);
+        assert!(spread > 0.0 && spread < 1.0);
+        assert_eq!(FrontSpread::default().analyze(&even[..1]), 1.0);
+    }
+
+    #[test]
+    fn diversity() {
+        let pop: Vec<Cached<BitBenchmark<OneMax, 4>>> = [
+            [true, true, false, false],
+            [true, false, false, false],
+            [false, false, true, true],
+        ]
+        .iter()
+        .map(|bits| Cached::new(BitBenchmark::new(BitString::new(bits.to_vec())).unwrap()))
+        .collect();
+
+        let mut all = Diversity::new(Hamming);
+        let summary = all.analyze(&pop);
+        assert_eq!(summary.mean(), (1.0 + 4.0 + 3.0) / 3.0);
+        assert_eq!(summary.min(), 1.0);
+
+        let mut sampled = Diversity::<_, Hamming>::default().with_max_pairs(50);
+        let summary = sampled.analyze(&pop);
+        assert!(summary.min() >= 1.0 && summary.mean() <= 4.0);
+        assert_eq!(all.analyze(&pop[..1]).mean(), 0.0);
+    }
+
+    #[test]
+    fn quantiles() {
+        let pop = [4.0, 1.0, 3.0, 2.0, 10.0].map(|x| Cached::new(Walk(x)));
+        let quantiles = FitnessQuantiles::new(&[0.1, 0.75]).analyze(&pop);
+        // fitness is -|x|
+        assert_eq!(quantiles.min(), -10.0);
+        assert_eq!(quantiles.max(), -1.0);
+        assert_eq!(quantiles.median(), -3.0);
+        assert!((quantiles.quantile(0.1).unwrap() - -7.6).abs() < 1e-12);
+        assert_eq!(quantiles.quantile(0.75), Some(-2.0));
+        assert_eq!(quantiles.quantile(0.5), None);
+        assert!(FitnessQuantiles::default()
+            .analyze(&pop[..0])
+            .median()
+            .is_nan());
+    }
+
+    #[test]
+    fn downsample() {
+        let mut sink = Downsample::new(4);
+        for gen in 0..9 {
+            sink.push(gen, gen);
+        }
+        assert_eq!(StatsSink::<usize>::interval(&sink), 4);
+        assert_eq!(sink.finish(), [0, 4, 8]);
+
+        let mut sink = Downsample::new(3);
+        for gen in 0..6 {
+            sink.push(gen, gen);
+        }
+        assert_eq!(StatsSink::<usize>::interval(&sink), 2);
+        assert_eq!(sink.finish(), [0, 2, 4]);
+    }
+
+    #[test]
+    fn eval_counters() {
+        use crate::{alg::Simple, hof::BestN, select::Tournament, Evolution};
+
+        let evo = |mutpb| -> Evolution<Walk, _, _, EvalCounters> {
+            Evolution::new(
+                Simple::new(10, 0.0, mutpb, Tournament::new(2)),
+                BestN::new(1),
+            )
+        };
+        // every member of every generation is a new mutant
+        let log = evo(1.0).run_for(3);
+        for counts in &log.stats {
+            assert_eq!(counts.evaluated(), 10);
+            assert_eq!(counts.cached(), 0);
+            assert_eq!(counts.hit_rate(), 0.0);
+        }
+        // without variation, only the first generation is evaluated
+        let log = evo(0.0).run_for(3);
+        let evaluated: Vec<u64> = log.stats.iter().map(EvalCounts::evaluated).collect();
+        assert_eq!(evaluated, [10, 0, 0]);
+        assert_eq!(log.stats[2].cached(), 10);
+        assert_eq!(log.stats[2].hit_rate(), 1.0);
+
+        let pop: [Cached<Walk>; 0] = [];
+        assert_eq!(EvalCounters::default().analyze(&pop).hit_rate(), 0.0);
+    }
+}
@@ -0,0 +1,342 @@
+//! Reusable criteria for ending a run
+//!
+//! Each criterion implements [`Termination`], can be combined with others using
+//! [`.and()`] and [`.or()`], and is turned into a predicate for [`Evolution::run_until()`]
+//! with [`.predicate()`]:
+//!
+#![cfg_attr(feature = "std", doc = "```")]
+#![cfg_attr(not(feature = "std"), doc = "```ignore")]
+//! use std::time::Duration;
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::{Benchmark, Sphere};
+//! use eviolite::termination::{MaxDuration, MaxEvaluations, Stagnation, TargetFitness, Termination};
+//!
+//! let evo: Evolution<Benchmark<Sphere, 3>, _, _, ()> = Evolution::new(
+//!     alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+//!     hof::BestN::new(1),
+//! );
+//! let log = evo.run_until(
+//!     TargetFitness(-1e-6)
+//!         .or(Stagnation::new(20, 1e-9))
+//!         .or(MaxEvaluations::new(50_000))
+//!         .or(MaxDuration::new(Duration::from_secs(10)))
+//!         .predicate(),
+//! );
+//! ```
+//!
+//! Criteria look at the fitness of the population in the direction of the first objective
+//! given by [`Solution::DIRECTIONS`], so they need solutions whose fitness can be converted into an `f64`.
+//!
+//! [`Termination`]: ./trait.Termination.html
+//! [`.and()`]: ./trait.Termination.html#method.and
+//! [`.or()`]: ./trait.Termination.html#method.or
+//! [`.predicate()`]: ./trait.Termination.html#method.predicate
+//! [`Evolution::run_until()`]: ../struct.Evolution.html#method.run_until
+//! [`Solution::DIRECTIONS`]: ../trait.Solution.html#associatedconstant.DIRECTIONS
+
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use crate::{fitness::Direction, hof::HallOfFame, stats::GenerationStats, Generation, Solution};
+
+/// A condition for ending a run, checked once per generation
+///
+/// See the [module-level documentation](./index.html) for an example.
+pub trait Termination {
+    /// Check whether the run should end after `generation`.
+    ///
+    /// This is called exactly once for every generation, in order, so criteria can keep track of
+    /// how the run has been going.
+    fn is_met<T, Hof, Stat>(&mut self, generation: &Generation<T, Hof, Stat>) -> bool
+    where
+        T: Solution,
+        T::Fitness: Into<f64>,
+        Hof: HallOfFame<T>,
+        Stat: GenerationStats<T>;
+
+    /// Combine with `other` into a criterion that's met when both are.
+    ///
+    /// Both criteria are checked every generation, so neither of them misses a generation.
+    fn and<B: Termination>(self, other: B) -> And<Self, B>
+    where
+        Self: Sized,
+    {
+        And(self, other)
+    }
+
+    /// Combine with `other` into a criterion that's met when either is.
+    ///
+    /// Both criteria are checked every generation, so neither of them misses a generation.
+    fn or<B: Termination>(self, other: B) -> Or<Self, B>
+    where
+        Self: Sized,
+    {
+        Or(self, other)
+    }
+
+    /// Turn the criterion into a predicate to pass to [`Evolution::run_until()`]
+    /// or [`Evolution::run_until_with()`].
+    ///
+    /// [`Evolution::run_until()`]: ../struct.Evolution.html#method.run_until
+    /// [`Evolution::run_until_with()`]: ../struct.Evolution.html#method.run_until_with
+    fn predicate<T, Hof, Stat>(mut self) -> impl FnMut(Generation<T, Hof, Stat>) -> bool
+    where
+        Self: Sized,
+        T: Solution,
+        T::Fitness: Into<f64>,
+        Hof: HallOfFame<T>,
+        Stat: GenerationStats<T>,
+    {
+        move |generation| self.is_met(&generation)
+    }
+}
+
+// The best fitness in the generation's population
+fn best_fitness<T, Hof, Stat>(generation: &Generation<T, Hof, Stat>) -> f64
+where
+    T: Solution,
+    T::Fitness: Into<f64>,
+    Hof: HallOfFame<T>,
+    Stat: GenerationStats<T>,
+{
+    let direction = Direction::of::<T>(0);
+    generation
+        .pop
+        .iter()
+        .map(|ind| ind.evaluate().into())
+        .fold(direction.worst(), |a, b| direction.best(a, b))
+}
+
+/// Met when any solution in the population reaches a fitness at least as good as the target
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TargetFitness(pub f64);
+
+impl Termination for TargetFitness {
+    fn is_met<T, Hof, Stat>(&mut self, generation: &Generation<T, Hof, Stat>) -> bool
+    where
+        T: Solution,
+        T::Fitness: Into<f64>,
+        Hof: HallOfFame<T>,
+        Stat: GenerationStats<T>,
+    {
+        let best = best_fitness(generation);
+        best == self.0 || Direction::of::<T>(0).is_better(best, self.0)
+    }
+}
+
+/// Met when the best fitness in the population hasn't improved by more than `epsilon`
+/// for `gens` generations in a row
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stagnation {
+    gens: usize,
+    epsilon: f64,
+    best: Option<f64>,
+    stagnant: usize,
+}
+
+impl Stagnation {
+    /// End the run after `gens` generations without an improvement of more than `epsilon`.
+    pub fn new(gens: usize, epsilon: f64) -> Self {
+        Stagnation {
+            gens,
+            epsilon,
+            best: None,
+            stagnant: 0,
+        }
+    }
+}
+
+impl Termination for Stagnation {
+    fn is_met<T, Hof, Stat>(&mut self, generation: &Generation<T, Hof, Stat>) -> bool
+    where
+        T: Solution,
+        T::Fitness: Into<f64>,
+        Hof: HallOfFame<T>,
+        Stat: GenerationStats<T>,
+    {
+        let current = best_fitness(generation);
+        let improved = match self.best {
+            Some(best) => match Direction::of::<T>(0) {
+                Direction::Maximize => current > best + self.epsilon,
+                Direction::Minimize => current < best - self.epsilon,
+            },
+            None => true,
+        };
+        if improved {
+            self.best = Some(current);
+            self.stagnant = 0;
+        } else {
+            self.stagnant += 1;
+        }
+        self.stagnant >= self.gens
+    }
+}
+
+/// Met when at least `n` fitness evaluations have been computed since the run started
+///
+/// Evaluations are counted as described in [`Generation::evaluations`], so this ends a run
+/// after the same generation as [`Evolution::run_for_evaluations()`] does.
+///
+/// [`Generation::evaluations`]: ../struct.Generation.html#structfield.evaluations
+/// [`Evolution::run_for_evaluations()`]: ../struct.Evolution.html#method.run_for_evaluations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxEvaluations {
+    n: u64,
+}
+
+impl MaxEvaluations {
+    /// End the run once `n` evaluations have been computed.
+    pub fn new(n: u64) -> Self {
+        MaxEvaluations { n }
+    }
+}
+
+impl Termination for MaxEvaluations {
+    fn is_met<T, Hof, Stat>(&mut self, generation: &Generation<T, Hof, Stat>) -> bool
+    where
+        T: Solution,
+        T::Fitness: Into<f64>,
+        Hof: HallOfFame<T>,
+        Stat: GenerationStats<T>,
+    {
+        generation.evaluations >= self.n
+    }
+}
+
+/// Met when at least the given duration has passed since the criterion was created
+///
+/// The criterion should be created right before the run it's for.
+/// The run only ends at the end of a generation, so it can take longer by up to one generation.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxDuration {
+    duration: Duration,
+    start: Instant,
+}
+
+#[cfg(feature = "std")]
+impl MaxDuration {
+    /// End the run once `duration` has passed.
+    pub fn new(duration: Duration) -> Self {
+        MaxDuration {
+            duration,
+            start: Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Termination for MaxDuration {
+    fn is_met<T, Hof, Stat>(&mut self, _: &Generation<T, Hof, Stat>) -> bool
+    where
+        T: Solution,
+        T::Fitness: Into<f64>,
+        Hof: HallOfFame<T>,
+        Stat: GenerationStats<T>,
+    {
+        self.start.elapsed() >= self.duration
+    }
+}
+
+/// Met when both criteria are, created with [`.and()`](./trait.Termination.html#method.and)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct And<A, B>(A, B);
+
+impl<A: Termination, B: Termination> Termination for And<A, B> {
+    fn is_met<T, Hof, Stat>(&mut self, generation: &Generation<T, Hof, Stat>) -> bool
+    where
+        T: Solution,
+        T::Fitness: Into<f64>,
+        Hof: HallOfFame<T>,
+        Stat: GenerationStats<T>,
+    {
+        let a = self.0.is_met(generation);
+        let b = self.1.is_met(generation);
+        a && b
+    }
+}
+
+/// Met when either criterion is, created with [`.or()`](./trait.Termination.html#method.or)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Or<A, B>(A, B);
+
+impl<A: Termination, B: Termination> Termination for Or<A, B> {
+    fn is_met<T, Hof, Stat>(&mut self, generation: &Generation<T, Hof, Stat>) -> bool
+    where
+        T: Solution,
+        T::Fitness: Into<f64>,
+        Hof: HallOfFame<T>,
+        Stat: GenerationStats<T>,
+    {
+        let a = self.0.is_met(generation);
+        let b = self.1.is_met(generation);
+        a || b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hof::BestN, testutils::Walk, Cached};
+
+    // Check `criterion` against a population of walks at `xs`, whose fitness is -|x|
+    fn check(criterion: &mut impl Termination, xs: &[f64]) -> bool {
+        check_after(criterion, xs, 0)
+    }
+
+    // The same, after `evaluations` evaluations
+    fn check_after(criterion: &mut impl Termination, xs: &[f64], evaluations: u64) -> bool {
+        let pop: Vec<Cached<Walk>> = xs.iter().map(|&x| Cached::new(Walk(x))).collect();
+        criterion.is_met(&Generation::<_, _, ()> {
+            gen: 0,
+            pop: &pop,
+            hall_of_fame: &BestN::<Walk>::new(1),
+            stats: &(),
+            evaluations,
+        })
+    }
+
+    #[test]
+    fn criteria() {
+        let mut target = TargetFitness(-0.5);
+        assert!(!check(&mut target, &[1.0, -0.8]));
+        assert!(check(&mut target, &[1.0, 0.5]));
+
+        let mut stagnation = Stagnation::new(2, 0.1);
+        assert!(!check(&mut stagnation, &[1.0]));
+        assert!(!check(&mut stagnation, &[0.95]));
+        assert!(!check(&mut stagnation, &[0.5]));
+        assert!(!check(&mut stagnation, &[0.45]));
+        assert!(check(&mut stagnation, &[0.41]));
+
+        let mut both = TargetFitness(-0.5).and(Stagnation::new(1, 0.0));
+        assert!(!check(&mut both, &[0.1]));
+        assert!(check(&mut both, &[0.1]));
+        let mut either = TargetFitness(-0.5).or(MaxEvaluations::new(u64::MAX));
+        assert!(check(&mut either, &[0.1]));
+        assert!(!check(&mut either, &[1.0]));
+
+        // the budget counts the run's own evaluations, not those made before it started
+        let mut budget = MaxEvaluations::new(100);
+        assert!(!check_after(&mut budget, &[1.0], 99));
+        assert!(check_after(&mut budget, &[1.0], 100));
+    }
+
+    #[test]
+    fn same_budget_as_run_for_evaluations() {
+        use crate::{alg::Simple, select::Tournament, Evolution};
+
+        // every offspring is mutated, so each generation evaluates all 10 of its members
+        let evo = || -> Evolution<Walk, _, _, ()> {
+            Evolution::new(Simple::new(10, 0.0, 1.0, Tournament::new(2)), BestN::new(1))
+        };
+        // an earlier run's evaluations don't count towards the budget
+        assert_eq!(evo().run_for(3).evaluations, 30);
+        let until = evo().run_until(MaxEvaluations::new(35).predicate());
+        let budget = evo().run_for_evaluations(35);
+        assert_eq!(until.evaluations, 40);
+        assert_eq!(budget.evaluations, 40);
+        assert_eq!(until.elapsed.len(), budget.elapsed.len());
+    }
+}
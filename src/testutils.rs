@@ -1,12 +1,13 @@
 use rand::Rng;
 
 use crate::{
-    fitness::MultiObjective,
+    fitness::{Direction, MultiObjective},
     repro_rng::{random, thread_rng},
     Solution,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct One(pub f64);
 
 impl Solution for One {
@@ -29,6 +30,7 @@ impl Solution for One {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Foo(pub [f64; 2]);
 
 impl Solution for Foo {
@@ -73,3 +75,53 @@ impl Solution for Bar {
         unreachable!()
     }
 }
+
+// A solution whose crossover and mutation actually use randomness
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Walk(pub f64);
+
+impl Solution for Walk {
+    type Fitness = f64;
+
+    fn generate() -> Self {
+        Walk(random())
+    }
+
+    fn evaluate(&self) -> f64 {
+        -self.0.abs()
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        let t: f64 = random();
+        (a.0, b.0) = (t * a.0 + (1.0 - t) * b.0, t * b.0 + (1.0 - t) * a.0);
+    }
+
+    fn mutate(&mut self) {
+        self.0 += thread_rng().gen_range(-0.5..0.5);
+    }
+}
+
+// Any of the solutions above, with every objective minimized instead
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Minimized<T>(pub T);
+
+impl<T: Solution> Solution for Minimized<T> {
+    type Fitness = T::Fitness;
+    const DIRECTIONS: &'static [Direction] = &[Direction::Minimize; 3];
+
+    fn generate() -> Self {
+        Minimized(T::generate())
+    }
+
+    fn evaluate(&self) -> Self::Fitness {
+        self.0.evaluate()
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        T::crossover(&mut a.0, &mut b.0);
+    }
+    fn mutate(&mut self) {
+        self.0.mutate();
+    }
+}
@@ -1,43 +1,27 @@
-mod cached;
-pub use cached::Cached;
-
-pub trait IterIndices {
-    type Item;
-    fn iter_indices<I>(&self, indices: I) -> IndicesIter<Self::Item, I>
-    where
-        I: Iterator<Item = usize>;
-}
+use alloc::vec::Vec;
 
-impl<T> IterIndices for Vec<T> {
-    type Item = T;
-    fn iter_indices<I>(&self, indices: I) -> IndicesIter<Self::Item, I>
-    where
-        I: Iterator<Item = usize>,
-    {
-        IndicesIter {
-            inner: self,
-            indices,
-        }
-    }
-}
-
-pub struct IndicesIter<'a, T, I>
-where
-    I: Iterator<Item = usize>,
-{
-    inner: &'a [T],
-    indices: I,
-}
+mod cached;
+pub(crate) mod counter;
+#[cfg(feature = "std")]
+mod decoded;
+#[cfg(any(feature = "checkpoint", feature = "distributed"))]
+pub(crate) mod encoding;
+#[cfg(feature = "std")]
+mod fn_solution;
+#[cfg(feature = "std")]
+mod memoized;
+#[cfg(feature = "serde")]
+pub(crate) mod serde_array;
+#[cfg(feature = "parallel")]
+pub(crate) mod thread_pool;
 
-impl<'a, T, I> Iterator for IndicesIter<'a, T, I>
-where
-    I: Iterator<Item = usize>,
-{
-    type Item = &'a T;
-    fn next(&mut self) -> Option<Self::Item> {
-        self.indices.next().map(|idx| &self.inner[idx])
-    }
-}
+pub use cached::{Cached, CachedMut};
+#[cfg(feature = "std")]
+pub use decoded::{Decode, Decoded};
+#[cfg(feature = "std")]
+pub use fn_solution::{FnConfig, FnSolution};
+#[cfg(feature = "std")]
+pub use memoized::{Memo, Memoized};
 
 pub trait NFromFunction<T> {
     fn n_from_function(n: usize, f: impl Fn() -> T) -> Self;
@@ -1,6 +1,26 @@
-use std::{cell::UnsafeCell, fmt::Debug};
+#[cfg(feature = "parallel")]
+use core::sync::atomic::AtomicU32;
+#[cfg(any(feature = "parallel", not(feature = "std"), test))]
+use core::sync::atomic::Ordering;
+use core::{
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+};
 
-use crate::{fitness::MultiObjective, Solution};
+use super::counter;
+use crate::{
+    fitness::{Direction, MultiObjective},
+    Solution,
+};
+
+#[cfg(feature = "std")]
+type FitnessCell<F> = std::sync::OnceLock<F>;
+#[cfg(not(feature = "std"))]
+type FitnessCell<F> = SpinOnce<F>;
+
+// Marks a solution that has never been evaluated, since that's a NaN `as_secs_f32` never returns
+#[cfg(feature = "parallel")]
+const NO_DURATION: u32 = u32::MAX;
 
 /// A wrapper around a solution that automatically caches the fitness value
 ///
@@ -8,9 +28,17 @@ use crate::{fitness::MultiObjective, Solution};
 /// part of an evolutionary algorithm. This wrapper type makes it so that that computation
 /// will only ever happen once for every distinct individual. It implements [`Solution`] itself,
 /// so you can use the exact same interface you would if it weren't there.
+///
+/// A solution is only ever evaluated once, even if several threads try to evaluate it at the same time;
+/// the others wait for the first one to finish and use its result.
+/// `Cached<T>` is `Send` and `Sync` whenever `T` is.
 pub struct Cached<T: Solution> {
     inner: T,
-    fitness: UnsafeCell<Option<T::Fitness>>,
+    fitness: FitnessCell<T::Fitness>,
+    // How long the last evaluation took in seconds, as the bits of an `f32`, which survives changes
+    // to the solution, so offspring start out with their parent's timing as an estimate of their own.
+    #[cfg(feature = "parallel")]
+    duration: AtomicU32,
 }
 
 impl<T> Solution for Cached<T>
@@ -18,34 +46,58 @@ where
     T: Solution,
 {
     type Fitness = T::Fitness;
+    const DIRECTIONS: &'static [Direction] = T::DIRECTIONS;
+    const DOMINANCE_TOLERANCE: f64 = T::DOMINANCE_TOLERANCE;
 
     fn generate() -> Self {
-        Cached {
-            inner: T::generate(),
-            fitness: UnsafeCell::new(None),
-        }
+        Cached::new(T::generate())
     }
 
     fn evaluate(&self) -> Self::Fitness {
-        if let Some(fitness) = unsafe { *self.fitness.get() } {
+        *self.fitness.get_or_init(|| {
+            #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+            let start = std::time::Instant::now();
+            let fitness = self.inner.evaluate();
+            #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+            let nanos = {
+                let elapsed = start.elapsed();
+                #[cfg(feature = "parallel")]
+                self.duration
+                    .store(elapsed.as_secs_f32().to_bits(), Ordering::Relaxed);
+                elapsed.as_nanos() as u64
+            };
+            #[cfg(not(all(feature = "std", not(target_arch = "wasm32"))))]
+            let nanos = 0;
+            counter::count(nanos);
             fitness
-        } else {
-            let new_fitness = self.inner.evaluate();
-            unsafe {
-                *self.fitness.get() = Some(new_fitness);
-            }
-            new_fitness
-        }
+        })
+    }
+
+    // Without an estimate from the solution itself, fall back on how long the last evaluation took,
+    // which is the parent's for offspring that haven't been evaluated yet.
+    fn evaluation_cost(&self) -> Option<f64> {
+        let estimate = self.inner.evaluation_cost();
+        #[cfg(feature = "parallel")]
+        let estimate = estimate.or_else(|| {
+            let duration = self.duration.load(Ordering::Relaxed);
+            (duration != NO_DURATION).then(|| f32::from_bits(duration) as f64)
+        });
+        estimate
     }
 
     fn crossover(a: &mut Self, b: &mut Self) {
         T::crossover(&mut a.inner, &mut b.inner);
-        a.clear_cache();
-        b.clear_cache();
+        a.repair();
+        b.repair();
     }
 
     fn mutate(&mut self) {
         self.inner.mutate();
+        self.repair();
+    }
+
+    fn repair(&mut self) {
+        self.inner.repair();
         self.clear_cache();
     }
 }
@@ -56,16 +108,92 @@ where
 {
     /// Create a new wrapper around an existing solution.
     pub fn new(individual: T) -> Self {
-        Cached {
-            inner: individual,
-            fitness: UnsafeCell::new(None),
-        }
+        Cached::with_fitness(individual, None)
     }
 
     /// Consumes the `Cached`, returning a tuple of the solution it contained
     /// and an [`Option`] of the fitness value that could have been cached.
-    pub fn into_inner(mut self) -> (T, Option<T::Fitness>) {
-        (self.inner, *self.fitness.get_mut())
+    pub fn into_inner(self) -> (T, Option<T::Fitness>) {
+        (self.inner, self.fitness.into_inner())
+    }
+
+    /// Get mutable access to the solution, e.g. to repair it in a callback.
+    ///
+    /// The cached fitness value is deleted when the returned guard is dropped,
+    /// so the changed solution will be evaluated again.
+    pub fn get_mut(&mut self) -> CachedMut<'_, T> {
+        CachedMut { cached: self }
+    }
+
+    /// Change the solution with `f`, deleting the cached fitness value afterward
+    /// so the changed solution will be evaluated again.
+    ///
+    /// Returns the value returned by `f`.
+    pub fn modify<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.get_mut())
+    }
+
+    /// Get the cached fitness value, if the solution has been evaluated,
+    /// without ever evaluating it.
+    ///
+    /// Unlike [`.evaluate()`], this is always cheap, so it's safe to use on the main thread
+    /// in callbacks, statistics, and halls of fame.
+    ///
+    /// [`.evaluate()`]: #method.evaluate
+    pub fn fitness(&self) -> Option<T::Fitness> {
+        self.fitness.get().copied()
+    }
+
+    /// Get the cached fitness value of a solution that is known to have been evaluated,
+    /// e.g. by [`par_evaluate()`], without ever evaluating it.
+    ///
+    /// Every solution in the population passed to the callbacks of [`Evolution`]'s `run_*_with` methods,
+    /// the statistics, and the hall of fame has been evaluated.
+    ///
+    /// # Panics
+    /// Panics if the solution hasn't been evaluated.
+    ///
+    /// [`par_evaluate()`]: ./fitness/fn.par_evaluate.html
+    /// [`Evolution`]: ./struct.Evolution.html
+    pub fn fitness_unchecked(&self) -> T::Fitness {
+        self.fitness()
+            .expect("fitness_unchecked called on a solution that hasn't been evaluated")
+    }
+
+    /// Cache a fitness value that was computed outside of [`.evaluate()`],
+    /// e.g. by a batch evaluator or on another machine.
+    ///
+    /// If the solution doesn't have a cached fitness value yet, `fitness` is cached
+    /// and counted by [`evaluation_count()`] like any other evaluation.
+    /// Otherwise, the cached value is kept and `fitness` is returned as an error;
+    /// use [`.replace_fitness()`] to overwrite it.
+    ///
+    /// This only takes a shared reference, so it can be used on a population while other threads
+    /// are evaluating it. If another thread is evaluating the same solution at the same time,
+    /// whichever value is cached first is kept.
+    ///
+    /// [`.evaluate()`]: #method.evaluate
+    /// [`evaluation_count()`]: ./fitness/fn.evaluation_count.html
+    /// [`.replace_fitness()`]: #method.replace_fitness
+    pub fn set_fitness(&self, fitness: T::Fitness) -> Result<(), T::Fitness> {
+        self.fitness.set(fitness)?;
+        counter::count(0);
+        Ok(())
+    }
+
+    /// Cache a fitness value that was computed outside of [`.evaluate()`],
+    /// overwriting any value that was already cached.
+    ///
+    /// Returns the fitness value that was cached before, if it existed.
+    /// Like [`.set_fitness()`], `fitness` is counted by [`evaluation_count()`].
+    ///
+    /// [`.evaluate()`]: #method.evaluate
+    /// [`.set_fitness()`]: #method.set_fitness
+    /// [`evaluation_count()`]: ./fitness/fn.evaluation_count.html
+    pub fn replace_fitness(&mut self, fitness: T::Fitness) -> Option<T::Fitness> {
+        let previous = self.fitness.take();
+        let _ = self.set_fitness(fitness);
+        previous
     }
 
     /// Delete any cached fitness value.
@@ -76,7 +204,24 @@ where
     /// Using it incorrectly can cause evaluations to be repeated
     /// unnecessarily, leading to heavy slowdowns.
     pub fn clear_cache(&mut self) -> Option<T::Fitness> {
-        std::mem::replace(self.fitness.get_mut(), None)
+        self.fitness.take()
+    }
+
+    // Cache the fitness value of an identical solution, without counting it as an evaluation
+    #[cfg(feature = "std")]
+    pub(crate) fn share_fitness(&self, other: &Self) {
+        if let Some(fitness) = other.fitness() {
+            let _ = self.fitness.set(fitness);
+        }
+    }
+
+    pub(crate) fn with_fitness(inner: T, fitness: Option<T::Fitness>) -> Self {
+        Cached {
+            inner,
+            fitness: fitness.map_or_else(FitnessCell::new, FitnessCell::from),
+            #[cfg(feature = "parallel")]
+            duration: AtomicU32::new(NO_DURATION),
+        }
     }
 }
 
@@ -87,11 +232,52 @@ where
     fn clone(&self) -> Self {
         Cached {
             inner: self.inner.clone(),
-            fitness: UnsafeCell::new(unsafe { *self.fitness.get() }),
+            fitness: self.fitness.clone(),
+            #[cfg(feature = "parallel")]
+            duration: AtomicU32::new(self.duration.load(Ordering::Relaxed)),
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        self.inner.clone_from(&source.inner);
+        self.fitness.clone_from(&source.fitness);
+        #[cfg(feature = "parallel")]
+        {
+            *self.duration.get_mut() = source.duration.load(Ordering::Relaxed);
         }
     }
 }
 
+/// Mutable access to the solution inside a [`Cached`], which deletes its cached fitness value when dropped
+///
+/// This is returned by [`Cached::get_mut()`].
+///
+/// [`Cached`]: ./struct.Cached.html
+/// [`Cached::get_mut()`]: ./struct.Cached.html#method.get_mut
+pub struct CachedMut<'a, T: Solution> {
+    cached: &'a mut Cached<T>,
+}
+
+impl<T: Solution> Deref for CachedMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.cached.inner
+    }
+}
+
+impl<T: Solution> DerefMut for CachedMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.cached.inner
+    }
+}
+
+impl<T: Solution> Drop for CachedMut<'_, T> {
+    fn drop(&mut self) {
+        self.cached.clear_cache();
+    }
+}
+
 impl<T> AsRef<T> for Cached<T>
 where
     T: Solution,
@@ -123,7 +309,7 @@ impl<T> PartialOrd for Cached<T>
 where
     T: Solution + PartialOrd,
 {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         self.inner.partial_cmp(&other.inner)
     }
 }
@@ -132,7 +318,7 @@ impl<T> PartialOrd<T> for Cached<T>
 where
     T: Solution + PartialOrd,
 {
-    fn partial_cmp(&self, other: &T) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &T) -> Option<core::cmp::Ordering> {
         self.inner.partial_cmp(other)
     }
 }
@@ -142,21 +328,376 @@ where
     T: Solution + Debug,
     T::Fitness: Debug,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Cached")
             .field("solution", &self.inner)
-            .field("stored_fitness", unsafe { &*self.fitness.get() })
+            .field("stored_fitness", &self.fitness.get())
             .finish()
     }
 }
 
-unsafe impl<T: Solution> Sync for Cached<T> {}
+// Without `std` there's no `OnceLock`, but interrupt handlers or other cores can still share solutions,
+// so the fitness goes in a cell that's initialized once behind an atomic state, like `spin::Once`.
+// Anything that finds it being initialized spins until it's done, so fitness values never have to be dropped
+// or moved out while they might be read, which is why this only holds `Copy` values.
+#[cfg(any(not(feature = "std"), test))]
+pub(crate) struct SpinOnce<F: Copy> {
+    state: core::sync::atomic::AtomicU8,
+    value: core::cell::UnsafeCell<core::mem::MaybeUninit<F>>,
+}
+
+#[cfg(any(not(feature = "std"), test))]
+const EMPTY: u8 = 0;
+#[cfg(any(not(feature = "std"), test))]
+const RUNNING: u8 = 1;
+#[cfg(any(not(feature = "std"), test))]
+const READY: u8 = 2;
+
+// SAFETY: the value is only written by whoever moves the state from `EMPTY` to `RUNNING`,
+// and only read after the state is `READY`, with acquire and release orderings between the two,
+// exactly as `OnceLock` does.
+#[cfg(any(not(feature = "std"), test))]
+unsafe impl<F: Copy + Send + Sync> Sync for SpinOnce<F> {}
+
+#[cfg(any(not(feature = "std"), test))]
+impl<F: Copy> SpinOnce<F> {
+    pub(crate) const fn new() -> Self {
+        SpinOnce {
+            state: core::sync::atomic::AtomicU8::new(EMPTY),
+            value: core::cell::UnsafeCell::new(core::mem::MaybeUninit::uninit()),
+        }
+    }
+
+    pub(crate) fn get(&self) -> Option<&F> {
+        match self.state.load(Ordering::Acquire) {
+            // SAFETY: the value was written before the state became `READY`, and is never written again
+            READY => Some(unsafe { (*self.value.get()).assume_init_ref() }),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn get_or_init(&self, f: impl FnOnce() -> F) -> &F {
+        loop {
+            if let Some(value) = self.get() {
+                return value;
+            }
+            match self
+                .state
+                .compare_exchange(EMPTY, RUNNING, Ordering::Acquire, Ordering::Acquire)
+            {
+                Ok(_) => {
+                    // If `f` panics, the cell is emptied again so that the next access retries,
+                    // like `OnceLock` does, instead of everyone waiting for it forever
+                    let reset = Reset(&self.state);
+                    let value = f();
+                    core::mem::forget(reset);
+                    // SAFETY: winning the exchange gives exclusive access until the state is `READY`
+                    unsafe { (*self.value.get()).write(value) };
+                    self.state.store(READY, Ordering::Release);
+                    return self.get().unwrap();
+                }
+                Err(_) => {
+                    while self.state.load(Ordering::Acquire) == RUNNING {
+                        core::hint::spin_loop();
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn set(&self, value: F) -> Result<(), F> {
+        match self
+            .state
+            .compare_exchange(EMPTY, RUNNING, Ordering::Acquire, Ordering::Relaxed)
+        {
+            Ok(_) => {
+                // SAFETY: as in `get_or_init`
+                unsafe { (*self.value.get()).write(value) };
+                self.state.store(READY, Ordering::Release);
+                Ok(())
+            }
+            Err(_) => Err(value),
+        }
+    }
+
+    pub(crate) fn take(&mut self) -> Option<F> {
+        let value = self.get().copied();
+        *self.state.get_mut() = EMPTY;
+        value
+    }
+
+    pub(crate) fn into_inner(self) -> Option<F> {
+        self.get().copied()
+    }
+}
+
+// Empties a `SpinOnce` whose initializer panicked
+#[cfg(any(not(feature = "std"), test))]
+struct Reset<'a>(&'a core::sync::atomic::AtomicU8);
+
+#[cfg(any(not(feature = "std"), test))]
+impl Drop for Reset<'_> {
+    fn drop(&mut self) {
+        self.0.store(EMPTY, Ordering::Release);
+    }
+}
+
+#[cfg(any(not(feature = "std"), test))]
+impl<F: Copy> From<F> for SpinOnce<F> {
+    fn from(value: F) -> Self {
+        SpinOnce {
+            state: core::sync::atomic::AtomicU8::new(READY),
+            value: core::cell::UnsafeCell::new(core::mem::MaybeUninit::new(value)),
+        }
+    }
+}
+
+#[cfg(any(not(feature = "std"), test))]
+impl<F: Copy> Clone for SpinOnce<F> {
+    fn clone(&self) -> Self {
+        self.get().map_or_else(SpinOnce::new, |&value| value.into())
+    }
+}
 
 impl<T, const M: usize> Cached<T>
 where
     T: Solution<Fitness = MultiObjective<M>>,
 {
     pub(crate) fn fit(this: &Self, m: usize) -> f64 {
-        unsafe { &*this.fitness.get() }.as_ref().unwrap()[m]
+        this.fitness_unchecked()[m]
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<T> serde::Serialize for Cached<T>
+where
+    T: Solution + serde::Serialize,
+    T::Fitness: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Cached", 2)?;
+        state.serialize_field("solution", &self.inner)?;
+        state.serialize_field("fitness", &self.fitness.get())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de, T> serde::Deserialize<'de> for Cached<T>
+where
+    T: Solution + serde::Deserialize<'de>,
+    T::Fitness: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Cached")]
+        struct Repr<T, F> {
+            solution: T,
+            fitness: Option<F>,
+        }
+
+        let repr = Repr::<T, T::Fitness>::deserialize(deserializer)?;
+        Ok(Cached::with_fitness(repr.solution, repr.fitness))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::{sync::atomic::AtomicUsize, thread, time::Duration};
+
+    use super::*;
+    use crate::testutils::Walk;
+
+    static SLOW_EVALUATIONS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone)]
+    struct Slow;
+
+    impl Solution for Slow {
+        type Fitness = f64;
+
+        fn generate() -> Self {
+            Slow
+        }
+
+        fn evaluate(&self) -> f64 {
+            SLOW_EVALUATIONS.fetch_add(1, Ordering::Relaxed);
+            thread::sleep(Duration::from_millis(20));
+            1.0
+        }
+
+        fn crossover(_: &mut Self, _: &mut Self) {}
+
+        fn mutate(&mut self) {}
+    }
+
+    #[test]
+    fn send_and_sync() {
+        fn assert_send_sync<S: Send + Sync>(_: S) {}
+        assert_send_sync(Cached::new(Walk(0.0)));
+        assert_send_sync(vec![Cached::new(Walk(0.0))]);
+    }
+
+    #[test]
+    fn fitness_without_evaluating() {
+        let ind = Cached::new(Walk(-2.0));
+        assert_eq!(ind.fitness(), None);
+        assert_eq!(ind.evaluate(), -2.0);
+        assert_eq!(ind.fitness(), Some(-2.0));
+        assert_eq!(ind.fitness_unchecked(), -2.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn fitness_unchecked_before_evaluating() {
+        Cached::new(Walk(1.0)).fitness_unchecked();
+    }
+
+    #[test]
+    fn changes_invalidate_cache() {
+        let mut ind = Cached::new(Walk(3.0));
+        ind.evaluate();
+        ind.get_mut().0 = 1.0;
+        assert_eq!(ind.fitness(), None);
+        assert_eq!(ind.evaluate(), -1.0);
+
+        let clamped = ind.modify(|walk| {
+            walk.0 = walk.0.clamp(-0.5, 0.5);
+            walk.0
+        });
+        assert_eq!(clamped, 0.5);
+        assert_eq!(ind.evaluate(), -0.5);
+    }
+
+    #[test]
+    fn external_fitness() {
+        let mut ind = Cached::new(Walk(3.0));
+        let before = counter::total().evaluations();
+        assert_eq!(ind.set_fitness(5.0), Ok(()));
+        assert_eq!(ind.set_fitness(6.0), Err(6.0));
+        assert_eq!(ind.evaluate(), 5.0);
+        assert_eq!(ind.replace_fitness(7.0), Some(5.0));
+        assert_eq!(ind.fitness(), Some(7.0));
+        assert!(counter::total().evaluations() - before >= 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_keeps_fitness() {
+        let pop = vec![Cached::new(Walk(2.0)), Cached::new(Walk(-1.0))];
+        pop[0].evaluate();
+
+        let json = serde_json::to_string(&pop).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"solution":2.0,"fitness":-2.0},{"solution":-1.0,"fitness":null}]"#
+        );
+        let loaded: Vec<Cached<Walk>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded[0].fitness(), Some(-2.0));
+        assert_eq!(loaded[1].fitness(), None);
+    }
+
+    #[test]
+    fn evaluated_once_across_threads() {
+        let ind = Cached::new(Slow);
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| assert_eq!(ind.evaluate(), 1.0));
+            }
+        });
+        assert_eq!(SLOW_EVALUATIONS.load(Ordering::Relaxed), 1);
+
+        // a population can be moved to another thread, cache and all
+        let moved = thread::spawn(move || ind.into_inner()).join().unwrap();
+        assert_eq!(moved.1, Some(1.0));
+    }
+
+    #[test]
+    fn spin_once() {
+        // the cell used without `std` has the same guarantees as `OnceLock`
+        let cell = SpinOnce::new();
+        let inits = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let value = cell.get_or_init(|| {
+                        thread::sleep(Duration::from_millis(20));
+                        inits.fetch_add(1, Ordering::Relaxed);
+                        1.0
+                    });
+                    assert_eq!(*value, 1.0);
+                });
+            }
+        });
+        assert_eq!(inits.load(Ordering::Relaxed), 1);
+        assert_eq!(cell.set(2.0), Err(2.0));
+        assert_eq!(cell.clone().get(), Some(&1.0));
+
+        let mut cell = cell;
+        assert_eq!(cell.take(), Some(1.0));
+        assert_eq!(cell.get(), None);
+        assert_eq!(cell.clone().get(), None);
+        assert_eq!(cell.set(3.0), Ok(()));
+        assert_eq!(SpinOnce::from(4.0).into_inner(), Some(4.0));
+    }
+
+    #[test]
+    fn spin_once_after_panic() {
+        // a panicking initializer leaves the cell empty instead of stuck being initialized
+        let cell = SpinOnce::new();
+        thread::scope(|scope| {
+            let panicked = scope.spawn(|| cell.get_or_init(|| panic!("evaluation failed")));
+            assert!(panicked.join().is_err());
+        });
+        assert_eq!(cell.get(), None);
+        assert_eq!(*cell.get_or_init(|| 1.0), 1.0);
+    }
+
+    #[test]
+    fn repaired_after_variation() {
+        // a walk that has to stay within [-1, 1]
+        #[derive(Clone)]
+        struct Bounded(Walk, usize);
+
+        impl Solution for Bounded {
+            type Fitness = f64;
+
+            fn generate() -> Self {
+                Bounded(Walk::generate(), 0)
+            }
+
+            fn evaluate(&self) -> f64 {
+                assert!(self.0 .0.abs() <= 1.0);
+                self.0.evaluate()
+            }
+
+            fn crossover(a: &mut Self, b: &mut Self) {
+                Walk::crossover(&mut a.0, &mut b.0);
+            }
+
+            fn mutate(&mut self) {
+                self.0 .0 += 10.0;
+            }
+
+            fn repair(&mut self) {
+                self.0 .0 = self.0 .0.clamp(-1.0, 1.0);
+                self.1 += 1;
+            }
+        }
+
+        let mut pop: Vec<Cached<Bounded>> = (0..20).map(|_| Cached::generate()).collect();
+        crate::fitness::par_evaluate(&pop);
+        pop[0].mutate();
+        assert_eq!(pop[0].fitness(), None);
+        assert_eq!(pop[0].as_ref().0 .0, 1.0);
+        assert_eq!(pop[0].as_ref().1, 1);
+        assert_eq!(pop[1].as_ref().1, 0);
+
+        crate::alg::var_and(&mut pop, 0.5, 0.5);
+        crate::fitness::par_evaluate(&pop);
     }
 }
@@ -0,0 +1,138 @@
+use core::sync::atomic::Ordering;
+#[cfg(feature = "std")]
+use std::{cell::RefCell, sync::Arc};
+
+// Not every target has 64-bit atomics, e.g. most 32-bit microcontrollers,
+// so those count with a pointer-sized integer instead. The time in nanoseconds then wraps around
+// after about four seconds, so it's only good for taking differences over short spans.
+#[cfg(target_has_atomic = "64")]
+type AtomicCount = core::sync::atomic::AtomicU64;
+#[cfg(not(target_has_atomic = "64"))]
+type AtomicCount = core::sync::atomic::AtomicUsize;
+
+fn load(count: &AtomicCount) -> u64 {
+    #[cfg(target_has_atomic = "64")]
+    return count.load(Ordering::Relaxed);
+    #[cfg(not(target_has_atomic = "64"))]
+    return count.load(Ordering::Relaxed) as u64;
+}
+
+// Counts fitness evaluations, and the total time spent in `Solution::evaluate` in nanoseconds
+#[derive(Debug, Default)]
+pub(crate) struct EvalCounter {
+    evaluations: AtomicCount,
+    nanos: AtomicCount,
+}
+
+impl EvalCounter {
+    const fn new() -> Self {
+        EvalCounter {
+            evaluations: AtomicCount::new(0),
+            nanos: AtomicCount::new(0),
+        }
+    }
+
+    pub(crate) fn evaluations(&self) -> u64 {
+        load(&self.evaluations)
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn nanos(&self) -> u64 {
+        load(&self.nanos)
+    }
+
+    fn add(&self, nanos: u64) {
+        self.evaluations.fetch_add(1, Ordering::Relaxed);
+        self.nanos.fetch_add(nanos as _, Ordering::Relaxed);
+    }
+}
+
+// Every evaluation in the program
+static TOTAL: EvalCounter = EvalCounter::new();
+
+pub(crate) fn total() -> &'static EvalCounter {
+    &TOTAL
+}
+
+// The evaluations of the run entered on this thread, if any.
+// Without `std` there are no threads to tell runs apart by, so only the total is counted.
+#[cfg(feature = "std")]
+std::thread_local! {
+    static RUN: RefCell<Option<Arc<EvalCounter>>> = const { RefCell::new(None) };
+}
+
+// Count an evaluation that took `nanos`, both in the total and in the run entered on this thread
+pub(crate) fn count(nanos: u64) {
+    TOTAL.add(nanos);
+    #[cfg(feature = "std")]
+    RUN.with(|run| {
+        if let Some(run) = &*run.borrow() {
+            run.add(nanos);
+        }
+    });
+}
+
+// Get the counter of the run entered on this thread, to enter it on the threads doing the run's work
+#[cfg(feature = "std")]
+pub(crate) fn current() -> Option<Arc<EvalCounter>> {
+    RUN.with(|run| run.borrow().clone())
+}
+
+// Wrap `f` so that the evaluations it makes are counted in the run entered on this thread,
+// whichever thread it's called on, e.g. by a parallel iterator
+#[cfg(feature = "parallel")]
+pub(crate) fn carry<A, R>(f: impl Fn(A) -> R + Sync + Send) -> impl Fn(A) -> R + Sync + Send {
+    let run = current();
+    move |arg| {
+        let _run = enter(run.clone());
+        f(arg)
+    }
+}
+
+// Count this thread's evaluations in `counter` until the guard is dropped.
+// `None` stops them from being counted in any run.
+#[cfg(feature = "std")]
+pub(crate) fn enter(counter: Option<Arc<EvalCounter>>) -> RunGuard {
+    RunGuard {
+        previous: RUN.with(|run| run.replace(counter)),
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) struct RunGuard {
+    previous: Option<Arc<EvalCounter>>,
+}
+
+#[cfg(feature = "std")]
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        RUN.with(|run| *run.borrow_mut() = previous);
+    }
+}
+
+#[cfg(all(test, feature = "std", not(target_arch = "wasm32")))]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn counts_entered_run() {
+        let run = Arc::new(EvalCounter::default());
+        {
+            let _guard = enter(Some(run.clone()));
+            count(5);
+            // evaluations on other threads belong to other runs
+            thread::spawn(|| count(1)).join().unwrap();
+            {
+                let _inner = enter(None);
+                count(1);
+            }
+            count(2);
+        }
+        count(1);
+        assert_eq!(run.evaluations(), 2);
+        assert_eq!(run.nanos(), 7);
+    }
+}
@@ -0,0 +1,295 @@
+use std::{fmt::Debug, sync::OnceLock};
+
+use crate::{fitness::Direction, Solution};
+
+/// A trait for genotypes that are decoded into a separate phenotype before being evaluated
+///
+/// This is an alternative to implementing [`Solution`] directly, for problems where
+/// variation works on one representation (the *genotype*, which is `Self`)
+/// while evaluation and reporting work on another (the *phenotype*).
+/// Wrapping a genotype in [`Decoded`] makes it a [`Solution`] that decodes itself
+/// at most once and keeps the phenotype alongside it, so the phenotype can be read back
+/// from the population, hall of fame, statistics, and callbacks without decoding it again.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::{Decode, Decoded};
+///
+/// // bits decoded into the integer they represent
+/// #[derive(Clone)]
+/// struct Bits([bool; 8]);
+///
+/// impl Decode for Bits {
+///     type Phenotype = u8;
+///     type Fitness = f64;
+///
+///     fn generate() -> Self {
+///         Bits(random())
+///     }
+///
+///     fn decode(&self) -> u8 {
+///         self.0.iter().fold(0, |acc, &bit| (acc << 1) | bit as u8)
+///     }
+///
+///     fn evaluate(phenotype: &u8) -> f64 {
+///         -(*phenotype as f64 - 100.0).abs()
+///     }
+///
+///     fn crossover(a: &mut Self, b: &mut Self) {
+///         a.0[4..].swap_with_slice(&mut b.0[4..]);
+///     }
+///
+///     fn mutate(&mut self) {
+///         let i = thread_rng().gen_range(0..8);
+///         self.0[i] = !self.0[i];
+///     }
+/// }
+///
+/// let evo: Evolution<Decoded<Bits>, _, _, ()> = Evolution::new(
+///     alg::Simple::new(20, 0.5, 0.3, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// );
+/// let log = evo.run_for(10);
+/// let best: u8 = *log.hall_of_fame[0].as_ref().phenotype();
+/// # let _ = best;
+/// ```
+///
+/// [`Solution`]: ./trait.Solution.html
+/// [`Decoded`]: ./struct.Decoded.html
+pub trait Decode: Clone + Sync {
+    /// The type this genotype decodes into.
+    type Phenotype: Clone + Send + Sync;
+
+    /// The type that represents a solution's fitness. See [`Solution::Fitness`].
+    ///
+    /// [`Solution::Fitness`]: ./trait.Solution.html#associatedtype.Fitness
+    type Fitness: Copy + Send + Sync;
+
+    /// The direction in which each objective is optimized. See [`Solution::DIRECTIONS`].
+    ///
+    /// [`Solution::DIRECTIONS`]: ./trait.Solution.html#associatedconstant.DIRECTIONS
+    const DIRECTIONS: &'static [Direction] = &[];
+
+    /// The tolerance for equal objective values. See [`Solution::DOMINANCE_TOLERANCE`].
+    ///
+    /// [`Solution::DOMINANCE_TOLERANCE`]: ./trait.Solution.html#associatedconstant.DOMINANCE_TOLERANCE
+    const DOMINANCE_TOLERANCE: f64 = 0.0;
+
+    /// Randomly generate a new genotype.
+    fn generate() -> Self;
+
+    /// Decode this genotype into its phenotype.
+    ///
+    /// Like [`Solution::evaluate`], this must always return the same value for a given genotype.
+    ///
+    /// [`Solution::evaluate`]: ./trait.Solution.html#tymethod.evaluate
+    fn decode(&self) -> Self::Phenotype;
+
+    /// Evaluate the fitness of a phenotype.
+    fn evaluate(phenotype: &Self::Phenotype) -> Self::Fitness;
+
+    /// Crossover operator on genotypes. See [`Solution::crossover`].
+    ///
+    /// [`Solution::crossover`]: ./trait.Solution.html#tymethod.crossover
+    fn crossover(a: &mut Self, b: &mut Self);
+
+    /// Mutation operator on genotypes. See [`Solution::mutate`].
+    ///
+    /// [`Solution::mutate`]: ./trait.Solution.html#tymethod.mutate
+    fn mutate(&mut self);
+
+    /// Repair operator on genotypes. See [`Solution::repair`].
+    ///
+    /// [`Solution::repair`]: ./trait.Solution.html#method.repair
+    fn repair(&mut self) {}
+}
+
+/// A [`Solution`] made from a [`Decode`] genotype, which keeps its decoded phenotype
+///
+/// The phenotype is decoded the first time it is needed, either by evaluation or by
+/// [`.phenotype()`], and is then kept until the genotype is changed by crossover or mutation.
+///
+/// [`Solution`]: ./trait.Solution.html
+/// [`Decode`]: ./trait.Decode.html
+/// [`.phenotype()`]: ./struct.Decoded.html#method.phenotype
+pub struct Decoded<G: Decode> {
+    genotype: G,
+    phenotype: OnceLock<G::Phenotype>,
+}
+
+impl<G: Decode> Decoded<G> {
+    /// Wrap an existing genotype.
+    pub fn new(genotype: G) -> Self {
+        Decoded {
+            genotype,
+            phenotype: OnceLock::new(),
+        }
+    }
+
+    /// Get the genotype.
+    pub fn genotype(&self) -> &G {
+        &self.genotype
+    }
+
+    /// Get the phenotype, decoding it if that hasn't happened yet.
+    pub fn phenotype(&self) -> &G::Phenotype {
+        self.phenotype.get_or_init(|| self.genotype.decode())
+    }
+
+    /// Consume the `Decoded`, returning the genotype and the phenotype, if it was decoded.
+    pub fn into_parts(self) -> (G, Option<G::Phenotype>) {
+        (self.genotype, self.phenotype.into_inner())
+    }
+}
+
+impl<G: Decode> Solution for Decoded<G> {
+    type Fitness = G::Fitness;
+    const DIRECTIONS: &'static [Direction] = G::DIRECTIONS;
+    const DOMINANCE_TOLERANCE: f64 = G::DOMINANCE_TOLERANCE;
+
+    fn generate() -> Self {
+        Decoded::new(G::generate())
+    }
+
+    fn evaluate(&self) -> Self::Fitness {
+        G::evaluate(self.phenotype())
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        G::crossover(&mut a.genotype, &mut b.genotype);
+        a.phenotype.take();
+        b.phenotype.take();
+    }
+
+    fn mutate(&mut self) {
+        self.genotype.mutate();
+        self.phenotype.take();
+    }
+
+    fn repair(&mut self) {
+        self.genotype.repair();
+        self.phenotype.take();
+    }
+}
+
+impl<G: Decode> Clone for Decoded<G> {
+    fn clone(&self) -> Self {
+        Decoded {
+            genotype: self.genotype.clone(),
+            phenotype: self.phenotype.clone(),
+        }
+    }
+}
+
+impl<G> Debug for Decoded<G>
+where
+    G: Decode + Debug,
+    G::Phenotype: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Decoded")
+            .field("genotype", &self.genotype)
+            .field("phenotype", &self.phenotype.get())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::Cached;
+
+    thread_local! {
+        static DECODED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    // Decimal digits decoded into the number they represent, which has to stay below 500
+    #[derive(Clone, Debug, PartialEq)]
+    struct Digits([u8; 3]);
+
+    impl Decode for Digits {
+        type Phenotype = u32;
+        type Fitness = f64;
+
+        fn generate() -> Self {
+            Digits([0; 3])
+        }
+
+        fn decode(&self) -> u32 {
+            DECODED.with(|decoded| decoded.set(decoded.get() + 1));
+            self.0.iter().fold(0, |acc, &digit| acc * 10 + digit as u32)
+        }
+
+        fn evaluate(phenotype: &u32) -> f64 {
+            *phenotype as f64
+        }
+
+        fn crossover(a: &mut Self, b: &mut Self) {
+            core::mem::swap(&mut a.0[0], &mut b.0[0]);
+        }
+
+        fn mutate(&mut self) {
+            self.0[2] = (self.0[2] + 1) % 10;
+        }
+
+        fn repair(&mut self) {
+            self.0[0] = self.0[0].min(4);
+        }
+    }
+
+    fn decodes() -> usize {
+        DECODED.with(Cell::get)
+    }
+
+    // The kept phenotype, if it's been decoded
+    fn kept(ind: &Decoded<Digits>) -> Option<u32> {
+        ind.clone().into_parts().1
+    }
+
+    #[test]
+    fn decoded_once() {
+        let ind = Cached::new(Decoded::new(Digits([1, 2, 3])));
+        let before = decodes();
+        assert_eq!(ind.evaluate(), 123.0);
+        assert_eq!(*ind.as_ref().phenotype(), 123);
+        assert_eq!(decodes() - before, 1);
+
+        // clones keep the phenotype
+        let clone = ind.clone();
+        assert_eq!(*clone.as_ref().phenotype(), 123);
+        assert_eq!(decodes() - before, 1);
+        assert_eq!(
+            format!("{:?}", clone.as_ref()),
+            "Decoded { genotype: Digits([1, 2, 3]), phenotype: Some(123) }"
+        );
+    }
+
+    #[test]
+    fn decoded_again_after_variation() {
+        let mut a = Decoded::new(Digits([1, 2, 9]));
+        let mut b = Decoded::new(Digits([8, 0, 0]));
+        a.phenotype();
+        b.phenotype();
+
+        a.mutate();
+        assert_eq!(kept(&a), None);
+        assert_eq!(*a.phenotype(), 120);
+
+        Decoded::crossover(&mut a, &mut b);
+        assert_eq!((kept(&a), kept(&b)), (None, None));
+        assert_eq!((*a.phenotype(), *b.phenotype()), (820, 100));
+
+        a.repair();
+        assert_eq!(kept(&a), None);
+        assert_eq!(*a.phenotype(), 420);
+        assert_eq!(a.genotype(), &Digits([4, 2, 0]));
+        assert_eq!(a.into_parts(), (Digits([4, 2, 0]), Some(420)));
+
+        // a new solution is decoded when it's first needed
+        let generated = Decoded::<Digits>::generate();
+        assert_eq!(kept(&generated), None);
+        assert_eq!(generated.evaluate(), 0.0);
+    }
+}
@@ -0,0 +1,17 @@
+use std::io;
+
+use bincode::Options;
+
+// Fixed-width little-endian integers, so the encoding doesn't depend on the platform
+pub(crate) fn options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_little_endian()
+}
+
+pub(crate) fn bincode_error(error: bincode::ErrorKind) -> io::Error {
+    match error {
+        bincode::ErrorKind::Io(error) => error,
+        error => io::Error::new(io::ErrorKind::InvalidData, error),
+    }
+}
@@ -0,0 +1,328 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt::Debug,
+    marker::PhantomData,
+    ops::Deref,
+    sync::{Mutex, OnceLock},
+};
+
+use crate::Solution;
+
+type Registry = Mutex<HashMap<TypeId, &'static (dyn Any + Send + Sync)>>;
+
+type Crossover<T> = Box<dyn Fn(&mut T, &mut T) + Send + Sync>;
+type Repair<T> = Box<dyn Fn(&mut T) + Send + Sync>;
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// The closures that make up an [`FnSolution`]
+///
+/// A configuration has to be [installed] before any [`FnSolution`] using it can be generated.
+/// Only one configuration can be installed for each combination of `T`, `F`, and `Tag`.
+/// `Tag` can be any type, and only exists to tell apart configurations
+/// that would otherwise have the same type; it defaults to `()`.
+///
+/// [`FnSolution`]: ./struct.FnSolution.html
+/// [installed]: ./struct.FnConfig.html#method.install
+pub struct FnConfig<T, F, Tag = ()> {
+    generate: Box<dyn Fn() -> T + Send + Sync>,
+    evaluate: Box<dyn Fn(&T) -> F + Send + Sync>,
+    crossover: Crossover<T>,
+    mutate: Box<dyn Fn(&mut T) + Send + Sync>,
+    repair: Option<Repair<T>>,
+    _phantom: PhantomData<fn() -> Tag>,
+}
+
+impl<T, F> FnConfig<T, F, ()> {
+    /// Create a configuration from the four operations of a [`Solution`].
+    ///
+    /// [`Solution`]: ./trait.Solution.html
+    pub fn new(
+        generate: impl Fn() -> T + Send + Sync + 'static,
+        evaluate: impl Fn(&T) -> F + Send + Sync + 'static,
+        crossover: impl Fn(&mut T, &mut T) + Send + Sync + 'static,
+        mutate: impl Fn(&mut T) + Send + Sync + 'static,
+    ) -> Self {
+        FnConfig::tagged(generate, evaluate, crossover, mutate)
+    }
+}
+
+impl<T, F, Tag> FnConfig<T, F, Tag> {
+    /// Create a configuration with a tag type other than `()`.
+    ///
+    /// See [`FnConfig::new`](./struct.FnConfig.html#method.new).
+    pub fn tagged(
+        generate: impl Fn() -> T + Send + Sync + 'static,
+        evaluate: impl Fn(&T) -> F + Send + Sync + 'static,
+        crossover: impl Fn(&mut T, &mut T) + Send + Sync + 'static,
+        mutate: impl Fn(&mut T) + Send + Sync + 'static,
+    ) -> Self {
+        FnConfig {
+            generate: Box::new(generate),
+            evaluate: Box::new(evaluate),
+            crossover: Box::new(crossover),
+            mutate: Box::new(mutate),
+            repair: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Repair solutions with `repair` after they're changed. See [`Solution::repair`].
+    ///
+    /// [`Solution::repair`]: ./trait.Solution.html#method.repair
+    pub fn with_repair(mut self, repair: impl Fn(&mut T) + Send + Sync + 'static) -> Self {
+        self.repair = Some(Box::new(repair));
+        self
+    }
+
+    /// Install this configuration, so that [`FnSolution<T, F, Tag>`] can be generated.
+    ///
+    /// The configuration is kept for the rest of the program, and a reference to it is returned.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if a configuration has already been installed for the same `T`, `F`, and `Tag`.
+    ///
+    /// [`FnSolution<T, F, Tag>`]: ./struct.FnSolution.html
+    pub fn install(self) -> &'static Self
+    where
+        T: 'static,
+        F: 'static,
+        Tag: 'static,
+    {
+        // the registry is never left half-updated, so it's still usable after a failed install or lookup
+        let mut registry = REGISTRY
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let key = TypeId::of::<Self>();
+        assert!(
+            !registry.contains_key(&key),
+            "an FnConfig has already been installed for this combination of types; \
+             use FnConfig::tagged to install another one"
+        );
+        let config: &'static Self = Box::leak(Box::new(self));
+        registry.insert(key, config);
+        config
+    }
+
+    fn installed() -> &'static Self
+    where
+        T: 'static,
+        F: 'static,
+        Tag: 'static,
+    {
+        let registry = REGISTRY
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        registry
+            .get(&TypeId::of::<Self>())
+            .and_then(|config| config.downcast_ref())
+            .expect(
+                "no FnConfig has been installed for this FnSolution; call FnConfig::install first",
+            )
+    }
+}
+
+/// A [`Solution`] built from closures
+///
+/// This is a quick way to optimize a value of any type without defining a new type
+/// and implementing [`Solution`] on it. Create an [`FnConfig`] from the four operations,
+/// install it, and then use `FnSolution<T, F>` as your solution type.
+/// It derefs to the value it contains.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::{FnConfig, FnSolution};
+///
+/// FnConfig::new(
+///     || random::<f64>() * 10.0,
+///     |x: &f64| -(x - 3.0).abs(),
+///     |a: &mut f64, b: &mut f64| *a = (*a + *b) / 2.0,
+///     |x: &mut f64| *x += random::<f64>() - 0.5,
+/// )
+/// .install();
+///
+/// let evo: Evolution<FnSolution<f64, f64>, _, _, ()> = Evolution::new(
+///     alg::Simple::new(20, 0.5, 0.3, select::Tournament::new(3)),
+///     hof::BestN::new(1),
+/// );
+/// let log = evo.run_for(10);
+/// assert!((**log.hall_of_fame[0].as_ref() - 3.0).abs() < 3.0);
+/// ```
+///
+/// [`Solution`]: ./trait.Solution.html
+/// [`FnConfig`]: ./struct.FnConfig.html
+pub struct FnSolution<T: 'static, F: 'static, Tag: 'static = ()> {
+    value: T,
+    config: &'static FnConfig<T, F, Tag>,
+}
+
+impl<T, F, Tag> FnSolution<T, F, Tag> {
+    /// Wrap an existing value, using the installed configuration.
+    ///
+    /// Panics
+    /// ======
+    /// Panics if no configuration has been installed for `T`, `F`, and `Tag`.
+    pub fn new(value: T) -> Self {
+        FnSolution {
+            value,
+            config: FnConfig::installed(),
+        }
+    }
+
+    /// Consume the `FnSolution`, returning the value it contained.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, F, Tag> Solution for FnSolution<T, F, Tag>
+where
+    T: Clone + Sync,
+    F: Copy + Send + Sync,
+{
+    type Fitness = F;
+
+    fn generate() -> Self {
+        let config = FnConfig::installed();
+        FnSolution {
+            value: (config.generate)(),
+            config,
+        }
+    }
+
+    fn evaluate(&self) -> F {
+        (self.config.evaluate)(&self.value)
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        (a.config.crossover)(&mut a.value, &mut b.value);
+    }
+
+    fn mutate(&mut self) {
+        (self.config.mutate)(&mut self.value);
+    }
+
+    fn repair(&mut self) {
+        if let Some(repair) = &self.config.repair {
+            repair(&mut self.value);
+        }
+    }
+}
+
+impl<T: Clone, F, Tag> Clone for FnSolution<T, F, Tag> {
+    fn clone(&self) -> Self {
+        FnSolution {
+            value: self.value.clone(),
+            config: self.config,
+        }
+    }
+}
+
+impl<T, F, Tag> Deref for FnSolution<T, F, Tag> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, F, Tag> AsRef<T> for FnSolution<T, F, Tag> {
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Debug, F, Tag> Debug for FnSolution<T, F, Tag> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T: PartialEq, F, Tag> PartialEq for FnSolution<T, F, Tag> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        ptr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+    };
+
+    use super::*;
+
+    // Tags, so each test installs its own configuration
+    struct Shared;
+    struct Twice;
+    struct Missing;
+
+    #[test]
+    fn closures_are_shared() {
+        let evaluations = Arc::new(AtomicUsize::new(0));
+        let counted = evaluations.clone();
+        let config = FnConfig::<i64, f64, Shared>::tagged(
+            || 1,
+            move |x: &i64| {
+                counted.fetch_add(1, Ordering::Relaxed);
+                *x as f64
+            },
+            |a: &mut i64, b: &mut i64| core::mem::swap(a, b),
+            |x: &mut i64| *x += 1,
+        )
+        .with_repair(|x: &mut i64| *x = (*x).min(3))
+        .install();
+
+        let mut a = FnSolution::<i64, f64, Shared>::generate();
+        assert_eq!(*a, 1);
+        let clone = a.clone();
+        assert!(ptr::eq(clone.config, config) && ptr::eq(a.config, config));
+
+        // every thread uses the same closures
+        let fitness: Vec<f64> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|x| {
+                    scope.spawn(move || {
+                        let mut ind = FnSolution::<i64, f64, Shared>::new(x);
+                        ind.mutate();
+                        assert!(ptr::eq(ind.config, config));
+                        ind.evaluate()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(fitness, [1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(evaluations.load(Ordering::Relaxed), 4);
+
+        let mut b = FnSolution::new(7);
+        FnSolution::crossover(&mut a, &mut b);
+        assert_eq!((*a, *b), (7, 1));
+        a.repair();
+        assert_eq!(a.into_inner(), 3);
+        assert_eq!(format!("{:?}", b), "1");
+        assert!(b == clone);
+    }
+
+    #[test]
+    #[should_panic = "already been installed"]
+    fn installed_once() {
+        let config = || FnConfig::<u8, f64, Twice>::tagged(|| 0, |_| 0.0, |_, _| {}, |_| {});
+        config().install();
+        config().install();
+    }
+
+    #[test]
+    #[should_panic = "no FnConfig has been installed"]
+    fn needs_a_config() {
+        FnSolution::<u8, f64, Missing>::new(0);
+    }
+}
@@ -0,0 +1,272 @@
+use std::{fmt::Debug, marker::PhantomData, sync::OnceLock};
+
+use crate::{fitness::Direction, Solution};
+
+/// A value derived from a solution that is worth computing only once per solution
+///
+/// This is for quantities other than fitness that are read over and over,
+/// such as a behavior descriptor for novelty search or MAP-Elites, or the constraint violations
+/// of a solution for constrained selection. Wrapping a solution in [`Memoized`]
+/// keeps the value alongside it until the solution changes, just like [`Cached`] does for fitness.
+///
+/// ```
+/// use eviolite::prelude::*;
+/// use eviolite::{Memo, Memoized};
+/// # #[derive(Clone)]
+/// # struct Robot([f64; 4]);
+/// # impl Robot { fn simulate(&self) -> [f64; 2] { [self.0[0], self.0[1]] } }
+/// # impl Solution for Robot {
+/// #     type Fitness = f64;
+/// #     fn generate() -> Self { Robot(random()) }
+/// #     fn evaluate(&self) -> f64 { self.0.iter().sum() }
+/// #     fn crossover(a: &mut Self, b: &mut Self) { std::mem::swap(&mut a.0[0], &mut b.0[0]) }
+/// #     fn mutate(&mut self) { self.0[1] = random() }
+/// # }
+///
+/// // where the robot ends up, which is expensive to simulate
+/// struct FinalPosition;
+///
+/// impl Memo<Robot> for FinalPosition {
+///     type Value = [f64; 2];
+///
+///     fn compute(robot: &Robot) -> [f64; 2] {
+///         robot.simulate()
+///     }
+/// }
+///
+/// let pop: Vec<Cached<Memoized<Robot, FinalPosition>>> =
+///     (0..10).map(|_| Cached::generate()).collect();
+/// let positions: Vec<[f64; 2]> = pop.iter().map(|ind| *ind.as_ref().value()).collect();
+/// # let _ = positions;
+/// ```
+///
+/// To memoize several values, either compute them all in one `Memo` whose value is a struct,
+/// or wrap one `Memoized` in another.
+///
+/// [`Memoized`]: ./struct.Memoized.html
+/// [`Cached`]: ./struct.Cached.html
+pub trait Memo<T>: 'static {
+    /// The type of the memoized value.
+    type Value: Clone + Send + Sync;
+
+    /// Compute the value for `solution`.
+    ///
+    /// Like [`Solution::evaluate`], this must always return the same value for a given solution.
+    ///
+    /// [`Solution::evaluate`]: ./trait.Solution.html#tymethod.evaluate
+    fn compute(solution: &T) -> Self::Value;
+}
+
+/// A [`Solution`] that keeps a [`Memo`] value alongside it
+///
+/// The value is computed the first time it is needed with [`.value()`], and is then kept until
+/// the solution is changed by crossover, mutation, or [`.solution_mut()`], which also deletes
+/// the cached fitness when the `Memoized` is wrapped in a [`Cached`].
+/// Everything else is passed through to the wrapped solution.
+///
+/// [`Solution`]: ./trait.Solution.html
+/// [`Memo`]: ./trait.Memo.html
+/// [`.value()`]: ./struct.Memoized.html#method.value
+/// [`.solution_mut()`]: ./struct.Memoized.html#method.solution_mut
+/// [`Cached`]: ./struct.Cached.html
+pub struct Memoized<T: Solution, M: Memo<T>> {
+    solution: T,
+    value: OnceLock<M::Value>,
+    _phantom: PhantomData<fn() -> M>,
+}
+
+impl<T: Solution, M: Memo<T>> Memoized<T, M> {
+    /// Wrap an existing solution.
+    pub fn new(solution: T) -> Self {
+        Memoized {
+            solution,
+            value: OnceLock::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get the solution.
+    pub fn solution(&self) -> &T {
+        &self.solution
+    }
+
+    /// Get mutable access to the solution, deleting the memoized value.
+    ///
+    /// Inside a [`Cached`], use this through [`Cached::modify()`] so the fitness is deleted too.
+    ///
+    /// [`Cached`]: ./struct.Cached.html
+    /// [`Cached::modify()`]: ./struct.Cached.html#method.modify
+    pub fn solution_mut(&mut self) -> &mut T {
+        self.value.take();
+        &mut self.solution
+    }
+
+    /// Get the memoized value, computing it if that hasn't happened yet.
+    pub fn value(&self) -> &M::Value {
+        self.value.get_or_init(|| M::compute(&self.solution))
+    }
+
+    /// Consume the `Memoized`, returning the solution and the value, if it was computed.
+    pub fn into_parts(self) -> (T, Option<M::Value>) {
+        (self.solution, self.value.into_inner())
+    }
+}
+
+impl<T: Solution, M: Memo<T>> Solution for Memoized<T, M> {
+    type Fitness = T::Fitness;
+    const DIRECTIONS: &'static [Direction] = T::DIRECTIONS;
+    const DOMINANCE_TOLERANCE: f64 = T::DOMINANCE_TOLERANCE;
+
+    fn generate() -> Self {
+        Memoized::new(T::generate())
+    }
+
+    fn evaluate(&self) -> Self::Fitness {
+        self.solution.evaluate()
+    }
+
+    fn crossover(a: &mut Self, b: &mut Self) {
+        T::crossover(&mut a.solution, &mut b.solution);
+        a.value.take();
+        b.value.take();
+    }
+
+    fn mutate(&mut self) {
+        self.solution.mutate();
+        self.value.take();
+    }
+
+    fn repair(&mut self) {
+        self.solution.repair();
+        self.value.take();
+    }
+
+    fn evaluation_cost(&self) -> Option<f64> {
+        self.solution.evaluation_cost()
+    }
+}
+
+impl<T: Solution, M: Memo<T>> Clone for Memoized<T, M> {
+    fn clone(&self) -> Self {
+        Memoized {
+            solution: self.solution.clone(),
+            value: self.value.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, M> Debug for Memoized<T, M>
+where
+    T: Solution + Debug,
+    M: Memo<T>,
+    M::Value: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Memoized")
+            .field("solution", &self.solution)
+            .field("value", &self.value.get())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cached;
+
+    // A counter that is moved by variation and kept non-negative by repair
+    #[derive(Clone, Debug, PartialEq)]
+    struct Counter(i32);
+
+    impl Solution for Counter {
+        type Fitness = f64;
+
+        fn generate() -> Self {
+            Counter(0)
+        }
+
+        fn evaluate(&self) -> f64 {
+            self.0 as f64
+        }
+
+        fn crossover(a: &mut Self, b: &mut Self) {
+            core::mem::swap(&mut a.0, &mut b.0);
+        }
+
+        fn mutate(&mut self) {
+            self.0 -= 3;
+        }
+
+        fn repair(&mut self) {
+            self.0 = self.0.max(0);
+        }
+    }
+
+    // The counter's square
+    struct Square;
+
+    impl Memo<Counter> for Square {
+        type Value = i32;
+
+        fn compute(counter: &Counter) -> i32 {
+            counter.0 * counter.0
+        }
+    }
+
+    type Squared = Memoized<Counter, Square>;
+
+    // The memoized value, if it's been computed
+    fn memo(ind: &Squared) -> Option<i32> {
+        ind.clone().into_parts().1
+    }
+
+    #[test]
+    fn variation_resets_the_memo() {
+        let mut a = Squared::new(Counter(2));
+        assert_eq!(memo(&a), None);
+        assert_eq!(*a.value(), 4);
+        assert_eq!(memo(&a), Some(4));
+
+        a.mutate();
+        assert_eq!(memo(&a), None);
+        assert_eq!(*a.value(), 1);
+
+        let mut b = Squared::new(Counter(5));
+        b.value();
+        Squared::crossover(&mut a, &mut b);
+        assert_eq!((memo(&a), memo(&b)), (None, None));
+        assert_eq!((*a.value(), *b.value()), (25, 1));
+
+        b.repair();
+        assert_eq!(memo(&b), None);
+        assert_eq!(*b.value(), 0);
+
+        a.solution_mut().0 = 3;
+        assert_eq!(memo(&a), None);
+        assert_eq!(*a.value(), 9);
+        assert_eq!(a.solution(), &Counter(3));
+        assert_eq!(a.evaluate(), 3.0);
+        assert_eq!(a.into_parts(), (Counter(3), Some(9)));
+    }
+
+    #[test]
+    fn clones_keep_the_memo() {
+        let ind = Squared::new(Counter(7));
+        ind.value();
+        let clone = ind.clone();
+        assert_eq!(memo(&clone), Some(49));
+        assert_eq!(
+            format!("{:?}", clone),
+            "Memoized { solution: Counter(7), value: Some(49) }"
+        );
+
+        // inside a `Cached`, variation and `modify` reset it along with the fitness
+        let mut cached = Cached::new(ind);
+        cached.evaluate();
+        cached.modify(|ind| ind.solution_mut().0 = 1);
+        assert_eq!(cached.fitness(), None);
+        assert_eq!(memo(cached.as_ref()), None);
+        assert_eq!(*cached.as_ref().value(), 1);
+    }
+}
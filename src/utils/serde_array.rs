@@ -0,0 +1,48 @@
+// `serde` only implements its traits for arrays of up to 32 elements, and not for arrays
+// of a generic length, so arrays of `f64` with a const generic length go through here
+// with `#[serde(with = "crate::utils::serde_array")]`.
+
+use std::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{Error, SeqAccess, Visitor},
+    ser::SerializeTuple,
+    Deserializer, Serializer,
+};
+
+pub fn serialize<S: Serializer, const M: usize>(
+    array: &[f64; M],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(M)?;
+    for value in array {
+        tuple.serialize_element(value)?;
+    }
+    tuple.end()
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, const M: usize>(
+    deserializer: D,
+) -> Result<[f64; M], D::Error> {
+    struct ArrayVisitor<const M: usize>(PhantomData<[f64; M]>);
+
+    impl<'de, const M: usize> Visitor<'de> for ArrayVisitor<M> {
+        type Value = [f64; M];
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "an array of {} numbers", M)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[f64; M], A::Error> {
+            let mut array = [0.0; M];
+            for (i, value) in array.iter_mut().enumerate() {
+                *value = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+            }
+            Ok(array)
+        }
+    }
+
+    deserializer.deserialize_tuple(M, ArrayVisitor(PhantomData))
+}
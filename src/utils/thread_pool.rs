@@ -0,0 +1,69 @@
+use std::{cell::RefCell, sync::Arc};
+
+use rayon::ThreadPool;
+
+use super::counter;
+
+std::thread_local! {
+    static POOL: RefCell<Option<Arc<ThreadPool>>> = const { RefCell::new(None) };
+}
+
+// Run `op` in the thread pool entered on this thread, if there is one,
+// so that any parallel work it does stays in that pool.
+// Otherwise, it runs on the current thread, using rayon's global pool for parallel work.
+// Either way, `op` counts its evaluations in the run entered on this thread, but the work it
+// spreads across other threads has to carry the run along with `counter::carry()`.
+pub(crate) fn install<R: Send>(op: impl FnOnce() -> R + Send) -> R {
+    match POOL.with(|pool| pool.borrow().clone()) {
+        Some(pool) => {
+            let run = counter::current();
+            pool.install(|| {
+                let _run = counter::enter(run);
+                op()
+            })
+        }
+        None => op(),
+    }
+}
+
+// Use `pool` for all of eviolite's parallel work on this thread until the guard is dropped.
+// `None` keeps whichever pool was already in use.
+pub(crate) fn enter(pool: Option<Arc<ThreadPool>>) -> PoolGuard {
+    let previous = pool.map(|pool| POOL.with(|current| current.replace(Some(pool))));
+    PoolGuard { previous }
+}
+
+pub(crate) struct PoolGuard {
+    previous: Option<Option<Arc<ThreadPool>>>,
+}
+
+impl Drop for PoolGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            POOL.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn install_uses_entered_pool() {
+        let pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(3)
+                .build()
+                .unwrap(),
+        );
+        assert_eq!(install(rayon::current_thread_index), None);
+        {
+            let _guard = enter(Some(pool.clone()));
+            assert_eq!(install(rayon::current_num_threads), 3);
+            let _inner = enter(None);
+            assert!(install(rayon::current_thread_index).is_some());
+        }
+        assert_eq!(install(rayon::current_thread_index), None);
+    }
+}
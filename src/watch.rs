@@ -0,0 +1,207 @@
+//! Watching a run's progress from another thread
+//!
+//! A [`Watch`] is a handle that an [`Evolution`] publishes its progress to after every generation,
+//! through [`Evolution::with_watch()`]. Clones of the handle can be held by other threads,
+//! like a UI or a service endpoint, which can poll the latest [`Progress`] or wait for the next one,
+//! and use the hall of fame in it as an intermediate answer while the run goes on.
+//!
+//! ```
+//! use std::thread;
+//! use eviolite::prelude::*;
+//! use eviolite::benchmarks::{Benchmark, Sphere};
+//! use eviolite::watch::Watch;
+//!
+//! let watch = Watch::new();
+//! let publisher = watch.clone();
+//! let run = thread::spawn(move || {
+//!     let evo: Evolution<Benchmark<Sphere, 3>, _, _, stats::FitnessSummary> = Evolution::new(
+//!         alg::Simple::new(50, 0.5, 0.2, select::Tournament::new(3)),
+//!         hof::BestN::new(1),
+//!     )
+//!     .with_watch(&publisher);
+//!     evo.run_for(100)
+//! });
+//!
+//! let mut seen = None;
+//! while let Some(progress) = watch.wait_newer(seen) {
+//!     println!("generation {}: best {:?}", progress.gen, progress.hall_of_fame[0].evaluate());
+//!     seen = Some(progress.gen);
+//! }
+//! assert!(watch.is_finished());
+//! run.join().unwrap();
+//! ```
+//!
+//! An `Evolution` can't be sent between threads, so it has to be created on the thread that runs it,
+//! as above.
+//!
+//! [`Watch`]: ./struct.Watch.html
+//! [`Evolution`]: ../struct.Evolution.html
+//! [`Evolution::with_watch()`]: ../struct.Evolution.html#method.with_watch
+//! [`Progress`]: ./struct.Progress.html
+
+use std::sync::{Arc, Condvar, Mutex};
+
+/// The state of a run after a generation, as published to a [`Watch`]
+///
+/// [`Watch`]: ./struct.Watch.html
+#[derive(Clone, Debug)]
+pub struct Progress<Hof, Stat> {
+    /// The number of the generation.
+    pub gen: usize,
+    /// A copy of the hall of fame after recording the generation.
+    pub hall_of_fame: Hof,
+    /// The statistics of the generation.
+    pub stats: Stat,
+    /// The number of evaluations computed by this run, up to and including the generation's.
+    /// See [`Generation::evaluations`].
+    ///
+    /// [`Generation::evaluations`]: ../struct.Generation.html#structfield.evaluations
+    pub evaluations: u64,
+}
+
+struct State<Hof, Stat> {
+    latest: Option<Progress<Hof, Stat>>,
+    finished: bool,
+}
+
+/// A handle for watching the progress of a run from any thread
+///
+/// Every clone of a `Watch` refers to the same run.
+/// See the [module-level documentation](./index.html) for an example.
+pub struct Watch<Hof, Stat> {
+    shared: Arc<(Mutex<State<Hof, Stat>>, Condvar)>,
+}
+
+impl<Hof, Stat> Watch<Hof, Stat> {
+    /// Create a handle that nothing has been published to yet.
+    pub fn new() -> Self {
+        Watch {
+            shared: Arc::new((
+                Mutex::new(State {
+                    latest: None,
+                    finished: false,
+                }),
+                Condvar::new(),
+            )),
+        }
+    }
+
+    /// Returns `true` once the run publishing to this handle has ended.
+    pub fn is_finished(&self) -> bool {
+        self.state().finished
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State<Hof, Stat>> {
+        // a panic while publishing can't leave the state half-updated, so a poisoned lock is still usable
+        self.shared
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Replace the latest progress and wake up everyone waiting for it
+    pub(crate) fn publish(&self, progress: Progress<Hof, Stat>) {
+        self.state().latest = Some(progress);
+        self.shared.1.notify_all();
+    }
+
+    pub(crate) fn finish(&self) {
+        self.state().finished = true;
+        self.shared.1.notify_all();
+    }
+}
+
+impl<Hof: Clone, Stat: Clone> Watch<Hof, Stat> {
+    /// Get the progress after the most recent generation, or `None` if there hasn't been one yet.
+    pub fn latest(&self) -> Option<Progress<Hof, Stat>> {
+        self.state().latest.clone()
+    }
+
+    /// Wait until a generation later than `gen` has been published and return its progress,
+    /// or return `None` if the run ends first.
+    ///
+    /// With `None`, this waits for the first generation, or returns the latest one right away
+    /// if there is one. Generations can be skipped if they're published faster than they're waited for.
+    pub fn wait_newer(&self, gen: Option<usize>) -> Option<Progress<Hof, Stat>> {
+        let is_newer = |state: &State<Hof, Stat>| match (&state.latest, gen) {
+            (Some(latest), Some(gen)) => latest.gen > gen,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        let state = self
+            .shared
+            .1
+            .wait_while(self.state(), |state| !is_newer(state) && !state.finished)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if is_newer(&state) {
+            state.latest.clone()
+        } else {
+            None
+        }
+    }
+}
+
+impl<Hof, Stat> Clone for Watch<Hof, Stat> {
+    fn clone(&self) -> Self {
+        Watch {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<Hof, Stat> Default for Watch<Hof, Stat> {
+    fn default() -> Self {
+        Watch::new()
+    }
+}
+
+// Held by the run, and marks the watch as finished when the run is dropped
+pub(crate) struct Publisher<Hof, Stat>(pub(crate) Watch<Hof, Stat>);
+
+impl<Hof, Stat> Drop for Publisher<Hof, Stat> {
+    fn drop(&mut self) {
+        self.0.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+    use crate::{alg, hof::BestN, select, testutils::Walk, Evolution, Solution};
+
+    #[test]
+    fn progress_is_published() {
+        let watch: Watch<BestN<Walk>, ()> = Watch::new();
+        assert!(watch.latest().is_none());
+
+        let publisher = watch.clone();
+        let run = thread::spawn(move || {
+            let evo: Evolution<Walk, _, _, ()> = Evolution::new(
+                alg::Simple::new(20, 0.5, 0.3, select::Tournament::new(2)),
+                BestN::new(1),
+            )
+            .with_watch(&publisher);
+            let log = evo.run_for(30);
+            (log.hall_of_fame[0].evaluate(), log.evaluations)
+        });
+
+        let mut gens = Vec::new();
+        let mut seen = None;
+        while let Some(progress) = watch.wait_newer(seen) {
+            assert_eq!(progress.hall_of_fame.len(), 1);
+            gens.push(progress.gen);
+            seen = Some(progress.gen);
+        }
+        let (best, evaluations) = run.join().unwrap();
+
+        assert!(watch.is_finished());
+        assert!(gens.windows(2).all(|pair| pair[0] < pair[1]));
+        let latest = watch.latest().unwrap();
+        assert_eq!(latest.gen, 29);
+        assert_eq!(latest.hall_of_fame[0].evaluate(), best);
+        assert_eq!(latest.evaluations, evaluations);
+        assert!(watch.wait_newer(Some(29)).is_none());
+    }
+}